@@ -1,5 +1,7 @@
 //! Code generation for block behaviors.
 
+use std::collections::BTreeSet;
+
 use heck::ToShoutySnakeCase;
 use proc_macro2::{Ident, Span, TokenStream};
 use quote::quote;
@@ -30,13 +32,43 @@ fn generate_registrations<'a>(
     quote! { #(#registrations)* }
 }
 
-pub fn build(blocks: &[BlockClass]) -> String {
+/// Finds the blocks that drop like another block, as vanilla's `Properties.dropsLike` does.
+///
+/// Every such block is a wall mounted variant without a loot table of its own, dropping
+/// like the standing block named the same without `wall_`.
+fn generate_drops_like(blocks: &[BlockClass], loot_tables: &BTreeSet<String>) -> TokenStream {
+    let pairs: Vec<(&str, String)> = blocks
+        .iter()
+        .filter(|block| !loot_tables.contains(&block.name))
+        .filter_map(|block| {
+            let standing = block.name.replacen("wall_", "", 1);
+            (standing != block.name && loot_tables.contains(&standing))
+                .then_some((block.name.as_str(), standing))
+        })
+        .collect();
+
+    let count = pairs.len();
+    let entries = pairs
+        .iter()
+        .map(|(wall, standing)| quote! { (#wall, #standing) });
+    quote! {
+        /// Vanilla blocks that use another block's loot table instead of their own, as
+        /// `(block, drops like)`.
+        pub const DROPS_LIKE: [(&str, &str); #count] = [#(#entries),*];
+    }
+}
+
+pub fn build(blocks: &[BlockClass], loot_tables: &BTreeSet<String>) -> String {
     let mut barrel_blocks = Vec::new();
     let mut crafting_table_blocks = Vec::new();
     let mut crop_blocks = Vec::new();
+    let mut drop_experience_blocks = Vec::new();
+    let mut end_portal_blocks = Vec::new();
     let mut end_portal_frame_blocks = Vec::new();
     let mut farm_blocks = Vec::new();
     let mut fence_blocks = Vec::new();
+    let mut liquid_blocks = Vec::new();
+    let mut nether_portal_blocks = Vec::new();
     let mut rotated_pillar_blocks = Vec::new();
     let mut standing_sign_blocks = Vec::new();
     let mut wall_sign_blocks = Vec::new();
@@ -49,9 +81,19 @@ pub fn build(blocks: &[BlockClass]) -> String {
             "BarrelBlock" => barrel_blocks.push(const_ident),
             "CraftingTableBlock" => crafting_table_blocks.push(const_ident),
             "CropBlock" => crop_blocks.push(const_ident),
+            "DropExperienceBlock"
+            | "RedStoneOreBlock"
+            | "SculkBlock"
+            | "SculkCatalystBlock"
+            | "SculkSensorBlock"
+            | "CalibratedSculkSensorBlock"
+            | "SculkShriekerBlock" => drop_experience_blocks.push(const_ident),
+            "EndPortalBlock" => end_portal_blocks.push(const_ident),
             "EndPortalFrameBlock" => end_portal_frame_blocks.push(const_ident),
             "FarmBlock" => farm_blocks.push(const_ident),
             "FenceBlock" => fence_blocks.push(const_ident),
+            "LiquidBlock" => liquid_blocks.push(const_ident),
+            "NetherPortalBlock" => nether_portal_blocks.push(const_ident),
             "RotatedPillarBlock" => rotated_pillar_blocks.push(const_ident),
             "StandingSignBlock" => standing_sign_blocks.push(const_ident),
             "WallSignBlock" => wall_sign_blocks.push(const_ident),
@@ -64,9 +106,13 @@ pub fn build(blocks: &[BlockClass]) -> String {
     let barrel_type = Ident::new("BarrelBlock", Span::call_site());
     let crafting_table_type = Ident::new("CraftingTableBlock", Span::call_site());
     let crop_type = Ident::new("CropBlock", Span::call_site());
+    let drop_experience_type = Ident::new("DropExperienceBlock", Span::call_site());
+    let end_portal_type = Ident::new("EndPortalBlock", Span::call_site());
     let end_portal_frame_type = Ident::new("EndPortalFrameBlock", Span::call_site());
     let farmland_type = Ident::new("FarmlandBlock", Span::call_site());
     let fence_type = Ident::new("FenceBlock", Span::call_site());
+    let liquid_type = Ident::new("LiquidBlock", Span::call_site());
+    let nether_portal_type = Ident::new("NetherPortalBlock", Span::call_site());
     let pillar_type = Ident::new("RotatedPillarBlock", Span::call_site());
     let standing_sign_type = Ident::new("StandingSignBlock", Span::call_site());
     let wall_sign_type = Ident::new("WallSignBlock", Span::call_site());
@@ -77,10 +123,17 @@ pub fn build(blocks: &[BlockClass]) -> String {
     let crafting_table_registrations =
         generate_registrations(crafting_table_blocks.iter(), &crafting_table_type);
     let crop_registrations = generate_registrations(crop_blocks.iter(), &crop_type);
+    let drop_experience_registrations =
+        generate_registrations(drop_experience_blocks.iter(), &drop_experience_type);
+    let end_portal_registrations =
+        generate_registrations(end_portal_blocks.iter(), &end_portal_type);
     let end_portal_frame_registrations =
         generate_registrations(end_portal_frame_blocks.iter(), &end_portal_frame_type);
     let farm_registrations = generate_registrations(farm_blocks.iter(), &farmland_type);
     let fence_registrations = generate_registrations(fence_blocks.iter(), &fence_type);
+    let liquid_registrations = generate_registrations(liquid_blocks.iter(), &liquid_type);
+    let nether_portal_registrations =
+        generate_registrations(nether_portal_blocks.iter(), &nether_portal_type);
     let pillar_registrations = generate_registrations(rotated_pillar_blocks.iter(), &pillar_type);
     let standing_sign_registrations =
         generate_registrations(standing_sign_blocks.iter(), &standing_sign_type);
@@ -92,30 +145,39 @@ pub fn build(blocks: &[BlockClass]) -> String {
     let wall_hanging_sign_registrations =
         generate_registrations(wall_hanging_sign_blocks.iter(), &wall_hanging_sign_type);
 
+    let drops_like = generate_drops_like(blocks, loot_tables);
+
     let output = quote! {
         //! Generated block behavior assignments.
 
         use steel_registry::vanilla_blocks;
         use crate::behavior::BlockBehaviorRegistry;
         use crate::behavior::blocks::{
-            BarrelBlock, CraftingTableBlock, CropBlock, EndPortalFrameBlock, FarmlandBlock,
-            FenceBlock, RotatedPillarBlock, StandingSignBlock, WallSignBlock,
-            CeilingHangingSignBlock, WallHangingSignBlock,
+            BarrelBlock, CraftingTableBlock, CropBlock, DropExperienceBlock, EndPortalBlock,
+            EndPortalFrameBlock, FarmlandBlock, FenceBlock, LiquidBlock, NetherPortalBlock,
+            RotatedPillarBlock, StandingSignBlock, WallSignBlock, CeilingHangingSignBlock,
+            WallHangingSignBlock,
         };
 
         pub fn register_block_behaviors(registry: &mut BlockBehaviorRegistry) {
             #barrel_registrations
             #crafting_table_registrations
             #crop_registrations
+            #drop_experience_registrations
+            #end_portal_registrations
             #end_portal_frame_registrations
             #farm_registrations
             #fence_registrations
+            #liquid_registrations
+            #nether_portal_registrations
             #pillar_registrations
             #standing_sign_registrations
             #wall_sign_registrations
             #ceiling_hanging_sign_registrations
             #wall_hanging_sign_registrations
         }
+
+        #drops_like
     };
 
     output.to_string()
@@ -1,5 +1,6 @@
 #![allow(missing_docs)]
 
+use std::collections::BTreeSet;
 use std::fs;
 
 use serde::Deserialize;
@@ -8,6 +9,8 @@ mod blocks;
 mod items;
 
 const OUT_DIR: &str = "src/behavior/generated";
+const BLOCK_LOOT_TABLES: &str =
+    "../steel-registry/build_assets/builtin_datapacks/minecraft/data/minecraft/loot_table/blocks";
 
 #[derive(Debug, Deserialize)]
 struct Classes {
@@ -21,15 +24,24 @@ pub fn main() {
     let classes: Classes =
         serde_json::from_str(&classes_json).expect("Failed to parse classes.json");
 
+    let block_loot_tables: BTreeSet<String> = fs::read_dir(BLOCK_LOOT_TABLES)
+        .expect("Failed to read block loot tables")
+        .filter_map(|entry| {
+            let path = entry.expect("Failed to read block loot table").path();
+            path.file_stem()?.to_str().map(str::to_owned)
+        })
+        .collect();
+
     fs::create_dir_all(OUT_DIR).expect("Failed to create output directory");
 
     fs::write(
         format!("{OUT_DIR}/blocks.rs"),
-        blocks::build(&classes.blocks),
+        blocks::build(&classes.blocks, &block_loot_tables),
     )
     .expect("Failed to write blocks.rs");
     fs::write(format!("{OUT_DIR}/items.rs"), items::build(&classes.items))
         .expect("Failed to write items.rs");
 
     println!("cargo:rerun-if-changed=build/classes.json");
+    println!("cargo:rerun-if-changed={BLOCK_LOOT_TABLES}");
 }
@@ -184,6 +184,59 @@ pub trait BlockBehaviour: Send + Sync {
         // Default: no-op
     }
 
+    /// Called when a scheduled tick for this block fires.
+    ///
+    /// Ticks are scheduled with [`World::schedule_block_tick`] and only fire if the
+    /// block at `pos` is still this block. Used for things like buttons releasing,
+    /// repeaters switching and sand falling.
+    ///
+    /// # Arguments
+    /// * `state` - The current block state
+    /// * `world` - The world the block is in
+    /// * `pos` - The position of the block
+    #[allow(unused_variables)]
+    fn tick(&self, state: BlockStateId, world: &World, pos: BlockPos) {
+        // Default: no-op
+    }
+
+    /// Called every tick for each block a player's bounding box is inside of.
+    ///
+    /// This is the Rust equivalent of vanilla's `BlockBehaviour.entityInside()`, limited to
+    /// players until other entities move on their own. Used by portals.
+    ///
+    /// # Arguments
+    /// * `state` - The current block state
+    /// * `world` - The world the block is in
+    /// * `pos` - The position of the block
+    /// * `player` - The player inside the block
+    #[allow(unused_variables)]
+    fn player_inside(&self, state: BlockStateId, world: &World, pos: BlockPos, player: &Player) {
+        // Default: no-op
+    }
+
+    /// Called after a player breaks the block and its drops have been spawned.
+    ///
+    /// This is the Rust equivalent of vanilla's `BlockBehaviour.spawnAfterBreak()`. Ores
+    /// use it to drop experience.
+    ///
+    /// # Arguments
+    /// * `state` - The state the block had
+    /// * `world` - The world the block was in
+    /// * `pos` - The position of the block
+    /// * `tool` - The item the block was broken with, as it was before it took damage
+    /// * `drop_experience` - Whether experience may be dropped
+    #[allow(unused_variables)]
+    fn spawn_after_break(
+        &self,
+        state: BlockStateId,
+        world: &World,
+        pos: BlockPos,
+        tool: &ItemStack,
+        drop_experience: bool,
+    ) {
+        // Default: no-op
+    }
+
     // === Block Entity Methods ===
 
     /// Returns whether this block has an associated block entity.
@@ -147,8 +147,9 @@ impl BlockBehaviour for CropBlock {
     }
 
     fn random_tick(&self, state: BlockStateId, world: &World, pos: BlockPos) {
-        // TODO: Check light level >= 9 when light engine is implemented
-        // For now, assume sufficient light
+        if world.get_raw_brightness(&pos, 0) < 9 {
+            return;
+        }
 
         let age = self.get_age(state);
         if age < self.max_age {
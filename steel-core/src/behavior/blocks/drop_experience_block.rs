@@ -0,0 +1,119 @@
+//! Experience dropping block behavior implementation.
+//!
+//! Ores and sculk blocks drop experience orbs when broken without silk touch.
+
+use std::ops::RangeInclusive;
+
+use steel_registry::blocks::BlockRef;
+use steel_registry::game_rules::GameRuleValue;
+use steel_registry::item_stack::ItemStack;
+use steel_registry::vanilla_blocks;
+use steel_registry::vanilla_game_rules::BLOCK_DROPS;
+use steel_utils::math::Vector3;
+use steel_utils::{BlockPos, BlockStateId};
+
+use crate::behavior::block::BlockBehaviour;
+use crate::behavior::context::BlockPlaceContext;
+use crate::entity::ExperienceOrb;
+use crate::world::World;
+
+/// Behavior for blocks that drop experience when broken.
+///
+/// Covers vanilla's `DropExperienceBlock` as well as the redstone ore and sculk
+/// blocks, which only differ from it in behavior Steel doesn't implement yet.
+pub struct DropExperienceBlock {
+    block: BlockRef,
+    experience: RangeInclusive<i32>,
+}
+
+impl DropExperienceBlock {
+    /// Creates a new experience dropping block behavior.
+    ///
+    /// The amount of experience is picked from the block, using the same ranges as
+    /// vanilla's `Blocks` class.
+    #[must_use]
+    pub fn new(block: BlockRef) -> Self {
+        Self {
+            block,
+            experience: experience_range(block),
+        }
+    }
+}
+
+impl BlockBehaviour for DropExperienceBlock {
+    fn get_state_for_placement(&self, _context: &BlockPlaceContext<'_>) -> Option<BlockStateId> {
+        Some(self.block.default_state())
+    }
+
+    fn spawn_after_break(
+        &self,
+        _state: BlockStateId,
+        world: &World,
+        pos: BlockPos,
+        tool: &ItemStack,
+        drop_experience: bool,
+    ) {
+        // TODO: Route this through the block_experience enchantment effect once
+        // enchantments are implemented, silk touch is the only vanilla user of it
+        if !drop_experience
+            || tool.get_enchantment_level_by_name("silk_touch") > 0
+            || world.get_game_rule(BLOCK_DROPS) != GameRuleValue::Bool(true)
+        {
+            return;
+        }
+
+        let amount = rand::random_range(self.experience.clone());
+        if amount > 0 {
+            let center = Vector3::new(
+                f64::from(pos.x()) + 0.5,
+                f64::from(pos.y()) + 0.5,
+                f64::from(pos.z()) + 0.5,
+            );
+            ExperienceOrb::award(world, center, amount);
+        }
+    }
+}
+
+/// Gets the experience a block drops, matching the values vanilla's `Blocks` class
+/// passes to each experience dropping block.
+fn experience_range(block: BlockRef) -> RangeInclusive<i32> {
+    let ranges = [
+        (vanilla_blocks::GOLD_ORE, 0..=0),
+        (vanilla_blocks::DEEPSLATE_GOLD_ORE, 0..=0),
+        (vanilla_blocks::IRON_ORE, 0..=0),
+        (vanilla_blocks::DEEPSLATE_IRON_ORE, 0..=0),
+        (vanilla_blocks::COPPER_ORE, 0..=0),
+        (vanilla_blocks::DEEPSLATE_COPPER_ORE, 0..=0),
+        (vanilla_blocks::COAL_ORE, 0..=2),
+        (vanilla_blocks::DEEPSLATE_COAL_ORE, 0..=2),
+        (vanilla_blocks::DIAMOND_ORE, 3..=7),
+        (vanilla_blocks::DEEPSLATE_DIAMOND_ORE, 3..=7),
+        (vanilla_blocks::EMERALD_ORE, 3..=7),
+        (vanilla_blocks::DEEPSLATE_EMERALD_ORE, 3..=7),
+        (vanilla_blocks::LAPIS_ORE, 2..=5),
+        (vanilla_blocks::DEEPSLATE_LAPIS_ORE, 2..=5),
+        (vanilla_blocks::NETHER_QUARTZ_ORE, 2..=5),
+        (vanilla_blocks::NETHER_GOLD_ORE, 0..=1),
+        (vanilla_blocks::REDSTONE_ORE, 1..=5),
+        (vanilla_blocks::DEEPSLATE_REDSTONE_ORE, 1..=5),
+        (vanilla_blocks::SCULK, 1..=1),
+        (vanilla_blocks::SCULK_CATALYST, 5..=5),
+        (vanilla_blocks::SCULK_SENSOR, 5..=5),
+        (vanilla_blocks::CALIBRATED_SCULK_SENSOR, 5..=5),
+        (vanilla_blocks::SCULK_SHRIEKER, 5..=5),
+    ];
+
+    ranges
+        .into_iter()
+        .find(|(other, _)| other.key == block.key)
+        .map_or_else(
+            || {
+                log::warn!(
+                    "{} has no experience range, it drops no experience",
+                    block.key
+                );
+                0..=0
+            },
+            |(_, range)| range,
+        )
+}
@@ -0,0 +1,40 @@
+//! End portal block implementation.
+
+use steel_registry::blocks::BlockRef;
+use steel_utils::{BlockPos, BlockStateId};
+
+use crate::behavior::block::BlockBehaviour;
+use crate::behavior::context::BlockPlaceContext;
+use crate::player::Player;
+use crate::world::{Portal, World};
+
+/// Height of the part of the block that sends players through, like vanilla's shape.
+const PORTAL_HEIGHT: f64 = 0.75;
+
+/// Behavior for the end portal block.
+///
+/// Players stepping into it go to the end, or from the end back to the overworld.
+pub struct EndPortalBlock {
+    block: BlockRef,
+}
+
+impl EndPortalBlock {
+    /// Creates a new end portal block behavior for the given block.
+    #[must_use]
+    pub const fn new(block: BlockRef) -> Self {
+        Self { block }
+    }
+}
+
+impl BlockBehaviour for EndPortalBlock {
+    fn get_state_for_placement(&self, _context: &BlockPlaceContext<'_>) -> Option<BlockStateId> {
+        Some(self.block.default_state())
+    }
+
+    fn player_inside(&self, _state: BlockStateId, _world: &World, pos: BlockPos, player: &Player) {
+        // TODO: Show the end credits the first time a player leaves the end
+        if player.position.lock().y < f64::from(pos.y()) + PORTAL_HEIGHT {
+            player.set_as_inside_portal(Portal::End, pos);
+        }
+    }
+}
@@ -1,12 +1,25 @@
 //! End portal frame block implementation.
 
+use std::ptr;
+
 use steel_registry::blocks::BlockRef;
 use steel_registry::blocks::block_state_ext::BlockStateExt;
-use steel_registry::blocks::properties::BlockStateProperties;
-use steel_utils::BlockStateId;
+use steel_registry::blocks::properties::{BlockStateProperties, Direction};
+use steel_registry::{REGISTRY, level_events, vanilla_blocks};
+use steel_utils::types::UpdateFlags;
+use steel_utils::{BlockPos, BlockStateId};
 
 use crate::behavior::block::BlockBehaviour;
 use crate::behavior::context::BlockPlaceContext;
+use crate::world::World;
+
+/// The horizontal directions, each pointing from the portal center to one side of the ring.
+const SIDES: [Direction; 4] = [
+    Direction::North,
+    Direction::East,
+    Direction::South,
+    Direction::West,
+];
 
 /// Behavior for end portal frame blocks.
 pub struct EndPortalFrameBlock {
@@ -19,6 +32,71 @@ impl EndPortalFrameBlock {
     pub const fn new(block: BlockRef) -> Self {
         Self { block }
     }
+
+    /// Fills the portal if the frame at `pos` completed a ring of 12 frames with eyes.
+    ///
+    /// The frames must all face away from the portal or all face towards it, like vanilla's
+    /// `EndPortalFrameBlock.getOrCreatePortalShape()` pattern. Returns whether a portal
+    /// was created.
+    pub fn try_create_portal(world: &World, pos: BlockPos) -> bool {
+        let state = world.get_block_state(&pos);
+        let Some(facing) = state.try_get_value(&BlockStateProperties::HORIZONTAL_FACING) else {
+            return false;
+        };
+
+        // The frame is on one of the three middle blocks of a side, so the center is two
+        // blocks in front of or behind it and at most one block to the side.
+        for inward in [facing.opposite(), facing] {
+            let (in_x, _, in_z) = inward.offset();
+            let (side_x, _, side_z) = inward.rotate_y_clockwise().offset();
+            for shift in -1..=1 {
+                let center = pos.offset(in_x * 2 + side_x * shift, 0, in_z * 2 + side_z * shift);
+                if is_complete_ring(world, center) {
+                    create_portal(world, center);
+                    return true;
+                }
+            }
+        }
+        false
+    }
+}
+
+/// Yields the 12 frame positions around a 3x3 portal, each with the direction pointing
+/// away from the portal.
+fn ring(center: BlockPos) -> impl Iterator<Item = (BlockPos, Direction)> {
+    SIDES.into_iter().flat_map(move |side| {
+        let (out_x, _, out_z) = side.offset();
+        let (along_x, _, along_z) = side.rotate_y_clockwise().offset();
+        (-1..=1).map(move |i| {
+            let pos = center.offset(out_x * 2 + along_x * i, 0, out_z * 2 + along_z * i);
+            (pos, side)
+        })
+    })
+}
+
+fn is_complete_ring(world: &World, center: BlockPos) -> bool {
+    let frames_face = |outward: bool| {
+        ring(center).all(|(pos, side)| {
+            let state = world.get_block_state(&pos);
+            let expected = if outward { side } else { side.opposite() };
+            ptr::eq(state.get_block(), vanilla_blocks::END_PORTAL_FRAME)
+                && state.get_value(&BlockStateProperties::EYE)
+                && state.get_value(&BlockStateProperties::HORIZONTAL_FACING) == expected
+        })
+    };
+    frames_face(true) || frames_face(false)
+}
+
+fn create_portal(world: &World, center: BlockPos) {
+    let portal = REGISTRY
+        .blocks
+        .get_default_state_id(vanilla_blocks::END_PORTAL);
+    for x in -1..=1 {
+        for z in -1..=1 {
+            world.set_block(center.offset(x, 0, z), portal, UpdateFlags::UPDATE_CLIENTS);
+        }
+    }
+    world.global_level_event(level_events::SOUND_END_PORTAL_SPAWN, center, 0);
 }
 
 impl BlockBehaviour for EndPortalFrameBlock {
@@ -29,3 +107,26 @@ impl BlockBehaviour for EndPortalFrameBlock {
         ))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use steel_utils::BlockPos;
+
+    use super::ring;
+
+    #[test]
+    fn test_ring_surrounds_portal() {
+        let center = BlockPos::new(10, 40, -7);
+        let frames: Vec<_> = ring(center).collect();
+        assert_eq!(frames.len(), 12);
+        for (pos, side) in frames {
+            let dx = pos.x() - center.x();
+            let dz = pos.z() - center.z();
+            // Every frame is on the edge of the 5x5 square but not on a corner.
+            assert_eq!(dx.abs().max(dz.abs()), 2);
+            assert!(dx.abs().min(dz.abs()) <= 1);
+            let (out_x, _, out_z) = side.offset();
+            assert_eq!(dx * out_x + dz * out_z, 2);
+        }
+    }
+}
@@ -107,10 +107,9 @@ impl BlockBehaviour for FarmlandBlock {
     fn random_tick(&self, state: BlockStateId, world: &World, pos: BlockPos) {
         let moisture: u8 = state.get_value(&BlockStateProperties::MOISTURE);
 
-        // TODO: Check for rain when weather is implemented
-        let is_near_water = Self::is_near_water(world, pos);
+        let is_wet = Self::is_near_water(world, pos) || world.is_raining_at(pos.offset(0, 1, 0));
 
-        if !is_near_water {
+        if !is_wet {
             // Not near water - decrease moisture or turn to dirt
             if moisture > 0 {
                 // Decrease moisture by 1
@@ -0,0 +1,138 @@
+//! Liquid block implementation for water and lava.
+
+use std::ptr;
+
+use steel_registry::blocks::BlockRef;
+use steel_registry::blocks::block_state_ext::BlockStateExt;
+use steel_registry::blocks::properties::Direction;
+use steel_registry::vanilla_blocks;
+use steel_utils::types::UpdateFlags;
+use steel_utils::{BlockPos, BlockStateId, Identifier};
+
+use crate::behavior::block::BlockBehaviour;
+use crate::behavior::context::BlockPlaceContext;
+use crate::behavior::fluids::{FlowingFluid, fizz};
+use crate::world::World;
+
+/// Behavior for the water and lava blocks.
+///
+/// The block schedules fluid ticks whenever something around it changes, and the
+/// fluid itself does the spreading. Lava touching water hardens here, before it
+/// ever gets to flow.
+pub struct LiquidBlock {
+    block: BlockRef,
+    fluid: FlowingFluid,
+}
+
+impl LiquidBlock {
+    /// Creates a new liquid block behavior for the given block.
+    #[must_use]
+    pub fn new(block: BlockRef) -> Self {
+        let fluid = if ptr::eq(block, vanilla_blocks::LAVA) {
+            FlowingFluid::lava()
+        } else {
+            FlowingFluid::water()
+        };
+        Self { block, fluid }
+    }
+
+    fn is_lava(&self) -> bool {
+        ptr::eq(self.block, vanilla_blocks::LAVA)
+    }
+
+    /// Schedules a tick for the fluid held by `state`.
+    fn schedule_tick(&self, world: &World, pos: BlockPos, state: BlockStateId) {
+        let fluid = state.get_fluid_state().fluid();
+        world.schedule_fluid_tick(pos, fluid, self.fluid.tick_delay(world));
+    }
+
+    /// Hardens lava next to water into obsidian or cobblestone, and lava over soul soil
+    /// next to blue ice into basalt.
+    ///
+    /// Returns false if the lava was replaced and shouldn't spread.
+    fn should_spread_liquid(&self, world: &World, pos: BlockPos, state: BlockStateId) -> bool {
+        if !self.is_lava() {
+            return true;
+        }
+
+        let water = Identifier::vanilla_static("water");
+        let below = world.get_block_state(&Direction::Down.relative(&pos));
+        let over_soul_soil = ptr::eq(below.get_block(), vanilla_blocks::SOUL_SOIL);
+        for direction in [
+            Direction::Down,
+            Direction::South,
+            Direction::North,
+            Direction::East,
+            Direction::West,
+        ] {
+            let neighbor_state = world.get_block_state(&direction.opposite().relative(&pos));
+            if neighbor_state.get_fluid_state().is(&water) {
+                let block = if state.get_fluid_state().is_source() {
+                    vanilla_blocks::OBSIDIAN
+                } else {
+                    vanilla_blocks::COBBLESTONE
+                };
+                world.set_block(pos, block.default_state(), UpdateFlags::UPDATE_ALL);
+                fizz(world, pos);
+                return false;
+            }
+            if over_soul_soil && ptr::eq(neighbor_state.get_block(), vanilla_blocks::BLUE_ICE) {
+                world.set_block(
+                    pos,
+                    vanilla_blocks::BASALT.default_state(),
+                    UpdateFlags::UPDATE_ALL,
+                );
+                fizz(world, pos);
+                return false;
+            }
+        }
+        true
+    }
+}
+
+impl BlockBehaviour for LiquidBlock {
+    fn get_state_for_placement(&self, _context: &BlockPlaceContext<'_>) -> Option<BlockStateId> {
+        Some(self.block.default_state())
+    }
+
+    fn update_shape(
+        &self,
+        state: BlockStateId,
+        world: &World,
+        pos: BlockPos,
+        _direction: Direction,
+        _neighbor_pos: BlockPos,
+        neighbor_state: BlockStateId,
+    ) -> BlockStateId {
+        if state.get_fluid_state().is_source() || neighbor_state.get_fluid_state().is_source() {
+            self.schedule_tick(world, pos, state);
+        }
+        state
+    }
+
+    fn on_place(
+        &self,
+        state: BlockStateId,
+        world: &World,
+        pos: BlockPos,
+        _old_state: BlockStateId,
+        _moved_by_piston: bool,
+    ) {
+        if self.should_spread_liquid(world, pos, state) {
+            self.schedule_tick(world, pos, state);
+        }
+    }
+
+    fn handle_neighbor_changed(
+        &self,
+        state: BlockStateId,
+        world: &World,
+        pos: BlockPos,
+        _source_block: BlockRef,
+        _moved_by_piston: bool,
+    ) {
+        if self.should_spread_liquid(world, pos, state) {
+            self.schedule_tick(world, pos, state);
+        }
+    }
+}
@@ -6,18 +6,26 @@
 mod barrel_block;
 mod crafting_table_block;
 mod crop_block;
+mod drop_experience_block;
+mod end_portal_block;
 mod end_portal_frame_block;
 mod farmland_block;
 mod fence_block;
+mod liquid_block;
+mod nether_portal_block;
 mod rotated_pillar_block;
 mod sign_block;
 
 pub use barrel_block::BarrelBlock;
 pub use crafting_table_block::CraftingTableBlock;
 pub use crop_block::CropBlock;
+pub use drop_experience_block::DropExperienceBlock;
+pub use end_portal_block::EndPortalBlock;
 pub use end_portal_frame_block::EndPortalFrameBlock;
 pub use farmland_block::FarmlandBlock;
 pub use fence_block::FenceBlock;
+pub use liquid_block::LiquidBlock;
+pub use nether_portal_block::NetherPortalBlock;
 pub use rotated_pillar_block::RotatedPillarBlock;
 pub use sign_block::{
     CeilingHangingSignBlock, StandingSignBlock, WallHangingSignBlock, WallSignBlock,
@@ -0,0 +1,34 @@
+//! Nether portal block implementation.
+
+use steel_registry::blocks::BlockRef;
+use steel_utils::{BlockPos, BlockStateId};
+
+use crate::behavior::block::BlockBehaviour;
+use crate::behavior::context::BlockPlaceContext;
+use crate::player::Player;
+use crate::world::{Portal, World};
+
+/// Behavior for the nether portal block.
+///
+/// Players standing in it long enough travel between the overworld and the nether.
+pub struct NetherPortalBlock {
+    block: BlockRef,
+}
+
+impl NetherPortalBlock {
+    /// Creates a new nether portal block behavior for the given block.
+    #[must_use]
+    pub const fn new(block: BlockRef) -> Self {
+        Self { block }
+    }
+}
+
+impl BlockBehaviour for NetherPortalBlock {
+    fn get_state_for_placement(&self, _context: &BlockPlaceContext<'_>) -> Option<BlockStateId> {
+        Some(self.block.default_state())
+    }
+
+    fn player_inside(&self, _state: BlockStateId, _world: &World, pos: BlockPos, player: &Player) {
+        player.set_as_inside_portal(Portal::Nether, pos);
+    }
+}
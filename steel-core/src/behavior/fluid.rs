@@ -0,0 +1,83 @@
+//! Fluid behavior trait and registry.
+
+use steel_registry::REGISTRY;
+use steel_registry::fluid::FluidRef;
+use steel_utils::{BlockPos, BlockStateId};
+
+use crate::world::World;
+
+/// Trait defining the behavior of a fluid.
+///
+/// Fluids are placed as blocks, so most of their behavior lives on their block. This
+/// trait handles what vanilla keeps on `Fluid` itself, like flowing on scheduled ticks.
+pub trait FluidBehaviour: Send + Sync {
+    /// Called when a scheduled fluid tick for this fluid fires.
+    ///
+    /// Scheduled with [`World::schedule_fluid_tick`]. Only called if the block at `pos`
+    /// still holds this fluid.
+    ///
+    /// # Arguments
+    /// * `world` - The world the fluid is in
+    /// * `pos` - The position of the fluid
+    /// * `state` - The block state holding the fluid
+    #[allow(unused_variables)]
+    fn tick(&self, world: &World, pos: BlockPos, state: BlockStateId) {
+        // Default: no-op
+    }
+}
+
+/// Default fluid behavior that does nothing.
+pub struct DefaultFluidBehaviour;
+
+impl FluidBehaviour for DefaultFluidBehaviour {}
+
+/// Registry for fluid behaviors.
+///
+/// Created after the main registry is frozen. All fluids start with the default
+/// behavior, then custom behaviors are registered for specific fluids.
+pub struct FluidBehaviorRegistry {
+    behaviors: Vec<Box<dyn FluidBehaviour>>,
+}
+
+impl FluidBehaviorRegistry {
+    /// Creates a new behavior registry with default behaviors for all fluids.
+    #[must_use]
+    pub fn new() -> Self {
+        let behaviors = (0..REGISTRY.fluids.len())
+            .map(|_| Box::new(DefaultFluidBehaviour) as Box<dyn FluidBehaviour>)
+            .collect();
+
+        Self { behaviors }
+    }
+
+    /// Sets a custom behavior for a fluid.
+    ///
+    /// # Panics
+    /// Panics if the fluid is not registered.
+    pub fn set_behavior(&mut self, fluid: FluidRef, behavior: Box<dyn FluidBehaviour>) {
+        let id = *REGISTRY
+            .fluids
+            .get_id(fluid)
+            .expect("fluid should be registered");
+        self.behaviors[id] = behavior;
+    }
+
+    /// Gets the behavior for a fluid.
+    ///
+    /// # Panics
+    /// Panics if the fluid is not registered.
+    #[must_use]
+    pub fn get_behavior(&self, fluid: FluidRef) -> &dyn FluidBehaviour {
+        let id = *REGISTRY
+            .fluids
+            .get_id(fluid)
+            .expect("fluid should be registered");
+        self.behaviors[id].as_ref()
+    }
+}
+
+impl Default for FluidBehaviorRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,702 @@
+//! Flowing fluid implementation shared by water and lava.
+
+use std::ptr;
+
+use rustc_hash::FxHashMap;
+use steel_registry::REGISTRY;
+use steel_registry::blocks::block_state_ext::BlockStateExt;
+use steel_registry::blocks::properties::{BlockStateProperties, Direction};
+use steel_registry::blocks::shapes::is_shape_full_block;
+use steel_registry::fluid::{FluidRef, FluidState};
+use steel_registry::game_rules::GameRuleValue;
+use steel_registry::item_stack::ItemStack;
+use steel_registry::vanilla_game_rules::{LAVA_SOURCE_CONVERSION, WATER_SOURCE_CONVERSION};
+use steel_registry::{vanilla_blocks, vanilla_fluids};
+use steel_utils::math::Vector3;
+use steel_utils::types::UpdateFlags;
+use steel_utils::{BlockPos, BlockStateId, Identifier};
+
+use crate::behavior::fluid::FluidBehaviour;
+use crate::behavior::fluids::{create_legacy_block, fizz, fluid_height, fluid_id, is_same_fluid};
+use crate::block_entity::BlockEntitySnapshot;
+use crate::world::World;
+
+/// Horizontal directions in the order vanilla's `Direction.Plane.HORIZONTAL` visits them.
+const HORIZONTAL: [Direction; 4] = [
+    Direction::North,
+    Direction::East,
+    Direction::South,
+    Direction::West,
+];
+
+/// Distance returned by slope searches that find no way down.
+const NO_SLOPE: u32 = 1000;
+
+const WATER_TAG: Identifier = Identifier::vanilla_static("water");
+const LAVA_TAG: Identifier = Identifier::vanilla_static("lava");
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum FluidKind {
+    Water,
+    Lava,
+}
+
+/// Behavior for fluids that spread, matching vanilla's `FlowingFluid` together with the
+/// `WaterFluid` and `LavaFluid` overrides.
+///
+/// Sources and flowing fluids spread on scheduled ticks: down first, then sideways toward
+/// the nearest drop within the slope search distance. Flowing fluid recomputes its level
+/// from its neighbors each tick and dries up once nothing feeds it.
+#[derive(Clone, Copy)]
+pub struct FlowingFluid {
+    kind: FluidKind,
+}
+
+impl FlowingFluid {
+    /// Creates the behavior for water and flowing water.
+    #[must_use]
+    pub const fn water() -> Self {
+        Self {
+            kind: FluidKind::Water,
+        }
+    }
+
+    /// Creates the behavior for lava and flowing lava.
+    #[must_use]
+    pub const fn lava() -> Self {
+        Self {
+            kind: FluidKind::Lava,
+        }
+    }
+
+    fn source(self) -> FluidRef {
+        match self.kind {
+            FluidKind::Water => &vanilla_fluids::WATER,
+            FluidKind::Lava => &vanilla_fluids::LAVA,
+        }
+    }
+
+    fn flowing(self) -> FluidRef {
+        match self.kind {
+            FluidKind::Water => &vanilla_fluids::FLOWING_WATER,
+            FluidKind::Lava => &vanilla_fluids::FLOWING_LAVA,
+        }
+    }
+
+    /// Returns the delay between spread ticks in `world`.
+    ///
+    /// Lava flows three times faster in dimensions with fast lava, like the Nether.
+    #[must_use]
+    pub fn tick_delay(self, world: &World) -> u32 {
+        match self.kind {
+            FluidKind::Lava if world.dimension.fast_lava => 10,
+            _ => self.source().tick_delay,
+        }
+    }
+
+    /// Returns how much the level drops per block of horizontal flow.
+    fn drop_off(self, world: &World) -> u8 {
+        match self.kind {
+            FluidKind::Lava if !world.dimension.fast_lava => 2,
+            _ => 1,
+        }
+    }
+
+    /// Returns how far sideways the fluid looks for a drop to flow toward.
+    fn slope_find_distance(self, world: &World) -> u32 {
+        match self.kind {
+            FluidKind::Lava if !world.dimension.fast_lava => 2,
+            _ => 4,
+        }
+    }
+
+    fn can_convert_to_source(self, world: &World) -> bool {
+        let rule = match self.kind {
+            FluidKind::Water => WATER_SOURCE_CONVERSION,
+            FluidKind::Lava => LAVA_SOURCE_CONVERSION,
+        };
+        world.get_game_rule(rule) == GameRuleValue::Bool(true)
+    }
+
+    fn source_state(self, falling: bool) -> FluidState {
+        FluidState::new(fluid_id(self.source()), 8, falling)
+    }
+
+    fn flowing_state(self, amount: u8, falling: bool) -> FluidState {
+        FluidState::flowing(fluid_id(self.flowing()), amount, falling)
+    }
+
+    /// Returns whether `fluid_state` holds this fluid, source or flowing.
+    fn is_same(self, fluid_state: FluidState) -> bool {
+        !fluid_state.is_empty() && is_same_fluid(fluid_state.fluid(), self.source())
+    }
+
+    fn is_source_of_this_type(self, fluid_state: FluidState) -> bool {
+        self.is_same(fluid_state) && fluid_state.is_source()
+    }
+
+    /// Returns which of the flowing fluids `fluid_state` holds, if any.
+    #[must_use]
+    pub fn of(fluid_state: FluidState) -> Option<Self> {
+        [Self::water(), Self::lava()]
+            .into_iter()
+            .find(|fluid| fluid.is_same(fluid_state))
+    }
+
+    /// Returns whether the fluid state next to this fluid takes part in its flow.
+    fn affects_flow(self, fluid_state: FluidState) -> bool {
+        fluid_state.is_empty() || self.is_same(fluid_state)
+    }
+
+    /// Returns the direction the fluid at `pos` pushes entities in, with a length of one
+    /// or zero.
+    ///
+    /// Flowing fluid runs towards lower neighbors. Falling fluid next to a wall mostly
+    /// pushes down. Matches vanilla's `FlowingFluid.getFlow()`.
+    #[must_use]
+    pub fn get_flow(self, world: &World, pos: BlockPos, fluid_state: FluidState) -> Vector3<f64> {
+        let own_height = fluid_state.own_height();
+        let mut flow = Vector3::new(0.0, 0.0, 0.0);
+        for direction in HORIZONTAL {
+            let neighbor_pos = direction.relative(&pos);
+            let neighbor_state = world.get_block_state(&neighbor_pos);
+            let neighbor_fluid = neighbor_state.get_fluid_state();
+            if !self.affects_flow(neighbor_fluid) {
+                continue;
+            }
+            let neighbor_height = neighbor_fluid.own_height();
+            let mut distance = 0.0;
+            if neighbor_height == 0.0 {
+                // Flow towards a drop into more of the same fluid
+                if !blocks_motion(neighbor_state) {
+                    let below_fluid = world
+                        .get_block_state(&Direction::Down.relative(&neighbor_pos))
+                        .get_fluid_state();
+                    let below_height = below_fluid.own_height();
+                    if self.affects_flow(below_fluid) && below_height > 0.0 {
+                        distance = own_height - (below_height - 0.888_888_9);
+                    }
+                }
+            } else if neighbor_height > 0.0 {
+                distance = own_height - neighbor_height;
+            }
+            if distance != 0.0 {
+                let (step_x, _, step_z) = direction.offset();
+                flow.x += f64::from(step_x as f32 * distance);
+                flow.z += f64::from(step_z as f32 * distance);
+            }
+        }
+
+        if fluid_state.falling {
+            for direction in HORIZONTAL {
+                let side = direction.relative(&pos);
+                if self.is_solid_face(world, side, direction)
+                    || self.is_solid_face(world, Direction::Up.relative(&side), direction)
+                {
+                    flow = flow.normalize_or_zero().add_raw(0.0, -6.0, 0.0);
+                    break;
+                }
+            }
+        }
+        flow.normalize_or_zero()
+    }
+
+    /// Returns whether the block at `pos` walls in falling fluid on its `direction` face.
+    ///
+    /// Matches vanilla's `FlowingFluid.isSolidFace()`.
+    fn is_solid_face(self, world: &World, pos: BlockPos, direction: Direction) -> bool {
+        let state = world.get_block_state(&pos);
+        if self.is_same(state.get_fluid_state()) {
+            return false;
+        }
+        let block = state.get_block();
+        direction == Direction::Up
+            || (!ptr::eq(block, vanilla_blocks::ICE)
+                && !ptr::eq(block, vanilla_blocks::FROSTED_ICE)
+                && state.is_face_sturdy(direction))
+    }
+
+    /// Returns the delay before the next tick after changing from `old` to `new`.
+    ///
+    /// Rising lava usually waits four times as long, which keeps lava lakes from
+    /// refilling as quickly as water.
+    fn spread_delay(self, world: &World, pos: BlockPos, old: FluidState, new: FluidState) -> u32 {
+        let delay = self.tick_delay(world);
+        if self.kind == FluidKind::Lava
+            && !old.is_empty()
+            && !new.is_empty()
+            && !old.falling
+            && !new.falling
+            && fluid_height(world, pos, new) > fluid_height(world, pos, old)
+            && rand::random_range(0..4) != 0
+        {
+            delay * 4
+        } else {
+            delay
+        }
+    }
+
+    /// Computes the fluid `pos` should hold from its neighbors.
+    ///
+    /// Matches vanilla's `FlowingFluid.getNewLiquid()`.
+    fn get_new_liquid(self, world: &World, pos: BlockPos, state: BlockStateId) -> FluidState {
+        let mut highest_neighbor = 0;
+        let mut neighbor_sources = 0;
+        for direction in HORIZONTAL {
+            let neighbor_state = world.get_block_state(&direction.relative(&pos));
+            let neighbor_fluid = neighbor_state.get_fluid_state();
+            if self.is_same(neighbor_fluid)
+                && can_pass_through_wall(direction, state, neighbor_state)
+            {
+                if neighbor_fluid.is_source() {
+                    neighbor_sources += 1;
+                }
+                highest_neighbor = highest_neighbor.max(neighbor_fluid.amount);
+            }
+        }
+
+        if neighbor_sources >= 2 && self.can_convert_to_source(world) {
+            let below_state = world.get_block_state(&Direction::Down.relative(&pos));
+            if below_state.is_solid() || self.is_source_of_this_type(below_state.get_fluid_state())
+            {
+                return self.source_state(false);
+            }
+        }
+
+        let above_state = world.get_block_state(&Direction::Up.relative(&pos));
+        if self.is_same(above_state.get_fluid_state())
+            && can_pass_through_wall(Direction::Up, state, above_state)
+        {
+            return self.flowing_state(8, true);
+        }
+
+        let amount = i32::from(highest_neighbor) - i32::from(self.drop_off(world));
+        if amount <= 0 {
+            FluidState::EMPTY
+        } else {
+            self.flowing_state(amount as u8, false)
+        }
+    }
+
+    /// Spreads the fluid at `pos` down if it can, and otherwise sideways.
+    fn spread(self, world: &World, pos: BlockPos, state: BlockStateId, fluid_state: FluidState) {
+        if fluid_state.is_empty() {
+            return;
+        }
+
+        let below_pos = Direction::Down.relative(&pos);
+        let below_state = world.get_block_state(&below_pos);
+        let below_fluid = below_state.get_fluid_state();
+        if self.can_maybe_pass_through(state, Direction::Down, below_state, below_fluid) {
+            let new_fluid = self.get_new_liquid(world, below_pos, below_state);
+            if can_be_replaced_with(
+                world,
+                below_pos,
+                below_fluid,
+                new_fluid.fluid(),
+                Direction::Down,
+            ) && can_hold_specific_fluid(below_state, new_fluid.fluid())
+            {
+                self.spread_to(world, below_pos, below_state, Direction::Down, new_fluid);
+                if self.source_neighbor_count(world, pos) >= 3 {
+                    self.spread_to_sides(world, pos, fluid_state, state);
+                }
+                return;
+            }
+        }
+
+        if fluid_state.is_source() || !self.is_water_hole(state, below_state) {
+            self.spread_to_sides(world, pos, fluid_state, state);
+        }
+    }
+
+    fn spread_to_sides(
+        self,
+        world: &World,
+        pos: BlockPos,
+        fluid_state: FluidState,
+        state: BlockStateId,
+    ) {
+        let neighbor_amount = if fluid_state.falling {
+            7
+        } else {
+            i32::from(fluid_state.amount) - i32::from(self.drop_off(world))
+        };
+        if neighbor_amount <= 0 {
+            return;
+        }
+
+        for (direction, neighbor_fluid) in self.get_spread(world, pos, state) {
+            let neighbor_pos = direction.relative(&pos);
+            let neighbor_state = world.get_block_state(&neighbor_pos);
+            self.spread_to(
+                world,
+                neighbor_pos,
+                neighbor_state,
+                direction,
+                neighbor_fluid,
+            );
+        }
+    }
+
+    /// Returns the directions the fluid should spread in, with the fluid for each.
+    ///
+    /// Only the directions with the shortest path to a drop are kept, so fluid on flat
+    /// ground spreads everywhere while fluid near an edge heads for it.
+    fn get_spread(
+        self,
+        world: &World,
+        pos: BlockPos,
+        state: BlockStateId,
+    ) -> Vec<(Direction, FluidState)> {
+        let mut lowest = NO_SLOPE;
+        let mut spreads = Vec::new();
+        let mut context = SpreadContext::default();
+
+        for direction in HORIZONTAL {
+            let test_pos = direction.relative(&pos);
+            let test_state = world.get_block_state(&test_pos);
+            let test_fluid = test_state.get_fluid_state();
+            if !self.can_maybe_pass_through(state, direction, test_state, test_fluid) {
+                continue;
+            }
+            let new_fluid = self.get_new_liquid(world, test_pos, test_state);
+            if !can_hold_specific_fluid(test_state, new_fluid.fluid()) {
+                continue;
+            }
+
+            let distance = if context.is_hole(self, world, test_pos) {
+                0
+            } else {
+                self.get_slope_distance(
+                    world,
+                    test_pos,
+                    1,
+                    direction.opposite(),
+                    test_state,
+                    &mut context,
+                )
+            };
+            if distance < lowest {
+                spreads.clear();
+            }
+            if distance <= lowest {
+                if can_be_replaced_with(world, test_pos, test_fluid, new_fluid.fluid(), direction) {
+                    spreads.push((direction, new_fluid));
+                }
+                lowest = distance;
+            }
+        }
+
+        // Vanilla collects these into an EnumMap, so they spread in declaration order
+        spreads.sort_by_key(|(direction, _)| *direction as u8);
+        spreads
+    }
+
+    /// Returns the number of steps from `pos` to the nearest drop, or [`NO_SLOPE`].
+    fn get_slope_distance(
+        self,
+        world: &World,
+        pos: BlockPos,
+        pass: u32,
+        from: Direction,
+        state: BlockStateId,
+        context: &mut SpreadContext,
+    ) -> u32 {
+        let mut lowest = NO_SLOPE;
+        for direction in HORIZONTAL {
+            if direction == from {
+                continue;
+            }
+            let test_pos = direction.relative(&pos);
+            let test_state = context.block_state(world, test_pos);
+            let test_fluid = test_state.get_fluid_state();
+            if !self.can_pass_through(self.flowing(), state, direction, test_state, test_fluid) {
+                continue;
+            }
+            if context.is_hole(self, world, test_pos) {
+                return pass;
+            }
+            if pass < self.slope_find_distance(world) {
+                let distance = self.get_slope_distance(
+                    world,
+                    test_pos,
+                    pass + 1,
+                    direction.opposite(),
+                    test_state,
+                    context,
+                );
+                lowest = lowest.min(distance);
+            }
+        }
+        lowest
+    }
+
+    /// Returns whether the fluid above `bottom_state` could flow down into it.
+    fn is_water_hole(self, top_state: BlockStateId, bottom_state: BlockStateId) -> bool {
+        if !can_pass_through_wall(Direction::Down, top_state, bottom_state) {
+            return false;
+        }
+        self.is_same(bottom_state.get_fluid_state())
+            || (can_hold_any_fluid(bottom_state)
+                && can_hold_specific_fluid(bottom_state, self.flowing()))
+    }
+
+    fn can_pass_through(
+        self,
+        fluid: FluidRef,
+        source_state: BlockStateId,
+        direction: Direction,
+        test_state: BlockStateId,
+        test_fluid: FluidState,
+    ) -> bool {
+        self.can_maybe_pass_through(source_state, direction, test_state, test_fluid)
+            && can_hold_specific_fluid(test_state, fluid)
+    }
+
+    fn can_maybe_pass_through(
+        self,
+        source_state: BlockStateId,
+        direction: Direction,
+        test_state: BlockStateId,
+        test_fluid: FluidState,
+    ) -> bool {
+        !self.is_source_of_this_type(test_fluid)
+            && can_hold_any_fluid(test_state)
+            && can_pass_through_wall(direction, source_state, test_state)
+    }
+
+    fn source_neighbor_count(self, world: &World, pos: BlockPos) -> usize {
+        HORIZONTAL
+            .into_iter()
+            .filter(|direction| {
+                let fluid = world
+                    .get_block_state(&direction.relative(&pos))
+                    .get_fluid_state();
+                self.is_source_of_this_type(fluid)
+            })
+            .count()
+    }
+
+    /// Places `target` at `pos`, replacing whatever was there.
+    fn spread_to(
+        self,
+        world: &World,
+        pos: BlockPos,
+        state: BlockStateId,
+        direction: Direction,
+        target: FluidState,
+    ) {
+        // Lava falling onto water hardens it into stone instead of flowing into it
+        if self.kind == FluidKind::Lava
+            && direction == Direction::Down
+            && state.get_fluid_state().is(&WATER_TAG)
+        {
+            let block = state.get_block();
+            if ptr::eq(block, vanilla_blocks::WATER) || ptr::eq(block, vanilla_blocks::LAVA) {
+                world.set_block(
+                    pos,
+                    vanilla_blocks::STONE.default_state(),
+                    UpdateFlags::UPDATE_ALL,
+                );
+            }
+            fizz(world, pos);
+            return;
+        }
+
+        if is_liquid_container(state) {
+            self.place_liquid(world, pos, state, target);
+        } else {
+            if !state.is_air() {
+                self.before_destroying_block(world, pos, state);
+            }
+            world.set_block(pos, create_legacy_block(target), UpdateFlags::UPDATE_ALL);
+        }
+    }
+
+    /// Waterlogs a block that can hold water. Matches `SimpleWaterloggedBlock.placeLiquid()`.
+    fn place_liquid(self, world: &World, pos: BlockPos, state: BlockStateId, target: FluidState) {
+        let waterlogged = state.try_get_value(&BlockStateProperties::WATERLOGGED);
+        if waterlogged == Some(false) && ptr::eq(target.fluid(), &vanilla_fluids::WATER) {
+            world.set_block(
+                pos,
+                state.set_value(&BlockStateProperties::WATERLOGGED, true),
+                UpdateFlags::UPDATE_ALL,
+            );
+            world.schedule_fluid_tick(pos, target.fluid(), self.tick_delay(world));
+        }
+    }
+
+    /// Called before the fluid washes away the block at `pos`.
+    ///
+    /// Water drops the block like breaking it without a tool does, matching
+    /// `WaterFluid.beforeDestroyingBlock()`, while lava burns it.
+    fn before_destroying_block(self, world: &World, pos: BlockPos, state: BlockStateId) {
+        match self.kind {
+            FluidKind::Water => {
+                let block_entity = world
+                    .get_block_entity(&pos)
+                    .map(|block_entity| BlockEntitySnapshot::of(&*block_entity.lock()));
+                world.drop_resources(pos, state, block_entity.as_ref(), &ItemStack::empty());
+            }
+            FluidKind::Lava => fizz(world, pos),
+        }
+    }
+}
+
+impl FluidBehaviour for FlowingFluid {
+    fn tick(&self, world: &World, pos: BlockPos, state: BlockStateId) {
+        let mut state = state;
+        let mut fluid_state = state.get_fluid_state();
+        if !fluid_state.is_source() {
+            let new_fluid_state = self.get_new_liquid(world, pos, state);
+            let delay = self.spread_delay(world, pos, fluid_state, new_fluid_state);
+            if new_fluid_state.is_empty() {
+                fluid_state = new_fluid_state;
+                state = vanilla_blocks::AIR.default_state();
+                world.set_block(pos, state, UpdateFlags::UPDATE_ALL);
+            } else if new_fluid_state != fluid_state {
+                fluid_state = new_fluid_state;
+                state = create_legacy_block(new_fluid_state);
+                world.set_block(pos, state, UpdateFlags::UPDATE_ALL);
+                world.schedule_fluid_tick(pos, new_fluid_state.fluid(), delay);
+            }
+        }
+        self.spread(world, pos, state, fluid_state);
+    }
+}
+
+/// Caches block states and drops looked up while searching for a slope.
+///
+/// Matches vanilla's `FlowingFluid.SpreadContext`.
+#[derive(Default)]
+struct SpreadContext {
+    states: FxHashMap<BlockPos, BlockStateId>,
+    holes: FxHashMap<BlockPos, bool>,
+}
+
+impl SpreadContext {
+    fn block_state(&mut self, world: &World, pos: BlockPos) -> BlockStateId {
+        *self
+            .states
+            .entry(pos)
+            .or_insert_with(|| world.get_block_state(&pos))
+    }
+
+    fn is_hole(&mut self, fluid: FlowingFluid, world: &World, pos: BlockPos) -> bool {
+        if let Some(&hole) = self.holes.get(&pos) {
+            return hole;
+        }
+        let state = self.block_state(world, pos);
+        let below_state = world.get_block_state(&Direction::Down.relative(&pos));
+        let hole = fluid.is_water_hole(state, below_state);
+        self.holes.insert(pos, hole);
+        hole
+    }
+}
+
+/// Returns whether the fluid in `fluid_state` at `pos` can be replaced by `fluid`.
+///
+/// Water only gives way to other fluids flowing down onto it, lava only to water.
+fn can_be_replaced_with(
+    world: &World,
+    pos: BlockPos,
+    fluid_state: FluidState,
+    fluid: FluidRef,
+    direction: Direction,
+) -> bool {
+    if fluid_state.is_empty() {
+        return true;
+    }
+    let replaced_by_water = REGISTRY.fluids.is_in_tag(fluid, &WATER_TAG);
+    if fluid_state.is(&WATER_TAG) {
+        direction == Direction::Down && !replaced_by_water
+    } else if fluid_state.is(&LAVA_TAG) {
+        fluid_height(world, pos, fluid_state) >= 0.444_444_45 && replaced_by_water
+    } else {
+        false
+    }
+}
+
+/// Returns whether fluid can cross between two neighboring blocks.
+///
+/// Full collision shapes always block. Otherwise a full face on either side blocks,
+/// which is a simplification of vanilla merging both faces before checking.
+fn can_pass_through_wall(
+    direction: Direction,
+    source_state: BlockStateId,
+    target_state: BlockStateId,
+) -> bool {
+    let target_shape = target_state.get_collision_shape();
+    if is_shape_full_block(target_shape) {
+        return false;
+    }
+    let source_shape = source_state.get_collision_shape();
+    if is_shape_full_block(source_shape) {
+        return false;
+    }
+    if source_shape.is_empty() && target_shape.is_empty() {
+        return true;
+    }
+    !source_state.is_face_sturdy(direction) && !target_state.is_face_sturdy(direction.opposite())
+}
+
+/// Returns whether the block handles fluids itself instead of being washed away, like
+/// waterloggable blocks and underwater plants.
+fn is_liquid_container(state: BlockStateId) -> bool {
+    let block = state.get_block();
+    state
+        .try_get_value(&BlockStateProperties::WATERLOGGED)
+        .is_some()
+        || ptr::eq(block, vanilla_blocks::KELP)
+        || ptr::eq(block, vanilla_blocks::KELP_PLANT)
+        || ptr::eq(block, vanilla_blocks::SEAGRASS)
+        || ptr::eq(block, vanilla_blocks::TALL_SEAGRASS)
+}
+
+/// Returns whether the block stops entities and fluids, which is most solid blocks.
+///
+/// Matches vanilla's `BlockState.blocksMotion()`.
+fn blocks_motion(state: BlockStateId) -> bool {
+    let block = state.get_block();
+    !ptr::eq(block, vanilla_blocks::COBWEB)
+        && !ptr::eq(block, vanilla_blocks::BAMBOO_SAPLING)
+        && state.is_solid()
+}
+
+/// Returns whether fluid can flow into the block at all.
+fn can_hold_any_fluid(state: BlockStateId) -> bool {
+    if is_liquid_container(state) {
+        return true;
+    }
+    if blocks_motion(state) {
+        return false;
+    }
+
+    let block = state.get_block();
+    !REGISTRY
+        .blocks
+        .is_in_tag(block, &Identifier::vanilla_static("doors"))
+        && !REGISTRY
+            .blocks
+            .is_in_tag(block, &Identifier::vanilla_static("signs"))
+        && !ptr::eq(block, vanilla_blocks::LADDER)
+        && !ptr::eq(block, vanilla_blocks::SUGAR_CANE)
+        && !ptr::eq(block, vanilla_blocks::BUBBLE_COLUMN)
+        && !ptr::eq(block, vanilla_blocks::NETHER_PORTAL)
+        && !ptr::eq(block, vanilla_blocks::END_PORTAL)
+        && !ptr::eq(block, vanilla_blocks::END_GATEWAY)
+        && !ptr::eq(block, vanilla_blocks::STRUCTURE_VOID)
+}
+
+/// Returns whether the block accepts `fluid`. Only waterloggable blocks take any, and
+/// only water sources.
+fn can_hold_specific_fluid(state: BlockStateId, fluid: FluidRef) -> bool {
+    if !is_liquid_container(state) {
+        return true;
+    }
+    state
+        .try_get_value(&BlockStateProperties::WATERLOGGED)
+        .is_some()
+        && ptr::eq(fluid, &vanilla_fluids::WATER)
+}
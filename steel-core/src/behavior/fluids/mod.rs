@@ -0,0 +1,127 @@
+//! Fluid behavior implementations for vanilla fluids.
+//!
+//! Water and lava share [`FlowingFluid`], which is registered for both the source and
+//! flowing variant of each fluid in `init_behaviors`.
+
+mod flowing_fluid;
+
+pub use flowing_fluid::FlowingFluid;
+
+use steel_registry::REGISTRY;
+use steel_registry::blocks::block_state_ext::BlockStateExt;
+use steel_registry::blocks::properties::{BlockStateProperties, Direction};
+use steel_registry::fluid::{FluidRef, FluidState};
+use steel_registry::{level_events, vanilla_blocks};
+use steel_utils::{BlockPos, BlockStateId};
+
+use crate::world::World;
+
+/// Returns the registry id of a fluid, as stored in [`FluidState::fluid_id`].
+///
+/// # Panics
+/// Panics if the fluid is not registered.
+#[must_use]
+pub fn fluid_id(fluid: FluidRef) -> u8 {
+    *REGISTRY
+        .fluids
+        .get_id(fluid)
+        .expect("fluid should be registered") as u8
+}
+
+/// Returns whether two fluids are variants of the same fluid, like water and flowing water.
+///
+/// Matches vanilla's `Fluid.isSame()`.
+#[must_use]
+pub fn is_same_fluid(a: FluidRef, b: FluidRef) -> bool {
+    !a.is_empty && !b.is_empty && a.block == b.block
+}
+
+/// Returns the block state that holds the given fluid on its own.
+///
+/// Matches vanilla's `FluidState.createLegacyBlock()`: empty fluids become air, anything
+/// else becomes its liquid block with the matching level.
+#[must_use]
+pub fn create_legacy_block(fluid_state: FluidState) -> BlockStateId {
+    if fluid_state.is_empty() {
+        return vanilla_blocks::AIR.default_state();
+    }
+    let block = REGISTRY
+        .blocks
+        .by_key(&fluid_state.fluid().block)
+        .expect("fluid block should be registered");
+    block
+        .default_state()
+        .set_value(&BlockStateProperties::LEVEL, fluid_state.to_block_level())
+}
+
+/// Returns the height of a fluid at `pos`, which is a full block if the same fluid is above.
+///
+/// Matches vanilla's `FluidState.getHeight()`.
+#[must_use]
+pub fn fluid_height(world: &World, pos: BlockPos, fluid_state: FluidState) -> f32 {
+    if fluid_state.is_empty() {
+        return 0.0;
+    }
+    let above = world
+        .get_block_state(&Direction::Up.relative(&pos))
+        .get_fluid_state();
+    if !above.is_empty() && is_same_fluid(fluid_state.fluid(), above.fluid()) {
+        1.0
+    } else {
+        fluid_state.own_height()
+    }
+}
+
+/// Plays the lava extinguish sound and smoke at `pos`.
+pub fn fizz(world: &World, pos: BlockPos) {
+    world.level_event(level_events::LAVA_FIZZ, pos, 0, None);
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ptr;
+
+    use steel_registry::{RegistryExt, vanilla_fluids};
+
+    use super::*;
+    use crate::test_utils::init_test_registry;
+
+    #[test]
+    fn test_liquid_block_levels_round_trip() {
+        init_test_registry();
+        for level in 0..=8 {
+            let state = vanilla_blocks::WATER
+                .default_state()
+                .set_value(&BlockStateProperties::LEVEL, level);
+            let fluid_state = state.get_fluid_state();
+            assert_eq!(fluid_state.is_source(), level == 0);
+            assert_eq!(create_legacy_block(fluid_state), state);
+        }
+
+        let flowing = vanilla_blocks::LAVA
+            .default_state()
+            .set_value(&BlockStateProperties::LEVEL, 2u8)
+            .get_fluid_state();
+        assert!(ptr::eq(flowing.fluid(), &vanilla_fluids::FLOWING_LAVA));
+        assert_eq!(flowing.amount, 6);
+    }
+
+    #[test]
+    fn test_waterlogged_blocks_hold_water() {
+        init_test_registry();
+        let fence = vanilla_blocks::OAK_FENCE.default_state();
+        assert!(fence.get_fluid_state().is_empty());
+
+        let waterlogged = fence.set_value(&BlockStateProperties::WATERLOGGED, true);
+        let fluid_state = waterlogged.get_fluid_state();
+        assert!(fluid_state.is_source());
+        assert!(is_same_fluid(
+            fluid_state.fluid(),
+            &vanilla_fluids::FLOWING_WATER
+        ));
+        assert_eq!(
+            create_legacy_block(fluid_state),
+            vanilla_blocks::WATER.default_state()
+        );
+    }
+}
@@ -8,6 +8,7 @@ use steel_registry::vanilla_blocks;
 use steel_utils::types::UpdateFlags;
 
 use crate::behavior::ItemBehavior;
+use crate::behavior::blocks::EndPortalFrameBlock;
 use crate::behavior::context::{InteractionResult, UseOnContext};
 
 /// Behavior for the ender eye item.
@@ -18,7 +19,7 @@ pub struct EnderEyeBehavior;
 
 impl ItemBehavior for EnderEyeBehavior {
     fn use_on(&self, context: &mut UseOnContext) -> InteractionResult {
-        // TODO: updateNeighbourForOutputSignal, portal completion check
+        // TODO: updateNeighbourForOutputSignal
 
         let clicked_pos = context.hit_result.block_pos;
         let clicked_state = context.world.get_block_state(&clicked_pos);
@@ -52,6 +53,8 @@ impl ItemBehavior for EnderEyeBehavior {
 
         context.item_stack.shrink(1);
 
+        EndPortalFrameBlock::try_create_portal(context.world, clicked_pos);
+
         InteractionResult::Success
     }
 }
@@ -11,6 +11,7 @@
 //! are created:
 //! - `BlockBehaviorRegistry` - assigns default or custom behaviors to each block
 //! - `ItemBehaviorRegistry` - assigns default or custom behaviors to each item
+//! - `FluidBehaviorRegistry` - assigns default or custom behaviors to each fluid
 //!
 //! # Usage
 //!
@@ -27,6 +28,8 @@
 mod block;
 pub mod blocks;
 mod context;
+mod fluid;
+pub mod fluids;
 mod item;
 pub mod items;
 
@@ -43,12 +46,14 @@ pub mod item_behaviours;
 pub use block::{BlockBehaviorRegistry, BlockBehaviour, DefaultBlockBehaviour};
 use block_behaviours::register_block_behaviors;
 pub use context::{BlockHitResult, BlockPlaceContext, InteractionResult, UseOnContext};
+pub use fluid::{DefaultFluidBehaviour, FluidBehaviorRegistry, FluidBehaviour};
+pub use fluids::FlowingFluid;
 pub use item::{ItemBehavior, ItemBehaviorRegistry};
 use item_behaviours::register_item_behaviors;
 pub use items::{BlockItemBehavior, DefaultItemBehavior, EnderEyeBehavior, FilledBucketBehavior};
 use std::ops::Deref;
 use std::sync::OnceLock;
-use steel_registry::{vanilla_blocks, vanilla_items};
+use steel_registry::{vanilla_blocks, vanilla_fluids, vanilla_items};
 
 /// Wrapper for the global block behavior registry that implements `Deref`.
 pub struct BlockBehaviorLock(OnceLock<BlockBehaviorRegistry>);
@@ -72,6 +77,17 @@ impl Deref for ItemBehaviorLock {
     }
 }
 
+/// Wrapper for the global fluid behavior registry that implements `Deref`.
+pub struct FluidBehaviorLock(OnceLock<FluidBehaviorRegistry>);
+
+impl Deref for FluidBehaviorLock {
+    type Target = FluidBehaviorRegistry;
+
+    fn deref(&self) -> &Self::Target {
+        self.0.get().expect("Fluid behaviors not initialized")
+    }
+}
+
 /// Global block behavior registry.
 ///
 /// Access behaviors directly via deref: `BLOCK_BEHAVIORS.get_behavior(block)`
@@ -82,6 +98,11 @@ pub static BLOCK_BEHAVIORS: BlockBehaviorLock = BlockBehaviorLock(OnceLock::new(
 /// Access behaviors directly via deref: `ITEM_BEHAVIORS.get_behavior(item)`
 pub static ITEM_BEHAVIORS: ItemBehaviorLock = ItemBehaviorLock(OnceLock::new());
 
+/// Global fluid behavior registry.
+///
+/// Access behaviors directly via deref: `FLUID_BEHAVIORS.get_behavior(fluid)`
+pub static FLUID_BEHAVIORS: FluidBehaviorLock = FluidBehaviorLock(OnceLock::new());
+
 /// Initializes the global behavior registries.
 ///
 /// This should be called once after the main registry is frozen.
@@ -121,4 +142,21 @@ pub fn init_behaviors() {
         ITEM_BEHAVIORS.0.set(item_behaviors).is_ok(),
         "Item behavior registry already initialized"
     );
+
+    let mut fluid_behaviors = FluidBehaviorRegistry::new();
+    fluid_behaviors.set_behavior(&vanilla_fluids::WATER, Box::new(FlowingFluid::water()));
+    fluid_behaviors.set_behavior(
+        &vanilla_fluids::FLOWING_WATER,
+        Box::new(FlowingFluid::water()),
+    );
+    fluid_behaviors.set_behavior(&vanilla_fluids::LAVA, Box::new(FlowingFluid::lava()));
+    fluid_behaviors.set_behavior(
+        &vanilla_fluids::FLOWING_LAVA,
+        Box::new(FlowingFluid::lava()),
+    );
+
+    assert!(
+        FLUID_BEHAVIORS.0.set(fluid_behaviors).is_ok(),
+        "Fluid behavior registry already initialized"
+    );
 }
@@ -6,18 +6,17 @@
 use std::any::Any;
 use std::sync::{Arc, Weak};
 
+use simdnbt::ToNbtTag;
 use simdnbt::borrow::{BaseNbtCompound as BorrowedNbtCompound, NbtCompound as NbtCompoundView};
-use simdnbt::owned::{NbtCompound, NbtList, NbtTag};
-use simdnbt::{FromNbtTag, ToNbtTag};
-use steel_registry::REGISTRY;
+use simdnbt::owned::NbtCompound;
 use steel_registry::block_entity_type::BlockEntityTypeRef;
-use steel_registry::data_components::DataComponentPatch;
 use steel_registry::item_stack::ItemStack;
 use steel_registry::vanilla_block_entity_types;
-use steel_utils::{BlockPos, BlockStateId, Identifier};
+use steel_utils::{BlockPos, BlockStateId};
+use text_components::TextComponent;
 
 use crate::block_entity::BlockEntity;
-use crate::inventory::container::Container;
+use crate::inventory::container::{Container, load_all_items, save_all_items};
 use crate::world::World;
 
 /// Number of slots in a barrel (3 rows of 9).
@@ -37,6 +36,8 @@ pub struct BarrelBlockEntity {
     removed: bool,
     /// The 27 item slots.
     items: Vec<ItemStack>,
+    /// The name given to the barrel with a renamed item.
+    custom_name: Option<TextComponent>,
 }
 
 impl BarrelBlockEntity {
@@ -49,6 +50,7 @@ impl BarrelBlockEntity {
             state,
             removed: false,
             items: vec![ItemStack::empty(); BARREL_SLOTS],
+            custom_name: None,
         }
     }
 }
@@ -106,39 +108,18 @@ impl BlockEntity for BarrelBlockEntity {
     fn load_additional(&mut self, nbt: &BorrowedNbtCompound<'_>) {
         // Convert to NbtCompound view for accessing methods
         let nbt_view: NbtCompoundView<'_, '_> = nbt.into();
-
-        // Load items from NBT using borrowed NBT for proper ItemStack parsing
-        if let Some(items_list) = nbt_view.list("Items")
-            && let Some(compounds) = items_list.compounds()
-        {
-            for compound in compounds {
-                // Each item has a "Slot" byte and item data
-                if let Some(slot) = compound.byte("Slot") {
-                    let slot = slot as usize;
-                    if slot < BARREL_SLOTS {
-                        // Parse item directly from the borrowed compound
-                        if let Some(item) = item_from_borrowed_compound(&compound) {
-                            self.items[slot] = item;
-                        }
-                    }
-                }
-            }
-        }
+        load_all_items(&nbt_view, "Items", &mut self.items);
+        self.custom_name = nbt_view
+            .get("CustomName")
+            .and_then(|tag| TextComponent::from_nbt(&tag.to_owned()));
     }
 
     fn save_additional(&self, nbt: &mut NbtCompound) {
         // Save items to NBT (only non-empty slots)
-        let mut items: Vec<NbtCompound> = Vec::new();
-        for (slot, item) in self.items.iter().enumerate() {
-            if !item.is_empty() {
-                // Use ItemStack's ToNbtTag implementation for proper component serialization
-                if let NbtTag::Compound(mut item_nbt) = item.clone().to_nbt_tag() {
-                    item_nbt.insert("Slot", slot as i8);
-                    items.push(item_nbt);
-                }
-            }
+        save_all_items(nbt, "Items", &self.items);
+        if let Some(custom_name) = &self.custom_name {
+            nbt.insert("CustomName", custom_name.to_nbt_tag());
         }
-        nbt.insert("Items", NbtList::Compound(items));
     }
 
     fn get_update_tag(&self) -> Option<NbtCompound> {
@@ -147,6 +128,10 @@ impl BlockEntity for BarrelBlockEntity {
         None
     }
 
+    fn get_custom_name(&self) -> Option<&TextComponent> {
+        self.custom_name.as_ref()
+    }
+
     fn as_container(&self) -> Option<&(dyn Container + 'static)> {
         Some(self)
     }
@@ -184,27 +169,3 @@ impl Container for BarrelBlockEntity {
         BlockEntity::set_changed(self);
     }
 }
-
-/// Parses an `ItemStack` from a borrowed `NbtCompound`.
-///
-/// This mirrors the logic of `ItemStack::from_nbt_tag` but works directly with
-/// borrowed compound data, properly parsing component patches.
-fn item_from_borrowed_compound(compound: &NbtCompoundView<'_, '_>) -> Option<ItemStack> {
-    // Get the item ID
-    let id_str = compound.string("id")?.to_str();
-    let id = id_str.parse::<Identifier>().ok()?;
-
-    // Look up the item in the registry
-    let item = REGISTRY.items.by_key(&id)?;
-
-    // Get the count (default to 1 if not present)
-    let count = compound.int("count").unwrap_or(1);
-
-    // Parse components if present
-    let patch = compound
-        .get("components")
-        .and_then(DataComponentPatch::from_nbt_tag)
-        .unwrap_or_default();
-
-    Some(ItemStack::with_count_and_patch(item, count, patch))
-}
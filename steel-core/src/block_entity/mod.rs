@@ -33,7 +33,9 @@ use std::sync::Arc;
 use simdnbt::borrow::BaseNbtCompound as BorrowedNbtCompound;
 use simdnbt::owned::NbtCompound;
 use steel_registry::block_entity_type::BlockEntityTypeRef;
+use steel_registry::item_stack::ItemStack;
 use steel_utils::{BlockPos, BlockStateId, locks::SyncMutex};
+use text_components::TextComponent;
 
 pub use registry::{BLOCK_ENTITIES, BlockEntityFactory, BlockEntityRegistry, init_block_entities};
 pub use storage::BlockEntityStorage;
@@ -152,6 +154,15 @@ pub trait BlockEntity: Send + Sync {
         // Default: no-op
     }
 
+    // === Naming ===
+
+    /// Returns the name given to this block entity with a renamed item, if any.
+    ///
+    /// Loot tables copy it back onto the dropped item with `copy_name`.
+    fn get_custom_name(&self) -> Option<&TextComponent> {
+        None
+    }
+
     // === Container Access ===
 
     /// Returns this block entity as a container, if it implements Container.
@@ -173,3 +184,32 @@ pub trait BlockEntity: Send + Sync {
 
 /// Type alias for a shared, thread-safe block entity.
 pub type SharedBlockEntity = Arc<SyncMutex<dyn BlockEntity>>;
+
+/// The parts of a block entity that the loot of its block can depend on.
+///
+/// Taken before the block is removed, since that removes the block entity too and
+/// lets containers drop their contents.
+pub struct BlockEntitySnapshot {
+    /// The type of the block entity.
+    pub block_entity_type: BlockEntityTypeRef,
+    /// The name given to the block entity, see [`BlockEntity::get_custom_name`].
+    pub custom_name: Option<TextComponent>,
+    /// The items of a container block entity.
+    pub inventory: Option<Vec<ItemStack>>,
+}
+
+impl BlockEntitySnapshot {
+    /// Copies the loot relevant data out of a block entity.
+    #[must_use]
+    pub fn of(block_entity: &dyn BlockEntity) -> Self {
+        Self {
+            block_entity_type: block_entity.get_type(),
+            custom_name: block_entity.get_custom_name().cloned(),
+            inventory: block_entity.as_container().map(|container| {
+                (0..container.get_container_size())
+                    .map(|slot| container.get_item(slot).clone())
+                    .collect()
+            }),
+        }
+    }
+}
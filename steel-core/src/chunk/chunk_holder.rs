@@ -22,6 +22,7 @@ pub static SLOW_CHUNK_GEN: AtomicBool = AtomicBool::new(false);
 
 use crate::chunk::chunk_generation_task::{NeighborReady, StaticCache2D};
 use crate::chunk::chunk_ticket_manager::generation_status;
+use crate::lighting::LightLayer;
 use crate::world::World;
 use crate::{
     ChunkMap,
@@ -56,6 +57,10 @@ impl ChunkGuard {
         self.0.read()
     }
 
+    pub fn read_recursive(&self) -> RwLockReadGuard<'_, ChunkAccess> {
+        self.0.read_recursive()
+    }
+
     pub fn with_write<F, R>(&self, f: F) -> R
     where
         F: FnOnce(&mut ChunkAccess) -> R,
@@ -97,6 +102,9 @@ pub struct ChunkHolder {
     /// Per-section sets of changed block positions (section-relative packed shorts).
     /// Index is `(block_y - min_y) / 16`.
     changed_blocks_per_section: Box<[SyncMutex<FxHashSet<i16>>]>,
+    /// Sections with pending light changes, as (layer, light section index).
+    /// Light section indices start one section below the world.
+    changed_light_sections: SyncMutex<FxHashSet<(LightLayer, usize)>>,
 }
 
 impl ChunkHolder {
@@ -140,6 +148,7 @@ impl ChunkHolder {
             height,
             has_changed_sections: AtomicBool::new(false),
             changed_blocks_per_section,
+            changed_light_sections: SyncMutex::new(FxHashSet::default()),
         }
     }
 
@@ -168,6 +177,28 @@ impl ChunkHolder {
         !had_changes
     }
 
+    /// Records a light change in the given light section.
+    /// Returns `true` if this is the first change (chunk should be added to broadcast list).
+    pub fn section_light_changed(&self, layer: LightLayer, light_section_index: usize) -> bool {
+        if light_section_index > self.changed_blocks_per_section.len() + 1 {
+            return false;
+        }
+
+        let had_changes = self.has_changed_sections.swap(true, Ordering::AcqRel);
+        self.changed_light_sections
+            .lock()
+            .insert((layer, light_section_index));
+
+        !had_changes
+    }
+
+    /// Takes all pending light changes as (layer, light section index).
+    ///
+    /// Call after [`Self::take_changed_blocks`], which resets the pending changes flag.
+    pub fn take_changed_light(&self) -> FxHashSet<(LightLayer, usize)> {
+        mem::take(&mut *self.changed_light_sections.lock())
+    }
+
     /// Returns whether there are pending block changes to broadcast.
     pub fn has_changes_to_broadcast(&self) -> bool {
         self.has_changed_sections.load(Ordering::Acquire)
@@ -258,6 +289,21 @@ impl ChunkHolder {
         }
     }
 
+    /// Like [`Self::try_chunk`], but doesn't wait for pending writers.
+    ///
+    /// Use this when holding the chunk while locking other chunks, otherwise two
+    /// pending upgrades can each wait on a reader that is stuck on the other.
+    #[inline]
+    pub fn try_chunk_recursive(
+        &self,
+        status: ChunkStatus,
+    ) -> Option<RwLockReadGuard<'_, ChunkAccess>> {
+        match &*self.chunk_result.borrow() {
+            ChunkResult::Ok(s) if status <= *s => Some(self.data.read_recursive()),
+            _ => None,
+        }
+    }
+
     /// Waits until the chunk has reached the given status, then calls the function.
     pub fn await_chunk(
         &self,
@@ -11,11 +11,13 @@ use rayon::{
     ThreadPool, ThreadPoolBuilder,
     iter::{IntoParallelIterator, ParallelIterator},
 };
-use rustc_hash::FxBuildHasher;
+use rustc_hash::{FxBuildHasher, FxHashSet};
 use steel_protocol::packets::game::{
-    BlockChange, CBlockUpdate, CSectionBlocksUpdate, CSetChunkCenter,
+    BlockChange, CBlockUpdate, CLightUpdate, CSectionBlocksUpdate, CSetChunkCenter,
+};
+use steel_registry::{
+    dimension_type::DimensionTypeRef, vanilla_dimension_types, vanilla_noise_settings,
 };
-use steel_registry::{REGISTRY, dimension_type::DimensionTypeRef, vanilla_blocks};
 use steel_utils::{BlockPos, ChunkPos, SectionPos, locks::SyncMutex};
 use tokio::runtime::Runtime;
 use tokio_util::task::TaskTracker;
@@ -25,16 +27,21 @@ use crate::chunk::chunk_holder::ChunkHolder;
 use crate::chunk::chunk_ticket_manager::{
     ChunkTicketManager, LevelChange, MAX_VIEW_DISTANCE, is_full,
 };
+use crate::chunk::level_chunk::LevelChunk;
 use crate::chunk::player_chunk_view::PlayerChunkView;
 use crate::chunk::world_gen_context::ChunkGeneratorType;
 use crate::chunk::{chunk_access::ChunkAccess, chunk_ticket_manager::is_ticked};
 use crate::chunk::{
     chunk_access::ChunkStatus, chunk_generation_task::ChunkGenerationTask,
-    flat_chunk_generator::FlatChunkGenerator, world_gen_context::WorldGenContext,
+    flat_chunk_generator::FlatChunkGenerator, flat_settings::FlatSettings,
+    noise_chunk_generator::NoiseChunkGenerator, world_gen_context::WorldGenContext,
 };
 use crate::chunk_saver::RegionManager;
+use crate::lighting::{LevelLightEngine, LightLayer, LightRegion};
 use crate::player::Player;
+use crate::ticks::{LevelChunkTicks, LevelTicks, ScheduledTick, TickType};
 use crate::world::World;
+use crate::worldgen::BiomeSource;
 
 /// Timing information for chunk map tick operations.
 #[derive(Debug, Default)]
@@ -49,12 +56,16 @@ pub struct ChunkMapTickTimings {
     pub scheduled_count: usize,
     /// Time spent spawning generation tasks.
     pub run_generation: Duration,
+    /// Time spent relighting block changes.
+    pub light_updates: Duration,
     /// Time spent broadcasting block changes.
     pub broadcast_changes: Duration,
     /// Time spent processing chunk unloads.
     pub process_unloads: Duration,
     /// Time spent collecting tickable chunks.
     pub collect_tickable: Duration,
+    /// Time spent running scheduled block and fluid ticks.
+    pub scheduled_ticks: Duration,
     /// Time spent ticking chunks (random ticks, etc.).
     pub tick_chunks: Duration,
     /// Number of chunks that were ticked.
@@ -87,28 +98,56 @@ pub struct ChunkMap {
     pub region_manager: Arc<RegionManager>,
     /// Chunk holders with pending block changes to broadcast.
     pub chunks_to_broadcast: SyncMutex<Vec<Arc<ChunkHolder>>>,
+    /// Queues and runs light updates for block changes.
+    pub light_engine: LevelLightEngine,
+    /// The clock and drain order of scheduled block and fluid ticks.
+    pub level_ticks: LevelTicks,
     /// Last length of `tickable_chunks` to pre-allocate with appropriate capacity.
     last_tickable_len: AtomicUsize,
 }
 
 impl ChunkMap {
     /// Creates a new chunk map.
+    ///
+    /// Worlds with `flat_settings` are superflat. Otherwise the overworld, the nether and
+    /// the end are generated from noise with the world `seed`, and other dimensions are
+    /// classic superflat.
     #[must_use]
     #[allow(clippy::missing_panics_doc, clippy::unwrap_used)]
     pub fn new(
         chunk_runtime: Arc<Runtime>,
         world: Weak<World>,
         dimension: &DimensionTypeRef,
+        seed: i64,
+        flat_settings: Option<&FlatSettings>,
     ) -> Self {
-        let generator = Arc::new(ChunkGeneratorType::Flat(FlatChunkGenerator::new(
-            REGISTRY
-                .blocks
-                .get_default_state_id(vanilla_blocks::BEDROCK), // Bedrock
-            REGISTRY.blocks.get_default_state_id(vanilla_blocks::DIRT), // Dirt
-            REGISTRY
-                .blocks
-                .get_default_state_id(vanilla_blocks::GRASS_BLOCK), // Grass Block
-        )));
+        let noise = |settings, biome_source| {
+            ChunkGeneratorType::Noise(NoiseChunkGenerator::new(
+                settings,
+                biome_source,
+                seed,
+                dimension.min_y,
+                dimension.height,
+            ))
+        };
+        let generator = Arc::new(match flat_settings {
+            Some(settings) => {
+                ChunkGeneratorType::Flat(FlatChunkGenerator::new(settings, dimension.height))
+            }
+            None if dimension.key == vanilla_dimension_types::OVERWORLD.key => {
+                noise(vanilla_noise_settings::OVERWORLD, BiomeSource::overworld())
+            }
+            None if dimension.key == vanilla_dimension_types::THE_NETHER.key => {
+                noise(vanilla_noise_settings::NETHER, BiomeSource::nether())
+            }
+            None if dimension.key == vanilla_dimension_types::THE_END.key => {
+                noise(vanilla_noise_settings::END, BiomeSource::TheEnd)
+            }
+            None => ChunkGeneratorType::Flat(FlatChunkGenerator::new(
+                &FlatSettings::default(),
+                dimension.height,
+            )),
+        });
 
         Self {
             chunks: scc::HashMap::default(),
@@ -122,6 +161,8 @@ impl ChunkMap {
             chunk_runtime,
             region_manager: Arc::new(RegionManager::new(format!("world/{}", dimension.key.path))),
             chunks_to_broadcast: SyncMutex::new(Vec::new()),
+            light_engine: LevelLightEngine::new(dimension.has_skylight),
+            level_ticks: LevelTicks::new(),
             last_tickable_len: AtomicUsize::new(0),
         }
     }
@@ -138,6 +179,18 @@ impl ChunkMap {
         Some(f(&guard))
     }
 
+    /// Returns whether the chunk at the given position is within simulation distance
+    /// and fully loaded, i.e. whether entities in it should be ticked.
+    #[must_use]
+    pub fn is_chunk_ticking(&self, pos: &ChunkPos) -> bool {
+        self.chunks
+            .read_sync(pos, |_, holder| {
+                is_ticked(holder.ticket_level.load(Ordering::Relaxed))
+                    && holder.try_chunk(ChunkStatus::Full).is_some()
+            })
+            .unwrap_or(false)
+    }
+
     /// Records a block change at the given position.
     /// This marks the chunk as having pending changes to broadcast.
     pub fn block_changed(&self, pos: &BlockPos) {
@@ -154,10 +207,76 @@ impl ChunkMap {
         }
     }
 
+    /// Records a light change in a chunk's light section.
+    /// This marks the chunk as having pending changes to broadcast.
+    pub fn section_light_changed(
+        &self,
+        holder: &Arc<ChunkHolder>,
+        layer: LightLayer,
+        light_section_index: usize,
+    ) {
+        if holder.section_light_changed(layer, light_section_index) {
+            self.chunks_to_broadcast.lock().push(holder.clone());
+        }
+    }
+
+    /// Relights all block changes queued on the light engine since the last call.
+    pub fn run_light_updates(&self) {
+        let positions = self.light_engine.take_pending();
+        if positions.is_empty() {
+            return;
+        }
+
+        // Light travels at most 15 blocks, so the neighbors of each chunk are enough
+        let mut chunk_positions = FxHashSet::default();
+        for pos in &positions {
+            let chunk_pos = ChunkPos::new(
+                SectionPos::block_to_section_coord(pos.0.x),
+                SectionPos::block_to_section_coord(pos.0.z),
+            );
+            chunk_positions.insert(chunk_pos);
+            chunk_positions.extend(chunk_pos.neighbors());
+        }
+
+        let holders: Vec<Arc<ChunkHolder>> = chunk_positions
+            .iter()
+            .filter_map(|pos| self.chunks.read_sync(pos, |_, holder| holder.clone()))
+            .collect();
+
+        let _lock = self.light_engine.update_lock();
+        let guards: Vec<_> = holders
+            .iter()
+            .filter_map(|holder| {
+                holder
+                    .try_chunk_recursive(ChunkStatus::InitializeLight)
+                    .map(|guard| (holder, guard))
+            })
+            .collect();
+
+        let context = &self.world_gen_context;
+        let mut region = LightRegion::new(
+            context.min_y(),
+            context.height(),
+            self.light_engine.has_skylight(),
+        );
+        for (holder, guard) in &guards {
+            region.add_chunk(holder.get_pos(), guard.sections());
+        }
+
+        self.light_engine.run_updates(&mut region, &positions);
+
+        for (chunk_pos, layer, light_section_index) in region.take_changes() {
+            if let Some((holder, _)) = guards.iter().find(|(h, _)| h.get_pos() == chunk_pos) {
+                self.section_light_changed(holder, layer, light_section_index);
+            }
+        }
+    }
+
     /// Broadcasts all pending block changes to nearby players.
     ///
     /// # Panics
     /// Panics if a section has exactly one change (should never happen).
+    #[allow(clippy::too_many_lines)]
     pub fn broadcast_changed_chunks(&self) {
         let holders = {
             let mut guard = self.chunks_to_broadcast.lock();
@@ -175,8 +294,9 @@ impl ChunkMap {
 
             // Take all pending changes from this chunk holder
             let changes_by_section = holder.take_changed_blocks();
+            let light_changes = holder.take_changed_light();
 
-            if changes_by_section.is_empty() {
+            if changes_by_section.is_empty() && light_changes.is_empty() {
                 continue;
             }
 
@@ -186,6 +306,28 @@ impl ChunkMap {
                 continue;
             }
 
+            // Like vanilla, light goes first so the client relights the new blocks with it
+            if !light_changes.is_empty()
+                && let Some(light_data) = self
+                    .with_full_chunk(&chunk_pos, |chunk| {
+                        chunk
+                            .as_full()
+                            .map(|chunk| chunk.extract_light_update(&light_changes))
+                    })
+                    .flatten()
+            {
+                let packet = CLightUpdate {
+                    x: chunk_pos.0.x,
+                    z: chunk_pos.0.y,
+                    light_data,
+                };
+                for entity_id in &tracking_players {
+                    if let Some(player) = world.players.get_by_entity_id(*entity_id) {
+                        player.connection.send_packet(packet.clone());
+                    }
+                }
+            }
+
             // For each section with changes, send appropriate packet
             for (section_index, changed_positions) in changes_by_section {
                 let section_y = min_y / 16 + section_index as i32;
@@ -403,6 +545,13 @@ impl ChunkMap {
             timings.run_generation = start.elapsed();
         }
 
+        {
+            let _span = tracing::trace_span!("light_updates").entered();
+            let start = Instant::now();
+            self.run_light_updates();
+            timings.light_updates = start.elapsed();
+        }
+
         {
             let _span = tracing::trace_span!("broadcast_changes").entered();
             let start = Instant::now();
@@ -429,6 +578,7 @@ impl ChunkMap {
         if !runs_normally {
             return timings;
         }
+        self.level_ticks.set_time(tick_count);
 
         {
             let _span = tracing::trace_span!("collect_tickable").entered();
@@ -451,6 +601,13 @@ impl ChunkMap {
             timings.tickable_count = tickable_chunks.len();
 
             if !tickable_chunks.is_empty() {
+                {
+                    let _span = tracing::trace_span!("scheduled_ticks").entered();
+                    let start = Instant::now();
+                    self.run_scheduled_ticks(&tickable_chunks);
+                    timings.scheduled_ticks = start.elapsed();
+                }
+
                 let _span = tracing::trace_span!(
                     "tick_chunks",
                     count = tickable_chunks.len(),
@@ -471,6 +628,35 @@ impl ChunkMap {
         timings
     }
 
+    /// Runs the scheduled block ticks, then the scheduled fluid ticks, that are due in
+    /// the given chunks.
+    fn run_scheduled_ticks(&self, tickable_chunks: &[Arc<ChunkHolder>]) {
+        let world = self.world_gen_context.world();
+
+        for tick in &self.collect_due_ticks(tickable_chunks, |chunk| &chunk.block_ticks) {
+            world.run_block_tick(tick);
+        }
+        for tick in &self.collect_due_ticks(tickable_chunks, |chunk| &chunk.fluid_ticks) {
+            world.run_fluid_tick(tick);
+        }
+    }
+
+    /// Takes the due ticks of one type out of the given chunks.
+    ///
+    /// The chunks are released before returning, since running ticks usually changes blocks.
+    fn collect_due_ticks<T: TickType>(
+        &self,
+        tickable_chunks: &[Arc<ChunkHolder>],
+        queue: fn(&LevelChunk) -> &SyncMutex<LevelChunkTicks<T>>,
+    ) -> Vec<ScheduledTick<T>> {
+        let guards: Vec<_> = tickable_chunks
+            .iter()
+            .filter_map(|holder| holder.try_chunk(ChunkStatus::Full))
+            .collect();
+        self.level_ticks
+            .collect_due(guards.iter().filter_map(|guard| guard.as_full()).map(queue))
+    }
+
     /// Saves a chunk to disk. Does not remove from `unloading_chunks`.
     #[allow(clippy::missing_panics_doc, clippy::unwrap_used)]
     #[instrument(level = "trace", skip(self, chunk_holder), fields(chunk = ?chunk_holder.get_pos()))]
@@ -492,22 +678,48 @@ impl ChunkMap {
             if prepared.is_some() {
                 chunk_guard.clear_dirty();
             }
+            let world = chunk_guard.as_full().and_then(LevelChunk::get_level);
 
-            (prepared, status)
+            (prepared, status, world)
         }; // chunk_guard dropped here
 
-        let (prepared, status) = prepared;
+        let (prepared, status, world) = prepared;
 
         // Save chunk data if dirty
         if let Some(prepared) = prepared {
+            let pos = prepared.pos;
+            let entity_ids = prepared.entity_ids().to_vec();
             let result = self.region_manager.save_chunk_data(prepared, status).await;
 
-            if let Err(e) = result {
-                tracing::error!("Error saving chunk: {e}");
+            match result {
+                // The chunk is unloading, so its entities leave the world with it once they
+                // are safely on disk, unless it was loaded again in the meantime
+                Ok(_) => {
+                    if let Some(world) = world
+                        && self.unloading_chunks.contains_sync(&pos)
+                    {
+                        world.unload_entities(&entity_ids);
+                    }
+                }
+                // Keep the entities in the world and try again on a later tick
+                Err(e) => {
+                    tracing::error!("Error saving chunk: {e}");
+                    if let Some(chunk) = chunk_holder.try_chunk(ChunkStatus::StructureStarts) {
+                        chunk.mark_dirty();
+                    }
+                }
             }
         }
     }
 
+    /// Returns whether any non-player entity is still inside the chunk.
+    fn has_entities(chunk: &ChunkAccess) -> bool {
+        chunk
+            .as_full()
+            .and_then(LevelChunk::get_level)
+            .is_some_and(|world| world.entities.has_entities_in_chunk(chunk.pos()))
+    }
+
     /// Processes chunks that are pending unload.
     ///
     /// Iterates over `unloading_chunks`. For each chunk with `strong_count == 1`:
@@ -517,10 +729,11 @@ impl ChunkMap {
     pub fn process_unloads(self: &Arc<Self>) {
         self.unloading_chunks.retain_sync(|pos, holder| {
             if Arc::strong_count(holder) == 1 {
-                // Check if dirty by trying to get chunk access
+                // Check if dirty by trying to get chunk access. Entities still in
+                // the chunk have to be saved with it before it can be dropped.
                 let is_dirty = holder
                     .try_chunk(ChunkStatus::StructureStarts)
-                    .is_some_and(|chunk| chunk.is_dirty());
+                    .is_some_and(|chunk| chunk.is_dirty() || Self::has_entities(&chunk));
 
                 if is_dirty {
                     // Save the chunk, keep until next tick when it's clean
@@ -613,6 +826,12 @@ impl ChunkMap {
                     MAX_VIEW_DISTANCE.saturating_sub(new_view.view_distance),
                 );
 
+                // The client may still be centered on where it was in another world.
+                connection.send_packet(CSetChunkCenter {
+                    x: new_view.center.0.x,
+                    y: new_view.center.0.y,
+                });
+
                 let mut chunk_sender = player.chunk_sender.lock();
                 new_view.for_each(|pos| {
                     chunk_sender.mark_chunk_pending_to_send(pos);
@@ -12,6 +12,7 @@ use crate::chunk::{
     section::{ChunkSection, Sections},
     world_gen_context::WorldGenContext,
 };
+use crate::lighting::{LightLayer, LightRegion, initialize_chunk_light, light_chunk};
 
 pub struct ChunkStatusTasks;
 
@@ -74,12 +75,17 @@ impl ChunkStatusTasks {
         Ok(())
     }
 
+    #[allow(clippy::missing_panics_doc)]
     pub fn generate_biomes(
-        _context: Arc<WorldGenContext>,
+        context: Arc<WorldGenContext>,
         _step: &ChunkStep,
         _cache: &Arc<StaticCache2D<Arc<ChunkHolder>>>,
-        _holder: Arc<ChunkHolder>,
+        holder: Arc<ChunkHolder>,
     ) -> Result<(), anyhow::Error> {
+        let chunk = holder
+            .try_chunk(ChunkStatus::StructureReferences)
+            .expect("Chunk not found at status StructureReferences");
+        context.generator.create_biomes(&chunk);
         Ok(())
     }
 
@@ -124,21 +130,90 @@ impl ChunkStatusTasks {
         Ok(())
     }
 
+    /// Places the sky and block light sources of the chunk.
+    ///
+    /// # Panics
+    /// Panics if the chunk is not at `ChunkStatus::Features` or higher.
     pub fn initialize_light(
-        _context: Arc<WorldGenContext>,
+        context: Arc<WorldGenContext>,
         _step: &ChunkStep,
         _cache: &Arc<StaticCache2D<Arc<ChunkHolder>>>,
-        _holder: Arc<ChunkHolder>,
+        holder: Arc<ChunkHolder>,
     ) -> Result<(), anyhow::Error> {
+        let world = context.world();
+        let chunk = holder
+            .try_chunk(ChunkStatus::Features)
+            .expect("Chunk not found at status Features");
+        initialize_chunk_light(
+            chunk.sections(),
+            world.chunk_map.light_engine.has_skylight(),
+        );
+
+        // Chunks loaded from disk may already have been sent without light
+        if holder.persisted_status() == Some(ChunkStatus::Full) {
+            for light_section_index in 0..chunk.sections().sections.len() + 2 {
+                for layer in LightLayer::ALL {
+                    world
+                        .chunk_map
+                        .section_light_changed(&holder, layer, light_section_index);
+                }
+            }
+        }
         Ok(())
     }
 
+    /// Propagates light within the chunk and across the borders to its neighbors.
+    ///
+    /// # Panics
+    /// Panics if the chunk is not at `ChunkStatus::InitializeLight` or higher.
     pub fn light(
-        _context: Arc<WorldGenContext>,
+        context: Arc<WorldGenContext>,
         _step: &ChunkStep,
-        _cache: &Arc<StaticCache2D<Arc<ChunkHolder>>>,
-        _holder: Arc<ChunkHolder>,
+        cache: &Arc<StaticCache2D<Arc<ChunkHolder>>>,
+        holder: Arc<ChunkHolder>,
     ) -> Result<(), anyhow::Error> {
+        let world = context.world();
+        let light_engine = &world.chunk_map.light_engine;
+        let pos = holder.get_pos();
+
+        let _lock = light_engine.generation_lock();
+        let neighbors: Vec<_> = (-1..=1)
+            .flat_map(|dz| (-1..=1).map(move |dx| (dx, dz)))
+            .map(|(dx, dz)| cache.get(pos.0.x + dx, pos.0.y + dz))
+            .filter_map(|neighbor| {
+                neighbor
+                    .try_chunk_recursive(ChunkStatus::InitializeLight)
+                    .map(|guard| (neighbor, guard))
+            })
+            .collect();
+        assert!(
+            neighbors
+                .iter()
+                .any(|(neighbor, _)| neighbor.get_pos() == pos),
+            "Chunk not found at status InitializeLight"
+        );
+
+        let mut region = LightRegion::new(
+            context.min_y(),
+            context.height(),
+            light_engine.has_skylight(),
+        );
+        for (neighbor, guard) in &neighbors {
+            region.add_chunk(neighbor.get_pos(), guard.sections());
+        }
+
+        light_chunk(&mut region, pos);
+
+        // Neighbors that players may already see need their changes sent
+        for (chunk_pos, layer, light_section_index) in region.take_changes() {
+            if let Some((neighbor, _)) = neighbors.iter().find(|(n, _)| n.get_pos() == chunk_pos)
+                && neighbor.persisted_status() == Some(ChunkStatus::Full)
+            {
+                world
+                    .chunk_map
+                    .section_light_changed(neighbor, layer, light_section_index);
+            }
+        }
         Ok(())
     }
 
@@ -0,0 +1,137 @@
+//! This module contains the `DataLayer` struct, a nibble array holding one light layer of a section.
+
+/// A 16x16x16 array of 4-bit values, used to store sky or block light.
+///
+/// Matches vanilla's `DataLayer`: values are packed two per byte in y, z, x order,
+/// and the backing array is only allocated once a value differs from the default.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DataLayer {
+    data: Option<Box<[u8; Self::SIZE]>>,
+    default_value: u8,
+}
+
+impl DataLayer {
+    /// The size of the packed array in bytes.
+    pub const SIZE: usize = 2048;
+
+    /// Creates a layer where every value is `default_value`.
+    #[must_use]
+    pub const fn new(default_value: u8) -> Self {
+        Self {
+            data: None,
+            default_value,
+        }
+    }
+
+    /// Returns the index of the given section-relative coordinates in y, z, x order.
+    #[inline]
+    #[must_use]
+    pub const fn index(x: usize, y: usize, z: usize) -> usize {
+        (y << 8) | (z << 4) | x
+    }
+
+    /// Gets the value at the given section-relative coordinates.
+    #[inline]
+    #[must_use]
+    pub fn get(&self, x: usize, y: usize, z: usize) -> u8 {
+        self.get_index(Self::index(x, y, z))
+    }
+
+    /// Gets the value at the given index in y, z, x order.
+    #[inline]
+    #[must_use]
+    pub fn get_index(&self, index: usize) -> u8 {
+        match &self.data {
+            Some(data) => (data[index >> 1] >> ((index & 1) << 2)) & 0xF,
+            None => self.default_value,
+        }
+    }
+
+    /// Sets the value at the given section-relative coordinates.
+    #[inline]
+    pub fn set(&mut self, x: usize, y: usize, z: usize, value: u8) {
+        self.set_index(Self::index(x, y, z), value);
+    }
+
+    /// Sets the value at the given index in y, z, x order.
+    pub fn set_index(&mut self, index: usize, value: u8) {
+        debug_assert!(value <= 15);
+        let default_value = self.default_value;
+        if self.data.is_none() && value == default_value {
+            return;
+        }
+        let data = self
+            .data
+            .get_or_insert_with(|| Box::new([default_value | (default_value << 4); Self::SIZE]));
+        let shift = (index & 1) << 2;
+        let byte = &mut data[index >> 1];
+        *byte = (*byte & !(0xF << shift)) | (value << shift);
+    }
+
+    /// Sets every value to `value`, releasing the backing array.
+    pub fn fill(&mut self, value: u8) {
+        self.data = None;
+        self.default_value = value;
+    }
+
+    /// Returns true if every value is known to be the same without scanning the array.
+    #[must_use]
+    pub fn is_definitely_homogenous(&self) -> bool {
+        self.data.is_none()
+    }
+
+    /// Returns true if every value is zero.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        match &self.data {
+            Some(data) => data.iter().all(|&b| b == 0),
+            None => self.default_value == 0,
+        }
+    }
+
+    /// Returns the packed nibble array as sent to the client.
+    #[must_use]
+    pub fn to_bytes(&self) -> Vec<u8> {
+        match &self.data {
+            Some(data) => data.to_vec(),
+            None => vec![self.default_value | (self.default_value << 4); Self::SIZE],
+        }
+    }
+}
+
+impl Default for DataLayer {
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_value_without_allocation() {
+        let mut layer = DataLayer::new(15);
+        assert_eq!(layer.get(3, 7, 9), 15);
+        layer.set(3, 7, 9, 15);
+        assert!(layer.is_definitely_homogenous());
+        assert_eq!(layer.to_bytes(), vec![0xFF; DataLayer::SIZE]);
+    }
+
+    #[test]
+    fn test_set_get_nibbles() {
+        let mut layer = DataLayer::default();
+        layer.set(0, 0, 0, 4);
+        layer.set(1, 0, 0, 11);
+        layer.set(15, 15, 15, 7);
+        assert_eq!(layer.get(0, 0, 0), 4);
+        assert_eq!(layer.get(1, 0, 0), 11);
+        assert_eq!(layer.get(15, 15, 15), 7);
+        assert_eq!(layer.get(2, 0, 0), 0);
+
+        let bytes = layer.to_bytes();
+        assert_eq!(bytes[0], 0xB4);
+        assert_eq!(bytes[DataLayer::SIZE - 1], 0x70);
+        assert!(!layer.is_empty());
+    }
+}
@@ -1,55 +1,78 @@
+//! This module contains the `FlatChunkGenerator`, which stacks configured layers.
+
+use std::iter;
+use std::sync::LazyLock;
+
+use steel_registry::biome::BiomeRef;
+use steel_registry::blocks::block_state_ext::BlockStateExt;
+use steel_registry::{REGISTRY, vanilla_biomes};
 use steel_utils::BlockStateId;
 
+use crate::chunk::flat_settings::FlatSettings;
+use crate::chunk::paletted_container::PalettedContainer;
 use crate::chunk::{chunk_access::ChunkAccess, chunk_generator::ChunkGenerator};
 
-/// A chunk generator that generates a flat world.
+/// A chunk generator that generates a flat world, like vanilla's `FlatLevelSource`.
 pub struct FlatChunkGenerator {
-    /// The block state id for bedrock.
-    pub bedrock: BlockStateId,
-    /// The block state id for dirt.
-    pub dirt: BlockStateId,
-    /// The block state id for grass blocks.
-    pub grass: BlockStateId,
+    /// The block state of every y level, from the bottom of the world upwards.
+    layers: Vec<BlockStateId>,
+    biome: BiomeRef,
 }
 
 impl FlatChunkGenerator {
-    /// Creates a new `FlatChunkGenerator`.
+    /// Creates a new `FlatChunkGenerator` for a dimension that is `height` blocks tall.
+    ///
+    /// The layers start at the bottom of the dimension and are cut off at its top. Unknown
+    /// blocks are skipped and an unknown biome falls back to plains, with a warning.
     #[must_use]
-    pub fn new(bedrock: BlockStateId, dirt: BlockStateId, grass: BlockStateId) -> Self {
-        Self {
-            bedrock,
-            dirt,
-            grass,
+    pub fn new(settings: &FlatSettings, height: i32) -> Self {
+        let height = height.max(0) as usize;
+        let mut layers = Vec::new();
+        for layer in &settings.layers {
+            let Some(block) = REGISTRY.blocks.by_key(&layer.block) else {
+                log::warn!("Unknown block {} in superflat layers", layer.block);
+                continue;
+            };
+            let state = REGISTRY.blocks.get_default_state_id(block);
+            let count = (layer.height as usize).min(height - layers.len());
+            layers.extend(iter::repeat_n(state, count));
         }
+
+        let biome = REGISTRY.biomes.by_key(&settings.biome).unwrap_or_else(|| {
+            log::warn!("Unknown superflat biome {}, using plains", settings.biome);
+            LazyLock::force(&vanilla_biomes::PLAINS)
+        });
+
+        Self { layers, biome }
+    }
+
+    /// The biome of every position.
+    #[must_use]
+    pub const fn biome(&self) -> BiomeRef {
+        self.biome
     }
 }
 
 impl ChunkGenerator for FlatChunkGenerator {
     fn create_structures(&self, _chunk: &ChunkAccess) {}
 
-    fn create_biomes(&self, _chunk: &ChunkAccess) {}
+    fn create_biomes(&self, chunk: &ChunkAccess) {
+        let biome = *REGISTRY.biomes.get_id(self.biome) as u8;
+        for section in &chunk.sections().sections {
+            section.write().biomes = PalettedContainer::Homogeneous(biome);
+        }
+    }
 
     fn fill_from_noise(&self, chunk: &ChunkAccess) {
-        // Layers:
-        // 0: Bedrock
-        // 1-2: Dirt
-        // 3: Grass Block
-        // (Relative to bottom of chunk, assuming 0 is bottom)
-
-        // TODO: Get actual height from level/config?
-        // For now assuming standard height behavior where 0 is bottom of the chunk data.
-
-        for x in 0..16 {
-            for z in 0..16 {
-                // Bedrock at bottom
-                chunk.set_relative_block(x, 0, z, self.bedrock);
-
-                // Dirt layers
-                chunk.set_relative_block(x, 1, z, self.dirt);
-                chunk.set_relative_block(x, 2, z, self.dirt);
-
-                // Grass block
-                chunk.set_relative_block(x, 3, z, self.grass);
+        // Relative y 0 is the bottom of the dimension, where the first layer goes.
+        for (y, &state) in self.layers.iter().enumerate() {
+            if state.is_air() {
+                continue;
+            }
+            for x in 0..16 {
+                for z in 0..16 {
+                    chunk.set_relative_block(x, y, z, state);
+                }
             }
         }
     }
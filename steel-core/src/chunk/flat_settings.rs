@@ -0,0 +1,273 @@
+//! Settings for superflat worlds: the layers to stack and the biome to use.
+
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+use steel_utils::Identifier;
+
+/// A run of one block stacked `height` times.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FlatLayer {
+    /// The block of the layer, placed in its default state.
+    pub block: Identifier,
+    /// How many blocks tall the layer is.
+    pub height: u32,
+}
+
+impl FlatLayer {
+    /// Creates a layer of a vanilla block.
+    #[must_use]
+    pub const fn vanilla(block: &'static str, height: u32) -> Self {
+        Self {
+            block: Identifier::vanilla_static(block),
+            height,
+        }
+    }
+}
+
+/// The layers and biome of a superflat world, like vanilla's `FlatLevelGeneratorSettings`.
+///
+/// In config files this is either the name of a vanilla preset (`"classic"`,
+/// `"redstone ready"`, ...), a preset string such as
+/// `"minecraft:bedrock,2*minecraft:dirt,minecraft:grass_block;minecraft:plains"`, or an
+/// object with `layers` and `biome`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "FlatSettingsRepr")]
+pub struct FlatSettings {
+    /// The layers from the bottom of the world upwards.
+    pub layers: Vec<FlatLayer>,
+    /// The biome of every position.
+    pub biome: Identifier,
+}
+
+/// The vanilla presets, as `(name, preset string)`.
+const PRESETS: [(&str, &str); 9] = [
+    (
+        "classic",
+        "minecraft:bedrock,2*minecraft:dirt,minecraft:grass_block;minecraft:plains",
+    ),
+    (
+        "tunnelers_dream",
+        "minecraft:bedrock,230*minecraft:stone,5*minecraft:dirt,minecraft:grass_block;\
+         minecraft:windswept_hills",
+    ),
+    (
+        "water_world",
+        "minecraft:bedrock,64*minecraft:deepslate,5*minecraft:stone,5*minecraft:dirt,\
+         5*minecraft:sand,90*minecraft:water;minecraft:deep_ocean",
+    ),
+    (
+        "overworld",
+        "minecraft:bedrock,59*minecraft:stone,3*minecraft:dirt,minecraft:grass_block;\
+         minecraft:plains",
+    ),
+    (
+        "snowy_kingdom",
+        "minecraft:bedrock,59*minecraft:stone,3*minecraft:dirt,minecraft:grass_block,\
+         minecraft:snow;minecraft:snowy_plains",
+    ),
+    (
+        "bottomless_pit",
+        "2*minecraft:cobblestone,3*minecraft:dirt,minecraft:grass_block;minecraft:plains",
+    ),
+    (
+        "desert",
+        "minecraft:bedrock,3*minecraft:stone,52*minecraft:sandstone,8*minecraft:sand;\
+         minecraft:desert",
+    ),
+    (
+        "redstone_ready",
+        "minecraft:bedrock,3*minecraft:stone,116*minecraft:sandstone;minecraft:desert",
+    ),
+    ("the_void", "minecraft:air;minecraft:the_void"),
+];
+
+impl FlatSettings {
+    /// Returns the vanilla preset called `name`, ignoring case and treating spaces like
+    /// underscores, so `"Redstone Ready"` and `"redstone_ready"` are the same preset.
+    #[must_use]
+    pub fn preset(name: &str) -> Option<Self> {
+        let name = name.trim().to_ascii_lowercase().replace([' ', '-'], "_");
+        let name = name.strip_prefix("minecraft:").unwrap_or(&name);
+        PRESETS
+            .iter()
+            .find(|(preset, _)| *preset == name)
+            .and_then(|(_, layers)| layers.parse().ok())
+    }
+
+    /// The total height of all layers.
+    #[must_use]
+    pub fn height(&self) -> u32 {
+        self.layers.iter().map(|layer| layer.height).sum()
+    }
+}
+
+impl Default for FlatSettings {
+    /// The classic preset: bedrock, two dirt and grass in plains.
+    fn default() -> Self {
+        Self {
+            layers: vec![
+                FlatLayer::vanilla("bedrock", 1),
+                FlatLayer::vanilla("dirt", 2),
+                FlatLayer::vanilla("grass_block", 1),
+            ],
+            biome: default_biome(),
+        }
+    }
+}
+
+fn default_biome() -> Identifier {
+    Identifier::vanilla_static("plains")
+}
+
+/// Parses an identifier, defaulting to the `minecraft` namespace like vanilla does.
+fn parse_identifier(s: &str) -> Result<Identifier, String> {
+    let s = s.trim();
+    if s.contains(':') {
+        Identifier::from_str(s).map_err(|e| format!("{e}: {s}"))
+    } else if Identifier::validate_path(s) && !s.is_empty() {
+        Ok(Identifier::vanilla(s.to_owned()))
+    } else {
+        Err(format!("Invalid resource location: {s}"))
+    }
+}
+
+impl FromStr for FlatSettings {
+    type Err = String;
+
+    /// Parses a preset string: comma separated layers from the bottom up, each optionally
+    /// prefixed with `<height>*`, then `;` and the biome. The biome defaults to plains.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (layers, biome) = match s.split_once(';') {
+            Some((layers, biome)) => (layers, parse_identifier(biome)?),
+            None => (s, default_biome()),
+        };
+
+        let layers = layers
+            .split(',')
+            .map(|layer| {
+                let (height, block) = match layer.split_once('*') {
+                    Some((height, block)) => (
+                        height
+                            .trim()
+                            .parse()
+                            .map_err(|_| format!("Invalid layer height: {height}"))?,
+                        block,
+                    ),
+                    None => (1, layer),
+                };
+                Ok(FlatLayer {
+                    block: parse_identifier(block)?,
+                    height,
+                })
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+
+        Ok(Self { layers, biome })
+    }
+}
+
+impl Display for FlatSettings {
+    /// Writes the settings as a preset string.
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for (index, layer) in self.layers.iter().enumerate() {
+            if index > 0 {
+                f.write_str(",")?;
+            }
+            if layer.height != 1 {
+                write!(f, "{}*", layer.height)?;
+            }
+            write!(f, "{}", layer.block)?;
+        }
+        write!(f, ";{}", self.biome)
+    }
+}
+
+/// The accepted config forms of [`FlatSettings`].
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum FlatSettingsRepr {
+    Preset(String),
+    Custom {
+        layers: Vec<FlatLayer>,
+        #[serde(default = "default_biome")]
+        biome: Identifier,
+    },
+}
+
+impl TryFrom<FlatSettingsRepr> for FlatSettings {
+    type Error = String;
+
+    fn try_from(repr: FlatSettingsRepr) -> Result<Self, Self::Error> {
+        match repr {
+            FlatSettingsRepr::Preset(preset) => {
+                Self::preset(&preset).map_or_else(|| preset.parse(), Ok)
+            }
+            FlatSettingsRepr::Custom { layers, biome } => Ok(Self { layers, biome }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FlatLayer, FlatSettings, PRESETS};
+
+    #[test]
+    fn test_presets_parse() {
+        for (name, _) in PRESETS {
+            assert!(FlatSettings::preset(name).is_some(), "{name} should parse");
+        }
+        assert_eq!(
+            FlatSettings::preset("classic"),
+            Some(FlatSettings::default())
+        );
+        let redstone = FlatSettings::preset("Redstone Ready").expect("preset exists");
+        assert_eq!(redstone.height(), 120);
+        assert_eq!(redstone.biome.path, "desert");
+        assert_eq!(
+            FlatSettings::preset("water_world").map(|settings| settings.height()),
+            Some(170)
+        );
+        assert_eq!(FlatSettings::preset("nether"), None);
+    }
+
+    #[test]
+    fn test_preset_string_round_trips() {
+        let settings: FlatSettings = "bedrock, 3*stone,minecraft:sandstone;minecraft:desert"
+            .parse()
+            .expect("valid preset string");
+        assert_eq!(
+            settings.layers,
+            [
+                FlatLayer::vanilla("bedrock", 1),
+                FlatLayer::vanilla("stone", 3),
+                FlatLayer::vanilla("sandstone", 1),
+            ]
+        );
+        assert_eq!(
+            settings.to_string(),
+            "minecraft:bedrock,3*minecraft:stone,minecraft:sandstone;minecraft:desert"
+        );
+        assert!("x*stone".parse::<FlatSettings>().is_err());
+        assert!("Stone".parse::<FlatSettings>().is_err());
+    }
+
+    #[test]
+    fn test_deserializes_every_form() {
+        let preset: FlatSettings = serde_json::from_str("\"desert\"").expect("preset name");
+        assert_eq!(preset.biome.path, "desert");
+
+        let custom: FlatSettings = serde_json::from_str(
+            r#"{"layers":[{"block":"minecraft:bedrock","height":1}],"biome":"minecraft:badlands"}"#,
+        )
+        .expect("layer object");
+        assert_eq!(
+            serde_json::from_str::<FlatSettings>(
+                &serde_json::to_string(&custom).expect("serializes")
+            )
+            .expect("round trips"),
+            custom
+        );
+    }
+}
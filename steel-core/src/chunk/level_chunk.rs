@@ -9,13 +9,22 @@ use std::{
 };
 
 use rand::Rng;
+use rustc_hash::FxHashSet;
 use steel_protocol::packets::game::{
     BlockEntityInfo, ChunkPacketData, HeightmapType as ProtocolHeightmapType, Heightmaps,
     LightUpdatePacketData,
 };
-use steel_registry::{REGISTRY, blocks::block_state_ext::BlockStateExt, vanilla_blocks};
+use steel_registry::{
+    REGISTRY,
+    blocks::{BlockRef, block_state_ext::BlockStateExt},
+    fluid::FluidRef,
+    vanilla_blocks,
+};
 use steel_utils::{
-    BlockPos, BlockStateId, ChunkPos, codec::BitSet, locks::SyncRwLock, types::UpdateFlags,
+    BlockPos, BlockStateId, ChunkPos,
+    codec::BitSet,
+    locks::{SyncMutex, SyncRwLock},
+    types::UpdateFlags,
 };
 
 use crate::behavior::BLOCK_BEHAVIORS;
@@ -25,6 +34,8 @@ use crate::chunk::{
     proto_chunk::ProtoChunk,
     section::Sections,
 };
+use crate::lighting::{LevelLightEngine, LightLayer};
+use crate::ticks::{LevelChunkTicks, LevelTicks, TickPriority};
 use crate::world::World;
 
 /// A chunk that is ready to be sent to the client.
@@ -49,6 +60,10 @@ pub struct LevelChunk {
     level: Weak<World>,
     /// Block entities stored in this chunk.
     block_entities: BlockEntityStorage,
+    /// Scheduled block ticks in this chunk.
+    pub block_ticks: SyncMutex<LevelChunkTicks<BlockRef>>,
+    /// Scheduled fluid ticks in this chunk.
+    pub fluid_ticks: SyncMutex<LevelChunkTicks<FluidRef>>,
 }
 
 impl LevelChunk {
@@ -175,6 +190,8 @@ impl LevelChunk {
             height,
             level,
             block_entities: BlockEntityStorage::new(),
+            block_ticks: SyncMutex::new(LevelChunkTicks::new()),
+            fluid_ticks: SyncMutex::new(LevelChunkTicks::new()),
         }
     }
 
@@ -213,6 +230,8 @@ impl LevelChunk {
             height,
             level,
             block_entities: BlockEntityStorage::new(),
+            block_ticks: SyncMutex::new(LevelChunkTicks::new()),
+            fluid_ticks: SyncMutex::new(LevelChunkTicks::new()),
         }
     }
 
@@ -337,6 +356,46 @@ impl LevelChunk {
         self.block_entities.cleanup_tickers();
     }
 
+    // === Scheduled Tick Methods ===
+
+    /// Schedules a block tick in this chunk.
+    ///
+    /// Returns false if the position already has a pending tick for this block.
+    pub fn schedule_block_tick(
+        &self,
+        level_ticks: &LevelTicks,
+        pos: BlockPos,
+        block: BlockRef,
+        delay: u32,
+        priority: TickPriority,
+    ) -> bool {
+        let scheduled =
+            level_ticks.schedule(&mut self.block_ticks.lock(), block, pos, delay, priority);
+        if scheduled {
+            self.mark_unsaved();
+        }
+        scheduled
+    }
+
+    /// Schedules a fluid tick in this chunk.
+    ///
+    /// Returns false if the position already has a pending tick for this fluid.
+    pub fn schedule_fluid_tick(
+        &self,
+        level_ticks: &LevelTicks,
+        pos: BlockPos,
+        fluid: FluidRef,
+        delay: u32,
+        priority: TickPriority,
+    ) -> bool {
+        let scheduled =
+            level_ticks.schedule(&mut self.fluid_ticks.lock(), fluid, pos, delay, priority);
+        if scheduled {
+            self.mark_unsaved();
+        }
+        scheduled
+    }
+
     /// Sets a block state at the given position.
     ///
     /// Returns the old block state, or `None` if nothing changed.
@@ -403,19 +462,11 @@ impl LevelChunk {
         let old_block = old_state.get_block();
         let new_block = state.get_block();
 
-        // TODO: Light updates
-        // In vanilla, light engine is notified when section emptiness changes:
-        // let is_empty = section.read().states.has_only_air();
-        // if was_empty != is_empty {
-        //     level.chunk_source.light_engine.update_section_status(pos, is_empty);
-        //     level.chunk_source.on_section_emptiness_changed(chunk_pos.x, section_y, chunk_pos.z, is_empty);
-        // }
-        //
-        // And when light properties change:
-        // if LightEngine::has_different_light_properties(old_state, state) {
-        //     self.sky_light_sources.update(self, local_x, y, local_z);
-        //     level.chunk_source.light_engine.check_block(pos);
-        // }
+        if LevelLightEngine::has_different_light_properties(old_state, state)
+            && let Some(level) = self.get_level()
+        {
+            level.chunk_map.light_engine.check_block(pos);
+        }
 
         // Re-read the block to verify it wasn't changed concurrently
         let current_block = section
@@ -508,6 +559,24 @@ impl LevelChunk {
         section_guard.states.get(local_x, local_y, local_z)
     }
 
+    /// Gets the light of one layer at the given position.
+    #[must_use]
+    pub fn get_light(&self, layer: LightLayer, pos: BlockPos) -> u8 {
+        let y = pos.0.y;
+        if y < self.min_y || y >= self.min_y + self.height {
+            return 0;
+        }
+
+        self.sections.sections[self.get_section_index(y)]
+            .read()
+            .light(layer)
+            .get(
+                (pos.0.x & 15) as usize,
+                (y & 15) as usize,
+                (pos.0.z & 15) as usize,
+            )
+    }
+
     /// Extracts the chunk data for sending to the client.
     #[must_use]
     pub fn extract_chunk_data(&self) -> ChunkPacketData {
@@ -576,22 +645,64 @@ impl LevelChunk {
     /// Extracts the light data for sending to the client.
     #[must_use]
     pub fn extract_light_data(&self) -> LightUpdatePacketData {
+        self.light_data_for(|_, _| true)
+    }
+
+    /// Extracts the light of the given sections, as (layer, light section index).
+    #[must_use]
+    pub fn extract_light_update(
+        &self,
+        changes: &FxHashSet<(LightLayer, usize)>,
+    ) -> LightUpdatePacketData {
+        self.light_data_for(|layer, index| changes.contains(&(layer, index)))
+    }
+
+    /// Builds light data for the light sections accepted by `filter`.
+    ///
+    /// Matches vanilla's `ClientboundLightUpdatePacketData`: sections with light go in the
+    /// mask with their data, sections without any light go in the empty mask. The light
+    /// sections below and above the world hold no data and are left out of both.
+    fn light_data_for(&self, filter: impl Fn(LightLayer, usize) -> bool) -> LightUpdatePacketData {
         // Vanilla's light section count is sectionsCount + 2 (one below and one above the world)
         let light_section_count = self.sections.sections.len() + 2;
-        let mut sky_y_mask = BitSet(vec![0; light_section_count.div_ceil(64)].into_boxed_slice());
-        let mut block_y_mask = BitSet(vec![0; light_section_count.div_ceil(64)].into_boxed_slice());
-        let empty_sky_y_mask = BitSet(vec![0; light_section_count.div_ceil(64)].into_boxed_slice());
-        let empty_block_y_mask =
-            BitSet(vec![0; light_section_count.div_ceil(64)].into_boxed_slice());
+        let mask = || BitSet(vec![0; light_section_count.div_ceil(64)].into_boxed_slice());
+        let mut sky_y_mask = mask();
+        let mut block_y_mask = mask();
+        let mut empty_sky_y_mask = mask();
+        let mut empty_block_y_mask = mask();
 
         let mut sky_updates = Vec::new();
         let mut block_updates = Vec::new();
 
-        for i in 0..light_section_count {
-            sky_y_mask.set(i, true);
-            block_y_mask.set(i, true);
-            sky_updates.push(vec![0xFF; 2048]);
-            block_updates.push(vec![0xFF; 2048]);
+        let has_skylight = self
+            .get_level()
+            .is_none_or(|level| level.dimension.has_skylight);
+
+        for (section_index, section) in self.sections.sections.iter().enumerate() {
+            let light_index = section_index + 1;
+            let section = section.read();
+
+            for layer in LightLayer::ALL {
+                if !filter(layer, light_index) || (layer == LightLayer::Sky && !has_skylight) {
+                    continue;
+                }
+                let (y_mask, empty_y_mask, updates) = match layer {
+                    LightLayer::Sky => (&mut sky_y_mask, &mut empty_sky_y_mask, &mut sky_updates),
+                    LightLayer::Block => (
+                        &mut block_y_mask,
+                        &mut empty_block_y_mask,
+                        &mut block_updates,
+                    ),
+                };
+
+                let data = section.light(layer);
+                if data.is_empty() {
+                    empty_y_mask.set(light_index, true);
+                } else {
+                    y_mask.set(light_index, true);
+                    updates.push(data.to_bytes());
+                }
+            }
         }
 
         LightUpdatePacketData {
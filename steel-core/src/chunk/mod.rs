@@ -10,13 +10,17 @@ pub mod chunk_pyramid;
 pub mod chunk_status_tasks;
 /// Tracks chunk levels based on ticket propagation.
 pub mod chunk_ticket_manager;
+pub mod data_layer;
 pub mod heightmap;
 /// Tracks the chunks that are visible to a player.
 pub mod player_chunk_view;
 
 /// Generates flat worlds with configurable layers.
 pub mod flat_chunk_generator;
+pub mod flat_settings;
 pub mod level_chunk;
+/// Generates terrain from noise settings and density functions.
+pub mod noise_chunk_generator;
 pub mod paletted_container;
 pub mod proto_chunk;
 pub mod section;
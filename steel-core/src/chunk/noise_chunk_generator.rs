@@ -0,0 +1,232 @@
+//! This module contains the `NoiseChunkGenerator`, which shapes terrain from density functions.
+
+use steel_registry::REGISTRY;
+use steel_registry::biome::BiomeRef;
+use steel_registry::blocks::block_state_ext::BlockStateExt;
+use steel_registry::noise_settings::NoiseGeneratorSettingsRef;
+use steel_utils::{BlockPos, BlockStateId, ChunkPos};
+
+use crate::chunk::{chunk_access::ChunkAccess, chunk_generator::ChunkGenerator};
+use crate::worldgen::aquifer::{Aquifer, GlobalFluidPicker, NoiseBasedAquifer};
+use crate::worldgen::noise_chunk::NoiseChunk;
+use crate::worldgen::ore_veinifier::OreVeinifier;
+use crate::worldgen::{BiomeSource, RandomState};
+
+/// A chunk generator that places blocks from a noise router, like vanilla's
+/// `NoiseBasedChunkGenerator`.
+///
+/// Only the biomes, base terrain, aquifers and ore veins are generated. Surface rules and
+/// carvers are not supported yet, so the terrain is bare stone and fluids.
+pub struct NoiseChunkGenerator {
+    settings: NoiseGeneratorSettingsRef,
+    biome_source: BiomeSource,
+    random_state: RandomState,
+    ore_veinifier: OreVeinifier,
+    global_fluid_picker: GlobalFluidPicker,
+    default_block: BlockStateId,
+    min_y: i32,
+    height: i32,
+}
+
+impl NoiseChunkGenerator {
+    /// Creates a new `NoiseChunkGenerator` for a dimension spanning `height` blocks from
+    /// `min_y`.
+    #[must_use]
+    pub fn new(
+        settings: NoiseGeneratorSettingsRef,
+        biome_source: BiomeSource,
+        seed: i64,
+        min_y: i32,
+        height: i32,
+    ) -> Self {
+        Self {
+            settings,
+            biome_source,
+            random_state: RandomState::new(settings, seed),
+            ore_veinifier: OreVeinifier::new(),
+            global_fluid_picker: GlobalFluidPicker::new(
+                settings.sea_level,
+                REGISTRY.blocks.get_default_state_id(settings.default_fluid),
+            ),
+            default_block: REGISTRY.blocks.get_default_state_id(settings.default_block),
+            min_y,
+            height,
+        }
+    }
+
+    /// The noise settings this generator uses.
+    #[must_use]
+    pub const fn settings(&self) -> NoiseGeneratorSettingsRef {
+        self.settings
+    }
+
+    /// The seeded noises and density functions of this generator.
+    #[must_use]
+    pub const fn random_state(&self) -> &RandomState {
+        &self.random_state
+    }
+
+    /// The sea level of the generated terrain.
+    #[must_use]
+    pub const fn sea_level(&self) -> i32 {
+        self.settings.sea_level
+    }
+
+    /// The biome source of this generator.
+    #[must_use]
+    pub const fn biome_source(&self) -> &BiomeSource {
+        &self.biome_source
+    }
+
+    /// Finds the closest position to `origin` whose biome matches `predicate`, see
+    /// [`BiomeSource::find_closest_biome_3d`].
+    pub fn find_closest_biome_3d(
+        &self,
+        origin: BlockPos,
+        radius: i32,
+        horizontal_step: i32,
+        vertical_step: i32,
+        predicate: impl Fn(BiomeRef) -> bool,
+    ) -> Option<(BlockPos, BiomeRef)> {
+        self.biome_source.find_closest_biome_3d(
+            &self.random_state,
+            origin,
+            radius,
+            horizontal_step,
+            vertical_step,
+            self.min_y,
+            self.min_y + self.height - 1,
+            predicate,
+        )
+    }
+
+    /// The vertical range of the fill loop in cells, as the first cell and the cell count.
+    fn cell_range(&self) -> (i32, i32) {
+        let noise = &self.settings.noise;
+        let cell_height = noise.cell_height();
+        let min_y = noise.min_y.max(self.min_y);
+        let max_y = (noise.min_y + noise.height).min(self.min_y + self.height);
+        (
+            min_y.div_euclid(cell_height),
+            (max_y - min_y).div_euclid(cell_height),
+        )
+    }
+
+    fn create_noise_chunk(&self, pos: ChunkPos) -> NoiseChunk<'_> {
+        let noise = &self.settings.noise;
+        let (cell_min_y, cell_count_y) = self.cell_range();
+        NoiseChunk::new(
+            &self.random_state.graph,
+            self.random_state.router.preliminary_surface_level,
+            noise.cell_width(),
+            noise.cell_height(),
+            cell_min_y,
+            cell_count_y,
+            pos.0.x * 16,
+            pos.0.y * 16,
+        )
+    }
+}
+
+impl ChunkGenerator for NoiseChunkGenerator {
+    fn create_structures(&self, _chunk: &ChunkAccess) {}
+
+    fn create_biomes(&self, chunk: &ChunkAccess) {
+        let pos = chunk.pos();
+        let quart_x = pos.0.x * 4;
+        let quart_z = pos.0.y * 4;
+        // The climate noises are flat cached per chunk, so reuse the noise chunk's caches.
+        let mut noise_chunk = self.create_noise_chunk(pos);
+        for (index, section) in chunk.sections().sections.iter().enumerate() {
+            let quart_y = ((self.min_y >> 4) + index as i32) * 4;
+            let mut section = section.write();
+            for x in 0..4 {
+                for y in 0..4 {
+                    for z in 0..4 {
+                        let biome = self.biome_source.get_noise_biome(
+                            &self.random_state,
+                            &mut noise_chunk,
+                            quart_x + x,
+                            quart_y + y,
+                            quart_z + z,
+                        );
+                        section.biomes.set(
+                            x as usize,
+                            y as usize,
+                            z as usize,
+                            *REGISTRY.biomes.get_id(biome) as u8,
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    fn fill_from_noise(&self, chunk: &ChunkAccess) {
+        let cell_height = self.settings.noise.cell_height();
+        let (cell_min_y, cell_count_y) = self.cell_range();
+        if cell_count_y <= 0 {
+            return;
+        }
+
+        let pos = chunk.pos();
+        let first_block_x = pos.0.x * 16;
+        let first_block_z = pos.0.y * 16;
+        let router = self.random_state.router;
+        let mut noise_chunk = self.create_noise_chunk(pos);
+        let mut aquifer = if self.settings.aquifers_enabled {
+            Aquifer::NoiseBased(Box::new(NoiseBasedAquifer::new(
+                pos,
+                router,
+                &self.random_state.aquifer_random,
+                cell_min_y * cell_height,
+                cell_count_y * cell_height,
+                self.global_fluid_picker,
+            )))
+        } else {
+            Aquifer::Disabled(self.global_fluid_picker)
+        };
+
+        let bottom_y = cell_min_y * cell_height;
+        let top_y = bottom_y + cell_count_y * cell_height;
+        for x in 0..16 {
+            for z in 0..16 {
+                for y in bottom_y..top_y {
+                    let block_pos = BlockPos::new(first_block_x + x, y, first_block_z + z);
+                    let density = noise_chunk.sample(router.final_density, block_pos);
+                    let state = aquifer
+                        .compute_substance(&mut noise_chunk, block_pos, density)
+                        .or_else(|| {
+                            self.settings
+                                .ore_veins_enabled
+                                .then(|| {
+                                    self.ore_veinifier.compute(
+                                        &mut noise_chunk,
+                                        &router,
+                                        &self.random_state.ore_random,
+                                        block_pos,
+                                    )
+                                })
+                                .flatten()
+                        })
+                        .unwrap_or(self.default_block);
+
+                    if !state.is_air() {
+                        chunk.set_relative_block(
+                            x as usize,
+                            (y - self.min_y) as usize,
+                            z as usize,
+                            state,
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    fn build_surface(&self, _chunk: &ChunkAccess) {}
+
+    fn apply_carvers(&self, _chunk: &ChunkAccess) {}
+
+    fn apply_biome_decorations(&self, _chunk: &ChunkAccess) {}
+}
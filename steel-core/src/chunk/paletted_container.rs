@@ -141,6 +141,18 @@ impl<V: Hash + Eq + Copy + Default + Debug, const DIM: usize> PalettedContainer<
         }
     }
 
+    /// Returns whether any value in the container matches `predicate`.
+    ///
+    /// Only the palette is checked, which drops values once their last entry is replaced,
+    /// so whole sections can be skipped before scanning them. Matches vanilla's
+    /// `PalettedContainer.maybeHas()`.
+    pub fn maybe_has(&self, predicate: impl Fn(V) -> bool) -> bool {
+        match self {
+            Self::Homogeneous(value) => predicate(*value),
+            Self::Heterogeneous(data) => data.palette.iter().any(|&(value, _)| predicate(value)),
+        }
+    }
+
     /// Sets the value at the given coordinates.
     pub fn set(&mut self, x: usize, y: usize, z: usize, value: V) -> V {
         debug_assert!(x < Self::SIZE);
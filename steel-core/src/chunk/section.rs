@@ -5,7 +5,9 @@ use steel_registry::blocks::block_state_ext::BlockStateExt;
 use steel_utils::{BlockStateId, locks::SyncRwLock, serial::WriteTo};
 
 use crate::behavior::{BLOCK_BEHAVIORS, BlockBehaviorRegistry};
+use crate::chunk::data_layer::DataLayer;
 use crate::chunk::paletted_container::{BiomePalette, BlockPalette};
+use crate::lighting::LightLayer;
 
 /// A wrapper around a chunk section.
 #[derive(Debug)]
@@ -128,6 +130,10 @@ pub struct ChunkSection {
     non_empty_block_count: u16,
     /// Number of randomly-ticking blocks in this section (0-4096).
     pub ticking_block_count: u16,
+    /// The sky light of the section, filled in by the light engine.
+    pub sky_light: DataLayer,
+    /// The block light of the section, filled in by the light engine.
+    pub block_light: DataLayer,
 }
 
 impl ChunkSection {
@@ -142,6 +148,8 @@ impl ChunkSection {
             biomes,
             non_empty_block_count: 0,
             ticking_block_count: 0,
+            sky_light: DataLayer::default(),
+            block_light: DataLayer::default(),
         }
     }
 
@@ -153,6 +161,25 @@ impl ChunkSection {
             biomes: BiomePalette::Homogeneous(0),
             non_empty_block_count: 0,
             ticking_block_count: 0,
+            sky_light: DataLayer::default(),
+            block_light: DataLayer::default(),
+        }
+    }
+
+    /// Returns the light stored for the given layer.
+    #[must_use]
+    pub const fn light(&self, layer: LightLayer) -> &DataLayer {
+        match layer {
+            LightLayer::Sky => &self.sky_light,
+            LightLayer::Block => &self.block_light,
+        }
+    }
+
+    /// Returns the light stored for the given layer mutably.
+    pub const fn light_mut(&mut self, layer: LightLayer) -> &mut DataLayer {
+        match layer {
+            LightLayer::Sky => &mut self.sky_light,
+            LightLayer::Block => &mut self.block_light,
         }
     }
 
@@ -3,10 +3,12 @@
 use std::sync::{Arc, Weak};
 
 use enum_dispatch::enum_dispatch;
+use steel_registry::biome::BiomeRef;
+use steel_utils::BlockPos;
 
 use crate::chunk::{
     chunk_access::ChunkAccess, chunk_generator::ChunkGenerator,
-    flat_chunk_generator::FlatChunkGenerator,
+    flat_chunk_generator::FlatChunkGenerator, noise_chunk_generator::NoiseChunkGenerator,
 };
 use crate::world::World;
 
@@ -14,9 +16,55 @@ use crate::world::World;
 #[enum_dispatch(ChunkGenerator)]
 pub enum ChunkGeneratorType {
     Flat(FlatChunkGenerator),
+    Noise(NoiseChunkGenerator),
     //Custom(Box<dyn ChunkGenerator>),
 }
 
+impl ChunkGeneratorType {
+    /// Whether clients should treat the world as superflat, which lowers the horizon.
+    #[must_use]
+    pub const fn is_flat(&self) -> bool {
+        matches!(self, Self::Flat(_))
+    }
+
+    /// The sea level sent to clients.
+    #[must_use]
+    pub const fn sea_level(&self) -> i32 {
+        match self {
+            // Vanilla's flat generator reports -63 regardless of its layers.
+            Self::Flat(_) => -63,
+            Self::Noise(generator) => generator.sea_level(),
+        }
+    }
+
+    /// Finds the closest position to `origin` whose biome matches `predicate`.
+    ///
+    /// Returns `None` if nothing matches. Flat worlds have a single biome, so `origin`
+    /// itself is returned if that biome matches.
+    pub fn find_closest_biome_3d(
+        &self,
+        origin: BlockPos,
+        radius: i32,
+        horizontal_step: i32,
+        vertical_step: i32,
+        predicate: impl Fn(BiomeRef) -> bool,
+    ) -> Option<(BlockPos, BiomeRef)> {
+        match self {
+            Self::Flat(generator) => {
+                let biome = generator.biome();
+                predicate(biome).then_some((origin, biome))
+            }
+            Self::Noise(generator) => generator.find_closest_biome_3d(
+                origin,
+                radius,
+                horizontal_step,
+                vertical_step,
+                predicate,
+            ),
+        }
+    }
+}
+
 /// Context for world generation.
 ///
 /// Similar to vanilla's `WorldGenContext`, this provides access to the level/dimension
@@ -0,0 +1,1756 @@
+//! Conversion between vanilla Anvil worlds and the Steel region format.
+//!
+//! Anvil region files (`r.<x>.<z>.mca`) start with two 4KB tables: the location of every
+//! chunk in 4KB sectors and the time it was last saved. Each chunk is a big-endian length,
+//! a compression byte and an NBT compound laid out like vanilla's `SerializableChunkData`.
+//! Chunks too large for the region file are stored in a `c.<x>.<z>.mcc` file next to it.
+//!
+//! Only fully generated chunks are converted, the others are generated again. Vanilla
+//! keeps entities in separate region files, they are written on export but not imported.
+
+use std::{
+    io::{self, Cursor, Read, Write},
+    path::{Path, PathBuf},
+    slice,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use flate2::{
+    Compression,
+    read::{GzDecoder, ZlibDecoder},
+    write::{GzEncoder, ZlibEncoder},
+};
+use simdnbt::borrow::{
+    BaseNbtCompound as BorrowedNbtCompound, NbtCompound as NbtCompoundView,
+    read_compound as read_borrowed_compound,
+};
+use simdnbt::{
+    FromNbtTag,
+    owned::{NbtCompound, NbtList, NbtTag},
+};
+use steel_registry::REGISTRY;
+use steel_registry::dimension_type::DimensionTypeRef;
+use steel_registry::game_rules::{GameRuleRef, GameRuleValue, GameRuleValues};
+use steel_registry::vanilla_dimension_types::{OVERWORLD, THE_END, THE_NETHER};
+use steel_utils::{ChunkPos, Identifier};
+use tokio::fs;
+
+use crate::chunk::chunk_access::ChunkStatus;
+use crate::chunk::flat_settings::{FlatLayer, FlatSettings};
+use crate::level_data::{LevelData, LevelDataManager};
+
+use super::{
+    bit_pack::{bits_for_palette_len, pack_indices, unpack_indices},
+    format::{
+        BIOMES_PER_SECTION, BLOCKS_PER_SECTION, CHUNKS_PER_REGION, FILE_HEADER_SIZE,
+        FORMAT_VERSION, PersistentBiomeData, PersistentBlockEntity, PersistentBlockState,
+        PersistentChunk, PersistentScheduledTick, PersistentSection, REGION_MAGIC, RegionHeader,
+        RegionPos, SECTOR_SIZE, TOTAL_HEADER_SIZE,
+    },
+    region_manager::{PreparedChunkSave, RegionManager},
+};
+
+/// Size of the location and timestamp tables at the start of a region file.
+const ANVIL_HEADER_SIZE: usize = 2 * SECTOR_SIZE;
+/// Most sectors the location table can point at for one chunk.
+const MAX_CHUNK_SECTORS: usize = 255;
+
+/// Data version of 1.21.11, which exported worlds are written for.
+const DATA_VERSION: i32 = 4671;
+/// Name of the version exported worlds are written for.
+const VERSION_NAME: &str = "1.21.11";
+/// Version of the Anvil format, stored in `level.dat`.
+const ANVIL_VERSION: i32 = 19133;
+
+/// Chunk compressed with gzip.
+const COMPRESSION_GZIP: u8 = 1;
+/// Chunk compressed with zlib, what vanilla writes by default.
+const COMPRESSION_ZLIB: u8 = 2;
+/// Uncompressed chunk.
+const COMPRESSION_NONE: u8 = 3;
+/// Chunk compressed with LZ4, see [`decompress_lz4_blocks`].
+const COMPRESSION_LZ4: u8 = 4;
+/// Set on the compression byte of chunks stored in a `.mcc` file.
+const EXTERNAL_FLAG: u8 = 0x80;
+
+/// Magic starting every block of an LZ4 chunk.
+const LZ4_MAGIC: &[u8; 8] = b"LZ4Block";
+/// Magic, method, both lengths and the checksum.
+const LZ4_HEADER_SIZE: usize = 21;
+/// Block method for data stored without compression.
+const LZ4_METHOD_RAW: u8 = 0x10;
+/// Block method for LZ4 compressed data.
+const LZ4_METHOD_LZ4: u8 = 0x20;
+
+/// Fewest bits vanilla packs block states with.
+const MIN_BLOCK_BITS: u32 = 4;
+
+/// The vanilla dimensions with the directory vanilla stores them in, relative to the
+/// world directory.
+const DIMENSIONS: [(DimensionTypeRef, &str); 3] =
+    [(OVERWORLD, ""), (THE_NETHER, "DIM-1"), (THE_END, "DIM1")];
+
+/// What [`import_world`] or [`export_world`] converted.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct ConversionSummary {
+    /// Chunks written in the new format.
+    pub converted: usize,
+    /// Chunks left out because they were not fully generated or could not be read.
+    pub skipped: usize,
+}
+
+/// Imports a vanilla world into a Steel world directory.
+///
+/// `source` is the vanilla world directory, the one containing `level.dat`. Every
+/// dimension is written to `target/<dimension>`, where the server looks for it. The seed,
+/// time, weather and spawn point are taken from `level.dat`.
+///
+/// # Errors
+/// Returns an error if a dimension already has Steel region files, so an existing world is
+/// never overwritten, or if reading or writing fails. Single unreadable chunks are skipped.
+pub async fn import_world(source: &Path, target: &Path) -> io::Result<ConversionSummary> {
+    for (dimension, _) in DIMENSIONS {
+        let dir = target.join(dimension.key.path.as_ref());
+        if has_files_with_extension(&dir, "srg").await? {
+            return Err(io::Error::new(
+                io::ErrorKind::AlreadyExists,
+                format!("{} already contains a world", dir.display()),
+            ));
+        }
+    }
+
+    let mut summary = ConversionSummary::default();
+    for (dimension, vanilla_dir) in DIMENSIONS {
+        let region_dir = source.join(vanilla_dir).join("region");
+        if !fs::try_exists(&region_dir).await? {
+            continue;
+        }
+        let manager = RegionManager::new(target.join(dimension.key.path.as_ref()));
+        for (region_pos, path) in region_files(&region_dir, "mca").await? {
+            import_region(&path, region_pos, dimension, &manager, &mut summary).await?;
+        }
+        manager.close_all().await?;
+        log::info!("Imported {}", dimension.key);
+    }
+
+    import_level_data(source, target).await?;
+    Ok(summary)
+}
+
+/// Converts every chunk of one region file.
+async fn import_region(
+    path: &Path,
+    region_pos: RegionPos,
+    dimension: DimensionTypeRef,
+    manager: &RegionManager,
+    summary: &mut ConversionSummary,
+) -> io::Result<()> {
+    let chunks = read_region(path, region_pos).await?;
+    let Some(first) = chunks.first() else {
+        return Ok(());
+    };
+
+    // Keep the region open so its header is only written once
+    let first_pos = first.pos;
+    manager.acquire_chunk(first_pos).await?;
+    for chunk in chunks {
+        let converted = read_named_compound(&chunk.nbt).ok().and_then(|nbt| {
+            import_chunk(
+                &(&nbt).into(),
+                dimension.min_y,
+                dimension.height,
+                chunk.timestamp,
+            )
+        });
+        let Some(persistent) = converted else {
+            summary.skipped += 1;
+            continue;
+        };
+        manager
+            .save_chunk_data(
+                PreparedChunkSave::from_persistent(chunk.pos, persistent),
+                ChunkStatus::Full,
+            )
+            .await?;
+        summary.converted += 1;
+    }
+    manager.release_chunk(first_pos).await
+}
+
+/// Returns whether a directory contains a file with the given extension.
+async fn has_files_with_extension(dir: &Path, extension: &str) -> io::Result<bool> {
+    let mut entries = match fs::read_dir(dir).await {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(false),
+        Err(e) => return Err(e),
+    };
+    while let Some(entry) = entries.next_entry().await? {
+        if entry.path().extension().is_some_and(|ext| ext == extension) {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+/// Lists the `r.<x>.<z>.<extension>` files of a region directory.
+async fn region_files(dir: &Path, extension: &str) -> io::Result<Vec<(RegionPos, PathBuf)>> {
+    let mut files = Vec::new();
+    let mut entries = fs::read_dir(dir).await?;
+    while let Some(entry) = entries.next_entry().await? {
+        let name = entry.file_name();
+        let Some(coords) = name
+            .to_str()
+            .and_then(|name| name.strip_prefix("r."))
+            .and_then(|name| name.strip_suffix(extension))
+            .and_then(|name| name.strip_suffix('.'))
+        else {
+            continue;
+        };
+        if let Some((x, z)) = coords.split_once('.')
+            && let (Ok(x), Ok(z)) = (x.parse(), z.parse())
+        {
+            files.push((RegionPos::new(x, z), entry.path()));
+        }
+    }
+    files.sort_by_key(|(pos, _)| (pos.x, pos.z));
+    Ok(files)
+}
+
+/// The uncompressed NBT of a chunk read from a region file.
+struct AnvilChunk {
+    pos: ChunkPos,
+    /// Unix timestamp of the last time vanilla saved the chunk.
+    timestamp: u32,
+    nbt: Vec<u8>,
+}
+
+/// Reads and decompresses every chunk of a region file.
+///
+/// Chunks that point outside the file or fail to decompress are logged and left out.
+async fn read_region(path: &Path, region_pos: RegionPos) -> io::Result<Vec<AnvilChunk>> {
+    let file = fs::read(path).await?;
+    if file.len() < ANVIL_HEADER_SIZE {
+        // Vanilla leaves empty region files behind when it never wrote a chunk
+        return Ok(Vec::new());
+    }
+
+    let mut chunks = Vec::new();
+    for index in 0..CHUNKS_PER_REGION {
+        let location = u32::from_be_bytes(four_bytes(&file, index * 4));
+        if location == 0 {
+            continue;
+        }
+        let pos = chunk_pos(region_pos, index);
+        let timestamp = u32::from_be_bytes(four_bytes(&file, SECTOR_SIZE + index * 4));
+
+        let offset = (location >> 8) as usize * SECTOR_SIZE;
+        match read_chunk(&file, offset, path, pos).await {
+            Ok(nbt) => chunks.push(AnvilChunk {
+                pos,
+                timestamp,
+                nbt,
+            }),
+            Err(e) => log::warn!("Skipping chunk {pos:?} in {}: {e}", path.display()),
+        }
+    }
+    Ok(chunks)
+}
+
+/// Returns the position of the chunk at an index of a region's tables.
+fn chunk_pos(region_pos: RegionPos, index: usize) -> ChunkPos {
+    let (local_x, local_z) = RegionHeader::index_to_local(index);
+    ChunkPos::new(
+        region_pos.x * 32 + local_x as i32,
+        region_pos.z * 32 + local_z as i32,
+    )
+}
+
+/// Copies four bytes starting at an offset the caller checked.
+fn four_bytes(data: &[u8], offset: usize) -> [u8; 4] {
+    [
+        data[offset],
+        data[offset + 1],
+        data[offset + 2],
+        data[offset + 3],
+    ]
+}
+
+/// Reads and decompresses the chunk stored at a byte offset.
+async fn read_chunk(file: &[u8], offset: usize, path: &Path, pos: ChunkPos) -> io::Result<Vec<u8>> {
+    let invalid = |message: &str| io::Error::new(io::ErrorKind::InvalidData, message.to_owned());
+    let header = file
+        .get(offset..offset + 5)
+        .ok_or_else(|| invalid("chunk starts past the end of the file"))?;
+    let length = u32::from_be_bytes([header[0], header[1], header[2], header[3]]) as usize;
+    let compression = header[4];
+
+    if compression & EXTERNAL_FLAG != 0 {
+        let external = path.with_file_name(format!("c.{}.{}.mcc", pos.0.x, pos.0.y));
+        let data = fs::read(&external).await?;
+        return decompress(compression & !EXTERNAL_FLAG, &data);
+    }
+    let data = length
+        .checked_sub(1)
+        .and_then(|length| file.get(offset + 5..offset + 5 + length))
+        .ok_or_else(|| invalid("chunk ends past the end of the file"))?;
+    decompress(compression, data)
+}
+
+/// Decompresses chunk data with the given compression type.
+fn decompress(compression: u8, data: &[u8]) -> io::Result<Vec<u8>> {
+    let mut decompressed = Vec::new();
+    match compression {
+        COMPRESSION_GZIP => {
+            GzDecoder::new(data).read_to_end(&mut decompressed)?;
+        }
+        COMPRESSION_ZLIB => {
+            ZlibDecoder::new(data).read_to_end(&mut decompressed)?;
+        }
+        COMPRESSION_NONE => decompressed.extend_from_slice(data),
+        COMPRESSION_LZ4 => decompressed = decompress_lz4_blocks(data)?,
+        _ => {
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                format!("unsupported chunk compression {compression}"),
+            ));
+        }
+    }
+    Ok(decompressed)
+}
+
+/// Decompresses the block stream vanilla writes with `LZ4BlockOutputStream`.
+///
+/// Every block is the magic, a method byte, the compressed and original lengths and a
+/// checksum, all little-endian, followed by the data. An empty block ends the stream.
+fn decompress_lz4_blocks(mut data: &[u8]) -> io::Result<Vec<u8>> {
+    let invalid = |message: &str| io::Error::new(io::ErrorKind::InvalidData, message.to_owned());
+    let mut decompressed = Vec::new();
+    while !data.is_empty() {
+        if data.len() < LZ4_HEADER_SIZE || &data[..8] != LZ4_MAGIC {
+            return Err(invalid("invalid LZ4 block header"));
+        }
+        let method = data[8] & 0xF0;
+        let compressed_len = u32::from_le_bytes(four_bytes(data, 9)) as usize;
+        let original_len = u32::from_le_bytes(four_bytes(data, 13)) as usize;
+        let block = data
+            .get(LZ4_HEADER_SIZE..LZ4_HEADER_SIZE + compressed_len)
+            .ok_or_else(|| invalid("truncated LZ4 block"))?;
+        if original_len == 0 {
+            break;
+        }
+
+        match method {
+            LZ4_METHOD_RAW => decompressed.extend_from_slice(block),
+            LZ4_METHOD_LZ4 => {
+                let block = lz4_flex::block::decompress(block, original_len)
+                    .map_err(|e| invalid(&e.to_string()))?;
+                decompressed.extend_from_slice(&block);
+            }
+            _ => return Err(invalid("unknown LZ4 block method")),
+        }
+        data = &data[LZ4_HEADER_SIZE + compressed_len..];
+    }
+    Ok(decompressed)
+}
+
+/// Writes a compound the way NBT files and region files store it, with an empty root name.
+fn named_bytes(nbt: &NbtCompound) -> Vec<u8> {
+    let mut bytes = vec![10, 0, 0];
+    nbt.write(&mut bytes);
+    bytes
+}
+
+/// Reads an NBT file's root compound, skipping its type and name.
+fn read_named_compound(data: &[u8]) -> io::Result<BorrowedNbtCompound<'_>> {
+    let invalid = |message: &str| io::Error::new(io::ErrorKind::InvalidData, message.to_owned());
+    let [10, high, low, ..] = *data else {
+        return Err(invalid("root tag is not a compound"));
+    };
+    let name_len = usize::from(u16::from_be_bytes([high, low]));
+    let payload = data
+        .get(3 + name_len..)
+        .ok_or_else(|| invalid("truncated root tag"))?;
+    read_borrowed_compound(&mut Cursor::new(payload)).map_err(|e| invalid(&format!("{e:?}")))
+}
+
+/// Converts a vanilla chunk compound, returning `None` for chunks that are not fully
+/// generated.
+///
+/// Sections outside the dimension's height are dropped, missing ones are filled with air.
+fn import_chunk(
+    nbt: &NbtCompoundView<'_, '_>,
+    min_y: i32,
+    height: i32,
+    last_modified: u32,
+) -> Option<PersistentChunk> {
+    let status = nbt.string("Status")?.to_str();
+    if status.strip_prefix("minecraft:").unwrap_or(&status) != "full" {
+        return None;
+    }
+    let chunk_x = nbt.int("xPos")?;
+    let chunk_z = nbt.int("zPos")?;
+
+    let mut chunk = PersistentChunk {
+        last_modified,
+        block_states: Vec::new(),
+        biomes: Vec::new(),
+        sections: Vec::new(),
+        block_entities: Vec::new(),
+        entities: Vec::new(),
+        block_ticks: Vec::new(),
+        fluid_ticks: Vec::new(),
+    };
+
+    let vanilla_sections: Vec<_> = nbt
+        .list("sections")
+        .and_then(|list| list.compounds())
+        .map(|sections| sections.into_iter().collect())
+        .unwrap_or_default();
+    let min_section = min_y >> 4;
+    for section_y in min_section..min_section + (height >> 4) {
+        let vanilla = vanilla_sections
+            .iter()
+            .find(|section| section.byte("Y").map(i32::from) == Some(section_y));
+        let section = import_section(vanilla, &mut chunk);
+        chunk.sections.push(section);
+    }
+
+    if let Some(block_entities) = nbt.list("block_entities").and_then(|list| list.compounds()) {
+        chunk.block_entities = block_entities
+            .into_iter()
+            .filter_map(|entity| import_block_entity(&entity))
+            .collect();
+    }
+    chunk.block_ticks = import_ticks(nbt, "block_ticks", chunk_x, chunk_z);
+    chunk.fluid_ticks = import_ticks(nbt, "fluid_ticks", chunk_x, chunk_z);
+    Some(chunk)
+}
+
+/// Adds a value to a chunk-wide palette, returning its index.
+fn palette_index<T: PartialEq>(palette: &mut Vec<T>, value: T) -> u16 {
+    if let Some(index) = palette.iter().position(|entry| *entry == value) {
+        return index as u16;
+    }
+    palette.push(value);
+    (palette.len() - 1) as u16
+}
+
+/// Returns how many bits vanilla packs each entry of a palette with.
+fn vanilla_bits(palette_len: usize, min_bits: u32) -> u32 {
+    if palette_len <= 1 {
+        return 0;
+    }
+    (usize::BITS - (palette_len - 1).leading_zeros()).max(min_bits)
+}
+
+/// Unpacks vanilla's packed longs, where entries never span two longs.
+///
+/// Returns `None` if the array has the wrong length for the entry count.
+fn unpack_vanilla(data: &[i64], bits: u32, count: usize) -> Option<Vec<u32>> {
+    let per_long = (64 / bits) as usize;
+    if data.len() != count.div_ceil(per_long) {
+        return None;
+    }
+    let mask = (1u64 << bits) - 1;
+    Some(
+        (0..count)
+            .map(|i| {
+                let long = data[i / per_long] as u64;
+                ((long >> ((i % per_long) as u32 * bits)) & mask) as u32
+            })
+            .collect(),
+    )
+}
+
+/// Packs indices the way vanilla's `SimpleBitStorage` does, the reverse of
+/// [`unpack_vanilla`].
+fn pack_vanilla(indices: &[u32], bits: u32) -> Vec<i64> {
+    let per_long = (64 / bits) as usize;
+    indices
+        .chunks(per_long)
+        .map(|chunk| {
+            chunk.iter().enumerate().fold(0u64, |long, (i, &index)| {
+                long | (u64::from(index) << (i as u32 * bits))
+            }) as i64
+        })
+        .collect()
+}
+
+/// Maps a section's packed indices onto a section-local palette of chunk palette indices.
+///
+/// Returns the deduplicated section palette and the index of every entry into it.
+fn remap_palette(
+    vanilla_palette: &[u16],
+    data: Option<Vec<i64>>,
+    min_bits: u32,
+    count: usize,
+) -> (Vec<u16>, Vec<u32>) {
+    let bits = vanilla_bits(vanilla_palette.len(), min_bits);
+    let indices = data
+        .filter(|_| bits > 0)
+        .and_then(|data| unpack_vanilla(&data, bits, count))
+        .unwrap_or_else(|| vec![0; count]);
+
+    let mut palette = Vec::new();
+    let mut local = Vec::with_capacity(vanilla_palette.len());
+    for &entry in vanilla_palette {
+        local.push(u32::from(palette_index(&mut palette, entry)));
+    }
+    let indices = indices
+        .into_iter()
+        .map(|index| local.get(index as usize).copied().unwrap_or(0))
+        .collect();
+    (palette, indices)
+}
+
+/// Converts one vanilla section, or creates an air section if it is missing.
+fn import_section(
+    section: Option<&NbtCompoundView<'_, '_>>,
+    chunk: &mut PersistentChunk,
+) -> PersistentSection {
+    let block_states = section.and_then(|section| section.compound("block_states"));
+    let mut vanilla_palette: Vec<u16> = block_states
+        .as_ref()
+        .and_then(|states| states.list("palette"))
+        .and_then(|list| list.compounds())
+        .map(|palette| {
+            palette
+                .into_iter()
+                .map(|state| palette_index(&mut chunk.block_states, import_block_state(&state)))
+                .collect()
+        })
+        .unwrap_or_default();
+    if vanilla_palette.is_empty() {
+        let air = PersistentBlockState {
+            name: Identifier::vanilla_static("air"),
+            properties: Vec::new(),
+        };
+        vanilla_palette.push(palette_index(&mut chunk.block_states, air));
+    }
+    let data = block_states
+        .as_ref()
+        .and_then(|states| states.long_array("data"))
+        .map(|data| data.to_vec());
+    let (palette, indices) =
+        remap_palette(&vanilla_palette, data, MIN_BLOCK_BITS, BLOCKS_PER_SECTION);
+
+    let biomes = import_biomes(
+        section.and_then(|section| section.compound("biomes")),
+        chunk,
+    );
+    match bits_for_palette_len(palette.len()) {
+        None => PersistentSection::Homogeneous {
+            block_state: palette[0],
+            biomes,
+        },
+        Some(bits) => PersistentSection::Heterogeneous {
+            block_data: pack_indices(&indices, bits),
+            palette,
+            bits_per_entry: bits,
+            biomes,
+        },
+    }
+}
+
+/// Converts a palette entry like `{Name: "minecraft:oak_stairs", Properties: {...}}`.
+fn import_block_state(state: &NbtCompoundView<'_, '_>) -> PersistentBlockState {
+    let name = state
+        .get("Name")
+        .and_then(Identifier::from_nbt_tag)
+        .unwrap_or_else(|| Identifier::vanilla_static("air"));
+    let properties = state
+        .compound("Properties")
+        .map(|properties| {
+            properties
+                .iter()
+                .filter_map(|(key, value)| {
+                    Some((
+                        key.to_str().into_owned(),
+                        value.string()?.to_str().into_owned(),
+                    ))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    PersistentBlockState { name, properties }
+}
+
+/// Converts a section's biomes, defaulting to plains if they are missing.
+fn import_biomes(
+    biomes: Option<NbtCompoundView<'_, '_>>,
+    chunk: &mut PersistentChunk,
+) -> PersistentBiomeData {
+    let mut vanilla_palette: Vec<u16> = biomes
+        .as_ref()
+        .and_then(|biomes| biomes.list("palette"))
+        .and_then(|list| list.strings())
+        .map(|palette| {
+            palette
+                .iter()
+                .filter_map(|biome| biome.to_str().parse::<Identifier>().ok())
+                .map(|biome| palette_index(&mut chunk.biomes, biome))
+                .collect()
+        })
+        .unwrap_or_default();
+    if vanilla_palette.is_empty() {
+        vanilla_palette.push(palette_index(
+            &mut chunk.biomes,
+            Identifier::vanilla_static("plains"),
+        ));
+    }
+    let data = biomes
+        .as_ref()
+        .and_then(|biomes| biomes.long_array("data"))
+        .map(|data| data.to_vec());
+    let (palette, indices) = remap_palette(&vanilla_palette, data, 1, BIOMES_PER_SECTION);
+
+    match bits_for_palette_len(palette.len()) {
+        None => PersistentBiomeData::Homogeneous { biome: palette[0] },
+        Some(bits) => PersistentBiomeData::Heterogeneous {
+            biome_data: pack_indices(&indices, bits),
+            palette,
+            bits_per_entry: bits,
+        },
+    }
+}
+
+/// Converts a block entity, keeping everything but its id and position as its data.
+fn import_block_entity(nbt: &NbtCompoundView<'_, '_>) -> Option<PersistentBlockEntity> {
+    let entity_type = nbt.get("id").and_then(Identifier::from_nbt_tag)?;
+    let (x, y, z) = (nbt.int("x")?, nbt.int("y")?, nbt.int("z")?);
+
+    let mut data: NbtCompound = nbt.to_owned();
+    for key in ["id", "x", "y", "z", "keepPacked"] {
+        data.remove(key);
+    }
+    let mut nbt_data = Vec::new();
+    data.write(&mut nbt_data);
+
+    Some(PersistentBlockEntity {
+        x: (x & 15) as u8,
+        y: y as i16,
+        z: (z & 15) as u8,
+        entity_type,
+        nbt_data,
+    })
+}
+
+/// Converts vanilla's scheduled ticks, stored as `{i, x, y, z, t, p}` with absolute
+/// positions.
+fn import_ticks(
+    nbt: &NbtCompoundView<'_, '_>,
+    key: &str,
+    chunk_x: i32,
+    chunk_z: i32,
+) -> Vec<PersistentScheduledTick> {
+    let Some(ticks) = nbt.list(key).and_then(|list| list.compounds()) else {
+        return Vec::new();
+    };
+    ticks
+        .into_iter()
+        .filter_map(|tick| {
+            Some(PersistentScheduledTick {
+                kind: tick.get("i").and_then(Identifier::from_nbt_tag)?,
+                x: (tick.int("x")? - chunk_x * 16) as u8,
+                y: tick.int("y")? as i16,
+                z: (tick.int("z")? - chunk_z * 16) as u8,
+                delay: tick.int("t")?,
+                priority: tick.int("p").unwrap_or(0) as i8,
+            })
+        })
+        .collect()
+}
+
+/// Copies the seed, time, weather, game rules, spawn point and superflat layers from
+/// `level.dat` into the `level.json` of every dimension.
+async fn import_level_data(source: &Path, target: &Path) -> io::Result<()> {
+    let path = source.join("level.dat");
+    let compressed = match fs::read(&path).await {
+        Ok(compressed) => compressed,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {
+            log::warn!("{} not found, keeping the configured seed", path.display());
+            return Ok(());
+        }
+        Err(e) => return Err(e),
+    };
+    let mut bytes = Vec::new();
+    GzDecoder::new(&compressed[..]).read_to_end(&mut bytes)?;
+    let root = read_named_compound(&bytes)?;
+    let root: NbtCompoundView<'_, '_> = (&root).into();
+    let Some(data) = root.compound("Data") else {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "level.dat has no Data compound",
+        ));
+    };
+
+    let seed = data
+        .compound("WorldGenSettings")
+        .and_then(|settings| settings.long("seed"))
+        .or_else(|| data.long("RandomSeed"))
+        .unwrap_or_else(rand::random);
+
+    for (dimension, _) in DIMENSIONS {
+        let mut manager =
+            LevelDataManager::new(target.join(dimension.key.path.as_ref()), seed, None).await?;
+        let level = manager.data_mut();
+        level.seed = seed;
+        import_level(&data, dimension, level);
+        manager.save_force().await?;
+    }
+    Ok(())
+}
+
+/// Copies what a dimension keeps from the `Data` compound of `level.dat`.
+fn import_level(
+    data: &NbtCompoundView<'_, '_>,
+    dimension: DimensionTypeRef,
+    level: &mut LevelData,
+) {
+    level.game_time = data.long("Time").unwrap_or(0);
+    level.day_time = data.long("DayTime").unwrap_or(0);
+    level.weather.raining = data.byte("raining").is_some_and(|b| b != 0);
+    level.weather.rain_time = data.int("rainTime").unwrap_or(0);
+    level.weather.thundering = data.byte("thundering").is_some_and(|b| b != 0);
+    level.weather.thunder_time = data.int("thunderTime").unwrap_or(0);
+    level.weather.clear_weather_time = data.int("clearWeatherTime").unwrap_or(0);
+    if let Some(game_rules) = data.compound("GameRules") {
+        import_game_rules(&game_rules, &mut level.game_rules_values);
+    }
+
+    if dimension.key == OVERWORLD.key {
+        if let Some((x, y, z, angle)) = read_spawn(data) {
+            level.spawn.x = x;
+            level.spawn.y = y;
+            level.spawn.z = z;
+            level.spawn.angle = angle;
+            level.initialized = true;
+        }
+        // Steel only generates superflat terrain in the overworld
+        level.flat_settings = import_flat_settings(data, dimension);
+    }
+}
+
+/// Game rules 1.21.11 renamed beyond switching to snake case, as `(old, new)`.
+const RENAMED_GAME_RULES: [(&str, &str); 27] = [
+    ("announceAdvancements", "show_advancement_messages"),
+    ("commandBlocksEnabled", "command_blocks_work"),
+    ("commandModificationBlockLimit", "max_block_modifications"),
+    ("disableElytraMovementCheck", "elytra_movement_check"),
+    ("disablePlayerMovementCheck", "player_movement_check"),
+    ("disableRaids", "raids"),
+    ("doDaylightCycle", "advance_time"),
+    ("doEntityDrops", "entity_drops"),
+    ("doFireTick", "fire_spread_radius_around_player"),
+    ("doImmediateRespawn", "immediate_respawn"),
+    ("doInsomnia", "spawn_phantoms"),
+    ("doLimitedCrafting", "limited_crafting"),
+    ("doMobLoot", "mob_drops"),
+    ("doMobSpawning", "spawn_mobs"),
+    ("doPatrolSpawning", "spawn_patrols"),
+    ("doTileDrops", "block_drops"),
+    ("doTraderSpawning", "spawn_wandering_traders"),
+    ("doVinesSpread", "spread_vines"),
+    ("doWardenSpawning", "spawn_wardens"),
+    ("doWeatherCycle", "advance_weather"),
+    ("maxCommandChainLength", "max_command_sequence_length"),
+    ("maxCommandForkCount", "max_command_forks"),
+    ("minecartMaxSpeed", "max_minecart_speed"),
+    ("naturalRegeneration", "natural_health_regeneration"),
+    ("snowAccumulationHeight", "max_snow_accumulation_height"),
+    ("spawnRadius", "respawn_radius"),
+    ("spawnerBlocksEnabled", "spawner_blocks_work"),
+];
+
+/// Old game rules that mean the opposite of the rule they were renamed to.
+const INVERTED_GAME_RULES: [&str; 3] = [
+    "disableElytraMovementCheck",
+    "disablePlayerMovementCheck",
+    "disableRaids",
+];
+
+/// Reads the `GameRules` compound of `level.dat`.
+///
+/// Vanilla stores every value as a string, under camel case names before 1.21.11. Rules
+/// Steel doesn't know are skipped.
+fn import_game_rules(game_rules: &NbtCompoundView<'_, '_>, values: &mut GameRuleValues) {
+    for (name, value) in game_rules.iter() {
+        let name = name.to_str();
+        let name = name.strip_prefix("minecraft:").unwrap_or(&name);
+        let Some(rule) = game_rule_by_old_name(name) else {
+            continue;
+        };
+
+        let value = if let Some(value) = value.string() {
+            match value.to_str().as_ref() {
+                "true" => GameRuleValue::Bool(true),
+                "false" => GameRuleValue::Bool(false),
+                number => match number.parse() {
+                    Ok(number) => GameRuleValue::Int(number),
+                    Err(_) => continue,
+                },
+            }
+        } else if let Some(value) = value.byte() {
+            GameRuleValue::Bool(value != 0)
+        } else if let Some(value) = value.int() {
+            GameRuleValue::Int(value)
+        } else {
+            continue;
+        };
+
+        let value = match (value, rule.default_value) {
+            (GameRuleValue::Bool(value), GameRuleValue::Bool(_))
+                if INVERTED_GAME_RULES.contains(&name) =>
+            {
+                GameRuleValue::Bool(!value)
+            }
+            // `doFireTick` turned into a radius, which is zero when fire doesn't spread
+            (GameRuleValue::Bool(value), GameRuleValue::Int(default)) => {
+                GameRuleValue::Int(if value { default } else { 0 })
+            }
+            (value, _) => value,
+        };
+        if !values.set(rule, value, &REGISTRY.game_rules) {
+            log::warn!("Skipping invalid value {value:?} of game rule {name}");
+        }
+    }
+}
+
+/// Finds a game rule by its name in this version or a camel case name from before 1.21.11.
+fn game_rule_by_old_name(name: &str) -> Option<GameRuleRef> {
+    let name = match RENAMED_GAME_RULES.iter().find(|(old, _)| *old == name) {
+        Some((_, new)) => (*new).to_owned(),
+        None => name.chars().fold(String::new(), |mut snake, c| {
+            if c.is_ascii_uppercase() {
+                snake.push('_');
+            }
+            snake.push(c.to_ascii_lowercase());
+            snake
+        }),
+    };
+    REGISTRY.game_rules.by_key(&Identifier::vanilla(name))
+}
+
+/// Reads the layers of a dimension's generator if it is a superflat one.
+fn import_flat_settings(
+    data: &NbtCompoundView<'_, '_>,
+    dimension: DimensionTypeRef,
+) -> Option<FlatSettings> {
+    let generator = data
+        .compound("WorldGenSettings")?
+        .compound("dimensions")?
+        .compound(&dimension.key.to_string())?
+        .compound("generator")?;
+    if generator.string("type")?.to_str() != "minecraft:flat" {
+        return None;
+    }
+
+    let settings = generator.compound("settings")?;
+    let layers = settings
+        .list("layers")?
+        .compounds()?
+        .iter()
+        .map(|layer| {
+            Some(FlatLayer {
+                block: layer.get("block").and_then(Identifier::from_nbt_tag)?,
+                height: u32::try_from(layer.int("height")?).ok()?,
+            })
+        })
+        .collect::<Option<Vec<_>>>()?;
+    let biome = settings
+        .get("biome")
+        .and_then(Identifier::from_nbt_tag)
+        .unwrap_or_else(|| Identifier::vanilla_static("plains"));
+    Some(FlatSettings { layers, biome })
+}
+
+/// Reads the spawn point, stored in a `spawn` compound since 1.21.9 and in separate
+/// fields before.
+fn read_spawn(data: &NbtCompoundView<'_, '_>) -> Option<(i32, i32, i32, f32)> {
+    if let Some(spawn) = data.compound("spawn") {
+        let pos = spawn.int_array("pos")?.to_vec();
+        let [x, y, z] = pos[..] else {
+            return None;
+        };
+        return Some((x, y, z, spawn.float("yaw").unwrap_or(0.0)));
+    }
+    Some((
+        data.int("SpawnX")?,
+        data.int("SpawnY")?,
+        data.int("SpawnZ")?,
+        data.float("SpawnAngle").unwrap_or(0.0),
+    ))
+}
+
+/// Exports a Steel world directory as a vanilla world.
+///
+/// `source` is the directory the server keeps its dimensions in and `target` becomes the
+/// vanilla world directory. `level.dat` is created from the overworld's level data. Light
+/// and heightmaps are not written, vanilla computes them again when it loads the chunks.
+///
+/// # Errors
+/// Returns an error if `target` already contains a vanilla world, or if reading or writing
+/// fails. Single unreadable chunks are skipped.
+pub async fn export_world(source: &Path, target: &Path) -> io::Result<ConversionSummary> {
+    if fs::try_exists(target.join("level.dat")).await? {
+        return Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            format!("{} already contains a world", target.display()),
+        ));
+    }
+
+    let mut summary = ConversionSummary::default();
+    for (dimension, vanilla_dir) in DIMENSIONS {
+        let dir = source.join(dimension.key.path.as_ref());
+        if !fs::try_exists(&dir).await? {
+            continue;
+        }
+        let vanilla_dir = target.join(vanilla_dir);
+        for (region_pos, path) in region_files(&dir, "srg").await? {
+            export_region(&path, region_pos, dimension, &vanilla_dir, &mut summary).await?;
+        }
+        log::info!("Exported {}", dimension.key);
+    }
+
+    export_level_data(source, target).await?;
+    Ok(summary)
+}
+
+/// Converts every chunk of one Steel region file into the region and entity files of a
+/// vanilla dimension directory.
+async fn export_region(
+    path: &Path,
+    region_pos: RegionPos,
+    dimension: DimensionTypeRef,
+    vanilla_dir: &Path,
+    summary: &mut ConversionSummary,
+) -> io::Result<()> {
+    let mut chunks = Vec::new();
+    let mut entities = Vec::new();
+    for (pos, status, chunk) in read_steel_region(path, region_pos).await? {
+        if status != ChunkStatus::Full {
+            summary.skipped += 1;
+            continue;
+        }
+        if !chunk.entities.is_empty() {
+            entities.push(AnvilChunk {
+                pos,
+                timestamp: chunk.last_modified,
+                nbt: named_bytes(&export_entities(&chunk, pos)),
+            });
+        }
+        chunks.push(AnvilChunk {
+            pos,
+            timestamp: chunk.last_modified,
+            nbt: named_bytes(&export_chunk(&chunk, pos, dimension.min_y)),
+        });
+        summary.converted += 1;
+    }
+
+    let file_name = format!("r.{}.{}.mca", region_pos.x, region_pos.z);
+    if !chunks.is_empty() {
+        write_region(&vanilla_dir.join("region").join(&file_name), &chunks).await?;
+    }
+    if !entities.is_empty() {
+        write_region(&vanilla_dir.join("entities").join(&file_name), &entities).await?;
+    }
+    Ok(())
+}
+
+/// Reads and decodes every chunk of a Steel region file along with its status.
+///
+/// Chunks that point outside the file or fail to decode are logged and left out.
+async fn read_steel_region(
+    path: &Path,
+    region_pos: RegionPos,
+) -> io::Result<Vec<(ChunkPos, ChunkStatus, PersistentChunk)>> {
+    let invalid = |message: String| io::Error::new(io::ErrorKind::InvalidData, message);
+    let file = fs::read(path).await?;
+    if file.len() < TOTAL_HEADER_SIZE || file[..4] != REGION_MAGIC {
+        return Err(invalid(format!(
+            "{} is not a Steel region file",
+            path.display()
+        )));
+    }
+    let version = u16::from_le_bytes([file[4], file[5]]);
+    if version > FORMAT_VERSION {
+        return Err(invalid(format!(
+            "Region file version {version} is newer than supported version {FORMAT_VERSION}"
+        )));
+    }
+
+    let header = RegionHeader::from_bytes(&file[FILE_HEADER_SIZE..TOTAL_HEADER_SIZE]);
+    let mut chunks = Vec::new();
+    for (index, entry) in header.entries.iter().enumerate() {
+        if !entry.exists() {
+            continue;
+        }
+        let pos = chunk_pos(region_pos, index);
+        let offset = entry.sector_offset as usize * SECTOR_SIZE;
+        let chunk = file
+            .get(offset..offset + entry.size_bytes as usize)
+            .ok_or_else(|| invalid("chunk ends past the end of the file".to_owned()))
+            .and_then(zstd::decode_all)
+            .and_then(|data| {
+                PersistentChunk::decode(&data, version).map_err(|e| invalid(e.to_string()))
+            });
+        match chunk {
+            Ok(chunk) => chunks.push((pos, entry.status, chunk)),
+            Err(e) => log::warn!("Skipping chunk {pos:?} in {}: {e}", path.display()),
+        }
+    }
+    Ok(chunks)
+}
+
+/// Writes chunks into a new Anvil region file, compressed with zlib like vanilla does.
+///
+/// Chunks too large for the location table are written to a `.mcc` file next to it.
+async fn write_region(path: &Path, chunks: &[AnvilChunk]) -> io::Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).await?;
+    }
+
+    let mut file = vec![0; ANVIL_HEADER_SIZE];
+    for chunk in chunks {
+        let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(&chunk.nbt)?;
+        let compressed = encoder.finish()?;
+
+        let mut data = Vec::with_capacity(compressed.len() + 5);
+        if compressed.len() + 5 > MAX_CHUNK_SECTORS * SECTOR_SIZE {
+            let external =
+                path.with_file_name(format!("c.{}.{}.mcc", chunk.pos.0.x, chunk.pos.0.y));
+            fs::write(&external, &compressed).await?;
+            data.extend_from_slice(&1u32.to_be_bytes());
+            data.push(COMPRESSION_ZLIB | EXTERNAL_FLAG);
+        } else {
+            data.extend_from_slice(&(compressed.len() as u32 + 1).to_be_bytes());
+            data.push(COMPRESSION_ZLIB);
+            data.extend_from_slice(&compressed);
+        }
+
+        let sector = file.len() / SECTOR_SIZE;
+        let sectors = data.len().div_ceil(SECTOR_SIZE);
+        file.extend_from_slice(&data);
+        file.resize((sector + sectors) * SECTOR_SIZE, 0);
+
+        let (local_x, local_z) = RegionPos::local_chunk_pos(chunk.pos.0.x, chunk.pos.0.y);
+        let offset = RegionHeader::chunk_index(local_x, local_z) * 4;
+        let location = ((sector as u32) << 8) | sectors as u32;
+        file[offset..offset + 4].copy_from_slice(&location.to_be_bytes());
+        file[SECTOR_SIZE + offset..SECTOR_SIZE + offset + 4]
+            .copy_from_slice(&chunk.timestamp.to_be_bytes());
+    }
+    fs::write(path, file).await
+}
+
+/// Creates a string tag.
+fn string(value: &str) -> NbtTag {
+    NbtTag::String(value.into())
+}
+
+/// Reads NBT stored as a compound's payload, returning an empty compound if it is invalid.
+fn owned_compound(data: &[u8]) -> NbtCompound {
+    let Ok(nbt) = read_borrowed_compound(&mut Cursor::new(data)) else {
+        return NbtCompound::new();
+    };
+    let view: NbtCompoundView<'_, '_> = (&nbt).into();
+    view.to_owned()
+}
+
+/// Converts a chunk into vanilla's chunk compound.
+///
+/// `isLightOn` is left false, so vanilla lights the chunk when it loads it.
+fn export_chunk(chunk: &PersistentChunk, pos: ChunkPos, min_y: i32) -> NbtCompound {
+    let min_section = min_y >> 4;
+    let sections = chunk
+        .sections
+        .iter()
+        .zip(min_section..)
+        .map(|(section, y)| export_section(section, y, chunk))
+        .collect();
+    let block_entities = chunk
+        .block_entities
+        .iter()
+        .map(|entity| export_block_entity(entity, pos))
+        .collect();
+
+    let mut nbt = NbtCompound::new();
+    nbt.insert("DataVersion", DATA_VERSION);
+    nbt.insert("xPos", pos.0.x);
+    nbt.insert("yPos", min_section);
+    nbt.insert("zPos", pos.0.y);
+    nbt.insert("Status", string("minecraft:full"));
+    nbt.insert("LastUpdate", 0i64);
+    nbt.insert("InhabitedTime", 0i64);
+    nbt.insert("isLightOn", 0i8);
+    nbt.insert("sections", NbtList::Compound(sections));
+    nbt.insert("block_entities", NbtList::Compound(block_entities));
+    nbt.insert("block_ticks", export_ticks(&chunk.block_ticks, pos));
+    nbt.insert("fluid_ticks", export_ticks(&chunk.fluid_ticks, pos));
+    nbt
+}
+
+/// Converts one section, resolving its section palettes against the chunk's palettes.
+fn export_section(section: &PersistentSection, y: i32, chunk: &PersistentChunk) -> NbtCompound {
+    let (palette, indices, biomes) = match section {
+        PersistentSection::Homogeneous {
+            block_state,
+            biomes,
+        } => (slice::from_ref(block_state), None, biomes),
+        PersistentSection::Heterogeneous {
+            palette,
+            bits_per_entry,
+            block_data,
+            biomes,
+        } => (
+            &palette[..],
+            Some(unpack_indices(
+                block_data,
+                *bits_per_entry,
+                BLOCKS_PER_SECTION,
+            )),
+            biomes,
+        ),
+    };
+    let air = PersistentBlockState {
+        name: Identifier::vanilla_static("air"),
+        properties: Vec::new(),
+    };
+    let states = palette
+        .iter()
+        .map(|&index| {
+            export_block_state(chunk.block_states.get(usize::from(index)).unwrap_or(&air))
+        })
+        .collect();
+    let block_states = export_container(
+        NbtList::Compound(states),
+        palette.len(),
+        indices,
+        MIN_BLOCK_BITS,
+    );
+
+    let (palette, indices) = match biomes {
+        PersistentBiomeData::Homogeneous { biome } => (slice::from_ref(biome), None),
+        PersistentBiomeData::Heterogeneous {
+            palette,
+            bits_per_entry,
+            biome_data,
+        } => (
+            &palette[..],
+            Some(unpack_indices(
+                biome_data,
+                *bits_per_entry,
+                BIOMES_PER_SECTION,
+            )),
+        ),
+    };
+    let plains = Identifier::vanilla_static("plains");
+    let names = palette
+        .iter()
+        .map(|&index| {
+            let biome = chunk.biomes.get(usize::from(index)).unwrap_or(&plains);
+            biome.to_string().as_str().into()
+        })
+        .collect();
+    let biomes = export_container(NbtList::String(names), palette.len(), indices, 1);
+
+    let mut nbt = NbtCompound::new();
+    nbt.insert("Y", y as i8);
+    nbt.insert("block_states", block_states);
+    nbt.insert("biomes", biomes);
+    nbt
+}
+
+/// Builds a paletted container the way vanilla stores it, `{palette: [...], data: [L; ...]}`.
+///
+/// Containers with a single entry have no data.
+fn export_container(
+    palette: NbtList,
+    palette_len: usize,
+    indices: Option<Vec<u32>>,
+    min_bits: u32,
+) -> NbtCompound {
+    let mut container = NbtCompound::new();
+    container.insert("palette", palette);
+    let bits = vanilla_bits(palette_len, min_bits);
+    if bits > 0
+        && let Some(indices) = indices
+    {
+        container.insert("data", NbtTag::LongArray(pack_vanilla(&indices, bits)));
+    }
+    container
+}
+
+/// Converts a block state into a palette entry like `{Name: "minecraft:oak_stairs", ...}`.
+fn export_block_state(state: &PersistentBlockState) -> NbtCompound {
+    let mut nbt = NbtCompound::new();
+    nbt.insert("Name", string(&state.name.to_string()));
+    if !state.properties.is_empty() {
+        let mut properties = NbtCompound::new();
+        for (key, value) in &state.properties {
+            properties.insert(key.as_str(), string(value));
+        }
+        nbt.insert("Properties", properties);
+    }
+    nbt
+}
+
+/// Converts a block entity back to vanilla's layout, with its id and absolute position.
+fn export_block_entity(entity: &PersistentBlockEntity, pos: ChunkPos) -> NbtCompound {
+    let mut nbt = owned_compound(&entity.nbt_data);
+    nbt.insert("id", string(&entity.entity_type.to_string()));
+    nbt.insert("x", pos.0.x * 16 + i32::from(entity.x));
+    nbt.insert("y", i32::from(entity.y));
+    nbt.insert("z", pos.0.y * 16 + i32::from(entity.z));
+    nbt.insert("keepPacked", 0i8);
+    nbt
+}
+
+/// Converts scheduled ticks to vanilla's `{i, x, y, z, t, p}` with absolute positions.
+fn export_ticks(ticks: &[PersistentScheduledTick], pos: ChunkPos) -> NbtList {
+    let ticks = ticks
+        .iter()
+        .map(|tick| {
+            let mut nbt = NbtCompound::new();
+            nbt.insert("i", string(&tick.kind.to_string()));
+            nbt.insert("x", pos.0.x * 16 + i32::from(tick.x));
+            nbt.insert("y", i32::from(tick.y));
+            nbt.insert("z", pos.0.y * 16 + i32::from(tick.z));
+            nbt.insert("t", tick.delay);
+            nbt.insert("p", i32::from(tick.priority));
+            nbt
+        })
+        .collect();
+    NbtList::Compound(ticks)
+}
+
+/// Builds the compound vanilla stores a chunk's entities in, inside the `entities` region
+/// files.
+fn export_entities(chunk: &PersistentChunk, pos: ChunkPos) -> NbtCompound {
+    let entities = chunk
+        .entities
+        .iter()
+        .map(|entity| {
+            let mut nbt = owned_compound(&entity.nbt_data);
+            nbt.insert("id", string(&entity.entity_type.to_string()));
+            nbt
+        })
+        .collect();
+
+    let mut nbt = NbtCompound::new();
+    nbt.insert("DataVersion", DATA_VERSION);
+    nbt.insert("Position", NbtTag::IntArray(vec![pos.0.x, pos.0.y]));
+    nbt.insert("Entities", NbtList::Compound(entities));
+    nbt
+}
+
+/// Writes `level.dat` from the overworld's level data.
+async fn export_level_data(source: &Path, target: &Path) -> io::Result<()> {
+    let dir = source.join(OVERWORLD.key.path.as_ref());
+    if !fs::try_exists(dir.join("level.json")).await? {
+        log::warn!("{} has no level.json, using a random seed", dir.display());
+    }
+    let manager = LevelDataManager::new(&dir, rand::random(), None).await?;
+
+    let name = target
+        .file_name()
+        .map_or_else(|| "world".into(), |name| name.to_string_lossy());
+    let last_played = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_millis() as i64);
+    let mut root = NbtCompound::new();
+    root.insert("Data", level_dat(manager.data(), &name, last_played));
+
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(&named_bytes(&root))?;
+    fs::create_dir_all(target).await?;
+    fs::write(target.join("level.dat"), encoder.finish()?).await
+}
+
+/// Builds the `Data` compound of `level.dat`.
+fn level_dat(level: &LevelData, name: &str, last_played: i64) -> NbtCompound {
+    let mut version = NbtCompound::new();
+    version.insert("Id", DATA_VERSION);
+    version.insert("Name", string(VERSION_NAME));
+    version.insert("Series", string("main"));
+    version.insert("Snapshot", 0i8);
+
+    let mut spawn = NbtCompound::new();
+    spawn.insert("dimension", string("minecraft:overworld"));
+    spawn.insert(
+        "pos",
+        NbtTag::IntArray(vec![level.spawn.x, level.spawn.y, level.spawn.z]),
+    );
+    spawn.insert("yaw", level.spawn.angle);
+    spawn.insert("pitch", 0.0f32);
+
+    let mut data_packs = NbtCompound::new();
+    data_packs.insert("Enabled", NbtList::String(vec!["vanilla".into()]));
+    data_packs.insert("Disabled", NbtList::String(Vec::new()));
+
+    let mut data = NbtCompound::new();
+    data.insert("DataVersion", DATA_VERSION);
+    data.insert("version", ANVIL_VERSION);
+    data.insert("Version", version);
+    data.insert("LevelName", string(name));
+    data.insert("LastPlayed", last_played);
+    data.insert("initialized", 1i8);
+    data.insert("Time", level.game_time);
+    data.insert("DayTime", level.day_time);
+    data.insert("raining", i8::from(level.weather.raining));
+    data.insert("rainTime", level.weather.rain_time);
+    data.insert("thundering", i8::from(level.weather.thundering));
+    data.insert("thunderTime", level.weather.thunder_time);
+    data.insert("clearWeatherTime", level.weather.clear_weather_time);
+    data.insert("spawn", spawn);
+    data.insert("GameRules", game_rules(&level.game_rules_values));
+    data.insert("DataPacks", data_packs);
+    data.insert("WorldGenSettings", world_gen_settings(level));
+    data
+}
+
+/// Writes every game rule under its key, with booleans stored as bytes like vanilla.
+fn game_rules(values: &GameRuleValues) -> NbtCompound {
+    let mut game_rules = NbtCompound::new();
+    for (_, rule) in REGISTRY.game_rules.iter() {
+        let key = rule.key.to_string();
+        match values.get(rule, &REGISTRY.game_rules) {
+            GameRuleValue::Bool(value) => game_rules.insert(key.as_str(), i8::from(value)),
+            GameRuleValue::Int(value) => game_rules.insert(key.as_str(), value),
+        }
+    }
+    game_rules
+}
+
+/// Describes the dimensions like vanilla's `WorldGenSettings`, so vanilla generates new
+/// chunks the way Steel would.
+fn world_gen_settings(level: &LevelData) -> NbtCompound {
+    let overworld = match &level.flat_settings {
+        Some(flat) => flat_generator(flat),
+        None => noise_generator("minecraft:overworld", multi_noise("minecraft:overworld")),
+    };
+    let nether = noise_generator("minecraft:nether", multi_noise("minecraft:nether"));
+    let mut end_biomes = NbtCompound::new();
+    end_biomes.insert("type", string("minecraft:the_end"));
+    let end = noise_generator("minecraft:end", end_biomes);
+
+    let mut dimensions = NbtCompound::new();
+    for (key, generator) in [
+        ("minecraft:overworld", overworld),
+        ("minecraft:the_nether", nether),
+        ("minecraft:the_end", end),
+    ] {
+        let mut dimension = NbtCompound::new();
+        dimension.insert("type", string(key));
+        dimension.insert("generator", generator);
+        dimensions.insert(key, dimension);
+    }
+
+    let mut settings = NbtCompound::new();
+    settings.insert("seed", level.seed);
+    settings.insert("generate_features", 1i8);
+    settings.insert("bonus_chest", 0i8);
+    settings.insert("dimensions", dimensions);
+    settings
+}
+
+/// A noise based generator with the given noise settings and biome source.
+fn noise_generator(settings: &str, biome_source: NbtCompound) -> NbtCompound {
+    let mut generator = NbtCompound::new();
+    generator.insert("type", string("minecraft:noise"));
+    generator.insert("settings", string(settings));
+    generator.insert("biome_source", biome_source);
+    generator
+}
+
+/// A biome source using one of vanilla's multi noise presets.
+fn multi_noise(preset: &str) -> NbtCompound {
+    let mut biome_source = NbtCompound::new();
+    biome_source.insert("type", string("minecraft:multi_noise"));
+    biome_source.insert("preset", string(preset));
+    biome_source
+}
+
+/// A superflat generator with the world's layers and biome.
+fn flat_generator(flat: &FlatSettings) -> NbtCompound {
+    let layers = flat
+        .layers
+        .iter()
+        .map(|layer| {
+            let mut nbt = NbtCompound::new();
+            nbt.insert("block", string(&layer.block.to_string()));
+            nbt.insert("height", layer.height as i32);
+            nbt
+        })
+        .collect();
+    let mut settings = NbtCompound::new();
+    settings.insert("layers", NbtList::Compound(layers));
+    settings.insert("biome", string(&flat.biome.to_string()));
+
+    let mut generator = NbtCompound::new();
+    generator.insert("type", string("minecraft:flat"));
+    generator.insert("settings", settings);
+    generator
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, process};
+
+    use steel_registry::RegistryExt;
+    use steel_registry::vanilla_game_rules::{
+        ADVANCE_TIME, FIRE_SPREAD_RADIUS_AROUND_PLAYER, RAIDS, RANDOM_TICK_SPEED,
+    };
+
+    use super::*;
+    use crate::test_utils::init_test_registry;
+
+    fn block_state(name: &str, properties: &[(&str, &str)]) -> NbtCompound {
+        let mut state = NbtCompound::new();
+        state.insert("Name", string(name));
+        if !properties.is_empty() {
+            let mut compound = NbtCompound::new();
+            for (key, value) in properties {
+                compound.insert(*key, string(value));
+            }
+            state.insert("Properties", compound);
+        }
+        state
+    }
+
+    /// A full chunk at (-1, 2) with a mixed section at y = 0 and a chest in it.
+    fn vanilla_chunk() -> NbtCompound {
+        let indices: Vec<u32> = (0..BLOCKS_PER_SECTION as u32).map(|i| i % 3).collect();
+        let mut block_states = NbtCompound::new();
+        block_states.insert(
+            "palette",
+            NbtList::Compound(vec![
+                block_state("minecraft:air", &[]),
+                block_state("minecraft:stone", &[]),
+                block_state(
+                    "minecraft:oak_stairs",
+                    &[("facing", "east"), ("half", "top")],
+                ),
+            ]),
+        );
+        block_states.insert("data", NbtTag::LongArray(pack_vanilla(&indices, 4)));
+        let mut biomes = NbtCompound::new();
+        biomes.insert("palette", NbtList::String(vec!["minecraft:desert".into()]));
+        let mut section = NbtCompound::new();
+        section.insert("Y", 0i8);
+        section.insert("block_states", block_states);
+        section.insert("biomes", biomes);
+
+        let mut chest = NbtCompound::new();
+        chest.insert("id", string("minecraft:chest"));
+        chest.insert("x", -3);
+        chest.insert("y", 5);
+        chest.insert("z", 33);
+        chest.insert("CustomName", string("Loot"));
+
+        let mut tick = NbtCompound::new();
+        tick.insert("i", string("minecraft:water"));
+        tick.insert("x", -16);
+        tick.insert("y", 1);
+        tick.insert("z", 40);
+        tick.insert("t", 5);
+        tick.insert("p", 0);
+
+        let mut chunk = NbtCompound::new();
+        chunk.insert("Status", string("minecraft:full"));
+        chunk.insert("xPos", -1);
+        chunk.insert("zPos", 2);
+        chunk.insert("sections", NbtList::Compound(vec![section]));
+        chunk.insert("block_entities", NbtList::Compound(vec![chest]));
+        chunk.insert("fluid_ticks", NbtList::Compound(vec![tick]));
+        chunk
+    }
+
+    #[test]
+    fn imports_sections_block_entities_and_ticks() {
+        let bytes = named_bytes(&vanilla_chunk());
+        let nbt = read_named_compound(&bytes).expect("valid nbt");
+        let chunk = import_chunk(&(&nbt).into(), -64, 384, 7).expect("full chunk");
+
+        assert_eq!(chunk.last_modified, 7);
+        assert_eq!(chunk.sections.len(), 24);
+        let PersistentSection::Heterogeneous {
+            palette,
+            bits_per_entry,
+            block_data,
+            biomes,
+        } = &chunk.sections[4]
+        else {
+            panic!("section 0 should be mixed");
+        };
+        let indices = unpack_indices(block_data, *bits_per_entry, BLOCKS_PER_SECTION);
+        let stairs = &chunk.block_states[palette[indices[2] as usize] as usize];
+        assert_eq!(stairs.name, Identifier::vanilla_static("oak_stairs"));
+        assert!(
+            stairs
+                .properties
+                .contains(&("half".to_owned(), "top".to_owned()))
+        );
+        let PersistentBiomeData::Homogeneous { biome } = biomes else {
+            panic!("biomes should be homogeneous");
+        };
+        assert_eq!(
+            chunk.biomes[*biome as usize],
+            Identifier::vanilla_static("desert")
+        );
+        assert!(matches!(
+            chunk.sections[0],
+            PersistentSection::Homogeneous { .. }
+        ));
+
+        let chest = &chunk.block_entities[0];
+        assert_eq!((chest.x, chest.y, chest.z), (13, 5, 1));
+        assert_eq!(chest.entity_type, Identifier::vanilla_static("chest"));
+        let data = read_borrowed_compound(&mut Cursor::new(&chest.nbt_data[..])).expect("nbt");
+        let data: NbtCompoundView<'_, '_> = (&data).into();
+        assert!(data.get("id").is_none());
+        assert!(data.string("CustomName").is_some());
+
+        let tick = &chunk.fluid_ticks[0];
+        assert_eq!((tick.x, tick.y, tick.z, tick.delay), (0, 1, 8, 5));
+    }
+
+    #[test]
+    fn skips_unfinished_chunks() {
+        let mut chunk = vanilla_chunk();
+        chunk.insert("Status", string("minecraft:features"));
+        let bytes = named_bytes(&chunk);
+        let nbt = read_named_compound(&bytes).expect("valid nbt");
+        assert!(import_chunk(&(&nbt).into(), -64, 384, 0).is_none());
+    }
+
+    #[test]
+    fn decompresses_chunk_formats() {
+        let data = b"chunk data".to_vec();
+        assert_eq!(decompress(COMPRESSION_NONE, &data).expect("raw"), data);
+
+        // A raw LZ4 block followed by the empty block ending the stream
+        let mut lz4 = Vec::new();
+        for (method, block) in [(LZ4_METHOD_RAW, &data[..]), (LZ4_METHOD_RAW, &[][..])] {
+            lz4.extend_from_slice(LZ4_MAGIC);
+            lz4.push(method);
+            lz4.extend_from_slice(&(block.len() as u32).to_le_bytes());
+            lz4.extend_from_slice(&(block.len() as u32).to_le_bytes());
+            lz4.extend_from_slice(&0u32.to_le_bytes());
+            lz4.extend_from_slice(block);
+        }
+        assert_eq!(decompress(COMPRESSION_LZ4, &lz4).expect("lz4"), data);
+        assert!(decompress(127, &data).is_err());
+    }
+
+    #[test]
+    fn vanilla_bit_widths() {
+        assert_eq!(vanilla_bits(1, MIN_BLOCK_BITS), 0);
+        assert_eq!(vanilla_bits(3, MIN_BLOCK_BITS), 4);
+        assert_eq!(vanilla_bits(17, MIN_BLOCK_BITS), 5);
+        assert_eq!(vanilla_bits(3, 1), 2);
+
+        // 5 bits leave 4 bits of every long unused
+        let indices: Vec<u32> = (0..BLOCKS_PER_SECTION as u32).map(|i| i % 17).collect();
+        let packed = pack_vanilla(&indices, 5);
+        assert_eq!(packed.len(), BLOCKS_PER_SECTION.div_ceil(12));
+        assert_eq!(
+            unpack_vanilla(&packed, 5, BLOCKS_PER_SECTION),
+            Some(indices)
+        );
+        assert_eq!(unpack_vanilla(&packed[1..], 5, BLOCKS_PER_SECTION), None);
+    }
+
+    /// Resolves every block of a chunk, bottom section first.
+    fn blocks(chunk: &PersistentChunk) -> Vec<&PersistentBlockState> {
+        chunk
+            .sections
+            .iter()
+            .flat_map(|section| {
+                let (palette, indices) = match section {
+                    PersistentSection::Homogeneous { block_state, .. } => {
+                        (vec![*block_state], vec![0; BLOCKS_PER_SECTION])
+                    }
+                    PersistentSection::Heterogeneous {
+                        palette,
+                        bits_per_entry,
+                        block_data,
+                        ..
+                    } => (
+                        palette.clone(),
+                        unpack_indices(block_data, *bits_per_entry, BLOCKS_PER_SECTION),
+                    ),
+                };
+                indices
+                    .into_iter()
+                    .map(move |index| usize::from(palette[index as usize]))
+            })
+            .map(|index| &chunk.block_states[index])
+            .collect()
+    }
+
+    #[test]
+    fn exported_chunks_import_unchanged() {
+        let bytes = named_bytes(&vanilla_chunk());
+        let nbt = read_named_compound(&bytes).expect("valid nbt");
+        let mut chunk = import_chunk(&(&nbt).into(), -64, 384, 7).expect("full chunk");
+
+        // A section with more states than vanilla packs into 4 bits
+        let first = chunk.block_states.len() as u16;
+        chunk
+            .block_states
+            .extend((0..20).map(|note| PersistentBlockState {
+                name: Identifier::vanilla_static("note_block"),
+                properties: vec![("note".to_owned(), note.to_string())],
+            }));
+        let indices: Vec<u32> = (0..BLOCKS_PER_SECTION as u32).map(|i| i % 20).collect();
+        chunk.sections[5] = PersistentSection::Heterogeneous {
+            palette: (first..first + 20).collect(),
+            bits_per_entry: 8,
+            block_data: pack_indices(&indices, 8),
+            biomes: PersistentBiomeData::Homogeneous { biome: 0 },
+        };
+
+        let exported = named_bytes(&export_chunk(&chunk, ChunkPos::new(-1, 2), -64));
+        let nbt = read_named_compound(&exported).expect("valid nbt");
+        let round_trip = import_chunk(&(&nbt).into(), -64, 384, 7).expect("full chunk");
+
+        assert_eq!(blocks(&round_trip), blocks(&chunk));
+        let PersistentSection::Heterogeneous {
+            biomes: PersistentBiomeData::Homogeneous { biome },
+            ..
+        } = &round_trip.sections[4]
+        else {
+            panic!("section 0 should be mixed with a single biome");
+        };
+        assert_eq!(
+            round_trip.biomes[*biome as usize],
+            Identifier::vanilla_static("desert")
+        );
+
+        let (before, after) = (&chunk.block_entities[0], &round_trip.block_entities[0]);
+        assert_eq!((after.x, after.y, after.z), (before.x, before.y, before.z));
+        assert_eq!(after.entity_type, before.entity_type);
+        let data = read_borrowed_compound(&mut Cursor::new(&after.nbt_data[..])).expect("nbt");
+        let data: NbtCompoundView<'_, '_> = (&data).into();
+        assert!(data.get("x").is_none());
+        assert_eq!(
+            data.string("CustomName")
+                .map(|name| name.to_str().into_owned()),
+            Some("Loot".to_owned())
+        );
+
+        let (before, after) = (&chunk.fluid_ticks[0], &round_trip.fluid_ticks[0]);
+        assert_eq!(
+            (after.x, after.y, after.z, after.delay, after.priority),
+            (before.x, before.y, before.z, before.delay, before.priority)
+        );
+    }
+
+    #[tokio::test]
+    async fn region_files_round_trip() {
+        let dir = env::temp_dir().join(format!("steel-anvil-{}", process::id()));
+        let path = dir.join("r.-1.0.mca");
+        // Random bytes don't compress, so this chunk has to go to a .mcc file
+        let large: Vec<u8> = (0..MAX_CHUNK_SECTORS * SECTOR_SIZE)
+            .map(|_| rand::random())
+            .collect();
+        let chunks = [
+            AnvilChunk {
+                pos: ChunkPos::new(-1, 0),
+                timestamp: 5,
+                nbt: b"small chunk".to_vec(),
+            },
+            AnvilChunk {
+                pos: ChunkPos::new(-32, 31),
+                timestamp: 6,
+                nbt: large,
+            },
+        ];
+
+        write_region(&path, &chunks).await.expect("region written");
+        let external = fs::try_exists(dir.join("c.-32.31.mcc")).await;
+        let read = read_region(&path, RegionPos::new(-1, 0)).await;
+        let _ = fs::remove_dir_all(&dir).await;
+
+        assert!(external.expect("readable directory"));
+        let read = read.expect("region read");
+        assert_eq!(read.len(), 2);
+        for chunk in &chunks {
+            let found = read
+                .iter()
+                .find(|read| read.pos == chunk.pos)
+                .expect("chunk read back");
+            assert_eq!(found.timestamp, chunk.timestamp);
+            assert_eq!(found.nbt, chunk.nbt);
+        }
+    }
+
+    #[test]
+    fn level_dat_round_trips() {
+        init_test_registry();
+        let rules = &REGISTRY.game_rules;
+        let mut level = LevelData::new_with_seed(42);
+        level.day_time = 6000;
+        level.flat_settings = FlatSettings::preset("redstone ready");
+        assert!(
+            level
+                .game_rules_values
+                .set(ADVANCE_TIME, GameRuleValue::Bool(false), rules)
+        );
+        assert!(
+            level
+                .game_rules_values
+                .set(RANDOM_TICK_SPEED, GameRuleValue::Int(7), rules)
+        );
+
+        let mut root = NbtCompound::new();
+        root.insert("Data", level_dat(&level, "world", 0));
+        let bytes = named_bytes(&root);
+        let nbt = read_named_compound(&bytes).expect("valid nbt");
+        let root: NbtCompoundView<'_, '_> = (&nbt).into();
+        let data = root.compound("Data").expect("Data compound");
+        let mut imported = LevelData::new_with_seed(42);
+        import_level(&data, OVERWORLD, &mut imported);
+
+        assert_eq!(imported.day_time, 6000);
+        assert!(imported.flat_settings.is_some());
+        assert_eq!(imported.flat_settings, level.flat_settings);
+        for (_, rule) in rules.iter() {
+            assert_eq!(
+                imported.game_rules_values.get(rule, rules),
+                level.game_rules_values.get(rule, rules),
+                "game rule {}",
+                rule.key
+            );
+        }
+    }
+
+    #[test]
+    fn imports_legacy_game_rules() {
+        init_test_registry();
+        let mut game_rules = NbtCompound::new();
+        game_rules.insert("doDaylightCycle", string("false"));
+        game_rules.insert("disableRaids", string("true"));
+        game_rules.insert("doFireTick", string("false"));
+        game_rules.insert("randomTickSpeed", string("9"));
+        game_rules.insert("someModdedRule", string("true"));
+        let mut root = NbtCompound::new();
+        root.insert("GameRules", game_rules);
+        let bytes = named_bytes(&root);
+        let nbt = read_named_compound(&bytes).expect("valid nbt");
+        let root: NbtCompoundView<'_, '_> = (&nbt).into();
+
+        let rules = &REGISTRY.game_rules;
+        let mut values = GameRuleValues::new(rules);
+        import_game_rules(&root.compound("GameRules").expect("GameRules"), &mut values);
+        assert_eq!(values.get(ADVANCE_TIME, rules), GameRuleValue::Bool(false));
+        assert_eq!(values.get(RAIDS, rules), GameRuleValue::Bool(false));
+        assert_eq!(
+            values.get(FIRE_SPREAD_RADIUS_AROUND_PLAYER, rules),
+            GameRuleValue::Int(0)
+        );
+        assert_eq!(values.get(RANDOM_TICK_SPEED, rules), GameRuleValue::Int(9));
+    }
+}
@@ -27,6 +27,18 @@
 //!
 //! Block data uses power-of-2 bit packing (1, 2, 4, 8, 16 bits) to avoid entries
 //! spanning u64 boundaries.
+//!
+//! ## Versions
+//!
+//! - **2**: sections, palettes and block entities.
+//! - **3**: adds a trailing entity section to every chunk. Chunks written by
+//!   version 2 have no entity section and are decoded with [`PersistentChunkV2`].
+//! - **4**: adds scheduled block and fluid ticks after the entities. Chunks written
+//!   by version 3 are decoded with [`PersistentChunkV3`].
+//!
+//! Every chunk of a region uses the layout of the version in the region's header.
+//! Regions written by an older version are rewritten in the current layout when
+//! they are opened.
 
 use steel_utils::Identifier;
 use wincode::{SchemaRead, SchemaWrite};
@@ -37,7 +49,7 @@ use crate::chunk::chunk_access::ChunkStatus;
 pub const REGION_MAGIC: [u8; 4] = *b"STLR";
 
 /// Current format version. Increment when making breaking changes.
-pub const FORMAT_VERSION: u16 = 2;
+pub const FORMAT_VERSION: u16 = 4;
 
 /// Number of chunks per region side (32×32 = 1024 chunks per region).
 pub const REGION_SIZE: usize = 32;
@@ -286,6 +298,89 @@ pub struct PersistentChunk {
     pub sections: Vec<PersistentSection>,
     /// Block entities (chests, signs, etc.). Currently placeholder.
     pub block_entities: Vec<PersistentBlockEntity>,
+    /// Non-player entities (dropped items, etc.) inside this chunk.
+    pub entities: Vec<PersistentEntity>,
+    /// Pending scheduled block ticks, in run order.
+    pub block_ticks: Vec<PersistentScheduledTick>,
+    /// Pending scheduled fluid ticks, in run order.
+    pub fluid_ticks: Vec<PersistentScheduledTick>,
+}
+
+impl PersistentChunk {
+    /// Decodes a chunk with the layout of the format `version` stored in the header
+    /// of its region file.
+    ///
+    /// # Errors
+    /// Returns an error if the data doesn't match the layout of that version.
+    pub fn decode(data: &[u8], version: u16) -> Result<Self, wincode::ReadError> {
+        match version {
+            ..=2 => wincode::deserialize::<PersistentChunkV2>(data).map(Self::from),
+            3 => wincode::deserialize::<PersistentChunkV3>(data).map(Self::from),
+            _ => wincode::deserialize::<Self>(data),
+        }
+    }
+}
+
+/// Chunk layout used by format version 3, without scheduled ticks.
+#[derive(SchemaWrite, SchemaRead)]
+pub struct PersistentChunkV3 {
+    /// Unix timestamp of last modification.
+    pub last_modified: u32,
+    /// Block states used in this chunk.
+    pub block_states: Vec<PersistentBlockState>,
+    /// Biomes used in this chunk.
+    pub biomes: Vec<Identifier>,
+    /// Vertical sections.
+    pub sections: Vec<PersistentSection>,
+    /// Block entities.
+    pub block_entities: Vec<PersistentBlockEntity>,
+    /// Non-player entities.
+    pub entities: Vec<PersistentEntity>,
+}
+
+impl From<PersistentChunkV3> for PersistentChunk {
+    fn from(chunk: PersistentChunkV3) -> Self {
+        Self {
+            last_modified: chunk.last_modified,
+            block_states: chunk.block_states,
+            biomes: chunk.biomes,
+            sections: chunk.sections,
+            block_entities: chunk.block_entities,
+            entities: chunk.entities,
+            block_ticks: Vec::new(),
+            fluid_ticks: Vec::new(),
+        }
+    }
+}
+
+/// Chunk layout used by format version 2, without the entity section.
+#[derive(SchemaWrite, SchemaRead)]
+pub struct PersistentChunkV2 {
+    /// Unix timestamp of last modification.
+    pub last_modified: u32,
+    /// Block states used in this chunk.
+    pub block_states: Vec<PersistentBlockState>,
+    /// Biomes used in this chunk.
+    pub biomes: Vec<Identifier>,
+    /// Vertical sections.
+    pub sections: Vec<PersistentSection>,
+    /// Block entities.
+    pub block_entities: Vec<PersistentBlockEntity>,
+}
+
+impl From<PersistentChunkV2> for PersistentChunk {
+    fn from(chunk: PersistentChunkV2) -> Self {
+        Self {
+            last_modified: chunk.last_modified,
+            block_states: chunk.block_states,
+            biomes: chunk.biomes,
+            sections: chunk.sections,
+            block_entities: chunk.block_entities,
+            entities: Vec::new(),
+            block_ticks: Vec::new(),
+            fluid_ticks: Vec::new(),
+        }
+    }
 }
 
 /// A 16×16×16 section of a chunk.
@@ -349,6 +444,35 @@ pub struct PersistentBlockEntity {
     pub nbt_data: Vec<u8>,
 }
 
+/// A non-player entity stored with the chunk it is in.
+///
+/// Position, UUID and the rest of the entity's state live in the NBT data,
+/// written by `Entity::save`.
+#[derive(SchemaWrite, SchemaRead)]
+pub struct PersistentEntity {
+    /// Entity type identifier (e.g., "minecraft:item").
+    pub entity_type: Identifier,
+    /// Serialized NBT data (simdnbt binary format).
+    pub nbt_data: Vec<u8>,
+}
+
+/// A scheduled block or fluid tick stored with the chunk it is in.
+#[derive(SchemaWrite, SchemaRead)]
+pub struct PersistentScheduledTick {
+    /// Block or fluid identifier (e.g., "minecraft:water").
+    pub kind: Identifier,
+    /// Relative X position within chunk (0-15).
+    pub x: u8,
+    /// Absolute Y position (world height).
+    pub y: i16,
+    /// Relative Z position within chunk (0-15).
+    pub z: u8,
+    /// Ticks left until the tick is due when the chunk was saved.
+    pub delay: i32,
+    /// Vanilla tick priority, from -3 (runs first) to 3.
+    pub priority: i8,
+}
+
 /// Position of a region in region coordinates.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct RegionPos {
@@ -482,4 +606,104 @@ mod tests {
         // Needs more than gap, append at end
         assert_eq!(header.find_free_sectors(6, 12), 12);
     }
+
+    fn test_sections() -> Vec<PersistentSection> {
+        vec![PersistentSection::Homogeneous {
+            block_state: 0,
+            biomes: PersistentBiomeData::Homogeneous { biome: 0 },
+        }]
+    }
+
+    #[test]
+    fn test_decode_with_entities() {
+        let chunk = PersistentChunk {
+            last_modified: 42,
+            block_states: Vec::new(),
+            biomes: Vec::new(),
+            sections: test_sections(),
+            block_entities: Vec::new(),
+            entities: vec![PersistentEntity {
+                entity_type: Identifier::vanilla_static("item"),
+                nbt_data: vec![1, 2, 3],
+            }],
+            block_ticks: vec![PersistentScheduledTick {
+                kind: Identifier::vanilla_static("stone_button"),
+                x: 1,
+                y: -20,
+                z: 15,
+                delay: 20,
+                priority: 0,
+            }],
+            fluid_ticks: Vec::new(),
+        };
+        let data = wincode::serialize(&chunk).expect("chunk should serialize");
+
+        let decoded = PersistentChunk::decode(&data, FORMAT_VERSION).expect("chunk should decode");
+        assert_eq!(decoded.last_modified, 42);
+        assert_eq!(decoded.entities.len(), 1);
+        assert_eq!(
+            decoded.entities[0].entity_type,
+            chunk.entities[0].entity_type
+        );
+        assert_eq!(decoded.entities[0].nbt_data, vec![1, 2, 3]);
+        assert_eq!(decoded.block_ticks.len(), 1);
+        assert_eq!(decoded.block_ticks[0].y, -20);
+        assert_eq!(decoded.block_ticks[0].delay, 20);
+    }
+
+    #[test]
+    fn test_decode_version_3_chunk() {
+        let chunk = PersistentChunkV3 {
+            last_modified: 9,
+            block_states: Vec::new(),
+            biomes: Vec::new(),
+            sections: test_sections(),
+            block_entities: Vec::new(),
+            entities: vec![PersistentEntity {
+                entity_type: Identifier::vanilla_static("item"),
+                nbt_data: vec![4],
+            }],
+        };
+        let data = wincode::serialize(&chunk).expect("chunk should serialize");
+
+        let decoded = PersistentChunk::decode(&data, 3).expect("version 3 chunk should decode");
+        assert_eq!(decoded.last_modified, 9);
+        assert_eq!(decoded.entities.len(), 1);
+        assert!(decoded.block_ticks.is_empty());
+        assert!(decoded.fluid_ticks.is_empty());
+    }
+
+    #[test]
+    fn test_decode_version_2_chunk() {
+        let chunk = PersistentChunkV2 {
+            last_modified: 7,
+            block_states: Vec::new(),
+            biomes: Vec::new(),
+            sections: test_sections(),
+            block_entities: Vec::new(),
+        };
+        let data = wincode::serialize(&chunk).expect("chunk should serialize");
+
+        let decoded = PersistentChunk::decode(&data, 2).expect("legacy chunk should decode");
+        assert_eq!(decoded.last_modified, 7);
+        assert_eq!(decoded.sections.len(), 1);
+        assert!(decoded.entities.is_empty());
+    }
+
+    #[test]
+    fn test_decode_uses_the_region_version() {
+        let chunk = PersistentChunkV3 {
+            last_modified: 5,
+            block_states: Vec::new(),
+            biomes: Vec::new(),
+            sections: Vec::new(),
+            block_entities: Vec::new(),
+            entities: Vec::new(),
+        };
+        let data = wincode::serialize(&chunk).expect("chunk should serialize");
+
+        // Reading a chunk with the wrong layout must not silently succeed
+        assert!(PersistentChunk::decode(&data, FORMAT_VERSION).is_err());
+        assert!(PersistentChunk::decode(&data, 3).is_ok());
+    }
 }
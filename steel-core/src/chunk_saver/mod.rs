@@ -29,7 +29,11 @@
 //! - **Power-of-2 bit packing** for efficient storage (1, 2, 4, 8, 16 bits)
 //! - **Homogeneous section optimization** (single block type = no bit array)
 //! - **zstd compression** per-chunk for good compression ratios
+//!
+//! Vanilla worlds can be brought over with [`anvil::import_world`] and converted back with
+//! [`anvil::export_world`].
 
+pub mod anvil;
 mod bit_pack;
 mod format;
 mod region_manager;
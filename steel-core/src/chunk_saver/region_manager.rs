@@ -7,13 +7,15 @@
 use std::{
     io::{self, Cursor},
     path::PathBuf,
-    sync::{Weak, atomic::Ordering},
+    sync::{Arc, Weak, atomic::Ordering},
     time::{SystemTime, UNIX_EPOCH},
 };
 
 use rustc_hash::FxHashMap;
 use simdnbt::borrow::read_compound as read_borrowed_compound;
 use simdnbt::owned::NbtCompound;
+use steel_registry::blocks::BlockRef;
+use steel_registry::fluid::FluidRef;
 use steel_registry::{REGISTRY, Registry};
 use steel_utils::{BlockPos, BlockStateId, ChunkPos, Identifier, locks::AsyncRwLock};
 use tokio::{
@@ -29,15 +31,18 @@ use crate::chunk::{
     proto_chunk::ProtoChunk,
     section::{ChunkSection, SectionHolder, Sections},
 };
+use crate::entity::{Entity, load_entity};
+use crate::ticks::{LevelChunkTicks, SavedTick, TickPriority, TickType};
 use crate::world::World;
 
 use super::{
     bit_pack::{bits_for_palette_len, pack_indices, unpack_indices},
     format::{
-        BIOMES_PER_SECTION, BLOCKS_PER_SECTION, CHUNK_TABLE_SIZE, FILE_HEADER_SIZE,
+        BIOMES_PER_SECTION, BLOCKS_PER_SECTION, CHUNK_TABLE_SIZE, ChunkEntry, FILE_HEADER_SIZE,
         FIRST_DATA_SECTOR, FORMAT_VERSION, MAX_CHUNK_SIZE, PersistentBiomeData,
-        PersistentBlockEntity, PersistentBlockState, PersistentChunk, PersistentSection,
-        REGION_MAGIC, RegionHeader, RegionPos, SECTOR_SIZE,
+        PersistentBlockEntity, PersistentBlockState, PersistentChunk, PersistentEntity,
+        PersistentScheduledTick, PersistentSection, REGION_MAGIC, RegionHeader, RegionPos,
+        SECTOR_SIZE,
     },
 };
 
@@ -59,6 +64,27 @@ pub struct PreparedChunkSave {
     pub pos: ChunkPos,
     /// The serialized chunk data.
     persistent: PersistentChunk,
+    /// IDs of the entities written with the chunk.
+    entity_ids: Vec<i32>,
+}
+
+impl PreparedChunkSave {
+    /// Wraps a chunk that was converted from another format.
+    pub(super) fn from_persistent(pos: ChunkPos, persistent: PersistentChunk) -> Self {
+        Self {
+            pos,
+            persistent,
+            entity_ids: Vec::new(),
+        }
+    }
+
+    /// Returns the IDs of the entities written with the chunk.
+    ///
+    /// When the chunk is unloading, these must be removed from the world.
+    #[must_use]
+    pub fn entity_ids(&self) -> &[i32] {
+        &self.entity_ids
+    }
 }
 
 /// An open region file with its header.
@@ -198,11 +224,19 @@ impl RegionManager {
         // Read chunk table
         let mut table_bytes = vec![0u8; CHUNK_TABLE_SIZE];
         file.read_exact(&mut table_bytes).await?;
-        let header = RegionHeader::from_bytes(&table_bytes);
+        let mut header = RegionHeader::from_bytes(&table_bytes);
 
         // Calculate file size in sectors
         let file_size = file.seek(io::SeekFrom::End(0)).await?;
-        let file_sectors = file_size.div_ceil(SECTOR_SIZE as u64) as u32;
+        let mut file_sectors = file_size.div_ceil(SECTOR_SIZE as u64) as u32;
+
+        if version < FORMAT_VERSION {
+            log::info!(
+                "Upgrading {} from format version {version} to {FORMAT_VERSION}",
+                path.display()
+            );
+            Self::upgrade_region(&mut file, &mut header, version, &mut file_sectors).await?;
+        }
 
         Ok(RegionHandle {
             file,
@@ -213,6 +247,49 @@ impl RegionManager {
         })
     }
 
+    /// Rewrites every chunk of a region written by an older format version in the
+    /// current layout, so that the version in the header describes all of its chunks.
+    ///
+    /// The upgraded chunks are appended after the old data, and the header is only
+    /// rewritten once all of them are on disk, so an interrupted upgrade leaves the
+    /// old region readable.
+    async fn upgrade_region(
+        file: &mut File,
+        header: &mut RegionHeader,
+        version: u16,
+        file_sectors: &mut u32,
+    ) -> io::Result<()> {
+        let invalid = |e: String| io::Error::new(io::ErrorKind::InvalidData, e);
+
+        for entry in header.entries.iter_mut() {
+            if !entry.exists() {
+                continue;
+            }
+            let compressed =
+                Self::read_chunk_data(file, entry.sector_offset, entry.size_bytes).await?;
+            let data = zstd::decode_all(&compressed[..])?;
+            let persistent =
+                PersistentChunk::decode(&data, version).map_err(|e| invalid(e.to_string()))?;
+            let data = wincode::serialize(&persistent).map_err(|e| invalid(e.to_string()))?;
+            let compressed = zstd::encode_all(&data[..], 3)?;
+
+            let sector_offset = *file_sectors;
+            Self::write_chunk_data(file, sector_offset, &compressed, file_sectors).await?;
+            *entry = ChunkEntry::new(sector_offset, compressed.len() as u32, entry.status);
+        }
+
+        // The version and the chunk table are adjacent, write them together
+        let mut header_bytes = Vec::with_capacity(FILE_HEADER_SIZE + CHUNK_TABLE_SIZE);
+        header_bytes.extend_from_slice(&REGION_MAGIC);
+        header_bytes.extend_from_slice(&FORMAT_VERSION.to_le_bytes());
+        header_bytes.extend_from_slice(&[0; 2]);
+        header_bytes.extend_from_slice(&header.to_bytes());
+        file.seek(io::SeekFrom::Start(0)).await?;
+        file.write_all(&header_bytes).await?;
+        file.flush().await?;
+        Ok(())
+    }
+
     /// Creates a new empty region file.
     async fn create_region(&self, pos: RegionPos) -> io::Result<RegionHandle> {
         fs::create_dir_all(&self.base_path).await?;
@@ -307,23 +384,57 @@ impl RegionManager {
     /// Returns `Ok(true)` if the chunk was saved.
     /// Prepares chunk data for saving. Call this while holding the chunk lock,
     /// then pass the result to `save_chunk_data` after releasing the lock.
+    ///
+    /// Chunks containing entities are always saved, since entities move without
+    /// marking the chunk dirty.
     #[must_use]
     pub fn prepare_chunk_save(chunk: &ChunkAccess) -> Option<PreparedChunkSave> {
-        if !chunk.is_dirty() {
+        let pos = chunk.pos();
+
+        // Entities are only tracked for full chunks
+        let world = chunk.as_full().and_then(LevelChunk::get_level);
+        let entities: Vec<Arc<dyn Entity>> = world
+            .as_ref()
+            .map(|world| world.get_entities_in_chunk(pos))
+            .unwrap_or_default();
+
+        if !chunk.is_dirty() && entities.is_empty() {
             return None;
         }
 
-        let pos = chunk.pos();
-
         // Get block entities if this is a full chunk
         let block_entities: Vec<SharedBlockEntity> = chunk
             .as_full()
-            .map(super::super::chunk::level_chunk::LevelChunk::get_block_entities)
+            .map(LevelChunk::get_block_entities)
             .unwrap_or_default();
 
-        let persistent = Self::to_persistent(chunk.sections(), &block_entities, pos);
+        // Scheduled ticks are saved relative to the current tick
+        let (block_ticks, fluid_ticks) = match (chunk.as_full(), &world) {
+            (Some(chunk), Some(world)) => {
+                let time = world.chunk_map.level_ticks.time();
+                (
+                    chunk.block_ticks.lock().save(time),
+                    chunk.fluid_ticks.lock().save(time),
+                )
+            }
+            _ => (Vec::new(), Vec::new()),
+        };
 
-        Some(PreparedChunkSave { pos, persistent })
+        let persistent = Self::to_persistent(
+            chunk.sections(),
+            &block_entities,
+            &entities,
+            &block_ticks,
+            &fluid_ticks,
+            pos,
+        );
+        let entity_ids = entities.iter().map(|entity| entity.get_id()).collect();
+
+        Some(PreparedChunkSave {
+            pos,
+            persistent,
+            entity_ids,
+        })
     }
 
     /// Saves prepared chunk data to disk. This is the async part that doesn't
@@ -400,7 +511,7 @@ impl RegionManager {
 
         // Update header entry
         handle.header.entries[index] =
-            super::format::ChunkEntry::new(sector_offset, compressed.len() as u32, status);
+            ChunkEntry::new(sector_offset, compressed.len() as u32, status);
 
         // If we opened this region and no chunks are loaded from it,
         // write the header and close it immediately
@@ -462,7 +573,7 @@ impl RegionManager {
         let data = zstd::decode_all(&compressed[..])?;
 
         // Deserialize
-        let persistent: PersistentChunk = wincode::deserialize(&data)
+        let persistent = PersistentChunk::decode(&data, FORMAT_VERSION)
             .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))?;
 
         // Convert to runtime format (persistent is dropped after this - no duplication!)
@@ -604,6 +715,9 @@ impl RegionManager {
     fn to_persistent(
         sections: &Sections,
         block_entities: &[SharedBlockEntity],
+        entities: &[Arc<dyn Entity>],
+        block_ticks: &[SavedTick<BlockRef>],
+        fluid_ticks: &[SavedTick<FluidRef>],
         chunk_pos: ChunkPos,
     ) -> PersistentChunk {
         let mut builder = ChunkBuilder::new(&REGISTRY);
@@ -637,6 +751,24 @@ impl RegionManager {
             })
             .collect();
 
+        // Serialize entities; ones that opt out of saving are skipped
+        let persistent_entities: Vec<PersistentEntity> = entities
+            .iter()
+            .filter_map(|entity| {
+                let mut nbt = NbtCompound::new();
+                if !entity.save(&mut nbt) {
+                    return None;
+                }
+                let mut nbt_bytes = Vec::new();
+                nbt.write(&mut nbt_bytes);
+
+                Some(PersistentEntity {
+                    entity_type: Identifier::vanilla_static(entity.get_type().key),
+                    nbt_data: nbt_bytes,
+                })
+            })
+            .collect();
+
         PersistentChunk {
             last_modified: SystemTime::now()
                 .duration_since(UNIX_EPOCH)
@@ -645,9 +777,56 @@ impl RegionManager {
             biomes: builder.biomes,
             sections: persistent_sections,
             block_entities: persistent_block_entities,
+            entities: persistent_entities,
+            block_ticks: Self::ticks_to_persistent(block_ticks, chunk_pos),
+            fluid_ticks: Self::ticks_to_persistent(fluid_ticks, chunk_pos),
         }
     }
 
+    /// Converts scheduled ticks to persistent format.
+    fn ticks_to_persistent<T: TickType>(
+        ticks: &[SavedTick<T>],
+        chunk_pos: ChunkPos,
+    ) -> Vec<PersistentScheduledTick> {
+        ticks
+            .iter()
+            .map(|tick| PersistentScheduledTick {
+                kind: tick.ty.key().clone(),
+                x: (tick.pos.0.x - chunk_pos.0.x * 16) as u8,
+                y: tick.pos.0.y as i16,
+                z: (tick.pos.0.z - chunk_pos.0.y * 16) as u8,
+                delay: tick.delay,
+                priority: tick.priority.value(),
+            })
+            .collect()
+    }
+
+    /// Converts persistent scheduled ticks to runtime format, skipping unknown types.
+    fn persistent_to_ticks<T: TickType>(
+        ticks: &[PersistentScheduledTick],
+        chunk_pos: ChunkPos,
+    ) -> Vec<SavedTick<T>> {
+        ticks
+            .iter()
+            .filter_map(|tick| {
+                let Some(ty) = T::from_key(&tick.kind) else {
+                    log::warn!("Skipping scheduled tick of unknown type {}", tick.kind);
+                    return None;
+                };
+                Some(SavedTick {
+                    ty,
+                    pos: BlockPos::new(
+                        chunk_pos.0.x * 16 + i32::from(tick.x),
+                        i32::from(tick.y),
+                        chunk_pos.0.y * 16 + i32::from(tick.z),
+                    ),
+                    delay: tick.delay,
+                    priority: TickPriority::from_value(tick.priority),
+                })
+            })
+            .collect()
+    }
+
     /// Converts a runtime section to persistent format.
     fn section_to_persistent(
         section: &SectionHolder,
@@ -787,9 +966,24 @@ impl RegionManager {
                     }
                 }
 
+                // Pending ticks get their times once the chunk is first ticked
+                *chunk.block_ticks.lock() =
+                    LevelChunkTicks::load(Self::persistent_to_ticks(&persistent.block_ticks, pos));
+                *chunk.fluid_ticks.lock() =
+                    LevelChunkTicks::load(Self::persistent_to_ticks(&persistent.fluid_ticks, pos));
+
                 // Clear dirty flag since we just loaded (add_and_register marks dirty)
                 chunk.dirty.store(false, Ordering::Release);
 
+                // Restore entities into the world
+                if let Some(world) = chunk.get_level() {
+                    for persistent_entity in &persistent.entities {
+                        if let Some(entity) = Self::persistent_to_entity(persistent_entity) {
+                            world.add_entity(entity);
+                        }
+                    }
+                }
+
                 ChunkAccess::Full(chunk)
             }
             _ => ChunkAccess::Proto(ProtoChunk::from_disk(
@@ -839,6 +1033,19 @@ impl RegionManager {
         }
     }
 
+    /// Recreates an entity from its persistent form.
+    fn persistent_to_entity(persistent: &PersistentEntity) -> Option<Arc<dyn Entity>> {
+        let entity_type = (persistent.entity_type.namespace == Identifier::VANILLA_NAMESPACE)
+            .then(|| REGISTRY.entity_types.by_key(&persistent.entity_type.path))
+            .flatten();
+        let Some(entity_type) = entity_type else {
+            log::warn!("Skipping entity of unknown type {}", persistent.entity_type);
+            return None;
+        };
+        let nbt = read_borrowed_compound(&mut Cursor::new(&persistent.nbt_data)).ok()?;
+        load_entity(entity_type, &nbt)
+    }
+
     /// Converts a persistent section to runtime format.
     fn persistent_to_section(
         persistent: &PersistentSection,
@@ -955,3 +1162,58 @@ impl RegionManager {
         0 // Plains fallback
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chunk_saver::format::{PersistentChunkV3, TOTAL_HEADER_SIZE};
+
+    #[tokio::test]
+    async fn old_regions_are_upgraded_on_open() {
+        let dir = tempfile::tempdir().expect("scratch directory");
+        let path = dir.path().join(RegionPos::new(0, 0).filename());
+        let chunk = PersistentChunkV3 {
+            last_modified: 11,
+            block_states: Vec::new(),
+            biomes: Vec::new(),
+            sections: Vec::new(),
+            block_entities: Vec::new(),
+            entities: Vec::new(),
+        };
+        let data = wincode::serialize(&chunk).expect("chunk should serialize");
+        let compressed = zstd::encode_all(&data[..], 3).expect("chunk should compress");
+
+        // A region written by format version 3
+        let mut header = RegionHeader::new();
+        header.entries[0] = ChunkEntry::new(
+            FIRST_DATA_SECTOR,
+            compressed.len() as u32,
+            ChunkStatus::Full,
+        );
+        let mut file = Vec::new();
+        file.extend_from_slice(&REGION_MAGIC);
+        file.extend_from_slice(&3u16.to_le_bytes());
+        file.extend_from_slice(&[0; 2]);
+        file.extend_from_slice(&header.to_bytes());
+        file.resize(FIRST_DATA_SECTOR as usize * SECTOR_SIZE, 0);
+        file.extend_from_slice(&compressed);
+        fs::write(&path, file).await.expect("region written");
+
+        let manager = RegionManager::new(dir.path());
+        let pos = ChunkPos::new(0, 0);
+        assert!(manager.acquire_chunk(pos).await.expect("region opens"));
+        manager.release_chunk(pos).await.expect("region closes");
+
+        let file = fs::read(&path).await.expect("region read");
+        assert_eq!(u16::from_le_bytes([file[4], file[5]]), FORMAT_VERSION);
+        let header = RegionHeader::from_bytes(&file[FILE_HEADER_SIZE..TOTAL_HEADER_SIZE]);
+        let entry = header.entries[0];
+        assert_eq!(entry.status, ChunkStatus::Full);
+        let offset = entry.sector_offset as usize * SECTOR_SIZE;
+        let data = zstd::decode_all(&file[offset..offset + entry.size_bytes as usize])
+            .expect("chunk should decompress");
+        let upgraded =
+            PersistentChunk::decode(&data, FORMAT_VERSION).expect("chunk uses the current layout");
+        assert_eq!(upgraded.last_modified, 11);
+    }
+}
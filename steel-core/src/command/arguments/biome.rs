@@ -0,0 +1,43 @@
+//! A biome argument.
+use steel_protocol::packets::game::{ArgumentType, SuggestionType};
+use steel_registry::REGISTRY;
+use steel_registry::biome::BiomeRef;
+use steel_utils::Identifier;
+
+use crate::command::arguments::CommandArgument;
+use crate::command::context::CommandContext;
+
+/// A biome argument, accepting a biome key with an optional `minecraft` namespace.
+///
+/// Biome tags are not supported since the registry has none.
+pub struct BiomeArgument;
+
+impl CommandArgument for BiomeArgument {
+    type Output = BiomeRef;
+
+    fn parse<'a>(
+        &self,
+        arg: &'a [&'a str],
+        _context: &mut CommandContext,
+    ) -> Option<(&'a [&'a str], Self::Output)> {
+        let s = arg.first()?;
+
+        let key = if s.contains(':') {
+            s.parse().ok()?
+        } else {
+            Identifier::vanilla((*s).to_string())
+        };
+        let biome = REGISTRY.biomes.by_key(&key)?;
+
+        Some((&arg[1..], biome))
+    }
+
+    fn usage(&self) -> (ArgumentType, Option<SuggestionType>) {
+        (
+            ArgumentType::Resource {
+                identifier: "minecraft:worldgen/biome",
+            },
+            None,
+        )
+    }
+}
@@ -0,0 +1,45 @@
+//! A real-time duration argument.
+use steel_protocol::packets::game::{ArgumentStringTypeBehavior, ArgumentType, SuggestionType};
+
+use crate::command::arguments::CommandArgument;
+use crate::command::context::CommandContext;
+
+/// A duration like `30m`, `12h` or `7d`, parsed into seconds.
+///
+/// Unlike [`TimeArgument`](super::time::TimeArgument), which counts game ticks, this
+/// measures wall-clock time, with the units `s`, `m`, `h`, `d` and `w`.
+pub struct DurationArgument;
+
+impl CommandArgument for DurationArgument {
+    type Output = u64;
+
+    fn parse<'a>(
+        &self,
+        arg: &'a [&'a str],
+        _context: &mut CommandContext,
+    ) -> Option<(&'a [&'a str], Self::Output)> {
+        let s = arg.first()?;
+        let (number, unit) = s.split_at_checked(s.len().checked_sub(1)?)?;
+
+        let number = number.parse::<u64>().ok().filter(|&number| number > 0)?;
+        let seconds = match unit {
+            "s" => 1,
+            "m" => 60,
+            "h" => 60 * 60,
+            "d" => 24 * 60 * 60,
+            "w" => 7 * 24 * 60 * 60,
+            _ => return None,
+        };
+
+        Some((&arg[1..], number.checked_mul(seconds)?))
+    }
+
+    fn usage(&self) -> (ArgumentType, Option<SuggestionType>) {
+        (
+            ArgumentType::String {
+                behavior: ArgumentStringTypeBehavior::SingleWord,
+            },
+            None,
+        )
+    }
+}
@@ -0,0 +1,60 @@
+//! A game profile argument.
+use crate::command::arguments::player::PlayerArgument;
+use crate::command::arguments::{CommandArgument, SuggestionContext};
+use crate::command::context::CommandContext;
+use crate::server::user_lists::ListedPlayer;
+use steel_protocol::packets::game::{ArgumentType, SuggestionEntry, SuggestionType};
+
+/// A game profile argument.
+///
+/// Accepts player selectors, which only match online players, and plain names. Names of
+/// players who are offline are resolved without a UUID.
+pub struct GameProfileArgument;
+
+impl CommandArgument for GameProfileArgument {
+    type Output = Vec<ListedPlayer>;
+
+    fn parse<'a>(
+        &self,
+        arg: &'a [&'a str],
+        context: &mut CommandContext,
+    ) -> Option<(&'a [&'a str], Self::Output)> {
+        let name = *arg.first()?;
+        if name.starts_with('@') {
+            let (rest, players) = PlayerArgument::new().parse(arg, context)?;
+            let profiles = players
+                .iter()
+                .map(|player| ListedPlayer {
+                    uuid: Some(player.gameprofile.id),
+                    name: player.gameprofile.name.clone(),
+                })
+                .collect();
+            return Some((rest, profiles));
+        }
+
+        let profile = context
+            .server
+            .get_players()
+            .into_iter()
+            .find(|player| player.gameprofile.name.eq_ignore_ascii_case(name))
+            .map_or_else(
+                || ListedPlayer {
+                    uuid: None,
+                    name: name.to_owned(),
+                },
+                |player| ListedPlayer {
+                    uuid: Some(player.gameprofile.id),
+                    name: player.gameprofile.name.clone(),
+                },
+            );
+        Some((&arg[1..], vec![profile]))
+    }
+
+    fn usage(&self) -> (ArgumentType, Option<SuggestionType>) {
+        (ArgumentType::GameProfile, Some(SuggestionType::AskServer))
+    }
+
+    fn suggest(&self, prefix: &str, suggestion_ctx: &SuggestionContext) -> Vec<SuggestionEntry> {
+        PlayerArgument::new().suggest(prefix, suggestion_ctx)
+    }
+}
@@ -1,12 +1,16 @@
 //! This module contains types and utilities for parsing command arguments.
 pub mod anchor;
+pub mod biome;
 pub mod bool;
+pub mod duration;
 pub mod entity;
 pub mod float;
+pub mod game_profile;
 pub mod gamemode;
 pub mod integer;
 pub mod player;
 pub mod rotation;
+pub mod string;
 pub mod text_component;
 pub mod time;
 pub mod vector2;
@@ -0,0 +1,57 @@
+//! A string argument.
+use steel_protocol::packets::game::{ArgumentStringTypeBehavior, ArgumentType, SuggestionType};
+
+use crate::command::arguments::CommandArgument;
+use crate::command::context::CommandContext;
+
+/// A string argument.
+pub struct StringArgument {
+    behavior: ArgumentStringTypeBehavior,
+}
+
+impl StringArgument {
+    /// Creates an argument consuming a single word.
+    #[must_use]
+    pub fn word() -> Self {
+        StringArgument {
+            behavior: ArgumentStringTypeBehavior::SingleWord,
+        }
+    }
+
+    /// Creates an argument consuming the rest of the command.
+    #[must_use]
+    pub fn greedy() -> Self {
+        StringArgument {
+            behavior: ArgumentStringTypeBehavior::GreedyPhrase,
+        }
+    }
+}
+
+impl CommandArgument for StringArgument {
+    type Output = String;
+
+    fn parse<'a>(
+        &self,
+        arg: &'a [&'a str],
+        _context: &mut CommandContext,
+    ) -> Option<(&'a [&'a str], Self::Output)> {
+        match self.behavior {
+            ArgumentStringTypeBehavior::GreedyPhrase if !arg.is_empty() => {
+                Some((&[], arg.join(" ")))
+            }
+            _ => {
+                let word = arg.first()?;
+                Some((&arg[1..], (*word).to_owned()))
+            }
+        }
+    }
+
+    fn usage(&self) -> (ArgumentType, Option<SuggestionType>) {
+        (
+            ArgumentType::String {
+                behavior: self.behavior,
+            },
+            None,
+        )
+    }
+}
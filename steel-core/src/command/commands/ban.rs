@@ -0,0 +1,134 @@
+//! Handler for the "ban" command.
+use crate::command::arguments::duration::DurationArgument;
+use crate::command::arguments::game_profile::GameProfileArgument;
+use crate::command::arguments::string::StringArgument;
+use crate::command::commands::{
+    CommandExecutor, CommandHandlerBuilder, CommandHandlerDyn, argument, literal,
+};
+use crate::command::context::CommandContext;
+use crate::command::error::CommandError;
+use crate::server::user_lists::{BanDetails, DEFAULT_BAN_REASON, ListedPlayer, PlayerBan};
+use steel_utils::translations;
+use text_components::TextComponent;
+
+/// Handler for the "ban" command.
+///
+/// Besides vanilla's syntax, `/ban <targets> for <duration> [<reason>]` bans for a limited
+/// time.
+#[must_use]
+pub fn command_handler() -> impl CommandHandlerDyn {
+    CommandHandlerBuilder::new(
+        &["ban"],
+        "Bans players from the server.",
+        "minecraft:command.ban",
+    )
+    .then(
+        argument("targets", GameProfileArgument)
+            .executes(BanCommandExecutor)
+            .then(
+                literal("for").then(
+                    argument("duration", DurationArgument)
+                        .executes(BanCommandExecutor)
+                        .then(
+                            argument("reason", StringArgument::greedy())
+                                .executes(BanCommandExecutor),
+                        ),
+                ),
+            )
+            .then(argument("reason", StringArgument::greedy()).executes(BanCommandExecutor)),
+    )
+}
+
+struct BanCommandExecutor;
+
+impl CommandExecutor<((), Vec<ListedPlayer>)> for BanCommandExecutor {
+    fn execute(
+        &self,
+        args: ((), Vec<ListedPlayer>),
+        context: &mut CommandContext,
+    ) -> Result<(), CommandError> {
+        let ((), targets) = args;
+        ban(targets, DEFAULT_BAN_REASON, None, context)
+    }
+}
+
+impl CommandExecutor<(((), Vec<ListedPlayer>), String)> for BanCommandExecutor {
+    fn execute(
+        &self,
+        args: (((), Vec<ListedPlayer>), String),
+        context: &mut CommandContext,
+    ) -> Result<(), CommandError> {
+        let (((), targets), reason) = args;
+        ban(targets, &reason, None, context)
+    }
+}
+
+impl CommandExecutor<(((), Vec<ListedPlayer>), u64)> for BanCommandExecutor {
+    fn execute(
+        &self,
+        args: (((), Vec<ListedPlayer>), u64),
+        context: &mut CommandContext,
+    ) -> Result<(), CommandError> {
+        let (((), targets), duration) = args;
+        ban(targets, DEFAULT_BAN_REASON, Some(duration), context)
+    }
+}
+
+impl CommandExecutor<((((), Vec<ListedPlayer>), u64), String)> for BanCommandExecutor {
+    fn execute(
+        &self,
+        args: ((((), Vec<ListedPlayer>), u64), String),
+        context: &mut CommandContext,
+    ) -> Result<(), CommandError> {
+        let ((((), targets), duration), reason) = args;
+        ban(targets, &reason, Some(duration), context)
+    }
+}
+
+fn ban(
+    targets: Vec<ListedPlayer>,
+    reason: &str,
+    duration: Option<u64>,
+    context: &CommandContext,
+) -> Result<(), CommandError> {
+    let server = &context.server;
+    let mut banned = 0;
+
+    for target in targets {
+        let entry = PlayerBan {
+            player: target.clone(),
+            ban: BanDetails::new(context.sender.to_string(), reason.to_owned(), duration),
+        };
+        if !server
+            .user_lists
+            .banned_players
+            .add(entry, |existing| existing.player.same_player(&target))
+        {
+            continue;
+        }
+        banned += 1;
+        context.sender.send_message(
+            &translations::COMMANDS_BAN_SUCCESS
+                .message([
+                    TextComponent::plain(target.name.clone()),
+                    TextComponent::plain(reason.to_owned()),
+                ])
+                .into(),
+        );
+
+        for player in server.get_players() {
+            if target.matches(player.gameprofile.id, &player.gameprofile.name) {
+                player
+                    .connection
+                    .disconnect(translations::MULTIPLAYER_DISCONNECT_BANNED.msg());
+            }
+        }
+    }
+
+    if banned == 0 {
+        return Err(CommandError::CommandFailed(Box::new(
+            translations::COMMANDS_BAN_FAILED.msg().into(),
+        )));
+    }
+    Ok(())
+}
@@ -0,0 +1,160 @@
+//! Handler for the "ban-ip" command.
+use crate::command::arguments::duration::DurationArgument;
+use crate::command::arguments::string::StringArgument;
+use crate::command::commands::{
+    CommandExecutor, CommandHandlerBuilder, CommandHandlerDyn, argument, literal,
+};
+use crate::command::context::CommandContext;
+use crate::command::error::CommandError;
+use crate::player::Player;
+use crate::server::user_lists::{BanDetails, DEFAULT_BAN_REASON, IpBan};
+use std::net::IpAddr;
+use std::sync::Arc;
+use steel_utils::translations;
+use text_components::TextComponent;
+
+/// Handler for the "ban-ip" command.
+///
+/// Besides vanilla's syntax, `/ban-ip <target> for <duration> [<reason>]` bans for a
+/// limited time.
+#[must_use]
+pub fn command_handler() -> impl CommandHandlerDyn {
+    CommandHandlerBuilder::new(
+        &["ban-ip"],
+        "Bans an IP address, or the address of a player, from the server.",
+        "minecraft:command.ban-ip",
+    )
+    .then(
+        argument("target", StringArgument::word())
+            .executes(BanIpCommandExecutor)
+            .then(
+                literal("for").then(
+                    argument("duration", DurationArgument)
+                        .executes(BanIpCommandExecutor)
+                        .then(
+                            argument("reason", StringArgument::greedy())
+                                .executes(BanIpCommandExecutor),
+                        ),
+                ),
+            )
+            .then(argument("reason", StringArgument::greedy()).executes(BanIpCommandExecutor)),
+    )
+}
+
+struct BanIpCommandExecutor;
+
+impl CommandExecutor<((), String)> for BanIpCommandExecutor {
+    fn execute(
+        &self,
+        args: ((), String),
+        context: &mut CommandContext,
+    ) -> Result<(), CommandError> {
+        let ((), target) = args;
+        ban_ip(&target, DEFAULT_BAN_REASON, None, context)
+    }
+}
+
+impl CommandExecutor<(((), String), String)> for BanIpCommandExecutor {
+    fn execute(
+        &self,
+        args: (((), String), String),
+        context: &mut CommandContext,
+    ) -> Result<(), CommandError> {
+        let (((), target), reason) = args;
+        ban_ip(&target, &reason, None, context)
+    }
+}
+
+impl CommandExecutor<(((), String), u64)> for BanIpCommandExecutor {
+    fn execute(
+        &self,
+        args: (((), String), u64),
+        context: &mut CommandContext,
+    ) -> Result<(), CommandError> {
+        let (((), target), duration) = args;
+        ban_ip(&target, DEFAULT_BAN_REASON, Some(duration), context)
+    }
+}
+
+impl CommandExecutor<((((), String), u64), String)> for BanIpCommandExecutor {
+    fn execute(
+        &self,
+        args: ((((), String), u64), String),
+        context: &mut CommandContext,
+    ) -> Result<(), CommandError> {
+        let ((((), target), duration), reason) = args;
+        ban_ip(&target, &reason, Some(duration), context)
+    }
+}
+
+fn ban_ip(
+    target: &str,
+    reason: &str,
+    duration: Option<u64>,
+    context: &CommandContext,
+) -> Result<(), CommandError> {
+    let server = &context.server;
+    let ip = match target.parse::<IpAddr>() {
+        Ok(ip) => ip,
+        Err(_) => server
+            .get_players()
+            .into_iter()
+            .find(|player| player.gameprofile.name.eq_ignore_ascii_case(target))
+            .map(|player| player.connection.address().ip())
+            .ok_or_else(|| {
+                CommandError::CommandFailed(Box::new(
+                    translations::COMMANDS_BANIP_INVALID.msg().into(),
+                ))
+            })?,
+    };
+
+    let entry = IpBan {
+        ip,
+        ban: BanDetails::new(context.sender.to_string(), reason.to_owned(), duration),
+    };
+    if !server
+        .user_lists
+        .banned_ips
+        .add(entry, |existing| existing.ip == ip)
+    {
+        return Err(CommandError::CommandFailed(Box::new(
+            translations::COMMANDS_BANIP_FAILED.msg().into(),
+        )));
+    }
+    context.sender.send_message(
+        &translations::COMMANDS_BANIP_SUCCESS
+            .message([
+                TextComponent::plain(ip.to_string()),
+                TextComponent::plain(reason.to_owned()),
+            ])
+            .into(),
+    );
+
+    let affected: Vec<Arc<Player>> = server
+        .get_players()
+        .into_iter()
+        .filter(|player| player.connection.address().ip() == ip)
+        .collect();
+    if affected.is_empty() {
+        return Ok(());
+    }
+    let names = affected
+        .iter()
+        .map(|player| player.gameprofile.name.as_str())
+        .collect::<Vec<_>>()
+        .join(", ");
+    context.sender.send_message(
+        &translations::COMMANDS_BANIP_INFO
+            .message([
+                TextComponent::plain(affected.len().to_string()),
+                TextComponent::plain(names),
+            ])
+            .into(),
+    );
+    for player in affected {
+        player
+            .connection
+            .disconnect(translations::MULTIPLAYER_DISCONNECT_IP_BANNED.msg());
+    }
+    Ok(())
+}
@@ -0,0 +1,55 @@
+//! Handler for the "deop" command.
+use crate::command::arguments::player::PlayerArgument;
+use crate::command::commands::{
+    CommandExecutor, CommandHandlerBuilder, CommandHandlerDyn, argument,
+};
+use crate::command::context::CommandContext;
+use crate::command::error::CommandError;
+use crate::player::Player;
+use std::sync::Arc;
+use steel_utils::translations;
+use text_components::TextComponent;
+
+/// Handler for the "deop" command.
+#[must_use]
+pub fn command_handler() -> impl CommandHandlerDyn {
+    CommandHandlerBuilder::new(
+        &["deop"],
+        "Revokes operator status from players.",
+        "minecraft:command.deop",
+    )
+    .then(argument("targets", PlayerArgument::new()).executes(DeopCommandExecutor))
+}
+
+struct DeopCommandExecutor;
+
+impl CommandExecutor<((), Vec<Arc<Player>>)> for DeopCommandExecutor {
+    fn execute(
+        &self,
+        args: ((), Vec<Arc<Player>>),
+        context: &mut CommandContext,
+    ) -> Result<(), CommandError> {
+        let ((), targets) = args;
+        let mut changed = 0;
+
+        for target in targets {
+            if !context.server.permissions.deop(target.gameprofile.id) {
+                continue;
+            }
+            changed += 1;
+            context.server.send_commands(&target);
+            context.sender.send_message(
+                &translations::COMMANDS_DEOP_SUCCESS
+                    .message([TextComponent::plain(target.gameprofile.name.clone())])
+                    .into(),
+            );
+        }
+
+        if changed == 0 {
+            return Err(CommandError::CommandFailed(Box::new(
+                translations::COMMANDS_DEOP_FAILED.msg().into(),
+            )));
+        }
+        Ok(())
+    }
+}
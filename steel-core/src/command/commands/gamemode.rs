@@ -47,7 +47,7 @@ impl CommandExecutor<((), GameType)> for GameModeCommandExecutor {
             .ok_or(CommandError::InvalidRequirement)?;
 
         // Set the player's game mode
-        if !player.set_game_mode(gamemode) {
+        if !player.set_game_mode(gamemode, &context.server) {
             // Player was already in the requested game mode
             return Ok(());
         }
@@ -76,7 +76,7 @@ impl CommandExecutor<(((), GameType), Vec<Arc<Player>>)> for GameModeTargetComma
         let mode_translation = get_gamemode_translation(gamemode);
 
         for target in targets {
-            if target.set_game_mode(gamemode) {
+            if target.set_game_mode(gamemode, &context.server) {
                 // Send message to target
                 target.send_message(
                     &translations::COMMANDS_GAMEMODE_SUCCESS_SELF
@@ -9,6 +9,7 @@ use crate::command::error::CommandError;
 use std::borrow::Cow;
 use steel_registry::REGISTRY;
 use steel_registry::game_rules::{GameRuleRef, GameRuleType, GameRuleValue};
+use steel_registry::vanilla_game_rules::ADVANCE_TIME;
 use steel_utils::translations;
 use text_components::TextComponent;
 
@@ -81,6 +82,10 @@ impl CommandExecutor<((), bool)> for SetBoolExecutor {
         let rule_name = self.0.key.path.to_string();
 
         world.set_game_rule(self.0, GameRuleValue::Bool(value));
+        // Clients only advance the time of day on their own while the rule is on
+        if self.0.key == ADVANCE_TIME.key {
+            world.broadcast_time();
+        }
 
         context.sender.send_message(
             &translations::COMMANDS_GAMERULE_SET
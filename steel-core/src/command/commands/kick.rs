@@ -0,0 +1,71 @@
+//! Handler for the "kick" command.
+use crate::command::arguments::player::PlayerArgument;
+use crate::command::arguments::string::StringArgument;
+use crate::command::commands::{
+    CommandExecutor, CommandHandlerBuilder, CommandHandlerDyn, argument,
+};
+use crate::command::context::CommandContext;
+use crate::command::error::CommandError;
+use crate::player::Player;
+use std::sync::Arc;
+use steel_utils::translations;
+use text_components::TextComponent;
+
+/// Handler for the "kick" command.
+#[must_use]
+pub fn command_handler() -> impl CommandHandlerDyn {
+    CommandHandlerBuilder::new(
+        &["kick"],
+        "Kicks players from the server.",
+        "minecraft:command.kick",
+    )
+    .then(
+        argument("targets", PlayerArgument::new())
+            .executes(KickCommandExecutor)
+            .then(argument("reason", StringArgument::greedy()).executes(KickCommandExecutor)),
+    )
+}
+
+struct KickCommandExecutor;
+
+impl CommandExecutor<((), Vec<Arc<Player>>)> for KickCommandExecutor {
+    fn execute(
+        &self,
+        args: ((), Vec<Arc<Player>>),
+        context: &mut CommandContext,
+    ) -> Result<(), CommandError> {
+        let ((), targets) = args;
+        kick(
+            targets,
+            &translations::MULTIPLAYER_DISCONNECT_KICKED.msg().into(),
+            context,
+        );
+        Ok(())
+    }
+}
+
+impl CommandExecutor<(((), Vec<Arc<Player>>), String)> for KickCommandExecutor {
+    fn execute(
+        &self,
+        args: (((), Vec<Arc<Player>>), String),
+        context: &mut CommandContext,
+    ) -> Result<(), CommandError> {
+        let (((), targets), reason) = args;
+        kick(targets, &TextComponent::plain(reason), context);
+        Ok(())
+    }
+}
+
+fn kick(targets: Vec<Arc<Player>>, reason: &TextComponent, context: &CommandContext) {
+    for target in targets {
+        target.connection.disconnect(reason.clone());
+        context.sender.send_message(
+            &translations::COMMANDS_KICK_SUCCESS
+                .message([
+                    TextComponent::plain(target.gameprofile.name.clone()),
+                    reason.clone(),
+                ])
+                .into(),
+        );
+    }
+}
@@ -0,0 +1,104 @@
+//! Handler for the "locate" command.
+use steel_registry::biome::BiomeRef;
+use steel_utils::{BlockPos, translations};
+use text_components::format::Color;
+use text_components::interactivity::{ClickEvent, HoverEvent};
+use text_components::{Modifier, TextComponent};
+
+use crate::command::arguments::biome::BiomeArgument;
+use crate::command::commands::{
+    CommandExecutor, CommandHandlerBuilder, CommandHandlerDyn, argument, literal,
+};
+use crate::command::context::CommandContext;
+use crate::command::error::CommandError;
+
+/// How far from the origin biomes are searched for, in blocks.
+const BIOME_SEARCH_RADIUS: i32 = 6400;
+/// The horizontal distance between sampled columns, in blocks.
+const BIOME_HORIZONTAL_STEP: i32 = 32;
+/// The vertical distance between samples in a column, in blocks.
+const BIOME_VERTICAL_STEP: i32 = 64;
+
+/// Handler for the "locate" command.
+#[must_use]
+pub fn command_handler() -> impl CommandHandlerDyn {
+    CommandHandlerBuilder::new(
+        &["locate"],
+        "Finds the nearest biome of a type.",
+        "minecraft:command.locate",
+    )
+    .then(literal("biome").then(argument("biome", BiomeArgument).executes(LocateBiomeExecutor)))
+}
+
+struct LocateBiomeExecutor;
+
+impl CommandExecutor<((), BiomeRef)> for LocateBiomeExecutor {
+    fn execute(
+        &self,
+        args: ((), BiomeRef),
+        context: &mut CommandContext,
+    ) -> Result<(), CommandError> {
+        let ((), biome) = args;
+        let world = context.get_world()?;
+        let position = context.position.ok_or(CommandError::InvalidRequirement)?;
+        let origin = BlockPos::new(
+            position.x.floor() as i32,
+            position.y.floor() as i32,
+            position.z.floor() as i32,
+        );
+
+        let found = world
+            .chunk_map
+            .world_gen_context
+            .generator
+            .find_closest_biome_3d(
+                origin,
+                BIOME_SEARCH_RADIUS,
+                BIOME_HORIZONTAL_STEP,
+                BIOME_VERTICAL_STEP,
+                |candidate| candidate.key == biome.key,
+            );
+        let Some((pos, _)) = found else {
+            return Err(CommandError::CommandFailed(Box::new(
+                translations::COMMANDS_LOCATE_BIOME_NOT_FOUND
+                    .message([TextComponent::from(biome.key.to_string())])
+                    .into(),
+            )));
+        };
+
+        let dx = (pos.x() - origin.x()) as f32;
+        let dy = (pos.y() - origin.y()) as f32;
+        let dz = (pos.z() - origin.z()) as f32;
+        let distance = (dx * dx + dy * dy + dz * dz).sqrt().floor() as i32;
+        let coordinates = translations::CHAT_SQUARE_BRACKETS
+            .message([translations::CHAT_COORDINATES
+                .message([
+                    TextComponent::from(pos.x().to_string()),
+                    TextComponent::from(pos.y().to_string()),
+                    TextComponent::from(pos.z().to_string()),
+                ])
+                .component()])
+            .component()
+            .color(Color::Green)
+            .click_event(ClickEvent::suggest_command(format!(
+                "/tp @s {} {} {}",
+                pos.x(),
+                pos.y(),
+                pos.z()
+            )))
+            .hover_event(HoverEvent::show_text(
+                &translations::CHAT_COORDINATES_TOOLTIP,
+            ));
+
+        context.sender.send_message(
+            &translations::COMMANDS_LOCATE_BIOME_SUCCESS
+                .message([
+                    TextComponent::from(biome.key.to_string()),
+                    coordinates,
+                    TextComponent::from(distance.to_string()),
+                ])
+                .into(),
+        );
+        Ok(())
+    }
+}
@@ -1,13 +1,23 @@
 //! This module contains the command building structs.
+pub mod ban;
+pub mod ban_ip;
+pub mod deop;
 pub mod execute;
 pub mod flyspeed;
 pub mod gamemode;
 pub mod gamerule;
+pub mod kick;
+pub mod locate;
+pub mod op;
+pub mod pardon;
+pub mod pardon_ip;
 pub mod seed;
 pub mod stop;
 pub mod tellraw;
 pub mod tick;
+pub mod time;
 pub mod weather;
+pub mod whitelist;
 
 use std::marker::PhantomData;
 use std::ops::Not;
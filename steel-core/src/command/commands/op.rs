@@ -0,0 +1,61 @@
+//! Handler for the "op" command.
+use crate::command::arguments::player::PlayerArgument;
+use crate::command::commands::{
+    CommandExecutor, CommandHandlerBuilder, CommandHandlerDyn, argument,
+};
+use crate::command::context::CommandContext;
+use crate::command::error::CommandError;
+use crate::player::Player;
+use crate::server::permissions::PermissionLevel;
+use std::sync::Arc;
+use steel_utils::translations;
+use text_components::TextComponent;
+
+/// Handler for the "op" command.
+#[must_use]
+pub fn command_handler() -> impl CommandHandlerDyn {
+    CommandHandlerBuilder::new(
+        &["op"],
+        "Grants operator status to players.",
+        "minecraft:command.op",
+    )
+    .then(argument("targets", PlayerArgument::new()).executes(OpCommandExecutor))
+}
+
+struct OpCommandExecutor;
+
+impl CommandExecutor<((), Vec<Arc<Player>>)> for OpCommandExecutor {
+    fn execute(
+        &self,
+        args: ((), Vec<Arc<Player>>),
+        context: &mut CommandContext,
+    ) -> Result<(), CommandError> {
+        let ((), targets) = args;
+        let mut changed = 0;
+
+        for target in targets {
+            let profile = &target.gameprofile;
+            if !context
+                .server
+                .permissions
+                .op(profile.id, &profile.name, PermissionLevel::Owners)
+            {
+                continue;
+            }
+            changed += 1;
+            context.server.send_commands(&target);
+            context.sender.send_message(
+                &translations::COMMANDS_OP_SUCCESS
+                    .message([TextComponent::plain(profile.name.clone())])
+                    .into(),
+            );
+        }
+
+        if changed == 0 {
+            return Err(CommandError::CommandFailed(Box::new(
+                translations::COMMANDS_OP_FAILED.msg().into(),
+            )));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,58 @@
+//! Handler for the "pardon" command.
+use crate::command::arguments::game_profile::GameProfileArgument;
+use crate::command::commands::{
+    CommandExecutor, CommandHandlerBuilder, CommandHandlerDyn, argument,
+};
+use crate::command::context::CommandContext;
+use crate::command::error::CommandError;
+use crate::server::user_lists::ListedPlayer;
+use steel_utils::translations;
+use text_components::TextComponent;
+
+/// Handler for the "pardon" command.
+#[must_use]
+pub fn command_handler() -> impl CommandHandlerDyn {
+    CommandHandlerBuilder::new(
+        &["pardon"],
+        "Removes players from the ban list.",
+        "minecraft:command.pardon",
+    )
+    .then(argument("targets", GameProfileArgument).executes(PardonCommandExecutor))
+}
+
+struct PardonCommandExecutor;
+
+impl CommandExecutor<((), Vec<ListedPlayer>)> for PardonCommandExecutor {
+    fn execute(
+        &self,
+        args: ((), Vec<ListedPlayer>),
+        context: &mut CommandContext,
+    ) -> Result<(), CommandError> {
+        let ((), targets) = args;
+        let mut pardoned = 0;
+
+        for target in targets {
+            if !context
+                .server
+                .user_lists
+                .banned_players
+                .remove(|entry| entry.player.same_player(&target))
+            {
+                continue;
+            }
+            pardoned += 1;
+            context.sender.send_message(
+                &translations::COMMANDS_PARDON_SUCCESS
+                    .message([TextComponent::plain(target.name)])
+                    .into(),
+            );
+        }
+
+        if pardoned == 0 {
+            return Err(CommandError::CommandFailed(Box::new(
+                translations::COMMANDS_PARDON_FAILED.msg().into(),
+            )));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,55 @@
+//! Handler for the "pardon-ip" command.
+use crate::command::arguments::string::StringArgument;
+use crate::command::commands::{
+    CommandExecutor, CommandHandlerBuilder, CommandHandlerDyn, argument,
+};
+use crate::command::context::CommandContext;
+use crate::command::error::CommandError;
+use std::net::IpAddr;
+use steel_utils::translations;
+use text_components::TextComponent;
+
+/// Handler for the "pardon-ip" command.
+#[must_use]
+pub fn command_handler() -> impl CommandHandlerDyn {
+    CommandHandlerBuilder::new(
+        &["pardon-ip"],
+        "Removes an IP address from the ban list.",
+        "minecraft:command.pardon-ip",
+    )
+    .then(argument("target", StringArgument::word()).executes(PardonIpCommandExecutor))
+}
+
+struct PardonIpCommandExecutor;
+
+impl CommandExecutor<((), String)> for PardonIpCommandExecutor {
+    fn execute(
+        &self,
+        args: ((), String),
+        context: &mut CommandContext,
+    ) -> Result<(), CommandError> {
+        let ((), target) = args;
+        let Ok(ip) = target.parse::<IpAddr>() else {
+            return Err(CommandError::CommandFailed(Box::new(
+                translations::COMMANDS_PARDONIP_INVALID.msg().into(),
+            )));
+        };
+
+        if !context
+            .server
+            .user_lists
+            .banned_ips
+            .remove(|entry| entry.ip == ip)
+        {
+            return Err(CommandError::CommandFailed(Box::new(
+                translations::COMMANDS_PARDONIP_FAILED.msg().into(),
+            )));
+        }
+        context.sender.send_message(
+            &translations::COMMANDS_PARDONIP_SUCCESS
+                .message([TextComponent::plain(ip.to_string())])
+                .into(),
+        );
+        Ok(())
+    }
+}
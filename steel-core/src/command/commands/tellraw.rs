@@ -36,7 +36,7 @@ impl CommandExecutor<(((), Vec<Arc<Player>>), TextComponent)> for TellrawCommand
         let sender = match &context.sender {
             CommandSender::Player(player) => &player.gameprofile.name,
             CommandSender::Console => "Console",
-            CommandSender::Rcon => "Rcon",
+            CommandSender::Rcon(_) => "Rcon",
         };
         log::info!("{}'s tellraw: {:p}", sender, args.1);
         for player in args.0.1 {
@@ -0,0 +1,116 @@
+//! Handler for the "time" command.
+use steel_utils::translations;
+use text_components::TextComponent;
+use text_components::translation::Translation;
+
+use crate::command::arguments::time::TimeArgument;
+use crate::command::commands::{
+    CommandExecutor, CommandHandlerBuilder, CommandHandlerDyn, argument, literal,
+};
+use crate::command::context::CommandContext;
+use crate::command::error::CommandError;
+
+/// Length of a Minecraft day in ticks.
+const TICKS_PER_DAY: i64 = 24_000;
+
+/// Handler for the "time" command.
+#[must_use]
+pub fn command_handler() -> impl CommandHandlerDyn {
+    CommandHandlerBuilder::new(
+        &["time"],
+        "Changes or queries the time of day.",
+        "minecraft:command.time",
+    )
+    // /time set (day|noon|night|midnight|<time>)
+    .then(
+        literal("set")
+            .then(literal("day").executes(TimeSetPresetExecutor(1_000)))
+            .then(literal("noon").executes(TimeSetPresetExecutor(6_000)))
+            .then(literal("night").executes(TimeSetPresetExecutor(13_000)))
+            .then(literal("midnight").executes(TimeSetPresetExecutor(18_000)))
+            .then(argument("time", TimeArgument).executes(TimeSetExecutor)),
+    )
+    // /time add <time>
+    .then(literal("add").then(argument("time", TimeArgument).executes(TimeAddExecutor)))
+    // /time query (daytime|gametime|day)
+    .then(
+        literal("query")
+            .then(literal("daytime").executes(TimeQueryExecutor::DayTime))
+            .then(literal("gametime").executes(TimeQueryExecutor::GameTime))
+            .then(literal("day").executes(TimeQueryExecutor::Day)),
+    )
+}
+
+/// Sends the time a command changed or queried.
+fn send_time(context: &CommandContext, translation: &Translation<1>, time: i64) {
+    context.sender.send_message(
+        &translation
+            .message([TextComponent::from(time.to_string())])
+            .into(),
+    );
+}
+
+/// Sets the time of day of every world, like vanilla does.
+fn set_time(context: &CommandContext, time: i64) {
+    for world in &context.server.worlds {
+        world.set_day_time(time);
+    }
+    send_time(context, &translations::COMMANDS_TIME_SET, time);
+}
+
+// /time set (day|noon|night|midnight)
+struct TimeSetPresetExecutor(i64);
+
+impl CommandExecutor<()> for TimeSetPresetExecutor {
+    fn execute(&self, _args: (), context: &mut CommandContext) -> Result<(), CommandError> {
+        set_time(context, self.0);
+        Ok(())
+    }
+}
+
+// /time set <time>
+struct TimeSetExecutor;
+
+impl CommandExecutor<((), i32)> for TimeSetExecutor {
+    fn execute(&self, args: ((), i32), context: &mut CommandContext) -> Result<(), CommandError> {
+        let ((), time) = args;
+        set_time(context, i64::from(time));
+        Ok(())
+    }
+}
+
+// /time add <time>
+struct TimeAddExecutor;
+
+impl CommandExecutor<((), i32)> for TimeAddExecutor {
+    fn execute(&self, args: ((), i32), context: &mut CommandContext) -> Result<(), CommandError> {
+        let ((), amount) = args;
+        for world in &context.server.worlds {
+            world.set_day_time(world.day_time() + i64::from(amount));
+        }
+
+        let time = context.get_world()?.day_time() % TICKS_PER_DAY;
+        send_time(context, &translations::COMMANDS_TIME_SET, time);
+        Ok(())
+    }
+}
+
+// /time query (daytime|gametime|day)
+enum TimeQueryExecutor {
+    DayTime,
+    GameTime,
+    Day,
+}
+
+impl CommandExecutor<()> for TimeQueryExecutor {
+    fn execute(&self, _args: (), context: &mut CommandContext) -> Result<(), CommandError> {
+        let world = context.get_world()?;
+        let time = match self {
+            Self::DayTime => world.day_time() % TICKS_PER_DAY,
+            Self::GameTime => world.game_time() % i64::from(i32::MAX),
+            Self::Day => world.day_time() / TICKS_PER_DAY % i64::from(i32::MAX),
+        };
+        send_time(context, &translations::COMMANDS_TIME_QUERY, time);
+        Ok(())
+    }
+}
@@ -5,6 +5,7 @@ use crate::command::commands::{
 };
 use crate::command::context::CommandContext;
 use crate::command::error::CommandError;
+use crate::world::{RAIN_DELAY, RAIN_DURATION, THUNDER_DURATION};
 use steel_utils::translations;
 
 /// Handler for the "weather" command.
@@ -12,7 +13,7 @@ use steel_utils::translations;
 pub fn command_handler() -> impl CommandHandlerDyn {
     CommandHandlerBuilder::new(
         &["weather"],
-        "Changes the weather in the current dimension.",
+        "Changes the weather.",
         "minecraft:command.weather",
     )
     .then(
@@ -41,9 +42,9 @@ enum WeatherCommandExecutor {
 impl CommandExecutor<()> for WeatherCommandExecutor {
     fn execute(&self, _args: (), context: &mut CommandContext) -> Result<(), CommandError> {
         let duration = match self {
-            WeatherCommandExecutor::Clear => rand::random_range(12_000..=180_000),
-            WeatherCommandExecutor::Rain => rand::random_range(12_000..=24_000),
-            WeatherCommandExecutor::Thunder => rand::random_range(3_600..=15_600),
+            WeatherCommandExecutor::Clear => rand::random_range(RAIN_DELAY),
+            WeatherCommandExecutor::Rain => rand::random_range(RAIN_DURATION),
+            WeatherCommandExecutor::Thunder => rand::random_range(THUNDER_DURATION),
         };
 
         self.execute(((), duration), context)
@@ -52,31 +53,25 @@ impl CommandExecutor<()> for WeatherCommandExecutor {
 
 impl CommandExecutor<((), i32)> for WeatherCommandExecutor {
     fn execute(&self, args: ((), i32), context: &mut CommandContext) -> Result<(), CommandError> {
-        let ((), _duration) = args;
-        let _world = context
-            .world
-            .as_ref()
-            .ok_or(CommandError::InvalidRequirement)?;
+        let ((), duration) = args;
+        // Like vanilla, the weather lives in the overworld wherever the command is run
+        let world = context.server.overworld();
 
-        // TODO: Apply the duration to the world's weather system once weather state is implemented
-
-        match self {
+        let message = match self {
             WeatherCommandExecutor::Clear => {
-                context
-                    .sender
-                    .send_message(&translations::COMMANDS_WEATHER_SET_CLEAR.msg().into());
+                world.set_weather_parameters(duration, 0, false, false);
+                &translations::COMMANDS_WEATHER_SET_CLEAR
             }
             WeatherCommandExecutor::Rain => {
-                context
-                    .sender
-                    .send_message(&translations::COMMANDS_WEATHER_SET_RAIN.msg().into());
+                world.set_weather_parameters(0, duration, true, false);
+                &translations::COMMANDS_WEATHER_SET_RAIN
             }
             WeatherCommandExecutor::Thunder => {
-                context
-                    .sender
-                    .send_message(&translations::COMMANDS_WEATHER_SET_THUNDER.msg().into());
+                world.set_weather_parameters(0, duration, true, true);
+                &translations::COMMANDS_WEATHER_SET_THUNDER
             }
-        }
+        };
+        context.sender.send_message(&message.msg().into());
 
         Ok(())
     }
@@ -0,0 +1,205 @@
+//! Handler for the "whitelist" command.
+use crate::command::arguments::game_profile::GameProfileArgument;
+use crate::command::commands::{
+    CommandExecutor, CommandHandlerBuilder, CommandHandlerDyn, argument, literal,
+};
+use crate::command::context::CommandContext;
+use crate::command::error::CommandError;
+use crate::config::STEEL_CONFIG;
+use crate::server::Server;
+use crate::server::permissions::PermissionLevel;
+use crate::server::user_lists::ListedPlayer;
+use steel_utils::translations;
+use text_components::TextComponent;
+
+/// Handler for the "whitelist" command.
+#[must_use]
+pub fn command_handler() -> impl CommandHandlerDyn {
+    CommandHandlerBuilder::new(
+        &["whitelist"],
+        "Manages the server whitelist.",
+        "minecraft:command.whitelist",
+    )
+    .then(literal("on").executes(WhitelistOnExecutor))
+    .then(literal("off").executes(WhitelistOffExecutor))
+    .then(literal("list").executes(WhitelistListExecutor))
+    .then(
+        literal("add")
+            .then(argument("targets", GameProfileArgument).executes(WhitelistAddExecutor)),
+    )
+    .then(
+        literal("remove")
+            .then(argument("targets", GameProfileArgument).executes(WhitelistRemoveExecutor)),
+    )
+    .then(literal("reload").executes(WhitelistReloadExecutor))
+}
+
+/// Kicks online players who may no longer join, if the whitelist is enforced.
+///
+/// Matches vanilla's `MinecraftServer.kickUnlistedPlayers()`.
+fn kick_unlisted_players(server: &Server) {
+    if !STEEL_CONFIG.enforce_whitelist || !server.user_lists.is_whitelist_enabled() {
+        return;
+    }
+    for player in server.get_players() {
+        let profile = &player.gameprofile;
+        if server.permissions.op_level(profile.id) == PermissionLevel::All
+            && !server.user_lists.is_whitelisted(profile.id, &profile.name)
+        {
+            player
+                .connection
+                .disconnect(translations::MULTIPLAYER_DISCONNECT_NOT_WHITELISTED.msg());
+        }
+    }
+}
+
+// /whitelist on
+struct WhitelistOnExecutor;
+impl CommandExecutor<()> for WhitelistOnExecutor {
+    fn execute(&self, _args: (), context: &mut CommandContext) -> Result<(), CommandError> {
+        if !context.server.user_lists.set_whitelist_enabled(true) {
+            return Err(CommandError::CommandFailed(Box::new(
+                translations::COMMANDS_WHITELIST_ALREADY_ON.msg().into(),
+            )));
+        }
+        context
+            .sender
+            .send_message(&translations::COMMANDS_WHITELIST_ENABLED.msg().into());
+        kick_unlisted_players(&context.server);
+        Ok(())
+    }
+}
+
+// /whitelist off
+struct WhitelistOffExecutor;
+impl CommandExecutor<()> for WhitelistOffExecutor {
+    fn execute(&self, _args: (), context: &mut CommandContext) -> Result<(), CommandError> {
+        if !context.server.user_lists.set_whitelist_enabled(false) {
+            return Err(CommandError::CommandFailed(Box::new(
+                translations::COMMANDS_WHITELIST_ALREADY_OFF.msg().into(),
+            )));
+        }
+        context
+            .sender
+            .send_message(&translations::COMMANDS_WHITELIST_DISABLED.msg().into());
+        Ok(())
+    }
+}
+
+// /whitelist list
+struct WhitelistListExecutor;
+impl CommandExecutor<()> for WhitelistListExecutor {
+    fn execute(&self, _args: (), context: &mut CommandContext) -> Result<(), CommandError> {
+        let entries = context.server.user_lists.whitelist.entries();
+        if entries.is_empty() {
+            context
+                .sender
+                .send_message(&translations::COMMANDS_WHITELIST_NONE.msg().into());
+            return Ok(());
+        }
+
+        let names = entries
+            .iter()
+            .map(|entry| entry.name.as_str())
+            .collect::<Vec<_>>()
+            .join(", ");
+        context.sender.send_message(
+            &translations::COMMANDS_WHITELIST_LIST
+                .message([
+                    TextComponent::plain(entries.len().to_string()),
+                    TextComponent::plain(names),
+                ])
+                .into(),
+        );
+        Ok(())
+    }
+}
+
+// /whitelist add <targets>
+struct WhitelistAddExecutor;
+impl CommandExecutor<((), Vec<ListedPlayer>)> for WhitelistAddExecutor {
+    fn execute(
+        &self,
+        args: ((), Vec<ListedPlayer>),
+        context: &mut CommandContext,
+    ) -> Result<(), CommandError> {
+        let ((), targets) = args;
+        let mut added = 0;
+
+        for target in targets {
+            let name = target.name.clone();
+            if !context
+                .server
+                .user_lists
+                .whitelist
+                .add(target.clone(), |existing| existing.same_player(&target))
+            {
+                continue;
+            }
+            added += 1;
+            context.sender.send_message(
+                &translations::COMMANDS_WHITELIST_ADD_SUCCESS
+                    .message([TextComponent::plain(name)])
+                    .into(),
+            );
+        }
+
+        if added == 0 {
+            return Err(CommandError::CommandFailed(Box::new(
+                translations::COMMANDS_WHITELIST_ADD_FAILED.msg().into(),
+            )));
+        }
+        Ok(())
+    }
+}
+
+// /whitelist remove <targets>
+struct WhitelistRemoveExecutor;
+impl CommandExecutor<((), Vec<ListedPlayer>)> for WhitelistRemoveExecutor {
+    fn execute(
+        &self,
+        args: ((), Vec<ListedPlayer>),
+        context: &mut CommandContext,
+    ) -> Result<(), CommandError> {
+        let ((), targets) = args;
+        let mut removed = 0;
+
+        for target in targets {
+            if !context
+                .server
+                .user_lists
+                .whitelist
+                .remove(|entry| entry.same_player(&target))
+            {
+                continue;
+            }
+            removed += 1;
+            context.sender.send_message(
+                &translations::COMMANDS_WHITELIST_REMOVE_SUCCESS
+                    .message([TextComponent::plain(target.name)])
+                    .into(),
+            );
+        }
+
+        if removed == 0 {
+            return Err(CommandError::CommandFailed(Box::new(
+                translations::COMMANDS_WHITELIST_REMOVE_FAILED.msg().into(),
+            )));
+        }
+        kick_unlisted_players(&context.server);
+        Ok(())
+    }
+}
+
+// /whitelist reload
+struct WhitelistReloadExecutor;
+impl CommandExecutor<()> for WhitelistReloadExecutor {
+    fn execute(&self, _args: (), context: &mut CommandContext) -> Result<(), CommandError> {
+        context.server.user_lists.whitelist.reload();
+        context
+            .sender
+            .send_message(&translations::COMMANDS_WHITELIST_RELOADED.msg().into());
+        kick_unlisted_players(&context.server);
+        Ok(())
+    }
+}
@@ -10,7 +10,7 @@ use std::sync::Arc;
 use steel_protocol::packets::game::{CCommandSuggestions, CCommands, CommandNode, SuggestionEntry};
 use text_components::{Modifier, TextComponent, format::Color};
 
-use crate::command::commands::CommandHandlerDyn;
+use crate::command::commands::{CommandHandlerDyn, SuggestionResult};
 use crate::command::context::CommandContext;
 use crate::command::error::CommandError;
 use crate::command::sender::CommandSender;
@@ -29,15 +29,25 @@ impl CommandDispatcher {
     #[must_use]
     pub fn new() -> Self {
         let dispatcher = CommandDispatcher::new_empty();
+        dispatcher.register(commands::ban::command_handler());
+        dispatcher.register(commands::ban_ip::command_handler());
+        dispatcher.register(commands::deop::command_handler());
         dispatcher.register(commands::execute::command_handler());
         dispatcher.register(commands::flyspeed::command_handler());
         dispatcher.register(commands::gamemode::command_handler());
         dispatcher.register(commands::gamerule::command_handler());
+        dispatcher.register(commands::kick::command_handler());
+        dispatcher.register(commands::locate::command_handler());
+        dispatcher.register(commands::op::command_handler());
+        dispatcher.register(commands::pardon::command_handler());
+        dispatcher.register(commands::pardon_ip::command_handler());
         dispatcher.register(commands::seed::command_handler());
         dispatcher.register(commands::stop::command_handler());
         dispatcher.register(commands::tick::command_handler());
+        dispatcher.register(commands::time::command_handler());
         dispatcher.register(commands::weather::command_handler());
         dispatcher.register(commands::tellraw::command_handler());
+        dispatcher.register(commands::whitelist::command_handler());
         dispatcher
     }
 
@@ -97,12 +107,9 @@ impl CommandDispatcher {
             )));
         };
 
-        // TODO: Implement permission checking logic here
-        // if let CommandSender::Player(ref player) = sender
-        //     && !server.player_has_permission(player, &handler.permission)
-        // {
-        //     return Err(PermissionDenied);
-        // };
+        if !context.sender.has_permission(server, handler.permission()) {
+            return Err(CommandError::PermissionDenied);
+        }
 
         handler.execute(command_args, context, server)
     }
@@ -126,19 +133,20 @@ impl CommandDispatcher {
         Ok((command, command_args.split_whitespace().collect()))
     }
 
-    /// Generates the `CCommands` packet, containing the usage information of every registered commands.
-    pub fn get_commands(&self) -> CCommands {
+    /// Generates the `CCommands` packet, containing the usage information of every command
+    /// the sender may use.
+    pub fn get_commands(&self, sender: &CommandSender, server: &Server) -> CCommands {
         let mut nodes = Vec::with_capacity(self.handlers.len() + 1);
         nodes.push(CommandNode::new_root());
 
         let mut root_children = Vec::with_capacity(self.handlers.len());
         self.handlers.iter_sync(|command, handler| {
-            if *command != handler.names()[0] {
+            if *command != handler.names()[0]
+                || !sender.has_permission(server, handler.permission())
+            {
                 return true;
             }
 
-            // TODO: Implement permission checking logic here
-
             handler.usage(&mut nodes, &mut root_children);
             true
         });
@@ -178,6 +186,27 @@ impl CommandDispatcher {
         // Remove leading slash if present
         let command = command.strip_prefix('/').unwrap_or(command);
 
+        let packet = match self.suggest(CommandSender::Player(Arc::clone(player)), command, server)
+        {
+            // Adjust start position to account for leading slash
+            Some(result) => {
+                CCommandSuggestions::new(id, result.start + 1, result.length, result.suggestions)
+            }
+            // No suggestions
+            None => CCommandSuggestions::new(id, 0, 0, vec![]),
+        };
+        player.connection.send_packet(packet);
+    }
+
+    /// Gets the suggestions for a partially typed command without its leading slash.
+    ///
+    /// Positions in the result are relative to the start of `command`.
+    pub fn suggest(
+        &self,
+        sender: CommandSender,
+        command: &str,
+        server: Arc<Server>,
+    ) -> Option<SuggestionResult> {
         // Split into parts, preserving trailing space as empty string
         let mut parts: Vec<&str> = command.split(' ').collect();
 
@@ -191,26 +220,19 @@ impl CommandDispatcher {
         // If empty or typing command name, suggest command names
         if parts.is_empty() || (parts.len() == 1 && !has_trailing_space) {
             let prefix = parts.first().copied().unwrap_or("");
-            let suggestions = self.get_command_suggestions(prefix);
-            // Start position is 1 (after the slash)
-            player.connection.send_packet(CCommandSuggestions::new(
-                id,
-                1,
-                prefix.len() as i32,
-                suggestions,
-            ));
-            return;
+            return Some(SuggestionResult {
+                suggestions: self.get_command_suggestions(prefix, &sender, &server),
+                start: 0,
+                length: prefix.len() as i32,
+            });
         }
 
-        // Get the command handler
+        // Get the command handler; unknown commands have no suggestions
         let command_name = parts[0];
-        let Some(handler) = self.handlers.read_sync(command_name, |_, v| v.clone()) else {
-            // Unknown command - no suggestions
-            player
-                .connection
-                .send_packet(CCommandSuggestions::new(id, 0, 0, vec![]));
-            return;
-        };
+        let handler = self.handlers.read_sync(command_name, |_, v| v.clone())?;
+        if !sender.has_permission(&server, handler.permission()) {
+            return None;
+        }
 
         // Calculate where args start (after "command_name ")
         let args_start_pos = command_name.len() + 1; // +1 for space
@@ -219,33 +241,28 @@ impl CommandDispatcher {
         let args = &parts[1..];
 
         // Create context for suggestion
-        let mut context = CommandContext::new(CommandSender::Player(Arc::clone(player)), server);
+        let mut context = CommandContext::new(sender, server);
 
         // Get suggestions from handler
-        if let Some(result) = handler.suggest(args, args_start_pos, &mut context) {
-            // Adjust start position to account for leading slash
-            player.connection.send_packet(CCommandSuggestions::new(
-                id,
-                result.start + 1, // +1 for leading slash
-                result.length,
-                result.suggestions,
-            ));
-        } else {
-            // No suggestions
-            player
-                .connection
-                .send_packet(CCommandSuggestions::new(id, 0, 0, vec![]));
-        }
+        handler.suggest(args, args_start_pos, &mut context)
     }
 
-    /// Gets command name suggestions matching the given prefix.
-    fn get_command_suggestions(&self, prefix: &str) -> Vec<SuggestionEntry> {
+    /// Gets the names of the commands the sender may use matching the given prefix.
+    fn get_command_suggestions(
+        &self,
+        prefix: &str,
+        sender: &CommandSender,
+        server: &Server,
+    ) -> Vec<SuggestionEntry> {
         let mut suggestions = Vec::new();
         let prefix_lower = prefix.to_lowercase();
 
         self.handlers.iter_sync(|name, handler| {
             // Only include primary command names (not aliases)
-            if *name == handler.names()[0] && name.to_lowercase().starts_with(&prefix_lower) {
+            if *name == handler.names()[0]
+                && name.to_lowercase().starts_with(&prefix_lower)
+                && sender.has_permission(server, handler.permission())
+            {
                 suggestions.push(SuggestionEntry::new(*name));
             }
             true
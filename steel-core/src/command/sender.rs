@@ -1,8 +1,10 @@
 //! Module defining the sender of a command.
 use std::{fmt, sync::Arc};
+use steel_utils::locks::SyncMutex;
 use text_components::TextComponent;
 
 use crate::player::Player;
+use crate::server::Server;
 
 /// The sender of a command.
 #[derive(Clone)]
@@ -11,8 +13,9 @@ pub enum CommandSender {
     Player(Arc<Player>),
     /// The command was sent via the server's console.
     Console,
-    /// The command was sent via Rcon.
-    Rcon,
+    /// The command was sent via Rcon. Messages are collected in the buffer and sent
+    /// back as the response.
+    Rcon(Arc<SyncMutex<String>>),
 }
 
 impl CommandSender {
@@ -25,13 +28,29 @@ impl CommandSender {
         }
     }
 
+    /// Returns whether the sender may use something guarded by the given permission.
+    ///
+    /// The console and RCON may use everything.
+    #[must_use]
+    pub fn has_permission(&self, server: &Server, permission: &str) -> bool {
+        match self {
+            Self::Player(player) => server
+                .permissions
+                .has_permission(player.gameprofile.id, permission),
+            Self::Console | Self::Rcon(_) => true,
+        }
+    }
+
     /// Sends a system message to the command sender.
     pub fn send_message(&self, text: &TextComponent) {
         match self {
             Self::Player(player) => player.send_message(text),
             Self::Console => log::info!("{text:p}"),
-            // TODO: Implement Rcon message sending
-            Self::Rcon => unimplemented!(),
+            Self::Rcon(output) => {
+                let mut output = output.lock();
+                output.push_str(&format!("{text:p}"));
+                output.push('\n');
+            }
         }
     }
 }
@@ -44,7 +63,7 @@ impl fmt::Display for CommandSender {
             match self {
                 Self::Player(p) => &p.gameprofile.name,
                 Self::Console => "Server",
-                Self::Rcon => "Rcon",
+                Self::Rcon(_) => "Rcon",
             }
         )
     }
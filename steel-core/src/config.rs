@@ -3,6 +3,7 @@
 //! The `ServerConfig` struct is defined here, but loading is handled by the `steel` crate.
 //! Steel-core accesses config via `STEEL_CONFIG` after steel initializes it.
 
+use std::net::IpAddr;
 use std::ops::Deref;
 use std::sync::OnceLock;
 
@@ -12,6 +13,8 @@ use steel_protocol::packets::config::{CServerLinks, Link, ServerLinksType};
 use steel_utils::codec::Or;
 use text_components::TextComponent;
 
+use crate::chunk::flat_settings::FlatSettings;
+
 /// Reference to the server configuration.
 ///
 /// This is initialized by the `steel` crate during server startup.
@@ -104,6 +107,74 @@ impl ServerLinks {
     }
 }
 
+/// RCON (remote console) configuration
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct RconConfig {
+    /// Enable the RCON listener
+    pub enable: bool,
+    /// The port the RCON listener binds to
+    pub port: u16,
+    /// The password RCON clients have to authenticate with
+    pub password: String,
+}
+
+impl Default for RconConfig {
+    fn default() -> Self {
+        Self {
+            enable: false,
+            port: 25575,
+            password: String::new(),
+        }
+    }
+}
+
+/// Query (GameSpy4 UDP) listener configuration
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct QueryConfig {
+    /// Enable the query listener
+    pub enable: bool,
+    /// The UDP port the query listener binds to
+    pub port: u16,
+}
+
+impl Default for QueryConfig {
+    fn default() -> Self {
+        Self {
+            enable: false,
+            port: 25565,
+        }
+    }
+}
+
+/// How a proxy in front of the server forwards the real player information.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ForwardingMode {
+    /// Players connect directly, nothing is forwarded
+    #[default]
+    None,
+    /// BungeeCord legacy forwarding, appended to the handshake hostname
+    Bungeecord,
+    /// Velocity modern forwarding, answered in a signed login plugin query
+    Velocity,
+}
+
+/// Proxy settings
+#[derive(Debug, Clone, Deserialize, Default)]
+#[serde(default)]
+pub struct ProxyConfig {
+    /// How the proxy forwards player addresses and profiles
+    pub forwarding: ForwardingMode,
+    /// The secret Velocity signs forwarded information with
+    pub velocity_secret: String,
+    /// Whether connections from trusted proxies start with a HAProxy PROXY protocol header
+    pub proxy_protocol: bool,
+    /// The addresses PROXY protocol headers are accepted from
+    pub trusted_proxies: Vec<IpAddr>,
+}
+
 /// The server configuration.
 #[derive(Debug, Clone, Deserialize)]
 pub struct ServerConfig {
@@ -115,8 +186,15 @@ pub struct ServerConfig {
     pub server_port: u16,
     /// The seed for the world generator.
     pub seed: String,
+    /// The superflat layers for newly created worlds, or `None` for the usual terrain.
+    /// Existing worlds keep the generator stored in their `level.json`.
+    #[serde(default)]
+    pub flat_world: Option<FlatSettings>,
     /// The maximum number of players that can be on the server at once.
     pub max_players: u32,
+    /// Whether to leave out the list of online players from server list pings.
+    #[serde(default)]
+    pub hide_online_players: bool,
     /// The view distance of the server.
     pub view_distance: u8,
     /// The simulation distance of the server.
@@ -133,8 +211,34 @@ pub struct ServerConfig {
     pub favicon: String,
     /// Whether to enforce secure chat.
     pub enforce_secure_chat: bool,
+    /// Whether only whitelisted players and operators may join.
+    #[serde(default)]
+    pub whitelist: bool,
+    /// Whether players who are not whitelisted get kicked when the whitelist is turned on
+    /// or reloaded.
+    #[serde(default)]
+    pub enforce_whitelist: bool,
     /// The compression settings for the server.
     pub compression: Option<CompressionInfo>,
     /// All settings and configurations for server links
     pub server_links: Option<ServerLinks>,
+    /// Remote console settings, or `None` to disable RCON
+    #[serde(default)]
+    pub rcon: Option<RconConfig>,
+    /// Query listener settings, or `None` to disable the query protocol
+    #[serde(default)]
+    pub query: Option<QueryConfig>,
+    /// Proxy settings, or `None` when players connect directly
+    #[serde(default)]
+    pub proxy: Option<ProxyConfig>,
+}
+
+impl ServerConfig {
+    /// Returns how player information is forwarded by a proxy, if at all.
+    #[must_use]
+    pub fn forwarding(&self) -> ForwardingMode {
+        self.proxy
+            .as_ref()
+            .map_or(ForwardingMode::None, |proxy| proxy.forwarding)
+    }
 }
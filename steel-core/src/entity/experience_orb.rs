@@ -0,0 +1,404 @@
+//! Experience orb entities.
+//!
+//! Based on vanilla's `ExperienceOrb`: orbs fall, drift towards the nearest player
+//! within [`FOLLOW_RANGE`] blocks, give their value to the first player that touches
+//! them and despawn after [`LIFETIME`] ticks.
+
+use std::sync::atomic::{AtomicBool, AtomicI32, Ordering};
+use std::sync::{Arc, Weak};
+
+use simdnbt::borrow::BaseNbtCompound as BorrowedNbtCompound;
+use simdnbt::borrow::NbtCompound as NbtCompoundView;
+use simdnbt::owned::NbtCompound;
+use steel_protocol::packets::game::CTakeItemEntity;
+use steel_registry::blocks::block_state_ext::BlockStateExt;
+use steel_registry::blocks::shapes::AABBd;
+use steel_registry::entity_data::DataValue;
+use steel_registry::entity_types::EntityTypeRef;
+use steel_registry::vanilla_entities;
+use steel_registry::vanilla_entity_data::ExperienceOrbEntityData;
+use steel_utils::locks::SyncMutex;
+use steel_utils::math::Vector3;
+use steel_utils::types::GameType;
+use steel_utils::{BlockPos, ChunkPos, SectionPos};
+use uuid::Uuid;
+
+use crate::entity::{Entity, LivingEntity, next_entity_id, read_entity_base, save_entity_base};
+use crate::physics::{
+    CollisionWorld, EntityPhysicsState, LAVA_TAG, MoverType, WATER_TAG, WorldCollisionProvider,
+    is_eye_in_fluid, move_entity, update_in_fluid_state,
+};
+use crate::player::Player;
+use crate::world::World;
+
+/// Number of ticks an orb lives before despawning (5 minutes).
+pub const LIFETIME: i32 = 6000;
+/// Distance in blocks within which orbs move towards a player.
+pub const FOLLOW_RANGE: f64 = 8.0;
+
+/// Downward acceleration applied every tick.
+const GRAVITY: f64 = 0.03;
+
+/// The orb sizes an amount of experience is split into, largest first.
+///
+/// Matches vanilla's `ExperienceOrb.getExperienceValue()`.
+const ORB_VALUES: [i32; 10] = [2477, 1237, 617, 307, 149, 73, 37, 17, 7, 3];
+
+/// A ball of experience points lying in the world.
+pub struct ExperienceOrb {
+    /// The entity ID.
+    id: i32,
+    /// The entity UUID.
+    uuid: Uuid,
+    /// Position, velocity and collision state.
+    physics: SyncMutex<EntityPhysicsState>,
+    /// Yaw the orb was spawned with.
+    yaw: f32,
+    /// Synchronized entity data, holding the orb's value.
+    entity_data: SyncMutex<ExperienceOrbEntityData>,
+    /// Ticks since the orb was spawned.
+    age: AtomicI32,
+    /// How many orbs of this value this entity stands for.
+    count: AtomicI32,
+    /// The player the orb is moving towards.
+    following: SyncMutex<Weak<Player>>,
+    /// Whether the orb has been removed from the world.
+    removed: AtomicBool,
+}
+
+impl ExperienceOrb {
+    /// Creates an orb worth `value` points with a small random push.
+    #[must_use]
+    pub fn new(position: Vector3<f64>, value: i32) -> Self {
+        let mut physics = EntityPhysicsState::new(position, vanilla_entities::EXPERIENCE_ORB);
+        physics.velocity = Vector3::new(
+            (rand::random::<f64>() * 0.2 - 0.1) * 2.0,
+            rand::random::<f64>() * 0.2 * 2.0,
+            (rand::random::<f64>() * 0.2 - 0.1) * 2.0,
+        );
+
+        let mut entity_data = ExperienceOrbEntityData::new();
+        entity_data.value.set(value);
+
+        Self {
+            id: next_entity_id(),
+            uuid: Uuid::new_v4(),
+            physics: SyncMutex::new(physics),
+            yaw: rand::random::<f32>() * 360.0,
+            entity_data: SyncMutex::new(entity_data),
+            age: AtomicI32::new(0),
+            count: AtomicI32::new(1),
+            following: SyncMutex::new(Weak::new()),
+            removed: AtomicBool::new(false),
+        }
+    }
+
+    /// Splits `amount` experience into orbs and spawns them at `position`.
+    ///
+    /// Matches vanilla's `ExperienceOrb.award()`.
+    pub fn award(world: &World, position: Vector3<f64>, mut amount: i32) {
+        while amount > 0 {
+            let value = experience_value(amount);
+            amount -= value;
+            world.add_entity(Arc::new(Self::new(position, value)));
+        }
+    }
+
+    /// Returns how many experience points the orb is worth.
+    #[must_use]
+    pub fn value(&self) -> i32 {
+        *self.entity_data.lock().value.get()
+    }
+
+    /// Returns the chunk the orb is currently in.
+    #[must_use]
+    pub fn chunk_pos(&self) -> ChunkPos {
+        let pos = self.get_position();
+        ChunkPos::new(
+            SectionPos::block_to_section_coord(pos.x.floor() as i32),
+            SectionPos::block_to_section_coord(pos.z.floor() as i32),
+        )
+    }
+
+    /// Marks the orb for removal. The world removes it at the end of the tick.
+    pub fn discard(&self) {
+        self.removed.store(true, Ordering::Relaxed);
+    }
+
+    /// Picks the player to follow and accelerates towards them.
+    ///
+    /// Matches vanilla's `ExperienceOrb.followNearbyPlayer()`.
+    fn follow_nearby_player(&self, world: &World, physics: &mut EntityPhysicsState) {
+        let mut following = self.following.lock();
+        let mut player = following.upgrade().filter(|player| {
+            player.game_mode.load() != GameType::Spectator
+                && player
+                    .get_position()
+                    .squared_distance_to_vec(physics.position)
+                    <= FOLLOW_RANGE * FOLLOW_RANGE
+        });
+        if player.is_none() {
+            player = nearest_player(world, physics.position).filter(|player| player.is_alive());
+            *following = player.as_ref().map_or_else(Weak::new, Arc::downgrade);
+        }
+
+        if let Some(player) = player {
+            let target = player.get_position();
+            let eye_height = f64::from(vanilla_entities::PLAYER.dimensions.eye_height);
+            let delta = Vector3::new(
+                target.x - physics.position.x,
+                target.y + eye_height / 2.0 - physics.position.y,
+                target.z - physics.position.z,
+            );
+            let distance_squared = delta.length_squared();
+            if distance_squared >= 1.0e-10 {
+                let pull = 1.0 - distance_squared.sqrt() / FOLLOW_RANGE;
+                physics.velocity += delta.normalize() * (pull * pull * 0.1);
+            }
+        }
+    }
+}
+
+impl Entity for ExperienceOrb {
+    fn get_id(&self) -> i32 {
+        self.id
+    }
+
+    fn get_uuid(&self) -> Uuid {
+        self.uuid
+    }
+
+    fn get_type(&self) -> EntityTypeRef {
+        vanilla_entities::EXPERIENCE_ORB
+    }
+
+    fn get_position(&self) -> Vector3<f64> {
+        self.physics.lock().position
+    }
+
+    fn get_velocity(&self) -> Vector3<f64> {
+        self.physics.lock().velocity
+    }
+
+    fn get_rotation(&self) -> (f32, f32) {
+        (self.yaw, 0.0)
+    }
+
+    fn on_ground(&self) -> bool {
+        self.physics.lock().on_ground
+    }
+
+    fn get_bounding_box(&self) -> AABBd {
+        self.physics.lock().bounding_box
+    }
+
+    /// Matches vanilla's `ExperienceOrb.tick()`.
+    fn tick(&self, world: &World) {
+        let collision_world = WorldCollisionProvider::new(world);
+        let mut physics = self.physics.lock();
+        update_in_fluid_state(world, &mut physics);
+
+        let stuck = !collision_world
+            .get_block_collisions(&physics.bounding_box.deflate(1.0E-7))
+            .is_empty();
+        if is_eye_in_fluid(world, &physics, &WATER_TAG) {
+            // Orbs slowly float up to the surface
+            let velocity = physics.velocity;
+            physics.velocity = Vector3::new(
+                velocity.x * f64::from(0.99_f32),
+                (velocity.y + f64::from(5.0E-4_f32)).min(f64::from(0.06_f32)),
+                velocity.z * f64::from(0.99_f32),
+            );
+        } else if !stuck {
+            physics.velocity.y -= GRAVITY;
+        }
+
+        // Lava throws orbs up in a random direction
+        let block_pos = BlockPos::new(
+            physics.position.x.floor() as i32,
+            physics.position.y.floor() as i32,
+            physics.position.z.floor() as i32,
+        );
+        if world
+            .get_block_state(&block_pos)
+            .get_fluid_state()
+            .is(&LAVA_TAG)
+        {
+            physics.velocity = Vector3::new(
+                f64::from((rand::random::<f32>() - rand::random::<f32>()) * 0.2),
+                f64::from(0.2_f32),
+                f64::from((rand::random::<f32>() - rand::random::<f32>()) * 0.2),
+            );
+        }
+
+        self.follow_nearby_player(world, &mut physics);
+
+        let velocity = physics.velocity;
+        if stuck {
+            let new_position = physics.position + velocity;
+            physics.set_position(new_position);
+        } else {
+            let result = move_entity(
+                &physics,
+                velocity,
+                MoverType::SelfMovement,
+                &collision_world,
+            );
+            physics.set_position(result.final_position);
+            physics.on_ground = result.on_ground;
+            physics.horizontal_collision = result.horizontal_collision;
+            physics.vertical_collision = result.vertical_collision;
+            if result.actual_movement.x != velocity.x {
+                physics.velocity.x = 0.0;
+            }
+            if result.actual_movement.z != velocity.z {
+                physics.velocity.z = 0.0;
+            }
+            if result.vertical_collision {
+                physics.velocity.y = 0.0;
+            }
+        }
+
+        let friction = if physics.on_ground {
+            let below = BlockPos::new(
+                physics.position.x.floor() as i32,
+                (physics.position.y - 0.500_001).floor() as i32,
+                physics.position.z.floor() as i32,
+            );
+            f64::from(world.get_block_state(&below).get_block().config.friction) * 0.98
+        } else {
+            0.98
+        };
+        physics.velocity = physics.velocity * friction;
+
+        // Orbs bounce a little when they land
+        if physics.on_ground && velocity.y < -GRAVITY {
+            physics.velocity.y = -velocity.y * 0.4;
+        }
+        drop(physics);
+
+        if self.age.fetch_add(1, Ordering::Relaxed) + 1 >= LIFETIME {
+            self.discard();
+        }
+    }
+
+    fn is_removed(&self) -> bool {
+        self.removed.load(Ordering::Relaxed)
+    }
+
+    /// Matches vanilla's `ExperienceOrb.playerTouch()`.
+    fn player_touch(&self, world: &World, player: &Player) {
+        if self.is_removed() || !player.try_take_xp() {
+            return;
+        }
+
+        world.broadcast_to_nearby(
+            self.chunk_pos(),
+            CTakeItemEntity {
+                item_id: self.id,
+                player_id: player.id,
+                amount: 1,
+            },
+            None,
+        );
+
+        // TODO: Repair items enchanted with Mending before giving the rest
+        player.give_experience_points(self.value());
+
+        if self.count.fetch_sub(1, Ordering::Relaxed) <= 1 {
+            self.discard();
+        }
+    }
+
+    fn pack_all_data(&self) -> Vec<DataValue> {
+        self.entity_data.lock().pack_all()
+    }
+
+    fn pack_dirty_data(&self) -> Option<Vec<DataValue>> {
+        self.entity_data.lock().pack_dirty()
+    }
+
+    /// Matches vanilla's `ExperienceOrb.addAdditionalSaveData()`.
+    fn save(&self, nbt: &mut NbtCompound) -> bool {
+        if self.is_removed() {
+            return false;
+        }
+
+        save_entity_base(self, nbt);
+        nbt.insert("Health", 5i16);
+        nbt.insert("Age", self.age.load(Ordering::Relaxed) as i16);
+        nbt.insert("Value", self.value() as i16);
+        nbt.insert("Count", self.count.load(Ordering::Relaxed));
+        true
+    }
+
+    /// Matches vanilla's `ExperienceOrb.readAdditionalSaveData()`.
+    fn load(&mut self, nbt: &BorrowedNbtCompound<'_>) {
+        let base = read_entity_base(nbt);
+        if let Some(uuid) = base.uuid {
+            self.uuid = uuid;
+        }
+        if let Some((yaw, _)) = base.rotation {
+            self.yaw = yaw;
+        }
+        let physics = self.physics.get_mut();
+        if let Some(position) = base.position {
+            physics.set_position(position);
+        }
+        if let Some(velocity) = base.velocity {
+            physics.velocity = velocity;
+        }
+        physics.on_ground = base.on_ground;
+
+        let view: NbtCompoundView<'_, '_> = nbt.into();
+        *self.age.get_mut() = view.short("Age").map_or(0, i32::from);
+        *self.count.get_mut() = view.int("Count").unwrap_or(1).max(1);
+        self.entity_data
+            .get_mut()
+            .value
+            .set(view.short("Value").map_or(0, i32::from));
+    }
+}
+
+/// Gets the value of the largest orb that fits into `amount`.
+fn experience_value(amount: i32) -> i32 {
+    ORB_VALUES
+        .into_iter()
+        .find(|&value| amount >= value)
+        .unwrap_or(1)
+}
+
+/// Finds the closest player within [`FOLLOW_RANGE`] that isn't spectating.
+fn nearest_player(world: &World, position: Vector3<f64>) -> Option<Arc<Player>> {
+    let mut nearest = None;
+    let mut nearest_distance = FOLLOW_RANGE * FOLLOW_RANGE;
+    world.players.iter_players(|_uuid, player| {
+        if player.game_mode.load() != GameType::Spectator {
+            let distance = player.get_position().squared_distance_to_vec(position);
+            if distance < nearest_distance {
+                nearest_distance = distance;
+                nearest = Some(player.clone());
+            }
+        }
+        true
+    });
+    nearest
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn experience_splits_into_the_largest_orbs() {
+        let mut amount = 2500;
+        let mut values = Vec::new();
+        while amount > 0 {
+            let value = experience_value(amount);
+            amount -= value;
+            values.push(value);
+        }
+        assert_eq!(values, [2477, 17, 3, 3]);
+        assert_eq!(experience_value(2), 1);
+    }
+}
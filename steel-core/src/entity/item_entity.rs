@@ -0,0 +1,649 @@
+//! Dropped item entities.
+//!
+//! Based on vanilla's `ItemEntity`: items fall and slide with block friction,
+//! merge with nearby identical stacks, can be picked up by players once their
+//! pickup delay runs out and despawn after [`LIFETIME`] ticks.
+
+use std::sync::{
+    Arc,
+    atomic::{AtomicBool, AtomicI32, Ordering},
+};
+
+use simdnbt::borrow::BaseNbtCompound as BorrowedNbtCompound;
+use simdnbt::borrow::NbtCompound as NbtCompoundView;
+use simdnbt::owned::NbtCompound;
+use simdnbt::{FromNbtTag, ToNbtTag};
+use steel_protocol::packets::game::CTakeItemEntity;
+use steel_registry::blocks::block_state_ext::BlockStateExt;
+use steel_registry::blocks::properties::Direction;
+use steel_registry::blocks::shapes::AABBd;
+use steel_registry::entity_data::DataValue;
+use steel_registry::entity_types::EntityTypeRef;
+use steel_registry::item_stack::ItemStack;
+use steel_registry::vanilla_entities;
+use steel_registry::vanilla_entity_data::ItemEntityData;
+use steel_utils::locks::SyncMutex;
+use steel_utils::math::Vector3;
+use steel_utils::{BlockPos, ChunkPos, SectionPos};
+use uuid::Uuid;
+
+use crate::entity::persistence::{read_uuid, write_uuid};
+use crate::entity::{Entity, next_entity_id, read_entity_base, save_entity_base};
+use crate::inventory::container::Container;
+use crate::physics::{
+    CollisionWorld, EntityPhysicsState, MoverType, WorldCollisionProvider, move_entity,
+    update_in_fluid_state,
+};
+use crate::player::Player;
+use crate::world::World;
+
+/// Number of ticks an item lives before despawning (5 minutes).
+pub const LIFETIME: i32 = 6000;
+/// Pickup delay that prevents the item from ever being picked up.
+pub const INFINITE_PICKUP_DELAY: i32 = 32767;
+/// Age that prevents the item from ever despawning.
+pub const INFINITE_LIFETIME: i32 = -32768;
+/// Pickup delay used for items popped out of broken blocks.
+pub const DEFAULT_PICKUP_DELAY: i32 = 10;
+
+/// Downward acceleration applied every tick.
+const GRAVITY: f64 = 0.04;
+
+/// Slows an item down by `drag` sideways and lets it rise slowly, for items in water or lava.
+///
+/// Matches vanilla's `ItemEntity.setUnderwaterMovement()` and `setUnderLavaMovement()`.
+fn float_up(velocity: Vector3<f64>, drag: f32) -> Vector3<f64> {
+    let buoyancy = if velocity.y < f64::from(0.06_f32) {
+        f64::from(5.0E-4_f32)
+    } else {
+        0.0
+    };
+    Vector3::new(
+        velocity.x * f64::from(drag),
+        velocity.y + buoyancy,
+        velocity.z * f64::from(drag),
+    )
+}
+
+/// An item stack lying in the world.
+pub struct ItemEntity {
+    /// The entity ID.
+    id: i32,
+    /// The entity UUID.
+    uuid: Uuid,
+    /// Position, velocity and collision state.
+    physics: SyncMutex<EntityPhysicsState>,
+    /// Yaw the item was spawned with.
+    yaw: f32,
+    /// Synchronized entity data, holding the item stack itself.
+    entity_data: SyncMutex<ItemEntityData>,
+    /// Ticks since the item was spawned.
+    age: AtomicI32,
+    /// Ticks left until the item can be picked up.
+    pickup_delay: AtomicI32,
+    /// Local tick counter.
+    tick_count: AtomicI32,
+    /// The player that dropped this item, if any.
+    thrower: SyncMutex<Option<Uuid>>,
+    /// The only player allowed to pick this item up, if any.
+    target: SyncMutex<Option<Uuid>>,
+    /// Whether the velocity changed enough this tick to be sent immediately.
+    has_impulse: AtomicBool,
+    /// Whether the item has been removed from the world.
+    removed: AtomicBool,
+}
+
+impl ItemEntity {
+    /// Creates a new item entity with an explicit velocity.
+    #[must_use]
+    pub fn new(position: Vector3<f64>, item: ItemStack, velocity: Vector3<f64>) -> Self {
+        let mut physics = EntityPhysicsState::new(position, vanilla_entities::ITEM);
+        physics.velocity = velocity;
+
+        let mut entity_data = ItemEntityData::new();
+        entity_data.item.set(item);
+
+        Self {
+            id: next_entity_id(),
+            uuid: Uuid::new_v4(),
+            physics: SyncMutex::new(physics),
+            yaw: rand::random::<f32>() * 360.0,
+            entity_data: SyncMutex::new(entity_data),
+            age: AtomicI32::new(0),
+            pickup_delay: AtomicI32::new(0),
+            tick_count: AtomicI32::new(0),
+            thrower: SyncMutex::new(None),
+            target: SyncMutex::new(None),
+            has_impulse: AtomicBool::new(false),
+            removed: AtomicBool::new(false),
+        }
+    }
+
+    /// Creates a new item entity with a small random horizontal push and a hop upwards.
+    ///
+    /// Matches vanilla's `ItemEntity(Level, double, double, double, ItemStack)`.
+    #[must_use]
+    pub fn with_random_velocity(position: Vector3<f64>, item: ItemStack) -> Self {
+        let velocity = Vector3::new(
+            rand::random::<f64>() * 0.2 - 0.1,
+            0.2,
+            rand::random::<f64>() * 0.2 - 0.1,
+        );
+        Self::new(position, item, velocity)
+    }
+
+    /// Returns a clone of the item stack carried by this entity.
+    #[must_use]
+    pub fn item(&self) -> ItemStack {
+        self.entity_data.lock().item.get().clone()
+    }
+
+    /// Replaces the item stack carried by this entity.
+    pub fn set_item(&self, item: ItemStack) {
+        self.entity_data.lock().item.set(item);
+    }
+
+    /// Returns the chunk the item is currently in.
+    #[must_use]
+    pub fn chunk_pos(&self) -> ChunkPos {
+        let pos = self.get_position();
+        ChunkPos::new(
+            SectionPos::block_to_section_coord(pos.x.floor() as i32),
+            SectionPos::block_to_section_coord(pos.z.floor() as i32),
+        )
+    }
+
+    /// Sets the pickup delay in ticks.
+    pub fn set_pickup_delay(&self, ticks: i32) {
+        self.pickup_delay.store(ticks, Ordering::Relaxed);
+    }
+
+    /// Sets the default pickup delay used for block drops.
+    pub fn set_default_pickup_delay(&self) {
+        self.set_pickup_delay(DEFAULT_PICKUP_DELAY);
+    }
+
+    /// Makes the item impossible to pick up.
+    pub fn set_never_pickup(&self) {
+        self.set_pickup_delay(INFINITE_PICKUP_DELAY);
+    }
+
+    /// Makes the item never despawn.
+    pub fn set_unlimited_lifetime(&self) {
+        self.age.store(INFINITE_LIFETIME, Ordering::Relaxed);
+    }
+
+    /// Sets the player who dropped this item.
+    pub fn set_thrower(&self, thrower: Option<Uuid>) {
+        *self.thrower.lock() = thrower;
+    }
+
+    /// Returns the player who dropped this item.
+    #[must_use]
+    pub fn thrower(&self) -> Option<Uuid> {
+        *self.thrower.lock()
+    }
+
+    /// Restricts pickup to the given player.
+    pub fn set_target(&self, target: Option<Uuid>) {
+        *self.target.lock() = target;
+    }
+
+    /// Marks the item for removal. The world removes it at the end of the tick.
+    pub fn discard(&self) {
+        self.removed.store(true, Ordering::Relaxed);
+    }
+
+    /// Nudges an item stuck inside a block towards the nearest open side.
+    ///
+    /// Matches vanilla's `Entity.moveTowardsClosestSpace()`.
+    fn move_towards_closest_space(
+        &self,
+        world: &World,
+        physics: &mut EntityPhysicsState,
+        center: Vector3<f64>,
+    ) {
+        let pos = BlockPos::new(
+            center.x.floor() as i32,
+            center.y.floor() as i32,
+            center.z.floor() as i32,
+        );
+        let delta = Vector3::new(
+            center.x - f64::from(pos.x()),
+            center.y - f64::from(pos.y()),
+            center.z - f64::from(pos.z()),
+        );
+
+        let mut closest_direction = Direction::Up;
+        let mut closest_distance = f64::MAX;
+        for direction in [
+            Direction::North,
+            Direction::South,
+            Direction::West,
+            Direction::East,
+            Direction::Up,
+        ] {
+            let (dx, dy, dz) = direction.offset();
+            let neighbor = pos.offset(dx, dy, dz);
+            if is_collision_shape_full_block(world, &neighbor) {
+                continue;
+            }
+            let step = dx + dy + dz;
+            let axis_delta = match (dx, dy) {
+                (0, 0) => delta.z,
+                (0, _) => delta.y,
+                _ => delta.x,
+            };
+            let distance = if step > 0 {
+                1.0 - axis_delta
+            } else {
+                axis_delta
+            };
+            if distance < closest_distance {
+                closest_distance = distance;
+                closest_direction = direction;
+            }
+        }
+
+        let speed = f64::from(rand::random::<f32>() * 0.2 + 0.1);
+        let (dx, dy, dz) = closest_direction.offset();
+        let scaled = physics.velocity * 0.75;
+        physics.velocity = Vector3::new(
+            if dx == 0 {
+                scaled.x
+            } else {
+                f64::from(dx) * speed
+            },
+            if dy == 0 {
+                scaled.y
+            } else {
+                f64::from(dy) * speed
+            },
+            if dz == 0 {
+                scaled.z
+            } else {
+                f64::from(dz) * speed
+            },
+        );
+    }
+
+    /// Returns whether this item may currently merge with others.
+    fn is_mergable(&self) -> bool {
+        let item = self.item();
+        let age = self.age.load(Ordering::Relaxed);
+        !self.is_removed()
+            && self.pickup_delay.load(Ordering::Relaxed) != INFINITE_PICKUP_DELAY
+            && age != INFINITE_LIFETIME
+            && age < LIFETIME
+            && item.count() < item.max_stack_size()
+    }
+
+    /// Merges this item with identical items lying next to it.
+    fn merge_with_neighbours(&self, world: &World) {
+        let search = self.get_bounding_box().inflate_xyz(0.5, 0.0, 0.5);
+        for other in world.get_item_entities_in(&search) {
+            if other.id == self.id || !other.is_mergable() {
+                continue;
+            }
+            self.try_to_merge(&other);
+            if self.is_removed() {
+                break;
+            }
+        }
+    }
+
+    /// Merges two items if they hold the same stack, moving items into the larger one.
+    fn try_to_merge(&self, other: &ItemEntity) {
+        if *self.target.lock() != *other.target.lock() {
+            return;
+        }
+        let this_item = self.item();
+        let other_item = other.item();
+        if !are_mergable(&this_item, &other_item) {
+            return;
+        }
+        if other_item.count() < this_item.count() {
+            Self::merge(self, this_item, other, other_item);
+        } else {
+            Self::merge(other, other_item, self, this_item);
+        }
+    }
+
+    /// Moves as many items as fit from `from` into `to`.
+    fn merge(
+        to: &ItemEntity,
+        mut to_stack: ItemStack,
+        from: &ItemEntity,
+        mut from_stack: ItemStack,
+    ) {
+        let moved = (to_stack.max_stack_size() - to_stack.count()).min(from_stack.count());
+        to_stack.grow(moved);
+        from_stack.shrink(moved);
+        to.set_item(to_stack);
+        from.set_item(from_stack.clone());
+
+        to.pickup_delay.store(
+            to.pickup_delay
+                .load(Ordering::Relaxed)
+                .max(from.pickup_delay.load(Ordering::Relaxed)),
+            Ordering::Relaxed,
+        );
+        to.age.store(
+            to.age
+                .load(Ordering::Relaxed)
+                .min(from.age.load(Ordering::Relaxed)),
+            Ordering::Relaxed,
+        );
+
+        if from_stack.is_empty() {
+            from.discard();
+        }
+    }
+}
+
+impl Entity for ItemEntity {
+    fn get_id(&self) -> i32 {
+        self.id
+    }
+
+    fn get_uuid(&self) -> Uuid {
+        self.uuid
+    }
+
+    fn get_type(&self) -> EntityTypeRef {
+        vanilla_entities::ITEM
+    }
+
+    fn get_position(&self) -> Vector3<f64> {
+        self.physics.lock().position
+    }
+
+    fn get_velocity(&self) -> Vector3<f64> {
+        self.physics.lock().velocity
+    }
+
+    fn get_rotation(&self) -> (f32, f32) {
+        (self.yaw, 0.0)
+    }
+
+    fn on_ground(&self) -> bool {
+        self.physics.lock().on_ground
+    }
+
+    fn get_bounding_box(&self) -> AABBd {
+        self.physics.lock().bounding_box
+    }
+
+    /// Matches vanilla's `ItemEntity.tick()`.
+    fn tick(&self, world: &World) {
+        if self.item().is_empty() {
+            self.discard();
+            return;
+        }
+
+        let tick_count = self.tick_count.fetch_add(1, Ordering::Relaxed);
+        let fluids = update_in_fluid_state(world, &mut self.physics.lock());
+
+        let pickup_delay = self.pickup_delay.load(Ordering::Relaxed);
+        if pickup_delay > 0 && pickup_delay != INFINITE_PICKUP_DELAY {
+            self.pickup_delay.store(pickup_delay - 1, Ordering::Relaxed);
+        }
+
+        let (old_position, old_velocity) = {
+            let physics = self.physics.lock();
+            (physics.position, physics.velocity)
+        };
+
+        {
+            let mut physics = self.physics.lock();
+            // Items only float once the fluid is deeper than a tenth of a block
+            let min_depth = f64::from(0.1_f32);
+            if physics.in_water && fluids.water > min_depth {
+                physics.velocity = float_up(physics.velocity, 0.99);
+            } else if physics.in_lava && fluids.lava > min_depth {
+                physics.velocity = float_up(physics.velocity, 0.95);
+            } else {
+                physics.velocity.y -= GRAVITY;
+            }
+        }
+
+        let collision_world = WorldCollisionProvider::new(world);
+        {
+            let mut physics = self.physics.lock();
+
+            let center = Vector3::new(
+                physics.position.x,
+                (physics.bounding_box.min_y + physics.bounding_box.max_y) / 2.0,
+                physics.position.z,
+            );
+            let no_physics = !collision_world
+                .get_block_collisions(&physics.bounding_box.deflate(1.0E-7))
+                .is_empty();
+            if no_physics {
+                self.move_towards_closest_space(world, &mut physics, center);
+            }
+
+            let velocity = physics.velocity;
+            if !physics.on_ground
+                || velocity.horizontal_length_squared() > 1.0E-5
+                || (tick_count + self.id) % 4 == 0
+            {
+                if no_physics {
+                    let new_position = physics.position + velocity;
+                    physics.set_position(new_position);
+                } else {
+                    let result = move_entity(
+                        &physics,
+                        velocity,
+                        MoverType::SelfMovement,
+                        &collision_world,
+                    );
+                    physics.set_position(result.final_position);
+                    physics.on_ground = result.on_ground;
+                    physics.horizontal_collision = result.horizontal_collision;
+                    physics.vertical_collision = result.vertical_collision;
+
+                    // Stop movement on the axes we collided on (vanilla: Block.updateEntityMovementAfterFallOn)
+                    if result.actual_movement.x != velocity.x {
+                        physics.velocity.x = 0.0;
+                    }
+                    if result.actual_movement.z != velocity.z {
+                        physics.velocity.z = 0.0;
+                    }
+                    if result.vertical_collision {
+                        physics.velocity.y = 0.0;
+                    }
+                }
+
+                let friction = if physics.on_ground {
+                    let below = BlockPos::new(
+                        physics.position.x.floor() as i32,
+                        (physics.position.y - 0.500_001).floor() as i32,
+                        physics.position.z.floor() as i32,
+                    );
+                    f64::from(world.get_block_state(&below).get_block().config.friction) * 0.98
+                } else {
+                    0.98
+                };
+                physics.velocity = physics.velocity.multiply(friction, 0.98, friction);
+
+                if physics.on_ground && physics.velocity.y < 0.0 {
+                    physics.velocity.y *= -0.5;
+                }
+            }
+        }
+
+        let new_position = self.get_position();
+        let moved = old_position.x.floor() != new_position.x.floor()
+            || old_position.y.floor() != new_position.y.floor()
+            || old_position.z.floor() != new_position.z.floor();
+        let merge_rate = if moved { 2 } else { 40 };
+        if tick_count % merge_rate == 0 && self.is_mergable() {
+            self.merge_with_neighbours(world);
+        }
+
+        let age = self.age.load(Ordering::Relaxed);
+        if age != INFINITE_LIFETIME {
+            self.age.store(age + 1, Ordering::Relaxed);
+        }
+
+        let new_velocity = self.physics.lock().velocity;
+        if new_velocity.sub(&old_velocity).length_squared() > 0.01 {
+            self.has_impulse.store(true, Ordering::Relaxed);
+        }
+
+        if self.age.load(Ordering::Relaxed) >= LIFETIME {
+            self.discard();
+        }
+    }
+
+    fn is_removed(&self) -> bool {
+        self.removed.load(Ordering::Relaxed)
+    }
+
+    /// Matches vanilla's `ItemEntity.playerTouch()`.
+    fn player_touch(&self, world: &World, player: &Player) {
+        if self.is_removed() || self.pickup_delay.load(Ordering::Relaxed) != 0 {
+            return;
+        }
+        if let Some(target) = *self.target.lock()
+            && target != player.gameprofile.id
+        {
+            return;
+        }
+
+        let mut stack = self.item();
+        let count = stack.count();
+        player.inventory.lock().add(&mut stack);
+        let taken = count - stack.count();
+        if taken <= 0 {
+            return;
+        }
+
+        world.broadcast_to_nearby(
+            self.chunk_pos(),
+            CTakeItemEntity {
+                item_id: self.id,
+                player_id: player.id,
+                amount: taken,
+            },
+            None,
+        );
+
+        if stack.is_empty() {
+            self.discard();
+        }
+        self.set_item(stack);
+    }
+
+    fn take_impulse(&self) -> bool {
+        self.has_impulse.swap(false, Ordering::Relaxed)
+    }
+
+    fn pack_all_data(&self) -> Vec<DataValue> {
+        self.entity_data.lock().pack_all()
+    }
+
+    fn pack_dirty_data(&self) -> Option<Vec<DataValue>> {
+        self.entity_data.lock().pack_dirty()
+    }
+
+    /// Matches vanilla's `ItemEntity.addAdditionalSaveData()`.
+    fn save(&self, nbt: &mut NbtCompound) -> bool {
+        let item = self.item();
+        if self.is_removed() || item.is_empty() {
+            return false;
+        }
+
+        save_entity_base(self, nbt);
+        nbt.insert("Age", self.age.load(Ordering::Relaxed) as i16);
+        nbt.insert(
+            "PickupDelay",
+            self.pickup_delay.load(Ordering::Relaxed) as i16,
+        );
+        if let Some(thrower) = self.thrower() {
+            write_uuid(nbt, "Thrower", thrower);
+        }
+        if let Some(target) = *self.target.lock() {
+            write_uuid(nbt, "Owner", target);
+        }
+        nbt.insert("Item", item.to_nbt_tag());
+        true
+    }
+
+    /// Matches vanilla's `ItemEntity.readAdditionalSaveData()`.
+    fn load(&mut self, nbt: &BorrowedNbtCompound<'_>) {
+        let base = read_entity_base(nbt);
+        if let Some(uuid) = base.uuid {
+            self.uuid = uuid;
+        }
+        if let Some((yaw, _)) = base.rotation {
+            self.yaw = yaw;
+        }
+        let physics = self.physics.get_mut();
+        if let Some(position) = base.position {
+            physics.set_position(position);
+        }
+        if let Some(velocity) = base.velocity {
+            physics.velocity = velocity;
+        }
+        physics.on_ground = base.on_ground;
+
+        let view: NbtCompoundView<'_, '_> = nbt.into();
+        *self.age.get_mut() = view.short("Age").map_or(0, i32::from);
+        *self.pickup_delay.get_mut() = view.short("PickupDelay").map_or(0, i32::from);
+        *self.thrower.get_mut() = read_uuid(&view, "Thrower");
+        *self.target.get_mut() = read_uuid(&view, "Owner");
+
+        let item = view
+            .get("Item")
+            .and_then(ItemStack::from_nbt_tag)
+            .unwrap_or_else(ItemStack::empty);
+        if item.is_empty() {
+            self.discard();
+        }
+        self.set_item(item);
+    }
+
+    fn as_item_entity(self: Arc<Self>) -> Option<Arc<ItemEntity>> {
+        Some(self)
+    }
+}
+
+/// Returns whether two stacks can be combined into a single item entity.
+///
+/// Matches vanilla's `ItemEntity.areMergable()`.
+fn are_mergable(a: &ItemStack, b: &ItemStack) -> bool {
+    b.count() + a.count() <= b.max_stack_size() && ItemStack::is_same_item_same_components(a, b)
+}
+
+/// Returns whether the block at `pos` has a full cube collision shape.
+fn is_collision_shape_full_block(world: &World, pos: &BlockPos) -> bool {
+    match world.get_block_state(pos).get_collision_shape() {
+        [shape] => {
+            shape.min_x <= 0.0
+                && shape.min_y <= 0.0
+                && shape.min_z <= 0.0
+                && shape.max_x >= 1.0
+                && shape.max_y >= 1.0
+                && shape.max_z >= 1.0
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn items_rise_slowly_in_fluids() {
+        let mut velocity = Vector3::new(0.2, -0.1, 0.0);
+        for _ in 0..400 {
+            velocity = float_up(velocity, 0.99);
+        }
+        // The rise stops speeding up at 0.06 blocks per tick
+        assert!(velocity.y > 0.059 && velocity.y < 0.061, "{velocity:?}");
+        assert!(velocity.x < 0.01);
+    }
+}
@@ -0,0 +1,273 @@
+//! This module contains entity-related traits and types.
+
+mod experience_orb;
+mod item_entity;
+mod persistence;
+mod server_entity;
+
+pub use experience_orb::ExperienceOrb;
+pub use item_entity::ItemEntity;
+pub use persistence::{EntityBaseData, load_entity, read_entity_base, save_entity_base};
+pub use server_entity::ServerEntity;
+
+use std::sync::{
+    Arc,
+    atomic::{AtomicI32, Ordering},
+};
+
+use simdnbt::borrow::BaseNbtCompound as BorrowedNbtCompound;
+use simdnbt::owned::NbtCompound;
+use steel_protocol::packets::game::{CAddEntity, to_angle_byte};
+use steel_registry::REGISTRY;
+use steel_registry::blocks::shapes::AABBd;
+use steel_registry::entity_data::DataValue;
+use steel_registry::entity_types::EntityTypeRef;
+use steel_registry::item_stack::ItemStack;
+use steel_utils::math::Vector3;
+use uuid::Uuid;
+
+use crate::{inventory::equipment::EquipmentSlot, player::Player, world::World};
+
+/// Counter for assigning unique entity IDs to every kind of entity.
+///
+/// Matches vanilla's static `Entity.ENTITY_COUNTER`.
+static ENTITY_COUNTER: AtomicI32 = AtomicI32::new(1); // Start at 1, 0 is reserved
+
+/// Allocates a new unique entity ID.
+#[must_use]
+pub fn next_entity_id() -> i32 {
+    ENTITY_COUNTER.fetch_add(1, Ordering::Relaxed)
+}
+
+/// A trait for  entities.
+///
+/// This trait provides the core functionality for entities.
+/// It's based on Minecraft's `Entity` class.
+pub trait Entity: Send + Sync {
+    /// Gets the network ID of the entity.
+    fn get_id(&self) -> i32;
+
+    /// Gets the Uuid of the entity.
+    fn get_uuid(&self) -> Uuid;
+
+    /// Gets the type of the entity.
+    fn get_type(&self) -> EntityTypeRef;
+
+    /// Gets the entity's position.
+    fn get_position(&self) -> Vector3<f64>;
+
+    /// Gets the entity's velocity.
+    fn get_velocity(&self) -> Vector3<f64> {
+        Vector3::default()
+    }
+
+    /// Gets the entity's rotation as `(yaw, pitch)` in degrees.
+    fn get_rotation(&self) -> (f32, f32) {
+        (0.0, 0.0)
+    }
+
+    /// Returns whether the entity is standing on the ground.
+    fn on_ground(&self) -> bool {
+        false
+    }
+
+    /// Gets the entity's bounding box.
+    fn get_bounding_box(&self) -> AABBd {
+        let pos = self.get_position();
+        let dimensions = self.get_type().dimensions;
+        AABBd::entity_box(
+            pos.x,
+            pos.y,
+            pos.z,
+            f64::from(dimensions.width) / 2.0,
+            f64::from(dimensions.height),
+        )
+    }
+
+    /// Ticks the entity. Only called while the entity is in a ticking chunk.
+    fn tick(&self, _world: &World) {}
+
+    /// Returns whether the entity has been removed and should be dropped from the world.
+    fn is_removed(&self) -> bool {
+        false
+    }
+
+    /// Called when a player's bounding box touches this entity.
+    fn player_touch(&self, _world: &World, _player: &Player) {}
+
+    /// Returns and clears whether the entity's velocity changed abruptly,
+    /// forcing an immediate sync to clients.
+    fn take_impulse(&self) -> bool {
+        false
+    }
+
+    /// Builds the packet that spawns this entity on a client.
+    fn get_spawn_packet(&self) -> CAddEntity {
+        let pos = self.get_position();
+        let (yaw, pitch) = self.get_rotation();
+        CAddEntity {
+            id: self.get_id(),
+            uuid: self.get_uuid(),
+            entity_type: *REGISTRY.entity_types.get_id(self.get_type()) as i32,
+            x: pos.x,
+            y: pos.y,
+            z: pos.z,
+            velocity: self.get_velocity(),
+            x_rot: to_angle_byte(pitch),
+            y_rot: to_angle_byte(yaw),
+            head_y_rot: to_angle_byte(yaw),
+            data: 0,
+        }
+    }
+
+    /// Packs every synced data value, sent when a player starts tracking the entity.
+    fn pack_all_data(&self) -> Vec<DataValue> {
+        Vec::new()
+    }
+
+    /// Packs the synced data values changed since the last call.
+    fn pack_dirty_data(&self) -> Option<Vec<DataValue>> {
+        None
+    }
+
+    /// Writes the entity's persistent state to NBT.
+    ///
+    /// Returns `false` if the entity should not be saved, in which case it is
+    /// dropped when its chunk unloads. Matches vanilla's `Entity.save()`.
+    fn save(&self, _nbt: &mut NbtCompound) -> bool {
+        false
+    }
+
+    /// Restores the entity's persistent state from NBT written by [`Entity::save`].
+    ///
+    /// Called on a freshly created entity before it is added to the world.
+    fn load(&mut self, _nbt: &BorrowedNbtCompound<'_>) {}
+
+    /// Gets the entity as a Player
+    fn as_player(self: Arc<Self>) -> Option<Arc<Player>> {
+        None
+    }
+
+    /// Gets the entity as an item entity.
+    fn as_item_entity(self: Arc<Self>) -> Option<Arc<ItemEntity>> {
+        None
+    }
+}
+
+/// A trait for living entities that can take damage, heal, and die.
+///
+/// This trait provides the core functionality for entities that have health,
+/// can be damaged, and can die. It's based on Minecraft's `LivingEntity` class.
+pub trait LivingEntity: Entity {
+    /// Gets the current health of the entity.
+    fn get_health(&self) -> f32;
+
+    /// Sets the health of the entity, clamped between 0 and max health.
+    fn set_health(&mut self, health: f32);
+
+    /// Gets the maximum health of the entity.
+    fn get_max_health(&self) -> f32;
+
+    /// Heals the entity by the specified amount.
+    fn heal(&mut self, amount: f32) {
+        let current_health = self.get_health();
+        if current_health > 0.0 {
+            self.set_health(current_health + amount);
+        }
+    }
+
+    /// Returns true if the entity is dead or dying (health <= 0).
+    fn is_dead_or_dying(&self) -> bool {
+        self.get_health() <= 0.0
+    }
+
+    /// Returns true if the entity is alive (health > 0).
+    fn is_alive(&self) -> bool {
+        !self.is_dead_or_dying()
+    }
+
+    /// Gets the absorption amount (extra health from effects like absorption).
+    fn get_absorption_amount(&self) -> f32;
+
+    /// Sets the absorption amount.
+    fn set_absorption_amount(&mut self, amount: f32);
+
+    /// Gets the entity's armor value.
+    fn get_armor_value(&self) -> i32;
+
+    /// Checks if the entity can be affected by potions.
+    fn is_affected_by_potions(&self) -> bool {
+        true
+    }
+
+    /// Checks if the entity is attackable.
+    fn attackable(&self) -> bool {
+        true
+    }
+
+    /// Checks if the entity is currently using an item.
+    fn is_using_item(&self) -> bool {
+        false
+    }
+
+    /// Checks if the entity is blocking with a shield or similar item.
+    fn is_blocking(&self) -> bool {
+        false
+    }
+
+    /// Checks if the entity is fall flying (using elytra).
+    fn is_fall_flying(&self) -> bool {
+        false
+    }
+
+    /// Checks if the entity is sleeping.
+    fn is_sleeping(&self) -> bool {
+        false
+    }
+
+    /// Stops the entity from sleeping.
+    fn stop_sleeping(&mut self) {}
+
+    /// Checks if the entity is sprinting.
+    fn is_sprinting(&self) -> bool {
+        false
+    }
+
+    /// Sets whether the entity is sprinting.
+    fn set_sprinting(&mut self, sprinting: bool);
+
+    /// Gets the entity's speed attribute value.
+    fn get_speed(&self) -> f32;
+
+    /// Sets the entity's speed.
+    fn set_speed(&mut self, speed: f32);
+
+    // Equipment methods
+
+    /// Gets a clone of the item in the specified equipment slot.
+    ///
+    /// Default implementation returns an empty stack.
+    fn get_item_by_slot(&self, _slot: EquipmentSlot) -> ItemStack {
+        ItemStack::empty()
+    }
+
+    /// Gets the main hand item.
+    fn get_main_hand_item(&self) -> ItemStack {
+        self.get_item_by_slot(EquipmentSlot::MainHand)
+    }
+
+    /// Gets the off hand item.
+    fn get_off_hand_item(&self) -> ItemStack {
+        self.get_item_by_slot(EquipmentSlot::OffHand)
+    }
+
+    /// Checks if the main hand slot is empty.
+    fn is_main_hand_empty(&self) -> bool {
+        self.get_item_by_slot(EquipmentSlot::MainHand).is_empty()
+    }
+
+    /// Checks if the off hand slot is empty.
+    fn is_off_hand_empty(&self) -> bool {
+        self.get_item_by_slot(EquipmentSlot::OffHand).is_empty()
+    }
+}
@@ -0,0 +1,135 @@
+//! NBT persistence shared by every saveable entity.
+//!
+//! Based on vanilla's `Entity.saveWithoutId()` / `Entity.load()` for the common
+//! fields and `EntityType.create()` for recreating entities from disk.
+
+use std::ptr;
+use std::sync::Arc;
+
+use simdnbt::borrow::BaseNbtCompound as BorrowedNbtCompound;
+use simdnbt::borrow::NbtCompound as NbtCompoundView;
+use simdnbt::owned::{NbtCompound, NbtList, NbtTag};
+use steel_registry::entity_types::EntityTypeRef;
+use steel_registry::item_stack::ItemStack;
+use steel_registry::vanilla_entities;
+use steel_utils::math::Vector3;
+use uuid::Uuid;
+
+use crate::entity::{Entity, ExperienceOrb, ItemEntity};
+
+/// Common entity state read from NBT.
+///
+/// Fields are `None` when missing or malformed so callers keep their defaults.
+#[derive(Debug, Default)]
+pub struct EntityBaseData {
+    /// The persistent UUID (`UUID`).
+    pub uuid: Option<Uuid>,
+    /// Position (`Pos`).
+    pub position: Option<Vector3<f64>>,
+    /// Velocity (`Motion`).
+    pub velocity: Option<Vector3<f64>>,
+    /// Yaw and pitch in degrees (`Rotation`).
+    pub rotation: Option<(f32, f32)>,
+    /// Whether the entity was standing on the ground (`OnGround`).
+    pub on_ground: bool,
+}
+
+/// Creates an entity of the given type and loads its state from NBT.
+///
+/// Returns `None` if the type can't be persisted yet or the loaded entity
+/// removed itself (e.g. an item entity with an empty stack).
+#[must_use]
+pub fn load_entity(
+    entity_type: EntityTypeRef,
+    nbt: &BorrowedNbtCompound<'_>,
+) -> Option<Arc<dyn Entity>> {
+    let mut entity: Box<dyn Entity> = if ptr::eq(entity_type, vanilla_entities::ITEM) {
+        Box::new(ItemEntity::new(
+            Vector3::default(),
+            ItemStack::empty(),
+            Vector3::default(),
+        ))
+    } else if ptr::eq(entity_type, vanilla_entities::EXPERIENCE_ORB) {
+        Box::new(ExperienceOrb::new(Vector3::default(), 0))
+    } else {
+        return None;
+    };
+
+    entity.load(nbt);
+    if entity.is_removed() {
+        return None;
+    }
+    Some(Arc::from(entity))
+}
+
+/// Writes the fields every entity shares: `UUID`, `Pos`, `Motion`, `Rotation` and `OnGround`.
+pub fn save_entity_base(entity: &dyn Entity, nbt: &mut NbtCompound) {
+    let pos = entity.get_position();
+    let velocity = entity.get_velocity();
+    let (yaw, pitch) = entity.get_rotation();
+
+    nbt.insert("Pos", NbtList::Double(vec![pos.x, pos.y, pos.z]));
+    nbt.insert(
+        "Motion",
+        NbtList::Double(vec![velocity.x, velocity.y, velocity.z]),
+    );
+    nbt.insert("Rotation", NbtList::Float(vec![yaw, pitch]));
+    nbt.insert("OnGround", i8::from(entity.on_ground()));
+    write_uuid(nbt, "UUID", entity.get_uuid());
+}
+
+/// Reads the fields written by [`save_entity_base`].
+#[must_use]
+pub fn read_entity_base(nbt: &BorrowedNbtCompound<'_>) -> EntityBaseData {
+    let view: NbtCompoundView<'_, '_> = nbt.into();
+
+    let rotation = view
+        .list("Rotation")
+        .and_then(|list| list.floats())
+        .and_then(|floats| match floats.to_vec()[..] {
+            [yaw, pitch] if yaw.is_finite() && pitch.is_finite() => Some((yaw, pitch)),
+            _ => None,
+        });
+
+    EntityBaseData {
+        uuid: read_uuid(&view, "UUID"),
+        position: read_vec3(&view, "Pos"),
+        velocity: read_vec3(&view, "Motion"),
+        rotation,
+        on_ground: view.byte("OnGround").is_some_and(|b| b != 0),
+    }
+}
+
+/// Writes a UUID as an int array, matching vanilla's `UUIDUtil.CODEC`.
+pub(crate) fn write_uuid(nbt: &mut NbtCompound, key: &str, uuid: Uuid) {
+    let (most, least) = uuid.as_u64_pair();
+    nbt.insert(
+        key,
+        NbtTag::IntArray(vec![
+            (most >> 32) as i32,
+            most as i32,
+            (least >> 32) as i32,
+            least as i32,
+        ]),
+    );
+}
+
+/// Reads a UUID written by [`write_uuid`].
+pub(crate) fn read_uuid(nbt: &NbtCompoundView<'_, '_>, key: &str) -> Option<Uuid> {
+    let ints = nbt.int_array(key)?.to_vec();
+    let [a, b, c, d] = ints[..] else {
+        return None;
+    };
+    let most = (u64::from(a as u32) << 32) | u64::from(b as u32);
+    let least = (u64::from(c as u32) << 32) | u64::from(d as u32);
+    Some(Uuid::from_u64_pair(most, least))
+}
+
+/// Reads a list of three finite doubles.
+fn read_vec3(nbt: &NbtCompoundView<'_, '_>, key: &str) -> Option<Vector3<f64>> {
+    let doubles = nbt.list(key)?.doubles()?.to_vec();
+    match doubles[..] {
+        [x, y, z] if x.is_finite() && y.is_finite() && z.is_finite() => Some(Vector3::new(x, y, z)),
+        _ => None,
+    }
+}
@@ -0,0 +1,261 @@
+//! Server-side network tracking for a single entity.
+//!
+//! Based on vanilla's `ServerEntity` and `ChunkMap.TrackedEntity`: keeps track of
+//! which players have been sent the entity and what state they last received.
+
+use rustc_hash::FxHashSet;
+use steel_protocol::packet_traits::{ClientPacket, EncodedPacket};
+use steel_protocol::packets::game::{
+    CEntityPositionSync, CMoveEntityPos, CMoveEntityPosRot, CMoveEntityRot, CRemoveEntities,
+    CSetEntityData, CSetEntityMotion, calc_delta, to_angle_byte,
+};
+use steel_protocol::utils::ConnectionProtocol;
+use steel_utils::codec::LpVec3;
+use steel_utils::math::Vector3;
+
+use crate::config::STEEL_CONFIG;
+use crate::entity::Entity;
+use crate::world::World;
+
+/// Minimum squared distance before a position change is broadcast.
+const POSITION_CHANGE_THRESHOLD: f64 = 7.629_394_5E-6;
+/// Ticks between forced absolute position syncs (vanilla `FORCED_POS_UPDATE_PERIOD`).
+const FORCED_POS_UPDATE_PERIOD: i32 = 400;
+/// Minimum angle byte difference before a rotation change is broadcast.
+const ROTATION_CHANGE_THRESHOLD: i32 = 1;
+
+/// Network state of an entity as last sent to the players tracking it.
+pub struct ServerEntity {
+    /// Position last sent to clients.
+    last_sent_position: Vector3<f64>,
+    /// Velocity last sent to clients.
+    last_sent_velocity: Vector3<f64>,
+    /// Yaw last sent to clients, as angle byte.
+    last_sent_y_rot: i8,
+    /// Pitch last sent to clients, as angle byte.
+    last_sent_x_rot: i8,
+    /// `on_ground` flag last sent to clients.
+    last_sent_on_ground: bool,
+    /// Ticks since tracking started.
+    tick_count: i32,
+    /// Ticks since the last absolute position sync.
+    teleport_delay: i32,
+    /// Entity IDs of the players that have been sent this entity.
+    seen_by: FxHashSet<i32>,
+}
+
+impl ServerEntity {
+    /// Creates a tracker for the entity in its current state.
+    #[must_use]
+    pub fn new(entity: &dyn Entity) -> Self {
+        let (yaw, pitch) = entity.get_rotation();
+        Self {
+            last_sent_position: entity.get_position(),
+            last_sent_velocity: entity.get_velocity(),
+            last_sent_y_rot: to_angle_byte(yaw),
+            last_sent_x_rot: to_angle_byte(pitch),
+            last_sent_on_ground: entity.on_ground(),
+            tick_count: 0,
+            teleport_delay: 0,
+            seen_by: FxHashSet::default(),
+        }
+    }
+
+    /// Returns whether the given player has been sent this entity.
+    #[must_use]
+    pub fn is_seen_by(&self, player_id: i32) -> bool {
+        self.seen_by.contains(&player_id)
+    }
+
+    /// Spawns the entity for players that started tracking it and removes it
+    /// for players that stopped tracking it.
+    ///
+    /// Matches vanilla's `TrackedEntity.updatePlayers()`.
+    pub fn update_players(
+        &mut self,
+        entity: &dyn Entity,
+        world: &World,
+        tracking_players: &FxHashSet<i32>,
+    ) {
+        let entity_id = entity.get_id();
+        self.seen_by.retain(|player_id| {
+            if tracking_players.contains(player_id) {
+                return true;
+            }
+            if let Some(player) = world.players.get_by_entity_id(*player_id) {
+                player
+                    .connection
+                    .send_packet(CRemoveEntities::single(entity_id));
+            }
+            false
+        });
+
+        for &player_id in tracking_players {
+            if player_id == entity_id || self.seen_by.contains(&player_id) {
+                continue;
+            }
+            let Some(player) = world.players.get_by_entity_id(player_id) else {
+                continue;
+            };
+            player.connection.send_packet(entity.get_spawn_packet());
+            let data = entity.pack_all_data();
+            if !data.is_empty() {
+                player
+                    .connection
+                    .send_packet(CSetEntityData::new(entity_id, data));
+            }
+            self.seen_by.insert(player_id);
+        }
+    }
+
+    /// Sends position, rotation, velocity and entity data changes to tracking players.
+    ///
+    /// Matches vanilla's `ServerEntity.sendChanges()`.
+    pub fn send_changes(&mut self, entity: &dyn Entity, world: &World) {
+        let entity_id = entity.get_id();
+        let position = entity.get_position();
+        let velocity = entity.get_velocity();
+        let on_ground = entity.on_ground();
+        let (yaw, pitch) = entity.get_rotation();
+        let y_rot = to_angle_byte(yaw);
+        let x_rot = to_angle_byte(pitch);
+        let has_impulse = entity.take_impulse();
+        let dirty_data = entity.pack_dirty_data();
+
+        let update_interval = entity.get_type().update_interval.max(1);
+        if self.tick_count % update_interval == 0 || has_impulse || dirty_data.is_some() {
+            self.teleport_delay += 1;
+
+            let position_changed = position.sub(&self.last_sent_position).length_squared()
+                >= POSITION_CHANGE_THRESHOLD;
+            let rotation_changed = (i32::from(y_rot) - i32::from(self.last_sent_y_rot)).abs()
+                >= ROTATION_CHANGE_THRESHOLD
+                || (i32::from(x_rot) - i32::from(self.last_sent_x_rot)).abs()
+                    >= ROTATION_CHANGE_THRESHOLD;
+            let force_sync = self.teleport_delay > FORCED_POS_UPDATE_PERIOD
+                || self.last_sent_on_ground != on_ground;
+
+            let last = self.last_sent_position;
+            let deltas = (
+                calc_delta(position.x, last.x),
+                calc_delta(position.y, last.y),
+                calc_delta(position.z, last.z),
+            );
+
+            let delta_fits = matches!(deltas, (Some(_), Some(_), Some(_)));
+
+            if force_sync || (position_changed && !delta_fits) {
+                self.teleport_delay = 0;
+                self.broadcast(
+                    world,
+                    CEntityPositionSync {
+                        entity_id,
+                        x: position.x,
+                        y: position.y,
+                        z: position.z,
+                        velocity_x: velocity.x,
+                        velocity_y: velocity.y,
+                        velocity_z: velocity.z,
+                        yaw,
+                        pitch,
+                        on_ground,
+                    },
+                );
+                self.mark_position_sent(position, on_ground);
+                self.mark_rotation_sent(y_rot, x_rot);
+            } else if let (Some(dx), Some(dy), Some(dz)) = deltas {
+                if position_changed && rotation_changed {
+                    self.broadcast(
+                        world,
+                        CMoveEntityPosRot {
+                            entity_id,
+                            dx,
+                            dy,
+                            dz,
+                            y_rot,
+                            x_rot,
+                            on_ground,
+                        },
+                    );
+                    self.mark_position_sent(position, on_ground);
+                    self.mark_rotation_sent(y_rot, x_rot);
+                } else if position_changed {
+                    self.broadcast(
+                        world,
+                        CMoveEntityPos {
+                            entity_id,
+                            dx,
+                            dy,
+                            dz,
+                            on_ground,
+                        },
+                    );
+                    self.mark_position_sent(position, on_ground);
+                } else if rotation_changed {
+                    self.broadcast(
+                        world,
+                        CMoveEntityRot {
+                            entity_id,
+                            y_rot,
+                            x_rot,
+                            on_ground,
+                        },
+                    );
+                    self.mark_rotation_sent(y_rot, x_rot);
+                }
+            }
+
+            let velocity_diff = velocity.sub(&self.last_sent_velocity).length_squared();
+            if velocity_diff > 1.0E-7 || (velocity_diff > 0.0 && velocity.length_squared() == 0.0) {
+                self.last_sent_velocity = velocity;
+                self.broadcast(
+                    world,
+                    CSetEntityMotion {
+                        entity_id,
+                        velocity: LpVec3(velocity),
+                    },
+                );
+            }
+        }
+
+        if let Some(values) = dirty_data {
+            self.broadcast(world, CSetEntityData::new(entity_id, values));
+        }
+
+        self.tick_count += 1;
+    }
+
+    /// Removes the entity from every player currently tracking it.
+    pub fn remove_all(&mut self, entity_id: i32, world: &World) {
+        self.broadcast(world, CRemoveEntities::single(entity_id));
+        self.seen_by.clear();
+    }
+
+    /// Sends a packet to every player tracking this entity.
+    pub fn broadcast<P: ClientPacket>(&self, world: &World, packet: P) {
+        if self.seen_by.is_empty() {
+            return;
+        }
+        let Ok(encoded) =
+            EncodedPacket::from_bare(packet, STEEL_CONFIG.compression, ConnectionProtocol::Play)
+        else {
+            log::warn!("Failed to encode entity tracking packet");
+            return;
+        };
+        for &player_id in &self.seen_by {
+            if let Some(player) = world.players.get_by_entity_id(player_id) {
+                player.connection.send_encoded_packet(encoded.clone());
+            }
+        }
+    }
+
+    fn mark_position_sent(&mut self, position: Vector3<f64>, on_ground: bool) {
+        self.last_sent_position = position;
+        self.last_sent_on_ground = on_ground;
+    }
+
+    fn mark_rotation_sent(&mut self, y_rot: i8, x_rot: i8) {
+        self.last_sent_y_rot = y_rot;
+        self.last_sent_x_rot = x_rot;
+    }
+}
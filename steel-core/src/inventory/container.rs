@@ -6,7 +6,13 @@
 use std::mem;
 
 use enum_dispatch::enum_dispatch;
+use simdnbt::borrow::NbtCompound as NbtCompoundView;
+use simdnbt::owned::{NbtCompound, NbtList, NbtTag};
+use simdnbt::{FromNbtTag, ToNbtTag};
+use steel_registry::REGISTRY;
+use steel_registry::data_components::DataComponentPatch;
 use steel_registry::item_stack::ItemStack;
+use steel_utils::Identifier;
 
 /// Default distance buffer for container interaction range checks.
 pub const DEFAULT_DISTANCE_BUFFER: f32 = 4.0;
@@ -202,3 +208,64 @@ pub fn calculate_redstone_signal_from_container(container: &dyn Container) -> i3
     // Equivalent to Java's Mth.lerpDiscrete(totalPercent, 0, 15)
     (total_percent * 15.0).round() as i32
 }
+
+/// Writes the non-empty stacks of `items` under `key` as a list of item compounds, each
+/// tagged with its `Slot`.
+///
+/// Based on Java's `ContainerHelper.saveAllItems`.
+pub fn save_all_items(nbt: &mut NbtCompound, key: &str, items: &[ItemStack]) {
+    let mut list: Vec<NbtCompound> = Vec::new();
+    for (slot, item) in items.iter().enumerate() {
+        if !item.is_empty() {
+            // Use ItemStack's ToNbtTag implementation for proper component serialization
+            if let NbtTag::Compound(mut item_nbt) = item.clone().to_nbt_tag() {
+                item_nbt.insert("Slot", slot as i8);
+                list.push(item_nbt);
+            }
+        }
+    }
+    nbt.insert(key, NbtList::Compound(list));
+}
+
+/// Reads a list written by [`save_all_items`] into `items`. Entries with a slot outside
+/// `items` or an unknown item are skipped.
+///
+/// Based on Java's `ContainerHelper.loadAllItems`.
+pub fn load_all_items(nbt: &NbtCompoundView<'_, '_>, key: &str, items: &mut [ItemStack]) {
+    let Some(compounds) = nbt.list(key).and_then(|list| list.compounds()) else {
+        return;
+    };
+    for compound in compounds {
+        // Each item has a "Slot" byte and item data
+        if let Some(slot) = compound.byte("Slot")
+            && let Some(target) = items.get_mut(slot as u8 as usize)
+            && let Some(item) = item_from_borrowed_compound(&compound)
+        {
+            *target = item;
+        }
+    }
+}
+
+/// Parses an `ItemStack` from a borrowed `NbtCompound`.
+///
+/// This mirrors the logic of `ItemStack::from_nbt_tag` but works directly with
+/// borrowed compound data, properly parsing component patches.
+fn item_from_borrowed_compound(compound: &NbtCompoundView<'_, '_>) -> Option<ItemStack> {
+    // Get the item ID
+    let id_str = compound.string("id")?.to_str();
+    let id = id_str.parse::<Identifier>().ok()?;
+
+    // Look up the item in the registry
+    let item = REGISTRY.items.by_key(&id)?;
+
+    // Get the count (default to 1 if not present)
+    let count = compound.int("count").unwrap_or(1);
+
+    // Parse components if present
+    let patch = compound
+        .get("components")
+        .and_then(DataComponentPatch::from_nbt_tag)
+        .unwrap_or_default();
+
+    Some(ItemStack::with_count_and_patch(item, count, patch))
+}
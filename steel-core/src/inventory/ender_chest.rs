@@ -0,0 +1,65 @@
+//! The per-player ender chest inventory.
+
+use steel_registry::item_stack::ItemStack;
+
+use crate::inventory::container::Container;
+
+/// Number of slots in an ender chest (3 rows of 9).
+pub const ENDER_CHEST_SLOTS: usize = 27;
+
+/// The items a player keeps in their ender chest.
+///
+/// Every ender chest block opens the same inventory for a given player.
+/// Based on Java's `PlayerEnderChestContainer`.
+pub struct PlayerEnderChestContainer {
+    items: Vec<ItemStack>,
+}
+
+impl PlayerEnderChestContainer {
+    /// Creates a new empty ender chest inventory.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            items: vec![ItemStack::empty(); ENDER_CHEST_SLOTS],
+        }
+    }
+
+    /// Returns a reference to the items in the ender chest.
+    #[must_use]
+    pub fn items(&self) -> &[ItemStack] {
+        &self.items
+    }
+
+    /// Returns a mutable reference to the items in the ender chest.
+    pub fn items_mut(&mut self) -> &mut [ItemStack] {
+        &mut self.items
+    }
+}
+
+impl Default for PlayerEnderChestContainer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Container for PlayerEnderChestContainer {
+    fn get_container_size(&self) -> usize {
+        ENDER_CHEST_SLOTS
+    }
+
+    fn get_item(&self, slot: usize) -> &ItemStack {
+        &self.items[slot]
+    }
+
+    fn get_item_mut(&mut self, slot: usize) -> &mut ItemStack {
+        &mut self.items[slot]
+    }
+
+    fn set_item(&mut self, slot: usize, stack: ItemStack) {
+        self.items[slot] = stack;
+    }
+
+    fn set_changed(&mut self) {
+        // Saved with the player, so there is no dirty state to track.
+    }
+}
@@ -7,6 +7,7 @@ pub mod chest_menu;
 pub mod container;
 pub mod crafting;
 pub mod crafting_menu;
+pub mod ender_chest;
 pub mod equipment;
 pub mod inventory_menu;
 pub mod lock;
@@ -17,5 +18,6 @@ pub mod slot;
 
 pub use chest_menu::{ChestMenu, ChestMenuProvider};
 pub use crafting_menu::{CraftingMenu, CraftingMenuProvider};
+pub use ender_chest::PlayerEnderChestContainer;
 pub use lock::SyncPlayerInv;
 pub use menu_provider::{MenuInstance, MenuProvider};
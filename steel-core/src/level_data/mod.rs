@@ -16,6 +16,8 @@ use steel_registry::game_rules::{GameRuleValue, GameRuleValues};
 use steel_utils::BlockPos;
 use tokio::fs;
 
+use crate::chunk::flat_settings::FlatSettings;
+
 /// Persistent level data that gets saved to disk.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LevelData {
@@ -36,6 +38,21 @@ pub struct LevelData {
     pub game_rules_values: GameRuleValues,
     /// Whether the world has been initialized.
     pub initialized: bool,
+    /// The superflat layers, or `None` if the world uses its dimension's usual generator.
+    ///
+    /// Always written, so that a missing field means the world predates noise generation.
+    #[serde(default = "legacy_flat_settings")]
+    pub flat_settings: Option<FlatSettings>,
+}
+
+/// The generator of worlds whose `level.json` has no `flat_settings`, which were all
+/// generated as classic superflat.
+#[allow(
+    clippy::unnecessary_wraps,
+    reason = "serde defaults must return the field type"
+)]
+fn legacy_flat_settings() -> Option<FlatSettings> {
+    Some(FlatSettings::default())
 }
 
 /// Spawn point data.
@@ -96,6 +113,7 @@ impl LevelData {
             game_rules: FxHashMap::default(),
             game_rules_values: GameRuleValues::new(&REGISTRY.game_rules),
             initialized: false,
+            flat_settings: None,
         }
     }
 
@@ -145,9 +163,13 @@ pub struct LevelDataManager {
 impl LevelDataManager {
     /// Creates a new level data manager for the given world directory.
     ///
-    /// If `level.json` exists, it will be loaded (the provided seed is ignored).
-    /// Otherwise, new data will be created with the provided seed.
-    pub async fn new(world_dir: impl AsRef<Path>, seed: i64) -> io::Result<Self> {
+    /// If `level.json` exists, it will be loaded (the provided seed and flat settings are
+    /// ignored). Otherwise, new data will be created with them.
+    pub async fn new(
+        world_dir: impl AsRef<Path>,
+        seed: i64,
+        flat_settings: Option<FlatSettings>,
+    ) -> io::Result<Self> {
         let path = world_dir.as_ref().join("level.json");
 
         let data = if path.exists() {
@@ -163,8 +185,11 @@ impl LevelDataManager {
             loaded.load_game_rules();
             loaded
         } else {
-            // Create new level data with the provided seed
-            LevelData::new_with_seed(seed)
+            // Create new level data with the provided seed and generator
+            LevelData {
+                flat_settings,
+                ..LevelData::new_with_seed(seed)
+            }
         };
 
         Ok(Self {
@@ -255,3 +280,60 @@ impl LevelDataManager {
         self.dirty = true;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn worlds_without_flat_settings_stay_flat() {
+        // A level.json as written before superflat layers could be configured
+        let loaded: LevelData = serde_json::from_str(
+            r#"{
+                "seed": 42,
+                "game_time": 1200,
+                "day_time": 1200,
+                "spawn": { "x": 0, "y": 64, "z": 0, "angle": 0.0 },
+                "weather": {
+                    "raining": false,
+                    "rain_time": 0,
+                    "thundering": false,
+                    "thunder_time": 0,
+                    "clear_weather_time": 0
+                },
+                "game_rules": { "advance_time": true, "random_tick_speed": 3 },
+                "initialized": true
+            }"#,
+        )
+        .expect("valid level.json");
+        assert_eq!(loaded.flat_settings, Some(FlatSettings::default()));
+    }
+
+    #[test]
+    fn noise_worlds_stay_noise() {
+        let data: LevelData = serde_json::from_str(
+            r#"{
+                "seed": 42,
+                "game_time": 0,
+                "day_time": 0,
+                "spawn": { "x": 0, "y": 64, "z": 0, "angle": 0.0 },
+                "weather": {
+                    "raining": false,
+                    "rain_time": 0,
+                    "thundering": false,
+                    "thunder_time": 0,
+                    "clear_weather_time": 0
+                },
+                "game_rules": {},
+                "initialized": false,
+                "flat_settings": null
+            }"#,
+        )
+        .expect("valid level.json");
+        assert_eq!(data.flat_settings, None);
+
+        let json = serde_json::to_string(&data).expect("serializes");
+        let again: LevelData = serde_json::from_str(&json).expect("round trips");
+        assert_eq!(again.flat_settings, None);
+    }
+}
@@ -13,7 +13,12 @@ pub mod config;
 pub mod entity;
 pub mod inventory;
 pub mod level_data;
+pub mod lighting;
 pub mod physics;
 pub mod player;
 pub mod server;
+#[cfg(test)]
+mod test_utils;
+pub mod ticks;
 pub mod world;
+pub mod worldgen;
@@ -0,0 +1,392 @@
+//! Light initialization for generated chunks and relighting of block changes.
+
+use std::mem;
+
+use parking_lot::{RwLockReadGuard, RwLockWriteGuard};
+use steel_registry::REGISTRY;
+use steel_registry::blocks::block_state_ext::BlockStateExt;
+use steel_registry::blocks::light::MAX_LIGHT_LEVEL;
+use steel_utils::locks::{SyncMutex, SyncRwLock};
+use steel_utils::{BlockPos, BlockStateId, ChunkPos};
+
+use crate::chunk::paletted_container::{BlockPalette, PalettedContainer};
+use crate::chunk::section::{ChunkSection, Sections};
+use crate::lighting::{LightLayer, LightPropagator, LightRegion};
+
+/// Collects block changes that affect light and relights them in batches.
+///
+/// Similar to vanilla's `ThreadedLevelLightEngine`, changes are queued while the world ticks
+/// and processed once per tick, before block and light changes are sent to players.
+pub struct LevelLightEngine {
+    has_skylight: bool,
+    pending: SyncMutex<Vec<BlockPos>>,
+    /// Held shared while generation lights chunks and exclusively while block changes are
+    /// relit, since removing light can't run alongside other threads raising it.
+    update_lock: SyncRwLock<()>,
+}
+
+impl LevelLightEngine {
+    /// Creates a light engine for a dimension.
+    #[must_use]
+    pub fn new(has_skylight: bool) -> Self {
+        Self {
+            has_skylight,
+            pending: SyncMutex::new(Vec::new()),
+            update_lock: SyncRwLock::new(()),
+        }
+    }
+
+    /// Returns whether the dimension has sky light.
+    #[must_use]
+    pub const fn has_skylight(&self) -> bool {
+        self.has_skylight
+    }
+
+    /// Returns whether replacing `old_state` with `new_state` can change light.
+    ///
+    /// Matches vanilla's `LightEngine.hasDifferentLightProperties()`.
+    #[must_use]
+    pub fn has_different_light_properties(
+        old_state: BlockStateId,
+        new_state: BlockStateId,
+    ) -> bool {
+        old_state != new_state
+            && REGISTRY.blocks.get_light_properties(old_state)
+                != REGISTRY.blocks.get_light_properties(new_state)
+    }
+
+    /// Queues a position whose light has to be recomputed.
+    pub fn check_block(&self, pos: BlockPos) {
+        self.pending.lock().push(pos);
+    }
+
+    /// Takes all queued positions.
+    pub(crate) fn take_pending(&self) -> Vec<BlockPos> {
+        mem::take(&mut *self.pending.lock())
+    }
+
+    /// Locks out block change updates while a chunk is lit during generation.
+    pub(crate) fn generation_lock(&self) -> RwLockReadGuard<'_, ()> {
+        self.update_lock.read()
+    }
+
+    /// Waits for generation lighting to finish and blocks it while block changes are relit.
+    pub(crate) fn update_lock(&self) -> RwLockWriteGuard<'_, ()> {
+        self.update_lock.write()
+    }
+
+    /// Recomputes the light around the given positions.
+    pub fn run_updates(&self, region: &mut LightRegion<'_>, positions: &[BlockPos]) {
+        for layer in LightLayer::ALL {
+            if layer == LightLayer::Sky && !self.has_skylight {
+                continue;
+            }
+            let mut propagator = LightPropagator::new(layer);
+            for &pos in positions {
+                propagator.check_block(region, pos);
+            }
+            propagator.run(region);
+        }
+    }
+}
+
+/// Resets the light of a chunk to its sources.
+///
+/// Block light is set to the emission of every block. Sky light is set to the maximum from
+/// the top of the world down to the first block that doesn't let skylight through.
+/// Nothing is propagated yet, that happens in [`light_chunk`] once the neighbors are ready.
+pub fn initialize_chunk_light(sections: &Sections, has_skylight: bool) {
+    for holder in &sections.sections {
+        let mut guard = holder.write();
+        let section = &mut *guard;
+        section.sky_light.fill(0);
+        section.block_light.fill(0);
+        initialize_block_sources(section);
+    }
+
+    if has_skylight {
+        initialize_sky_sources(sections);
+    }
+}
+
+fn initialize_block_sources(section: &mut ChunkSection) {
+    match &section.states {
+        PalettedContainer::Homogeneous(state) => {
+            section.block_light.fill(state.get_light_emission())
+        }
+        PalettedContainer::Heterogeneous(data) => {
+            if data
+                .palette
+                .iter()
+                .all(|(state, _)| state.get_light_emission() == 0)
+            {
+                return;
+            }
+            for (index, state) in data.iter_values().enumerate() {
+                let emission = state.get_light_emission();
+                if emission > 0 {
+                    section.block_light.set_index(index, emission);
+                }
+            }
+        }
+    }
+}
+
+fn initialize_sky_sources(sections: &Sections) {
+    // Columns that are still open to the sky, indexed by z * 16 + x
+    let mut open = [true; 256];
+    let mut open_count = open.len();
+
+    for holder in sections.sections.iter().rev() {
+        let mut guard = holder.write();
+        let section = &mut *guard;
+
+        if open_count == open.len()
+            && let PalettedContainer::Homogeneous(state) = &section.states
+            && state.propagates_skylight_down()
+        {
+            section.sky_light.fill(MAX_LIGHT_LEVEL);
+            continue;
+        }
+
+        for y in (0..16).rev() {
+            for z in 0..16 {
+                for x in 0..16 {
+                    let column = z * 16 + x;
+                    if !open[column] {
+                        continue;
+                    }
+                    if section.states.get(x, y, z).propagates_skylight_down() {
+                        section.sky_light.set(x, y, z, MAX_LIGHT_LEVEL);
+                    } else {
+                        open[column] = false;
+                        open_count -= 1;
+                    }
+                }
+            }
+        }
+
+        if open_count == 0 {
+            break;
+        }
+    }
+}
+
+/// Propagates the light of a chunk into itself and its neighbors in the region, and the
+/// light of the neighbors into the chunk.
+///
+/// The chunk must have been initialized with [`initialize_chunk_light`].
+pub fn light_chunk(region: &mut LightRegion<'_>, pos: ChunkPos) {
+    let Some(sections) = region.chunk(pos) else {
+        return;
+    };
+
+    for layer in LightLayer::ALL {
+        if layer == LightLayer::Sky && !region.has_skylight() {
+            continue;
+        }
+        let mut propagator = LightPropagator::new(layer);
+        seed_chunk(region, &mut propagator, layer, sections, pos);
+        if layer == LightLayer::Sky {
+            seed_sky_columns(region, &mut propagator, sections, pos);
+        }
+        seed_edges(region, &mut propagator, layer, pos);
+        propagator.run(region);
+    }
+}
+
+/// Queues every lit block of the chunk, except full sky light which is handled per column.
+fn seed_chunk(
+    region: &LightRegion<'_>,
+    propagator: &mut LightPropagator,
+    layer: LightLayer,
+    sections: &Sections,
+    pos: ChunkPos,
+) {
+    let skip = |level: u8| level == 0 || (layer == LightLayer::Sky && level == MAX_LIGHT_LEVEL);
+
+    for (section_index, holder) in sections.sections.iter().enumerate() {
+        let guard = holder.read();
+        let data = guard.light(layer);
+        if data.is_definitely_homogenous() && skip(data.get_index(0)) {
+            continue;
+        }
+
+        let base_y = region.min_y() + section_index as i32 * 16;
+        for index in 0..BlockPalette::VOLUME {
+            let level = data.get_index(index);
+            if !skip(level) {
+                propagator.enqueue_increase(block_pos_of(pos, base_y, index), level);
+            }
+        }
+    }
+}
+
+/// Queues the full sky light blocks that can light a lower neighboring column.
+fn seed_sky_columns(
+    region: &LightRegion<'_>,
+    propagator: &mut LightPropagator,
+    sections: &Sections,
+    pos: ChunkPos,
+) {
+    let heights = sky_column_heights(sections, region.min_y(), region.max_y());
+    let height_at = |x: i32, z: i32| {
+        ((0..16).contains(&x) && (0..16).contains(&z)).then(|| heights[(z * 16 + x) as usize])
+    };
+
+    for z in 0..16 {
+        for x in 0..16 {
+            let height = heights[(z * 16 + x) as usize];
+            let highest_neighbor = [(x - 1, z), (x + 1, z), (x, z - 1), (x, z + 1)]
+                .into_iter()
+                .filter_map(|(nx, nz)| height_at(nx, nz))
+                .max()
+                .unwrap_or(height);
+
+            // The lowest block always spreads down, the ones above only to the side
+            let top = highest_neighbor.max(height + 1).min(region.max_y());
+            for y in height..top {
+                propagator.enqueue_increase(
+                    BlockPos::new(pos.0.x * 16 + x, y, pos.0.y * 16 + z),
+                    MAX_LIGHT_LEVEL,
+                );
+            }
+        }
+    }
+}
+
+/// Returns the lowest Y with full sky light in each column that reaches the sky, indexed by
+/// z * 16 + x. Columns blocked at the top of the world get `max_y`.
+fn sky_column_heights(sections: &Sections, min_y: i32, max_y: i32) -> [i32; 256] {
+    let mut heights = [max_y; 256];
+    let mut open = [true; 256];
+    let mut open_count = open.len();
+
+    for (section_index, holder) in sections.sections.iter().enumerate().rev() {
+        let guard = holder.read();
+        let sky = &guard.sky_light;
+        let base_y = min_y + section_index as i32 * 16;
+
+        if sky.is_definitely_homogenous() {
+            if sky.get_index(0) != MAX_LIGHT_LEVEL {
+                break;
+            }
+            for (column, height) in heights.iter_mut().enumerate() {
+                if open[column] {
+                    *height = base_y;
+                }
+            }
+            continue;
+        }
+
+        for y in (0..16).rev() {
+            for z in 0..16 {
+                for x in 0..16 {
+                    let column = z * 16 + x;
+                    if !open[column] {
+                        continue;
+                    }
+                    if sky.get(x, y, z) == MAX_LIGHT_LEVEL {
+                        heights[column] = base_y + y as i32;
+                    } else {
+                        open[column] = false;
+                        open_count -= 1;
+                    }
+                }
+            }
+        }
+
+        if open_count == 0 {
+            break;
+        }
+    }
+
+    heights
+}
+
+/// Queues the blocks on both sides of the chunk's borders that are brighter than the block
+/// across from them.
+fn seed_edges(
+    region: &LightRegion<'_>,
+    propagator: &mut LightPropagator,
+    layer: LightLayer,
+    pos: ChunkPos,
+) {
+    let Some(sections) = region.chunk(pos) else {
+        return;
+    };
+
+    // (neighbor offset, local x/z of the border in this chunk, local x/z in the neighbor)
+    let sides: [(
+        (i32, i32),
+        fn(usize) -> (usize, usize),
+        fn(usize) -> (usize, usize),
+    ); 4] = [
+        ((-1, 0), |t| (0, t), |t| (15, t)),
+        ((1, 0), |t| (15, t), |t| (0, t)),
+        ((0, -1), |t| (t, 0), |t| (t, 15)),
+        ((0, 1), |t| (t, 15), |t| (t, 0)),
+    ];
+
+    for ((dx, dz), local, across) in sides {
+        let neighbor_pos = ChunkPos::new(pos.0.x + dx, pos.0.y + dz);
+        let Some(neighbor) = region.chunk(neighbor_pos) else {
+            continue;
+        };
+
+        for (section_index, (holder, neighbor_holder)) in sections
+            .sections
+            .iter()
+            .zip(neighbor.sections.iter())
+            .enumerate()
+        {
+            let guard = holder.read();
+            let neighbor_guard = neighbor_holder.read();
+            let data = guard.light(layer);
+            let neighbor_data = neighbor_guard.light(layer);
+            if data.is_definitely_homogenous()
+                && neighbor_data.is_definitely_homogenous()
+                && data.get_index(0) == neighbor_data.get_index(0)
+            {
+                continue;
+            }
+
+            let base_y = region.min_y() + section_index as i32 * 16;
+            for y in 0..16 {
+                for t in 0..16 {
+                    let (x, z) = local(t);
+                    let (nx, nz) = across(t);
+                    let level = data.get(x, y, z);
+                    let neighbor_level = neighbor_data.get(nx, y, nz);
+                    if level > neighbor_level + 1 {
+                        propagator.enqueue_increase(
+                            BlockPos::new(
+                                pos.0.x * 16 + x as i32,
+                                base_y + y as i32,
+                                pos.0.y * 16 + z as i32,
+                            ),
+                            level,
+                        );
+                    } else if neighbor_level > level + 1 {
+                        propagator.enqueue_increase(
+                            BlockPos::new(
+                                neighbor_pos.0.x * 16 + nx as i32,
+                                base_y + y as i32,
+                                neighbor_pos.0.y * 16 + nz as i32,
+                            ),
+                            neighbor_level,
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Converts an index in y, z, x order inside a section to a block position.
+fn block_pos_of(chunk: ChunkPos, base_y: i32, index: usize) -> BlockPos {
+    let x = (index & 15) as i32;
+    let z = ((index >> 4) & 15) as i32;
+    let y = (index >> 8) as i32;
+    BlockPos::new(chunk.0.x * 16 + x, base_y + y, chunk.0.y * 16 + z)
+}
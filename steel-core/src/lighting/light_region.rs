@@ -0,0 +1,168 @@
+//! A view over the light of a group of loaded chunks.
+
+use std::mem;
+
+use rustc_hash::{FxHashMap, FxHashSet};
+use steel_registry::REGISTRY;
+use steel_registry::blocks::light::{LightProperties, MAX_LIGHT_LEVEL};
+use steel_utils::{BlockPos, ChunkPos, SectionPos};
+
+use crate::chunk::data_layer::DataLayer;
+use crate::chunk::section::{SectionHolder, Sections};
+use crate::lighting::LightLayer;
+
+/// Read and write access to the light of a set of chunks.
+///
+/// Positions in chunks that weren't added are treated as unavailable, so propagation
+/// stops at the edge of the region. Every access locks a single section, which keeps
+/// regions of neighboring chunks usable from several threads at once.
+pub struct LightRegion<'a> {
+    chunks: FxHashMap<ChunkPos, &'a Sections>,
+    min_y: i32,
+    max_y: i32,
+    has_skylight: bool,
+    /// Sections whose light changed, as (chunk, layer, light section index).
+    changed: FxHashSet<(ChunkPos, LightLayer, usize)>,
+}
+
+impl<'a> LightRegion<'a> {
+    /// Creates an empty region for a world with the given height.
+    #[must_use]
+    pub fn new(min_y: i32, height: i32, has_skylight: bool) -> Self {
+        Self {
+            chunks: FxHashMap::default(),
+            min_y,
+            max_y: min_y + height,
+            has_skylight,
+            changed: FxHashSet::default(),
+        }
+    }
+
+    /// Adds a chunk to the region.
+    pub fn add_chunk(&mut self, pos: ChunkPos, sections: &'a Sections) {
+        self.chunks.insert(pos, sections);
+    }
+
+    /// Returns the sections of a chunk in the region.
+    #[must_use]
+    pub fn chunk(&self, pos: ChunkPos) -> Option<&'a Sections> {
+        self.chunks.get(&pos).copied()
+    }
+
+    /// Returns whether this world has sky light.
+    #[must_use]
+    pub const fn has_skylight(&self) -> bool {
+        self.has_skylight
+    }
+
+    /// Returns the lowest block Y coordinate.
+    #[must_use]
+    pub const fn min_y(&self) -> i32 {
+        self.min_y
+    }
+
+    /// Returns the block Y coordinate just above the world.
+    #[must_use]
+    pub const fn max_y(&self) -> i32 {
+        self.max_y
+    }
+
+    /// Takes the sections whose light changed since the last call.
+    ///
+    /// Section indices are light section indices, which start one section below the world.
+    pub fn take_changes(&mut self) -> FxHashSet<(ChunkPos, LightLayer, usize)> {
+        mem::take(&mut self.changed)
+    }
+
+    fn section_at(&self, pos: BlockPos) -> Option<(ChunkPos, usize, &'a SectionHolder)> {
+        let y = pos.y();
+        if y < self.min_y || y >= self.max_y {
+            return None;
+        }
+        let chunk_pos = ChunkPos::new(
+            SectionPos::block_to_section_coord(pos.x()),
+            SectionPos::block_to_section_coord(pos.z()),
+        );
+        let section_index = ((y - self.min_y) >> 4) as usize;
+        let section = self.chunks.get(&chunk_pos)?.sections.get(section_index)?;
+        Some((chunk_pos, section_index, section))
+    }
+
+    const fn layer_index(pos: BlockPos) -> usize {
+        DataLayer::index(
+            (pos.0.x & 15) as usize,
+            (pos.0.y & 15) as usize,
+            (pos.0.z & 15) as usize,
+        )
+    }
+
+    /// Gets the light at a position, or `None` if it isn't available.
+    ///
+    /// Above the world, sky light is always at its maximum.
+    #[must_use]
+    pub fn get_light(&self, layer: LightLayer, pos: BlockPos) -> Option<u8> {
+        if pos.y() >= self.max_y {
+            let chunk_pos = ChunkPos::new(
+                SectionPos::block_to_section_coord(pos.x()),
+                SectionPos::block_to_section_coord(pos.z()),
+            );
+            return (layer == LightLayer::Sky
+                && self.has_skylight
+                && self.chunks.contains_key(&chunk_pos))
+            .then_some(MAX_LIGHT_LEVEL);
+        }
+        let (_, _, section) = self.section_at(pos)?;
+        Some(
+            section
+                .read()
+                .light(layer)
+                .get_index(Self::layer_index(pos)),
+        )
+    }
+
+    /// Sets the light at a position. Unavailable positions are ignored.
+    pub fn set_light(&mut self, layer: LightLayer, pos: BlockPos, level: u8) {
+        let Some((chunk_pos, section_index, section)) = self.section_at(pos) else {
+            return;
+        };
+        let index = Self::layer_index(pos);
+        let mut guard = section.write();
+        let data = guard.light_mut(layer);
+        if data.get_index(index) != level {
+            data.set_index(index, level);
+            drop(guard);
+            self.changed.insert((chunk_pos, layer, section_index + 1));
+        }
+    }
+
+    /// Raises the light at a position to `level` if it is currently lower.
+    ///
+    /// The check and the write happen under one lock, so concurrent raises never lower light.
+    pub fn raise_light(&mut self, layer: LightLayer, pos: BlockPos, level: u8) -> bool {
+        let Some((chunk_pos, section_index, section)) = self.section_at(pos) else {
+            return false;
+        };
+        let index = Self::layer_index(pos);
+        let mut guard = section.write();
+        let data = guard.light_mut(layer);
+        if data.get_index(index) >= level {
+            return false;
+        }
+        data.set_index(index, level);
+        drop(guard);
+        self.changed.insert((chunk_pos, layer, section_index + 1));
+        true
+    }
+
+    /// Returns the light properties of the block at a position inside the world.
+    #[must_use]
+    pub fn light_properties(&self, pos: BlockPos) -> Option<LightProperties> {
+        let (_, _, section) = self.section_at(pos)?;
+        let state = section.read().states.get(
+            (pos.0.x & 15) as usize,
+            (pos.0.y & 15) as usize,
+            (pos.0.z & 15) as usize,
+        );
+        Some(REGISTRY.blocks.get_light_properties(state))
+    }
+}
@@ -0,0 +1,30 @@
+//! Block and sky light.
+//!
+//! Light is stored per section in [`DataLayer`](crate::chunk::data_layer::DataLayer)s and
+//! computed in two places:
+//! - During chunk generation, `initialize_light` places the light sources of a chunk and
+//!   `light` propagates them into the chunk and its neighbors.
+//! - When a block with different light properties is placed, the position is queued on the
+//!   [`LevelLightEngine`] and relit at the end of the tick, before changes are broadcast.
+
+mod engine;
+mod light_region;
+mod propagator;
+
+pub use engine::{LevelLightEngine, initialize_chunk_light, light_chunk};
+pub use light_region::LightRegion;
+pub use propagator::LightPropagator;
+
+/// The two kinds of light a section stores.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LightLayer {
+    /// Light coming from the sky.
+    Sky,
+    /// Light emitted by blocks.
+    Block,
+}
+
+impl LightLayer {
+    /// Both light layers.
+    pub const ALL: [Self; 2] = [Self::Sky, Self::Block];
+}
@@ -0,0 +1,250 @@
+//! Breadth-first propagation of a single light layer.
+
+use std::collections::VecDeque;
+
+use steel_registry::blocks::light::MAX_LIGHT_LEVEL;
+use steel_registry::blocks::properties::Direction;
+use steel_utils::BlockPos;
+
+use crate::lighting::{LightLayer, LightRegion};
+
+const DIRECTIONS: [Direction; 6] = [
+    Direction::Down,
+    Direction::Up,
+    Direction::North,
+    Direction::South,
+    Direction::West,
+    Direction::East,
+];
+
+/// Spreads light increases and removals through a [`LightRegion`].
+///
+/// Light drops by the target block's light block value (at least 1) per step. Sky light at
+/// the maximum level travels straight down through blocks that propagate skylight without
+/// dimming, like vanilla's `SkyLightEngine`.
+pub struct LightPropagator {
+    layer: LightLayer,
+    increase: VecDeque<(BlockPos, u8)>,
+    decrease: VecDeque<(BlockPos, u8)>,
+}
+
+impl LightPropagator {
+    /// Creates a propagator for one light layer.
+    #[must_use]
+    pub fn new(layer: LightLayer) -> Self {
+        Self {
+            layer,
+            increase: VecDeque::new(),
+            decrease: VecDeque::new(),
+        }
+    }
+
+    /// Queues a position whose current light should be spread to its neighbors.
+    pub fn enqueue_increase(&mut self, pos: BlockPos, level: u8) {
+        if level > 0 {
+            self.increase.push_back((pos, level));
+        }
+    }
+
+    /// Queues a full re-evaluation of the light at a position whose block changed.
+    pub fn check_block(&mut self, region: &mut LightRegion<'_>, pos: BlockPos) {
+        let Some(old_level) = region.get_light(self.layer, pos) else {
+            return;
+        };
+
+        if old_level > 0 {
+            region.set_light(self.layer, pos, 0);
+            self.decrease.push_back((pos, old_level));
+        }
+
+        if self.layer == LightLayer::Block
+            && let Some(properties) = region.light_properties(pos)
+            && properties.emission > 0
+        {
+            region.set_light(self.layer, pos, properties.emission);
+            self.increase.push_back((pos, properties.emission));
+        }
+
+        // Let the surroundings flow back into the position
+        for direction in DIRECTIONS {
+            let neighbor = direction.relative(&pos);
+            if let Some(level) = region.get_light(self.layer, neighbor) {
+                self.enqueue_increase(neighbor, level);
+            }
+        }
+    }
+
+    /// Runs all queued removals, then all queued increases.
+    pub fn run(&mut self, region: &mut LightRegion<'_>) {
+        self.propagate_decreases(region);
+        self.propagate_increases(region);
+    }
+
+    fn propagate_decreases(&mut self, region: &mut LightRegion<'_>) {
+        while let Some((pos, level)) = self.decrease.pop_front() {
+            for direction in DIRECTIONS {
+                let neighbor = direction.relative(&pos);
+                let Some(neighbor_level) = region.get_light(self.layer, neighbor) else {
+                    continue;
+                };
+                if neighbor_level == 0 {
+                    continue;
+                }
+
+                let straight_down = self.layer == LightLayer::Sky
+                    && direction == Direction::Down
+                    && level == MAX_LIGHT_LEVEL
+                    && neighbor_level == MAX_LIGHT_LEVEL;
+
+                if neighbor_level < level || straight_down {
+                    // The neighbor may have been lit by this position, clear it and keep going
+                    region.set_light(self.layer, neighbor, 0);
+                    self.decrease.push_back((neighbor, neighbor_level));
+
+                    if self.layer == LightLayer::Block
+                        && let Some(properties) = region.light_properties(neighbor)
+                        && properties.emission > 0
+                    {
+                        region.set_light(self.layer, neighbor, properties.emission);
+                        self.increase.push_back((neighbor, properties.emission));
+                    }
+                } else {
+                    // The neighbor has its own source, let it refill the cleared area
+                    self.increase.push_back((neighbor, neighbor_level));
+                }
+            }
+        }
+    }
+
+    fn propagate_increases(&mut self, region: &mut LightRegion<'_>) {
+        while let Some((pos, level)) = self.increase.pop_front() {
+            // Skip entries that were lowered or raised since they were queued
+            if region.get_light(self.layer, pos) != Some(level) {
+                continue;
+            }
+
+            for direction in DIRECTIONS {
+                let neighbor = direction.relative(&pos);
+                let Some(properties) = region.light_properties(neighbor) else {
+                    continue;
+                };
+
+                let new_level = if self.layer == LightLayer::Sky
+                    && direction == Direction::Down
+                    && level == MAX_LIGHT_LEVEL
+                    && properties.propagates_skylight_down
+                {
+                    MAX_LIGHT_LEVEL
+                } else {
+                    level.saturating_sub(properties.light_block.max(1))
+                };
+
+                if new_level > 0 && region.raise_light(self.layer, neighbor, new_level) {
+                    self.increase.push_back((neighbor, new_level));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use steel_registry::{REGISTRY, RegistryExt, vanilla_blocks};
+    use steel_utils::ChunkPos;
+
+    use super::*;
+    use crate::chunk::paletted_container::{BiomePalette, BlockPalette};
+    use crate::chunk::section::{ChunkSection, Sections};
+    use crate::lighting::initialize_chunk_light;
+    use crate::test_utils::init_test_registry;
+
+    fn stone_floor_chunk() -> Sections {
+        let stone = REGISTRY.blocks.get_base_state_id(vanilla_blocks::STONE);
+        let sections = (0..2)
+            .map(|i| {
+                let states = if i == 0 {
+                    BlockPalette::Homogeneous(stone)
+                } else {
+                    BlockPalette::Homogeneous(
+                        REGISTRY.blocks.get_base_state_id(vanilla_blocks::AIR),
+                    )
+                };
+                ChunkSection::new_with_biomes(states, BiomePalette::Homogeneous(0))
+            })
+            .collect::<Vec<_>>();
+        Sections::from_owned(sections.into_boxed_slice())
+    }
+
+    #[test]
+    fn test_block_light_spreads_and_is_removed() {
+        init_test_registry();
+        let sections = stone_floor_chunk();
+        let mut region = LightRegion::new(0, 32, true);
+        region.add_chunk(ChunkPos::new(0, 0), &sections);
+
+        let glowstone = REGISTRY.blocks.get_base_state_id(vanilla_blocks::GLOWSTONE);
+        let air = REGISTRY.blocks.get_base_state_id(vanilla_blocks::AIR);
+        let source_pos = BlockPos::new(8, 20, 8);
+
+        sections.set_relative_block(8, 20, 8, glowstone);
+        let mut propagator = LightPropagator::new(LightLayer::Block);
+        propagator.check_block(&mut region, source_pos);
+        propagator.run(&mut region);
+
+        assert_eq!(region.get_light(LightLayer::Block, source_pos), Some(15));
+        assert_eq!(
+            region.get_light(LightLayer::Block, BlockPos::new(8, 20, 11)),
+            Some(12)
+        );
+        // Light doesn't enter the stone below
+        assert_eq!(
+            region.get_light(LightLayer::Block, BlockPos::new(8, 15, 8)),
+            Some(0)
+        );
+
+        sections.set_relative_block(8, 20, 8, air);
+        propagator.check_block(&mut region, source_pos);
+        propagator.run(&mut region);
+
+        assert_eq!(region.get_light(LightLayer::Block, source_pos), Some(0));
+        assert_eq!(
+            region.get_light(LightLayer::Block, BlockPos::new(8, 20, 11)),
+            Some(0)
+        );
+    }
+
+    #[test]
+    fn test_sky_light_is_blocked_and_restored() {
+        init_test_registry();
+        let sections = stone_floor_chunk();
+        initialize_chunk_light(&sections, true);
+        let mut region = LightRegion::new(0, 32, true);
+        region.add_chunk(ChunkPos::new(0, 0), &sections);
+
+        let below = BlockPos::new(4, 16, 4);
+        assert_eq!(region.get_light(LightLayer::Sky, below), Some(15));
+        assert_eq!(
+            region.get_light(LightLayer::Sky, BlockPos::new(4, 15, 4)),
+            Some(0)
+        );
+
+        // A roof over one column lets in only the light that comes from the side
+        let stone = REGISTRY.blocks.get_base_state_id(vanilla_blocks::STONE);
+        let roof = BlockPos::new(4, 25, 4);
+        sections.set_relative_block(4, 25, 4, stone);
+        let mut propagator = LightPropagator::new(LightLayer::Sky);
+        propagator.check_block(&mut region, roof);
+        propagator.run(&mut region);
+
+        assert_eq!(region.get_light(LightLayer::Sky, roof), Some(0));
+        assert_eq!(region.get_light(LightLayer::Sky, below), Some(14));
+
+        let air = REGISTRY.blocks.get_base_state_id(vanilla_blocks::AIR);
+        sections.set_relative_block(4, 25, 4, air);
+        propagator.check_block(&mut region, roof);
+        propagator.run(&mut region);
+
+        assert_eq!(region.get_light(LightLayer::Sky, roof), Some(15));
+        assert_eq!(region.get_light(LightLayer::Sky, below), Some(15));
+    }
+}
@@ -0,0 +1,128 @@
+//! How entities float in fluids and get pushed by their currents.
+
+use steel_registry::blocks::block_state_ext::BlockStateExt;
+use steel_utils::math::Vector3;
+use steel_utils::{BlockPos, Identifier};
+
+use crate::behavior::fluids::{FlowingFluid, fluid_height};
+use crate::physics::EntityPhysicsState;
+use crate::world::World;
+
+/// The fluid tag holding water and flowing water.
+pub const WATER_TAG: Identifier = Identifier::vanilla_static("water");
+/// The fluid tag holding lava and flowing lava.
+pub const LAVA_TAG: Identifier = Identifier::vanilla_static("lava");
+
+/// How strongly water currents push entities.
+const WATER_FLOW_SCALE: f64 = 0.014;
+/// The weakest push a current gives an entity that is standing still.
+const MIN_FLOW_PUSH: f64 = 0.004_500_000_000_000_000_5;
+
+/// How deep an entity is in water and lava, measured from the bottom of its box.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FluidHeights {
+    /// The depth of the water the entity is in.
+    pub water: f64,
+    /// The depth of the lava the entity is in.
+    pub lava: f64,
+}
+
+/// Updates [`EntityPhysicsState::in_water`] and [`EntityPhysicsState::in_lava`] and lets
+/// water and lava currents push the entity.
+///
+/// Matches vanilla's `Entity.updateInWaterStateAndDoFluidPushing()` for entities that
+/// aren't players.
+pub fn update_in_fluid_state(world: &World, physics: &mut EntityPhysicsState) -> FluidHeights {
+    let water = update_fluid_height_and_push(world, physics, &WATER_TAG, WATER_FLOW_SCALE);
+    physics.in_water = water.is_some();
+    if physics.in_water {
+        physics.fall_distance = 0.0;
+    }
+
+    // Lava flows faster in dimensions with fast lava, so it pushes harder there too
+    let lava_flow_scale = if world.dimension.fast_lava {
+        0.007
+    } else {
+        0.002_333_333_333_333_333_5
+    };
+    let lava = update_fluid_height_and_push(world, physics, &LAVA_TAG, lava_flow_scale);
+    physics.in_lava = lava.is_some_and(|height| height > 0.0);
+
+    FluidHeights {
+        water: water.unwrap_or(0.0),
+        lava: lava.unwrap_or(0.0),
+    }
+}
+
+/// Finds how deep the entity is in the fluids of `tag` and adds their current to its
+/// velocity.
+///
+/// Returns `None` if the entity doesn't touch the fluid. Matches vanilla's
+/// `Entity.updateFluidHeightAndDoFluidPushing()`.
+fn update_fluid_height_and_push(
+    world: &World,
+    physics: &mut EntityPhysicsState,
+    tag: &Identifier,
+    flow_scale: f64,
+) -> Option<f64> {
+    let bounding_box = physics.bounding_box.deflate(0.001);
+    let mut height = None;
+    let mut current = Vector3::new(0.0, 0.0, 0.0);
+    let mut count = 0;
+    for x in bounding_box.min_x.floor() as i32..bounding_box.max_x.ceil() as i32 {
+        for y in bounding_box.min_y.floor() as i32..bounding_box.max_y.ceil() as i32 {
+            for z in bounding_box.min_z.floor() as i32..bounding_box.max_z.ceil() as i32 {
+                let pos = BlockPos::new(x, y, z);
+                let fluid_state = world.get_block_state(&pos).get_fluid_state();
+                if !fluid_state.is(tag) {
+                    continue;
+                }
+                let fluid_y = f64::from(y) + f64::from(fluid_height(world, pos, fluid_state));
+                if fluid_y < bounding_box.min_y {
+                    continue;
+                }
+                let depth = height.unwrap_or(0.0_f64).max(fluid_y - bounding_box.min_y);
+                height = Some(depth);
+
+                let mut flow = FlowingFluid::of(fluid_state)
+                    .map_or(Vector3::new(0.0, 0.0, 0.0), |fluid| {
+                        fluid.get_flow(world, pos, fluid_state)
+                    });
+                // Shallow fluid pushes less
+                if depth < 0.4 {
+                    flow = flow * depth;
+                }
+                current += flow;
+                count += 1;
+            }
+        }
+    }
+
+    if current.length() > 0.0 {
+        current = (current * (1.0 / f64::from(count))).normalize_or_zero() * flow_scale;
+        let velocity = physics.velocity;
+        if velocity.x.abs() < 0.003 && velocity.z.abs() < 0.003 && current.length() < MIN_FLOW_PUSH
+        {
+            current = current.normalize_or_zero() * MIN_FLOW_PUSH;
+        }
+        physics.velocity += current;
+    }
+    height
+}
+
+/// Returns whether the entity's eyes are below the surface of a fluid of `tag`.
+///
+/// Matches vanilla's `Entity.updateFluidOnEyes()` followed by `Entity.isEyeInFluid()`.
+#[must_use]
+pub fn is_eye_in_fluid(world: &World, physics: &EntityPhysicsState, tag: &Identifier) -> bool {
+    let eye = physics.eye_position();
+    let eye_y = eye.y - f64::from(0.111_111_11_f32);
+    let pos = BlockPos::new(
+        eye.x.floor() as i32,
+        eye_y.floor() as i32,
+        eye.z.floor() as i32,
+    );
+    let fluid_state = world.get_block_state(&pos).get_fluid_state();
+    fluid_state.is(tag)
+        && f64::from(pos.y()) + f64::from(fluid_height(world, pos, fluid_state)) > eye_y
+}
@@ -11,12 +11,14 @@
 
 mod collision;
 mod entity_move;
+mod fluid;
 mod physics_state;
 mod shapes;
 
 // Public API
 pub use collision::{CollisionWorld, WorldCollisionProvider};
 pub use entity_move::{MoveResult, MoverType, move_entity};
+pub use fluid::{FluidHeights, LAVA_TAG, WATER_TAG, is_eye_in_fluid, update_in_fluid_state};
 pub use physics_state::EntityPhysicsState;
 pub use shapes::{collide, join_is_not_empty, translate_shape};
 
@@ -10,6 +10,7 @@ use steel_utils::{
     types::{GameType, InteractionHand, UpdateFlags},
 };
 
+use crate::block_entity::BlockEntitySnapshot;
 use crate::player::Player;
 use crate::world::World;
 
@@ -272,6 +273,11 @@ impl BlockBreakingManager {
         // TODO: Check for GameMasterBlock (command blocks, etc.)
         // TODO: Check blockActionRestricted
 
+        // The block entity is gone once the block is removed, but its loot may depend on it
+        let block_entity = world
+            .get_block_entity(&pos)
+            .map(|block_entity| BlockEntitySnapshot::of(&*block_entity.lock()));
+
         // Remove the block
         let air_state = REGISTRY.blocks.get_base_state_id(vanilla_blocks::AIR);
         let changed = world.set_block(pos, air_state, UpdateFlags::UPDATE_ALL);
@@ -287,11 +293,14 @@ impl BlockBreakingManager {
                 world.destroy_block_effect(pos, u32::from(state.0), Some(player.id));
             }
 
-            // Check if player has correct tool for drops
-            let has_correct_tool = {
+            // Check if player has correct tool for drops, and keep a copy of the tool as it
+            // was before mining damaged it, which is what vanilla rolls the loot with
+            let (has_correct_tool, tool) = {
                 let inv = player.inventory.lock();
                 let main_hand = inv.get_item_in_hand(InteractionHand::MainHand);
-                main_hand.is_correct_tool_for_drops(state) || !requires_correct_tool(state)
+                let has_correct_tool =
+                    main_hand.is_correct_tool_for_drops(state) || !requires_correct_tool(state);
+                (has_correct_tool, main_hand.clone())
             };
 
             // Damage the tool if the block has non-zero destroy time
@@ -325,8 +334,7 @@ impl BlockBreakingManager {
                 && game_mode != GameType::Creative
                 && has_correct_tool
             {
-                // TODO: Call playerDestroy to spawn drops
-                drop_block_loot(player, world, pos, state);
+                world.drop_resources(pos, state, block_entity.as_ref(), &tool);
             }
         }
 
@@ -420,11 +428,3 @@ fn get_destroy_progress(player: &Player, block_state: BlockStateId) -> f32 {
 
     speed / destroy_time / divisor
 }
-
-/// Placeholder for block loot drops.
-///
-/// TODO: Implement proper loot table lookup and item spawning.
-#[allow(clippy::needless_pass_by_value)]
-fn drop_block_loot(_player: &Player, _world: &World, _pos: BlockPos, _state: BlockStateId) {
-    // Noop for now - will be implemented with loot tables
-}
@@ -0,0 +1,107 @@
+//! Player experience points and levels.
+
+use steel_protocol::packets::game::CSetExperience;
+
+/// A player's experience, mirroring the experience fields of vanilla's `Player`.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Experience {
+    /// The player's experience level.
+    pub level: i32,
+    /// How far the player is towards the next level, from 0 to 1.
+    pub progress: f32,
+    /// The total points collected, which is what the death screen shows as score.
+    pub total: i32,
+}
+
+impl Experience {
+    /// Gets the points needed to go from the current level to the next.
+    ///
+    /// Matches vanilla's `Player.getXpNeededForNextLevel()`.
+    #[must_use]
+    pub const fn xp_needed_for_next_level(&self) -> i32 {
+        if self.level >= 30 {
+            112 + (self.level - 30) * 9
+        } else if self.level >= 15 {
+            37 + (self.level - 15) * 5
+        } else {
+            7 + self.level * 2
+        }
+    }
+
+    /// Adds experience points, going up or down levels as the bar fills or empties.
+    ///
+    /// Matches vanilla's `Player.giveExperiencePoints()`.
+    pub fn give_points(&mut self, amount: i32) {
+        self.progress += amount as f32 / self.xp_needed_for_next_level() as f32;
+        self.total = self.total.saturating_add(amount).max(0);
+
+        while self.progress < 0.0 {
+            let points = self.progress * self.xp_needed_for_next_level() as f32;
+            if self.level > 0 {
+                self.give_levels(-1);
+                self.progress = 1.0 + points / self.xp_needed_for_next_level() as f32;
+            } else {
+                self.give_levels(-1);
+                self.progress = 0.0;
+            }
+        }
+
+        while self.progress >= 1.0 {
+            self.progress = (self.progress - 1.0) * self.xp_needed_for_next_level() as f32;
+            self.give_levels(1);
+            self.progress /= self.xp_needed_for_next_level() as f32;
+        }
+    }
+
+    /// Adds experience levels, resetting everything when dropping below level 0.
+    ///
+    /// Matches vanilla's `Player.giveExperienceLevels()`.
+    pub fn give_levels(&mut self, levels: i32) {
+        self.level = self.level.saturating_add(levels);
+        if self.level < 0 {
+            *self = Self::default();
+        }
+    }
+
+    /// Creates the packet that shows this experience on the client.
+    #[must_use]
+    pub const fn packet(&self) -> CSetExperience {
+        CSetExperience {
+            experience_progress: self.progress,
+            experience_level: self.level,
+            total_experience: self.total,
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::float_cmp)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn points_fill_levels() {
+        let mut experience = Experience::default();
+        experience.give_points(7);
+        assert_eq!(experience.level, 1);
+        assert_eq!(experience.progress, 0.0);
+
+        // Level 1 needs 9 points, so 16 more points cross it and land halfway into level 2
+        experience.give_points(9 + 11 / 2);
+        assert_eq!(experience.level, 2);
+        assert!((experience.progress - 5.0 / 11.0).abs() < 1.0e-6);
+        assert_eq!(experience.total, 21);
+    }
+
+    #[test]
+    fn negative_points_drop_levels() {
+        let mut experience = Experience::default();
+        experience.give_points(10);
+        experience.give_points(-5);
+        assert_eq!(experience.level, 0);
+        assert!((experience.progress - 5.0 / 7.0).abs() < 1.0e-6);
+
+        experience.give_levels(-1);
+        assert_eq!(experience, Experience::default());
+    }
+}
@@ -2,6 +2,7 @@
 mod abilities;
 pub mod block_breaking;
 pub mod chunk_sender;
+mod experience;
 mod game_mode;
 mod game_profile;
 pub mod message_chain;
@@ -9,12 +10,16 @@ mod message_validator;
 pub mod movement;
 /// This module contains the networking implementation for the player.
 pub mod networking;
+pub mod player_data;
 pub mod player_inventory;
 pub mod profile_key;
 mod signature_cache;
 
 pub use abilities::Abilities;
+pub use experience::Experience;
+pub use player_data::{PlayerDataStorage, PlayerSnapshot};
 
+use arc_swap::ArcSwap;
 use block_breaking::BlockBreakingManager;
 use crossbeam::atomic::AtomicCell;
 pub use game_profile::{GameProfile, GameProfileAction};
@@ -36,11 +41,13 @@ use steel_protocol::packets::game::{
     SPlayerAction, SSetCarriedItem, SUseItem, SUseItemOn,
 };
 use steel_registry::blocks::block_state_ext::BlockStateExt;
+use steel_registry::blocks::shapes::AABBd;
 use steel_registry::entity_data::EntityPose;
+use steel_registry::entity_types::EntityTypeRef;
 use steel_registry::game_rules::GameRuleValue;
 use steel_registry::vanilla_entity_data::PlayerEntityData;
 use steel_registry::vanilla_game_rules::{ELYTRA_MOVEMENT_CHECK, PLAYER_MOVEMENT_CHECK};
-use steel_registry::{REGISTRY, vanilla_chat_types};
+use steel_registry::{REGISTRY, vanilla_chat_types, vanilla_entities};
 
 use steel_utils::locks::SyncMutex;
 use steel_utils::types::GameType;
@@ -55,6 +62,7 @@ use uuid::Uuid;
 
 use crate::inventory::SyncPlayerInv;
 use crate::player::player_inventory::PlayerInventory;
+use crate::server::Server;
 use crate::{config::STEEL_CONFIG, entity::Entity};
 
 use steel_crypto::{SignatureValidator, public_key_from_bytes, signature::NoValidation};
@@ -79,9 +87,9 @@ use steel_utils::BlockPos;
 use steel_utils::types::InteractionHand;
 use steel_utils::{ChunkPos, math::Vector3, translations};
 
-use crate::entity::LivingEntity;
+use crate::entity::{ItemEntity, LivingEntity};
 use crate::inventory::{
-    MenuInstance, MenuProvider,
+    MenuInstance, MenuProvider, PlayerEnderChestContainer,
     container::Container,
     inventory_menu::InventoryMenu,
     lock::{ContainerId, ContainerLockGuard},
@@ -136,7 +144,7 @@ impl Default for ClientInformation {
 
 use crate::chunk::player_chunk_view::PlayerChunkView;
 use crate::player::{chunk_sender::ChunkSender, networking::JavaConnection};
-use crate::world::World;
+use crate::world::{PORTAL_COOLDOWN, Portal, PortalProcess, World};
 
 /// A struct representing a player.
 pub struct Player {
@@ -145,8 +153,8 @@ pub struct Player {
     /// The player's connection.
     pub connection: Arc<JavaConnection>,
 
-    /// The world the player is in.
-    pub world: Arc<World>,
+    /// The world the player is in. Swapped when the player changes dimension.
+    world: ArcSwap<World>,
 
     /// The entity ID assigned to this player.
     pub id: i32,
@@ -205,6 +213,9 @@ pub struct Player {
     /// The player's inventory container (shared with `inventory_menu`).
     pub inventory: SyncPlayerInv,
 
+    /// The items the player keeps in their ender chest.
+    pub ender_chest: Arc<SyncMutex<PlayerEnderChestContainer>>,
+
     /// The player's inventory menu (always open, even when `container_id` is 0).
     inventory_menu: SyncMutex<InventoryMenu>,
 
@@ -275,6 +286,25 @@ pub struct Player {
 
     /// Last `on_ground` state sent to tracking players (for detecting changes).
     last_sent_on_ground: AtomicBool,
+
+    /// How long the player has been standing in a portal, while they are in one or
+    /// shortly after they left it.
+    portal_process: SyncMutex<Option<PortalProcess>>,
+
+    /// Ticks until the player can use a portal again (vanilla `portalCooldown`).
+    portal_cooldown: AtomicI32,
+
+    /// The portal trip the player is ready for, until the server picks it up.
+    pending_portal: SyncMutex<Option<(Portal, BlockPos)>>,
+
+    /// The player's experience level and points.
+    experience: SyncMutex<Experience>,
+
+    /// Total experience last sent to the client (vanilla `lastSentExp`).
+    last_sent_experience: AtomicI32,
+
+    /// Ticks until the player can pick up another experience orb (vanilla `takeXpDelay`).
+    take_xp_delay: AtomicI32,
 }
 
 impl Player {
@@ -296,7 +326,7 @@ impl Player {
             gameprofile,
             connection,
 
-            world,
+            world: ArcSwap::new(world),
             id: entity_id,
             client_loaded: AtomicBool::new(false),
             position: SyncMutex::new(pos),
@@ -319,6 +349,7 @@ impl Player {
             game_mode: AtomicCell::new(GameType::Survival),
             inventory: inventory.clone(),
             inventory_menu: SyncMutex::new(InventoryMenu::new(inventory)),
+            ender_chest: Arc::new(SyncMutex::new(PlayerEnderChestContainer::new())),
             open_menu: SyncMutex::new(None),
             container_counter: AtomicU8::new(0),
             ack_block_changes_up_to: AtomicI32::new(-1),
@@ -340,9 +371,27 @@ impl Player {
             block_breaking: SyncMutex::new(BlockBreakingManager::new()),
             position_sync_delay: AtomicI32::new(0),
             last_sent_on_ground: AtomicBool::new(false),
+            portal_process: SyncMutex::new(None),
+            portal_cooldown: AtomicI32::new(0),
+            pending_portal: SyncMutex::new(None),
+            experience: SyncMutex::new(Experience::default()),
+            last_sent_experience: AtomicI32::new(-1),
+            take_xp_delay: AtomicI32::new(0),
         }
     }
 
+    /// Returns the world the player is currently in.
+    #[must_use]
+    pub fn world(&self) -> Arc<World> {
+        self.world.load_full()
+    }
+
+    /// Moves the player into another world. Only the server's dimension change should call
+    /// this, after the player has been removed from its old world.
+    pub(crate) fn set_world(&self, world: Arc<World>) {
+        self.world.store(world);
+    }
+
     /// Ticks the player.
     #[allow(clippy::cast_possible_truncation)]
     pub fn tick(&self) {
@@ -376,17 +425,30 @@ impl Player {
 
         *self.last_chunk_pos.lock() = chunk_pos;
 
-        self.world.chunk_map.update_player_status(self);
+        self.world().chunk_map.update_player_status(self);
 
-        self.chunk_sender
-            .lock()
-            .send_next_chunks(self.connection.clone(), &self.world, chunk_pos);
+        self.chunk_sender.lock().send_next_chunks(
+            self.connection.clone(),
+            &self.world(),
+            chunk_pos,
+        );
 
         // Broadcast inventory changes to client
         self.broadcast_inventory_changes();
 
         // Tick block breaking
-        self.block_breaking.lock().tick(self, &self.world);
+        self.block_breaking.lock().tick(self, &self.world());
+
+        // Let the blocks the player stands in react, then travel through portals
+        self.check_inside_blocks();
+        self.handle_portal();
+
+        // Pick up items the player is standing in
+        if self.take_xp_delay.load(Ordering::Relaxed) > 0 {
+            self.take_xp_delay.fetch_sub(1, Ordering::Relaxed);
+        }
+        self.touch_nearby_items();
+        self.sync_experience();
 
         // Update pose based on current state
         self.update_pose();
@@ -407,12 +469,54 @@ impl Player {
         // - Handling falling
     }
 
+    /// Returns the player's experience level and points.
+    #[must_use]
+    pub fn experience(&self) -> Experience {
+        *self.experience.lock()
+    }
+
+    /// Replaces the player's experience level and points.
+    pub fn set_experience(&self, experience: Experience) {
+        *self.experience.lock() = experience;
+    }
+
+    /// Gives the player experience points. A negative amount takes them away.
+    ///
+    /// Matches vanilla's `Player.giveExperiencePoints()`.
+    pub fn give_experience_points(&self, amount: i32) {
+        self.experience.lock().give_points(amount);
+    }
+
+    /// Starts the short cooldown between experience orb pickups.
+    ///
+    /// Returns `false` if the player picked up an orb too recently to take another.
+    pub fn try_take_xp(&self) -> bool {
+        self.take_xp_delay
+            .compare_exchange(0, 2, Ordering::Relaxed, Ordering::Relaxed)
+            .is_ok()
+    }
+
+    /// Sends the player's experience to the client if it changed since it was last sent.
+    ///
+    /// Matches the experience check in vanilla's `ServerPlayer.doTick()`. The first
+    /// tick always sends it, since nothing has been sent yet.
+    fn sync_experience(&self) {
+        let experience = self.experience();
+        if self
+            .last_sent_experience
+            .swap(experience.total, Ordering::Relaxed)
+            != experience.total
+        {
+            self.connection.send_packet(experience.packet());
+        }
+    }
+
     /// Syncs dirty entity data to nearby players.
     fn sync_entity_data(&self) {
         if let Some(dirty_values) = self.entity_data.lock().pack_dirty() {
             let packet = CSetEntityData::new(self.id, dirty_values);
             let chunk_pos = *self.last_chunk_pos.lock();
-            self.world.broadcast_to_nearby(chunk_pos, packet, None);
+            self.world().broadcast_to_nearby(chunk_pos, packet, None);
         }
     }
 
@@ -507,7 +611,7 @@ impl Player {
 
     /// Handles a chat message from the player.
     #[allow(clippy::too_many_lines)]
-    pub fn handle_chat(&self, packet: SChat, player: Arc<Player>) {
+    pub fn handle_chat(&self, packet: SChat, player: Arc<Player>, server: &Server) {
         let chat_message = packet.message.clone();
 
         let verification_result = if let Some(_signature) = &packet.signature {
@@ -596,25 +700,16 @@ impl Player {
                 };
 
                 log::info!("<{}> {}", player.gameprofile.name, chat_message);
-                self.world.broadcast_chat(
-                    chat_packet,
-                    Arc::clone(&player),
-                    last_seen,
-                    Some(sig_array),
-                );
+                server.broadcast_chat(chat_packet, Arc::clone(&player), last_seen, Some(sig_array));
             } else {
-                self.world.broadcast_unsigned_chat(
+                server.broadcast_unsigned_chat(
                     chat_packet,
                     &player.gameprofile.name,
                     &chat_message,
                 );
             }
         } else {
-            self.world.broadcast_unsigned_chat(
-                chat_packet,
-                &player.gameprofile.name,
-                &chat_message,
-            );
+            server.broadcast_unsigned_chat(chat_packet, &player.gameprofile.name, &chat_message);
         }
     }
 
@@ -699,14 +794,14 @@ impl Player {
     /// Returns `true` if movement should be validated, `false` to skip validation.
     fn should_validate_movement(&self, is_fall_flying: bool) -> bool {
         // Check playerMovementCheck gamerule
-        let player_check = self.world.get_game_rule(PLAYER_MOVEMENT_CHECK);
+        let player_check = self.world().get_game_rule(PLAYER_MOVEMENT_CHECK);
         if player_check != GameRuleValue::Bool(true) {
             return false;
         }
 
         // If fall flying, also check elytraMovementCheck gamerule
         if is_fall_flying {
-            let elytra_check = self.world.get_game_rule(ELYTRA_MOVEMENT_CHECK);
+            let elytra_check = self.world().get_game_rule(ELYTRA_MOVEMENT_CHECK);
             return elytra_check == GameRuleValue::Bool(true);
         }
 
@@ -753,7 +848,7 @@ impl Player {
         let is_fall_flying = self.fall_flying.load(Ordering::Relaxed);
         let was_on_ground = self.on_ground.load(Ordering::Relaxed);
         // Skip movement checks when tick rate is frozen (vanilla: tickRateManager().runsNormally())
-        let tick_frozen = !self.world.tick_runs_normally();
+        let tick_frozen = !self.world().tick_runs_normally();
 
         // Handle position updates
         if packet.has_pos {
@@ -800,7 +895,7 @@ impl Player {
 
                 // Validate movement using physics simulation
                 let mut validation = movement::validate_movement(
-                    &self.world,
+                    &self.world(),
                     &movement::MovementInput {
                         target_pos,
                         first_good_pos: first_good,
@@ -902,7 +997,7 @@ impl Player {
                             pitch,
                             on_ground: packet.on_ground,
                         };
-                        self.world
+                        self.world()
                             .broadcast_to_nearby(new_chunk, sync_packet, Some(self.id));
                     } else {
                         let move_packet = CMoveEntityPosRot {
@@ -914,7 +1009,7 @@ impl Player {
                             x_rot: to_angle_byte(pitch),
                             on_ground: packet.on_ground,
                         };
-                        self.world
+                        self.world()
                             .broadcast_to_nearby(new_chunk, move_packet, Some(self.id));
                     }
                 } else {
@@ -936,7 +1031,7 @@ impl Player {
                         pitch,
                         on_ground: packet.on_ground,
                     };
-                    self.world
+                    self.world()
                         .broadcast_to_nearby(new_chunk, sync_packet, Some(self.id));
                 }
             } else {
@@ -946,7 +1041,7 @@ impl Player {
                     x_rot: to_angle_byte(pitch),
                     on_ground: packet.on_ground,
                 };
-                self.world
+                self.world()
                     .broadcast_to_nearby(new_chunk, rot_packet, Some(self.id));
             }
 
@@ -955,7 +1050,7 @@ impl Player {
                     entity_id: self.id,
                     head_y_rot: to_angle_byte(yaw),
                 };
-                self.world
+                self.world()
                     .broadcast_to_nearby(new_chunk, head_packet, Some(self.id));
             }
 
@@ -967,7 +1062,7 @@ impl Player {
     /// Updates the player's chat session and initializes the message chain.
     ///
     /// This should be called when receiving a `ChatSessionUpdate` packet from the client.
-    pub fn set_chat_session(&self, session: RemoteChatSession) {
+    pub fn set_chat_session(&self, session: RemoteChatSession, server: &Server) {
         // Initialize the message chain for this session
         let chain = SignedMessageChain::new(self.gameprofile.id, session.session_id);
 
@@ -998,7 +1093,7 @@ impl Player {
         // Broadcast the chat session to all players so they can verify this player's signatures
         let update_packet =
             CPlayerInfoUpdate::update_chat_session(self.gameprofile.id, protocol_data);
-        self.world.broadcast_to_all(update_packet);
+        server.broadcast_to_all(update_packet);
     }
 
     /// Gets a reference to the player's chat session if present
@@ -1014,7 +1109,7 @@ impl Player {
     /// Handles a chat session update packet from the client.
     ///
     /// This validates the player's profile key and initializes signed chat if valid.
-    pub fn handle_chat_session_update(&self, packet: SChatSessionUpdate) {
+    pub fn handle_chat_session_update(&self, packet: SChatSessionUpdate, server: &Server) {
         log::info!("Player {} sent chat session update", self.gameprofile.name);
 
         // Convert the packet data to profile key data
@@ -1052,7 +1147,7 @@ impl Player {
 
         match session_data.validate(self.gameprofile.id, &*validator) {
             Ok(session) => {
-                self.set_chat_session(session);
+                self.set_chat_session(session, server);
             }
             Err(err) => {
                 log::warn!(
@@ -1105,7 +1200,7 @@ impl Player {
     /// Sets the player's game mode and notifies the client.
     ///
     /// Returns `true` if the game mode was changed, `false` if the player was already in the requested game mode.
-    pub fn set_game_mode(&self, gamemode: GameType) -> bool {
+    pub fn set_game_mode(&self, gamemode: GameType, server: &Server) -> bool {
         let current_gamemode = self.game_mode.load();
         if current_gamemode == gamemode {
             return false;
@@ -1128,7 +1223,7 @@ impl Player {
         // This updates PlayerInfo on clients, which is used for isSpectator() checks
         let update_packet =
             CPlayerInfoUpdate::update_game_mode(self.gameprofile.id, gamemode as i32);
-        self.world.broadcast_to_all(update_packet);
+        server.broadcast_to_all(update_packet);
 
         true
     }
@@ -1316,12 +1411,7 @@ impl Player {
             // TODO: Implement drop spam throttling
             // For now, just drop the item
             if !item_stack.is_empty() {
-                // TODO: Actually drop the item into the world
-                log::debug!(
-                    "Player {} would drop {:?} in creative mode",
-                    self.gameprofile.name,
-                    item_stack
-                );
+                self.drop_item(item_stack, true);
             }
         }
     }
@@ -1604,14 +1694,14 @@ impl Player {
 
     /// Sends block update packets for a position and its neighbor.
     fn send_block_updates(&self, pos: &BlockPos, direction: Direction) {
-        let state = self.world.get_block_state(pos);
+        let state = self.world().get_block_state(pos);
         self.connection.send_packet(CBlockUpdate {
             pos: *pos,
             block_state: state,
         });
 
         let neighbor_pos = direction.relative(pos);
-        let neighbor_state = self.world.get_block_state(&neighbor_pos);
+        let neighbor_state = self.world().get_block_state(&neighbor_pos);
         self.connection.send_packet(CBlockUpdate {
             pos: neighbor_pos,
             block_state: neighbor_state,
@@ -1628,7 +1718,7 @@ impl Player {
 
         let chunk = *self.last_chunk_pos.lock();
         let exclude = if update_self { None } else { Some(self.id) };
-        self.world.broadcast_to_nearby(chunk, packet, exclude);
+        self.world().broadcast_to_nearby(chunk, packet, exclude);
     }
 
     /// Handles a player input packet (movement keys, sneaking, sprinting).
@@ -1680,7 +1770,7 @@ impl Player {
         }
 
         // 5. Validate Y height
-        if pos.y() >= self.world.max_build_height() {
+        if pos.y() >= self.world().max_build_height() {
             // TODO: Send "build.tooHigh" message to player
             self.send_block_updates(pos, direction);
             return;
@@ -1693,13 +1783,13 @@ impl Player {
         }
 
         // 7. Check may_interact permission
-        if !self.world.may_interact(self, pos) {
+        if !self.world().may_interact(self, pos) {
             self.send_block_updates(pos, direction);
             return;
         }
 
         // 8. Call use_item_on
-        let result = game_mode::use_item_on(self, &self.world, packet.hand, &packet.block_hit);
+        let result = game_mode::use_item_on(self, &self.world(), packet.hand, &packet.block_hit);
 
         // 9. Handle result
         if let InteractionResult::Success = result {
@@ -1722,7 +1812,7 @@ impl Player {
             PlayerAction::StartDestroyBlock => {
                 self.block_breaking.lock().handle_block_break_action(
                     self,
-                    &self.world,
+                    &self.world(),
                     packet.pos,
                     BlockBreakAction::Start,
                     packet.direction,
@@ -1733,7 +1823,7 @@ impl Player {
             PlayerAction::StopDestroyBlock => {
                 self.block_breaking.lock().handle_block_break_action(
                     self,
-                    &self.world,
+                    &self.world(),
                     packet.pos,
                     BlockBreakAction::Stop,
                     packet.direction,
@@ -1744,7 +1834,7 @@ impl Player {
             PlayerAction::AbortDestroyBlock => {
                 self.block_breaking.lock().handle_block_break_action(
                     self,
-                    &self.world,
+                    &self.world(),
                     packet.pos,
                     BlockBreakAction::Abort,
                     packet.direction,
@@ -1753,12 +1843,10 @@ impl Player {
                 self.ack_block_changes_up_to(packet.sequence);
             }
             PlayerAction::DropAllItems => {
-                // TODO: Implement drop all items (Q + Ctrl)
-                log::debug!("Player {} wants to drop all items", self.gameprofile.name);
+                self.drop_selected_item(true);
             }
             PlayerAction::DropItem => {
-                // TODO: Implement drop single item (Q)
-                log::debug!("Player {} wants to drop an item", self.gameprofile.name);
+                self.drop_selected_item(false);
             }
             PlayerAction::ReleaseUseItem => {
                 // TODO: Implement release use item (releasing bow, etc.)
@@ -1800,7 +1888,7 @@ impl Player {
         }
 
         // Get block state at position
-        let state = self.world.get_block_state(&packet.pos);
+        let state = self.world().get_block_state(&packet.pos);
         if state.is_air() {
             return;
         }
@@ -1874,7 +1962,7 @@ impl Player {
         }
 
         // Get the block entity at the position
-        let Some(block_entity) = self.world.get_block_entity(&packet.pos) else {
+        let Some(block_entity) = self.world().get_block_entity(&packet.pos) else {
             return;
         };
 
@@ -1926,7 +2014,7 @@ impl Player {
 
         // Broadcast block entity update to nearby players
         if let Some(nbt) = update_tag {
-            self.world
+            self.world()
                 .broadcast_block_entity_update(pos, block_entity_type, nbt);
         }
     }
@@ -1938,7 +2026,7 @@ impl Player {
     /// * `is_front_text` - Whether to edit front (true) or back (false) text
     pub fn open_sign_editor(&self, pos: BlockPos, is_front_text: bool) {
         // Set this player as the one who may edit the sign
-        if let Some(block_entity) = self.world.get_block_entity(&pos) {
+        if let Some(block_entity) = self.world().get_block_entity(&pos) {
             let mut guard = block_entity.lock();
             if let Some(sign) = guard.as_any_mut().downcast_mut::<SignBlockEntity>() {
                 sign.set_player_who_may_edit(Some(self.gameprofile.id));
@@ -1946,7 +2034,7 @@ impl Player {
         }
 
         // Send the block update first to ensure client has latest state
-        let state = self.world.get_block_state(&pos);
+        let state = self.world().get_block_state(&pos);
         self.connection.send_packet(CBlockUpdate {
             pos,
             block_state: state,
@@ -2074,14 +2162,174 @@ impl Player {
         if item.is_empty() {
             return;
         }
-        // TODO: Spawn an ItemEntity in the world at the player's position
-        // For now, just log it
-        log::debug!(
-            "Player {} dropped item: {:?} (throw_randomly: {})",
-            self.gameprofile.name,
+
+        let pos = *self.position.lock();
+        let eye_height = if *self.entity_data.lock().pose.get() == EntityPose::Sneaking {
+            1.27
+        } else {
+            1.62
+        };
+
+        let velocity = if throw_randomly {
+            let power = rand::random::<f32>() * 0.5;
+            let direction = rand::random::<f32>() * std::f32::consts::TAU;
+            Vector3::new(
+                f64::from(-direction.sin() * power),
+                0.2,
+                f64::from(direction.cos() * power),
+            )
+        } else {
+            let (yaw, pitch) = self.rotation.load();
+            let power = 0.3f32;
+            let (sin_pitch, cos_pitch) = pitch.to_radians().sin_cos();
+            let (sin_yaw, cos_yaw) = yaw.to_radians().sin_cos();
+            let direction = rand::random::<f32>() * std::f32::consts::TAU;
+            let spread = 0.02 * rand::random::<f32>();
+            Vector3::new(
+                f64::from(-sin_yaw * cos_pitch * power + direction.cos() * spread),
+                f64::from(
+                    -sin_pitch * power
+                        + 0.1
+                        + (rand::random::<f32>() - rand::random::<f32>()) * 0.1,
+                ),
+                f64::from(cos_yaw * cos_pitch * power + direction.sin() * spread),
+            )
+        };
+
+        let entity = ItemEntity::new(
+            Vector3::new(pos.x, pos.y + eye_height - 0.3, pos.z),
             item,
-            throw_randomly
+            velocity,
         );
+        entity.set_pickup_delay(40);
+        entity.set_thrower(Some(self.gameprofile.id));
+        self.world().add_item_entity(entity);
+    }
+
+    /// Drops items from the selected hotbar slot, either one or the whole stack.
+    ///
+    /// Based on Java's `ServerPlayer.drop(boolean all)`.
+    fn drop_selected_item(&self, all: bool) {
+        if self.game_mode.load() == GameType::Spectator || !self.can_drop_items() {
+            return;
+        }
+
+        let dropped = {
+            let mut inventory = self.inventory.lock();
+            let selected = inventory.get_selected_item_mut();
+            if selected.is_empty() {
+                return;
+            }
+            let count = if all { selected.count() } else { 1 };
+            let mut dropped = selected.clone();
+            dropped.set_count(count);
+            selected.shrink(count);
+            dropped
+        };
+
+        self.drop_item(dropped, false);
+        self.broadcast_inventory_changes();
+    }
+
+    /// Calls [`crate::behavior::BlockBehaviour::player_inside`] for every block the player overlaps.
+    ///
+    /// Matches vanilla's `Entity.checkInsideBlocks()` for the player's current position.
+    fn check_inside_blocks(&self) {
+        if self.game_mode.load() == GameType::Spectator {
+            return;
+        }
+        let pos = *self.position.lock();
+        let aabb = AABBd::entity_box(pos.x, pos.y, pos.z, 0.3, 1.8).deflate(1.0E-5);
+        let world = self.world();
+        for x in aabb.min_x.floor() as i32..=aabb.max_x.floor() as i32 {
+            for y in aabb.min_y.floor() as i32..=aabb.max_y.floor() as i32 {
+                for z in aabb.min_z.floor() as i32..=aabb.max_z.floor() as i32 {
+                    let block_pos = BlockPos::new(x, y, z);
+                    let state = world.get_block_state(&block_pos);
+                    if state.is_air() {
+                        continue;
+                    }
+                    BLOCK_BEHAVIORS
+                        .get_behavior(state.get_block())
+                        .player_inside(state, &world, block_pos, self);
+                }
+            }
+        }
+    }
+
+    /// Marks the player as standing in `portal` at `pos` this tick.
+    ///
+    /// While the portal cooldown runs, standing in a portal only keeps it from running out,
+    /// so players don't bounce straight back through the portal they arrived in.
+    /// Matches vanilla's `Entity.setAsInsidePortal()`.
+    pub fn set_as_inside_portal(&self, portal: Portal, pos: BlockPos) {
+        if self.portal_cooldown.load(Ordering::Relaxed) > 0 {
+            self.set_portal_cooldown();
+            return;
+        }
+        let mut process = self.portal_process.lock();
+        match process.as_mut() {
+            Some(process) if process.is_same_portal(portal) => {
+                process.update_entry_position(pos);
+            }
+            _ => *process = Some(PortalProcess::new(portal, pos)),
+        }
+    }
+
+    /// Returns the ticks until the player can use a portal again.
+    #[must_use]
+    pub fn portal_cooldown(&self) -> i32 {
+        self.portal_cooldown.load(Ordering::Relaxed)
+    }
+
+    /// Restarts the portal cooldown.
+    pub fn set_portal_cooldown(&self) {
+        self.portal_cooldown
+            .store(PORTAL_COOLDOWN, Ordering::Relaxed);
+    }
+
+    /// Counts down the portal cooldown and queues a portal trip once the player has stood
+    /// in a portal long enough.
+    ///
+    /// Matches vanilla's `Entity.handlePortal()`. The trip itself needs the target world,
+    /// so the server performs it with [`Self::take_pending_portal`].
+    fn handle_portal(&self) {
+        let _ =
+            self.portal_cooldown
+                .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |cooldown| {
+                    (cooldown > 0).then_some(cooldown - 1)
+                });
+
+        let mut process_guard = self.portal_process.lock();
+        let Some(process) = process_guard.as_mut() else {
+            return;
+        };
+        let transition_time = process
+            .portal()
+            .transition_time(&self.world(), self.abilities.lock().invulnerable);
+        if process.process_portal_teleportation(transition_time, true) {
+            self.set_portal_cooldown();
+            *self.pending_portal.lock() = Some((process.portal(), process.entry_position()));
+        } else if process.has_expired() {
+            *process_guard = None;
+        }
+    }
+
+    /// Takes the portal trip the player is ready for, if any.
+    pub fn take_pending_portal(&self) -> Option<(Portal, BlockPos)> {
+        self.pending_portal.lock().take()
+    }
+
+    /// Picks up item entities the player is touching.
+    ///
+    /// Matches the entity touch part of vanilla's `Player.aiStep()`.
+    fn touch_nearby_items(&self) {
+        if self.game_mode.load() == GameType::Spectator {
+            return;
+        }
+        let pos = *self.position.lock();
+        let aabb = AABBd::entity_box(pos.x, pos.y, pos.z, 0.3, 1.8).inflate_xyz(1.0, 0.5, 1.0);
+        self.world().touch_entities(self, &aabb);
     }
 
     /// Returns true if the player can drop items.
@@ -2138,10 +2386,34 @@ impl Player {
 }
 
 impl Entity for Player {
+    fn get_id(&self) -> i32 {
+        self.id
+    }
+
     fn get_uuid(&self) -> Uuid {
         self.gameprofile.id
     }
 
+    fn get_type(&self) -> EntityTypeRef {
+        vanilla_entities::PLAYER
+    }
+
+    fn get_position(&self) -> Vector3<f64> {
+        *self.position.lock()
+    }
+
+    fn get_velocity(&self) -> Vector3<f64> {
+        self.get_delta_movement()
+    }
+
+    fn get_rotation(&self) -> (f32, f32) {
+        self.rotation.load()
+    }
+
+    fn on_ground(&self) -> bool {
+        self.is_on_ground()
+    }
+
     fn as_player(self: Arc<Self>) -> Option<Arc<Player>> {
         Some(self)
     }
@@ -2164,10 +2436,6 @@ impl LivingEntity for Player {
         20.0
     }
 
-    fn get_position(&self) -> Vector3<f64> {
-        *self.position.lock()
-    }
-
     fn get_absorption_amount(&self) -> f32 {
         *self.entity_data.lock().player_absorption.get()
     }
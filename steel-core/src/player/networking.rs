@@ -1,5 +1,6 @@
 //! This module contains the `JavaConnection` struct, which is used to represent a connection to a Java client.
 use std::io::Cursor;
+use std::net::SocketAddr;
 use std::sync::atomic::Ordering;
 use std::sync::{Arc, Weak};
 use std::time::{SystemTime, UNIX_EPOCH};
@@ -50,6 +51,7 @@ pub struct JavaConnection {
     compression: Option<CompressionInfo>,
     network_writer: Arc<AsyncMutex<TCPNetworkEncoder<BufWriter<OwnedWriteHalf>>>>,
     id: u64,
+    address: SocketAddr,
 
     player: Weak<Player>,
     keep_alive_tracker: SyncMutex<KeepAliveTracker>,
@@ -64,6 +66,7 @@ impl JavaConnection {
         compression: Option<CompressionInfo>,
         network_writer: Arc<AsyncMutex<TCPNetworkEncoder<BufWriter<OwnedWriteHalf>>>>,
         id: u64,
+        address: SocketAddr,
         player: Weak<Player>,
     ) -> Self {
         Self {
@@ -72,6 +75,7 @@ impl JavaConnection {
             compression,
             network_writer,
             id,
+            address,
             player,
             keep_alive_tracker: SyncMutex::new(KeepAliveTracker {
                 alive_time: 0,
@@ -134,6 +138,12 @@ impl JavaConnection {
         *self.latency.lock() as i32
     }
 
+    /// Returns the address the client connected from.
+    #[must_use]
+    pub fn address(&self) -> SocketAddr {
+        self.address
+    }
+
     /// Disconnects the client.
     pub fn disconnect(&self, reason: impl Into<TextComponent>) {
         self.send_packet(CDisconnect::new(&reason.into(), self));
@@ -197,10 +207,10 @@ impl JavaConnection {
                 player.handle_custom_payload(SCustomPayload::read_packet(data)?);
             }
             play::S_CHAT => {
-                player.handle_chat(SChat::read_packet(data)?, Arc::clone(&player));
+                player.handle_chat(SChat::read_packet(data)?, Arc::clone(&player), &server);
             }
             play::S_CHAT_SESSION_UPDATE => {
-                player.handle_chat_session_update(SChatSessionUpdate::read_packet(data)?);
+                player.handle_chat_session_update(SChatSessionUpdate::read_packet(data)?, &server);
             }
             play::S_CHAT_ACK => {
                 player.handle_chat_ack(SChatAck::read_packet(data)?);
@@ -351,7 +361,11 @@ impl JavaConnection {
     ///
     /// # Panics
     /// - If the player is not available.
-    pub async fn sender(self: Arc<Self>, mut sender_recv: UnboundedReceiver<EncodedPacket>) {
+    pub async fn sender(
+        self: Arc<Self>,
+        mut sender_recv: UnboundedReceiver<EncodedPacket>,
+        server: Arc<Server>,
+    ) {
         loop {
             select! {
                 () = self.wait_for_close() => {
@@ -376,8 +390,7 @@ impl JavaConnection {
         }
 
         let player = self.player.upgrade().expect("Player is not available");
-        let world = player.world.clone();
-        world.remove_player(player).await;
+        server.remove_player(player).await;
     }
 }
 
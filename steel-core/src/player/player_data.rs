@@ -0,0 +1,439 @@
+//! Per-player save files.
+//!
+//! Every player is stored as zstd-compressed NBT in `<uuid>.dat` inside the playerdata
+//! directory. Files are written when the player leaves, on autosave and at shutdown, and
+//! read back before the login packet is sent. The compound follows the layout of vanilla's
+//! `ServerPlayer.addAdditionalSaveData()`.
+
+use std::{
+    io::{self, Cursor},
+    path::{Path, PathBuf},
+    sync::{Arc, atomic::Ordering},
+};
+
+use simdnbt::borrow::{
+    BaseNbtCompound as BorrowedNbtCompound, NbtCompound as NbtCompoundView,
+    read_compound as read_borrowed_compound,
+};
+use simdnbt::owned::NbtCompound;
+use simdnbt::{FromNbtTag, ToNbtTag};
+use steel_registry::item_stack::ItemStack;
+use steel_utils::Identifier;
+use steel_utils::locks::AsyncMutex;
+use steel_utils::types::GameType;
+use tokio::fs;
+use uuid::Uuid;
+
+use crate::entity::{LivingEntity, read_entity_base, save_entity_base};
+use crate::inventory::container::{Container, load_all_items, save_all_items};
+use crate::inventory::equipment::EquipmentSlot;
+use crate::player::{Abilities, Experience, Player, player_inventory::PlayerInventory};
+use crate::world::World;
+
+/// Directory the player files are stored in.
+pub const PLAYER_DATA_DIR: &str = "world/playerdata";
+
+/// Reads and writes the save files of players.
+///
+/// Based on vanilla's `PlayerDataStorage`.
+pub struct PlayerDataStorage {
+    dir: PathBuf,
+    /// Held while files are written, so a save running in the background finishes
+    /// before a newer save of the same player starts.
+    write_lock: Arc<AsyncMutex<()>>,
+}
+
+/// A player's state, taken on the tick so it can be written to disk elsewhere.
+pub struct PlayerSnapshot {
+    uuid: Uuid,
+    name: String,
+    nbt: NbtCompound,
+}
+
+impl PlayerSnapshot {
+    /// Takes a snapshot of everything about a player that is saved.
+    #[must_use]
+    pub fn new(player: &Player) -> Self {
+        let mut nbt = NbtCompound::new();
+        player.save_data(&mut nbt);
+        Self {
+            uuid: player.gameprofile.id,
+            name: player.gameprofile.name.clone(),
+            nbt,
+        }
+    }
+}
+
+impl PlayerDataStorage {
+    /// Creates storage for player files in the given directory.
+    #[must_use]
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            write_lock: Arc::new(AsyncMutex::new(())),
+        }
+    }
+
+    /// Saves a player.
+    pub async fn save(&self, player: &Player) -> io::Result<()> {
+        let snapshot = PlayerSnapshot::new(player);
+        let _guard = self.write_lock.lock().await;
+        write_snapshot(&self.dir, &snapshot).await
+    }
+
+    /// Writes snapshots in a background task, so saving doesn't hold up the tick.
+    ///
+    /// Returns `false` without writing anything if another save is still running, the
+    /// caller should try again later.
+    pub fn save_in_background(&self, snapshots: Vec<PlayerSnapshot>) -> bool {
+        // Locked right away, so later saves wait for these older snapshots to be written
+        let Ok(guard) = self.write_lock.clone().try_lock_owned() else {
+            return false;
+        };
+        let dir = self.dir.clone();
+        tokio::spawn(async move {
+            for snapshot in snapshots {
+                if let Err(e) = write_snapshot(&dir, &snapshot).await {
+                    log::error!("Failed to save player {}: {e}", snapshot.name);
+                }
+            }
+            drop(guard);
+        });
+        true
+    }
+
+    /// Loads a player's saved state, moving them into the world they were saved in.
+    ///
+    /// Returns `false` if the player has never been saved. Unreadable files are logged
+    /// and treated the same way, so the player starts fresh instead of being kicked.
+    pub async fn load(&self, player: &Player, worlds: &[Arc<World>]) -> bool {
+        let path = player_path(&self.dir, player.gameprofile.id);
+        let compressed = match fs::read(&path).await {
+            Ok(compressed) => compressed,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return false,
+            Err(e) => {
+                log::warn!("Failed to read player data {}: {e}", path.display());
+                return false;
+            }
+        };
+        let bytes = match zstd::decode_all(&compressed[..]) {
+            Ok(bytes) => bytes,
+            Err(e) => {
+                log::warn!("Failed to decompress player data {}: {e}", path.display());
+                return false;
+            }
+        };
+        let Ok(nbt) = read_borrowed_compound(&mut Cursor::new(&bytes[..])) else {
+            log::warn!("Invalid player data {}", path.display());
+            return false;
+        };
+
+        player.load_data(&nbt, worlds);
+        true
+    }
+}
+
+/// Returns the path of a player's save file.
+fn player_path(dir: &Path, uuid: Uuid) -> PathBuf {
+    dir.join(format!("{uuid}.dat"))
+}
+
+/// Writes a player's save file.
+///
+/// The file is written next to the old one first and then moved over it, so a crash
+/// mid-save never leaves a truncated file behind.
+async fn write_snapshot(dir: &Path, snapshot: &PlayerSnapshot) -> io::Result<()> {
+    let mut bytes = Vec::new();
+    snapshot.nbt.write(&mut bytes);
+    let compressed = zstd::encode_all(&bytes[..], 3)?;
+
+    fs::create_dir_all(dir).await?;
+    let path = player_path(dir, snapshot.uuid);
+    let temp = path.with_extension("dat_new");
+    fs::write(&temp, compressed).await?;
+    fs::rename(&temp, &path).await?;
+
+    log::debug!("Saved player data to {}", path.display());
+    Ok(())
+}
+
+impl Player {
+    /// Writes the state that survives a reconnect.
+    ///
+    /// Matches vanilla's `ServerPlayer.addAdditionalSaveData()`.
+    pub fn save_data(&self, nbt: &mut NbtCompound) {
+        save_entity_base(self, nbt);
+        nbt.insert("Dimension", self.world().dimension.key.clone().to_nbt_tag());
+        nbt.insert("Health", *self.entity_data.lock().health.get());
+        nbt.insert("playerGameType", i32::from(self.game_mode.load()));
+        let experience = self.experience();
+        nbt.insert("XpP", experience.progress);
+        nbt.insert("XpLevel", experience.level);
+        nbt.insert("XpTotal", experience.total);
+
+        let mut abilities = NbtCompound::new();
+        save_abilities(&self.abilities.lock(), &mut abilities);
+        nbt.insert("abilities", abilities);
+
+        let inventory = self.inventory.lock();
+        save_all_items(nbt, "Inventory", inventory.get_items());
+        nbt.insert("SelectedItemSlot", i32::from(inventory.get_selected_slot()));
+        let mut equipment = NbtCompound::new();
+        for slot in EquipmentSlot::ALL {
+            let item = inventory.equipment().get_ref(slot);
+            if !item.is_empty() {
+                equipment.insert(slot.name(), item.clone().to_nbt_tag());
+            }
+        }
+        nbt.insert("equipment", equipment);
+        drop(inventory);
+
+        save_all_items(nbt, "EnderItems", self.ender_chest.lock().items());
+    }
+
+    /// Restores the state written by [`Player::save_data`]. Must be called before the player
+    /// joins a world.
+    ///
+    /// Matches vanilla's `ServerPlayer.readAdditionalSaveData()`. Players saved in a
+    /// dimension that no longer exists stay in their current world.
+    pub fn load_data(&self, nbt: &BorrowedNbtCompound<'_>, worlds: &[Arc<World>]) {
+        let base = read_entity_base(nbt);
+        let view: NbtCompoundView<'_, '_> = nbt.into();
+
+        if let Some(dimension) = view.get("Dimension").and_then(Identifier::from_nbt_tag)
+            && let Some(world) = worlds.iter().find(|world| world.dimension.key == dimension)
+        {
+            self.set_world(world.clone());
+        }
+        if let Some(position) = base.position {
+            *self.position.lock() = position;
+            *self.prev_position.lock() = position;
+        }
+        if let Some(rotation) = base.rotation {
+            self.rotation.store(rotation);
+            self.prev_rotation.store(rotation);
+        }
+        if let Some(velocity) = base.velocity {
+            self.set_delta_movement(velocity);
+        }
+        self.on_ground.store(base.on_ground, Ordering::Relaxed);
+
+        if let Some(health) = view.float("Health") {
+            self.entity_data
+                .lock()
+                .health
+                .set(health.clamp(0.0, self.get_max_health()));
+        }
+        self.set_experience(Experience {
+            level: view.int("XpLevel").unwrap_or(0),
+            progress: view.float("XpP").unwrap_or(0.0),
+            total: view.int("XpTotal").unwrap_or(0),
+        });
+        let game_mode = view
+            .int("playerGameType")
+            .and_then(GameType::by_id)
+            .unwrap_or(GameType::Survival);
+        self.game_mode.store(game_mode);
+        let mut abilities = self.abilities.lock();
+        abilities.update_for_game_mode(game_mode);
+        if let Some(saved) = view.compound("abilities") {
+            load_abilities(&mut abilities, &saved);
+        }
+        drop(abilities);
+
+        let mut inventory = self.inventory.lock();
+        let mut items = vec![ItemStack::empty(); PlayerInventory::INVENTORY_SIZE];
+        load_all_items(&view, "Inventory", &mut items);
+        for (slot, item) in items.into_iter().enumerate() {
+            inventory.set_item(slot, item);
+        }
+        if let Some(selected) = view.int("SelectedItemSlot")
+            && PlayerInventory::is_hotbar_slot(selected as usize)
+        {
+            inventory.set_selected_slot(selected as u8);
+        }
+        if let Some(equipment) = view.compound("equipment") {
+            for slot in EquipmentSlot::ALL {
+                if let Some(item) = equipment.get(slot.name()).and_then(ItemStack::from_nbt_tag) {
+                    inventory.equipment_mut().set(slot, item);
+                }
+            }
+        }
+        drop(inventory);
+
+        load_all_items(&view, "EnderItems", self.ender_chest.lock().items_mut());
+    }
+}
+
+/// Writes abilities the way vanilla's `Abilities.addSaveData()` does.
+fn save_abilities(abilities: &Abilities, nbt: &mut NbtCompound) {
+    nbt.insert("invulnerable", i8::from(abilities.invulnerable));
+    nbt.insert("flying", i8::from(abilities.flying));
+    nbt.insert("mayfly", i8::from(abilities.may_fly));
+    nbt.insert("instabuild", i8::from(abilities.instabuild));
+    nbt.insert("mayBuild", i8::from(abilities.may_build));
+    nbt.insert("flySpeed", abilities.flying_speed);
+    nbt.insert("walkSpeed", abilities.walking_speed);
+}
+
+/// Reads abilities written by [`save_abilities`], keeping the current value of missing fields.
+fn load_abilities(abilities: &mut Abilities, nbt: &NbtCompoundView<'_, '_>) {
+    let flag = |key: &str, current: bool| nbt.byte(key).map_or(current, |b| b != 0);
+    abilities.invulnerable = flag("invulnerable", abilities.invulnerable);
+    abilities.flying = flag("flying", abilities.flying);
+    abilities.may_fly = flag("mayfly", abilities.may_fly);
+    abilities.instabuild = flag("instabuild", abilities.instabuild);
+    abilities.may_build = flag("mayBuild", abilities.may_build);
+    if let Some(speed) = nbt.float("flySpeed") {
+        abilities.flying_speed = speed;
+    }
+    if let Some(speed) = nbt.float("walkSpeed") {
+        abilities.walking_speed = speed;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::{Ipv4Addr, SocketAddr};
+
+    use steel_protocol::packet_writer::TCPNetworkEncoder;
+    use steel_registry::vanilla_dimension_types;
+    use steel_registry::vanilla_items::ITEMS;
+    use steel_utils::math::Vector3;
+    use tokio::io::BufWriter;
+    use tokio::net::{TcpListener, TcpStream};
+    use tokio::runtime::{Builder, Runtime};
+    use tokio::sync::mpsc;
+    use tokio_util::sync::CancellationToken;
+
+    use super::*;
+    use crate::player::{ClientInformation, GameProfile, networking::JavaConnection};
+    use crate::test_utils::init_test_registry;
+
+    /// Creates a player in `world` whose connection goes to a local socket nobody reads.
+    fn test_player(runtime: &Runtime, world: Arc<World>) -> Arc<Player> {
+        let (stream, address) = runtime.block_on(async {
+            let listener = TcpListener::bind(SocketAddr::from((Ipv4Addr::LOCALHOST, 0)))
+                .await
+                .expect("bind a local port");
+            let address = listener.local_addr().expect("local address");
+            let stream = TcpStream::connect(address).await.expect("connect");
+            (stream, address)
+        });
+        let (_read_half, write_half) = stream.into_split();
+        let writer = Arc::new(AsyncMutex::new(TCPNetworkEncoder::new(BufWriter::new(
+            write_half,
+        ))));
+        let (outgoing, _) = mpsc::unbounded_channel();
+        let profile = GameProfile {
+            id: Uuid::from_u128(0x5eed),
+            name: "Steve".to_owned(),
+            properties: Vec::new(),
+            profile_actions: None,
+        };
+
+        Arc::new_cyclic(|player| {
+            let connection = Arc::new(JavaConnection::new(
+                outgoing,
+                CancellationToken::new(),
+                None,
+                writer,
+                0,
+                address,
+                player.clone(),
+            ));
+            Player::new(
+                profile,
+                connection,
+                world,
+                0,
+                player,
+                ClientInformation::default(),
+            )
+        })
+    }
+
+    #[test]
+    fn player_round_trip() {
+        init_test_registry();
+        let runtime = Arc::new(
+            Builder::new_multi_thread()
+                .enable_all()
+                .build()
+                .expect("build a runtime"),
+        );
+        // Neither world reads the server config, and both only touch the disk when saved
+        let nether = runtime
+            .block_on(World::new(
+                runtime.clone(),
+                vanilla_dimension_types::THE_NETHER,
+                0,
+            ))
+            .expect("create the nether");
+        let end = runtime
+            .block_on(World::new(
+                runtime.clone(),
+                vanilla_dimension_types::THE_END,
+                0,
+            ))
+            .expect("create the end");
+
+        let saved = test_player(&runtime, nether.clone());
+        saved
+            .inventory
+            .lock()
+            .set_item(4, ItemStack::with_count(&ITEMS.diamond, 5));
+        saved
+            .ender_chest
+            .lock()
+            .set_item(26, ItemStack::new(&ITEMS.elytra));
+        *saved.position.lock() = Vector3::new(12.5, 70.0, -3.25);
+        saved.rotation.store((90.0, -30.0));
+        saved.entity_data.lock().health.set(7.5);
+        saved.game_mode.store(GameType::Adventure);
+
+        let mut nbt = NbtCompound::new();
+        saved.save_data(&mut nbt);
+        let mut bytes = Vec::new();
+        nbt.write(&mut bytes);
+        let nbt = read_borrowed_compound(&mut Cursor::new(&bytes[..])).expect("valid nbt");
+
+        let loaded = test_player(&runtime, end.clone());
+        loaded.load_data(&nbt, &[end, nether.clone()]);
+
+        assert_eq!(
+            loaded.inventory.lock().get_item(4),
+            &ItemStack::with_count(&ITEMS.diamond, 5)
+        );
+        assert!(loaded.inventory.lock().get_item(5).is_empty());
+        assert_eq!(
+            loaded.ender_chest.lock().get_item(26),
+            &ItemStack::new(&ITEMS.elytra)
+        );
+        assert_eq!(*loaded.position.lock(), Vector3::new(12.5, 70.0, -3.25));
+        assert_eq!(loaded.rotation.load(), (90.0, -30.0));
+        assert!(Arc::ptr_eq(&loaded.world(), &nether));
+        assert!((loaded.get_health() - 7.5).abs() < f32::EPSILON);
+        assert_eq!(loaded.game_mode.load(), GameType::Adventure);
+    }
+
+    #[test]
+    fn abilities_round_trip() {
+        let mut saved = Abilities::creative();
+        saved.flying = true;
+        saved.flying_speed = 0.1;
+
+        let mut nbt = NbtCompound::new();
+        save_abilities(&saved, &mut nbt);
+        let mut bytes = Vec::new();
+        nbt.write(&mut bytes);
+        let nbt = read_borrowed_compound(&mut Cursor::new(&bytes[..])).expect("valid nbt");
+        let view: NbtCompoundView<'_, '_> = (&nbt).into();
+
+        let mut loaded = Abilities::survival();
+        load_abilities(&mut loaded, &view);
+        assert!(loaded.invulnerable && loaded.flying && loaded.may_fly && loaded.instabuild);
+        assert!(loaded.may_build);
+        assert!((loaded.flying_speed - 0.1).abs() < f32::EPSILON);
+    }
+}
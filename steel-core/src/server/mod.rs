@@ -1,43 +1,107 @@
 //! This module contains the `Server` struct, which is the main entry point for the server.
+/// Operators and per-player permission grants.
+pub mod permissions;
 /// The registry cache for the server.
 pub mod registry_cache;
 /// The tick rate manager for the server.
 pub mod tick_rate_manager;
+/// The whitelist and ban lists.
+pub mod user_lists;
+
+mod player_list;
 
 use std::{
-    sync::{
-        Arc,
-        atomic::{AtomicI32, Ordering},
-    },
+    mem,
+    pin::pin,
+    sync::{Arc, Weak},
     time::{Duration, Instant},
 };
 
 use steel_crypto::key_store::KeyStore;
 use steel_protocol::packets::game::{
-    CLogin, CSystemChat, CTabList, CTickingState, CTickingStep, CommonPlayerSpawnInfo,
+    CLevelEvent, CLogin, CRespawn, CSetHeldSlot, CSystemChat, CTabList, CTickingState,
+    CTickingStep, respawn_data_flags,
 };
 use steel_registry::game_rules::GameRuleValue;
-use steel_registry::vanilla_dimension_types::OVERWORLD;
-use steel_registry::vanilla_game_rules::{IMMEDIATE_RESPAWN, LIMITED_CRAFTING, REDUCED_DEBUG_INFO};
+use steel_registry::level_events;
+use steel_registry::vanilla_dimension_types::{OVERWORLD, THE_END, THE_NETHER};
+use steel_registry::vanilla_game_rules::{
+    ALLOW_ENTERING_NETHER_USING_PORTALS, IMMEDIATE_RESPAWN, LIMITED_CRAFTING, REDUCED_DEBUG_INFO,
+};
 use steel_registry::{REGISTRY, Registry};
-use steel_utils::locks::SyncRwLock;
-use steel_utils::types::GameType;
+use steel_utils::locks::{SyncMutex, SyncRwLock};
+use steel_utils::math::Vector3;
+use steel_utils::translations;
+use steel_utils::{BlockPos, ChunkPos, Identifier, SectionPos};
 use text_components::{Modifier, TextComponent, format::Color};
 use tick_rate_manager::{SprintReport, TickRateManager};
-use tokio::{runtime::Runtime, task::spawn_blocking, time::sleep};
+use tokio::{runtime::Runtime, sync::Notify, task::spawn_blocking, time::sleep};
 use tokio_util::sync::CancellationToken;
+use uuid::Uuid;
 
 use crate::behavior::init_behaviors;
 use crate::block_entity::init_block_entities;
+use crate::chunk::chunk_ticket_manager::MAX_VIEW_DISTANCE;
 use crate::command::CommandDispatcher;
+use crate::command::sender::CommandSender;
 use crate::config::STEEL_CONFIG;
-use crate::player::Player;
+use crate::entity;
+use crate::player::player_data::PLAYER_DATA_DIR;
+use crate::player::{Player, PlayerDataStorage, PlayerSnapshot};
+use crate::server::permissions::Permissions;
+use crate::server::player_list::SEND_PLAYER_INFO_INTERVAL;
 use crate::server::registry_cache::RegistryCache;
-use crate::world::{World, WorldTickTimings};
+use crate::server::user_lists::UserLists;
+use crate::world::{Portal, PortalTravel, World, WorldTickTimings};
 
 /// Interval in ticks between tab list updates (20 ticks = 1 second).
 const TAB_LIST_UPDATE_INTERVAL: u64 = 20;
 
+/// Interval in ticks between autosaves (6000 ticks = 5 minutes), matching vanilla.
+const AUTOSAVE_INTERVAL: u64 = 6000;
+
+/// How long a login waits for an older session of the same player to be saved and removed.
+const DUPLICATE_LOGOUT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Radius in chunks loaded around a portal exit before it is found or built.
+const PORTAL_EXIT_CHUNK_RADIUS: u8 = 2;
+
+/// A player waiting for the chunks around their portal exit to load.
+struct PendingPortalTravel {
+    player: Arc<Player>,
+    to: Arc<World>,
+    travel: PortalTravel,
+    /// The center of the chunk ticket keeping the exit loaded.
+    ticket: ChunkPos,
+}
+
+impl PendingPortalTravel {
+    /// Returns whether every chunk the exit may touch is fully loaded.
+    fn exit_loaded(&self) -> bool {
+        let radius = i32::from(PORTAL_EXIT_CHUNK_RADIUS);
+        (-radius..=radius).all(|x| {
+            (-radius..=radius).all(|z| {
+                let pos = ChunkPos::new(self.ticket.0.x + x, self.ticket.0.y + z);
+                self.to.chunk_map.with_full_chunk(&pos, |_| ()).is_some()
+            })
+        })
+    }
+}
+
+/// A client that finished logging in but has not joined a world yet.
+pub trait ConfiguringClient: Send + Sync {
+    /// Disconnects the client with the given reason.
+    fn disconnect(self: Arc<Self>, reason: TextComponent);
+}
+
+/// A client in the configuration phase, tracked so a newer login can replace it.
+struct ConfiguringEntry {
+    id: u64,
+    uuid: Uuid,
+    name: String,
+    client: Weak<dyn ConfiguringClient>,
+}
+
 /// The main server struct.
 pub struct Server {
     /// The cancellation token for graceful shutdown.
@@ -46,14 +110,24 @@ pub struct Server {
     pub key_store: KeyStore,
     /// The registry cache for the server.
     pub registry_cache: RegistryCache,
-    /// A list of all the worlds on the server.
+    /// A list of all the worlds on the server. The overworld comes first.
     pub worlds: Vec<Arc<World>>,
     /// The tick rate manager for the server.
     pub tick_rate_manager: SyncRwLock<TickRateManager>,
     /// Saves and dispatches commands to appropriate handlers.
     pub command_dispatcher: SyncRwLock<CommandDispatcher>,
-    /// Counter for assigning unique entity IDs.
-    next_entity_id: AtomicI32,
+    /// Players waiting for their portal exit to load.
+    portal_travels: SyncMutex<Vec<PendingPortalTravel>>,
+    /// The save files of players.
+    pub player_data: PlayerDataStorage,
+    /// The operators and permission grants.
+    pub permissions: Permissions,
+    /// The whitelist and ban lists.
+    pub user_lists: UserLists,
+    /// Clients that logged in but have not joined a world yet.
+    configuring: SyncMutex<Vec<ConfiguringEntry>>,
+    /// Woken whenever a player was saved and removed from their world.
+    player_removed: Notify,
 }
 
 impl Server {
@@ -91,33 +165,45 @@ impl Server {
             })
         };
 
-        let overworld = World::new(chunk_runtime, OVERWORLD, seed)
-            .await
-            .expect("Failed to create overworld");
+        let mut worlds = Vec::new();
+        for dimension in [OVERWORLD, THE_NETHER, THE_END] {
+            let world = World::new(chunk_runtime.clone(), dimension, seed)
+                .await
+                .unwrap_or_else(|e| panic!("Failed to create {}: {e}", dimension.key));
+            worlds.push(world);
+        }
 
         Server {
             cancel_token,
             key_store: KeyStore::create(),
-            worlds: vec![overworld],
+            worlds,
             registry_cache,
             tick_rate_manager: SyncRwLock::new(TickRateManager::new()),
             command_dispatcher: SyncRwLock::new(CommandDispatcher::new()),
-            next_entity_id: AtomicI32::new(1), // Start at 1, 0 is reserved
+            portal_travels: SyncMutex::new(Vec::new()),
+            player_data: PlayerDataStorage::new(PLAYER_DATA_DIR),
+            permissions: Permissions::load("."),
+            user_lists: UserLists::load("."),
+            configuring: SyncMutex::new(Vec::new()),
+            player_removed: Notify::new(),
         }
     }
 
     /// Allocates a new unique entity ID.
+    ///
+    /// IDs are shared with non-player entities spawned by the worlds.
     #[must_use]
     pub fn next_entity_id(&self) -> i32 {
-        self.next_entity_id.fetch_add(1, Ordering::Relaxed)
+        entity::next_entity_id()
     }
 
-    /// Adds a player to the server.
+    /// Adds a player to the server, restoring their saved state first.
     ///
     /// # Panics
     /// Panics if the registry is not initialized.
-    pub fn add_player(&self, player: Arc<Player>) {
-        let world = &self.worlds[0];
+    pub async fn add_player(&self, player: Arc<Player>) {
+        let loaded = self.player_data.load(&player, &self.worlds).await;
+        let world = player.world();
 
         // Get gamerule values
         let reduced_debug_info =
@@ -126,53 +212,300 @@ impl Server {
         let do_limited_crafting =
             world.get_game_rule(LIMITED_CRAFTING) == GameRuleValue::Bool(true);
 
-        // Get world data
-        let hashed_seed = world.obfuscated_seed();
-        let dimension_key = world.dimension.key.clone();
-
         player.connection.send_packet(CLogin {
             player_id: player.id,
             hardcore: false,
-            levels: vec![dimension_key.clone()],
+            levels: self
+                .worlds
+                .iter()
+                .map(|world| world.dimension.key.clone())
+                .collect(),
             max_players: STEEL_CONFIG.max_players as i32,
             chunk_radius: player.view_distance().into(),
             simulation_distance: STEEL_CONFIG.simulation_distance.into(),
             reduced_debug_info,
             show_death_screen: !immediate_respawn,
             do_limited_crafting,
-            common_player_spawn_info: CommonPlayerSpawnInfo {
-                dimension_type: *(REGISTRY.dimension_types.get_id(
-                    REGISTRY
-                        .dimension_types
-                        .by_key(&dimension_key)
-                        .expect("Should be registered"),
-                )) as i32,
-                dimension: dimension_key,
-                seed: hashed_seed,
-                game_type: GameType::Survival,
-                previous_game_type: None,
-                is_debug: false,
-                // TODO: Change once we add a normal generator
-                is_flat: true,
-                last_death_location: None,
-                portal_cooldown: 0,
-                sea_level: 63, // Standard overworld sea level
-            },
+            common_player_spawn_info: world.common_player_spawn_info(&player),
             enforces_secure_chat: STEEL_CONFIG.enforce_secure_chat,
         });
 
         // Send player abilities (flight, invulnerability, etc.)
         player.send_abilities();
 
-        let commands = self.command_dispatcher.read().get_commands();
-        player.connection.send_packet(commands);
+        if loaded {
+            player.connection.send_packet(CSetHeldSlot {
+                slot: i32::from(player.inventory.lock().get_selected_slot()),
+            });
+            let pos = *player.position.lock();
+            let (yaw, pitch) = player.rotation.load();
+            player.teleport(pos.x, pos.y, pos.z, yaw, pitch);
+        }
+
+        self.send_commands(&player);
 
         // Send current ticking state to the joining player
         self.send_ticking_state_to_player(&player);
 
+        self.add_to_player_list(&player);
         world.add_player(player);
     }
 
+    /// Registers a client that logged in, replacing every other session of the same player.
+    ///
+    /// Older sessions are kicked with the vanilla duplicate login message, whether they are
+    /// still configuring or already playing. Returns `true` if any was kicked, in which
+    /// case the caller should wait for them with [`Server::wait_for_logout`].
+    pub fn start_configuration(
+        &self,
+        id: u64,
+        uuid: Uuid,
+        name: &str,
+        client: Weak<dyn ConfiguringClient>,
+    ) -> bool {
+        let is_same = |other_uuid: Uuid, other_name: &str| {
+            other_uuid == uuid || other_name.eq_ignore_ascii_case(name)
+        };
+        let reason = || -> TextComponent {
+            translations::MULTIPLAYER_DISCONNECT_DUPLICATE_LOGIN
+                .msg()
+                .into()
+        };
+        let mut kicked = false;
+
+        let mut configuring = self.configuring.lock();
+        configuring.retain(|entry| {
+            if !is_same(entry.uuid, &entry.name) {
+                return entry.client.strong_count() > 0;
+            }
+            if let Some(client) = entry.client.upgrade() {
+                client.disconnect(reason());
+                kicked = true;
+            }
+            false
+        });
+        configuring.push(ConfiguringEntry {
+            id,
+            uuid,
+            name: name.to_owned(),
+            client,
+        });
+        drop(configuring);
+
+        for player in self.get_players() {
+            if is_same(player.gameprofile.id, &player.gameprofile.name) {
+                player.connection.disconnect(reason());
+                kicked = true;
+            }
+        }
+        kicked
+    }
+
+    /// Stops tracking a client that finished configuration or disconnected during it.
+    pub fn end_configuration(&self, id: u64) {
+        self.configuring.lock().retain(|entry| entry.id != id);
+    }
+
+    /// Waits until no player with the given UUID or name is in a world anymore, so an
+    /// older session is saved before the new one loads its data.
+    ///
+    /// Returns `false` if the old session did not leave in time.
+    pub async fn wait_for_logout(&self, uuid: Uuid, name: &str) -> bool {
+        let logged_out = async {
+            loop {
+                // Registered before checking, so a removal in between still wakes us
+                let mut removed = pin!(self.player_removed.notified());
+                removed.as_mut().enable();
+                if !self.get_players().iter().any(|player| {
+                    player.gameprofile.id == uuid
+                        || player.gameprofile.name.eq_ignore_ascii_case(name)
+                }) {
+                    return;
+                }
+                removed.await;
+            }
+        };
+
+        tokio::select! {
+            () = logged_out => true,
+            () = sleep(DUPLICATE_LOGOUT_TIMEOUT) => false,
+            () = self.cancel_token.cancelled() => false,
+        }
+    }
+
+    /// Sends a player the commands they may use, e.g. again after their permissions changed.
+    pub fn send_commands(&self, player: &Arc<Player>) {
+        // Commands changing permissions call this while the dispatcher is already read-locked
+        let commands = self
+            .command_dispatcher
+            .read_recursive()
+            .get_commands(&CommandSender::Player(player.clone()), self);
+        player.connection.send_packet(commands);
+    }
+
+    /// Saves a player who left the server and removes them from their world.
+    pub async fn remove_player(&self, player: Arc<Player>) {
+        if let Err(e) = self.player_data.save(&player).await {
+            log::error!("Failed to save player {}: {e}", player.gameprofile.name);
+        }
+        let uuid = player.gameprofile.id;
+        if player.world().remove_player(player).await {
+            self.remove_from_player_list(uuid);
+        }
+        self.player_removed.notify_waiters();
+    }
+
+    /// Saves every online player.
+    pub async fn save_players(&self) {
+        for player in self.get_players() {
+            if let Err(e) = self.player_data.save(&player).await {
+                log::error!("Failed to save player {}: {e}", player.gameprofile.name);
+            }
+        }
+    }
+
+    /// Takes a snapshot of every online player and writes them off the tick.
+    ///
+    /// Returns `false` if another save was still running, so nothing was saved.
+    fn autosave_players(&self) -> bool {
+        let snapshots = self
+            .get_players()
+            .iter()
+            .map(|player| PlayerSnapshot::new(player))
+            .collect();
+        self.player_data.save_in_background(snapshots)
+    }
+
+    /// Gets the overworld, which holds the server-wide weather.
+    #[must_use]
+    pub fn overworld(&self) -> &Arc<World> {
+        &self.worlds[0]
+    }
+
+    /// Gets the world of a dimension.
+    #[must_use]
+    pub fn get_world(&self, dimension: &Identifier) -> Option<&Arc<World>> {
+        self.worlds
+            .iter()
+            .find(|world| &world.dimension.key == dimension)
+    }
+
+    /// Moves a player into another world at `position`, keeping their inventory,
+    /// abilities and entity data.
+    ///
+    /// Matches the dimension change of vanilla's `ServerPlayer.teleport()`.
+    pub async fn change_dimension(
+        &self,
+        player: &Arc<Player>,
+        to: &Arc<World>,
+        position: Vector3<f64>,
+        yaw: f32,
+    ) {
+        let (_, pitch) = player.rotation.load();
+        let from = player.world();
+        if Arc::ptr_eq(&from, to) {
+            player.teleport(position.x, position.y, position.z, yaw, pitch);
+            return;
+        }
+
+        player.connection.send_packet(CRespawn {
+            common_player_spawn_info: to.common_player_spawn_info(player),
+            data_to_keep: respawn_data_flags::KEEP_ALL_DATA,
+        });
+        from.transfer_player_out(player).await;
+
+        // The client dropped every chunk of the old world along with the respawn.
+        player.set_world(to.clone());
+        player.chunk_sender.lock().pending_chunks.clear();
+        player.teleport(position.x, position.y, position.z, yaw, pitch);
+
+        to.add_player(player.clone());
+        player.send_abilities();
+        player.send_inventory_to_remote();
+    }
+
+    /// Starts the portal trips players became ready for this tick and moves the players
+    /// whose exit has loaded.
+    async fn process_portal_travels(&self) {
+        for player in self.get_players() {
+            if let Some((portal, entry)) = player.take_pending_portal() {
+                self.start_portal_travel(&player, portal, entry);
+            }
+        }
+
+        let ready: Vec<PendingPortalTravel> = {
+            let mut travels = self.portal_travels.lock();
+            let (ready, waiting) = mem::take(&mut *travels)
+                .into_iter()
+                .partition(|travel| travel.player.connection.closed() || travel.exit_loaded());
+            *travels = waiting;
+            ready
+        };
+
+        for pending in ready {
+            pending
+                .to
+                .chunk_map
+                .chunk_tickets
+                .lock()
+                .remove_ticket(pending.ticket, MAX_VIEW_DISTANCE - PORTAL_EXIT_CHUNK_RADIUS);
+            if pending.player.connection.closed() {
+                continue;
+            }
+
+            let (yaw, _) = pending.player.rotation.load();
+            let (position, yaw) = pending.travel.create_exit(&pending.to, yaw);
+            self.change_dimension(&pending.player, &pending.to, position, yaw)
+                .await;
+            if pending.travel.portal == Portal::Nether {
+                pending.player.connection.send_packet(CLevelEvent::new(
+                    level_events::SOUND_PORTAL_TRAVEL,
+                    BlockPos::new(0, 0, 0),
+                    0,
+                    false,
+                ));
+            }
+        }
+    }
+
+    /// Plans a portal trip and loads the chunks around its exit.
+    fn start_portal_travel(&self, player: &Arc<Player>, portal: Portal, entry: BlockPos) {
+        let from = player.world();
+        let destination = portal.destination(from.dimension);
+        if destination.key == THE_NETHER.key
+            && from.get_game_rule(ALLOW_ENTERING_NETHER_USING_PORTALS) == GameRuleValue::Bool(false)
+        {
+            return;
+        }
+        let Some(to) = self.get_world(&destination.key).cloned() else {
+            return;
+        };
+
+        let mut travels = self.portal_travels.lock();
+        if travels
+            .iter()
+            .any(|pending| Arc::ptr_eq(&pending.player, player))
+        {
+            return;
+        }
+
+        let travel = portal.plan_travel(&from, &to, entry, *player.position.lock());
+        let ticket = ChunkPos::new(
+            SectionPos::block_to_section_coord(travel.origin.x()),
+            SectionPos::block_to_section_coord(travel.origin.z()),
+        );
+        to.chunk_map
+            .chunk_tickets
+            .lock()
+            .add_ticket(ticket, MAX_VIEW_DISTANCE - PORTAL_EXIT_CHUNK_RADIUS);
+        travels.push(PendingPortalTravel {
+            player: player.clone(),
+            to,
+            travel,
+            ticket,
+        });
+    }
+
     /// Gets all the players on the server
     pub fn get_players(&self) -> Vec<Arc<Player>> {
         let mut players = vec![];
@@ -188,6 +521,8 @@ impl Server {
     /// Runs the server tick loop.
     pub async fn run(self: Arc<Self>, cancel_token: CancellationToken) {
         let mut next_tick_time = Instant::now();
+        // Counts every tick, including frozen ones, so autosaves keep happening.
+        let mut ticks_since_autosave: u64 = 0;
 
         loop {
             if cancel_token.is_cancelled() {
@@ -246,6 +581,7 @@ impl Server {
             // Always tick worlds (for chunk loading/gen), but pass runs_normally
             // so game elements like random ticks only run when not frozen
             self.tick_worlds(tick_count, runs_normally).await;
+            self.process_portal_travels().await;
 
             // Record tick duration for TPS/MSPT tracking
             let (tps, mspt) = {
@@ -255,11 +591,22 @@ impl Server {
                 (tick_manager.get_tps(), tick_manager.get_average_mspt())
             };
 
+            // Broadcast player latency updates periodically
+            if tick_count.is_multiple_of(SEND_PLAYER_INFO_INTERVAL) {
+                let _span = tracing::trace_span!("broadcast_latency").entered();
+                self.broadcast_player_latency_updates();
+            }
+
             // Update tab list with TPS/MSPT periodically
             if tick_count % TAB_LIST_UPDATE_INTERVAL == 0 {
                 self.broadcast_tab_list(tps, mspt);
             }
 
+            ticks_since_autosave += 1;
+            if ticks_since_autosave >= AUTOSAVE_INTERVAL && self.autosave_players() {
+                ticks_since_autosave = 0;
+            }
+
             if should_sprint_this_tick {
                 let mut tick_manager = self.tick_rate_manager.write();
                 tick_manager.end_tick_work();
@@ -293,14 +640,17 @@ impl Server {
                     ?elapsed,
                     tick_count,
                     player_tick = ?timings.player_tick,
+                    entity_tick = ?timings.entity_tick,
                     ticket_updates = ?cm.ticket_updates,
                     holder_creation = ?cm.holder_creation,
                     schedule_generation = ?cm.schedule_generation,
                     scheduled_count = cm.scheduled_count,
                     run_generation = ?cm.run_generation,
+                    light_updates = ?cm.light_updates,
                     broadcast_changes = ?cm.broadcast_changes,
                     process_unloads = ?cm.process_unloads,
                     collect_tickable = ?cm.collect_tickable,
+                    scheduled_ticks = ?cm.scheduled_ticks,
                     tick_chunks = ?cm.tick_chunks,
                     tickable_count = cm.tickable_count,
                     total_chunks = cm.total_chunks,
@@ -349,8 +699,6 @@ impl Server {
 
     /// Broadcasts a sprint completion report to all players.
     fn broadcast_sprint_report(&self, report: &SprintReport) {
-        use steel_utils::translations;
-
         let message: TextComponent = translations::COMMANDS_TICK_SPRINT_REPORT
             .message([
                 TextComponent::from(format!("{}", report.ticks_per_second)),
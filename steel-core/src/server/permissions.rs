@@ -0,0 +1,303 @@
+//! Operators and per-player permission grants.
+//!
+//! Operators are stored in `ops.json` in vanilla's format and get a permission level
+//! from 0 to 4. Commands need the level vanilla requires for them, see [`required_level`].
+//! Players can also be granted single permission nodes in `permissions.json` without
+//! being made operator:
+//!
+//! ```json
+//! [
+//!   { "uuid": "…", "name": "Steve", "permissions": ["minecraft:command.weather"] }
+//! ]
+//! ```
+//!
+//! A grant ending in `*` matches every permission starting with what comes before it,
+//! so `minecraft:command.*` grants every command.
+
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use rustc_hash::FxHashMap;
+use serde::{Deserialize, Serialize};
+use steel_utils::locks::SyncRwLock;
+use uuid::Uuid;
+
+/// File the operators are stored in.
+pub const OPS_FILE: &str = "ops.json";
+/// File the per-player permission grants are read from.
+pub const PERMISSIONS_FILE: &str = "permissions.json";
+
+/// The vanilla permission levels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(into = "u8", try_from = "u8")]
+pub enum PermissionLevel {
+    /// Every player.
+    All = 0,
+    /// May bypass spawn protection.
+    Moderators = 1,
+    /// May use cheat commands like `/gamemode` and `/weather`.
+    Gamemasters = 2,
+    /// May manage players with commands like `/op` and `/tick`.
+    Admins = 3,
+    /// May use every command, including `/stop`.
+    Owners = 4,
+}
+
+impl From<PermissionLevel> for u8 {
+    fn from(level: PermissionLevel) -> Self {
+        level as u8
+    }
+}
+
+impl TryFrom<u8> for PermissionLevel {
+    type Error = String;
+
+    fn try_from(level: u8) -> Result<Self, Self::Error> {
+        match level {
+            0 => Ok(Self::All),
+            1 => Ok(Self::Moderators),
+            2 => Ok(Self::Gamemasters),
+            3 => Ok(Self::Admins),
+            4 => Ok(Self::Owners),
+            _ => Err(format!("invalid permission level {level}")),
+        }
+    }
+}
+
+/// Returns the level operators need for a permission without an explicit grant.
+///
+/// Matches the levels vanilla's commands require on a dedicated server.
+#[must_use]
+pub fn required_level(permission: &str) -> PermissionLevel {
+    match permission {
+        "minecraft:command.stop" => PermissionLevel::Owners,
+        "minecraft:command.op"
+        | "minecraft:command.deop"
+        | "minecraft:command.tick"
+        | "minecraft:command.whitelist"
+        | "minecraft:command.ban"
+        | "minecraft:command.ban-ip"
+        | "minecraft:command.pardon"
+        | "minecraft:command.pardon-ip"
+        | "minecraft:command.kick" => PermissionLevel::Admins,
+        _ => PermissionLevel::Gamemasters,
+    }
+}
+
+/// Returns whether a granted node covers a permission.
+fn grant_matches(grant: &str, permission: &str) -> bool {
+    match grant.strip_suffix('*') {
+        Some(prefix) => permission.starts_with(prefix),
+        None => grant == permission,
+    }
+}
+
+/// An entry of `ops.json`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OpEntry {
+    /// The operator's UUID.
+    pub uuid: Uuid,
+    /// The operator's name when they were made operator.
+    pub name: String,
+    /// The operator's permission level.
+    pub level: PermissionLevel,
+    /// Whether the operator may join a full server.
+    #[serde(default)]
+    pub bypasses_player_limit: bool,
+}
+
+/// An entry of `permissions.json`.
+#[derive(Debug, Clone, Deserialize)]
+struct GrantEntry {
+    uuid: Uuid,
+    permissions: Vec<String>,
+}
+
+/// The operators and permission grants of the server.
+pub struct Permissions {
+    ops_path: PathBuf,
+    ops: SyncRwLock<FxHashMap<Uuid, OpEntry>>,
+    /// Set if `ops.json` could not be read, so saving would throw away its entries.
+    ops_unreadable: bool,
+    grants: FxHashMap<Uuid, Vec<String>>,
+}
+
+impl Permissions {
+    /// Loads the operators and grants from the files in the given directory.
+    ///
+    /// Missing files are treated as empty. Unreadable ones are logged and ignored, so a
+    /// typo never locks everyone out of a running server, and `ops.json` is then never
+    /// written over.
+    #[must_use]
+    pub fn load(dir: impl AsRef<Path>) -> Self {
+        let dir = dir.as_ref();
+        let ops_path = dir.join(OPS_FILE);
+        let (ops, ops_unreadable) = match read_list::<OpEntry>(&ops_path) {
+            Ok(ops) => (ops, false),
+            Err(e) => {
+                log::error!(
+                    "Failed to read {}, changes to it will not be saved: {e}",
+                    ops_path.display()
+                );
+                (Vec::new(), true)
+            }
+        };
+        let permissions_path = dir.join(PERMISSIONS_FILE);
+        let grants = read_list::<GrantEntry>(&permissions_path).unwrap_or_else(|e| {
+            log::error!("Failed to read {}: {e}", permissions_path.display());
+            Vec::new()
+        });
+
+        Self {
+            ops_path,
+            ops: SyncRwLock::new(ops.into_iter().map(|entry| (entry.uuid, entry)).collect()),
+            ops_unreadable,
+            grants: grants
+                .into_iter()
+                .map(|entry| (entry.uuid, entry.permissions))
+                .collect(),
+        }
+    }
+
+    /// Returns the operator level of a player, [`PermissionLevel::All`] for non-operators.
+    #[must_use]
+    pub fn op_level(&self, uuid: Uuid) -> PermissionLevel {
+        self.ops
+            .read()
+            .get(&uuid)
+            .map_or(PermissionLevel::All, |entry| entry.level)
+    }
+
+    /// Returns whether a player may use something guarded by the given permission.
+    #[must_use]
+    pub fn has_permission(&self, uuid: Uuid, permission: &str) -> bool {
+        self.op_level(uuid) >= required_level(permission)
+            || self
+                .grants
+                .get(&uuid)
+                .is_some_and(|grants| grants.iter().any(|grant| grant_matches(grant, permission)))
+    }
+
+    /// Makes a player operator. Returns `false` if they already were one.
+    pub fn op(&self, uuid: Uuid, name: &str, level: PermissionLevel) -> bool {
+        let mut ops = self.ops.write();
+        if ops.contains_key(&uuid) {
+            return false;
+        }
+        ops.insert(
+            uuid,
+            OpEntry {
+                uuid,
+                name: name.to_owned(),
+                level,
+                bypasses_player_limit: false,
+            },
+        );
+        drop(ops);
+        self.save_ops();
+        true
+    }
+
+    /// Removes a player's operator status. Returns `false` if they were not operator.
+    pub fn deop(&self, uuid: Uuid) -> bool {
+        if self.ops.write().remove(&uuid).is_none() {
+            return false;
+        }
+        self.save_ops();
+        true
+    }
+
+    /// Writes the operators back to `ops.json`, logging failures.
+    fn save_ops(&self) {
+        if self.ops_unreadable {
+            log::error!(
+                "Not saving {} because it could not be read when it was loaded",
+                self.ops_path.display()
+            );
+            return;
+        }
+        let mut ops: Vec<OpEntry> = self.ops.read().values().cloned().collect();
+        ops.sort_by(|a, b| a.name.cmp(&b.name));
+        let result = serde_json::to_string_pretty(&ops)
+            .map_err(io::Error::from)
+            .and_then(|content| fs::write(&self.ops_path, content));
+        if let Err(e) = result {
+            log::error!("Failed to save {}: {e}", self.ops_path.display());
+        }
+    }
+}
+
+/// Reads a JSON list file, returning an empty list if it is missing.
+pub(crate) fn read_list<T: for<'de> Deserialize<'de>>(path: &Path) -> io::Result<Vec<T>> {
+    match fs::read_to_string(path) {
+        Ok(content) => Ok(serde_json::from_str(&content)?),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wildcard_grants() {
+        assert!(grant_matches(
+            "minecraft:command.weather",
+            "minecraft:command.weather"
+        ));
+        assert!(grant_matches(
+            "minecraft:command.*",
+            "minecraft:command.stop"
+        ));
+        assert!(grant_matches("*", "minecraft:command.stop"));
+        assert!(!grant_matches(
+            "minecraft:command.weather",
+            "minecraft:command.stop"
+        ));
+    }
+
+    #[test]
+    fn ops_and_grants() {
+        let op = Uuid::from_u128(1);
+        let granted = Uuid::from_u128(2);
+        let permissions = Permissions {
+            ops_path: PathBuf::new(),
+            ops: SyncRwLock::new(FxHashMap::from_iter([(
+                op,
+                OpEntry {
+                    uuid: op,
+                    name: "op".to_owned(),
+                    level: PermissionLevel::Gamemasters,
+                    bypasses_player_limit: false,
+                },
+            )])),
+            grants: FxHashMap::from_iter([(granted, vec!["minecraft:command.stop".to_owned()])]),
+        };
+
+        assert!(permissions.has_permission(op, "minecraft:command.weather"));
+        assert!(!permissions.has_permission(op, "minecraft:command.stop"));
+        assert!(permissions.has_permission(granted, "minecraft:command.stop"));
+        assert!(!permissions.has_permission(granted, "minecraft:command.weather"));
+        assert!(!permissions.has_permission(Uuid::from_u128(3), "minecraft:command.seed"));
+    }
+
+    #[test]
+    fn ops_file_format() {
+        let entry: OpEntry = serde_json::from_str(
+            r#"{"uuid":"00000000-0000-0000-0000-000000000001","name":"op","level":4,"bypassesPlayerLimit":true}"#,
+        )
+        .expect("vanilla ops.json entry");
+        assert_eq!(entry.level, PermissionLevel::Owners);
+        assert!(entry.bypasses_player_limit);
+        assert!(
+            serde_json::from_str::<OpEntry>(
+                r#"{"uuid":"00000000-0000-0000-0000-000000000001","name":"op","level":5}"#
+            )
+            .is_err()
+        );
+    }
+}
@@ -0,0 +1,182 @@
+//! This module contains the server-wide player list: the tab list and chat.
+//!
+//! Every world keeps its own players for entity tracking, but the tab list and chat span
+//! all of them, like vanilla's `PlayerList`.
+use std::sync::Arc;
+
+use steel_protocol::packet_traits::{ClientPacket, EncodedPacket};
+use steel_protocol::packets::game::{CPlayerChat, CPlayerInfoUpdate, CRemovePlayerInfo};
+use steel_protocol::utils::ConnectionProtocol;
+use uuid::Uuid;
+
+use crate::config::STEEL_CONFIG;
+use crate::player::{LastSeen, Player};
+use crate::server::Server;
+
+/// Interval in ticks between player info broadcasts (600 ticks = 30 seconds).
+/// Matches vanilla `PlayerList.SEND_PLAYER_INFO_INTERVAL`.
+pub(super) const SEND_PLAYER_INFO_INTERVAL: u64 = 600;
+
+impl Server {
+    /// Broadcasts a packet to all players in all worlds.
+    pub fn broadcast_to_all<P: ClientPacket>(&self, packet: P) {
+        let Ok(encoded) =
+            EncodedPacket::from_bare(packet, STEEL_CONFIG.compression, ConnectionProtocol::Play)
+        else {
+            return;
+        };
+        for world in &self.worlds {
+            world.broadcast_to_all_encoded(encoded.clone());
+        }
+    }
+
+    /// Adds a joining player to everyone's tab list and sends them everyone already online.
+    ///
+    /// Must be called before the player is added to their world, since clients only spawn
+    /// players they know from the tab list.
+    pub(super) fn add_to_player_list(&self, player: &Player) {
+        for existing_player in self.get_players() {
+            if existing_player.gameprofile.id == player.gameprofile.id {
+                continue;
+            }
+            player
+                .connection
+                .send_packet(CPlayerInfoUpdate::create_player_initializing(
+                    existing_player.gameprofile.id,
+                    existing_player.gameprofile.name.clone(),
+                    existing_player.gameprofile.properties.clone(),
+                    existing_player.game_mode.load().into(),
+                    existing_player.connection.latency(),
+                    None, // display_name
+                    true, // show_hat
+                ));
+
+            // Send chat session if available
+            if let Some(session) = existing_player.chat_session()
+                && let Ok(protocol_data) = session.as_data().to_protocol_data()
+            {
+                player
+                    .connection
+                    .send_packet(CPlayerInfoUpdate::update_chat_session(
+                        existing_player.gameprofile.id,
+                        protocol_data,
+                    ));
+            }
+        }
+
+        let player_info_packet = CPlayerInfoUpdate::create_player_initializing(
+            player.gameprofile.id,
+            player.gameprofile.name.clone(),
+            player.gameprofile.properties.clone(),
+            player.game_mode.load().into(),
+            player.connection.latency(),
+            None, // display_name
+            true, // show_hat
+        );
+        // The player isn't in a world yet, so they get their own entry separately
+        player.connection.send_packet(player_info_packet.clone());
+        self.broadcast_to_all(player_info_packet);
+    }
+
+    /// Removes a player who left the server from everyone's tab list.
+    pub(super) fn remove_from_player_list(&self, uuid: Uuid) {
+        self.broadcast_to_all(CRemovePlayerInfo::single(uuid));
+    }
+
+    /// Broadcasts latency updates for all players to all players.
+    /// This is called every `SEND_PLAYER_INFO_INTERVAL` ticks to update the ping display.
+    pub(super) fn broadcast_player_latency_updates(&self) {
+        let latency_entries: Vec<_> = self
+            .get_players()
+            .iter()
+            .map(|player| (player.gameprofile.id, player.connection.latency()))
+            .collect();
+
+        // Only broadcast if there are players
+        if !latency_entries.is_empty() {
+            self.broadcast_to_all(CPlayerInfoUpdate::update_latency(latency_entries));
+        }
+    }
+
+    /// Broadcasts a signed chat message to all players on the server.
+    pub fn broadcast_chat(
+        &self,
+        mut packet: CPlayerChat,
+        _sender: Arc<Player>,
+        sender_last_seen: LastSeen,
+        message_signature: Option<[u8; 256]>,
+    ) {
+        log::debug!(
+            "broadcast_chat: sender_last_seen has {} signatures, message_signature present: {}",
+            sender_last_seen.len(),
+            message_signature.is_some()
+        );
+
+        for recipient in self.get_players() {
+            let messages_received = recipient.get_and_increment_messages_received();
+            packet.global_index = messages_received;
+
+            log::debug!(
+                "Broadcasting to player {} (UUID: {}), global_index={}",
+                recipient.gameprofile.name,
+                recipient.gameprofile.id,
+                messages_received
+            );
+
+            // IMPORTANT: Index previous messages BEFORE updating the cache
+            // This matches vanilla's order: pack() then push()
+            let previous_messages = {
+                let recipient_cache = recipient.signature_cache.lock();
+                recipient_cache.index_previous_messages(&sender_last_seen)
+            };
+
+            log::debug!(
+                "  Indexed {} previous messages for recipient",
+                previous_messages.len()
+            );
+
+            packet.previous_messages.clone_from(&previous_messages);
+
+            // Send the packet
+            recipient.connection.send_packet(packet.clone());
+
+            // AFTER sending, update the recipient's cache using vanilla's push algorithm
+            // This adds all lastSeen signatures + current signature to the cache
+            if let Some(signature) = message_signature {
+                recipient
+                    .signature_cache
+                    .lock()
+                    .push(&sender_last_seen, Some(&signature));
+
+                log::debug!("  Added signature to recipient's cache and pending list");
+
+                // Add to pending messages for acknowledgment tracking
+                recipient
+                    .message_validator
+                    .lock()
+                    .add_pending(Some(Box::new(signature) as Box<[u8]>));
+            } else {
+                // Even unsigned messages update the pending tracker
+                recipient.message_validator.lock().add_pending(None);
+                log::debug!("  Added unsigned message to pending list");
+            }
+        }
+    }
+
+    /// Broadcasts an unsigned player chat message to all players on the server.
+    pub fn broadcast_unsigned_chat(
+        &self,
+        mut packet: CPlayerChat,
+        sender_name: &str,
+        message: &str,
+    ) {
+        log::info!("<{sender_name}> {message}");
+
+        for recipient in self.get_players() {
+            let messages_received = recipient.get_and_increment_messages_received();
+            packet.global_index = messages_received;
+
+            recipient.connection.send_packet(packet.clone());
+        }
+    }
+}
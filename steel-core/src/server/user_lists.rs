@@ -0,0 +1,581 @@
+//! The whitelist and the ban lists checked when players log in.
+//!
+//! Every list is a JSON array in the server directory, named like vanilla's files.
+//! Entries may leave out the UUID, in which case they match players by name. Dates use
+//! vanilla's `yyyy-MM-dd HH:mm:ss Z` format, and a ban that `expires` "forever" never ends.
+//! A file that cannot be read is never written over, so a typo does not wipe the list.
+
+use std::{
+    fs, io,
+    net::IpAddr,
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicBool, Ordering},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use steel_utils::locks::SyncRwLock;
+use steel_utils::translations;
+use text_components::TextComponent;
+use uuid::Uuid;
+
+use crate::config::STEEL_CONFIG;
+use crate::server::permissions::read_list;
+
+/// File the whitelist is stored in.
+pub const WHITELIST_FILE: &str = "whitelist.json";
+/// File the banned players are stored in.
+pub const BANNED_PLAYERS_FILE: &str = "banned-players.json";
+/// File the banned IP addresses are stored in.
+pub const BANNED_IPS_FILE: &str = "banned-ips.json";
+
+/// Reason given for bans when the command did not name one.
+pub const DEFAULT_BAN_REASON: &str = "Banned by an operator.";
+
+/// Returns the current time in seconds since the Unix epoch.
+#[must_use]
+pub fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs())
+}
+
+/// Written in place of an expiry date for bans that never end.
+const FOREVER: &str = "forever";
+
+/// Formats a Unix timestamp as a UTC date like `2024-05-01 13:37:00 +0000`.
+#[must_use]
+pub fn format_timestamp(timestamp: u64) -> String {
+    let days = (timestamp / 86_400) as i64;
+    let seconds = timestamp % 86_400;
+
+    // Converts days since the epoch to a civil date, see http://howardhinnant.github.io/date_algorithms.html
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{year:04}-{month:02}-{day:02} {:02}:{:02}:{:02} +0000",
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60
+    )
+}
+
+/// Parses a date in vanilla's `yyyy-MM-dd HH:mm:ss Z` format into a Unix timestamp.
+#[must_use]
+pub fn parse_timestamp(date: &str) -> Option<u64> {
+    let (date, rest) = date.trim().split_once(' ')?;
+    let (time, offset) = rest.split_once(' ')?;
+    let mut date = date.splitn(3, '-').map(str::parse::<i64>);
+    let (year, month, day) = (date.next()?.ok()?, date.next()?.ok()?, date.next()?.ok()?);
+    let mut time = time.splitn(3, ':').map(str::parse::<i64>);
+    let (hour, minute, second) = (time.next()?.ok()?, time.next()?.ok()?, time.next()?.ok()?);
+    if !(1..=12).contains(&month)
+        || !(1..=31).contains(&day)
+        || !(0..24).contains(&hour)
+        || !(0..60).contains(&minute)
+        || !(0..=60).contains(&second)
+    {
+        return None;
+    }
+
+    let (sign, offset) = match offset.split_at_checked(1)? {
+        ("+", offset) => (1, offset),
+        ("-", offset) => (-1, offset),
+        _ => return None,
+    };
+    if offset.len() != 4 || !offset.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let offset: i64 = offset.parse().ok()?;
+    let offset = sign * (offset / 100 * 3600 + offset % 100 * 60);
+
+    // Converts a civil date to days since the epoch, the inverse of `format_timestamp`
+    let year = year - i64::from(month <= 2);
+    let era = year.div_euclid(400);
+    let year_of_era = year.rem_euclid(400);
+    let day_of_year = (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    let days = era * 146_097 + day_of_era - 719_468;
+
+    u64::try_from(days * 86_400 + hour * 3600 + minute * 60 + second - offset).ok()
+}
+
+/// Reads and writes `created` dates, also accepting the Unix timestamps older versions of
+/// Steel wrote.
+mod date {
+    use serde::{Deserialize, Deserializer, Serializer, de::Error};
+
+    use super::{format_timestamp, parse_timestamp};
+
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    pub(super) enum StoredDate {
+        Timestamp(u64),
+        Text(String),
+    }
+
+    impl StoredDate {
+        pub(super) fn timestamp<E: Error>(self) -> Result<u64, E> {
+            match self {
+                Self::Timestamp(timestamp) => Ok(timestamp),
+                Self::Text(text) => parse_timestamp(&text)
+                    .ok_or_else(|| E::custom(format_args!("invalid date \"{text}\""))),
+            }
+        }
+    }
+
+    pub(super) fn serialize<S: Serializer>(
+        timestamp: &u64,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&format_timestamp(*timestamp))
+    }
+
+    pub(super) fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+        StoredDate::deserialize(deserializer)?.timestamp()
+    }
+}
+
+/// Reads and writes `expires` dates, where "forever" stands for no expiry.
+mod expiry {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    use super::{FOREVER, date::StoredDate, format_timestamp};
+
+    #[allow(clippy::ref_option, reason = "serde passes the field by reference")]
+    pub(super) fn serialize<S: Serializer>(
+        expires: &Option<u64>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match expires {
+            Some(timestamp) => serializer.serialize_str(&format_timestamp(*timestamp)),
+            None => serializer.serialize_str(FOREVER),
+        }
+    }
+
+    pub(super) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<u64>, D::Error> {
+        match Option::<StoredDate>::deserialize(deserializer)? {
+            None => Ok(None),
+            Some(StoredDate::Text(text)) if text.eq_ignore_ascii_case(FOREVER) => Ok(None),
+            Some(date) => date.timestamp().map(Some),
+        }
+    }
+}
+
+/// A player on a list, identified by UUID or, if unknown, by name.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ListedPlayer {
+    /// The player's UUID, if known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub uuid: Option<Uuid>,
+    /// The player's name.
+    pub name: String,
+}
+
+impl ListedPlayer {
+    /// Returns whether this entry refers to the given player.
+    #[must_use]
+    pub fn matches(&self, uuid: Uuid, name: &str) -> bool {
+        match self.uuid {
+            Some(listed) => listed == uuid,
+            None => self.name.eq_ignore_ascii_case(name),
+        }
+    }
+
+    /// Returns whether both entries refer to the same player.
+    #[must_use]
+    pub fn same_player(&self, other: &ListedPlayer) -> bool {
+        match other.uuid {
+            Some(uuid) => self.matches(uuid, &other.name),
+            None => self.name.eq_ignore_ascii_case(&other.name),
+        }
+    }
+}
+
+/// Why and until when something is banned.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BanDetails {
+    /// When the ban was created.
+    #[serde(with = "date")]
+    pub created: u64,
+    /// Who created the ban.
+    pub source: String,
+    /// When the ban ends, or `None` if it is permanent.
+    #[serde(default, with = "expiry")]
+    pub expires: Option<u64>,
+    /// The reason shown to the banned player.
+    pub reason: String,
+}
+
+impl BanDetails {
+    /// Creates a ban starting now, lasting the given number of seconds or forever.
+    #[must_use]
+    pub fn new(source: String, reason: String, duration: Option<u64>) -> Self {
+        let created = now();
+        Self {
+            created,
+            source,
+            expires: duration.map(|duration| created.saturating_add(duration)),
+            reason,
+        }
+    }
+
+    /// Returns whether the ban has run out at the given time.
+    #[must_use]
+    pub fn is_expired(&self, now: u64) -> bool {
+        self.expires.is_some_and(|expires| expires <= now)
+    }
+
+    /// Builds the disconnect message for a ban, naming its reason and end.
+    fn disconnect_message(&self, ip: bool) -> TextComponent {
+        let reason = TextComponent::plain(self.reason.clone());
+        let message: TextComponent = if ip {
+            translations::MULTIPLAYER_DISCONNECT_BANNED_IP_REASON.message([reason])
+        } else {
+            translations::MULTIPLAYER_DISCONNECT_BANNED_REASON.message([reason])
+        }
+        .into();
+        let Some(expires) = self.expires else {
+            return message;
+        };
+        let expires = TextComponent::plain(format_timestamp(expires));
+        message.add_children(vec![
+            if ip {
+                translations::MULTIPLAYER_DISCONNECT_BANNED_IP_EXPIRATION.message([expires])
+            } else {
+                translations::MULTIPLAYER_DISCONNECT_BANNED_EXPIRATION.message([expires])
+            }
+            .into(),
+        ])
+    }
+}
+
+/// An entry of `banned-players.json`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlayerBan {
+    /// The banned player.
+    #[serde(flatten)]
+    pub player: ListedPlayer,
+    /// The details of the ban.
+    #[serde(flatten)]
+    pub ban: BanDetails,
+}
+
+/// An entry of `banned-ips.json`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IpBan {
+    /// The banned address.
+    pub ip: IpAddr,
+    /// The details of the ban.
+    #[serde(flatten)]
+    pub ban: BanDetails,
+}
+
+/// A list of entries stored as a JSON array, written back whenever it changes.
+pub struct StoredList<T> {
+    path: PathBuf,
+    entries: SyncRwLock<Vec<T>>,
+    /// Set while the file could not be read, so saving would throw away its entries.
+    unreadable: AtomicBool,
+}
+
+impl<T: Clone + Serialize + DeserializeOwned> StoredList<T> {
+    /// Loads the list from a file, starting empty if it is missing.
+    ///
+    /// An invalid file is logged and leaves the list empty, and the list is not saved until
+    /// the file is fixed and reloaded.
+    #[must_use]
+    pub fn load(path: PathBuf) -> Self {
+        let list = Self {
+            path,
+            entries: SyncRwLock::new(Vec::new()),
+            unreadable: AtomicBool::new(false),
+        };
+        list.reload();
+        list
+    }
+
+    /// Reads the file again, dropping changes that were not saved.
+    ///
+    /// If the file cannot be read, the current entries are kept and nothing is saved
+    /// until a later reload succeeds.
+    pub fn reload(&self) {
+        match read_list(&self.path) {
+            Ok(entries) => {
+                *self.entries.write() = entries;
+                self.unreadable.store(false, Ordering::Relaxed);
+            }
+            Err(e) => {
+                log::error!(
+                    "Failed to read {}, changes to it will not be saved until it is fixed: {e}",
+                    self.path.display()
+                );
+                self.unreadable.store(true, Ordering::Relaxed);
+            }
+        }
+    }
+
+    /// Returns a copy of every entry.
+    #[must_use]
+    pub fn entries(&self) -> Vec<T> {
+        self.entries.read().clone()
+    }
+
+    /// Returns the first entry matching the predicate.
+    pub fn find(&self, predicate: impl Fn(&T) -> bool) -> Option<T> {
+        self.entries
+            .read()
+            .iter()
+            .find(|entry| predicate(entry))
+            .cloned()
+    }
+
+    /// Adds an entry unless one matching `existing` is already listed.
+    ///
+    /// Returns whether the entry was added.
+    pub fn add(&self, entry: T, existing: impl Fn(&T) -> bool) -> bool {
+        let mut entries = self.entries.write();
+        if entries.iter().any(existing) {
+            return false;
+        }
+        entries.push(entry);
+        drop(entries);
+        self.save();
+        true
+    }
+
+    /// Removes every entry matching the predicate.
+    ///
+    /// Returns whether anything was removed.
+    pub fn remove(&self, predicate: impl Fn(&T) -> bool) -> bool {
+        let mut entries = self.entries.write();
+        let len = entries.len();
+        entries.retain(|entry| !predicate(entry));
+        let removed = entries.len() != len;
+        drop(entries);
+        if removed {
+            self.save();
+        }
+        removed
+    }
+
+    /// Writes the list back to its file, logging failures.
+    fn save(&self) {
+        if self.unreadable.load(Ordering::Relaxed) {
+            log::error!(
+                "Not saving {} because it could not be read when it was loaded",
+                self.path.display()
+            );
+            return;
+        }
+        let result = serde_json::to_string_pretty(&*self.entries.read())
+            .map_err(io::Error::from)
+            .and_then(|content| fs::write(&self.path, content));
+        if let Err(e) = result {
+            log::error!("Failed to save {}: {e}", self.path.display());
+        }
+    }
+}
+
+/// The whitelist and ban lists of the server.
+pub struct UserLists {
+    /// The players allowed to join while the whitelist is on.
+    pub whitelist: StoredList<ListedPlayer>,
+    /// The banned players.
+    pub banned_players: StoredList<PlayerBan>,
+    /// The banned IP addresses.
+    pub banned_ips: StoredList<IpBan>,
+    whitelist_enabled: AtomicBool,
+}
+
+impl UserLists {
+    /// Loads the lists from the files in the given directory.
+    #[must_use]
+    pub fn load(dir: impl AsRef<Path>) -> Self {
+        let dir = dir.as_ref();
+        Self {
+            whitelist: StoredList::load(dir.join(WHITELIST_FILE)),
+            banned_players: StoredList::load(dir.join(BANNED_PLAYERS_FILE)),
+            banned_ips: StoredList::load(dir.join(BANNED_IPS_FILE)),
+            whitelist_enabled: AtomicBool::new(STEEL_CONFIG.whitelist),
+        }
+    }
+
+    /// Returns whether only whitelisted players may join.
+    #[must_use]
+    pub fn is_whitelist_enabled(&self) -> bool {
+        self.whitelist_enabled.load(Ordering::Relaxed)
+    }
+
+    /// Turns the whitelist on or off until the server restarts.
+    ///
+    /// Returns `false` if it already was in that state.
+    pub fn set_whitelist_enabled(&self, enabled: bool) -> bool {
+        self.whitelist_enabled.swap(enabled, Ordering::Relaxed) != enabled
+    }
+
+    /// Returns whether a player is on the whitelist.
+    #[must_use]
+    pub fn is_whitelisted(&self, uuid: Uuid, name: &str) -> bool {
+        self.whitelist
+            .find(|entry| entry.matches(uuid, name))
+            .is_some()
+    }
+
+    /// Returns the active ban of a player, dropping bans that ran out.
+    pub fn player_ban(&self, uuid: Uuid, name: &str) -> Option<PlayerBan> {
+        let now = now();
+        self.banned_players
+            .remove(|entry| entry.ban.is_expired(now));
+        self.banned_players
+            .find(|entry| entry.player.matches(uuid, name))
+    }
+
+    /// Returns the active ban of an IP address, dropping bans that ran out.
+    pub fn ip_ban(&self, ip: IpAddr) -> Option<IpBan> {
+        let now = now();
+        self.banned_ips.remove(|entry| entry.ban.is_expired(now));
+        self.banned_ips.find(|entry| entry.ip == ip)
+    }
+
+    /// Checks whether a player may join, returning the disconnect message if not.
+    ///
+    /// Matches vanilla's `PlayerList.canPlayerLogin()`: player bans are checked first, then
+    /// the whitelist, which operators bypass, and then IP bans.
+    #[must_use]
+    pub fn check_login(
+        &self,
+        uuid: Uuid,
+        name: &str,
+        ip: IpAddr,
+        bypasses_whitelist: bool,
+    ) -> Option<TextComponent> {
+        if let Some(entry) = self.player_ban(uuid, name) {
+            return Some(entry.ban.disconnect_message(false));
+        }
+        if self.is_whitelist_enabled() && !bypasses_whitelist && !self.is_whitelisted(uuid, name) {
+            return Some(
+                translations::MULTIPLAYER_DISCONNECT_NOT_WHITELISTED
+                    .msg()
+                    .into(),
+            );
+        }
+        if let Some(entry) = self.ip_ban(ip) {
+            return Some(entry.ban.disconnect_message(true));
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_timestamps() {
+        assert_eq!(format_timestamp(0), "1970-01-01 00:00:00 +0000");
+        assert_eq!(format_timestamp(951_827_696), "2000-02-29 12:34:56 +0000");
+        assert_eq!(format_timestamp(1_735_689_599), "2024-12-31 23:59:59 +0000");
+    }
+
+    #[test]
+    fn parses_timestamps() {
+        for timestamp in [0, 951_827_696, 1_735_689_599, 4_102_444_800] {
+            assert_eq!(
+                parse_timestamp(&format_timestamp(timestamp)),
+                Some(timestamp)
+            );
+        }
+        assert_eq!(
+            parse_timestamp("2000-02-29 14:34:56 +0200"),
+            Some(951_827_696)
+        );
+        assert_eq!(
+            parse_timestamp("2000-02-29 07:04:56 -0530"),
+            Some(951_827_696)
+        );
+        assert_eq!(parse_timestamp("2000-02-29 12:34:56"), None);
+        assert_eq!(parse_timestamp("2000-13-01 00:00:00 +0000"), None);
+        assert_eq!(parse_timestamp(FOREVER), None);
+    }
+
+    #[test]
+    fn players_match_by_uuid_or_name() {
+        let uuid = Uuid::from_u128(1);
+        let by_uuid = ListedPlayer {
+            uuid: Some(uuid),
+            name: "Old".to_owned(),
+        };
+        let by_name = ListedPlayer {
+            uuid: None,
+            name: "Steve".to_owned(),
+        };
+
+        assert!(by_uuid.matches(uuid, "Renamed"));
+        assert!(!by_uuid.matches(Uuid::from_u128(2), "Old"));
+        assert!(by_name.matches(Uuid::from_u128(2), "steve"));
+    }
+
+    #[test]
+    fn ban_entries_round_trip() {
+        let entry: PlayerBan = serde_json::from_str(
+            r#"{"name":"Steve","created":"1970-01-01 00:00:10 +0000","source":"Server","expires":"1970-01-01 00:00:20 +0000","reason":"Griefing"}"#,
+        )
+        .expect("valid ban entry");
+        assert_eq!(entry.player.uuid, None);
+        assert_eq!(entry.ban.created, 10);
+        assert!(!entry.ban.is_expired(19));
+        assert!(entry.ban.is_expired(20));
+
+        let json = serde_json::to_string(&entry).expect("serializes");
+        assert!(json.contains(r#""expires":"1970-01-01 00:00:20 +0000""#));
+        let again: PlayerBan = serde_json::from_str(&json).expect("round trips");
+        assert_eq!(again.player, entry.player);
+        assert_eq!(again.ban.expires, Some(20));
+        assert_eq!(again.ban.reason, "Griefing");
+    }
+
+    #[test]
+    fn reads_vanilla_ip_bans() {
+        let entry: IpBan = serde_json::from_str(
+            r#"{"ip":"192.0.2.1","created":"2024-05-01 13:37:00 +0200","source":"Server","expires":"forever","reason":"Spam"}"#,
+        )
+        .expect("valid ban entry");
+        assert_eq!(entry.ban.created, 1_714_563_420);
+        assert_eq!(entry.ban.expires, None);
+
+        let json = serde_json::to_string(&entry).expect("serializes");
+        assert!(json.contains(r#""created":"2024-05-01 11:37:00 +0000""#));
+        assert!(json.contains(r#""expires":"forever""#));
+    }
+
+    #[test]
+    fn invalid_lists_are_not_overwritten() {
+        let dir = tempfile::tempdir().expect("creates a temporary directory");
+        let path = dir.path().join(BANNED_IPS_FILE);
+        let content = r#"[{"ip":"192.0.2.1","created":"yesterday"}]"#;
+        fs::write(&path, content).expect("writes the list");
+
+        let list = StoredList::<IpBan>::load(path.clone());
+        assert!(list.entries().is_empty());
+        list.add(
+            IpBan {
+                ip: IpAddr::from([192, 0, 2, 2]),
+                ban: BanDetails::new("Server".to_owned(), "Spam".to_owned(), None),
+            },
+            |_| false,
+        );
+        assert_eq!(fs::read_to_string(&path).expect("reads the list"), content);
+    }
+}
@@ -0,0 +1,15 @@
+//! Helpers shared by the unit tests of this crate.
+
+use std::sync::Once;
+
+use steel_registry::{REGISTRY, Registry};
+
+/// Initializes the global registry with the vanilla data, once per test binary.
+pub fn init_test_registry() {
+    static INIT: Once = Once::new();
+    INIT.call_once(|| {
+        let mut registry = Registry::new_vanilla();
+        registry.freeze();
+        let _ = REGISTRY.init(registry);
+    });
+}
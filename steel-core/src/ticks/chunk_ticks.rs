@@ -0,0 +1,119 @@
+//! The scheduled tick queue of a single chunk.
+
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::mem;
+
+use rustc_hash::FxHashSet;
+use steel_utils::BlockPos;
+
+use crate::ticks::{LevelTicks, SavedTick, ScheduledTick, TickType};
+
+/// The scheduled ticks of one type in a chunk.
+///
+/// Matches vanilla's `LevelChunkTicks`: a position can only have one pending tick per
+/// block or fluid, and ticks loaded from disk stay in their saved form until the chunk
+/// is first ticked, when their delays are turned into absolute times.
+pub struct LevelChunkTicks<T: TickType> {
+    queue: BinaryHeap<Reverse<ScheduledTick<T>>>,
+    scheduled: FxHashSet<(BlockPos, usize)>,
+    pending: Vec<SavedTick<T>>,
+}
+
+impl<T: TickType> LevelChunkTicks<T> {
+    /// Creates an empty queue.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            queue: BinaryHeap::new(),
+            scheduled: FxHashSet::default(),
+            pending: Vec::new(),
+        }
+    }
+
+    /// Creates a queue holding ticks loaded from disk.
+    #[must_use]
+    pub fn load(saved: Vec<SavedTick<T>>) -> Self {
+        let mut ticks = Self::new();
+        for tick in saved {
+            if ticks.scheduled.insert((tick.pos, tick.ty.identity())) {
+                ticks.pending.push(tick);
+            }
+        }
+        ticks
+    }
+
+    /// Turns ticks loaded from disk into scheduled ticks relative to the current time.
+    pub fn unpack(&mut self, level_ticks: &LevelTicks) {
+        if self.pending.is_empty() {
+            return;
+        }
+        let now = level_ticks.time();
+        for tick in mem::take(&mut self.pending) {
+            self.queue.push(Reverse(ScheduledTick {
+                ty: tick.ty,
+                pos: tick.pos,
+                trigger_tick: now.saturating_add_signed(i64::from(tick.delay)),
+                priority: tick.priority,
+                sub_tick_order: level_ticks.next_sub_tick_order(),
+            }));
+        }
+    }
+
+    /// Schedules a tick. Returns false if the position already has a tick of this type.
+    pub fn schedule(&mut self, tick: ScheduledTick<T>) -> bool {
+        if !self.scheduled.insert((tick.pos, tick.ty.identity())) {
+            return false;
+        }
+        self.queue.push(Reverse(tick));
+        true
+    }
+
+    /// Returns whether the position has a pending tick of this type.
+    #[must_use]
+    pub fn has_scheduled(&self, pos: BlockPos, ty: T) -> bool {
+        self.scheduled.contains(&(pos, ty.identity()))
+    }
+
+    /// Removes and returns the next tick if it is due at `time`.
+    pub fn poll_due(&mut self, time: u64) -> Option<ScheduledTick<T>> {
+        if self.queue.peek()?.0.trigger_tick > time {
+            return None;
+        }
+        let Reverse(tick) = self.queue.pop()?;
+        self.scheduled.remove(&(tick.pos, tick.ty.identity()));
+        Some(tick)
+    }
+
+    /// Returns the number of pending ticks.
+    #[must_use]
+    pub fn count(&self) -> usize {
+        self.scheduled.len()
+    }
+
+    /// Returns the pending ticks in run order, with delays relative to `time`.
+    #[must_use]
+    pub fn save(&self, time: u64) -> Vec<SavedTick<T>> {
+        let mut scheduled: Vec<&ScheduledTick<T>> =
+            self.queue.iter().map(|Reverse(tick)| tick).collect();
+        scheduled.sort_unstable();
+
+        let mut saved = self.pending.clone();
+        saved.extend(scheduled.into_iter().map(|tick| {
+            SavedTick {
+                ty: tick.ty,
+                pos: tick.pos,
+                delay: (tick.trigger_tick as i64 - time as i64)
+                    .clamp(i64::from(i32::MIN), i64::from(i32::MAX)) as i32,
+                priority: tick.priority,
+            }
+        }));
+        saved
+    }
+}
+
+impl<T: TickType> Default for LevelChunkTicks<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,168 @@
+//! The world-wide clock and drain order of scheduled ticks.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use steel_utils::BlockPos;
+use steel_utils::locks::SyncMutex;
+
+use crate::ticks::{LevelChunkTicks, ScheduledTick, TickPriority, TickType};
+
+/// The maximum number of ticks of one type run per game tick, like vanilla.
+///
+/// Ticks past the limit stay queued and run on a later tick.
+pub const MAX_TICKS_PER_TICK: usize = 65536;
+
+/// Schedules ticks into chunk queues and collects the ones that are due.
+///
+/// Matches the shared part of vanilla's `LevelTicks`. The queues themselves live in the
+/// chunks, so pending ticks are saved and unloaded with them.
+pub struct LevelTicks {
+    time: AtomicU64,
+    next_sub_tick: AtomicU64,
+}
+
+impl LevelTicks {
+    /// Creates a clock starting at tick 0.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            time: AtomicU64::new(0),
+            next_sub_tick: AtomicU64::new(0),
+        }
+    }
+
+    /// Returns the current tick.
+    #[must_use]
+    pub fn time(&self) -> u64 {
+        self.time.load(Ordering::Relaxed)
+    }
+
+    /// Advances the clock to `time`. Called once per tick while the game runs normally.
+    pub(crate) fn set_time(&self, time: u64) {
+        self.time.store(time, Ordering::Relaxed);
+    }
+
+    /// Returns the next tie breaker for ticks due at the same time with the same priority.
+    pub fn next_sub_tick_order(&self) -> u64 {
+        self.next_sub_tick.fetch_add(1, Ordering::Relaxed)
+    }
+
+    /// Schedules a tick `delay` ticks from now into a chunk's queue.
+    ///
+    /// Returns false if the position already has a pending tick of this type.
+    pub fn schedule<T: TickType>(
+        &self,
+        ticks: &mut LevelChunkTicks<T>,
+        ty: T,
+        pos: BlockPos,
+        delay: u32,
+        priority: TickPriority,
+    ) -> bool {
+        ticks.unpack(self);
+        ticks.schedule(ScheduledTick {
+            ty,
+            pos,
+            trigger_tick: self.time() + u64::from(delay),
+            priority,
+            sub_tick_order: self.next_sub_tick_order(),
+        })
+    }
+
+    /// Removes the due ticks from the given chunk queues and returns them in run order.
+    ///
+    /// At most [`MAX_TICKS_PER_TICK`] ticks are returned, the rest are put back.
+    pub fn collect_due<'a, T: TickType>(
+        &self,
+        chunks: impl IntoIterator<Item = &'a SyncMutex<LevelChunkTicks<T>>>,
+    ) -> Vec<ScheduledTick<T>> {
+        let time = self.time();
+        let queues: Vec<_> = chunks.into_iter().collect();
+        let mut due = Vec::new();
+        for (index, queue) in queues.iter().enumerate() {
+            let mut queue = queue.lock();
+            queue.unpack(self);
+            while let Some(tick) = queue.poll_due(time) {
+                due.push((tick, index));
+            }
+        }
+        due.sort_unstable_by(|a, b| a.0.cmp(&b.0));
+
+        if due.len() > MAX_TICKS_PER_TICK {
+            for (tick, index) in due.split_off(MAX_TICKS_PER_TICK) {
+                queues[index].lock().schedule(tick);
+            }
+        }
+        due.into_iter().map(|(tick, _)| tick).collect()
+    }
+}
+
+impl Default for LevelTicks {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ptr;
+
+    use steel_registry::blocks::BlockRef;
+    use steel_registry::vanilla_blocks;
+
+    use super::*;
+
+    #[test]
+    fn test_due_ticks_run_in_order() {
+        let level_ticks = LevelTicks::new();
+        let first = SyncMutex::new(LevelChunkTicks::<BlockRef>::new());
+        let second = SyncMutex::new(LevelChunkTicks::<BlockRef>::new());
+        let a = BlockPos::new(0, 64, 0);
+        let b = BlockPos::new(16, 64, 0);
+
+        let stone = vanilla_blocks::STONE;
+        let dirt = vanilla_blocks::DIRT;
+        assert!(level_ticks.schedule(&mut first.lock(), stone, a, 2, TickPriority::Normal));
+        assert!(!level_ticks.schedule(&mut first.lock(), stone, a, 1, TickPriority::Normal));
+        assert!(level_ticks.schedule(&mut first.lock(), dirt, a, 1, TickPriority::Normal));
+        assert!(level_ticks.schedule(&mut second.lock(), stone, b, 2, TickPriority::High));
+
+        level_ticks.set_time(1);
+        let due = level_ticks.collect_due([&first, &second]);
+        assert_eq!(due.len(), 1);
+        assert!(ptr::eq(due[0].ty, dirt));
+
+        level_ticks.set_time(2);
+        let due = level_ticks.collect_due([&first, &second]);
+        assert_eq!(due.iter().map(|tick| tick.pos).collect::<Vec<_>>(), [b, a]);
+        assert_eq!(first.lock().count(), 0);
+    }
+
+    #[test]
+    fn test_saved_ticks_keep_their_delay() {
+        let level_ticks = LevelTicks::new();
+        let mut ticks = LevelChunkTicks::<BlockRef>::new();
+        let pos = BlockPos::new(3, 10, 5);
+        level_ticks.set_time(100);
+        level_ticks.schedule(
+            &mut ticks,
+            vanilla_blocks::STONE,
+            pos,
+            20,
+            TickPriority::Low,
+        );
+
+        let saved = ticks.save(105);
+        assert_eq!(saved.len(), 1);
+        assert_eq!(saved[0].delay, 15);
+
+        // Loaded on a server whose clock is somewhere else entirely
+        let mut loaded = LevelChunkTicks::load(saved);
+        assert!(loaded.has_scheduled(pos, vanilla_blocks::STONE));
+        level_ticks.set_time(5000);
+        loaded.unpack(&level_ticks);
+        assert!(loaded.poll_due(5014).is_none());
+        let tick = loaded.poll_due(5015).expect("tick should be due");
+        assert_eq!(tick.priority, TickPriority::Low);
+        assert!(!loaded.has_scheduled(pos, vanilla_blocks::STONE));
+    }
+}
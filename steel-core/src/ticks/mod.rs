@@ -0,0 +1,176 @@
+//! Scheduled block and fluid ticks.
+//!
+//! Mirrors vanilla's `net.minecraft.world.ticks`: every full chunk keeps a
+//! [`LevelChunkTicks`] queue for block ticks and one for fluid ticks, and the
+//! [`LevelTicks`] of the chunk map drains the due ticks of all ticking chunks once per
+//! tick, in a single order shared by the whole world.
+//!
+//! Ticks are saved with the chunk as delays relative to the current tick, so they keep
+//! their timing across unloads and restarts.
+
+mod chunk_ticks;
+mod level_ticks;
+
+use std::cmp::Ordering;
+use std::ptr;
+
+use steel_registry::REGISTRY;
+use steel_registry::blocks::BlockRef;
+use steel_registry::fluid::FluidRef;
+use steel_utils::{BlockPos, Identifier};
+
+pub use chunk_ticks::LevelChunkTicks;
+pub use level_ticks::{LevelTicks, MAX_TICKS_PER_TICK};
+
+/// The priority of a scheduled tick. Ticks due on the same game tick run in priority order.
+///
+/// Matches vanilla's `TickPriority`, where a lower value runs first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub enum TickPriority {
+    /// Runs before every other priority.
+    ExtremelyHigh,
+    /// Very high priority.
+    VeryHigh,
+    /// High priority.
+    High,
+    /// The default priority.
+    #[default]
+    Normal,
+    /// Low priority.
+    Low,
+    /// Very low priority.
+    VeryLow,
+    /// Runs after every other priority.
+    ExtremelyLow,
+}
+
+impl TickPriority {
+    /// Returns the vanilla value of this priority, from -3 to 3.
+    #[must_use]
+    pub const fn value(self) -> i8 {
+        self as i8 - 3
+    }
+
+    /// Gets a priority from its vanilla value, clamping out of range values.
+    #[must_use]
+    pub const fn from_value(value: i8) -> Self {
+        match value {
+            i8::MIN..=-3 => Self::ExtremelyHigh,
+            -2 => Self::VeryHigh,
+            -1 => Self::High,
+            0 => Self::Normal,
+            1 => Self::Low,
+            2 => Self::VeryLow,
+            3..=i8::MAX => Self::ExtremelyLow,
+        }
+    }
+}
+
+/// Something that can be scheduled to tick: a block or a fluid.
+pub trait TickType: Copy + Send + Sync + 'static {
+    /// Returns the registry key of this type, used when saving.
+    fn key(self) -> &'static Identifier;
+
+    /// Looks up a type by its registry key, used when loading.
+    fn from_key(key: &Identifier) -> Option<Self>;
+
+    /// Returns a value unique to this type, used to deduplicate ticks.
+    fn identity(self) -> usize;
+}
+
+impl TickType for BlockRef {
+    fn key(self) -> &'static Identifier {
+        &self.key
+    }
+
+    fn from_key(key: &Identifier) -> Option<Self> {
+        REGISTRY.blocks.by_key(key)
+    }
+
+    fn identity(self) -> usize {
+        ptr::from_ref(self).addr()
+    }
+}
+
+impl TickType for FluidRef {
+    fn key(self) -> &'static Identifier {
+        &self.key
+    }
+
+    fn from_key(key: &Identifier) -> Option<Self> {
+        REGISTRY.fluids.by_key(key)
+    }
+
+    fn identity(self) -> usize {
+        ptr::from_ref(self).addr()
+    }
+}
+
+/// A tick waiting in a chunk's queue.
+#[derive(Debug, Clone, Copy)]
+pub struct ScheduledTick<T> {
+    /// The block or fluid to tick.
+    pub ty: T,
+    /// The position to tick.
+    pub pos: BlockPos,
+    /// The game tick on which this tick is due.
+    pub trigger_tick: u64,
+    /// The priority among ticks due on the same game tick.
+    pub priority: TickPriority,
+    /// Tie breaker keeping ticks with the same time and priority in scheduling order.
+    pub sub_tick_order: u64,
+}
+
+impl<T> ScheduledTick<T> {
+    const fn order_key(&self) -> (u64, TickPriority, u64) {
+        (self.trigger_tick, self.priority, self.sub_tick_order)
+    }
+}
+
+impl<T> PartialEq for ScheduledTick<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.order_key() == other.order_key()
+    }
+}
+
+impl<T> Eq for ScheduledTick<T> {}
+
+impl<T> PartialOrd for ScheduledTick<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T> Ord for ScheduledTick<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.order_key().cmp(&other.order_key())
+    }
+}
+
+/// A tick as stored on disk, with its due time relative to the time it was saved.
+#[derive(Debug, Clone, Copy)]
+pub struct SavedTick<T> {
+    /// The block or fluid to tick.
+    pub ty: T,
+    /// The position to tick.
+    pub pos: BlockPos,
+    /// Ticks left until the tick is due. Overdue ticks have a negative delay.
+    pub delay: i32,
+    /// The priority among ticks due on the same game tick.
+    pub priority: TickPriority,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_priority_values_roundtrip() {
+        for value in -3..=3 {
+            assert_eq!(TickPriority::from_value(value).value(), value);
+        }
+        assert_eq!(TickPriority::from_value(-10), TickPriority::ExtremelyHigh);
+        assert_eq!(TickPriority::from_value(10), TickPriority::ExtremelyLow);
+        assert!(TickPriority::High < TickPriority::Normal);
+    }
+}
@@ -0,0 +1,406 @@
+//! Thread-safe storage for non-player entities, indexed by entity ID, UUID, chunk section
+//! and chunk.
+
+use std::hash::Hash;
+use std::sync::Arc;
+
+use crossbeam::atomic::AtomicCell;
+use rustc_hash::{FxBuildHasher, FxHashSet};
+use scc::HashMap;
+use steel_registry::blocks::shapes::AABBd;
+use steel_utils::locks::SyncMutex;
+use steel_utils::math::Vector3;
+use steel_utils::{ChunkPos, SectionPos};
+use uuid::Uuid;
+
+use crate::entity::{Entity, ServerEntity};
+
+/// An entity stored in the world along with its network tracker.
+pub struct TrackedEntity {
+    /// The entity itself.
+    pub entity: Arc<dyn Entity>,
+    /// Network sync state for players tracking the entity.
+    pub server_entity: SyncMutex<ServerEntity>,
+    /// The section the entity is currently indexed under.
+    section: AtomicCell<SectionPos>,
+}
+
+impl TrackedEntity {
+    /// Returns the section the entity is currently indexed under.
+    #[must_use]
+    pub fn section(&self) -> SectionPos {
+        self.section.load()
+    }
+
+    /// Returns the chunk the entity is currently indexed under.
+    #[must_use]
+    pub fn chunk_pos(&self) -> ChunkPos {
+        chunk_of(self.section())
+    }
+}
+
+/// Thread-safe entity storage with indexing by entity ID, UUID, chunk section and chunk.
+///
+/// Players are stored separately in [`super::PlayerMap`].
+pub struct EntityMap {
+    /// Primary index by entity ID (session-local identifier)
+    by_id: HashMap<i32, Arc<TrackedEntity>, FxBuildHasher>,
+    /// Secondary index by UUID (persistent identifier)
+    by_uuid: HashMap<Uuid, i32>,
+    /// Spatial index of entity IDs by chunk section
+    by_section: HashMap<SectionPos, FxHashSet<i32>, FxBuildHasher>,
+    /// Entity IDs by chunk column, so chunk lookups don't have to search every section
+    by_chunk: HashMap<ChunkPos, FxHashSet<i32>, FxBuildHasher>,
+}
+
+impl Default for EntityMap {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EntityMap {
+    /// Creates a new empty entity map.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            by_id: HashMap::default(),
+            by_uuid: HashMap::new(),
+            by_section: HashMap::default(),
+            by_chunk: HashMap::default(),
+        }
+    }
+
+    /// Inserts an entity into every index.
+    ///
+    /// Returns `false` if an entity with the same ID or UUID already exists.
+    pub fn insert(&self, entity: Arc<dyn Entity>) -> bool {
+        let id = entity.get_id();
+        if self.by_uuid.insert_sync(entity.get_uuid(), id).is_err() {
+            return false;
+        }
+
+        let section = section_of(entity.get_position());
+        let tracked = Arc::new(TrackedEntity {
+            server_entity: SyncMutex::new(ServerEntity::new(entity.as_ref())),
+            entity,
+            section: AtomicCell::new(section),
+        });
+        if self.by_id.insert_sync(id, tracked.clone()).is_err() {
+            let _ = self.by_uuid.remove_sync(&tracked.entity.get_uuid());
+            return false;
+        }
+        add_to_index(&self.by_section, section, id);
+        add_to_index(&self.by_chunk, chunk_of(section), id);
+        true
+    }
+
+    /// Removes an entity by ID from every index.
+    ///
+    /// Returns the removed entity if found.
+    pub fn remove(&self, id: i32) -> Option<Arc<TrackedEntity>> {
+        let (_, tracked) = self.by_id.remove_sync(&id)?;
+        let _ = self.by_uuid.remove_sync(&tracked.entity.get_uuid());
+        remove_from_index(&self.by_section, tracked.section(), id);
+        remove_from_index(&self.by_chunk, tracked.chunk_pos(), id);
+        Some(tracked)
+    }
+
+    /// Gets an entity by ID.
+    #[must_use]
+    pub fn get_by_id(&self, id: i32) -> Option<Arc<dyn Entity>> {
+        self.by_id
+            .read_sync(&id, |_, tracked| tracked.entity.clone())
+    }
+
+    /// Gets an entity by UUID.
+    #[must_use]
+    pub fn get_by_uuid(&self, uuid: &Uuid) -> Option<Arc<dyn Entity>> {
+        let id = self.by_uuid.read_sync(uuid, |_, id| *id)?;
+        self.get_by_id(id)
+    }
+
+    /// Gets an entity along with its tracker by ID.
+    #[must_use]
+    pub fn get_tracked(&self, id: i32) -> Option<Arc<TrackedEntity>> {
+        self.by_id.read_sync(&id, |_, tracked| tracked.clone())
+    }
+
+    /// Returns the IDs of all entities in the given section.
+    #[must_use]
+    pub fn get_in_section(&self, section: SectionPos) -> Vec<i32> {
+        self.by_section
+            .read_sync(&section, |_, ids| ids.iter().copied().collect())
+            .unwrap_or_default()
+    }
+
+    /// Returns every entity indexed under a section of the given chunk column.
+    #[must_use]
+    pub fn get_in_chunk(&self, chunk: ChunkPos) -> Vec<Arc<TrackedEntity>> {
+        let ids: Vec<i32> = self
+            .by_chunk
+            .read_sync(&chunk, |_, ids| ids.iter().copied().collect())
+            .unwrap_or_default();
+        ids.into_iter()
+            .filter_map(|id| self.get_tracked(id))
+            .collect()
+    }
+
+    /// Returns whether any entity is indexed under a section of the given chunk column.
+    #[must_use]
+    pub fn has_entities_in_chunk(&self, chunk: ChunkPos) -> bool {
+        self.by_chunk
+            .read_sync(&chunk, |_, ids| !ids.is_empty())
+            .unwrap_or(false)
+    }
+
+    /// Returns all entities whose bounding box intersects the given box.
+    ///
+    /// Like vanilla's `EntitySectionStorage.getEntities()`, sections within two
+    /// blocks of the box are searched to catch entities overhanging a section border.
+    #[must_use]
+    pub fn get_entities_in(&self, aabb: &AABBd) -> Vec<Arc<dyn Entity>> {
+        let min = section_of(Vector3::new(
+            aabb.min_x - 2.0,
+            aabb.min_y - 4.0,
+            aabb.min_z - 2.0,
+        ));
+        let max = section_of(Vector3::new(aabb.max_x + 2.0, aabb.max_y, aabb.max_z + 2.0));
+
+        let mut entities = Vec::new();
+        for x in min.0.x..=max.0.x {
+            for y in min.0.y..=max.0.y {
+                for z in min.0.z..=max.0.z {
+                    for id in self.get_in_section(SectionPos::new(x, y, z)) {
+                        if let Some(entity) = self.get_by_id(id)
+                            && !entity.is_removed()
+                            && entity.get_bounding_box().intersects(aabb)
+                        {
+                            entities.push(entity);
+                        }
+                    }
+                }
+            }
+        }
+        entities
+    }
+
+    /// Moves the entity to the section matching its current position, if it changed.
+    pub fn update_section(&self, tracked: &TrackedEntity) {
+        let new_section = section_of(tracked.entity.get_position());
+        let old_section = tracked.section.swap(new_section);
+        if old_section != new_section {
+            let id = tracked.entity.get_id();
+            remove_from_index(&self.by_section, old_section, id);
+            add_to_index(&self.by_section, new_section, id);
+
+            let (old_chunk, new_chunk) = (chunk_of(old_section), chunk_of(new_section));
+            if old_chunk != new_chunk {
+                remove_from_index(&self.by_chunk, old_chunk, id);
+                add_to_index(&self.by_chunk, new_chunk, id);
+            }
+        }
+    }
+
+    /// Iterates over all tracked entities.
+    ///
+    /// The callback returns `true` to continue iteration, `false` to stop.
+    pub fn iter_entities<F>(&self, mut f: F)
+    where
+        F: FnMut(&Arc<TrackedEntity>) -> bool,
+    {
+        self.by_id.iter_sync(|_, tracked| f(tracked));
+    }
+
+    /// Returns the number of entities.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.by_id.len()
+    }
+
+    /// Returns true if there are no entities.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.by_id.is_empty()
+    }
+}
+
+/// Adds an entity ID to the set stored under `key`, creating the set if needed.
+///
+/// The entry stays locked between finding and updating the set, so concurrent adds under
+/// the same key can't overwrite each other.
+fn add_to_index<K: Eq + Hash>(index: &HashMap<K, FxHashSet<i32>, FxBuildHasher>, key: K, id: i32) {
+    index.entry_sync(key).or_default().get_mut().insert(id);
+}
+
+/// Removes an entity ID from the set stored under `key`, dropping the set once it's empty.
+fn remove_from_index<K: Eq + Hash>(
+    index: &HashMap<K, FxHashSet<i32>, FxBuildHasher>,
+    key: K,
+    id: i32,
+) {
+    let should_remove = index
+        .update_sync(&key, |_, ids| {
+            ids.remove(&id);
+            ids.is_empty()
+        })
+        .unwrap_or(false);
+
+    if should_remove {
+        let _ = index.remove_if_sync(&key, |ids| ids.is_empty());
+    }
+}
+
+/// Returns the chunk column containing the given section.
+fn chunk_of(section: SectionPos) -> ChunkPos {
+    ChunkPos::new(section.0.x, section.0.z)
+}
+
+/// Returns the section containing the given position.
+fn section_of(pos: Vector3<f64>) -> SectionPos {
+    SectionPos::new(
+        SectionPos::block_to_section_coord(pos.x.floor() as i32),
+        SectionPos::block_to_section_coord(pos.y.floor() as i32),
+        SectionPos::block_to_section_coord(pos.z.floor() as i32),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::thread;
+
+    use steel_registry::entity_types::EntityTypeRef;
+    use steel_registry::vanilla_entities;
+
+    use super::*;
+
+    struct TestEntity {
+        id: i32,
+        uuid: Uuid,
+        position: SyncMutex<Vector3<f64>>,
+    }
+
+    impl TestEntity {
+        fn new(id: i32, position: Vector3<f64>) -> Arc<Self> {
+            Arc::new(Self {
+                id,
+                uuid: Uuid::new_v4(),
+                position: SyncMutex::new(position),
+            })
+        }
+    }
+
+    impl Entity for TestEntity {
+        fn get_id(&self) -> i32 {
+            self.id
+        }
+
+        fn get_uuid(&self) -> Uuid {
+            self.uuid
+        }
+
+        fn get_type(&self) -> EntityTypeRef {
+            vanilla_entities::ITEM
+        }
+
+        fn get_position(&self) -> Vector3<f64> {
+            *self.position.lock()
+        }
+    }
+
+    #[test]
+    fn test_insert_and_lookup() {
+        let map = EntityMap::new();
+        let entity = TestEntity::new(7, Vector3::new(8.0, 64.0, 8.0));
+        assert!(map.insert(entity.clone()));
+        assert!(!map.insert(entity.clone()));
+
+        assert_eq!(map.len(), 1);
+        assert!(map.get_by_id(7).is_some());
+        assert_eq!(map.get_by_uuid(&entity.uuid).map(|e| e.get_id()), Some(7));
+        assert_eq!(map.get_in_section(SectionPos::new(0, 4, 0)), vec![7]);
+    }
+
+    #[test]
+    fn test_remove() {
+        let map = EntityMap::new();
+        let entity = TestEntity::new(7, Vector3::new(8.0, 64.0, 8.0));
+        map.insert(entity.clone());
+
+        assert!(map.remove(7).is_some());
+        assert!(map.is_empty());
+        assert!(map.get_by_uuid(&entity.uuid).is_none());
+        assert!(map.get_in_section(SectionPos::new(0, 4, 0)).is_empty());
+    }
+
+    #[test]
+    fn test_section_update() {
+        let map = EntityMap::new();
+        let entity = TestEntity::new(7, Vector3::new(8.0, 64.0, 8.0));
+        map.insert(entity.clone());
+
+        *entity.position.lock() = Vector3::new(-8.0, 64.0, 40.0);
+        let tracked = map.get_tracked(7).expect("entity should be tracked");
+        map.update_section(&tracked);
+
+        assert!(map.get_in_section(SectionPos::new(0, 4, 0)).is_empty());
+        assert_eq!(map.get_in_section(SectionPos::new(-1, 4, 2)), vec![7]);
+        assert_eq!(tracked.chunk_pos(), ChunkPos::new(-1, 2));
+        assert!(!map.has_entities_in_chunk(ChunkPos::new(0, 0)));
+        assert_eq!(map.get_in_chunk(ChunkPos::new(-1, 2)).len(), 1);
+    }
+
+    #[test]
+    fn test_get_entities_in() {
+        let map = EntityMap::new();
+        map.insert(TestEntity::new(1, Vector3::new(0.5, 64.0, 0.5)));
+        map.insert(TestEntity::new(2, Vector3::new(15.9, 64.0, 0.5)));
+        map.insert(TestEntity::new(3, Vector3::new(100.0, 64.0, 100.0)));
+
+        let found: FxHashSet<i32> = map
+            .get_entities_in(&AABBd::new(0.0, 63.0, 0.0, 17.0, 66.0, 1.0))
+            .iter()
+            .map(|e| e.get_id())
+            .collect();
+        assert_eq!(found, [1, 2].into_iter().collect());
+    }
+
+    #[test]
+    fn test_get_in_chunk() {
+        let map = EntityMap::new();
+        map.insert(TestEntity::new(1, Vector3::new(0.5, -60.0, 0.5)));
+        map.insert(TestEntity::new(2, Vector3::new(15.5, 300.0, 15.5)));
+        map.insert(TestEntity::new(3, Vector3::new(16.5, 64.0, 0.5)));
+
+        let mut ids: Vec<i32> = map
+            .get_in_chunk(ChunkPos::new(0, 0))
+            .iter()
+            .map(|tracked| tracked.entity.get_id())
+            .collect();
+        ids.sort_unstable();
+        assert_eq!(ids, vec![1, 2]);
+        assert!(map.has_entities_in_chunk(ChunkPos::new(1, 0)));
+        assert!(!map.has_entities_in_chunk(ChunkPos::new(0, 1)));
+    }
+
+    #[test]
+    fn test_concurrent_inserts_into_one_section() {
+        let map = EntityMap::new();
+        thread::scope(|scope| {
+            for worker in 0..4 {
+                let map = &map;
+                scope.spawn(move || {
+                    for i in 0..250 {
+                        map.insert(TestEntity::new(
+                            worker * 250 + i,
+                            Vector3::new(8.0, 64.0, 8.0),
+                        ));
+                    }
+                });
+            }
+        });
+
+        assert_eq!(map.get_in_section(SectionPos::new(0, 4, 0)).len(), 1000);
+        assert_eq!(map.get_in_chunk(ChunkPos::new(0, 0)).len(), 1000);
+    }
+}
@@ -9,12 +9,13 @@ use std::{
 };
 
 use crate::chunk::chunk_map::ChunkMapTickTimings;
+use crate::chunk::heightmap::HeightmapType;
 
 use sha2::{Digest, Sha256};
 use steel_protocol::packet_traits::{ClientPacket, EncodedPacket};
 use steel_protocol::packets::game::{
-    CBlockDestruction, CBlockEvent, CLevelEvent, CPlayerChat, CPlayerInfoUpdate, CSound,
-    CSystemChat, SoundSource,
+    CBlockDestruction, CBlockEvent, CLevelEvent, CSetTime, CSound, CSystemChat,
+    CommonPlayerSpawnInfo, SoundSource,
 };
 use steel_protocol::utils::ConnectionProtocol;
 
@@ -22,34 +23,53 @@ use simdnbt::owned::NbtCompound;
 use steel_registry::block_entity_type::BlockEntityTypeRef;
 use steel_registry::blocks::BlockRef;
 use steel_registry::blocks::block_state_ext::BlockStateExt;
-use steel_registry::blocks::properties::Direction;
+use steel_registry::blocks::light::MAX_LIGHT_LEVEL;
+use steel_registry::blocks::properties::{BlockStateProperties, Direction};
 use steel_registry::game_rules::{GameRuleRef, GameRuleValue};
 use steel_registry::item_stack::ItemStack;
 use steel_registry::level_events;
+use steel_registry::loot_table::{BlockEntityRef, LootContext, LootTableRef, WeatherState};
 use steel_registry::vanilla_blocks;
-use steel_registry::vanilla_game_rules::RANDOM_TICK_SPEED;
+use steel_registry::vanilla_dimension_types;
+use steel_registry::vanilla_entities;
+use steel_registry::vanilla_fluids;
+use steel_registry::vanilla_game_rules::{ADVANCE_TIME, BLOCK_DROPS, RANDOM_TICK_SPEED};
 use steel_registry::{REGISTRY, dimension_type::DimensionTypeRef};
 
 use steel_registry::blocks::shapes::{AABBd, VoxelShape};
-use steel_utils::locks::SyncRwLock;
-use steel_utils::{BlockPos, BlockStateId, ChunkPos, SectionPos, types::UpdateFlags};
+use steel_utils::locks::{SyncMutex, SyncRwLock};
+use steel_utils::math::Vector3;
+use steel_utils::{BlockPos, BlockStateId, ChunkPos, Identifier, SectionPos, types::UpdateFlags};
 use tokio::{runtime::Runtime, time::Instant};
 
 use crate::{
     ChunkMap,
-    behavior::BLOCK_BEHAVIORS,
-    block_entity::SharedBlockEntity,
+    behavior::{BLOCK_BEHAVIORS, FlowingFluid, block_behaviours::DROPS_LIKE},
+    block_entity::{BlockEntitySnapshot, SharedBlockEntity},
     config::STEEL_CONFIG,
+    entity::ItemEntity,
     level_data::LevelDataManager,
-    player::{LastSeen, Player},
+    lighting::LightLayer,
+    player::Player,
 };
 
+mod entity_map;
 mod player_area_map;
 mod player_map;
+mod portal;
+mod weather;
 mod world_entities;
+mod world_ticks;
 
+pub use entity_map::{EntityMap, TrackedEntity};
 pub use player_area_map::PlayerAreaMap;
 pub use player_map::PlayerMap;
+pub use portal::{
+    END_SPAWN_POINT, NETHER_EXIT_RADIUS, PORTAL_COOLDOWN, Portal, PortalProcess, PortalTravel,
+};
+use portal::PortalExits;
+use weather::WeatherLevels;
+pub use weather::{RAIN_DELAY, RAIN_DURATION, THUNDER_DELAY, THUNDER_DURATION};
 
 /// Timing information for a world tick.
 #[derive(Debug)]
@@ -58,11 +78,13 @@ pub struct WorldTickTimings {
     pub chunk_map: ChunkMapTickTimings,
     /// Time spent ticking players.
     pub player_tick: Duration,
+    /// Time spent ticking non-player entities.
+    pub entity_tick: Duration,
 }
 
-/// Interval in ticks between player info broadcasts (600 ticks = 30 seconds).
-/// Matches vanilla `PlayerList.SEND_PLAYER_INFO_INTERVAL`.
-const SEND_PLAYER_INFO_INTERVAL: u64 = 600;
+/// Interval in ticks between time synchronizations (20 ticks = 1 second).
+/// Matches vanilla `MinecraftServer.tickChildren`.
+const TIME_SYNC_INTERVAL: u64 = 20;
 
 /// A struct that represents a world.
 pub struct World {
@@ -76,9 +98,15 @@ pub struct World {
     pub dimension: DimensionTypeRef,
     /// Level data manager for persistent world state.
     pub level_data: SyncRwLock<LevelDataManager>,
+    /// All non-player entities in the world.
+    pub entities: EntityMap,
+    /// How strong the rain and thunder currently are.
+    weather_levels: SyncMutex<WeatherLevels>,
     /// Whether the tick rate is running normally (not frozen/paused).
     /// When false, movement validation checks are skipped.
     tick_runs_normally: AtomicBool,
+    /// The nether portals already found as exits of trips into this world.
+    portal_exits: PortalExits,
 }
 
 impl World {
@@ -92,16 +120,38 @@ impl World {
         dimension: DimensionTypeRef,
         seed: i64,
     ) -> io::Result<Arc<Self>> {
+        // The configured superflat layers only replace the overworld generator.
+        let flat_world = if dimension.key == vanilla_dimension_types::OVERWORLD.key {
+            STEEL_CONFIG.flat_world.clone()
+        } else {
+            None
+        };
         let level_data =
-            LevelDataManager::new(format!("world/{}", dimension.key.path), seed).await?;
+            LevelDataManager::new(format!("world/{}", dimension.key.path), seed, flat_world)
+                .await?;
+
+        // The stored seed and generator win over the configured ones for worlds that
+        // already exist.
+        let seed = level_data.seed();
+        let flat_settings = level_data.data().flat_settings.clone();
+        let weather_levels = WeatherLevels::new(&level_data.data().weather);
 
         Ok(Arc::new_cyclic(|weak_self: &Weak<World>| Self {
-            chunk_map: Arc::new(ChunkMap::new(chunk_runtime, weak_self.clone(), &dimension)),
+            chunk_map: Arc::new(ChunkMap::new(
+                chunk_runtime,
+                weak_self.clone(),
+                &dimension,
+                seed,
+                flat_settings.as_ref(),
+            )),
             players: PlayerMap::new(),
             player_area_map: PlayerAreaMap::new(),
             dimension,
             level_data: SyncRwLock::new(level_data),
+            entities: EntityMap::new(),
+            weather_levels: SyncMutex::new(weather_levels),
             tick_runs_normally: AtomicBool::new(true),
+            portal_exits: PortalExits::default(),
         }))
     }
 
@@ -252,6 +302,59 @@ impl World {
             .set(rule, value, &REGISTRY.game_rules)
     }
 
+    /// Returns the total number of ticks the world has run for.
+    #[must_use]
+    pub fn game_time(&self) -> i64 {
+        self.level_data.read().game_time()
+    }
+
+    /// Returns the time of day in ticks. It keeps counting up across days.
+    #[must_use]
+    pub fn day_time(&self) -> i64 {
+        self.level_data.read().day_time()
+    }
+
+    /// Sets the time of day and sends it to the players in the world.
+    pub fn set_day_time(&self, time: i64) {
+        self.level_data.write().set_day_time(time);
+        self.broadcast_time();
+    }
+
+    /// Advances the game time, and the time of day if the `advance_time` game rule is on.
+    ///
+    /// Matches vanilla `ServerLevel.tickTime()`.
+    fn tick_time(&self) {
+        let advance_time = self.get_game_rule(ADVANCE_TIME) == GameRuleValue::Bool(true);
+        let mut level_data = self.level_data.write();
+        let game_time = level_data.game_time();
+        level_data.set_game_time(game_time + 1);
+        if advance_time {
+            let day_time = level_data.day_time();
+            level_data.set_day_time(day_time + 1);
+        }
+    }
+
+    /// Creates the packet that synchronizes the world's time with a client.
+    fn time_packet(&self) -> CSetTime {
+        let tick_day_time = self.get_game_rule(ADVANCE_TIME) == GameRuleValue::Bool(true);
+        let level_data = self.level_data.read();
+        CSetTime {
+            game_time: level_data.game_time(),
+            day_time: level_data.day_time(),
+            tick_day_time,
+        }
+    }
+
+    /// Sends the world's time to all players in it.
+    pub fn broadcast_time(&self) {
+        self.broadcast_to_all(self.time_packet());
+    }
+
+    /// Sends the world's time to a single player.
+    pub fn send_time(&self, player: &Player) {
+        player.connection.send_packet(self.time_packet());
+    }
+
     /// Gets the world seed.
     #[must_use]
     pub fn seed(&self) -> i64 {
@@ -274,6 +377,32 @@ impl World {
         i64::from_be_bytes(bytes)
     }
 
+    /// Builds the dimension info sent to a player joining or entering this world.
+    ///
+    /// # Panics
+    /// Panics if the dimension type is not registered.
+    #[must_use]
+    pub fn common_player_spawn_info(&self, player: &Player) -> CommonPlayerSpawnInfo {
+        let generator = &self.chunk_map.world_gen_context.generator;
+        CommonPlayerSpawnInfo {
+            dimension_type: *REGISTRY.dimension_types.get_id(
+                REGISTRY
+                    .dimension_types
+                    .by_key(&self.dimension.key)
+                    .expect("Should be registered"),
+            ) as i32,
+            dimension: self.dimension.key.clone(),
+            seed: self.obfuscated_seed(),
+            game_type: player.game_mode.load(),
+            previous_game_type: None,
+            is_debug: false,
+            is_flat: generator.is_flat(),
+            last_death_location: None,
+            portal_cooldown: player.portal_cooldown(),
+            sea_level: generator.sea_level(),
+        }
+    }
+
     /// Gets the block state at the given position.
     ///
     /// Returns the default block state (void air) if the position is out of bounds or the chunk is not loaded.
@@ -289,6 +418,68 @@ impl World {
             .unwrap_or_else(|| REGISTRY.blocks.get_base_state_id(vanilla_blocks::AIR))
     }
 
+    /// Gets the light of one layer at the given position.
+    ///
+    /// Matches vanilla's `Level.getBrightness()`. Above the world, sky light is at its
+    /// maximum in dimensions that have it. Unloaded chunks are dark.
+    pub fn get_brightness(&self, layer: LightLayer, pos: &BlockPos) -> u8 {
+        if pos.0.y > self.get_max_y() {
+            return if layer == LightLayer::Sky && self.dimension.has_skylight {
+                MAX_LIGHT_LEVEL
+            } else {
+                0
+            };
+        }
+        if !self.is_in_valid_bounds(pos) {
+            return 0;
+        }
+
+        let chunk_pos = Self::chunk_pos_for_block(pos);
+        self.chunk_map
+            .with_full_chunk(&chunk_pos, |chunk| {
+                chunk
+                    .as_full()
+                    .map_or(0, |chunk| chunk.get_light(layer, *pos))
+            })
+            .unwrap_or(0)
+    }
+
+    /// Gets the brightest of the sky and block light at the given position, with the sky
+    /// light reduced by `sky_darken`.
+    ///
+    /// Matches vanilla's `Level.getRawBrightness()`.
+    pub fn get_raw_brightness(&self, pos: &BlockPos, sky_darken: u8) -> u8 {
+        let sky = self
+            .get_brightness(LightLayer::Sky, pos)
+            .saturating_sub(sky_darken);
+        sky.max(self.get_brightness(LightLayer::Block, pos))
+    }
+
+    /// Gets the first free Y coordinate above the highest block of a column that counts
+    /// for `heightmap`.
+    ///
+    /// Matches vanilla's `Level.getHeight(Heightmap.Types, int, int)`. Unloaded columns
+    /// report the bottom of the world.
+    #[must_use]
+    pub fn get_height_at(&self, heightmap: HeightmapType, x: i32, z: i32) -> i32 {
+        let chunk_pos = ChunkPos::new(
+            SectionPos::block_to_section_coord(x),
+            SectionPos::block_to_section_coord(z),
+        );
+        self.chunk_map
+            .with_full_chunk(&chunk_pos, |chunk| {
+                chunk.as_full().map(|chunk| {
+                    chunk
+                        .heightmaps
+                        .read()
+                        .get(heightmap)
+                        .get_first_available((x & 15) as usize, (z & 15) as usize)
+                })
+            })
+            .flatten()
+            .unwrap_or_else(|| self.get_min_y())
+    }
+
     /// Sets a block at the given position.
     ///
     /// Returns `true` if the block was successfully set, `false` otherwise.
@@ -332,6 +523,8 @@ impl World {
         // Record the block change for broadcasting to clients
         log::debug!("Block changed at {pos:?}: {old_state:?} -> {block_state:?}");
         self.chunk_map.block_changed(&pos);
+        self.portal_exits.block_changed(old_state, block_state);
+        self.schedule_waterlogged_tick(pos, block_state);
 
         // Neighbor updates (when UPDATE_NEIGHBORS is set)
         if flags.contains(UpdateFlags::UPDATE_NEIGHBORS) {
@@ -419,6 +612,7 @@ impl World {
             neighbor_pos,
             neighbor_state,
         );
+        self.schedule_waterlogged_tick(pos, new_state);
 
         if new_state != current_state {
             log::debug!(
@@ -429,6 +623,17 @@ impl World {
         }
     }
 
+    /// Keeps the water in a waterlogged block flowing.
+    ///
+    /// Vanilla's `SimpleWaterloggedBlock`s schedule this tick themselves whenever they
+    /// are placed or their shape updates, so it is done here for every block.
+    fn schedule_waterlogged_tick(&self, pos: BlockPos, state: BlockStateId) {
+        if state.try_get_value(&BlockStateProperties::WATERLOGGED) == Some(true) {
+            let water = FlowingFluid::water();
+            self.schedule_fluid_tick(pos, &vanilla_fluids::WATER, water.tick_delay(self));
+        }
+    }
+
     /// Notifies a block that one of its neighbors changed.
     ///
     /// This is the Rust equivalent of vanilla's `Level.neighborChanged()`.
@@ -484,6 +689,15 @@ impl World {
     /// Returns timing information for the world tick.
     #[tracing::instrument(level = "trace", skip(self), name = "world_tick")]
     pub fn tick_b(&self, tick_count: u64, runs_normally: bool) -> WorldTickTimings {
+        // Weather and time stand still while the tick rate is frozen
+        if runs_normally {
+            self.tick_weather();
+            self.tick_time();
+            if tick_count.is_multiple_of(TIME_SYNC_INTERVAL) {
+                self.broadcast_time();
+            }
+        }
+
         let random_tick_speed = self.get_game_rule(RANDOM_TICK_SPEED).as_int().unwrap_or(3) as u32;
 
         let chunk_map_timings = self
@@ -501,105 +715,20 @@ impl World {
             start.elapsed()
         };
 
-        // Broadcast player latency updates periodically
-        if tick_count.is_multiple_of(SEND_PLAYER_INFO_INTERVAL) {
-            let _span = tracing::trace_span!("broadcast_latency").entered();
-            self.broadcast_player_latency_updates();
-        }
+        let entity_tick = {
+            let _span = tracing::trace_span!("entity_tick").entered();
+            let start = Instant::now();
+            self.tick_entities(runs_normally);
+            start.elapsed()
+        };
 
         WorldTickTimings {
             chunk_map: chunk_map_timings,
             player_tick,
+            entity_tick,
         }
     }
 
-    /// Broadcasts latency updates for all players to all players.
-    /// This is called every `SEND_PLAYER_INFO_INTERVAL` ticks to update the ping display.
-    fn broadcast_player_latency_updates(&self) {
-        // Collect all player latencies
-        let mut latency_entries = Vec::new();
-        self.players.iter_players(|uuid, player| {
-            latency_entries.push((*uuid, player.connection.latency()));
-            true
-        });
-
-        // Only broadcast if there are players
-        if !latency_entries.is_empty() {
-            let packet = CPlayerInfoUpdate::update_latency(latency_entries);
-            self.broadcast_to_all(packet);
-        }
-    }
-
-    /// Broadcasts a signed chat message to all players in the world.
-    ///
-    /// # Panics
-    /// Panics if `message_signature` is `None` after checking `is_some()` (should never happen).
-    pub fn broadcast_chat(
-        &self,
-        mut packet: CPlayerChat,
-        _sender: Arc<Player>,
-        sender_last_seen: LastSeen,
-        message_signature: Option<[u8; 256]>,
-    ) {
-        log::debug!(
-            "broadcast_chat: sender_last_seen has {} signatures, message_signature present: {}",
-            sender_last_seen.len(),
-            message_signature.is_some()
-        );
-
-        self.players.iter_players(|_, recipient| {
-            let messages_received = recipient.get_and_increment_messages_received();
-            packet.global_index = messages_received;
-
-            log::debug!(
-                "Broadcasting to player {} (UUID: {}), global_index={}",
-                recipient.gameprofile.name,
-                recipient.gameprofile.id,
-                messages_received
-            );
-
-            // IMPORTANT: Index previous messages BEFORE updating the cache
-            // This matches vanilla's order: pack() then push()
-            let previous_messages = {
-                let recipient_cache = recipient.signature_cache.lock();
-                recipient_cache.index_previous_messages(&sender_last_seen)
-            };
-
-            log::debug!(
-                "  Indexed {} previous messages for recipient",
-                previous_messages.len()
-            );
-
-            packet.previous_messages.clone_from(&previous_messages);
-
-            // Send the packet
-            recipient.connection.send_packet(packet.clone());
-
-            // AFTER sending, update the recipient's cache using vanilla's push algorithm
-            // This adds all lastSeen signatures + current signature to the cache
-            if let Some(signature) = message_signature {
-                recipient
-                    .signature_cache
-                    .lock()
-                    .push(&sender_last_seen, Some(&signature));
-
-                log::debug!("  Added signature to recipient's cache and pending list");
-
-                // Add to pending messages for acknowledgment tracking
-                recipient
-                    .message_validator
-                    .lock()
-                    .add_pending(Some(Box::new(signature) as Box<[u8]>));
-            } else {
-                // Even unsigned messages update the pending tracker
-                recipient.message_validator.lock().add_pending(None);
-                log::debug!("  Added unsigned message to pending list");
-            }
-
-            true
-        });
-    }
-
     /// Broadcasts a system chat message to all players.
     pub fn broadcast_system_chat(&self, packet: CSystemChat) {
         self.broadcast_to_all(packet);
@@ -645,24 +774,6 @@ impl World {
         });
     }
 
-    /// Broadcasts an unsigned player chat message to all players.
-    pub fn broadcast_unsigned_chat(
-        &self,
-        mut packet: CPlayerChat,
-        sender_name: &str,
-        message: &str,
-    ) {
-        log::info!("<{sender_name}> {message}");
-
-        self.players.iter_players(|_, recipient| {
-            let messages_received = recipient.get_and_increment_messages_received();
-            packet.global_index = messages_received;
-
-            recipient.connection.send_packet(packet.clone());
-            true
-        });
-    }
-
     /// Broadcasts a packet to all players tracking the given chunk.
     ///
     /// This method handles encoding the packet internally, avoiding boilerplate at call sites.
@@ -774,18 +885,101 @@ impl World {
     /// # Arguments
     /// * `pos` - The block position to drop the item at
     /// * `item` - The item stack to drop
-    pub fn drop_item_stack(&self, pos: BlockPos, item: ItemStack) {
+    pub fn drop_item_stack(&self, pos: BlockPos, mut item: ItemStack) {
+        const SIZE: f64 = 0.25;
+        const SPREAD: f64 = 1.0 - SIZE;
+
+        let x = f64::from(pos.x()) + rand::random::<f64>() * SPREAD + SIZE / 2.0;
+        let y = f64::from(pos.y()) + rand::random::<f64>() * SPREAD;
+        let z = f64::from(pos.z()) + rand::random::<f64>() * SPREAD + SIZE / 2.0;
+
+        while !item.is_empty() {
+            let count = rand::random_range(10..=30).min(item.count());
+            let mut split = item.clone();
+            split.set_count(count);
+            item.shrink(count);
+
+            let velocity = Vector3::new(
+                triangle(0.0, 0.114_850_001_711_398_36),
+                triangle(0.2, 0.114_850_001_711_398_36),
+                triangle(0.0, 0.114_850_001_711_398_36),
+            );
+            self.add_item_entity(ItemEntity::new(Vector3::new(x, y, z), split, velocity));
+        }
+    }
+
+    /// Pops a block drop out of the given block position.
+    ///
+    /// Based on Java's `Block.popResource`.
+    pub fn pop_resource(&self, pos: BlockPos, item: ItemStack) {
         if item.is_empty() {
             return;
         }
-        // TODO: Spawn ItemEntity when entity system is implemented
-        // For now, items are lost when containers are broken
-        log::debug!(
-            "Would drop item at {:?}: {:?} x{}",
-            pos,
-            item.item().key,
-            item.count()
-        );
+        let half_item_height = f64::from(vanilla_entities::ITEM.dimensions.height) / 2.0;
+        let x = f64::from(pos.x()) + 0.5 + rand::random_range(-0.25..0.25);
+        let y = f64::from(pos.y()) + 0.5 + rand::random_range(-0.25..0.25) - half_item_height;
+        let z = f64::from(pos.z()) + 0.5 + rand::random_range(-0.25..0.25);
+
+        let entity = ItemEntity::with_random_velocity(Vector3::new(x, y, z), item);
+        entity.set_default_pickup_delay();
+        self.add_item_entity(entity);
+    }
+
+    /// Rolls the loot table of a removed block, pops the items out of its position and
+    /// lets the block spawn its experience.
+    ///
+    /// Based on Java's `Block.dropResources` with the loot parameters from
+    /// `BlockBehaviour.getDrops`. `tool` is empty when no player broke the block.
+    pub fn drop_resources(
+        &self,
+        pos: BlockPos,
+        state: BlockStateId,
+        block_entity: Option<&BlockEntitySnapshot>,
+        tool: &ItemStack,
+    ) {
+        if self.get_game_rule(BLOCK_DROPS) == GameRuleValue::Bool(true)
+            && let Some(table) = get_block_loot_table(state.get_block())
+        {
+            self.drop_loot(table, pos, state, block_entity, tool);
+        }
+        BLOCK_BEHAVIORS
+            .get_behavior(state.get_block())
+            .spawn_after_break(state, self, pos, tool, true);
+    }
+
+    fn drop_loot(
+        &self,
+        table: LootTableRef,
+        pos: BlockPos,
+        state: BlockStateId,
+        block_entity: Option<&BlockEntitySnapshot>,
+        tool: &ItemStack,
+    ) {
+        let mut rng = rand::rng();
+        let mut ctx = LootContext::new(&mut rng)
+            .with_block_state(state)
+            .with_tool(tool)
+            .with_origin(
+                f64::from(pos.x()) + 0.5,
+                f64::from(pos.y()) + 0.5,
+                f64::from(pos.z()) + 0.5,
+            )
+            .with_game_time(self.game_time())
+            .with_weather(WeatherState {
+                raining: self.is_raining(),
+                thundering: self.is_thundering(),
+            });
+        if let Some(block_entity) = block_entity {
+            ctx = ctx.with_block_entity(BlockEntityRef {
+                block_entity_type: Some(&block_entity.block_entity_type.key),
+                custom_name: block_entity.custom_name.as_ref(),
+                inventory: block_entity.inventory.as_deref(),
+            });
+        }
+
+        for item in table.get_random_items(&mut ctx) {
+            self.pop_resource(pos, item);
+        }
     }
 
     /// Broadcasts a level event to nearby players within 64 blocks.
@@ -1019,3 +1213,22 @@ impl World {
         self.play_sound(sound_id, SoundSource::Blocks, pos, volume, pitch, exclude);
     }
 }
+
+/// Gets the loot table a block drops from.
+fn get_block_loot_table(block: BlockRef) -> Option<LootTableRef> {
+    let path = DROPS_LIKE
+        .iter()
+        .find(|(wall, _)| {
+            block.key.namespace == Identifier::VANILLA_NAMESPACE && *wall == block.key.path
+        })
+        .map_or(&*block.key.path, |(_, standing)| *standing);
+    let key = Identifier::new(block.key.namespace.clone(), format!("blocks/{path}"));
+    REGISTRY.loot_tables.by_key(&key)
+}
+
+/// Returns a random value in `min - max..min + max`, biased towards `min`.
+///
+/// Matches vanilla's `RandomSource.triangle()`.
+fn triangle(min: f64, max: f64) -> f64 {
+    min + max * (rand::random::<f64>() - rand::random::<f64>())
+}
@@ -0,0 +1,590 @@
+//! Nether and end portals: how long a player has to stand in one and where it takes them.
+
+use std::ptr;
+
+use rustc_hash::FxHashMap;
+use steel_registry::blocks::block_state_ext::BlockStateExt;
+use steel_registry::blocks::properties::{BlockStateProperties, Direction};
+use steel_registry::dimension_type::DimensionTypeRef;
+use steel_registry::vanilla_game_rules::{
+    PLAYERS_NETHER_PORTAL_CREATIVE_DELAY, PLAYERS_NETHER_PORTAL_DEFAULT_DELAY,
+};
+use steel_registry::{REGISTRY, vanilla_blocks, vanilla_dimension_types};
+use steel_utils::locks::SyncMutex;
+use steel_utils::math::{Axis, Vector3};
+use steel_utils::types::UpdateFlags;
+use steel_utils::{BlockPos, BlockStateId, ChunkPos, SectionPos};
+
+use crate::chunk::heightmap::HeightmapType;
+use crate::chunk::section::Sections;
+use crate::world::World;
+use crate::worldgen::biome_source::spiral_around;
+
+/// Ticks after a dimension change before a player can use a portal again.
+/// Matches vanilla's `Player.getDimensionChangingDelay()`.
+pub const PORTAL_COOLDOWN: i32 = 10;
+
+/// Where players arrive in the end, on top of an obsidian platform.
+/// Matches vanilla's `ServerLevel.END_SPAWN_POINT`.
+pub const END_SPAWN_POINT: BlockPos = BlockPos::new(100, 50, 0);
+
+/// How far from the scaled position the exit of a nether portal is searched for and built.
+///
+/// Vanilla finds existing portals up to 128 blocks away in the overworld through its point
+/// of interest index. Without one we scan the sections holding portal blocks, and only the
+/// chunks around the scaled position are loaded for that, so both directions use the
+/// nether radius.
+pub const NETHER_EXIT_RADIUS: i32 = 16;
+
+/// A kind of portal a player can stand in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Portal {
+    /// A nether portal, linking the overworld and the nether.
+    Nether,
+    /// An end portal, leading to the end and from there back to the overworld spawn.
+    End,
+}
+
+impl Portal {
+    /// Returns how many ticks a player has to stand in the portal before it takes them.
+    ///
+    /// Matches vanilla's `Portal.getPortalTransitionTime()`.
+    #[must_use]
+    pub fn transition_time(self, world: &World, invulnerable: bool) -> i32 {
+        match self {
+            Self::Nether => {
+                let rule = if invulnerable {
+                    PLAYERS_NETHER_PORTAL_CREATIVE_DELAY
+                } else {
+                    PLAYERS_NETHER_PORTAL_DEFAULT_DELAY
+                };
+                world.get_game_rule(rule).as_int().unwrap_or(0).max(1)
+            }
+            Self::End => 0,
+        }
+    }
+
+    /// Returns the dimension this portal leads to from `from`.
+    #[must_use]
+    pub fn destination(self, from: DimensionTypeRef) -> DimensionTypeRef {
+        match self {
+            Self::Nether if from.key == vanilla_dimension_types::THE_NETHER.key => {
+                vanilla_dimension_types::OVERWORLD
+            }
+            Self::Nether => vanilla_dimension_types::THE_NETHER,
+            Self::End if from.key == vanilla_dimension_types::THE_END.key => {
+                vanilla_dimension_types::OVERWORLD
+            }
+            Self::End => vanilla_dimension_types::THE_END,
+        }
+    }
+
+    /// Plans a trip from `from` to `to` for a player at `position` who stood in the portal
+    /// block at `entry`.
+    #[must_use]
+    pub fn plan_travel(
+        self,
+        from: &World,
+        to: &World,
+        entry: BlockPos,
+        position: Vector3<f64>,
+    ) -> PortalTravel {
+        let origin = match self {
+            Self::Nether => {
+                let scale = from.dimension.coordinate_scale / to.dimension.coordinate_scale;
+                BlockPos::new(
+                    (position.x * scale).floor() as i32,
+                    position.y.floor() as i32,
+                    (position.z * scale).floor() as i32,
+                )
+            }
+            Self::End if to.dimension.key == vanilla_dimension_types::THE_END.key => {
+                END_SPAWN_POINT
+            }
+            Self::End => to.level_data.read().data().spawn_pos(),
+        };
+        let axis = from
+            .get_block_state(&entry)
+            .try_get_value(&BlockStateProperties::HORIZONTAL_AXIS)
+            .unwrap_or(Axis::X);
+
+        PortalTravel {
+            portal: self,
+            origin,
+            axis,
+        }
+    }
+}
+
+/// A planned trip through a portal, waiting for the chunks around its exit to load.
+#[derive(Debug, Clone, Copy)]
+pub struct PortalTravel {
+    /// The portal the player went through.
+    pub portal: Portal,
+    /// The position in the target world around which the exit is found or built.
+    pub origin: BlockPos,
+    /// The axis of the entry portal, used for a new exit portal.
+    axis: Axis,
+}
+
+impl PortalTravel {
+    /// Finds or builds the exit in `to`, which must have the chunks around
+    /// [`Self::origin`] loaded, and returns where the player lands and their new yaw.
+    ///
+    /// Matches vanilla's `Portal.getPortalDestination()` of both portal blocks.
+    #[must_use]
+    pub fn create_exit(&self, to: &World, yaw: f32) -> (Vector3<f64>, f32) {
+        match self.portal {
+            Portal::End if to.dimension.key == vanilla_dimension_types::THE_END.key => {
+                let platform = END_SPAWN_POINT.offset(0, -1, 0);
+                create_end_platform(to, platform);
+                (bottom_center(platform), Direction::West.to_yaw())
+            }
+            Portal::End => {
+                let spawn = self.origin;
+                let y =
+                    to.get_height_at(HeightmapType::MotionBlockingNoLeaves, spawn.x(), spawn.z());
+                let angle = to.level_data.read().data().spawn.angle;
+                (bottom_center(BlockPos::new(spawn.x(), y, spawn.z())), angle)
+            }
+            Portal::Nether => {
+                if let Some(portal) = to.portal_exits.find(to, self.origin) {
+                    return (bottom_center(portal), yaw);
+                }
+                let direction = positive_direction(self.axis);
+                let corner = create_nether_portal(to, self.origin, self.axis);
+                let (step_x, _, step_z) = direction.offset();
+                let position = Vector3::new(
+                    f64::from(corner.x()) + 0.5 + f64::from(step_x) * 0.5,
+                    f64::from(corner.y()),
+                    f64::from(corner.z()) + 0.5 + f64::from(step_z) * 0.5,
+                );
+                (position, yaw)
+            }
+        }
+    }
+}
+
+/// How long a player has been standing in a portal.
+///
+/// Matches vanilla's `PortalProcessor`: the time builds up while the player stays inside
+/// and decays four times as fast once they leave.
+#[derive(Debug, Clone)]
+pub struct PortalProcess {
+    portal: Portal,
+    entry_position: BlockPos,
+    portal_time: i32,
+    inside_portal_this_tick: bool,
+}
+
+impl PortalProcess {
+    /// Starts tracking a player that just stepped into `portal` at `entry_position`.
+    #[must_use]
+    pub const fn new(portal: Portal, entry_position: BlockPos) -> Self {
+        Self {
+            portal,
+            entry_position,
+            portal_time: 0,
+            inside_portal_this_tick: true,
+        }
+    }
+
+    /// Returns the portal the player is standing in.
+    #[must_use]
+    pub const fn portal(&self) -> Portal {
+        self.portal
+    }
+
+    /// Returns the portal block the player last stood in.
+    #[must_use]
+    pub const fn entry_position(&self) -> BlockPos {
+        self.entry_position
+    }
+
+    /// Returns whether this tracks a stay in `portal`.
+    #[must_use]
+    pub fn is_same_portal(&self, portal: Portal) -> bool {
+        self.portal == portal
+    }
+
+    /// Records that the player is still inside the portal, at `entry_position`.
+    pub const fn update_entry_position(&mut self, entry_position: BlockPos) {
+        self.entry_position = entry_position;
+        self.inside_portal_this_tick = true;
+    }
+
+    /// Advances the stay by one tick and returns whether the player has been inside for
+    /// `transition_time` ticks and may travel.
+    pub fn process_portal_teleportation(&mut self, transition_time: i32, allowed: bool) -> bool {
+        if !self.inside_portal_this_tick {
+            self.portal_time = (self.portal_time - 4).max(0);
+            return false;
+        }
+        self.inside_portal_this_tick = false;
+        let ready = self.portal_time >= transition_time;
+        self.portal_time += 1;
+        allowed && ready
+    }
+
+    /// Returns whether the player has been out of the portal long enough to forget it.
+    #[must_use]
+    pub const fn has_expired(&self) -> bool {
+        self.portal_time <= 0
+    }
+}
+
+fn bottom_center(pos: BlockPos) -> Vector3<f64> {
+    Vector3::new(
+        f64::from(pos.x()) + 0.5,
+        f64::from(pos.y()),
+        f64::from(pos.z()) + 0.5,
+    )
+}
+
+/// The direction pointing along `axis` towards positive coordinates.
+const fn positive_direction(axis: Axis) -> Direction {
+    match axis {
+        Axis::Z => Direction::South,
+        Axis::X | Axis::Y => Direction::East,
+    }
+}
+
+/// Builds the 5x5 obsidian platform with three blocks of air above it that players
+/// arrive on in the end, like vanilla's `EndPlatformFeature.createEndPlatform()`.
+fn create_end_platform(world: &World, pos: BlockPos) {
+    let obsidian = REGISTRY
+        .blocks
+        .get_default_state_id(vanilla_blocks::OBSIDIAN);
+    let air = REGISTRY.blocks.get_default_state_id(vanilla_blocks::AIR);
+    for x in -2..=2 {
+        for z in -2..=2 {
+            for y in -1..3 {
+                let state = if y == -1 { obsidian } else { air };
+                let block_pos = pos.offset(x, y, z);
+                if world.get_block_state(&block_pos) != state {
+                    world.set_block(block_pos, state, UpdateFlags::UPDATE_ALL);
+                }
+            }
+        }
+    }
+}
+
+/// The nether portals found as exits, by the position they were searched around.
+///
+/// Cleared whenever a nether portal block is placed or removed, since that can change which
+/// portal is the closest.
+#[derive(Default)]
+pub(super) struct PortalExits(SyncMutex<FxHashMap<BlockPos, BlockPos>>);
+
+impl PortalExits {
+    /// Returns the bottom block of the nether portal closest to `origin`, searching the
+    /// world only if no earlier trip from `origin` found one.
+    fn find(&self, world: &World, origin: BlockPos) -> Option<BlockPos> {
+        if let Some(&portal) = self.0.lock().get(&origin) {
+            return Some(portal);
+        }
+        let portal = find_nether_portal(world, origin)?;
+        self.0.lock().insert(origin, portal);
+        Some(portal)
+    }
+
+    /// Forgets the found exits if a block change at any position added or removed a portal.
+    pub(super) fn block_changed(&self, old_state: BlockStateId, new_state: BlockStateId) {
+        if is_nether_portal(old_state) != is_nether_portal(new_state) {
+            self.0.lock().clear();
+        }
+    }
+}
+
+fn is_nether_portal(state: BlockStateId) -> bool {
+    ptr::eq(state.get_block(), vanilla_blocks::NETHER_PORTAL)
+}
+
+/// Finds the bottom block of the nether portal closest to `origin`, preferring lower
+/// portals at the same distance.
+fn find_nether_portal(world: &World, origin: BlockPos) -> Option<BlockPos> {
+    let max_y = world.get_min_y() + world.dimension.logical_height - 1;
+    let min = origin.offset(-NETHER_EXIT_RADIUS, 0, -NETHER_EXIT_RADIUS);
+    let max = origin.offset(NETHER_EXIT_RADIUS, 0, NETHER_EXIT_RADIUS);
+    let mut closest: Option<((i64, i32), BlockPos)> = None;
+    for chunk_x in
+        SectionPos::block_to_section_coord(min.x())..=SectionPos::block_to_section_coord(max.x())
+    {
+        for chunk_z in SectionPos::block_to_section_coord(min.z())
+            ..=SectionPos::block_to_section_coord(max.z())
+        {
+            let chunk_pos = ChunkPos::new(chunk_x, chunk_z);
+            world.chunk_map.with_full_chunk(&chunk_pos, |chunk| {
+                let sections = chunk.sections();
+                for_each_portal_bottom(sections, chunk_pos, world.get_min_y(), max_y, |pos| {
+                    if !(min.x()..=max.x()).contains(&pos.x())
+                        || !(min.z()..=max.z()).contains(&pos.z())
+                    {
+                        return;
+                    }
+                    let key = (distance_sqr(origin, pos), pos.y());
+                    if closest.is_none_or(|(closest, _)| key < closest) {
+                        closest = Some((key, pos));
+                    }
+                });
+            });
+        }
+    }
+    closest.map(|(_, pos)| pos)
+}
+
+/// Calls `f` with every nether portal block of a chunk's sections up to `max_y` that has no
+/// portal below it.
+///
+/// Sections whose palette holds no portal block are skipped, so only the few sections that
+/// contain a portal have their blocks scanned.
+fn for_each_portal_bottom(
+    sections: &Sections,
+    chunk_pos: ChunkPos,
+    min_y: i32,
+    max_y: i32,
+    mut f: impl FnMut(BlockPos),
+) {
+    // Whether the block below each column, indexed by `z * 16 + x`, is a portal
+    let mut below_is_portal = [false; 256];
+    for (index, section) in sections.sections.iter().enumerate() {
+        let section_y = min_y + index as i32 * 16;
+        let section = section.read();
+        if !section.states.maybe_has(is_nether_portal) {
+            below_is_portal = [false; 256];
+            continue;
+        }
+        for y in 0..16 {
+            let block_y = section_y + y as i32;
+            if block_y > max_y {
+                return;
+            }
+            for z in 0..16 {
+                for x in 0..16 {
+                    let is_portal = is_nether_portal(section.states.get(x, y, z));
+                    if is_portal && !below_is_portal[z * 16 + x] {
+                        f(BlockPos::new(
+                            chunk_pos.0.x * 16 + x as i32,
+                            block_y,
+                            chunk_pos.0.y * 16 + z as i32,
+                        ));
+                    }
+                    below_is_portal[z * 16 + x] = is_portal;
+                }
+            }
+        }
+    }
+}
+
+fn distance_sqr(a: BlockPos, b: BlockPos) -> i64 {
+    let dx = i64::from(a.x() - b.x());
+    let dy = i64::from(a.y() - b.y());
+    let dz = i64::from(a.z() - b.z());
+    dx * dx + dy * dy + dz * dz
+}
+
+fn can_portal_replace_block(world: &World, pos: &BlockPos) -> bool {
+    let state = world.get_block_state(pos);
+    state.get_block().config.replaceable && state.get_fluid_state().is_empty()
+}
+
+/// Checks that a 4x5 frame along `direction`, shifted `offset` blocks sideways, has solid
+/// ground below and replaceable blocks where the frame and portal go.
+fn can_host_frame(world: &World, origin: BlockPos, direction: Direction, offset: i32) -> bool {
+    let (step_x, _, step_z) = direction.offset();
+    let (side_x, _, side_z) = direction.rotate_y_clockwise().offset();
+    for i in -1..3 {
+        for j in -1..4 {
+            let pos = origin.offset(
+                step_x * i + side_x * offset,
+                j,
+                step_z * i + side_z * offset,
+            );
+            if j < 0 && !world.get_block_state(&pos).is_solid() {
+                return false;
+            }
+            if j >= 0 && !can_portal_replace_block(world, &pos) {
+                return false;
+            }
+        }
+    }
+    true
+}
+
+/// Builds a nether portal near `origin` and returns its bottom corner.
+///
+/// Matches vanilla's `PortalForcer.createPortal()`: the closest spot with room for the frame
+/// is used, and without one the portal floats on a small obsidian platform.
+fn create_nether_portal(world: &World, origin: BlockPos, axis: Axis) -> BlockPos {
+    let direction = positive_direction(axis);
+    let (step_x, _, step_z) = direction.offset();
+    let min_y = world.get_min_y();
+    let max_placeable_y = world
+        .get_max_y()
+        .min(min_y + world.dimension.logical_height - 1);
+
+    let mut closest: Option<(i64, BlockPos)> = None;
+    let mut closest_alternative: Option<(i64, BlockPos)> = None;
+    for (offset_x, offset_z) in spiral_around(NETHER_EXIT_RADIUS) {
+        let x = origin.x() + offset_x;
+        let z = origin.z() + offset_z;
+        let height = max_placeable_y.min(world.get_height_at(HeightmapType::MotionBlocking, x, z));
+        let mut y = height;
+        while y >= min_y {
+            if can_portal_replace_block(world, &BlockPos::new(x, y, z)) {
+                let first_empty_y = y;
+                while y > min_y && can_portal_replace_block(world, &BlockPos::new(x, y - 1, z)) {
+                    y -= 1;
+                }
+                let delta_y = first_empty_y - y;
+                if y + 4 <= max_placeable_y && (delta_y <= 0 || delta_y >= 3) {
+                    let pos = BlockPos::new(x, y, z);
+                    if can_host_frame(world, pos, direction, 0) {
+                        let distance = distance_sqr(origin, pos);
+                        if can_host_frame(world, pos, direction, -1)
+                            && can_host_frame(world, pos, direction, 1)
+                            && closest.is_none_or(|(closest, _)| closest > distance)
+                        {
+                            closest = Some((distance, pos));
+                        }
+                        if closest.is_none()
+                            && closest_alternative.is_none_or(|(closest, _)| closest > distance)
+                        {
+                            closest_alternative = Some((distance, pos));
+                        }
+                    }
+                }
+            }
+            y -= 1;
+        }
+    }
+
+    let obsidian = REGISTRY
+        .blocks
+        .get_default_state_id(vanilla_blocks::OBSIDIAN);
+    let corner = if let Some((_, pos)) = closest.or(closest_alternative) {
+        pos
+    } else {
+        let min_portal_y = (min_y + 1).max(70);
+        let max_portal_y = (max_placeable_y - 9).max(min_portal_y);
+        let corner = BlockPos::new(
+            origin.x() - step_x,
+            origin.y().clamp(min_portal_y, max_portal_y),
+            origin.z() - step_z,
+        );
+        let air = REGISTRY.blocks.get_default_state_id(vanilla_blocks::AIR);
+        let (side_x, _, side_z) = direction.rotate_y_clockwise().offset();
+        for i in -1..2 {
+            for j in 0..2 {
+                for k in -1..3 {
+                    let state = if k < 0 { obsidian } else { air };
+                    let pos = corner.offset(j * step_x + i * side_x, k, j * step_z + i * side_z);
+                    world.set_block(pos, state, UpdateFlags::UPDATE_ALL);
+                }
+            }
+        }
+        corner
+    };
+
+    for i in -1..3 {
+        for y in -1..4 {
+            if i == -1 || i == 2 || y == -1 || y == 3 {
+                let pos = corner.offset(i * step_x, y, i * step_z);
+                world.set_block(pos, obsidian, UpdateFlags::UPDATE_ALL);
+            }
+        }
+    }
+
+    let portal = REGISTRY
+        .blocks
+        .get_default_state_id(vanilla_blocks::NETHER_PORTAL)
+        .set_value(&BlockStateProperties::HORIZONTAL_AXIS, axis);
+    for i in 0..2 {
+        for y in 0..3 {
+            let pos = corner.offset(i * step_x, y, i * step_z);
+            world.set_block(
+                pos,
+                portal,
+                UpdateFlags::UPDATE_CLIENTS | UpdateFlags::UPDATE_KNOWN_SHAPE,
+            );
+        }
+    }
+
+    corner
+}
+
+#[cfg(test)]
+mod tests {
+    use steel_registry::{REGISTRY, vanilla_blocks};
+    use steel_utils::{BlockPos, ChunkPos};
+
+    use super::{Portal, PortalProcess, for_each_portal_bottom};
+    use crate::chunk::paletted_container::{BiomePalette, BlockPalette};
+    use crate::chunk::section::{ChunkSection, Sections};
+    use crate::test_utils::init_test_registry;
+
+    fn portal_bottoms(sections: &Sections, max_y: i32) -> Vec<BlockPos> {
+        let mut bottoms = Vec::new();
+        for_each_portal_bottom(sections, ChunkPos::new(1, -1), -16, max_y, |pos| {
+            bottoms.push(pos);
+        });
+        bottoms
+    }
+
+    #[test]
+    fn test_portal_bottoms_across_sections() {
+        init_test_registry();
+        let air = REGISTRY.blocks.get_base_state_id(vanilla_blocks::AIR);
+        let portal = REGISTRY
+            .blocks
+            .get_base_state_id(vanilla_blocks::NETHER_PORTAL);
+        let sections = Sections::from_owned(
+            (0..3)
+                .map(|_| {
+                    ChunkSection::new_with_biomes(
+                        BlockPalette::Homogeneous(air),
+                        BiomePalette::Homogeneous(0),
+                    )
+                })
+                .collect(),
+        );
+        // One portal reaching from the first section into the second, one in the third
+        for y in 14..=18 {
+            sections.set_relative_block(3, y, 5, portal);
+        }
+        for y in 40..=42 {
+            sections.set_relative_block(7, y, 2, portal);
+        }
+
+        assert_eq!(
+            portal_bottoms(&sections, 31),
+            [BlockPos::new(19, -2, -11), BlockPos::new(23, 24, -14)]
+        );
+        assert_eq!(portal_bottoms(&sections, 23), [BlockPos::new(19, -2, -11)]);
+    }
+
+    #[test]
+    fn test_portal_process_waits_for_transition_time() {
+        let mut process = PortalProcess::new(Portal::Nether, BlockPos::new(0, 64, 0));
+        for _ in 0..3 {
+            assert!(!process.process_portal_teleportation(3, true));
+            process.update_entry_position(BlockPos::new(0, 64, 0));
+        }
+        assert!(process.process_portal_teleportation(3, true));
+
+        process.update_entry_position(BlockPos::new(0, 64, 0));
+        assert!(!process.process_portal_teleportation(0, false));
+    }
+
+    #[test]
+    fn test_portal_process_decays_outside() {
+        let mut process = PortalProcess::new(Portal::Nether, BlockPos::new(0, 64, 0));
+        assert!(!process.process_portal_teleportation(80, true));
+        process.update_entry_position(BlockPos::new(0, 64, 0));
+        assert!(!process.process_portal_teleportation(80, true));
+        assert!(!process.has_expired());
+
+        assert!(!process.process_portal_teleportation(80, true));
+        assert!(process.has_expired());
+    }
+}
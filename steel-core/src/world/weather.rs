@@ -0,0 +1,324 @@
+//! This module contains the world's weather cycle and the rain checks built on it.
+use std::ops::RangeInclusive;
+
+use steel_protocol::packets::game::{CGameEvent, GameEventType};
+use steel_registry::REGISTRY;
+use steel_registry::biome::{BiomeRef, Precipitation};
+use steel_registry::blocks::light::MAX_LIGHT_LEVEL;
+use steel_registry::game_rules::GameRuleValue;
+use steel_registry::vanilla_game_rules::ADVANCE_WEATHER;
+use steel_utils::{BlockPos, ChunkPos, SectionPos};
+
+use crate::chunk::heightmap::HeightmapType;
+use crate::level_data::WeatherState;
+use crate::lighting::LightLayer;
+use crate::player::Player;
+use crate::world::World;
+
+/// Ticks of clear weather before it starts raining.
+pub const RAIN_DELAY: RangeInclusive<i32> = 12_000..=180_000;
+/// Ticks a rain lasts.
+pub const RAIN_DURATION: RangeInclusive<i32> = 12_000..=24_000;
+/// Ticks before a thunderstorm starts.
+pub const THUNDER_DELAY: RangeInclusive<i32> = 12_000..=180_000;
+/// Ticks a thunderstorm lasts.
+pub const THUNDER_DURATION: RangeInclusive<i32> = 3_600..=15_600;
+
+/// How much the rain and thunder levels move towards the weather each tick.
+const LEVEL_STEP: f32 = 0.01;
+
+/// How strong the weather currently is, from 0 (clear) to 1 (full rain or thunder).
+///
+/// The levels aren't saved. They fade towards the saved weather state so that the
+/// sky darkens and clears gradually on the client.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct WeatherLevels {
+    /// The rain level.
+    pub rain: f32,
+    /// The thunder level.
+    pub thunder: f32,
+}
+
+impl WeatherLevels {
+    /// Creates levels that already match the saved weather, so a world that was saved
+    /// while raining doesn't fade into the rain again on load.
+    ///
+    /// Matches vanilla's `ServerLevel.prepareWeather()`.
+    #[must_use]
+    pub fn new(weather: &WeatherState) -> Self {
+        Self {
+            rain: if weather.raining { 1.0 } else { 0.0 },
+            thunder: if weather.thundering { 1.0 } else { 0.0 },
+        }
+    }
+
+    /// Returns whether it rains heavily enough to count as raining.
+    #[must_use]
+    pub fn is_raining(&self) -> bool {
+        self.rain > 0.2
+    }
+
+    /// Returns whether it storms heavily enough to count as thundering.
+    #[must_use]
+    pub fn is_thundering(&self) -> bool {
+        self.thunder * self.rain > 0.9
+    }
+
+    /// Moves the levels one step towards the weather state.
+    fn step(&mut self, weather: &WeatherState) {
+        self.rain = Self::step_level(self.rain, weather.raining);
+        self.thunder = Self::step_level(self.thunder, weather.thundering);
+    }
+
+    fn step_level(level: f32, rising: bool) -> f32 {
+        let step = if rising { LEVEL_STEP } else { -LEVEL_STEP };
+        (level + step).clamp(0.0, 1.0)
+    }
+}
+
+/// Counts down the weather timers by one tick, flipping rain and thunder when their
+/// timer runs out and rolling a new timer when one isn't set.
+///
+/// Matches the game rule controlled part of vanilla's `ServerLevel.advanceWeatherCycle()`.
+pub fn advance_weather_cycle(weather: &mut WeatherState) {
+    if weather.clear_weather_time > 0 {
+        // `/weather clear` holds off both rain and thunder until it runs out
+        weather.clear_weather_time -= 1;
+        weather.thunder_time = i32::from(!weather.thundering);
+        weather.rain_time = i32::from(!weather.raining);
+        weather.thundering = false;
+        weather.raining = false;
+        return;
+    }
+
+    if weather.thunder_time > 0 {
+        weather.thunder_time -= 1;
+        if weather.thunder_time == 0 {
+            weather.thundering = !weather.thundering;
+        }
+    } else if weather.thundering {
+        weather.thunder_time = rand::random_range(THUNDER_DURATION);
+    } else {
+        weather.thunder_time = rand::random_range(THUNDER_DELAY);
+    }
+
+    if weather.rain_time > 0 {
+        weather.rain_time -= 1;
+        if weather.rain_time == 0 {
+            weather.raining = !weather.raining;
+        }
+    } else if weather.raining {
+        weather.rain_time = rand::random_range(RAIN_DURATION);
+    } else {
+        weather.rain_time = rand::random_range(RAIN_DELAY);
+    }
+}
+
+impl World {
+    /// Returns whether it is raining in this world.
+    #[must_use]
+    pub fn is_raining(&self) -> bool {
+        self.weather_levels.lock().is_raining()
+    }
+
+    /// Returns whether it is thundering in this world.
+    #[must_use]
+    pub fn is_thundering(&self) -> bool {
+        self.dimension.has_skylight
+            && !self.dimension.has_ceiling
+            && self.weather_levels.lock().is_thundering()
+    }
+
+    /// Returns whether rain falls on `pos`.
+    ///
+    /// Rain needs an open sky above the position and a biome that is warm enough to rain
+    /// rather than snow there. Matches vanilla's `Level.isRainingAt()`.
+    #[must_use]
+    pub fn is_raining_at(&self, pos: BlockPos) -> bool {
+        if !self.is_raining()
+            || self.get_brightness(LightLayer::Sky, &pos) < MAX_LIGHT_LEVEL
+            || self.get_height_at(HeightmapType::MotionBlocking, pos.0.x, pos.0.z) > pos.0.y
+        {
+            return false;
+        }
+
+        let sea_level = self.chunk_map.world_gen_context.generator.sea_level();
+        self.get_noise_biome(pos)
+            .is_some_and(|biome| biome.get_precipitation_at(pos, sea_level) == Precipitation::Rain)
+    }
+
+    /// Gets the biome stored for the 4x4x4 cell containing `pos`, if its chunk is loaded.
+    fn get_noise_biome(&self, pos: BlockPos) -> Option<BiomeRef> {
+        if !self.is_in_valid_bounds(&pos) {
+            return None;
+        }
+        let chunk_pos = ChunkPos::new(
+            SectionPos::block_to_section_coord(pos.0.x),
+            SectionPos::block_to_section_coord(pos.0.z),
+        );
+        let section_index = SectionPos::block_to_section_coord(pos.0.y - self.get_min_y());
+        let id = self
+            .chunk_map
+            .with_full_chunk(&chunk_pos, |chunk| {
+                let section = chunk.sections().sections.get(section_index as usize)?;
+                let x = ((pos.0.x & 15) >> 2) as usize;
+                let y = ((pos.0.y & 15) >> 2) as usize;
+                let z = ((pos.0.z & 15) >> 2) as usize;
+                Some(section.read().biomes.get(x, y, z))
+            })
+            .flatten()?;
+        REGISTRY.biomes.by_id(usize::from(id))
+    }
+
+    /// Sets the weather timers, like `/weather` does.
+    ///
+    /// `clear_time` keeps the sky clear for that many ticks. Otherwise the weather stays
+    /// as given for `weather_time` ticks. Matches vanilla's `ServerLevel.setWeatherParameters()`.
+    pub fn set_weather_parameters(
+        &self,
+        clear_time: i32,
+        weather_time: i32,
+        raining: bool,
+        thundering: bool,
+    ) {
+        let mut level_data = self.level_data.write();
+        let weather = &mut level_data.data_mut().weather;
+        weather.clear_weather_time = clear_time;
+        weather.rain_time = weather_time;
+        weather.thunder_time = weather_time;
+        weather.raining = raining;
+        weather.thundering = thundering;
+    }
+
+    /// Advances the weather by one tick and sends any change in it to the players.
+    ///
+    /// Matches vanilla's `ServerLevel.advanceWeatherCycle()`.
+    #[allow(clippy::float_cmp)]
+    pub(super) fn tick_weather(&self) {
+        let mut levels = *self.weather_levels.lock();
+        let previous = levels;
+        let was_raining = levels.is_raining();
+
+        if self.dimension.has_skylight {
+            let advance_weather = self.get_game_rule(ADVANCE_WEATHER) == GameRuleValue::Bool(true);
+            let mut level_data = self.level_data.write();
+            if advance_weather {
+                advance_weather_cycle(&mut level_data.data_mut().weather);
+            }
+            levels.step(&level_data.data().weather);
+        }
+        *self.weather_levels.lock() = levels;
+
+        if levels.rain != previous.rain {
+            self.broadcast_to_all(Self::weather_event(
+                GameEventType::RainLevelChange,
+                levels.rain,
+            ));
+        }
+        if levels.thunder != previous.thunder {
+            self.broadcast_to_all(Self::weather_event(
+                GameEventType::ThunderLevelChange,
+                levels.thunder,
+            ));
+        }
+        if was_raining != levels.is_raining() {
+            let event = if was_raining {
+                GameEventType::StopRaining
+            } else {
+                GameEventType::StartRaining
+            };
+            self.broadcast_to_all(Self::weather_event(event, 0.0));
+            self.broadcast_to_all(Self::weather_event(
+                GameEventType::RainLevelChange,
+                levels.rain,
+            ));
+            self.broadcast_to_all(Self::weather_event(
+                GameEventType::ThunderLevelChange,
+                levels.thunder,
+            ));
+        }
+    }
+
+    /// Sends the current weather to a player entering the world.
+    ///
+    /// Matches the weather part of vanilla's `PlayerList.sendLevelInfo()`.
+    pub fn send_weather(&self, player: &Player) {
+        let levels = *self.weather_levels.lock();
+        if levels.is_raining() {
+            player
+                .connection
+                .send_packet(Self::weather_event(GameEventType::StartRaining, 0.0));
+            player.connection.send_packet(Self::weather_event(
+                GameEventType::RainLevelChange,
+                levels.rain,
+            ));
+            player.connection.send_packet(Self::weather_event(
+                GameEventType::ThunderLevelChange,
+                levels.thunder,
+            ));
+        }
+    }
+
+    const fn weather_event(event: GameEventType, data: f32) -> CGameEvent {
+        CGameEvent { event, data }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clear_weather_time_holds_off_rain() {
+        let mut weather = WeatherState {
+            raining: true,
+            rain_time: 500,
+            thundering: true,
+            thunder_time: 500,
+            clear_weather_time: 2,
+        };
+        advance_weather_cycle(&mut weather);
+        assert!(!weather.raining && !weather.thundering);
+        assert_eq!(weather.clear_weather_time, 1);
+        assert_eq!((weather.rain_time, weather.thunder_time), (0, 0));
+
+        advance_weather_cycle(&mut weather);
+        assert_eq!(weather.clear_weather_time, 0);
+        assert_eq!((weather.rain_time, weather.thunder_time), (1, 1));
+
+        // Once the clear spell is over, the one tick timers flip the weather right away
+        advance_weather_cycle(&mut weather);
+        assert!(weather.raining && weather.thundering);
+    }
+
+    #[test]
+    fn timers_roll_new_durations_when_unset() {
+        let mut weather = WeatherState {
+            raining: true,
+            ..WeatherState::default()
+        };
+        advance_weather_cycle(&mut weather);
+        assert!(RAIN_DURATION.contains(&weather.rain_time));
+        assert!(THUNDER_DELAY.contains(&weather.thunder_time));
+        assert!(weather.raining && !weather.thundering);
+    }
+
+    #[test]
+    #[allow(clippy::float_cmp)]
+    fn levels_fade_towards_the_weather() {
+        let raining = WeatherState {
+            raining: true,
+            ..WeatherState::default()
+        };
+        let mut levels = WeatherLevels::default();
+        for _ in 0..15 {
+            levels.step(&raining);
+        }
+        assert!(!levels.is_raining());
+        for _ in 0..100 {
+            levels.step(&raining);
+        }
+        assert!(levels.is_raining());
+        assert!(levels.rain <= 1.0 && levels.thunder == 0.0);
+    }
+}
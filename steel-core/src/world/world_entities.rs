@@ -1,31 +1,59 @@
 //! This module contains the implementation of the world's entity-related methods.
 use std::sync::Arc;
 
-use steel_protocol::packets::game::{
-    CAddEntity, CGameEvent, CPlayerInfoUpdate, CRemoveEntities, CRemovePlayerInfo, GameEventType,
-};
+use rustc_hash::FxHashSet;
+use steel_protocol::packets::game::{CAddEntity, CGameEvent, CRemoveEntities, GameEventType};
+use steel_registry::blocks::shapes::AABBd;
 use steel_registry::{REGISTRY, vanilla_entities};
+use steel_utils::ChunkPos;
 use tokio::time::Instant;
 
-use crate::{player::Player, world::World};
+use crate::{
+    chunk::chunk_access::ChunkAccess,
+    entity::{Entity, ItemEntity},
+    player::Player,
+    world::World,
+};
 
 impl World {
     /// Removes a player from the world.
-    pub async fn remove_player(self: &Arc<Self>, player: Arc<Player>) {
-        let uuid = player.gameprofile.id;
-        let entity_id = player.id;
-
-        if self.players.remove(&uuid).await.is_some() {
-            let start = Instant::now();
+    ///
+    /// Returns `false` if the player was not in this world.
+    pub async fn remove_player(self: &Arc<Self>, player: Arc<Player>) -> bool {
+        let start = Instant::now();
+        if !self.detach_player(&player).await {
+            return false;
+        }
+        player.cleanup();
+        log::info!(
+            "Player {} removed in {:?}",
+            player.gameprofile.id,
+            start.elapsed()
+        );
+        true
+    }
 
-            self.player_area_map.on_player_leave(&player);
-            self.broadcast_to_all(CRemoveEntities::single(entity_id));
-            self.broadcast_to_all(CRemovePlayerInfo::single(uuid));
+    /// Removes a player who moves to another world, keeping their connection and state.
+    ///
+    /// The tab list is server-wide, so the player stays in it and is only despawned.
+    pub async fn transfer_player_out(&self, player: &Arc<Player>) {
+        self.detach_player(player).await;
+    }
 
-            self.chunk_map.remove_player(&player);
-            player.cleanup();
-            log::info!("Player {uuid} removed in {:?}", start.elapsed());
+    /// Removes a player from the world's indexes and despawns them for everyone else.
+    ///
+    /// Returns `false` if the player was not in this world.
+    async fn detach_player(&self, player: &Arc<Player>) -> bool {
+        let uuid = player.gameprofile.id;
+        if self.players.remove(&uuid).await.is_none() {
+            return false;
         }
+
+        self.player_area_map.on_player_leave(player);
+        self.broadcast_to_all(CRemoveEntities::single(player.id));
+
+        self.chunk_map.remove_player(player);
+        true
     }
 
     /// Adds a player to the world.
@@ -35,42 +63,22 @@ impl World {
             return;
         }
 
+        // The client keeps its own clock and weather, it needs both right away
+        self.send_time(&player);
+        self.send_weather(&player);
+
         // Note: player_area_map.on_player_join is called in chunk_map.update_player_status
         // when the player's view is first computed
 
         let pos = *player.position.lock();
         let (yaw, pitch) = player.rotation.load();
 
-        // Send existing players to the new player (tab list + entity spawn)
+        // The server keeps the tab list, only the entities are spawned here
+        let player_type_id = *REGISTRY.entity_types.get_id(vanilla_entities::PLAYER) as i32;
         self.players.iter_players(|_, existing_player| {
             if existing_player.gameprofile.id != player.gameprofile.id {
-                // Add to tab list with full player info
-                let add_existing = CPlayerInfoUpdate::create_player_initializing(
-                    existing_player.gameprofile.id,
-                    existing_player.gameprofile.name.clone(),
-                    existing_player.gameprofile.properties.clone(),
-                    existing_player.game_mode.load().into(),
-                    existing_player.connection.latency(),
-                    None, // display_name
-                    true, // show_hat
-                );
-                player.connection.send_packet(add_existing);
-
-                // Send chat session if available
-                if let Some(session) = existing_player.chat_session()
-                    && let Ok(protocol_data) = session.as_data().to_protocol_data()
-                {
-                    let session_packet = CPlayerInfoUpdate::update_chat_session(
-                        existing_player.gameprofile.id,
-                        protocol_data,
-                    );
-                    player.connection.send_packet(session_packet);
-                }
-
-                // Spawn existing player entity for new player
                 let existing_pos = *existing_player.position.lock();
                 let (existing_yaw, existing_pitch) = existing_player.rotation.load();
-                let player_type_id = *REGISTRY.entity_types.get_id(vanilla_entities::PLAYER) as i32;
                 player.connection.send_packet(CAddEntity::player(
                     existing_player.id,
                     existing_player.gameprofile.id,
@@ -85,17 +93,7 @@ impl World {
             true
         });
 
-        // Broadcast new player to all existing players (tab list + entity spawn)
-        let player_info_packet = CPlayerInfoUpdate::create_player_initializing(
-            player.gameprofile.id,
-            player.gameprofile.name.clone(),
-            player.gameprofile.properties.clone(),
-            player.game_mode.load().into(),
-            player.connection.latency(),
-            None, // display_name
-            true, // show_hat
-        );
-        let player_type_id = *REGISTRY.entity_types.get_id(vanilla_entities::PLAYER) as i32;
+        // Spawn the new player for everyone else in the world
         let spawn_packet = CAddEntity::player(
             player.id,
             player.gameprofile.id,
@@ -108,7 +106,6 @@ impl World {
         );
 
         self.players.iter_players(|_, p| {
-            p.connection.send_packet(player_info_packet.clone());
             // Don't send spawn packet to self
             if p.gameprofile.id != player.gameprofile.id {
                 p.connection.send_packet(spawn_packet.clone());
@@ -126,4 +123,121 @@ impl World {
             data: player.game_mode.load().into(),
         });
     }
+
+    /// Adds a non-player entity to the world. It is sent to nearby players on the next tick.
+    ///
+    /// Returns `false` if an entity with the same ID or UUID is already in the world.
+    pub fn add_entity(&self, entity: Arc<dyn Entity>) -> bool {
+        self.entities.insert(entity)
+    }
+
+    /// Adds an item entity to the world and returns a handle to it.
+    pub fn add_item_entity(&self, entity: ItemEntity) -> Arc<ItemEntity> {
+        let entity = Arc::new(entity);
+        self.add_entity(entity.clone());
+        entity
+    }
+
+    /// Removes a non-player entity from the world, despawning it for every player tracking it.
+    pub fn remove_entity(&self, entity_id: i32) -> Option<Arc<dyn Entity>> {
+        let tracked = self.entities.remove(entity_id)?;
+        tracked.server_entity.lock().remove_all(entity_id, self);
+        self.mark_entity_chunk_unsaved(tracked.chunk_pos());
+        Some(tracked.entity.clone())
+    }
+
+    /// Returns all non-player entities in the given chunk column.
+    #[must_use]
+    pub fn get_entities_in_chunk(&self, chunk: ChunkPos) -> Vec<Arc<dyn Entity>> {
+        self.entities
+            .get_in_chunk(chunk)
+            .into_iter()
+            .map(|tracked| tracked.entity.clone())
+            .collect()
+    }
+
+    /// Removes entities whose chunk was saved and is unloading.
+    ///
+    /// Unlike [`World::remove_entity`], this does not mark the chunk unsaved
+    /// since the entities were just written with it.
+    pub fn unload_entities(&self, entity_ids: &[i32]) {
+        for &entity_id in entity_ids {
+            if let Some(tracked) = self.entities.remove(entity_id) {
+                tracked.server_entity.lock().remove_all(entity_id, self);
+            }
+        }
+    }
+
+    /// Marks a loaded chunk unsaved after an entity left it, so the chunk's
+    /// saved entity list doesn't keep a stale copy.
+    fn mark_entity_chunk_unsaved(&self, chunk: ChunkPos) {
+        self.chunk_map
+            .with_full_chunk(&chunk, ChunkAccess::mark_dirty);
+    }
+
+    /// Returns all non-player entities whose bounding box intersects the given box.
+    #[must_use]
+    pub fn get_entities_in(&self, aabb: &AABBd) -> Vec<Arc<dyn Entity>> {
+        self.entities.get_entities_in(aabb)
+    }
+
+    /// Returns all item entities whose bounding box intersects the given box.
+    #[must_use]
+    pub fn get_item_entities_in(&self, aabb: &AABBd) -> Vec<Arc<ItemEntity>> {
+        self.get_entities_in(aabb)
+            .into_iter()
+            .filter_map(|entity| entity.as_item_entity())
+            .collect()
+    }
+
+    /// Ticks all entities in ticking chunks, removes discarded ones and syncs
+    /// the rest to the players whose chunk view contains them.
+    ///
+    /// Based on vanilla's `ServerLevel.tick()` entity loop and `ChunkMap.tick()` tracker update.
+    pub(super) fn tick_entities(&self, runs_normally: bool) {
+        let mut entities = Vec::with_capacity(self.entities.len());
+        self.entities.iter_entities(|tracked| {
+            entities.push(tracked.clone());
+            true
+        });
+
+        for tracked in entities {
+            let entity = tracked.entity.as_ref();
+            if runs_normally
+                && !entity.is_removed()
+                && self.chunk_map.is_chunk_ticking(&tracked.chunk_pos())
+            {
+                entity.tick(self);
+            }
+
+            if entity.is_removed() {
+                self.remove_entity(entity.get_id());
+                continue;
+            }
+
+            let old_chunk = tracked.chunk_pos();
+            self.entities.update_section(&tracked);
+            if tracked.chunk_pos() != old_chunk {
+                self.mark_entity_chunk_unsaved(old_chunk);
+            }
+
+            let tracking_players: FxHashSet<i32> = self
+                .player_area_map
+                .get_tracking_players(tracked.chunk_pos())
+                .into_iter()
+                .collect();
+            let mut server_entity = tracked.server_entity.lock();
+            server_entity.update_players(entity, self, &tracking_players);
+            server_entity.send_changes(entity, self);
+        }
+    }
+
+    /// Calls [`Entity::player_touch`] on every entity touching the given box.
+    ///
+    /// Matches the entity touch loop in vanilla's `Player.aiStep()`.
+    pub fn touch_entities(&self, player: &Player, aabb: &AABBd) {
+        for entity in self.get_entities_in(aabb) {
+            entity.player_touch(self, player);
+        }
+    }
 }
@@ -0,0 +1,107 @@
+//! This module contains the implementation of the world's scheduled tick methods.
+use std::ptr;
+
+use steel_registry::blocks::BlockRef;
+use steel_registry::blocks::block_state_ext::BlockStateExt;
+use steel_registry::fluid::FluidRef;
+use steel_utils::BlockPos;
+
+use crate::{
+    behavior::{BLOCK_BEHAVIORS, FLUID_BEHAVIORS},
+    ticks::{ScheduledTick, TickPriority},
+    world::World,
+};
+
+impl World {
+    /// Schedules a tick for the block at `pos` in `delay` ticks, with normal priority.
+    ///
+    /// Does nothing if the chunk isn't loaded or the position already has a pending
+    /// tick for this block. Matches vanilla's `Level.scheduleTick()`.
+    pub fn schedule_block_tick(&self, pos: BlockPos, block: BlockRef, delay: u32) {
+        self.schedule_block_tick_with_priority(pos, block, delay, TickPriority::Normal);
+    }
+
+    /// Schedules a tick for the block at `pos` in `delay` ticks.
+    ///
+    /// Ticks due on the same game tick run in priority order.
+    pub fn schedule_block_tick_with_priority(
+        &self,
+        pos: BlockPos,
+        block: BlockRef,
+        delay: u32,
+        priority: TickPriority,
+    ) {
+        let chunk_pos = Self::chunk_pos_for_block(&pos);
+        self.chunk_map.with_full_chunk(&chunk_pos, |chunk| {
+            if let Some(chunk) = chunk.as_full() {
+                chunk.schedule_block_tick(&self.chunk_map.level_ticks, pos, block, delay, priority);
+            }
+        });
+    }
+
+    /// Schedules a tick for the fluid at `pos` in `delay` ticks, with normal priority.
+    ///
+    /// Does nothing if the chunk isn't loaded or the position already has a pending
+    /// tick for this fluid.
+    pub fn schedule_fluid_tick(&self, pos: BlockPos, fluid: FluidRef, delay: u32) {
+        let chunk_pos = Self::chunk_pos_for_block(&pos);
+        self.chunk_map.with_full_chunk(&chunk_pos, |chunk| {
+            if let Some(chunk) = chunk.as_full() {
+                chunk.schedule_fluid_tick(
+                    &self.chunk_map.level_ticks,
+                    pos,
+                    fluid,
+                    delay,
+                    TickPriority::Normal,
+                );
+            }
+        });
+    }
+
+    /// Returns whether `pos` has a pending tick for `block`.
+    #[must_use]
+    pub fn has_scheduled_block_tick(&self, pos: &BlockPos, block: BlockRef) -> bool {
+        let chunk_pos = Self::chunk_pos_for_block(pos);
+        self.chunk_map
+            .with_full_chunk(&chunk_pos, |chunk| {
+                chunk
+                    .as_full()
+                    .is_some_and(|chunk| chunk.block_ticks.lock().has_scheduled(*pos, block))
+            })
+            .unwrap_or(false)
+    }
+
+    /// Returns whether `pos` has a pending tick for `fluid`.
+    #[must_use]
+    pub fn has_scheduled_fluid_tick(&self, pos: &BlockPos, fluid: FluidRef) -> bool {
+        let chunk_pos = Self::chunk_pos_for_block(pos);
+        self.chunk_map
+            .with_full_chunk(&chunk_pos, |chunk| {
+                chunk
+                    .as_full()
+                    .is_some_and(|chunk| chunk.fluid_ticks.lock().has_scheduled(*pos, fluid))
+            })
+            .unwrap_or(false)
+    }
+
+    /// Runs a due block tick if the block is still there.
+    pub(crate) fn run_block_tick(&self, tick: &ScheduledTick<BlockRef>) {
+        let state = self.get_block_state(&tick.pos);
+        if ptr::eq(state.get_block(), tick.ty) {
+            BLOCK_BEHAVIORS
+                .get_behavior(tick.ty)
+                .tick(state, self, tick.pos);
+        }
+    }
+
+    /// Runs a due fluid tick if the fluid is still there.
+    pub(crate) fn run_fluid_tick(&self, tick: &ScheduledTick<FluidRef>) {
+        let state = self.get_block_state(&tick.pos);
+        let fluid_state = state.get_fluid_state();
+        if !fluid_state.is_empty() && ptr::eq(fluid_state.fluid(), tick.ty) {
+            FLUID_BEHAVIORS
+                .get_behavior(tick.ty)
+                .tick(self, tick.pos, state);
+        }
+    }
+}
@@ -0,0 +1,500 @@
+//! Aquifers, which decide what fills the empty space carved out by the noise.
+
+use steel_registry::blocks::block_state_ext::BlockStateExt;
+use steel_registry::{REGISTRY, vanilla_blocks};
+use steel_utils::math::interpolation::{clamped_map, map};
+use steel_utils::random::{PositionalRandom, Random, RandomSplitter};
+use steel_utils::{BlockPos, BlockStateId, ChunkPos};
+
+use crate::worldgen::noise_chunk::NoiseChunk;
+use crate::worldgen::random_state::CompiledRouter;
+
+/// The fluid level used when an aquifer has no fluid at all.
+const WAY_BELOW_MIN_Y: i32 = -32512;
+
+const X_SPACING: i32 = 16;
+const Y_SPACING: i32 = 12;
+const Z_SPACING: i32 = 16;
+const X_RANGE: i32 = 10;
+const Y_RANGE: i32 = 9;
+const Z_RANGE: i32 = 10;
+
+/// Where the surface is sampled around an aquifer center, in chunks.
+const SURFACE_SAMPLING_OFFSETS_IN_CHUNKS: [[i32; 2]; 13] = [
+    [0, 0],
+    [-2, -1],
+    [-1, -1],
+    [0, -1],
+    [1, -1],
+    [-3, 0],
+    [-2, 0],
+    [-1, 0],
+    [1, 0],
+    [-2, 1],
+    [-1, 1],
+    [0, 1],
+    [1, 1],
+];
+
+/// A fluid filling everything below a level.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FluidStatus {
+    /// The first y level that is no longer filled.
+    pub fluid_level: i32,
+    /// The block the fluid is made of.
+    pub fluid_type: BlockStateId,
+}
+
+impl FluidStatus {
+    /// The block at `y`, which is air above the fluid level.
+    #[must_use]
+    pub fn at(&self, y: i32) -> BlockStateId {
+        if y < self.fluid_level {
+            self.fluid_type
+        } else {
+            REGISTRY.blocks.get_default_state_id(vanilla_blocks::AIR)
+        }
+    }
+}
+
+/// The fluids a dimension is filled with regardless of aquifers: the sea and a lava layer.
+#[derive(Debug, Clone, Copy)]
+pub struct GlobalFluidPicker {
+    lava: FluidStatus,
+    default_fluid: FluidStatus,
+}
+
+impl GlobalFluidPicker {
+    /// Creates the picker vanilla uses for noise generators.
+    #[must_use]
+    pub fn new(sea_level: i32, default_fluid: BlockStateId) -> Self {
+        Self {
+            lava: FluidStatus {
+                fluid_level: -54,
+                fluid_type: REGISTRY.blocks.get_default_state_id(vanilla_blocks::LAVA),
+            },
+            default_fluid: FluidStatus {
+                fluid_level: sea_level,
+                fluid_type: default_fluid,
+            },
+        }
+    }
+
+    /// The global fluid at a position.
+    #[must_use]
+    pub fn compute_fluid(&self, _x: i32, y: i32, _z: i32) -> FluidStatus {
+        if y < self.lava.fluid_level.min(self.default_fluid.fluid_level) {
+            self.lava
+        } else {
+            self.default_fluid
+        }
+    }
+}
+
+/// Picks the block for positions where the final density is not positive.
+pub enum Aquifer<'a> {
+    /// Only the global fluids, used when a generator has aquifers turned off.
+    Disabled(GlobalFluidPicker),
+    /// Vanilla's noise based aquifers with their own fluid levels.
+    NoiseBased(Box<NoiseBasedAquifer<'a>>),
+}
+
+impl Aquifer<'_> {
+    /// Returns the block at `pos` for the given density, or `None` if it should be solid.
+    pub fn compute_substance(
+        &mut self,
+        noise_chunk: &mut NoiseChunk<'_>,
+        pos: BlockPos,
+        density: f64,
+    ) -> Option<BlockStateId> {
+        match self {
+            Self::Disabled(picker) => (density <= 0.0)
+                .then(|| picker.compute_fluid(pos.x(), pos.y(), pos.z()).at(pos.y())),
+            Self::NoiseBased(aquifer) => aquifer.compute_substance(noise_chunk, pos, density),
+        }
+    }
+}
+
+/// Aquifers placed on a grid of randomly offset centers, each with its own fluid level.
+pub struct NoiseBasedAquifer<'a> {
+    router: CompiledRouter,
+    positional_random: &'a RandomSplitter,
+    global_fluid_picker: GlobalFluidPicker,
+    lava: BlockStateId,
+    water: BlockStateId,
+    min_grid_x: i32,
+    min_grid_y: i32,
+    min_grid_z: i32,
+    grid_size_x: i32,
+    grid_size_z: i32,
+    aquifer_cache: Vec<Option<FluidStatus>>,
+    location_cache: Vec<Option<BlockPos>>,
+}
+
+impl<'a> NoiseBasedAquifer<'a> {
+    /// Creates the aquifer for one chunk, covering `min_block_y` and the `y_block_size` blocks
+    /// above it.
+    #[must_use]
+    pub fn new(
+        chunk_pos: ChunkPos,
+        router: CompiledRouter,
+        positional_random: &'a RandomSplitter,
+        min_block_y: i32,
+        y_block_size: i32,
+        global_fluid_picker: GlobalFluidPicker,
+    ) -> Self {
+        let min_block_x = chunk_pos.0.x * 16;
+        let min_block_z = chunk_pos.0.y * 16;
+
+        let min_grid_x = grid_x(min_block_x) - 1;
+        let grid_size_x = grid_x(min_block_x + 15) + 1 - min_grid_x + 1;
+        let min_grid_y = grid_y(min_block_y) - 1;
+        let grid_size_y = grid_y(min_block_y + y_block_size) + 1 - min_grid_y + 1;
+        let min_grid_z = grid_z(min_block_z) - 1;
+        let grid_size_z = grid_z(min_block_z + 15) + 1 - min_grid_z + 1;
+        let total = (grid_size_x * grid_size_y * grid_size_z) as usize;
+
+        Self {
+            router,
+            positional_random,
+            global_fluid_picker,
+            lava: REGISTRY.blocks.get_default_state_id(vanilla_blocks::LAVA),
+            water: REGISTRY.blocks.get_default_state_id(vanilla_blocks::WATER),
+            min_grid_x,
+            min_grid_y,
+            min_grid_z,
+            grid_size_x,
+            grid_size_z,
+            aquifer_cache: vec![None; total],
+            location_cache: vec![None; total],
+        }
+    }
+
+    fn index(&self, grid_x: i32, grid_y: i32, grid_z: i32) -> usize {
+        let x = grid_x - self.min_grid_x;
+        let y = grid_y - self.min_grid_y;
+        let z = grid_z - self.min_grid_z;
+        ((y * self.grid_size_z + z) * self.grid_size_x + x) as usize
+    }
+
+    fn location(&mut self, grid_x: i32, grid_y: i32, grid_z: i32) -> (usize, BlockPos) {
+        let index = self.index(grid_x, grid_y, grid_z);
+        if let Some(location) = self.location_cache[index] {
+            return (index, location);
+        }
+        let mut random = self.positional_random.at(grid_x, grid_y, grid_z);
+        let location = BlockPos::new(
+            grid_x * X_SPACING + random.next_i32_bounded(X_RANGE),
+            grid_y * Y_SPACING + random.next_i32_bounded(Y_RANGE),
+            grid_z * Z_SPACING + random.next_i32_bounded(Z_RANGE),
+        );
+        self.location_cache[index] = Some(location);
+        (index, location)
+    }
+
+    fn compute_substance(
+        &mut self,
+        noise_chunk: &mut NoiseChunk<'_>,
+        pos: BlockPos,
+        density: f64,
+    ) -> Option<BlockStateId> {
+        if density > 0.0 {
+            return None;
+        }
+        let (x, y, z) = (pos.x(), pos.y(), pos.z());
+        let global_fluid = self.global_fluid_picker.compute_fluid(x, y, z);
+        if global_fluid.at(y) == self.lava {
+            return Some(self.lava);
+        }
+
+        let anchor_x = grid_x(x - 5);
+        let anchor_y = grid_y(y + 1);
+        let anchor_z = grid_z(z - 5);
+        let mut closest = [(i32::MAX, 0_usize); 3];
+        for offset_x in 0..=1 {
+            for offset_y in -1..=1 {
+                for offset_z in 0..=1 {
+                    let (index, location) = self.location(
+                        anchor_x + offset_x,
+                        anchor_y + offset_y,
+                        anchor_z + offset_z,
+                    );
+                    let dx = location.x() - x;
+                    let dy = location.y() - y;
+                    let dz = location.z() - z;
+                    let distance = dx * dx + dy * dy + dz * dz;
+                    if closest[0].0 >= distance {
+                        closest = [(distance, index), closest[0], closest[1]];
+                    } else if closest[1].0 >= distance {
+                        closest = [closest[0], (distance, index), closest[1]];
+                    } else if closest[2].0 >= distance {
+                        closest[2] = (distance, index);
+                    }
+                }
+            }
+        }
+        let [
+            (distance_1, index_1),
+            (distance_2, index_2),
+            (distance_3, index_3),
+        ] = closest;
+
+        let status_1 = self.aquifer_status(noise_chunk, index_1);
+        let similarity_12 = similarity(distance_1, distance_2);
+        let fluid_state = status_1.at(y);
+        if similarity_12 <= 0.0 {
+            return Some(fluid_state);
+        }
+        if fluid_state == self.water
+            && self
+                .global_fluid_picker
+                .compute_fluid(x, y - 1, z)
+                .at(y - 1)
+                == self.lava
+        {
+            return Some(fluid_state);
+        }
+
+        let mut barrier = None;
+        let status_2 = self.aquifer_status(noise_chunk, index_2);
+        let barrier_12 = similarity_12
+            * self.calculate_pressure(noise_chunk, pos, &mut barrier, status_1, status_2);
+        if density + barrier_12 > 0.0 {
+            return None;
+        }
+
+        let status_3 = self.aquifer_status(noise_chunk, index_3);
+        let similarity_13 = similarity(distance_1, distance_3);
+        if similarity_13 > 0.0 {
+            let barrier_13 = similarity_12
+                * similarity_13
+                * self.calculate_pressure(noise_chunk, pos, &mut barrier, status_1, status_3);
+            if density + barrier_13 > 0.0 {
+                return None;
+            }
+        }
+        let similarity_23 = similarity(distance_2, distance_3);
+        if similarity_23 > 0.0 {
+            let barrier_23 = similarity_12
+                * similarity_23
+                * self.calculate_pressure(noise_chunk, pos, &mut barrier, status_2, status_3);
+            if density + barrier_23 > 0.0 {
+                return None;
+            }
+        }
+        Some(fluid_state)
+    }
+
+    fn aquifer_status(&mut self, noise_chunk: &mut NoiseChunk<'_>, index: usize) -> FluidStatus {
+        if let Some(status) = self.aquifer_cache[index] {
+            return status;
+        }
+        let location = self.location_cache[index].expect("located before its status");
+        let status = self.compute_fluid(noise_chunk, location.x(), location.y(), location.z());
+        self.aquifer_cache[index] = Some(status);
+        status
+    }
+
+    fn calculate_pressure(
+        &self,
+        noise_chunk: &mut NoiseChunk<'_>,
+        pos: BlockPos,
+        barrier: &mut Option<f64>,
+        status_1: FluidStatus,
+        status_2: FluidStatus,
+    ) -> f64 {
+        let y = pos.y();
+        let type_1 = status_1.at(y);
+        let type_2 = status_2.at(y);
+        if (type_1 == self.lava && type_2 == self.water)
+            || (type_1 == self.water && type_2 == self.lava)
+        {
+            return 2.0;
+        }
+
+        let fluid_y_diff = (status_1.fluid_level - status_2.fluid_level).abs();
+        if fluid_y_diff == 0 {
+            return 0.0;
+        }
+        let average_fluid_y = 0.5 * f64::from(status_1.fluid_level + status_2.fluid_level);
+        let above_average = f64::from(y) + 0.5 - average_fluid_y;
+        let base_value = f64::from(fluid_y_diff) / 2.0;
+        let towards_middle = base_value - above_average.abs();
+        let gradient = if above_average > 0.0 {
+            if towards_middle > 0.0 {
+                towards_middle / 1.5
+            } else {
+                towards_middle / 2.5
+            }
+        } else {
+            let center_point = 3.0 + towards_middle;
+            if center_point > 0.0 {
+                center_point / 3.0
+            } else {
+                center_point / 10.0
+            }
+        };
+
+        let noise_value = if (-2.0..=2.0).contains(&gradient) {
+            *barrier.get_or_insert_with(|| noise_chunk.sample(self.router.barrier_noise, pos))
+        } else {
+            0.0
+        };
+        2.0 * (noise_value + gradient)
+    }
+
+    fn compute_fluid(
+        &self,
+        noise_chunk: &mut NoiseChunk<'_>,
+        x: i32,
+        y: i32,
+        z: i32,
+    ) -> FluidStatus {
+        let global_fluid = self.global_fluid_picker.compute_fluid(x, y, z);
+        let mut lowest_surface = i32::MAX;
+        let top_of_cell = y + 12;
+        let bottom_of_cell = y - 12;
+        let mut center_under_global_fluid = false;
+
+        for [offset_x, offset_z] in SURFACE_SAMPLING_OFFSETS_IN_CHUNKS {
+            let sample_x = x + offset_x * 16;
+            let sample_z = z + offset_z * 16;
+            let surface = noise_chunk.preliminary_surface_level(sample_x, sample_z);
+            let adjusted_surface = surface + 8;
+            let start = offset_x == 0 && offset_z == 0;
+            if start && bottom_of_cell > adjusted_surface {
+                return global_fluid;
+            }
+            let pokes_above_surface = top_of_cell > adjusted_surface;
+            if pokes_above_surface || start {
+                let fluid_at_surface =
+                    self.global_fluid_picker
+                        .compute_fluid(sample_x, adjusted_surface, sample_z);
+                if !fluid_at_surface.at(adjusted_surface).is_air() {
+                    if start {
+                        center_under_global_fluid = true;
+                    }
+                    if pokes_above_surface {
+                        return fluid_at_surface;
+                    }
+                }
+            }
+            lowest_surface = lowest_surface.min(surface);
+        }
+
+        let fluid_level = self.compute_surface_level(
+            noise_chunk,
+            x,
+            y,
+            z,
+            global_fluid,
+            lowest_surface,
+            center_under_global_fluid,
+        );
+        FluidStatus {
+            fluid_level,
+            fluid_type: self.compute_fluid_type(noise_chunk, x, y, z, global_fluid, fluid_level),
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn compute_surface_level(
+        &self,
+        noise_chunk: &mut NoiseChunk<'_>,
+        x: i32,
+        y: i32,
+        z: i32,
+        global_fluid: FluidStatus,
+        lowest_surface: i32,
+        center_under_global_fluid: bool,
+    ) -> i32 {
+        let pos = BlockPos::new(x, y, z);
+        // Deep dark regions never get aquifers.
+        let deep_dark = noise_chunk.sample_point(self.router.erosion, pos) < f64::from(-0.225_f32)
+            && noise_chunk.sample_point(self.router.depth, pos) > f64::from(0.9_f32);
+        let (partially_flooded, fully_flooded) = if deep_dark {
+            (-1.0, -1.0)
+        } else {
+            let distance_below_surface = lowest_surface + 8 - y;
+            let floodedness_factor = if center_under_global_fluid {
+                clamped_map(f64::from(distance_below_surface), 0.0, 64.0, 1.0, 0.0)
+            } else {
+                0.0
+            };
+            let floodedness = noise_chunk
+                .sample_point(self.router.fluid_level_floodedness_noise, pos)
+                .clamp(-1.0, 1.0);
+            let fully_threshold = map(floodedness_factor, 1.0, 0.0, -0.3, 0.8);
+            let partially_threshold = map(floodedness_factor, 1.0, 0.0, -0.8, 0.4);
+            (
+                floodedness - partially_threshold,
+                floodedness - fully_threshold,
+            )
+        };
+
+        if fully_flooded > 0.0 {
+            global_fluid.fluid_level
+        } else if partially_flooded > 0.0 {
+            Self::randomized_surface_level(&self.router, noise_chunk, x, y, z, lowest_surface)
+        } else {
+            WAY_BELOW_MIN_Y
+        }
+    }
+
+    fn randomized_surface_level(
+        router: &CompiledRouter,
+        noise_chunk: &mut NoiseChunk<'_>,
+        x: i32,
+        y: i32,
+        z: i32,
+        lowest_surface: i32,
+    ) -> i32 {
+        let cell_x = x.div_euclid(16);
+        let cell_y = y.div_euclid(40);
+        let cell_z = z.div_euclid(16);
+        let cell_middle_y = cell_y * 40 + 20;
+        let spread = noise_chunk.sample_point(
+            router.fluid_level_spread_noise,
+            BlockPos::new(cell_x, cell_y, cell_z),
+        ) * 10.0;
+        let quantized = (spread / 3.0).floor() as i32 * 3;
+        lowest_surface.min(cell_middle_y + quantized)
+    }
+
+    fn compute_fluid_type(
+        &self,
+        noise_chunk: &mut NoiseChunk<'_>,
+        x: i32,
+        y: i32,
+        z: i32,
+        global_fluid: FluidStatus,
+        fluid_level: i32,
+    ) -> BlockStateId {
+        if fluid_level <= -10
+            && fluid_level != WAY_BELOW_MIN_Y
+            && global_fluid.fluid_type != self.lava
+        {
+            let cell = BlockPos::new(x.div_euclid(64), y.div_euclid(40), z.div_euclid(64));
+            if noise_chunk.sample_point(self.router.lava_noise, cell).abs() > 0.3 {
+                return self.lava;
+            }
+        }
+        global_fluid.fluid_type
+    }
+}
+
+fn grid_x(x: i32) -> i32 {
+    x.div_euclid(X_SPACING)
+}
+
+fn grid_y(y: i32) -> i32 {
+    y.div_euclid(Y_SPACING)
+}
+
+fn grid_z(z: i32) -> i32 {
+    z.div_euclid(Z_SPACING)
+}
+
+fn similarity(distance_1: i32, distance_2: i32) -> f64 {
+    1.0 - f64::from((distance_2 - distance_1).abs()) / 25.0
+}
@@ -0,0 +1,294 @@
+//! Biome sources, which decide the biome at every quart position of a world.
+
+use std::iter;
+use std::sync::LazyLock;
+
+use steel_registry::biome::{Biome, BiomeRef};
+use steel_registry::vanilla_biomes;
+use steel_utils::{BlockPos, SectionPos};
+
+use crate::worldgen::RandomState;
+use crate::worldgen::climate::{Parameter, ParameterList, ParameterPoint, TargetPoint};
+use crate::worldgen::density_function::{DensityCaches, SinglePointContext};
+use crate::worldgen::overworld_biome_builder::OverworldBiomeBuilder;
+
+/// Decides which biome is at a position, like vanilla's `BiomeSource`.
+pub enum BiomeSource {
+    /// The same biome everywhere.
+    Fixed(BiomeRef),
+    /// Picks the biome whose climate is closest to the sampled climate noises.
+    MultiNoise(ParameterList<BiomeRef>),
+    /// The central island surrounded by outer islands picked from the end island noise,
+    /// like vanilla's `TheEndBiomeSource`.
+    TheEnd,
+}
+
+/// The biomes [`BiomeSource::TheEnd`] can place.
+static END_BIOMES: [&LazyLock<Biome>; 5] = [
+    &vanilla_biomes::THE_END,
+    &vanilla_biomes::END_HIGHLANDS,
+    &vanilla_biomes::END_MIDLANDS,
+    &vanilla_biomes::SMALL_END_ISLANDS,
+    &vanilla_biomes::END_BARRENS,
+];
+
+impl BiomeSource {
+    /// The multi-noise source with vanilla's overworld preset.
+    #[must_use]
+    pub fn overworld() -> Self {
+        Self::MultiNoise(ParameterList::new(OverworldBiomeBuilder::new().build()))
+    }
+
+    /// The multi-noise source with vanilla's nether preset.
+    #[must_use]
+    pub fn nether() -> Self {
+        let biome = |biome: &LazyLock<Biome>, temperature, humidity, offset| {
+            let point = ParameterPoint::new(
+                Parameter::point(temperature),
+                Parameter::point(humidity),
+                Parameter::point(0.0),
+                Parameter::point(0.0),
+                Parameter::point(0.0),
+                Parameter::point(0.0),
+                offset,
+            );
+            (point, LazyLock::force(biome))
+        };
+        Self::MultiNoise(ParameterList::new(vec![
+            biome(&vanilla_biomes::NETHER_WASTES, 0.0, 0.0, 0.0),
+            biome(&vanilla_biomes::SOUL_SAND_VALLEY, 0.0, -0.5, 0.0),
+            biome(&vanilla_biomes::CRIMSON_FOREST, 0.4, 0.0, 0.0),
+            biome(&vanilla_biomes::WARPED_FOREST, 0.0, 0.5, 0.375),
+            biome(&vanilla_biomes::BASALT_DELTAS, -0.5, 0.0, 0.175),
+        ]))
+    }
+
+    /// Returns whether this source can ever place `biome`.
+    #[must_use]
+    pub fn can_generate(&self, biome: BiomeRef) -> bool {
+        match self {
+            Self::Fixed(fixed) => fixed.key == biome.key,
+            Self::MultiNoise(parameters) => parameters
+                .values()
+                .iter()
+                .any(|(_, value)| value.key == biome.key),
+            Self::TheEnd => END_BIOMES.iter().any(|end| end.key == biome.key),
+        }
+    }
+
+    /// Returns the biome at a quart position, sampling the climate through `caches`.
+    pub fn get_noise_biome<C: DensityCaches>(
+        &self,
+        random_state: &RandomState,
+        caches: &mut C,
+        quart_x: i32,
+        quart_y: i32,
+        quart_z: i32,
+    ) -> BiomeRef {
+        match self {
+            Self::Fixed(biome) => biome,
+            Self::MultiNoise(parameters) => parameters.find_value(TargetPoint::sample(
+                random_state,
+                caches,
+                quart_x,
+                quart_y,
+                quart_z,
+            )),
+            Self::TheEnd => Self::end_biome(random_state, quart_x, quart_y, quart_z),
+        }
+    }
+
+    /// Picks the end biome of a quart position from the erosion noise, which the end
+    /// noise router sets to the end island noise.
+    fn end_biome(random_state: &RandomState, quart_x: i32, quart_y: i32, quart_z: i32) -> BiomeRef {
+        let chunk_x = SectionPos::block_to_section_coord(quart_x << 2);
+        let chunk_z = SectionPos::block_to_section_coord(quart_z << 2);
+        if i64::from(chunk_x).pow(2) + i64::from(chunk_z).pow(2) <= 4096 {
+            return LazyLock::force(&vanilla_biomes::THE_END);
+        }
+
+        // Sampled in the middle of the chunk so every column of a chunk agrees.
+        let pos = BlockPos::new((chunk_x * 2 + 1) * 8, quart_y << 2, (chunk_z * 2 + 1) * 8);
+        let height =
+            random_state
+                .graph
+                .compute(random_state.router.erosion, pos, &mut SinglePointContext);
+        let biome = if height > 0.25 {
+            &vanilla_biomes::END_HIGHLANDS
+        } else if height >= -0.0625 {
+            &vanilla_biomes::END_MIDLANDS
+        } else if height < -0.218_75 {
+            &vanilla_biomes::SMALL_END_ISLANDS
+        } else {
+            &vanilla_biomes::END_BARRENS
+        };
+        LazyLock::force(biome)
+    }
+
+    /// Searches outwards from `origin` for the closest position whose biome matches
+    /// `predicate`, like vanilla's `findClosestBiome3d`.
+    ///
+    /// Columns are visited in a square spiral every `horizontal_step` blocks up to `radius`
+    /// blocks away, and each column is checked every `vertical_step` blocks between `min_y`
+    /// and `max_y`, starting at the height of `origin`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn find_closest_biome_3d(
+        &self,
+        random_state: &RandomState,
+        origin: BlockPos,
+        radius: i32,
+        horizontal_step: i32,
+        vertical_step: i32,
+        min_y: i32,
+        max_y: i32,
+        predicate: impl Fn(BiomeRef) -> bool,
+    ) -> Option<(BlockPos, BiomeRef)> {
+        let heights = out_from_origin(origin.y(), min_y + 1, max_y + 1, vertical_step);
+        for (offset_x, offset_z) in spiral_around(radius.div_euclid(horizontal_step)) {
+            let x = origin.x() + offset_x * horizontal_step;
+            let z = origin.z() + offset_z * horizontal_step;
+            for &y in &heights {
+                let biome = self.get_noise_biome(
+                    random_state,
+                    &mut SinglePointContext,
+                    x >> 2,
+                    y >> 2,
+                    z >> 2,
+                );
+                if predicate(biome) {
+                    return Some((BlockPos::new(x, y, z), biome));
+                }
+            }
+        }
+        None
+    }
+}
+
+/// Lists the values between `lower` and `upper` every `step`, alternating outwards from
+/// `origin`, like vanilla's `Mth.outFromOrigin`.
+fn out_from_origin(origin: i32, lower: i32, upper: i32, step: i32) -> Vec<i32> {
+    debug_assert!(lower <= upper && step >= 1);
+    let origin = origin.clamp(lower, upper);
+    let mut values = Vec::new();
+    let mut current = origin;
+    // The next value only leaves the range once both sides are exhausted.
+    while (lower..=upper).contains(&current) {
+        values.push(current);
+
+        let distance = (origin - current).abs();
+        let below = current <= origin;
+        let next_above = origin + distance + step;
+        let next_below = origin - distance - if below { step } else { 0 };
+        current = if (!below || next_above > upper) && next_below >= lower {
+            next_below
+        } else {
+            next_above
+        };
+    }
+    values
+}
+
+/// Yields the offsets of a square spiral around the origin, first going east and then
+/// turning south, like vanilla's `BlockPos.spiralAround`.
+pub(crate) fn spiral_around(size: i32) -> impl Iterator<Item = (i32, i32)> {
+    const DIRECTIONS: [(i32, i32); 4] = [(1, 0), (0, 1), (-1, 0), (0, -1)];
+    let legs = 4 * size;
+    let mut leg = -1;
+    let mut leg_size = 0;
+    let mut leg_index = 0;
+    // Starts one step south, so the first move north lands on the origin.
+    let mut cursor = (0, 1);
+    iter::from_fn(move || {
+        let (step_x, step_z) = DIRECTIONS[((leg + 4) % 4) as usize];
+        cursor = (cursor.0 + step_x, cursor.1 + step_z);
+        if leg_index >= leg_size {
+            if leg >= legs {
+                return None;
+            }
+            leg += 1;
+            leg_index = 0;
+            leg_size = leg / 2 + 1;
+        }
+        leg_index += 1;
+        Some(cursor)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use steel_registry::biome::BiomeRef;
+    use steel_registry::vanilla_biomes;
+
+    use super::{BiomeSource, out_from_origin, spiral_around};
+    use crate::worldgen::climate::{TargetPoint, quantize};
+
+    #[test]
+    fn test_spiral_starts_at_origin_and_turns_south() {
+        let offsets: Vec<_> = spiral_around(1).collect();
+        assert_eq!(
+            offsets,
+            [
+                (0, 0),
+                (1, 0),
+                (1, 1),
+                (0, 1),
+                (-1, 1),
+                (-1, 0),
+                (-1, -1),
+                (0, -1),
+                (1, -1),
+            ]
+        );
+        assert_eq!(spiral_around(3).count(), 7 * 7);
+    }
+
+    #[test]
+    fn test_out_from_origin_alternates_up_and_down() {
+        assert_eq!(
+            out_from_origin(64, -63, 320, 64),
+            [64, 128, 0, 192, 256, 320]
+        );
+        assert_eq!(out_from_origin(500, 0, 100, 40), [100, 60, 20]);
+    }
+
+    /// Climates picked from the ranges of vanilla's `OverworldBiomeBuilder`, each inside
+    /// exactly one biome's parameter box.
+    #[test]
+    fn test_overworld_matches_vanilla_biome_table() {
+        let BiomeSource::MultiNoise(parameters) = BiomeSource::overworld() else {
+            panic!("The overworld should use multi-noise biomes");
+        };
+        let biome = |temperature, humidity, continentalness, erosion, depth| -> BiomeRef {
+            *parameters.find_value(TargetPoint {
+                temperature: quantize(temperature),
+                humidity: quantize(humidity),
+                continentalness: quantize(continentalness),
+                erosion: quantize(erosion),
+                depth: quantize(depth),
+                weirdness: 0,
+            })
+        };
+
+        let cases = [
+            ((0.0, 0.0, -1.1, 0.0, 0.0), &vanilla_biomes::MUSHROOM_FIELDS),
+            (
+                (-0.8, 0.0, -0.8, 0.0, 0.0),
+                &vanilla_biomes::DEEP_FROZEN_OCEAN,
+            ),
+            ((0.0, 0.0, -0.8, 0.0, 0.0), &vanilla_biomes::DEEP_OCEAN),
+            ((0.8, 0.0, -0.8, 0.0, 0.0), &vanilla_biomes::WARM_OCEAN),
+            ((-0.3, 0.0, -0.3, 0.0, 0.0), &vanilla_biomes::COLD_OCEAN),
+            ((0.3, 0.0, -0.3, 0.0, 1.0), &vanilla_biomes::LUKEWARM_OCEAN),
+            ((0.0, 0.8, 0.0, 0.0, 0.5), &vanilla_biomes::LUSH_CAVES),
+            ((0.0, 0.0, 0.9, 0.0, 0.5), &vanilla_biomes::DRIPSTONE_CAVES),
+            ((0.0, 0.0, 0.2, -0.9, 1.1), &vanilla_biomes::DEEP_DARK),
+        ];
+        for ((temperature, humidity, continentalness, erosion, depth), expected) in cases {
+            let found = biome(temperature, humidity, continentalness, erosion, depth);
+            assert_eq!(
+                found.key, expected.key,
+                "climate {temperature}, {humidity}, {continentalness}, {erosion}, {depth}"
+            );
+        }
+    }
+}
@@ -0,0 +1,497 @@
+//! Climate parameters and the nearest-point search used by multi-noise biome sources.
+//!
+//! This mirrors vanilla's `Climate` class: noise values are quantized to `i64`, biomes cover
+//! boxes in the 7 dimensional parameter space and a lookup picks the biome whose box is
+//! closest to the sampled point.
+
+use std::cmp::Ordering;
+use std::mem;
+
+use steel_utils::BlockPos;
+
+use crate::worldgen::RandomState;
+use crate::worldgen::density_function::DensityCaches;
+
+/// The number of dimensions of the climate parameter space.
+const PARAMETER_COUNT: usize = 7;
+
+/// The maximum number of children of a single R-tree node.
+const CHILDREN_PER_NODE: usize = 6;
+
+/// Quantizes a noise value the same way vanilla does.
+#[must_use]
+pub fn quantize(value: f32) -> i64 {
+    (value * 10000.0) as i64
+}
+
+/// An inclusive range of quantized climate values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Parameter {
+    /// The lower bound.
+    pub min: i64,
+    /// The upper bound.
+    pub max: i64,
+}
+
+impl Parameter {
+    /// A range containing a single value.
+    #[must_use]
+    pub fn point(value: f32) -> Self {
+        Self::span(value, value)
+    }
+
+    /// A range from `min` to `max`.
+    #[must_use]
+    pub fn span(min: f32, max: f32) -> Self {
+        debug_assert!(min <= max, "min > max: {min} {max}");
+        Self {
+            min: quantize(min),
+            max: quantize(max),
+        }
+    }
+
+    /// The smallest range containing both `self` and `other`.
+    #[must_use]
+    pub fn union(self, other: Self) -> Self {
+        Self {
+            min: self.min.min(other.min),
+            max: self.max.max(other.max),
+        }
+    }
+
+    /// The distance from `value` to the closest value in the range.
+    #[must_use]
+    pub const fn distance(self, value: i64) -> i64 {
+        let above = value - self.max;
+        let below = self.min - value;
+        if above > 0 {
+            above
+        } else if below > 0 {
+            below
+        } else {
+            0
+        }
+    }
+
+    const fn center(self) -> i64 {
+        i64::midpoint(self.min, self.max)
+    }
+}
+
+/// The climate a biome occupies.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParameterPoint {
+    /// The temperature range.
+    pub temperature: Parameter,
+    /// The humidity (vegetation) range.
+    pub humidity: Parameter,
+    /// The continentalness range.
+    pub continentalness: Parameter,
+    /// The erosion range.
+    pub erosion: Parameter,
+    /// The depth range.
+    pub depth: Parameter,
+    /// The weirdness (ridges) range.
+    pub weirdness: Parameter,
+    /// A fixed penalty added to the distance of every lookup.
+    pub offset: i64,
+}
+
+impl ParameterPoint {
+    /// Creates a parameter point, quantizing `offset`.
+    #[must_use]
+    pub fn new(
+        temperature: Parameter,
+        humidity: Parameter,
+        continentalness: Parameter,
+        erosion: Parameter,
+        depth: Parameter,
+        weirdness: Parameter,
+        offset: f32,
+    ) -> Self {
+        Self {
+            temperature,
+            humidity,
+            continentalness,
+            erosion,
+            depth,
+            weirdness,
+            offset: quantize(offset),
+        }
+    }
+
+    fn parameter_space(&self) -> [Parameter; PARAMETER_COUNT] {
+        [
+            self.temperature,
+            self.humidity,
+            self.continentalness,
+            self.erosion,
+            self.depth,
+            self.weirdness,
+            Parameter {
+                min: self.offset,
+                max: self.offset,
+            },
+        ]
+    }
+}
+
+/// A quantized climate sample at a single position.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TargetPoint {
+    /// The temperature.
+    pub temperature: i64,
+    /// The humidity (vegetation).
+    pub humidity: i64,
+    /// The continentalness.
+    pub continentalness: i64,
+    /// The erosion.
+    pub erosion: i64,
+    /// The depth.
+    pub depth: i64,
+    /// The weirdness (ridges).
+    pub weirdness: i64,
+}
+
+impl TargetPoint {
+    /// Samples the climate noises of `random_state` at a quart position.
+    pub fn sample<C: DensityCaches>(
+        random_state: &RandomState,
+        caches: &mut C,
+        quart_x: i32,
+        quart_y: i32,
+        quart_z: i32,
+    ) -> Self {
+        let pos = BlockPos::new(quart_x << 2, quart_y << 2, quart_z << 2);
+        let router = &random_state.router;
+        let mut sample =
+            |node| quantize(random_state.graph.compute(node, pos, &mut *caches) as f32);
+        Self {
+            temperature: sample(router.temperature),
+            humidity: sample(router.vegetation),
+            continentalness: sample(router.continents),
+            erosion: sample(router.erosion),
+            depth: sample(router.depth),
+            weirdness: sample(router.ridges),
+        }
+    }
+
+    const fn to_parameter_array(self) -> [i64; PARAMETER_COUNT] {
+        [
+            self.temperature,
+            self.humidity,
+            self.continentalness,
+            self.erosion,
+            self.depth,
+            self.weirdness,
+            0,
+        ]
+    }
+}
+
+fn distance(space: &[Parameter; PARAMETER_COUNT], target: &[i64; PARAMETER_COUNT]) -> i64 {
+    space
+        .iter()
+        .zip(target)
+        .map(|(parameter, &value)| {
+            let distance = parameter.distance(value);
+            distance * distance
+        })
+        .sum()
+}
+
+fn union_space<'a>(nodes: impl IntoIterator<Item = &'a Node>) -> [Parameter; PARAMETER_COUNT] {
+    let mut nodes = nodes.into_iter();
+    let mut space = *nodes.next().expect("need at least one node").space();
+    for node in nodes {
+        for (parameter, other) in space.iter_mut().zip(node.space()) {
+            *parameter = parameter.union(*other);
+        }
+    }
+    space
+}
+
+/// A node of the R-tree. Leaves index into the values of the [`ParameterList`].
+enum Node {
+    Leaf {
+        space: [Parameter; PARAMETER_COUNT],
+        index: usize,
+    },
+    SubTree {
+        space: [Parameter; PARAMETER_COUNT],
+        children: Vec<Self>,
+    },
+}
+
+impl Node {
+    const fn space(&self) -> &[Parameter; PARAMETER_COUNT] {
+        match self {
+            Self::Leaf { space, .. } | Self::SubTree { space, .. } => space,
+        }
+    }
+
+    fn sub_tree(children: Vec<Self>) -> Self {
+        Self::SubTree {
+            space: union_space(&children),
+            children,
+        }
+    }
+
+    /// Builds the tree the same way vanilla does, so that ties are broken identically.
+    fn build(mut children: Vec<Self>) -> Self {
+        assert!(
+            !children.is_empty(),
+            "need at least one child to build a node"
+        );
+        if children.len() == 1 {
+            return children.pop().expect("checked above");
+        }
+        if children.len() <= CHILDREN_PER_NODE {
+            children.sort_by_key(|node| {
+                node.space()
+                    .iter()
+                    .map(|parameter| parameter.center().abs())
+                    .sum::<i64>()
+            });
+            return Self::sub_tree(children);
+        }
+
+        // Vanilla sorts the same list once per dimension, so ties keep the previous order.
+        let bucket_size = bucket_size(children.len());
+        let mut order: Vec<usize> = (0..children.len()).collect();
+        let mut best_cost = i64::MAX;
+        let mut best = (0, Vec::new());
+        for dimension in 0..PARAMETER_COUNT {
+            order.sort_by(|&a, &b| compare(&children[a], &children[b], dimension, false));
+            let cost = order
+                .chunks(bucket_size)
+                .map(|bucket| {
+                    let space = union_space(bucket.iter().map(|&index| &children[index]));
+                    space
+                        .iter()
+                        .map(|parameter| (parameter.max - parameter.min).abs())
+                        .sum::<i64>()
+                })
+                .sum::<i64>();
+            if best_cost > cost {
+                best_cost = cost;
+                best = (dimension, order.clone());
+            }
+        }
+
+        let (dimension, order) = best;
+        let mut children: Vec<Option<Self>> = children.into_iter().map(Some).collect();
+        let mut buckets: Vec<Vec<Self>> = order
+            .chunks(bucket_size)
+            .map(|bucket| {
+                bucket
+                    .iter()
+                    .map(|&index| children[index].take().expect("every node is used once"))
+                    .collect()
+            })
+            .collect();
+        let spaces: Vec<_> = buckets
+            .iter()
+            .map(|bucket| union_space(bucket.iter()))
+            .collect();
+        let mut bucket_order: Vec<usize> = (0..buckets.len()).collect();
+        bucket_order.sort_by(|&a, &b| compare_spaces(&spaces[a], &spaces[b], dimension, true));
+        Self::SubTree {
+            space: union_space(buckets.iter().flatten()),
+            children: bucket_order
+                .into_iter()
+                .map(|index| Self::build(mem::take(&mut buckets[index])))
+                .collect(),
+        }
+    }
+
+    /// Finds the closest leaf, only replacing `best` with strictly closer leaves.
+    fn search(&self, target: &[i64; PARAMETER_COUNT], best: Option<(usize, i64)>) -> (usize, i64) {
+        match self {
+            Self::Leaf { space, index } => (*index, distance(space, target)),
+            Self::SubTree { children, .. } => {
+                let mut best = best;
+                for child in children {
+                    let child_distance = distance(child.space(), target);
+                    if best.is_none_or(|(_, best_distance)| best_distance > child_distance) {
+                        let (index, leaf_distance) = child.search(target, best);
+                        if best.is_none_or(|(_, best_distance)| best_distance > leaf_distance) {
+                            best = Some((index, leaf_distance));
+                        }
+                    }
+                }
+                best.expect("sub trees always have children")
+            }
+        }
+    }
+}
+
+/// The bucket size vanilla uses when splitting `len` nodes.
+fn bucket_size(len: usize) -> usize {
+    let children = CHILDREN_PER_NODE as f64;
+    children.powf(((len as f64 - 0.01).ln() / children.ln()).floor()) as usize
+}
+
+/// Orders two nodes by the centers of their ranges, starting at `dimension`.
+fn compare(a: &Node, b: &Node, dimension: usize, absolute: bool) -> Ordering {
+    compare_spaces(a.space(), b.space(), dimension, absolute)
+}
+
+fn compare_spaces(
+    a: &[Parameter; PARAMETER_COUNT],
+    b: &[Parameter; PARAMETER_COUNT],
+    dimension: usize,
+    absolute: bool,
+) -> Ordering {
+    let key = |space: &[Parameter; PARAMETER_COUNT], offset: usize| {
+        let center = space[(dimension + offset) % PARAMETER_COUNT].center();
+        if absolute { center.abs() } else { center }
+    };
+    (0..PARAMETER_COUNT)
+        .map(|offset| key(a, offset).cmp(&key(b, offset)))
+        .find(|ordering| ordering.is_ne())
+        .unwrap_or(Ordering::Equal)
+}
+
+/// A list of values placed in the climate parameter space, like vanilla's
+/// `Climate.ParameterList`.
+pub struct ParameterList<T> {
+    values: Vec<(ParameterPoint, T)>,
+    root: Node,
+}
+
+impl<T> ParameterList<T> {
+    /// Builds the search tree for `values`.
+    ///
+    /// # Panics
+    /// Panics if `values` is empty.
+    #[must_use]
+    pub fn new(values: Vec<(ParameterPoint, T)>) -> Self {
+        let leaves = values
+            .iter()
+            .enumerate()
+            .map(|(index, (point, _))| Node::Leaf {
+                space: point.parameter_space(),
+                index,
+            })
+            .collect();
+        Self {
+            root: Node::build(leaves),
+            values,
+        }
+    }
+
+    /// The values and the climate they occupy.
+    #[must_use]
+    pub fn values(&self) -> &[(ParameterPoint, T)] {
+        &self.values
+    }
+
+    /// Finds the value closest to `target`.
+    ///
+    /// Vanilla seeds the search with the previous result of the same thread, which only
+    /// matters for exact ties. Searching from scratch keeps lookups deterministic instead.
+    #[must_use]
+    pub fn find_value(&self, target: TargetPoint) -> &T {
+        let (index, _) = self.root.search(&target.to_parameter_array(), None);
+        &self.values[index].1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use steel_utils::random::Random;
+    use steel_utils::random::xoroshiro::Xoroshiro;
+
+    use super::{Parameter, ParameterList, ParameterPoint, TargetPoint, distance};
+
+    fn random_parameter(random: &mut Xoroshiro) -> Parameter {
+        let a = random.next_f32() * 2.0 - 1.0;
+        let b = random.next_f32() * 2.0 - 1.0;
+        Parameter::span(a.min(b), a.max(b))
+    }
+
+    #[test]
+    fn test_search_finds_the_closest_point() {
+        let mut random = Xoroshiro::from_seed(7);
+        let values: Vec<_> = (0..500)
+            .map(|index| {
+                let point = ParameterPoint::new(
+                    random_parameter(&mut random),
+                    random_parameter(&mut random),
+                    random_parameter(&mut random),
+                    random_parameter(&mut random),
+                    random_parameter(&mut random),
+                    random_parameter(&mut random),
+                    random.next_f32() * 0.1,
+                );
+                (point, index)
+            })
+            .collect();
+        let list = ParameterList::new(values);
+
+        for _ in 0..500 {
+            let target = TargetPoint {
+                temperature: i64::from(random.next_i32_bounded(20_000)) - 10_000,
+                humidity: i64::from(random.next_i32_bounded(20_000)) - 10_000,
+                continentalness: i64::from(random.next_i32_bounded(20_000)) - 10_000,
+                erosion: i64::from(random.next_i32_bounded(20_000)) - 10_000,
+                depth: i64::from(random.next_i32_bounded(20_000)) - 10_000,
+                weirdness: i64::from(random.next_i32_bounded(20_000)) - 10_000,
+            };
+            let array = target.to_parameter_array();
+            let closest = list
+                .values()
+                .iter()
+                .map(|(point, _)| distance(&point.parameter_space(), &array))
+                .min()
+                .expect("list is not empty");
+            let found = *list.find_value(target);
+            assert_eq!(
+                distance(&list.values()[found].0.parameter_space(), &array),
+                closest
+            );
+        }
+    }
+
+    #[test]
+    fn test_point_inside_a_box_finds_that_box() {
+        let list = ParameterList::new(vec![
+            (
+                ParameterPoint::new(
+                    Parameter::span(-1.0, 0.0),
+                    Parameter::span(-1.0, 1.0),
+                    Parameter::span(-1.0, 1.0),
+                    Parameter::span(-1.0, 1.0),
+                    Parameter::point(0.0),
+                    Parameter::span(-1.0, 1.0),
+                    0.0,
+                ),
+                "cold",
+            ),
+            (
+                ParameterPoint::new(
+                    Parameter::span(0.0, 1.0),
+                    Parameter::span(-1.0, 1.0),
+                    Parameter::span(-1.0, 1.0),
+                    Parameter::span(-1.0, 1.0),
+                    Parameter::point(0.0),
+                    Parameter::span(-1.0, 1.0),
+                    0.0,
+                ),
+                "warm",
+            ),
+        ]);
+        let target = |temperature| TargetPoint {
+            temperature,
+            humidity: 0,
+            continentalness: 0,
+            erosion: 0,
+            depth: 0,
+            weirdness: 0,
+        };
+        assert_eq!(*list.find_value(target(-5000)), "cold");
+        assert_eq!(*list.find_value(target(5000)), "warm");
+        assert_eq!(*list.find_value(target(20_000)), "warm");
+    }
+}
@@ -0,0 +1,480 @@
+//! Density functions bound to a world seed.
+//!
+//! [`RandomState`](super::RandomState) compiles the static trees from the registry into a
+//! flat list of nodes with their noises instantiated. Caching markers carry a slot index so
+//! that a [`NoiseChunk`](super::NoiseChunk) can keep its caches in plain vectors.
+
+use std::sync::Arc;
+
+use steel_registry::density_function::RarityValueMapper;
+use steel_utils::BlockPos;
+use steel_utils::math::interpolation::{clamped_map, lerp_f32};
+use steel_utils::noise::{BlendedNoise, NormalNoise, SimplexNoise};
+use steel_utils::random::Random;
+use steel_utils::random::legacy_random::LegacyRandom;
+
+/// The index of a node in a [`DensityGraph`].
+pub type NodeId = usize;
+
+/// A compiled density function node.
+#[allow(missing_docs)]
+pub enum Node {
+    Constant(f64),
+    Add(NodeId, NodeId),
+    Mul(NodeId, NodeId),
+    Min(NodeId, NodeId),
+    Max(NodeId, NodeId),
+    Abs(NodeId),
+    Square(NodeId),
+    Cube(NodeId),
+    HalfNegative(NodeId),
+    QuarterNegative(NodeId),
+    Squeeze(NodeId),
+    Invert(NodeId),
+    Clamp {
+        input: NodeId,
+        min: f64,
+        max: f64,
+    },
+    YClampedGradient {
+        from_y: f64,
+        to_y: f64,
+        from_value: f64,
+        to_value: f64,
+    },
+    Noise {
+        noise: Arc<NormalNoise>,
+        xz_scale: f64,
+        y_scale: f64,
+    },
+    ShiftedNoise {
+        noise: Arc<NormalNoise>,
+        xz_scale: f64,
+        y_scale: f64,
+        shift_x: NodeId,
+        shift_y: NodeId,
+        shift_z: NodeId,
+    },
+    ShiftA(Arc<NormalNoise>),
+    ShiftB(Arc<NormalNoise>),
+    Shift(Arc<NormalNoise>),
+    RangeChoice {
+        input: NodeId,
+        min_inclusive: f64,
+        max_exclusive: f64,
+        when_in_range: NodeId,
+        when_out_of_range: NodeId,
+    },
+    WeirdScaledSampler {
+        input: NodeId,
+        noise: Arc<NormalNoise>,
+        rarity_value_mapper: RarityValueMapper,
+    },
+    Spline(Box<Spline>),
+    BlendedNoise(Box<BlendedNoise>),
+    FindTopSurface {
+        density: NodeId,
+        upper_bound: NodeId,
+        lower_bound: i32,
+        cell_height: i32,
+    },
+    EndIslands(Box<EndIslands>),
+    Interpolated {
+        argument: NodeId,
+        slot: usize,
+    },
+    FlatCache {
+        argument: NodeId,
+        slot: usize,
+    },
+    Cache2d {
+        argument: NodeId,
+        slot: usize,
+    },
+    CacheOnce {
+        argument: NodeId,
+        slot: usize,
+    },
+    /// Blending with old chunks isn't supported, so these are their no-blend values.
+    BlendAlpha,
+    BlendOffset,
+    BlendDensity(NodeId),
+    /// Structures don't carve terrain yet, so the beardifier is always zero.
+    Beardifier,
+}
+
+/// A compiled cubic spline.
+#[allow(missing_docs)]
+pub struct Spline {
+    pub coordinate: NodeId,
+    pub locations: Box<[f32]>,
+    pub values: Box<[SplineValue]>,
+    pub derivatives: Box<[f32]>,
+}
+
+/// The value at a compiled spline point.
+#[allow(missing_docs)]
+pub enum SplineValue {
+    Constant(f32),
+    Spline(Box<Spline>),
+}
+
+/// The `end_islands` density function, seeded from the world seed.
+pub struct EndIslands {
+    island_noise: SimplexNoise,
+}
+
+impl EndIslands {
+    /// Creates the island noise like vanilla's `EndIslandDensityFunction`.
+    #[must_use]
+    pub fn new(seed: i64) -> Self {
+        let mut random = LegacyRandom::from_seed(seed as u64);
+        random.consume_count(17292);
+        Self {
+            island_noise: SimplexNoise::new(&mut random),
+        }
+    }
+
+    fn height_value(&self, section_x: i32, section_z: i32) -> f32 {
+        let chunk_x = section_x / 2;
+        let chunk_z = section_z / 2;
+        let sub_section_x = section_x % 2;
+        let sub_section_z = section_z % 2;
+        let distance = section_x
+            .wrapping_mul(section_x)
+            .wrapping_add(section_z.wrapping_mul(section_z));
+        let mut doffs = clamp_f32(100.0 - sqrt_f32(distance as f32) * 8.0, -100.0, 80.0);
+
+        for xo in -12..=12 {
+            for zo in -12..=12 {
+                let total_chunk_x = i64::from(chunk_x) + i64::from(xo);
+                let total_chunk_z = i64::from(chunk_z) + i64::from(zo);
+                if total_chunk_x * total_chunk_x + total_chunk_z * total_chunk_z > 4096
+                    && self
+                        .island_noise
+                        .get_value(total_chunk_x as f64, total_chunk_z as f64)
+                        < f64::from(-0.9_f32)
+                {
+                    let island_size = ((total_chunk_x as f32).abs() * 3439.0
+                        + (total_chunk_z as f32).abs() * 147.0)
+                        % 13.0
+                        + 9.0;
+                    let xd = (sub_section_x - xo * 2) as f32;
+                    let zd = (sub_section_z - zo * 2) as f32;
+                    let new_doffs = clamp_f32(
+                        100.0 - sqrt_f32(xd * xd + zd * zd) * island_size,
+                        -100.0,
+                        80.0,
+                    );
+                    doffs = doffs.max(new_doffs);
+                }
+            }
+        }
+        doffs
+    }
+
+    fn compute(&self, pos: BlockPos) -> f64 {
+        (f64::from(self.height_value(pos.x() / 8, pos.z() / 8)) - 8.0) / 128.0
+    }
+}
+
+/// Java's `Mth.sqrt(float)`, which takes the root in double precision.
+fn sqrt_f32(value: f32) -> f32 {
+    f64::from(value).sqrt() as f32
+}
+
+fn clamp_f32(value: f32, min: f32, max: f32) -> f32 {
+    if value < min { min } else { value.min(max) }
+}
+
+fn clamp(value: f64, min: f64, max: f64) -> f64 {
+    if value < min { min } else { value.min(max) }
+}
+
+/// Caches for the marker nodes of a density function.
+///
+/// Every method computes the argument directly by default, which is what vanilla does
+/// when a function is sampled outside of a chunk.
+pub trait DensityCaches: Sized {
+    /// Samples an `interpolated` node.
+    fn interpolated(
+        &mut self,
+        graph: &DensityGraph,
+        argument: NodeId,
+        _slot: usize,
+        pos: BlockPos,
+    ) -> f64 {
+        graph.compute(argument, pos, self)
+    }
+
+    /// Samples a `flat_cache` node.
+    fn flat_cache(
+        &mut self,
+        graph: &DensityGraph,
+        argument: NodeId,
+        _slot: usize,
+        pos: BlockPos,
+    ) -> f64 {
+        graph.compute(argument, pos, self)
+    }
+
+    /// Samples a `cache_2d` node.
+    fn cache_2d(
+        &mut self,
+        graph: &DensityGraph,
+        argument: NodeId,
+        _slot: usize,
+        pos: BlockPos,
+    ) -> f64 {
+        graph.compute(argument, pos, self)
+    }
+
+    /// Samples a `cache_once` or `cache_all_in_cell` node.
+    fn cache_once(
+        &mut self,
+        graph: &DensityGraph,
+        argument: NodeId,
+        _slot: usize,
+        pos: BlockPos,
+    ) -> f64 {
+        graph.compute(argument, pos, self)
+    }
+}
+
+/// Samples density functions at single points without any caching.
+pub struct SinglePointContext;
+
+impl DensityCaches for SinglePointContext {}
+
+/// The number of cache slots each kind of marker node uses.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SlotCounts {
+    /// The number of `interpolated` nodes.
+    pub interpolated: usize,
+    /// The number of `flat_cache` nodes.
+    pub flat_cache: usize,
+    /// The number of `cache_2d` nodes.
+    pub cache_2d: usize,
+    /// The number of `cache_once` and `cache_all_in_cell` nodes.
+    pub cache_once: usize,
+}
+
+/// A set of compiled density functions that can share nodes.
+pub struct DensityGraph {
+    pub(super) nodes: Vec<Node>,
+    pub(super) slots: SlotCounts,
+}
+
+impl DensityGraph {
+    /// Returns how many cache slots each kind of marker uses.
+    #[must_use]
+    pub const fn slots(&self) -> SlotCounts {
+        self.slots
+    }
+
+    /// Computes the value of `node` at `pos`.
+    #[allow(clippy::too_many_lines)]
+    pub fn compute<C: DensityCaches>(&self, node: NodeId, pos: BlockPos, caches: &mut C) -> f64 {
+        match &self.nodes[node] {
+            Node::Constant(value) => *value,
+            Node::Add(a, b) => self.compute(*a, pos, caches) + self.compute(*b, pos, caches),
+            Node::Mul(a, b) => {
+                let first = self.compute(*a, pos, caches);
+                if first == 0.0 {
+                    0.0
+                } else {
+                    first * self.compute(*b, pos, caches)
+                }
+            }
+            Node::Min(a, b) => self
+                .compute(*a, pos, caches)
+                .min(self.compute(*b, pos, caches)),
+            Node::Max(a, b) => self
+                .compute(*a, pos, caches)
+                .max(self.compute(*b, pos, caches)),
+            Node::Abs(argument) => self.compute(*argument, pos, caches).abs(),
+            Node::Square(argument) => {
+                let value = self.compute(*argument, pos, caches);
+                value * value
+            }
+            Node::Cube(argument) => {
+                let value = self.compute(*argument, pos, caches);
+                value * value * value
+            }
+            Node::HalfNegative(argument) => {
+                let value = self.compute(*argument, pos, caches);
+                if value > 0.0 { value } else { value * 0.5 }
+            }
+            Node::QuarterNegative(argument) => {
+                let value = self.compute(*argument, pos, caches);
+                if value > 0.0 { value } else { value * 0.25 }
+            }
+            Node::Squeeze(argument) => {
+                let value = clamp(self.compute(*argument, pos, caches), -1.0, 1.0);
+                value / 2.0 - value * value * value / 24.0
+            }
+            Node::Invert(argument) => 1.0 / self.compute(*argument, pos, caches),
+            Node::Clamp { input, min, max } => clamp(self.compute(*input, pos, caches), *min, *max),
+            Node::YClampedGradient {
+                from_y,
+                to_y,
+                from_value,
+                to_value,
+            } => clamped_map(f64::from(pos.y()), *from_y, *to_y, *from_value, *to_value),
+            Node::Noise {
+                noise,
+                xz_scale,
+                y_scale,
+            } => noise.get_value(
+                f64::from(pos.x()) * xz_scale,
+                f64::from(pos.y()) * y_scale,
+                f64::from(pos.z()) * xz_scale,
+            ),
+            Node::ShiftedNoise {
+                noise,
+                xz_scale,
+                y_scale,
+                shift_x,
+                shift_y,
+                shift_z,
+            } => {
+                let x = f64::from(pos.x()) * xz_scale + self.compute(*shift_x, pos, caches);
+                let y = f64::from(pos.y()) * y_scale + self.compute(*shift_y, pos, caches);
+                let z = f64::from(pos.z()) * xz_scale + self.compute(*shift_z, pos, caches);
+                noise.get_value(x, y, z)
+            }
+            Node::ShiftA(noise) => {
+                noise.get_value(f64::from(pos.x()) * 0.25, 0.0, f64::from(pos.z()) * 0.25) * 4.0
+            }
+            Node::ShiftB(noise) => {
+                noise.get_value(f64::from(pos.z()) * 0.25, f64::from(pos.x()) * 0.25, 0.0) * 4.0
+            }
+            Node::Shift(noise) => {
+                noise.get_value(
+                    f64::from(pos.x()) * 0.25,
+                    f64::from(pos.y()) * 0.25,
+                    f64::from(pos.z()) * 0.25,
+                ) * 4.0
+            }
+            Node::RangeChoice {
+                input,
+                min_inclusive,
+                max_exclusive,
+                when_in_range,
+                when_out_of_range,
+            } => {
+                let value = self.compute(*input, pos, caches);
+                if value >= *min_inclusive && value < *max_exclusive {
+                    self.compute(*when_in_range, pos, caches)
+                } else {
+                    self.compute(*when_out_of_range, pos, caches)
+                }
+            }
+            Node::WeirdScaledSampler {
+                input,
+                noise,
+                rarity_value_mapper,
+            } => {
+                let rarity = rarity_value_mapper.map(self.compute(*input, pos, caches));
+                rarity
+                    * noise
+                        .get_value(
+                            f64::from(pos.x()) / rarity,
+                            f64::from(pos.y()) / rarity,
+                            f64::from(pos.z()) / rarity,
+                        )
+                        .abs()
+            }
+            Node::Spline(spline) => f64::from(self.apply_spline(spline, pos, caches)),
+            Node::BlendedNoise(noise) => noise.compute(pos.x(), pos.y(), pos.z()),
+            Node::FindTopSurface {
+                density,
+                upper_bound,
+                lower_bound,
+                cell_height,
+            } => {
+                let upper = self.compute(*upper_bound, pos, caches);
+                let top_y = (upper / f64::from(*cell_height)).floor() as i32 * cell_height;
+                if top_y <= *lower_bound {
+                    return f64::from(*lower_bound);
+                }
+                let mut block_y = top_y;
+                while block_y >= *lower_bound {
+                    let sample = BlockPos::new(pos.x(), block_y, pos.z());
+                    if self.compute(*density, sample, caches) > 0.0 {
+                        return f64::from(block_y);
+                    }
+                    block_y -= cell_height;
+                }
+                f64::from(*lower_bound)
+            }
+            Node::EndIslands(islands) => islands.compute(pos),
+            Node::Interpolated { argument, slot } => {
+                caches.interpolated(self, *argument, *slot, pos)
+            }
+            Node::FlatCache { argument, slot } => caches.flat_cache(self, *argument, *slot, pos),
+            Node::Cache2d { argument, slot } => caches.cache_2d(self, *argument, *slot, pos),
+            Node::CacheOnce { argument, slot } => caches.cache_once(self, *argument, *slot, pos),
+            Node::BlendAlpha => 1.0,
+            Node::BlendOffset | Node::Beardifier => 0.0,
+            Node::BlendDensity(argument) => self.compute(*argument, pos, caches),
+        }
+    }
+
+    /// Evaluates a spline in single precision, like vanilla's `CubicSpline.Multipoint`.
+    fn apply_spline<C: DensityCaches>(
+        &self,
+        spline: &Spline,
+        pos: BlockPos,
+        caches: &mut C,
+    ) -> f32 {
+        let input = self.compute(spline.coordinate, pos, caches) as f32;
+        let locations = &spline.locations;
+        // The first point above the input, minus one. NaN sorts past every point.
+        let start =
+            locations.partition_point(|&location| location <= input || input.is_nan()) as isize - 1;
+        let last = locations.len() - 1;
+
+        if start < 0 {
+            let value = self.spline_value(&spline.values[0], pos, caches);
+            return Self::linear_extend(input, spline, value, 0);
+        }
+        let start = start as usize;
+        if start == last {
+            let value = self.spline_value(&spline.values[last], pos, caches);
+            return Self::linear_extend(input, spline, value, last);
+        }
+
+        let x1 = locations[start];
+        let x2 = locations[start + 1];
+        let t = (input - x1) / (x2 - x1);
+        let d1 = spline.derivatives[start];
+        let d2 = spline.derivatives[start + 1];
+        let y1 = self.spline_value(&spline.values[start], pos, caches);
+        let y2 = self.spline_value(&spline.values[start + 1], pos, caches);
+        let a = d1 * (x2 - x1) - (y2 - y1);
+        let b = -d2 * (x2 - x1) + (y2 - y1);
+        lerp_f32(t, y1, y2) + t * (1.0 - t) * lerp_f32(t, a, b)
+    }
+
+    fn spline_value<C: DensityCaches>(
+        &self,
+        value: &SplineValue,
+        pos: BlockPos,
+        caches: &mut C,
+    ) -> f32 {
+        match value {
+            SplineValue::Constant(value) => *value,
+            SplineValue::Spline(spline) => self.apply_spline(spline, pos, caches),
+        }
+    }
+
+    fn linear_extend(input: f32, spline: &Spline, value: f32, index: usize) -> f32 {
+        let derivative = spline.derivatives[index];
+        if derivative == 0.0 {
+            value
+        } else {
+            value + derivative * (input - spline.locations[index])
+        }
+    }
+}
@@ -0,0 +1,19 @@
+//! Noise based terrain generation.
+//!
+//! Density functions from the registry are bound to a world seed by [`RandomState`] and then
+//! sampled per chunk through a [`NoiseChunk`], which applies vanilla's caching and
+//! interpolation rules so the terrain matches vanilla for the same seed. Biomes are picked
+//! from the same climate noises by a [`BiomeSource`].
+
+pub mod aquifer;
+pub mod biome_source;
+pub mod climate;
+pub mod density_function;
+pub mod noise_chunk;
+pub mod ore_veinifier;
+pub mod overworld_biome_builder;
+pub mod random_state;
+
+pub use biome_source::BiomeSource;
+pub use noise_chunk::NoiseChunk;
+pub use random_state::RandomState;
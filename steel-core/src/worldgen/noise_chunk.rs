@@ -0,0 +1,253 @@
+//! Per-chunk sampling of a noise router.
+
+use rustc_hash::FxHashMap;
+use steel_utils::BlockPos;
+use steel_utils::math::interpolation::lerp;
+
+use crate::worldgen::density_function::{DensityCaches, DensityGraph, NodeId};
+
+/// The number of quart positions a flat cache covers along each axis.
+const FLAT_CACHE_SIZE: i32 = 16 / 4 + 1;
+
+/// Samples density functions for a single chunk, caching like vanilla's `NoiseChunk`.
+///
+/// `interpolated` nodes are only interpolated inside the fill loop, see
+/// [`sample`](Self::sample). Everywhere else they are computed directly, the same way vanilla
+/// treats samples that don't come from the chunk itself.
+pub struct NoiseChunk<'a> {
+    graph: &'a DensityGraph,
+    cell_width: i32,
+    cell_height: i32,
+    cell_count_xz: i32,
+    cell_count_y: i32,
+    cell_min_y: i32,
+    first_block_x: i32,
+    first_block_z: i32,
+    first_quart_x: i32,
+    first_quart_z: i32,
+    preliminary_surface_level: NodeId,
+    interpolating: bool,
+    flat_caches: Vec<Option<Box<[f64]>>>,
+    cache_2d: Vec<Option<(i32, i32, f64)>>,
+    cache_once: Vec<Option<(BlockPos, f64)>>,
+    /// Corner values for every `interpolated` node, indexed by `corner_index`.
+    interpolators: Vec<Option<Box<[f64]>>>,
+    preliminary_surface_cache: FxHashMap<(i32, i32), i32>,
+}
+
+impl<'a> NoiseChunk<'a> {
+    /// Creates the caches for the chunk starting at `first_block_x`, `first_block_z`.
+    ///
+    /// `cell_min_y` and `cell_count_y` give the vertical range of the fill loop in cells.
+    #[must_use]
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        graph: &'a DensityGraph,
+        preliminary_surface_level: NodeId,
+        cell_width: i32,
+        cell_height: i32,
+        cell_min_y: i32,
+        cell_count_y: i32,
+        first_block_x: i32,
+        first_block_z: i32,
+    ) -> Self {
+        let slots = graph.slots();
+        Self {
+            graph,
+            cell_width,
+            cell_height,
+            cell_count_xz: 16 / cell_width,
+            cell_count_y,
+            cell_min_y,
+            first_block_x,
+            first_block_z,
+            first_quart_x: first_block_x >> 2,
+            first_quart_z: first_block_z >> 2,
+            preliminary_surface_level,
+            interpolating: false,
+            flat_caches: vec![None; slots.flat_cache],
+            cache_2d: vec![None; slots.cache_2d],
+            cache_once: vec![None; slots.cache_once],
+            interpolators: vec![None; slots.interpolated],
+            preliminary_surface_cache: FxHashMap::default(),
+        }
+    }
+
+    /// Samples `node` at a block inside the fill loop, interpolating between cell corners.
+    pub fn sample(&mut self, node: NodeId, pos: BlockPos) -> f64 {
+        let graph = self.graph;
+        self.interpolating = true;
+        let value = graph.compute(node, pos, self);
+        self.interpolating = false;
+        value
+    }
+
+    /// Samples `node` at a single point outside of the fill loop.
+    pub fn sample_point(&mut self, node: NodeId, pos: BlockPos) -> f64 {
+        let graph = self.graph;
+        graph.compute(node, pos, self)
+    }
+
+    /// The rough height of the terrain, used by aquifers to find the surface.
+    pub fn preliminary_surface_level(&mut self, block_x: i32, block_z: i32) -> i32 {
+        let quart_x = block_x & !3;
+        let quart_z = block_z & !3;
+        if let Some(&level) = self.preliminary_surface_cache.get(&(quart_x, quart_z)) {
+            return level;
+        }
+        let level = self
+            .sample_point(
+                self.preliminary_surface_level,
+                BlockPos::new(quart_x, 0, quart_z),
+            )
+            .floor() as i32;
+        self.preliminary_surface_cache
+            .insert((quart_x, quart_z), level);
+        level
+    }
+
+    fn corner_index(&self, x: i32, y: i32, z: i32) -> usize {
+        let size_xz = self.cell_count_xz + 1;
+        let size_y = self.cell_count_y + 1;
+        ((x * size_xz + z) * size_y + y) as usize
+    }
+
+    fn fill_interpolator(&mut self, graph: &DensityGraph, argument: NodeId) -> Box<[f64]> {
+        let size_xz = self.cell_count_xz + 1;
+        let size_y = self.cell_count_y + 1;
+        let mut corners = vec![0.0; (size_xz * size_xz * size_y) as usize];
+        for x in 0..size_xz {
+            for z in 0..size_xz {
+                for y in 0..size_y {
+                    let pos = BlockPos::new(
+                        self.first_block_x + x * self.cell_width,
+                        (self.cell_min_y + y) * self.cell_height,
+                        self.first_block_z + z * self.cell_width,
+                    );
+                    corners[self.corner_index(x, y, z)] = graph.compute(argument, pos, self);
+                }
+            }
+        }
+        corners.into_boxed_slice()
+    }
+}
+
+impl DensityCaches for NoiseChunk<'_> {
+    #[allow(clippy::similar_names)]
+    fn interpolated(
+        &mut self,
+        graph: &DensityGraph,
+        argument: NodeId,
+        slot: usize,
+        pos: BlockPos,
+    ) -> f64 {
+        let local_x = pos.x() - self.first_block_x;
+        let local_y = pos.y() - self.cell_min_y * self.cell_height;
+        let local_z = pos.z() - self.first_block_z;
+        let cell_x = local_x.div_euclid(self.cell_width);
+        let cell_y = local_y.div_euclid(self.cell_height);
+        let cell_z = local_z.div_euclid(self.cell_width);
+        if !self.interpolating
+            || !(0..self.cell_count_xz).contains(&cell_x)
+            || !(0..self.cell_count_y).contains(&cell_y)
+            || !(0..self.cell_count_xz).contains(&cell_z)
+        {
+            return graph.compute(argument, pos, self);
+        }
+
+        if self.interpolators[slot].is_none() {
+            // Corners are sampled like single points; interpolation only applies to blocks.
+            self.interpolating = false;
+            let corners = self.fill_interpolator(graph, argument);
+            self.interpolating = true;
+            self.interpolators[slot] = Some(corners);
+        }
+        let corner = |x, y, z| {
+            self.interpolators[slot].as_ref().expect("filled above")
+                [self.corner_index(cell_x + x, cell_y + y, cell_z + z)]
+        };
+
+        let delta_x = f64::from(local_x.rem_euclid(self.cell_width)) / f64::from(self.cell_width);
+        let delta_y = f64::from(local_y.rem_euclid(self.cell_height)) / f64::from(self.cell_height);
+        let delta_z = f64::from(local_z.rem_euclid(self.cell_width)) / f64::from(self.cell_width);
+
+        // Same order as vanilla's `NoiseInterpolator`: y first, then x, then z.
+        let value_00 = lerp(delta_y, corner(0, 0, 0), corner(0, 1, 0));
+        let value_10 = lerp(delta_y, corner(1, 0, 0), corner(1, 1, 0));
+        let value_01 = lerp(delta_y, corner(0, 0, 1), corner(0, 1, 1));
+        let value_11 = lerp(delta_y, corner(1, 0, 1), corner(1, 1, 1));
+        let value_0 = lerp(delta_x, value_00, value_10);
+        let value_1 = lerp(delta_x, value_01, value_11);
+        lerp(delta_z, value_0, value_1)
+    }
+
+    fn flat_cache(
+        &mut self,
+        graph: &DensityGraph,
+        argument: NodeId,
+        slot: usize,
+        pos: BlockPos,
+    ) -> f64 {
+        let index_x = (pos.x() >> 2) - self.first_quart_x;
+        let index_z = (pos.z() >> 2) - self.first_quart_z;
+        if !(0..FLAT_CACHE_SIZE).contains(&index_x) || !(0..FLAT_CACHE_SIZE).contains(&index_z) {
+            return graph.compute(argument, pos, self);
+        }
+
+        if self.flat_caches[slot].is_none() {
+            let mut values = vec![0.0; (FLAT_CACHE_SIZE * FLAT_CACHE_SIZE) as usize];
+            for z in 0..FLAT_CACHE_SIZE {
+                for x in 0..FLAT_CACHE_SIZE {
+                    let pos = BlockPos::new(
+                        (self.first_quart_x + x) << 2,
+                        0,
+                        (self.first_quart_z + z) << 2,
+                    );
+                    values[(x + z * FLAT_CACHE_SIZE) as usize] = graph.compute(argument, pos, self);
+                }
+            }
+            self.flat_caches[slot] = Some(values.into_boxed_slice());
+        }
+        self.flat_caches[slot].as_ref().expect("filled above")
+            [(index_x + index_z * FLAT_CACHE_SIZE) as usize]
+    }
+
+    fn cache_2d(
+        &mut self,
+        graph: &DensityGraph,
+        argument: NodeId,
+        slot: usize,
+        pos: BlockPos,
+    ) -> f64 {
+        if let Some((x, z, value)) = self.cache_2d[slot]
+            && x == pos.x()
+            && z == pos.z()
+        {
+            return value;
+        }
+        let value = graph.compute(argument, pos, self);
+        self.cache_2d[slot] = Some((pos.x(), pos.z(), value));
+        value
+    }
+
+    fn cache_once(
+        &mut self,
+        graph: &DensityGraph,
+        argument: NodeId,
+        slot: usize,
+        pos: BlockPos,
+    ) -> f64 {
+        // Keyed on the interpolation mode too, since it changes what the argument returns.
+        if let Some((last, value)) = self.cache_once[slot]
+            && last == pos
+            && self.interpolating
+        {
+            return value;
+        }
+        let value = graph.compute(argument, pos, self);
+        if self.interpolating {
+            self.cache_once[slot] = Some((pos, value));
+        }
+        value
+    }
+}
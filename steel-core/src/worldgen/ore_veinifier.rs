@@ -0,0 +1,125 @@
+//! Large copper and iron ore veins placed during noise filling.
+
+use steel_registry::blocks::BlockRef;
+use steel_registry::{REGISTRY, vanilla_blocks};
+use steel_utils::math::interpolation::clamped_map;
+use steel_utils::random::{PositionalRandom, Random, RandomSplitter};
+use steel_utils::{BlockPos, BlockStateId};
+
+use crate::worldgen::noise_chunk::NoiseChunk;
+use crate::worldgen::random_state::CompiledRouter;
+
+struct VeinType {
+    ore: BlockStateId,
+    raw_ore_block: BlockStateId,
+    filler: BlockStateId,
+    min_y: i32,
+    max_y: i32,
+}
+
+impl VeinType {
+    fn new(
+        ore: BlockRef,
+        raw_ore_block: BlockRef,
+        filler: BlockRef,
+        min_y: i32,
+        max_y: i32,
+    ) -> Self {
+        Self {
+            ore: REGISTRY.blocks.get_default_state_id(ore),
+            raw_ore_block: REGISTRY.blocks.get_default_state_id(raw_ore_block),
+            filler: REGISTRY.blocks.get_default_state_id(filler),
+            min_y,
+            max_y,
+        }
+    }
+}
+
+/// Places ore veins where the vein noises of the router line up.
+pub struct OreVeinifier {
+    copper: VeinType,
+    iron: VeinType,
+}
+
+impl Default for OreVeinifier {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl OreVeinifier {
+    /// Creates the veinifier with vanilla's two vein types.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            copper: VeinType::new(
+                vanilla_blocks::COPPER_ORE,
+                vanilla_blocks::RAW_COPPER_BLOCK,
+                vanilla_blocks::GRANITE,
+                0,
+                50,
+            ),
+            iron: VeinType::new(
+                vanilla_blocks::DEEPSLATE_IRON_ORE,
+                vanilla_blocks::RAW_IRON_BLOCK,
+                vanilla_blocks::TUFF,
+                -60,
+                -8,
+            ),
+        }
+    }
+
+    /// Returns the vein block at `pos`, or `None` if there is no vein there.
+    pub fn compute(
+        &self,
+        noise_chunk: &mut NoiseChunk<'_>,
+        router: &CompiledRouter,
+        ore_random: &RandomSplitter,
+        pos: BlockPos,
+    ) -> Option<BlockStateId> {
+        let veininess = noise_chunk.sample(router.vein_toggle, pos);
+        let vein_type = if veininess > 0.0 {
+            &self.copper
+        } else {
+            &self.iron
+        };
+        let abs_veininess = veininess.abs();
+        let distance_from_top = vein_type.max_y - pos.y();
+        let distance_from_bottom = pos.y() - vein_type.min_y;
+        if distance_from_top < 0 || distance_from_bottom < 0 {
+            return None;
+        }
+
+        let distance_from_edge = distance_from_top.min(distance_from_bottom);
+        let edge_roundoff = clamped_map(f64::from(distance_from_edge), 0.0, 20.0, -0.2, 0.0);
+        if abs_veininess + edge_roundoff < f64::from(0.4_f32) {
+            return None;
+        }
+        let mut random = ore_random.at(pos.x(), pos.y(), pos.z());
+        if random.next_f32() > 0.7 {
+            return None;
+        }
+        if noise_chunk.sample(router.vein_ridged, pos) >= 0.0 {
+            return None;
+        }
+
+        let richness = clamped_map(
+            abs_veininess,
+            f64::from(0.4_f32),
+            f64::from(0.6_f32),
+            f64::from(0.1_f32),
+            f64::from(0.3_f32),
+        );
+        if f64::from(random.next_f32()) < richness
+            && noise_chunk.sample(router.vein_gap, pos) > f64::from(-0.3_f32)
+        {
+            if random.next_f32() < 0.02 {
+                Some(vein_type.raw_ore_block)
+            } else {
+                Some(vein_type.ore)
+            }
+        } else {
+            Some(vein_type.filler)
+        }
+    }
+}
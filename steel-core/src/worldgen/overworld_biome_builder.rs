@@ -0,0 +1,935 @@
+//! The climate parameters of the overworld biomes, ported from vanilla's
+//! `OverworldBiomeBuilder`.
+
+use std::sync::LazyLock;
+
+use steel_registry::biome::{Biome, BiomeRef};
+use steel_registry::vanilla_biomes as biomes;
+
+use crate::worldgen::climate::{Parameter, ParameterPoint};
+
+type BiomeKey = &'static LazyLock<Biome>;
+
+static OCEANS: [[BiomeKey; 5]; 2] = [
+    [
+        &biomes::DEEP_FROZEN_OCEAN,
+        &biomes::DEEP_COLD_OCEAN,
+        &biomes::DEEP_OCEAN,
+        &biomes::DEEP_LUKEWARM_OCEAN,
+        &biomes::WARM_OCEAN,
+    ],
+    [
+        &biomes::FROZEN_OCEAN,
+        &biomes::COLD_OCEAN,
+        &biomes::OCEAN,
+        &biomes::LUKEWARM_OCEAN,
+        &biomes::WARM_OCEAN,
+    ],
+];
+
+static MIDDLE_BIOMES: [[BiomeKey; 5]; 5] = [
+    [
+        &biomes::SNOWY_PLAINS,
+        &biomes::SNOWY_PLAINS,
+        &biomes::SNOWY_PLAINS,
+        &biomes::SNOWY_TAIGA,
+        &biomes::TAIGA,
+    ],
+    [
+        &biomes::PLAINS,
+        &biomes::PLAINS,
+        &biomes::FOREST,
+        &biomes::TAIGA,
+        &biomes::OLD_GROWTH_SPRUCE_TAIGA,
+    ],
+    [
+        &biomes::FLOWER_FOREST,
+        &biomes::PLAINS,
+        &biomes::FOREST,
+        &biomes::BIRCH_FOREST,
+        &biomes::DARK_FOREST,
+    ],
+    [
+        &biomes::SAVANNA,
+        &biomes::SAVANNA,
+        &biomes::FOREST,
+        &biomes::JUNGLE,
+        &biomes::JUNGLE,
+    ],
+    [
+        &biomes::DESERT,
+        &biomes::DESERT,
+        &biomes::DESERT,
+        &biomes::DESERT,
+        &biomes::DESERT,
+    ],
+];
+
+static MIDDLE_BIOMES_VARIANT: [[Option<BiomeKey>; 5]; 5] = [
+    [
+        Some(&biomes::ICE_SPIKES),
+        None,
+        Some(&biomes::SNOWY_TAIGA),
+        None,
+        None,
+    ],
+    [None, None, None, None, Some(&biomes::OLD_GROWTH_PINE_TAIGA)],
+    [
+        Some(&biomes::SUNFLOWER_PLAINS),
+        None,
+        None,
+        Some(&biomes::OLD_GROWTH_BIRCH_FOREST),
+        None,
+    ],
+    [
+        None,
+        None,
+        Some(&biomes::PLAINS),
+        Some(&biomes::SPARSE_JUNGLE),
+        Some(&biomes::BAMBOO_JUNGLE),
+    ],
+    [None, None, None, None, None],
+];
+
+static PLATEAU_BIOMES: [[BiomeKey; 5]; 5] = [
+    [
+        &biomes::SNOWY_PLAINS,
+        &biomes::SNOWY_PLAINS,
+        &biomes::SNOWY_PLAINS,
+        &biomes::SNOWY_TAIGA,
+        &biomes::SNOWY_TAIGA,
+    ],
+    [
+        &biomes::MEADOW,
+        &biomes::MEADOW,
+        &biomes::FOREST,
+        &biomes::TAIGA,
+        &biomes::OLD_GROWTH_SPRUCE_TAIGA,
+    ],
+    [
+        &biomes::MEADOW,
+        &biomes::MEADOW,
+        &biomes::MEADOW,
+        &biomes::MEADOW,
+        &biomes::PALE_GARDEN,
+    ],
+    [
+        &biomes::SAVANNA_PLATEAU,
+        &biomes::SAVANNA_PLATEAU,
+        &biomes::FOREST,
+        &biomes::FOREST,
+        &biomes::JUNGLE,
+    ],
+    [
+        &biomes::BADLANDS,
+        &biomes::BADLANDS,
+        &biomes::BADLANDS,
+        &biomes::WOODED_BADLANDS,
+        &biomes::WOODED_BADLANDS,
+    ],
+];
+
+static PLATEAU_BIOMES_VARIANT: [[Option<BiomeKey>; 5]; 5] = [
+    [Some(&biomes::ICE_SPIKES), None, None, None, None],
+    [
+        Some(&biomes::CHERRY_GROVE),
+        None,
+        Some(&biomes::MEADOW),
+        Some(&biomes::MEADOW),
+        Some(&biomes::OLD_GROWTH_PINE_TAIGA),
+    ],
+    [
+        Some(&biomes::CHERRY_GROVE),
+        Some(&biomes::CHERRY_GROVE),
+        Some(&biomes::FOREST),
+        Some(&biomes::BIRCH_FOREST),
+        None,
+    ],
+    [None, None, None, None, None],
+    [
+        Some(&biomes::ERODED_BADLANDS),
+        Some(&biomes::ERODED_BADLANDS),
+        None,
+        None,
+        None,
+    ],
+];
+
+static SHATTERED_BIOMES: [[Option<BiomeKey>; 5]; 5] = [
+    [
+        Some(&biomes::WINDSWEPT_GRAVELLY_HILLS),
+        Some(&biomes::WINDSWEPT_GRAVELLY_HILLS),
+        Some(&biomes::WINDSWEPT_HILLS),
+        Some(&biomes::WINDSWEPT_FOREST),
+        Some(&biomes::WINDSWEPT_FOREST),
+    ],
+    [
+        Some(&biomes::WINDSWEPT_GRAVELLY_HILLS),
+        Some(&biomes::WINDSWEPT_GRAVELLY_HILLS),
+        Some(&biomes::WINDSWEPT_HILLS),
+        Some(&biomes::WINDSWEPT_FOREST),
+        Some(&biomes::WINDSWEPT_FOREST),
+    ],
+    [
+        Some(&biomes::WINDSWEPT_HILLS),
+        Some(&biomes::WINDSWEPT_HILLS),
+        Some(&biomes::WINDSWEPT_HILLS),
+        Some(&biomes::WINDSWEPT_FOREST),
+        Some(&biomes::WINDSWEPT_FOREST),
+    ],
+    [None, None, None, None, None],
+    [None, None, None, None, None],
+];
+
+/// Lists the climate of every overworld biome.
+pub struct OverworldBiomeBuilder {
+    full_range: Parameter,
+    temperatures: [Parameter; 5],
+    humidities: [Parameter; 5],
+    erosions: [Parameter; 7],
+    frozen_range: Parameter,
+    unfrozen_range: Parameter,
+    mushroom_fields_continentalness: Parameter,
+    deep_ocean_continentalness: Parameter,
+    ocean_continentalness: Parameter,
+    coast_continentalness: Parameter,
+    inland_continentalness: Parameter,
+    near_inland_continentalness: Parameter,
+    mid_inland_continentalness: Parameter,
+    far_inland_continentalness: Parameter,
+}
+
+impl Default for OverworldBiomeBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+type Output = Vec<(ParameterPoint, BiomeRef)>;
+
+fn add_surface_biome(
+    out: &mut Output,
+    temperature: Parameter,
+    humidity: Parameter,
+    continentalness: Parameter,
+    erosion: Parameter,
+    weirdness: Parameter,
+    biome: BiomeKey,
+) {
+    for depth in [0.0, 1.0] {
+        out.push((
+            ParameterPoint::new(
+                temperature,
+                humidity,
+                continentalness,
+                erosion,
+                Parameter::point(depth),
+                weirdness,
+                0.0,
+            ),
+            LazyLock::force(biome),
+        ));
+    }
+}
+
+fn add_underground_biome(
+    out: &mut Output,
+    temperature: Parameter,
+    humidity: Parameter,
+    continentalness: Parameter,
+    erosion: Parameter,
+    weirdness: Parameter,
+    biome: BiomeKey,
+) {
+    out.push((
+        ParameterPoint::new(
+            temperature,
+            humidity,
+            continentalness,
+            erosion,
+            Parameter::span(0.2, 0.9),
+            weirdness,
+            0.0,
+        ),
+        LazyLock::force(biome),
+    ));
+}
+
+fn add_bottom_biome(
+    out: &mut Output,
+    temperature: Parameter,
+    humidity: Parameter,
+    continentalness: Parameter,
+    erosion: Parameter,
+    weirdness: Parameter,
+    biome: BiomeKey,
+) {
+    out.push((
+        ParameterPoint::new(
+            temperature,
+            humidity,
+            continentalness,
+            erosion,
+            Parameter::point(1.1),
+            weirdness,
+            0.0,
+        ),
+        LazyLock::force(biome),
+    ));
+}
+
+impl OverworldBiomeBuilder {
+    /// Creates the builder with vanilla's parameter ranges.
+    #[must_use]
+    pub fn new() -> Self {
+        let temperatures = [
+            Parameter::span(-1.0, -0.45),
+            Parameter::span(-0.45, -0.15),
+            Parameter::span(-0.15, 0.2),
+            Parameter::span(0.2, 0.55),
+            Parameter::span(0.55, 1.0),
+        ];
+        Self {
+            full_range: Parameter::span(-1.0, 1.0),
+            temperatures,
+            humidities: [
+                Parameter::span(-1.0, -0.35),
+                Parameter::span(-0.35, -0.1),
+                Parameter::span(-0.1, 0.1),
+                Parameter::span(0.1, 0.3),
+                Parameter::span(0.3, 1.0),
+            ],
+            erosions: [
+                Parameter::span(-1.0, -0.78),
+                Parameter::span(-0.78, -0.375),
+                Parameter::span(-0.375, -0.2225),
+                Parameter::span(-0.2225, 0.05),
+                Parameter::span(0.05, 0.45),
+                Parameter::span(0.45, 0.55),
+                Parameter::span(0.55, 1.0),
+            ],
+            frozen_range: temperatures[0],
+            unfrozen_range: temperatures[1].union(temperatures[4]),
+            mushroom_fields_continentalness: Parameter::span(-1.2, -1.05),
+            deep_ocean_continentalness: Parameter::span(-1.05, -0.455),
+            ocean_continentalness: Parameter::span(-0.455, -0.19),
+            coast_continentalness: Parameter::span(-0.19, -0.11),
+            inland_continentalness: Parameter::span(-0.11, 0.55),
+            near_inland_continentalness: Parameter::span(-0.11, 0.03),
+            mid_inland_continentalness: Parameter::span(0.03, 0.3),
+            far_inland_continentalness: Parameter::span(0.3, 1.0),
+        }
+    }
+
+    /// Returns every overworld biome together with the climate it occupies, in vanilla's
+    /// order.
+    #[must_use]
+    pub fn build(&self) -> Vec<(ParameterPoint, BiomeRef)> {
+        let mut out = Vec::new();
+        self.add_off_coast_biomes(&mut out);
+        self.add_inland_biomes(&mut out);
+        self.add_underground_biomes(&mut out);
+        out
+    }
+
+    fn add_off_coast_biomes(&self, out: &mut Output) {
+        add_surface_biome(
+            out,
+            self.full_range,
+            self.full_range,
+            self.mushroom_fields_continentalness,
+            self.full_range,
+            self.full_range,
+            &biomes::MUSHROOM_FIELDS,
+        );
+        for (i, &temperature) in self.temperatures.iter().enumerate() {
+            add_surface_biome(
+                out,
+                temperature,
+                self.full_range,
+                self.deep_ocean_continentalness,
+                self.full_range,
+                self.full_range,
+                OCEANS[0][i],
+            );
+            add_surface_biome(
+                out,
+                temperature,
+                self.full_range,
+                self.ocean_continentalness,
+                self.full_range,
+                self.full_range,
+                OCEANS[1][i],
+            );
+        }
+    }
+
+    fn add_inland_biomes(&self, out: &mut Output) {
+        self.add_mid_slice(out, Parameter::span(-1.0, -0.933_333_34));
+        self.add_high_slice(out, Parameter::span(-0.933_333_34, -0.766_666_7));
+        self.add_peaks(out, Parameter::span(-0.766_666_7, -0.566_666_66));
+        self.add_high_slice(out, Parameter::span(-0.566_666_66, -0.4));
+        self.add_mid_slice(out, Parameter::span(-0.4, -0.266_666_68));
+        self.add_low_slice(out, Parameter::span(-0.266_666_68, -0.05));
+        self.add_valleys(out, Parameter::span(-0.05, 0.05));
+        self.add_low_slice(out, Parameter::span(0.05, 0.266_666_68));
+        self.add_mid_slice(out, Parameter::span(0.266_666_68, 0.4));
+        self.add_high_slice(out, Parameter::span(0.4, 0.566_666_66));
+        self.add_peaks(out, Parameter::span(0.566_666_66, 0.766_666_7));
+        self.add_high_slice(out, Parameter::span(0.766_666_7, 0.933_333_34));
+        self.add_mid_slice(out, Parameter::span(0.933_333_34, 1.0));
+    }
+
+    fn coast_to_far_inland(&self) -> Parameter {
+        self.coast_continentalness
+            .union(self.far_inland_continentalness)
+    }
+
+    fn coast_to_near_inland(&self) -> Parameter {
+        self.coast_continentalness
+            .union(self.near_inland_continentalness)
+    }
+
+    fn near_to_far_inland(&self) -> Parameter {
+        self.near_inland_continentalness
+            .union(self.far_inland_continentalness)
+    }
+
+    fn mid_to_far_inland(&self) -> Parameter {
+        self.mid_inland_continentalness
+            .union(self.far_inland_continentalness)
+    }
+
+    fn erosions(&self, from: usize, to: usize) -> Parameter {
+        self.erosions[from].union(self.erosions[to])
+    }
+
+    fn add_peaks(&self, out: &mut Output, weirdness: Parameter) {
+        for (t, &temperature) in self.temperatures.iter().enumerate() {
+            for (h, &humidity) in self.humidities.iter().enumerate() {
+                let middle = pick_middle_biome(t, h, weirdness);
+                let middle_or_badlands = pick_middle_biome_or_badlands_if_hot(t, h, weirdness);
+                let middle_or_badlands_or_slope =
+                    pick_middle_biome_or_badlands_if_hot_or_slope_if_cold(t, h, weirdness);
+                let plateau = pick_plateau_biome(t, h, weirdness);
+                let shattered = pick_shattered_biome(t, h, weirdness);
+                let windswept_savanna =
+                    maybe_pick_windswept_savanna_biome(t, h, weirdness, shattered);
+                let peak = pick_peak_biome(t, h, weirdness);
+
+                let mut add = |continentalness, erosion, biome| {
+                    add_surface_biome(
+                        out,
+                        temperature,
+                        humidity,
+                        continentalness,
+                        erosion,
+                        weirdness,
+                        biome,
+                    );
+                };
+                add(self.coast_to_far_inland(), self.erosions[0], peak);
+                add(
+                    self.coast_to_near_inland(),
+                    self.erosions[1],
+                    middle_or_badlands_or_slope,
+                );
+                add(self.mid_to_far_inland(), self.erosions[1], peak);
+                add(self.coast_to_near_inland(), self.erosions(2, 3), middle);
+                add(self.mid_to_far_inland(), self.erosions[2], plateau);
+                add(
+                    self.mid_inland_continentalness,
+                    self.erosions[3],
+                    middle_or_badlands,
+                );
+                add(self.far_inland_continentalness, self.erosions[3], plateau);
+                add(self.coast_to_far_inland(), self.erosions[4], middle);
+                add(
+                    self.coast_to_near_inland(),
+                    self.erosions[5],
+                    windswept_savanna,
+                );
+                add(self.mid_to_far_inland(), self.erosions[5], shattered);
+                add(self.coast_to_far_inland(), self.erosions[6], middle);
+            }
+        }
+    }
+
+    fn add_high_slice(&self, out: &mut Output, weirdness: Parameter) {
+        for (t, &temperature) in self.temperatures.iter().enumerate() {
+            for (h, &humidity) in self.humidities.iter().enumerate() {
+                let middle = pick_middle_biome(t, h, weirdness);
+                let middle_or_badlands = pick_middle_biome_or_badlands_if_hot(t, h, weirdness);
+                let middle_or_badlands_or_slope =
+                    pick_middle_biome_or_badlands_if_hot_or_slope_if_cold(t, h, weirdness);
+                let plateau = pick_plateau_biome(t, h, weirdness);
+                let shattered = pick_shattered_biome(t, h, weirdness);
+                let windswept_savanna = maybe_pick_windswept_savanna_biome(t, h, weirdness, middle);
+                let slope = pick_slope_biome(t, h, weirdness);
+                let peak = pick_peak_biome(t, h, weirdness);
+
+                let mut add = |continentalness, erosion, biome| {
+                    add_surface_biome(
+                        out,
+                        temperature,
+                        humidity,
+                        continentalness,
+                        erosion,
+                        weirdness,
+                        biome,
+                    );
+                };
+                add(self.coast_continentalness, self.erosions(0, 1), middle);
+                add(self.near_inland_continentalness, self.erosions[0], slope);
+                add(self.mid_to_far_inland(), self.erosions[0], peak);
+                add(
+                    self.near_inland_continentalness,
+                    self.erosions[1],
+                    middle_or_badlands_or_slope,
+                );
+                add(self.mid_to_far_inland(), self.erosions[1], slope);
+                add(self.coast_to_near_inland(), self.erosions(2, 3), middle);
+                add(self.mid_to_far_inland(), self.erosions[2], plateau);
+                add(
+                    self.mid_inland_continentalness,
+                    self.erosions[3],
+                    middle_or_badlands,
+                );
+                add(self.far_inland_continentalness, self.erosions[3], plateau);
+                add(self.coast_to_far_inland(), self.erosions[4], middle);
+                add(
+                    self.coast_to_near_inland(),
+                    self.erosions[5],
+                    windswept_savanna,
+                );
+                add(self.mid_to_far_inland(), self.erosions[5], shattered);
+                add(self.coast_to_far_inland(), self.erosions[6], middle);
+            }
+        }
+    }
+
+    /// Adds the stony shores and swamps shared by the middle and low slices.
+    fn add_shores_and_swamps(&self, out: &mut Output, weirdness: Parameter) {
+        add_surface_biome(
+            out,
+            self.full_range,
+            self.full_range,
+            self.coast_continentalness,
+            self.erosions(0, 2),
+            weirdness,
+            &biomes::STONY_SHORE,
+        );
+        add_surface_biome(
+            out,
+            self.temperatures[1].union(self.temperatures[2]),
+            self.full_range,
+            self.near_to_far_inland(),
+            self.erosions[6],
+            weirdness,
+            &biomes::SWAMP,
+        );
+        add_surface_biome(
+            out,
+            self.temperatures[3].union(self.temperatures[4]),
+            self.full_range,
+            self.near_to_far_inland(),
+            self.erosions[6],
+            weirdness,
+            &biomes::MANGROVE_SWAMP,
+        );
+    }
+
+    fn add_mid_slice(&self, out: &mut Output, weirdness: Parameter) {
+        self.add_shores_and_swamps(out, weirdness);
+        for (t, &temperature) in self.temperatures.iter().enumerate() {
+            for (h, &humidity) in self.humidities.iter().enumerate() {
+                let middle = pick_middle_biome(t, h, weirdness);
+                let middle_or_badlands = pick_middle_biome_or_badlands_if_hot(t, h, weirdness);
+                let middle_or_badlands_or_slope =
+                    pick_middle_biome_or_badlands_if_hot_or_slope_if_cold(t, h, weirdness);
+                let shattered = pick_shattered_biome(t, h, weirdness);
+                let plateau = pick_plateau_biome(t, h, weirdness);
+                let beach = pick_beach_biome(t);
+                let windswept_savanna = maybe_pick_windswept_savanna_biome(t, h, weirdness, middle);
+                let shattered_coast = pick_shattered_coast_biome(t, h, weirdness);
+                let slope = pick_slope_biome(t, h, weirdness);
+
+                let mut add = |continentalness, erosion, biome| {
+                    add_surface_biome(
+                        out,
+                        temperature,
+                        humidity,
+                        continentalness,
+                        erosion,
+                        weirdness,
+                        biome,
+                    );
+                };
+                add(self.near_to_far_inland(), self.erosions[0], slope);
+                add(
+                    self.near_inland_continentalness
+                        .union(self.mid_inland_continentalness),
+                    self.erosions[1],
+                    middle_or_badlands_or_slope,
+                );
+                add(
+                    self.far_inland_continentalness,
+                    self.erosions[1],
+                    if t == 0 { slope } else { plateau },
+                );
+                add(self.near_inland_continentalness, self.erosions[2], middle);
+                add(
+                    self.mid_inland_continentalness,
+                    self.erosions[2],
+                    middle_or_badlands,
+                );
+                add(self.far_inland_continentalness, self.erosions[2], plateau);
+                add(self.coast_to_near_inland(), self.erosions[3], middle);
+                add(
+                    self.mid_to_far_inland(),
+                    self.erosions[3],
+                    middle_or_badlands,
+                );
+                if weirdness.max < 0 {
+                    add(self.coast_continentalness, self.erosions[4], beach);
+                    add(self.near_to_far_inland(), self.erosions[4], middle);
+                } else {
+                    add(self.coast_to_far_inland(), self.erosions[4], middle);
+                }
+                add(
+                    self.coast_continentalness,
+                    self.erosions[5],
+                    shattered_coast,
+                );
+                add(
+                    self.near_inland_continentalness,
+                    self.erosions[5],
+                    windswept_savanna,
+                );
+                add(self.mid_to_far_inland(), self.erosions[5], shattered);
+                if weirdness.max < 0 {
+                    add(self.coast_continentalness, self.erosions[6], beach);
+                } else {
+                    add(self.coast_continentalness, self.erosions[6], middle);
+                }
+                if t == 0 {
+                    add(self.near_to_far_inland(), self.erosions[6], middle);
+                }
+            }
+        }
+    }
+
+    fn add_low_slice(&self, out: &mut Output, weirdness: Parameter) {
+        self.add_shores_and_swamps(out, weirdness);
+        for (t, &temperature) in self.temperatures.iter().enumerate() {
+            for (h, &humidity) in self.humidities.iter().enumerate() {
+                let middle = pick_middle_biome(t, h, weirdness);
+                let middle_or_badlands = pick_middle_biome_or_badlands_if_hot(t, h, weirdness);
+                let middle_or_badlands_or_slope =
+                    pick_middle_biome_or_badlands_if_hot_or_slope_if_cold(t, h, weirdness);
+                let beach = pick_beach_biome(t);
+                let windswept_savanna = maybe_pick_windswept_savanna_biome(t, h, weirdness, middle);
+                let shattered_coast = pick_shattered_coast_biome(t, h, weirdness);
+
+                let mut add = |continentalness, erosion, biome| {
+                    add_surface_biome(
+                        out,
+                        temperature,
+                        humidity,
+                        continentalness,
+                        erosion,
+                        weirdness,
+                        biome,
+                    );
+                };
+                add(
+                    self.near_inland_continentalness,
+                    self.erosions(0, 1),
+                    middle_or_badlands,
+                );
+                add(
+                    self.mid_to_far_inland(),
+                    self.erosions(0, 1),
+                    middle_or_badlands_or_slope,
+                );
+                add(
+                    self.near_inland_continentalness,
+                    self.erosions(2, 3),
+                    middle,
+                );
+                add(
+                    self.mid_to_far_inland(),
+                    self.erosions(2, 3),
+                    middle_or_badlands,
+                );
+                add(self.coast_continentalness, self.erosions(3, 4), beach);
+                add(self.near_to_far_inland(), self.erosions[4], middle);
+                add(
+                    self.coast_continentalness,
+                    self.erosions[5],
+                    shattered_coast,
+                );
+                add(
+                    self.near_inland_continentalness,
+                    self.erosions[5],
+                    windswept_savanna,
+                );
+                add(self.mid_to_far_inland(), self.erosions[5], middle);
+                add(self.coast_continentalness, self.erosions[6], beach);
+                if t == 0 {
+                    add(self.near_to_far_inland(), self.erosions[6], middle);
+                }
+            }
+        }
+    }
+
+    fn add_valleys(&self, out: &mut Output, weirdness: Parameter) {
+        let mut add = |temperature, continentalness, erosion, biome| {
+            add_surface_biome(
+                out,
+                temperature,
+                self.full_range,
+                continentalness,
+                erosion,
+                weirdness,
+                biome,
+            );
+        };
+        let (frozen_coast, unfrozen_coast): (BiomeKey, BiomeKey) = if weirdness.max < 0 {
+            (&biomes::STONY_SHORE, &biomes::STONY_SHORE)
+        } else {
+            (&biomes::FROZEN_RIVER, &biomes::RIVER)
+        };
+        add(
+            self.frozen_range,
+            self.coast_continentalness,
+            self.erosions(0, 1),
+            frozen_coast,
+        );
+        add(
+            self.unfrozen_range,
+            self.coast_continentalness,
+            self.erosions(0, 1),
+            unfrozen_coast,
+        );
+        add(
+            self.frozen_range,
+            self.near_inland_continentalness,
+            self.erosions(0, 1),
+            &biomes::FROZEN_RIVER,
+        );
+        add(
+            self.unfrozen_range,
+            self.near_inland_continentalness,
+            self.erosions(0, 1),
+            &biomes::RIVER,
+        );
+        add(
+            self.frozen_range,
+            self.coast_to_far_inland(),
+            self.erosions(2, 5),
+            &biomes::FROZEN_RIVER,
+        );
+        add(
+            self.unfrozen_range,
+            self.coast_to_far_inland(),
+            self.erosions(2, 5),
+            &biomes::RIVER,
+        );
+        add(
+            self.frozen_range,
+            self.coast_continentalness,
+            self.erosions[6],
+            &biomes::FROZEN_RIVER,
+        );
+        add(
+            self.unfrozen_range,
+            self.coast_continentalness,
+            self.erosions[6],
+            &biomes::RIVER,
+        );
+        let inland = self
+            .inland_continentalness
+            .union(self.far_inland_continentalness);
+        add(
+            self.temperatures[1].union(self.temperatures[2]),
+            inland,
+            self.erosions[6],
+            &biomes::SWAMP,
+        );
+        add(
+            self.temperatures[3].union(self.temperatures[4]),
+            inland,
+            self.erosions[6],
+            &biomes::MANGROVE_SWAMP,
+        );
+        add(
+            self.frozen_range,
+            inland,
+            self.erosions[6],
+            &biomes::FROZEN_RIVER,
+        );
+
+        for (t, &temperature) in self.temperatures.iter().enumerate() {
+            for (h, &humidity) in self.humidities.iter().enumerate() {
+                add_surface_biome(
+                    out,
+                    temperature,
+                    humidity,
+                    self.mid_to_far_inland(),
+                    self.erosions(0, 1),
+                    weirdness,
+                    pick_middle_biome_or_badlands_if_hot(t, h, weirdness),
+                );
+            }
+        }
+    }
+
+    fn add_underground_biomes(&self, out: &mut Output) {
+        add_underground_biome(
+            out,
+            self.full_range,
+            self.full_range,
+            Parameter::span(0.8, 1.0),
+            self.full_range,
+            self.full_range,
+            &biomes::DRIPSTONE_CAVES,
+        );
+        add_underground_biome(
+            out,
+            self.full_range,
+            Parameter::span(0.7, 1.0),
+            self.full_range,
+            self.full_range,
+            self.full_range,
+            &biomes::LUSH_CAVES,
+        );
+        add_bottom_biome(
+            out,
+            self.full_range,
+            self.full_range,
+            self.full_range,
+            self.erosions(0, 1),
+            self.full_range,
+            &biomes::DEEP_DARK,
+        );
+    }
+}
+
+fn pick_middle_biome(temperature: usize, humidity: usize, weirdness: Parameter) -> BiomeKey {
+    if weirdness.max < 0 {
+        MIDDLE_BIOMES[temperature][humidity]
+    } else {
+        MIDDLE_BIOMES_VARIANT[temperature][humidity].unwrap_or(MIDDLE_BIOMES[temperature][humidity])
+    }
+}
+
+fn pick_middle_biome_or_badlands_if_hot(
+    temperature: usize,
+    humidity: usize,
+    weirdness: Parameter,
+) -> BiomeKey {
+    if temperature == 4 {
+        pick_badlands_biome(humidity, weirdness)
+    } else {
+        pick_middle_biome(temperature, humidity, weirdness)
+    }
+}
+
+fn pick_middle_biome_or_badlands_if_hot_or_slope_if_cold(
+    temperature: usize,
+    humidity: usize,
+    weirdness: Parameter,
+) -> BiomeKey {
+    if temperature == 0 {
+        pick_slope_biome(temperature, humidity, weirdness)
+    } else {
+        pick_middle_biome_or_badlands_if_hot(temperature, humidity, weirdness)
+    }
+}
+
+fn maybe_pick_windswept_savanna_biome(
+    temperature: usize,
+    humidity: usize,
+    weirdness: Parameter,
+    biome: BiomeKey,
+) -> BiomeKey {
+    if temperature > 1 && humidity < 4 && weirdness.max >= 0 {
+        &biomes::WINDSWEPT_SAVANNA
+    } else {
+        biome
+    }
+}
+
+fn pick_shattered_coast_biome(
+    temperature: usize,
+    humidity: usize,
+    weirdness: Parameter,
+) -> BiomeKey {
+    let biome = if weirdness.max >= 0 {
+        pick_middle_biome(temperature, humidity, weirdness)
+    } else {
+        pick_beach_biome(temperature)
+    };
+    maybe_pick_windswept_savanna_biome(temperature, humidity, weirdness, biome)
+}
+
+fn pick_beach_biome(temperature: usize) -> BiomeKey {
+    match temperature {
+        0 => &biomes::SNOWY_BEACH,
+        4 => &biomes::DESERT,
+        _ => &biomes::BEACH,
+    }
+}
+
+fn pick_badlands_biome(humidity: usize, weirdness: Parameter) -> BiomeKey {
+    if humidity < 2 {
+        if weirdness.max < 0 {
+            &biomes::BADLANDS
+        } else {
+            &biomes::ERODED_BADLANDS
+        }
+    } else if humidity < 3 {
+        &biomes::BADLANDS
+    } else {
+        &biomes::WOODED_BADLANDS
+    }
+}
+
+fn pick_plateau_biome(temperature: usize, humidity: usize, weirdness: Parameter) -> BiomeKey {
+    if weirdness.max >= 0
+        && let Some(biome) = PLATEAU_BIOMES_VARIANT[temperature][humidity]
+    {
+        return biome;
+    }
+    PLATEAU_BIOMES[temperature][humidity]
+}
+
+fn pick_peak_biome(temperature: usize, humidity: usize, weirdness: Parameter) -> BiomeKey {
+    if temperature <= 2 {
+        if weirdness.max < 0 {
+            &biomes::JAGGED_PEAKS
+        } else {
+            &biomes::FROZEN_PEAKS
+        }
+    } else if temperature == 3 {
+        &biomes::STONY_PEAKS
+    } else {
+        pick_badlands_biome(humidity, weirdness)
+    }
+}
+
+fn pick_slope_biome(temperature: usize, humidity: usize, weirdness: Parameter) -> BiomeKey {
+    if temperature >= 3 {
+        pick_plateau_biome(temperature, humidity, weirdness)
+    } else if humidity <= 1 {
+        &biomes::SNOWY_SLOPES
+    } else {
+        &biomes::GROVE
+    }
+}
+
+fn pick_shattered_biome(temperature: usize, humidity: usize, weirdness: Parameter) -> BiomeKey {
+    SHATTERED_BIOMES[temperature][humidity]
+        .unwrap_or_else(|| pick_middle_biome(temperature, humidity, weirdness))
+}
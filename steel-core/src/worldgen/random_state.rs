@@ -0,0 +1,422 @@
+//! The seeded state of a noise generator.
+
+use std::ptr;
+use std::sync::Arc;
+
+use rustc_hash::FxHashMap;
+use steel_registry::density_function::{
+    CubicSpline, DensityFunction, DensityFunctionRef, NoiseParametersRef,
+    SplineValue as SplineValueDef,
+};
+use steel_registry::noise_settings::NoiseGeneratorSettingsRef;
+use steel_utils::noise::{BlendedNoise, NormalNoise};
+use steel_utils::random::legacy_random::LegacyRandom;
+use steel_utils::random::xoroshiro::Xoroshiro;
+use steel_utils::random::{PositionalRandom, Random, RandomSplitter};
+
+use crate::worldgen::density_function::{
+    DensityGraph, EndIslands, Node, NodeId, SlotCounts, Spline, SplineValue,
+};
+
+/// The nodes of a noise router after it has been bound to a seed.
+#[allow(missing_docs)]
+#[derive(Debug, Clone, Copy)]
+pub struct CompiledRouter {
+    pub barrier_noise: NodeId,
+    pub fluid_level_floodedness_noise: NodeId,
+    pub fluid_level_spread_noise: NodeId,
+    pub lava_noise: NodeId,
+    pub temperature: NodeId,
+    pub vegetation: NodeId,
+    pub continents: NodeId,
+    pub erosion: NodeId,
+    pub depth: NodeId,
+    pub ridges: NodeId,
+    pub preliminary_surface_level: NodeId,
+    pub final_density: NodeId,
+    pub vein_toggle: NodeId,
+    pub vein_ridged: NodeId,
+    pub vein_gap: NodeId,
+}
+
+/// Everything a noise generator derives from the world seed.
+///
+/// Mirrors vanilla's `RandomState`: noises are created once per seed and shared by every
+/// density function that samples them.
+pub struct RandomState {
+    /// The compiled density functions of the router.
+    pub graph: DensityGraph,
+    /// Entry points into [`graph`](Self::graph).
+    pub router: CompiledRouter,
+    /// Seeds the aquifer's per-cell fluid sources.
+    pub aquifer_random: RandomSplitter,
+    /// Seeds ore vein block choices.
+    pub ore_random: RandomSplitter,
+}
+
+impl RandomState {
+    /// Binds the router of `settings` to `seed`.
+    #[must_use]
+    pub fn new(settings: NoiseGeneratorSettingsRef, seed: i64) -> Self {
+        let legacy = settings.use_legacy_random_source;
+        let random = if legacy {
+            LegacyRandom::from_seed(seed as u64).next_positional()
+        } else {
+            Xoroshiro::from_seed(seed as u64).next_positional()
+        };
+        let aquifer_random = random.with_hash_of("minecraft:aquifer").next_positional();
+        let ore_random = random.with_hash_of("minecraft:ore").next_positional();
+
+        let mut compiler = Compiler {
+            seed,
+            legacy,
+            random,
+            nodes: Vec::new(),
+            slots: SlotCounts::default(),
+            compiled: FxHashMap::default(),
+            noises: FxHashMap::default(),
+        };
+
+        let router = &settings.noise_router;
+        let router = CompiledRouter {
+            barrier_noise: compiler.compile(router.barrier_noise),
+            fluid_level_floodedness_noise: compiler.compile(router.fluid_level_floodedness_noise),
+            fluid_level_spread_noise: compiler.compile(router.fluid_level_spread_noise),
+            lava_noise: compiler.compile(router.lava_noise),
+            temperature: compiler.compile(router.temperature),
+            vegetation: compiler.compile(router.vegetation),
+            continents: compiler.compile(router.continents),
+            erosion: compiler.compile(router.erosion),
+            depth: compiler.compile(router.depth),
+            ridges: compiler.compile(router.ridges),
+            preliminary_surface_level: compiler.compile(router.preliminary_surface_level),
+            final_density: compiler.compile(router.final_density),
+            vein_toggle: compiler.compile(router.vein_toggle),
+            vein_ridged: compiler.compile(router.vein_ridged),
+            vein_gap: compiler.compile(router.vein_gap),
+        };
+
+        Self {
+            graph: DensityGraph {
+                nodes: compiler.nodes,
+                slots: compiler.slots,
+            },
+            router,
+            aquifer_random,
+            ore_random,
+        }
+    }
+}
+
+/// Turns static density function trees into a [`DensityGraph`].
+struct Compiler {
+    seed: i64,
+    legacy: bool,
+    random: RandomSplitter,
+    nodes: Vec<Node>,
+    slots: SlotCounts,
+    /// Functions referenced from several places are compiled once.
+    compiled: FxHashMap<*const DensityFunction, NodeId>,
+    noises: FxHashMap<*const (), Arc<NormalNoise>>,
+}
+
+impl Compiler {
+    fn push(&mut self, node: Node) -> NodeId {
+        self.nodes.push(node);
+        self.nodes.len() - 1
+    }
+
+    fn compile(&mut self, function: DensityFunctionRef) -> NodeId {
+        let key = ptr::from_ref(function);
+        if let Some(&id) = self.compiled.get(&key) {
+            return id;
+        }
+        let node = self.compile_node(function);
+        let id = self.push(node);
+        self.compiled.insert(key, id);
+        id
+    }
+
+    fn next_slot(counter: &mut usize) -> usize {
+        *counter += 1;
+        *counter - 1
+    }
+
+    #[allow(clippy::too_many_lines)]
+    fn compile_node(&mut self, function: DensityFunctionRef) -> Node {
+        match function {
+            DensityFunction::Constant(value) => Node::Constant(*value),
+            DensityFunction::Add(a, b) => Node::Add(self.compile(a), self.compile(b)),
+            DensityFunction::Mul(a, b) => Node::Mul(self.compile(a), self.compile(b)),
+            DensityFunction::Min(a, b) => Node::Min(self.compile(a), self.compile(b)),
+            DensityFunction::Max(a, b) => Node::Max(self.compile(a), self.compile(b)),
+            DensityFunction::Abs(argument) => Node::Abs(self.compile(argument)),
+            DensityFunction::Square(argument) => Node::Square(self.compile(argument)),
+            DensityFunction::Cube(argument) => Node::Cube(self.compile(argument)),
+            DensityFunction::HalfNegative(argument) => Node::HalfNegative(self.compile(argument)),
+            DensityFunction::QuarterNegative(argument) => {
+                Node::QuarterNegative(self.compile(argument))
+            }
+            DensityFunction::Squeeze(argument) => Node::Squeeze(self.compile(argument)),
+            DensityFunction::Invert(argument) => Node::Invert(self.compile(argument)),
+            DensityFunction::Clamp { input, min, max } => Node::Clamp {
+                input: self.compile(input),
+                min: *min,
+                max: *max,
+            },
+            DensityFunction::YClampedGradient {
+                from_y,
+                to_y,
+                from_value,
+                to_value,
+            } => Node::YClampedGradient {
+                from_y: f64::from(*from_y),
+                to_y: f64::from(*to_y),
+                from_value: *from_value,
+                to_value: *to_value,
+            },
+            DensityFunction::Noise {
+                noise,
+                xz_scale,
+                y_scale,
+            } => Node::Noise {
+                noise: self.noise(noise),
+                xz_scale: *xz_scale,
+                y_scale: *y_scale,
+            },
+            DensityFunction::ShiftedNoise {
+                noise,
+                xz_scale,
+                y_scale,
+                shift_x,
+                shift_y,
+                shift_z,
+            } => Node::ShiftedNoise {
+                noise: self.noise(noise),
+                xz_scale: *xz_scale,
+                y_scale: *y_scale,
+                shift_x: self.compile(shift_x),
+                shift_y: self.compile(shift_y),
+                shift_z: self.compile(shift_z),
+            },
+            DensityFunction::ShiftA(noise) => Node::ShiftA(self.noise(noise)),
+            DensityFunction::ShiftB(noise) => Node::ShiftB(self.noise(noise)),
+            DensityFunction::Shift(noise) => Node::Shift(self.noise(noise)),
+            DensityFunction::RangeChoice {
+                input,
+                min_inclusive,
+                max_exclusive,
+                when_in_range,
+                when_out_of_range,
+            } => Node::RangeChoice {
+                input: self.compile(input),
+                min_inclusive: *min_inclusive,
+                max_exclusive: *max_exclusive,
+                when_in_range: self.compile(when_in_range),
+                when_out_of_range: self.compile(when_out_of_range),
+            },
+            DensityFunction::WeirdScaledSampler {
+                input,
+                noise,
+                rarity_value_mapper,
+            } => Node::WeirdScaledSampler {
+                input: self.compile(input),
+                noise: self.noise(noise),
+                rarity_value_mapper: *rarity_value_mapper,
+            },
+            DensityFunction::Spline(spline) => Node::Spline(Box::new(self.spline(spline))),
+            DensityFunction::OldBlendedNoise {
+                xz_scale,
+                y_scale,
+                xz_factor,
+                y_factor,
+                smear_scale_multiplier,
+            } => {
+                let noise = if self.legacy {
+                    BlendedNoise::new(
+                        &mut LegacyRandom::from_seed(self.seed as u64),
+                        *xz_scale,
+                        *y_scale,
+                        *xz_factor,
+                        *y_factor,
+                        *smear_scale_multiplier,
+                    )
+                } else {
+                    BlendedNoise::new(
+                        &mut self.random.with_hash_of("minecraft:terrain"),
+                        *xz_scale,
+                        *y_scale,
+                        *xz_factor,
+                        *y_factor,
+                        *smear_scale_multiplier,
+                    )
+                };
+                Node::BlendedNoise(Box::new(noise))
+            }
+            DensityFunction::FindTopSurface {
+                density,
+                upper_bound,
+                lower_bound,
+                cell_height,
+            } => Node::FindTopSurface {
+                density: self.compile(density),
+                upper_bound: self.compile(upper_bound),
+                lower_bound: *lower_bound,
+                cell_height: *cell_height,
+            },
+            DensityFunction::EndIslands => Node::EndIslands(Box::new(EndIslands::new(self.seed))),
+            DensityFunction::Interpolated(argument) => Node::Interpolated {
+                argument: self.compile(argument),
+                slot: Self::next_slot(&mut self.slots.interpolated),
+            },
+            DensityFunction::FlatCache(argument) => Node::FlatCache {
+                argument: self.compile(argument),
+                slot: Self::next_slot(&mut self.slots.flat_cache),
+            },
+            DensityFunction::Cache2d(argument) => Node::Cache2d {
+                argument: self.compile(argument),
+                slot: Self::next_slot(&mut self.slots.cache_2d),
+            },
+            DensityFunction::CacheOnce(argument) | DensityFunction::CacheAllInCell(argument) => {
+                Node::CacheOnce {
+                    argument: self.compile(argument),
+                    slot: Self::next_slot(&mut self.slots.cache_once),
+                }
+            }
+            DensityFunction::BlendAlpha => Node::BlendAlpha,
+            DensityFunction::BlendOffset => Node::BlendOffset,
+            DensityFunction::BlendDensity(argument) => Node::BlendDensity(self.compile(argument)),
+            DensityFunction::Beardifier => Node::Beardifier,
+        }
+    }
+
+    fn spline(&mut self, spline: &'static CubicSpline) -> Spline {
+        let coordinate = self.compile(spline.coordinate);
+        let mut locations = Vec::with_capacity(spline.points.len());
+        let mut values = Vec::with_capacity(spline.points.len());
+        let mut derivatives = Vec::with_capacity(spline.points.len());
+        for point in spline.points {
+            locations.push(point.location);
+            derivatives.push(point.derivative);
+            values.push(match &point.value {
+                SplineValueDef::Constant(value) => SplineValue::Constant(*value),
+                SplineValueDef::Spline(nested) => {
+                    SplineValue::Spline(Box::new(self.spline(nested)))
+                }
+            });
+        }
+        Spline {
+            coordinate,
+            locations: locations.into_boxed_slice(),
+            values: values.into_boxed_slice(),
+            derivatives: derivatives.into_boxed_slice(),
+        }
+    }
+
+    /// Instantiates a noise the way vanilla's `RandomState.getOrCreateNoise` does.
+    fn noise(&mut self, parameters: NoiseParametersRef) -> Arc<NormalNoise> {
+        let key = ptr::from_ref(parameters).cast::<()>();
+        if let Some(noise) = self.noises.get(&key) {
+            return noise.clone();
+        }
+
+        let name = parameters.key.to_string();
+        let noise = if self.legacy {
+            // Legacy worlds keep the pre-1.18 seeding for these three noises.
+            match name.as_str() {
+                "minecraft:temperature" => NormalNoise::new_legacy(
+                    &mut LegacyRandom::from_seed(self.seed as u64),
+                    -7,
+                    &[1.0, 1.0],
+                ),
+                "minecraft:vegetation" => NormalNoise::new_legacy(
+                    &mut LegacyRandom::from_seed(self.seed.wrapping_add(1) as u64),
+                    -7,
+                    &[1.0, 1.0],
+                ),
+                "minecraft:offset" => {
+                    NormalNoise::new(&mut self.random.with_hash_of("minecraft:offset"), 0, &[0.0])
+                }
+                _ => self.normal_noise(&name, parameters),
+            }
+        } else {
+            self.normal_noise(&name, parameters)
+        };
+
+        let noise = Arc::new(noise);
+        self.noises.insert(key, noise.clone());
+        noise
+    }
+
+    fn normal_noise(&self, name: &str, parameters: NoiseParametersRef) -> NormalNoise {
+        NormalNoise::new(
+            &mut self.random.with_hash_of(name),
+            parameters.first_octave,
+            parameters.amplitudes,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use steel_registry::vanilla_noise_settings;
+    use steel_utils::BlockPos;
+
+    use super::RandomState;
+    use crate::worldgen::density_function::SinglePointContext;
+
+    #[test]
+    fn test_same_seed_gives_same_density() {
+        let first = RandomState::new(vanilla_noise_settings::OVERWORLD, 42);
+        let second = RandomState::new(vanilla_noise_settings::OVERWORLD, 42);
+        for pos in [
+            BlockPos::new(0, 64, 0),
+            BlockPos::new(-1234, 12, 5678),
+            BlockPos::new(300, -40, -9000),
+        ] {
+            let a = first
+                .graph
+                .compute(first.router.final_density, pos, &mut SinglePointContext);
+            let b = second
+                .graph
+                .compute(second.router.final_density, pos, &mut SinglePointContext);
+            assert_eq!(a.to_bits(), b.to_bits());
+        }
+    }
+
+    #[test]
+    fn test_overworld_is_solid_below_and_open_above() {
+        let state = RandomState::new(vanilla_noise_settings::OVERWORLD, 0);
+        let density = |y| {
+            state.graph.compute(
+                state.router.final_density,
+                BlockPos::new(100, y, -100),
+                &mut SinglePointContext,
+            )
+        };
+        assert!(density(-60) > 0.0);
+        assert!(density(300) < 0.0);
+    }
+
+    /// Above the noodle caves and below the overworld's lowest section, vanilla's
+    /// `final_density` is the top or bottom slide value alone: `-0.078125` or `0.1171875`,
+    /// scaled by `0.64` and squeezed to `x / 2 - x³ / 24`.
+    #[test]
+    fn test_overworld_slides_match_vanilla() {
+        const TOP: f64 = -0.024_994_791_666_666_67;
+        const BOTTOM: f64 = 0.037_482_421_875;
+
+        let state = RandomState::new(vanilla_noise_settings::OVERWORLD, 0);
+        for (x, z) in [(0, 0), (1000, -1000), (-31_337, 4242)] {
+            let density = |y| {
+                state.graph.compute(
+                    state.router.final_density,
+                    BlockPos::new(x, y, z),
+                    &mut SinglePointContext,
+                )
+            };
+            assert!((density(330) - TOP).abs() < 1e-12, "top at {x}, {z}");
+            assert!((density(-64) - BOTTOM).abs() < 1e-12, "bottom at {x}, {z}");
+        }
+    }
+}
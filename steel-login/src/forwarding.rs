@@ -0,0 +1,207 @@
+//! Proxy forwarding of player information.
+//!
+//! Proxies authenticate players themselves and connect to the server in offline mode, so
+//! without forwarding the server only sees the proxy's address and no skins. BungeeCord
+//! appends the real information to the handshake hostname, while Velocity answers a login
+//! plugin query with it, signed with a secret shared with the server.
+
+use std::io::{Cursor, Error as IoError, Result as IoResult};
+use std::net::IpAddr;
+
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use steel_protocol::packets::login::GameProfileProperty;
+use steel_utils::Identifier;
+use steel_utils::codec::VarInt;
+use steel_utils::serial::{PrefixedRead, ReadFrom};
+use thiserror::Error;
+use uuid::Uuid;
+
+/// The Velocity forwarding version the server asks for. It carries the address, profile
+/// and properties, but no chat signing key.
+pub const VELOCITY_FORWARDING_VERSION: u8 = 1;
+
+/// The size of the HMAC-SHA256 signature in front of Velocity's forwarded information.
+const SIGNATURE_LEN: usize = 32;
+
+/// Gets the login plugin channel Velocity answers with the forwarded information.
+#[must_use]
+pub fn velocity_channel() -> Identifier {
+    Identifier::new("velocity", "player_info")
+}
+
+/// Information a proxy forwarded about a connecting player.
+#[derive(Debug, Clone)]
+pub struct ForwardedPlayer {
+    /// The address the player connected to the proxy from.
+    pub address: IpAddr,
+    /// The player's UUID, as authenticated by the proxy.
+    pub id: Uuid,
+    /// The player's name. BungeeCord doesn't forward it, the hello packet's name is used.
+    pub name: Option<String>,
+    /// The profile properties, which hold the player's skin.
+    pub properties: Vec<GameProfileProperty>,
+}
+
+/// An error that can occur while reading forwarded player information.
+#[derive(Error, Debug)]
+pub enum ForwardingError {
+    /// The proxy didn't forward anything, so the player probably connected directly.
+    #[error("No forwarded player information")]
+    Missing,
+    /// The forwarded information couldn't be parsed.
+    #[error("Malformed forwarded player information")]
+    Malformed,
+    /// The forwarded information wasn't signed with the configured secret.
+    #[error("Forwarded player information has an invalid signature")]
+    InvalidSignature,
+    /// The proxy answered with a forwarding version older than the one asked for.
+    #[error("Unsupported forwarding version {0}")]
+    UnsupportedVersion(i32),
+}
+
+/// Reads the information BungeeCord legacy forwarding appends to the handshake hostname.
+///
+/// The hostname has the form `host\0address\0uuid\0properties`, where the properties are a
+/// JSON array and may be left out.
+///
+/// # Errors
+/// Returns an error if the hostname has nothing appended or can't be parsed.
+pub fn read_bungeecord_hostname(hostname: &str) -> Result<ForwardedPlayer, ForwardingError> {
+    let mut parts = hostname.split('\0').skip(1);
+    let (Some(address), Some(id)) = (parts.next(), parts.next()) else {
+        return Err(ForwardingError::Missing);
+    };
+
+    let properties = match parts.next() {
+        Some(properties) => {
+            serde_json::from_str(properties).map_err(|_| ForwardingError::Malformed)?
+        }
+        None => Vec::new(),
+    };
+
+    Ok(ForwardedPlayer {
+        address: address.parse().map_err(|_| ForwardingError::Malformed)?,
+        id: Uuid::parse_str(id).map_err(|_| ForwardingError::Malformed)?,
+        name: None,
+        properties,
+    })
+}
+
+/// Verifies and reads Velocity's answer to the player info query.
+///
+/// # Errors
+/// Returns an error if the signature doesn't match `secret` or the data can't be parsed.
+pub fn read_velocity_player_info(
+    secret: &[u8],
+    data: &[u8],
+) -> Result<ForwardedPlayer, ForwardingError> {
+    let (signature, info) = data
+        .split_at_checked(SIGNATURE_LEN)
+        .ok_or(ForwardingError::Malformed)?;
+    Hmac::<Sha256>::new_from_slice(secret)
+        .expect("HMAC takes keys of any size")
+        .chain_update(info)
+        .verify_slice(signature)
+        .map_err(|_| ForwardingError::InvalidSignature)?;
+
+    let data = &mut Cursor::new(info);
+    let version = VarInt::read(data)
+        .map_err(|_| ForwardingError::Malformed)?
+        .0;
+    if version < i32::from(VELOCITY_FORWARDING_VERSION) {
+        return Err(ForwardingError::UnsupportedVersion(version));
+    }
+    // Newer versions only add fields after the properties, which aren't read
+    read_velocity_fields(data).map_err(|_| ForwardingError::Malformed)
+}
+
+fn read_velocity_fields(data: &mut Cursor<&[u8]>) -> IoResult<ForwardedPlayer> {
+    let address = String::read_prefixed::<VarInt>(data)?;
+    let id = Uuid::read(data)?;
+    let name = String::read_prefixed_bound::<VarInt>(data, 16)?;
+
+    let count = VarInt::read(data)?.0;
+    let mut properties = Vec::new();
+    for _ in 0..count {
+        properties.push(GameProfileProperty {
+            name: String::read_prefixed::<VarInt>(data)?,
+            value: String::read_prefixed::<VarInt>(data)?,
+            signature: Option::<String>::read_prefixed::<VarInt>(data)?,
+        });
+    }
+
+    Ok(ForwardedPlayer {
+        address: address
+            .parse()
+            .map_err(|_| IoError::other("Invalid forwarded address"))?,
+        id,
+        name: Some(name),
+        properties,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use steel_utils::serial::{PrefixedWrite, WriteTo};
+
+    use super::*;
+
+    #[test]
+    fn bungeecord_hostname_is_split() {
+        let player = read_bungeecord_hostname(
+            "play.example.com\u{0}203.0.113.7\u{0}069a79f444e94726a5befca90e38aaf5\u{0}\
+             [{\"name\":\"textures\",\"value\":\"abc\",\"signature\":\"def\"}]",
+        )
+        .expect("Valid hostname");
+        assert_eq!(
+            player.address,
+            "203.0.113.7".parse::<IpAddr>().expect("Valid ip")
+        );
+        assert_eq!(
+            player.id,
+            Uuid::parse_str("069a79f4-44e9-4726-a5be-fca90e38aaf5").expect("Valid uuid")
+        );
+        assert_eq!(player.properties.len(), 1);
+        assert_eq!(player.properties[0].signature.as_deref(), Some("def"));
+
+        assert!(matches!(
+            read_bungeecord_hostname("play.example.com"),
+            Err(ForwardingError::Missing)
+        ));
+    }
+
+    #[test]
+    fn velocity_info_needs_the_right_secret() {
+        let mut info = Vec::new();
+        VarInt(1).write(&mut info).expect("Write to vec");
+        "198.51.100.2"
+            .write_prefixed::<VarInt>(&mut info)
+            .expect("Write to vec");
+        Uuid::nil().write(&mut info).expect("Write to vec");
+        "Steve"
+            .write_prefixed::<VarInt>(&mut info)
+            .expect("Write to vec");
+        VarInt(0).write(&mut info).expect("Write to vec");
+
+        let mut data = Hmac::<Sha256>::new_from_slice(b"secret")
+            .expect("HMAC takes keys of any size")
+            .chain_update(&info)
+            .finalize()
+            .into_bytes()
+            .to_vec();
+        data.extend_from_slice(&info);
+
+        let player = read_velocity_player_info(b"secret", &data).expect("Valid info");
+        assert_eq!(player.name.as_deref(), Some("Steve"));
+        assert_eq!(
+            player.address,
+            "198.51.100.2".parse::<IpAddr>().expect("Valid ip")
+        );
+
+        assert!(matches!(
+            read_velocity_player_info(b"wrong", &data),
+            Err(ForwardingError::InvalidSignature)
+        ));
+    }
+}
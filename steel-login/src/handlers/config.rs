@@ -108,6 +108,7 @@ impl JavaTcpClient {
                 self.compression.load(),
                 self.network_writer.clone(),
                 self.id,
+                self.address(),
                 player_weak.clone(),
             ));
 
@@ -125,6 +126,7 @@ impl JavaTcpClient {
             .send(ConnectionUpdate::Upgrade(player.connection.clone()))
             .expect("Failed to send connection update");
 
-        self.server.add_player(player);
+        self.server.add_player(player).await;
+        self.server.end_configuration(self.id);
     }
 }
@@ -1,18 +1,30 @@
 //! Login state packet handlers.
 
+use std::sync::{Arc, Weak};
+
 use rsa::Pkcs1v15Encrypt;
 use sha1::Sha1;
 use sha2::Digest;
-use steel_core::{config::STEEL_CONFIG, player::GameProfile};
+use steel_core::{
+    config::{ForwardingMode, STEEL_CONFIG},
+    player::GameProfile,
+    server::permissions::PermissionLevel,
+};
 use steel_protocol::{
-    packets::login::{CHello, CLoginCompression, CLoginFinished, SHello, SKey},
+    packets::login::{
+        CCustomQuery, CHello, CLoginCompression, CLoginFinished, SCustomQueryAnswer, SHello, SKey,
+    },
     utils::ConnectionProtocol,
 };
 use steel_utils::translations;
 use text_components::TextComponent;
 
 use crate::{
-    AuthError, is_valid_player_name, mojang_authenticate, offline_uuid, signed_bytes_be_to_hex,
+    AuthError,
+    forwarding::{
+        ForwardedPlayer, VELOCITY_FORWARDING_VERSION, read_velocity_player_info, velocity_channel,
+    },
+    is_valid_player_name, mojang_authenticate, offline_uuid, signed_bytes_be_to_hex,
     tcp_client::{ConnectionUpdate, JavaTcpClient},
 };
 
@@ -21,12 +33,43 @@ impl JavaTcpClient {
     ///
     /// # Panics
     /// This function will panic if the player name converted to a UUID fails.
-    pub async fn handle_hello(&self, packet: SHello) {
+    pub async fn handle_hello(self: &Arc<Self>, packet: SHello) {
         if !is_valid_player_name(&packet.name) {
             self.kick("Invalid player name".into()).await;
             return;
         }
 
+        // Behind a proxy, the proxy has already authenticated the player and encryption
+        // would only get in its way
+        match STEEL_CONFIG.forwarding() {
+            ForwardingMode::None => {}
+            ForwardingMode::Bungeecord => {
+                let forwarded = self.forwarded_player.lock().take();
+                if let Some(forwarded) = forwarded {
+                    self.finish_forwarded_login(forwarded, packet.name).await;
+                }
+                // Otherwise the handshake was rejected and the client is being kicked
+                return;
+            }
+            ForwardingMode::Velocity => {
+                let query_id = rand::random();
+                self.velocity_query_id.store(query_id);
+                *self.gameprofile.lock().await = Some(GameProfile {
+                    id: packet.profile_id,
+                    name: packet.name,
+                    properties: vec![],
+                    profile_actions: None,
+                });
+                self.send_bare_packet_now(CCustomQuery::new(
+                    query_id,
+                    velocity_channel(),
+                    Box::new([VELOCITY_FORWARDING_VERSION]),
+                ))
+                .await;
+                return;
+            }
+        }
+
         let id = if STEEL_CONFIG.online_mode {
             packet.profile_id
         } else {
@@ -66,7 +109,7 @@ impl JavaTcpClient {
     }
 
     /// Handles the key packet during the login state, used for encryption.
-    pub async fn handle_key(&self, packet: SKey) {
+    pub async fn handle_key(self: &Arc<Self>, packet: SKey) {
         let challenge = self.challenge.load();
 
         let Ok(challenge_response) = self
@@ -144,16 +187,99 @@ impl JavaTcpClient {
             }
         }
 
-        //TODO: Check for duplicate player UUID or name
-
         self.finish_login(profile).await;
     }
 
+    /// Handles the answer to a login plugin query, which is how Velocity forwards players.
+    pub async fn handle_custom_query_answer(self: &Arc<Self>, packet: SCustomQueryAnswer) {
+        if STEEL_CONFIG.forwarding() != ForwardingMode::Velocity
+            || packet.transaction_id != self.velocity_query_id.load()
+        {
+            // Vanilla never sends queries, so there is nothing else to answer
+            return;
+        }
+
+        let Some(data) = packet.payload else {
+            self.kick("This server requires you to connect with Velocity.".into())
+                .await;
+            return;
+        };
+
+        let secret = STEEL_CONFIG
+            .proxy
+            .as_ref()
+            .map_or("", |proxy| proxy.velocity_secret.as_str());
+        let forwarded = match read_velocity_player_info(secret.as_bytes(), &data) {
+            Ok(forwarded) => forwarded,
+            Err(error) => {
+                log::warn!(
+                    "Client {} sent invalid Velocity forwarding: {error}",
+                    self.id
+                );
+                self.kick("Unable to verify player details.".into()).await;
+                return;
+            }
+        };
+
+        let name = self
+            .gameprofile
+            .lock()
+            .await
+            .as_ref()
+            .map(|profile| profile.name.clone())
+            .unwrap_or_default();
+        self.finish_forwarded_login(forwarded, name).await;
+    }
+
+    /// Logs in a player with the information a proxy forwarded about them.
+    async fn finish_forwarded_login(self: &Arc<Self>, forwarded: ForwardedPlayer, name: String) {
+        self.set_forwarded_address(&forwarded);
+
+        let profile = GameProfile {
+            id: forwarded.id,
+            name: forwarded.name.unwrap_or(name),
+            properties: forwarded.properties,
+            profile_actions: None,
+        };
+        if !is_valid_player_name(&profile.name) {
+            self.kick("Invalid player name".into()).await;
+            return;
+        }
+        *self.gameprofile.lock().await = Some(profile.clone());
+
+        self.finish_login(&profile).await;
+    }
+
     /// Finishes the login process and transitions to the configuration state.
     ///
+    /// Players who are banned or not whitelisted are disconnected instead. Other sessions of
+    /// the same player are kicked, and the login waits until they have been saved.
+    ///
     /// # Panics
     /// This function will panic if the compression threshold cannot be converted to an i32.
-    pub async fn finish_login(&self, profile: &GameProfile) {
+    pub async fn finish_login(self: &Arc<Self>, profile: &GameProfile) {
+        let is_op = self.server.permissions.op_level(profile.id) > PermissionLevel::All;
+        if let Some(reason) = self.server.user_lists.check_login(
+            profile.id,
+            &profile.name,
+            self.address().ip(),
+            is_op,
+        ) {
+            self.kick(reason).await;
+            return;
+        }
+
+        let client: Weak<dyn ConfiguringClient> = Arc::downgrade(self);
+        if self
+            .server
+            .start_configuration(self.id, profile.id, &profile.name, client)
+            && !self.server.wait_for_logout(profile.id, &profile.name).await
+        {
+            self.kick(translations::MULTIPLAYER_DISCONNECT_SLOW_LOGIN.msg().into())
+                .await;
+            return;
+        }
+
         if let Some(compression) = STEEL_CONFIG.compression {
             self.send_bare_packet_now(CLoginCompression::new(
                 compression
@@ -1,24 +1,40 @@
 //! Status state packet handlers (server list ping).
 
-use steel_core::config::STEEL_CONFIG;
+use std::sync::Arc;
+
+use rand::seq::SliceRandom;
+use steel_core::{config::STEEL_CONFIG, player::Player};
 use steel_protocol::packets::{
     common::{CPongResponse, SPingRequest},
-    status::{CStatusResponse, Players, Status, Version},
+    status::{CStatusResponse, Players, Sample, Status, Version},
 };
 use steel_registry::packets::CURRENT_MC_PROTOCOL;
+use uuid::Uuid;
 
 use crate::tcp_client::JavaTcpClient;
 
+/// Most players listed in a status response, the same as vanilla.
+const SAMPLE_SIZE: usize = 12;
+/// Name shown for players that opted out of server listings.
+const ANONYMOUS_PLAYER_NAME: &str = "Anonymous Player";
+
 impl JavaTcpClient {
     /// Handles a status request from the client.
     pub async fn handle_status_request(&self) {
+        let players = self.server.get_players();
+        let online = players.len() as i32;
+        let sample = if STEEL_CONFIG.hide_online_players {
+            Vec::new()
+        } else {
+            player_sample(players)
+        };
+
         let res_packet = CStatusResponse::new(Status {
             description: &STEEL_CONFIG.motd,
             players: Some(Players {
                 max: STEEL_CONFIG.max_players.cast_signed(),
-                //TODO: Get online players count
-                online: 0,
-                sample: vec![],
+                online,
+                sample,
             }),
             enforce_secure_chat: STEEL_CONFIG.enforce_secure_chat,
             favicon: load_favicon(),
@@ -38,6 +54,31 @@ impl JavaTcpClient {
     }
 }
 
+/// Picks up to [`SAMPLE_SIZE`] random players to list.
+///
+/// Players that turned off "Allow Server Listings" are listed anonymously, like vanilla's
+/// `MinecraftServer.buildPlayerStatus()`.
+fn player_sample(mut players: Vec<Arc<Player>>) -> Vec<Sample> {
+    players.shuffle(&mut rand::rng());
+    players.truncate(SAMPLE_SIZE);
+    players
+        .iter()
+        .map(|player| {
+            if player.client_information().allows_listing {
+                Sample {
+                    name: player.gameprofile.name.clone(),
+                    id: player.gameprofile.id.to_string(),
+                }
+            } else {
+                Sample {
+                    name: ANONYMOUS_PLAYER_NAME.to_owned(),
+                    id: Uuid::nil().to_string(),
+                }
+            }
+        })
+        .collect()
+}
+
 /// Loads the favicon from config.
 fn load_favicon() -> Option<String> {
     use base64::{Engine, prelude::BASE64_STANDARD};
@@ -3,11 +3,13 @@
 //! This crate manages:
 //! - Pre-play TCP client connection (`JavaTcpClient`)
 //! - Mojang authentication
+//! - Proxy forwarding of player information
 //! - Login, configuration, and status state handlers
 //! - Type re-exports for convenience
 
 mod authentication;
 mod connection;
+mod forwarding;
 mod handlers;
 mod login;
 mod tcp_client;
@@ -15,6 +17,11 @@ mod tcp_client;
 // Authentication
 pub use authentication::{AuthError, TextureError, mojang_authenticate, signed_bytes_be_to_hex};
 
+// Proxy forwarding
+pub use forwarding::{
+    ForwardedPlayer, ForwardingError, read_bungeecord_hostname, read_velocity_player_info,
+};
+
 // Login helpers
 pub use login::{is_valid_player_name, offline_uuid};
 
@@ -11,8 +11,9 @@ use std::{
 };
 
 use crossbeam::atomic::AtomicCell;
+use steel_core::config::{ForwardingMode, STEEL_CONFIG};
 use steel_core::player::{ClientInformation, GameProfile, networking::JavaConnection};
-use steel_core::server::Server;
+use steel_core::server::{ConfiguringClient, Server};
 use steel_protocol::{
     packet_reader::TCPNetworkDecoder,
     packet_traits::{ClientPacket, CompressionInfo, EncodedPacket, ServerPacket},
@@ -21,12 +22,13 @@ use steel_protocol::{
         common::{CDisconnect, SClientInformation, SCustomPayload, SPingRequest},
         config::SSelectKnownPacks,
         handshake::{ClientIntent, SClientIntention},
-        login::{CLoginDisconnect, SHello, SKey},
+        login::{CLoginDisconnect, SCustomQueryAnswer, SHello, SKey},
     },
     utils::{ConnectionProtocol, PacketError, RawPacket},
 };
 use steel_registry::packets::{config, handshake, login as login_packets, status};
-use steel_utils::locks::AsyncMutex;
+use steel_utils::locks::{AsyncMutex, SyncMutex};
+use steel_utils::translations;
 use text_components::{
     TextComponent, content::Resolvable, custom::CustomData, resolving::TextResolutor,
 };
@@ -45,6 +47,12 @@ use tokio::{
 };
 use tokio_util::{sync::CancellationToken, task::TaskTracker};
 
+use crate::forwarding::{ForwardedPlayer, ForwardingError, read_bungeecord_hostname};
+
+/// The longest handshake hostname vanilla accepts. BungeeCord forwarding appends the player
+/// information to the hostname, so it is only enforced without it.
+const MAX_HOSTNAME_LEN: usize = 255;
+
 /// Represents updates to the connection state.
 #[derive(Clone)]
 pub enum ConnectionUpdate {
@@ -80,8 +88,8 @@ pub struct JavaTcpClient {
     pub client_information: AsyncMutex<ClientInformation>,
     /// The current connection state of the client (e.g., Handshaking, Status, Play).
     pub protocol: Arc<AtomicCell<ConnectionProtocol>>,
-    /// The client's IP address.
-    pub address: SocketAddr,
+    /// The client's IP address, replaced by the one a proxy forwards if there is one.
+    address: AtomicCell<SocketAddr>,
     /// A token to cancel the client's operations. Called when the connection is closed.
     pub cancel_token: CancellationToken,
 
@@ -96,6 +104,10 @@ pub struct JavaTcpClient {
     pub server: Arc<Server>,
     /// The challenge sent to the client during login.
     pub challenge: AtomicCell<[u8; 4]>,
+    /// The id of the player info query sent to Velocity during login.
+    pub velocity_query_id: AtomicCell<i32>,
+    /// The player information BungeeCord forwarded in the handshake.
+    pub forwarded_player: SyncMutex<Option<ForwardedPlayer>>,
 
     /// Channel for broadcasting connection state updates.
     pub connection_updates: Sender<ConnectionUpdate>,
@@ -128,7 +140,7 @@ impl JavaTcpClient {
             id,
             gameprofile: AsyncMutex::new(None),
             client_information: AsyncMutex::new(ClientInformation::default()),
-            address,
+            address: AtomicCell::new(address),
             protocol: Arc::new(AtomicCell::new(ConnectionProtocol::Handshake)),
             cancel_token,
 
@@ -139,6 +151,8 @@ impl JavaTcpClient {
             compression: Arc::new(AtomicCell::new(None)),
             server,
             challenge: AtomicCell::new([0; 4]),
+            velocity_query_id: AtomicCell::new(0),
+            forwarded_player: SyncMutex::new(None),
             connection_updates,
             connection_updated: Arc::new(Notify::new()),
             task_tracker,
@@ -147,6 +161,19 @@ impl JavaTcpClient {
         (client, recv, TCPNetworkDecoder::new(BufReader::new(read)))
     }
 
+    /// Gets the client's address.
+    #[must_use]
+    pub fn address(&self) -> SocketAddr {
+        self.address.load()
+    }
+
+    /// Replaces the client's address with the one a proxy forwarded, keeping the port.
+    pub fn set_forwarded_address(&self, forwarded: &ForwardedPlayer) {
+        let mut address = self.address.load();
+        address.set_ip(forwarded.address);
+        self.address.store(address);
+    }
+
     /// Closes the connection.
     pub fn close(&self) {
         self.cancel_token.cancel();
@@ -216,6 +243,7 @@ impl JavaTcpClient {
         let id = self.id;
         let mut connection_updates_recv = self.connection_updates.subscribe();
         let connection_updated = self.connection_updated.clone();
+        let server = self.server.clone();
 
         self.task_tracker.spawn(async move {
             let mut connection = None;
@@ -267,7 +295,7 @@ impl JavaTcpClient {
             drop(connection_updated);
 
             if let Some(connection) = connection {
-                connection.sender(sender_recv).await;
+                connection.sender(sender_recv, server).await;
             }
         });
     }
@@ -340,13 +368,16 @@ impl JavaTcpClient {
                 drop(self_clone);
 
                 connection.listener(reader, server).await;
+            } else {
+                // The client never joined, so a newer login must not wait for it
+                self_clone.server.end_configuration(id);
             }
         });
     }
 
-    async fn process_packet(&self, packet: RawPacket) -> Result<(), PacketError> {
+    async fn process_packet(self: &Arc<Self>, packet: RawPacket) -> Result<(), PacketError> {
         match self.protocol.load() {
-            ConnectionProtocol::Handshake => self.handle_handshake(packet),
+            ConnectionProtocol::Handshake => self.handle_handshake(packet).await,
             ConnectionProtocol::Status => self.handle_status(packet).await,
             ConnectionProtocol::Login => self.handle_login(packet).await,
             ConnectionProtocol::Config => self.handle_config(packet).await,
@@ -355,19 +386,31 @@ impl JavaTcpClient {
     }
 
     /// Handles a handshake packet.
-    pub fn handle_handshake(&self, packet: RawPacket) -> Result<(), PacketError> {
+    pub async fn handle_handshake(&self, packet: RawPacket) -> Result<(), PacketError> {
         let data = &mut Cursor::new(packet.payload.as_slice());
 
         match packet.id {
             handshake::S_INTENTION => {
-                let intent = match SClientIntention::read_packet(data)?.intention {
+                let packet = SClientIntention::read_packet(data)?;
+                let intent = match packet.intention {
                     ClientIntent::STATUS => ConnectionProtocol::Status,
                     ClientIntent::LOGIN | ClientIntent::TRANSFER => ConnectionProtocol::Login,
                 };
                 self.protocol.store(intent);
 
+                let forwarding = STEEL_CONFIG.forwarding();
+                if forwarding != ForwardingMode::Bungeecord
+                    && packet.hostname.len() > MAX_HOSTNAME_LEN
+                {
+                    return Err(PacketError::InvalidProtocol("Handshake".to_string()));
+                }
+
                 if intent != ConnectionProtocol::Status {
                     //TODO: Handle client version being too low or high
+
+                    if forwarding == ForwardingMode::Bungeecord {
+                        self.read_bungeecord_forwarding(&packet.hostname).await;
+                    }
                 }
             }
             id => {
@@ -378,6 +421,32 @@ impl JavaTcpClient {
         Ok(())
     }
 
+    /// Reads the player information BungeeCord appended to the handshake hostname.
+    async fn read_bungeecord_forwarding(&self, hostname: &str) {
+        match read_bungeecord_hostname(hostname) {
+            Ok(forwarded) => {
+                self.set_forwarded_address(&forwarded);
+                *self.forwarded_player.lock() = Some(forwarded);
+            }
+            Err(ForwardingError::Missing) => {
+                self.kick(
+                    "If you wish to use IP forwarding, please enable it in your BungeeCord \
+                     config as well!"
+                        .into(),
+                )
+                .await;
+            }
+            Err(error) => {
+                log::warn!(
+                    "Client {} sent invalid BungeeCord forwarding: {error}",
+                    self.id
+                );
+                self.kick(translations::MULTIPLAYER_DISCONNECT_GENERIC.msg().into())
+                    .await;
+            }
+        }
+    }
+
     /// Handles a status packet.
     pub async fn handle_status(&self, packet: RawPacket) -> Result<(), PacketError> {
         let data = &mut Cursor::new(packet.payload.as_slice());
@@ -396,12 +465,16 @@ impl JavaTcpClient {
     }
 
     /// Handles a login packet.
-    pub async fn handle_login(&self, packet: RawPacket) -> Result<(), PacketError> {
+    pub async fn handle_login(self: &Arc<Self>, packet: RawPacket) -> Result<(), PacketError> {
         let data = &mut Cursor::new(packet.payload.as_slice());
 
         match packet.id {
             login_packets::S_HELLO => self.handle_hello(SHello::read_packet(data)?).await,
             login_packets::S_KEY => self.handle_key(SKey::read_packet(data)?).await,
+            login_packets::S_CUSTOM_QUERY_ANSWER => {
+                self.handle_custom_query_answer(SCustomQueryAnswer::read_packet(data)?)
+                    .await;
+            }
             login_packets::S_LOGIN_ACKNOWLEDGED => {
                 self.handle_login_acknowledged().await;
             }
@@ -453,6 +526,13 @@ impl JavaTcpClient {
     }
 }
 
+impl ConfiguringClient for JavaTcpClient {
+    fn disconnect(self: Arc<Self>, reason: TextComponent) {
+        let task_tracker = self.task_tracker.clone();
+        task_tracker.spawn(async move { self.kick(reason).await });
+    }
+}
+
 impl TextResolutor for JavaTcpClient {
     fn resolve_content(&self, _resolvable: &Resolvable) -> TextComponent {
         TextComponent::new()
@@ -2,7 +2,8 @@
 
 use steel_macros::ClientPacket;
 use steel_registry::packets::play::C_ADD_ENTITY;
-use steel_utils::codec::VarInt;
+use steel_utils::codec::{LpVec3, VarInt};
+use steel_utils::math::Vector3;
 use steel_utils::serial::WriteTo;
 use uuid::Uuid;
 
@@ -22,6 +23,8 @@ pub struct CAddEntity {
     pub y: f64,
     /// Z position
     pub z: f64,
+    /// Initial velocity
+    pub velocity: Vector3<f64>,
     /// Pitch (vertical rotation) as angle byte
     pub x_rot: i8,
     /// Yaw (horizontal rotation) as angle byte
@@ -40,8 +43,7 @@ impl WriteTo for CAddEntity {
         writer.write_all(&self.x.to_be_bytes())?;
         writer.write_all(&self.y.to_be_bytes())?;
         writer.write_all(&self.z.to_be_bytes())?;
-        // Velocity as LpVec3 (zero velocity = single 0 byte)
-        writer.write_all(&[0u8])?;
+        LpVec3(self.velocity).write(writer)?;
         self.x_rot.write(writer)?;
         self.y_rot.write(writer)?;
         self.head_y_rot.write(writer)?;
@@ -70,6 +72,7 @@ impl CAddEntity {
             x,
             y,
             z,
+            velocity: Vector3::default(),
             x_rot: ((pitch / 360.0) * 256.0) as i8,
             y_rot: ((yaw / 360.0) * 256.0) as i8,
             head_y_rot: ((yaw / 360.0) * 256.0) as i8,
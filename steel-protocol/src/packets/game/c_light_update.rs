@@ -0,0 +1,20 @@
+//! Packet for updating the light of a chunk column.
+
+use steel_macros::{ClientPacket, WriteTo};
+use steel_registry::packets::play::C_LIGHT_UPDATE;
+
+use crate::packets::game::LightUpdatePacketData;
+
+/// Updates the sky and block light of some sections in a chunk column.
+#[derive(ClientPacket, WriteTo, Clone, Debug)]
+#[packet_id(Play = C_LIGHT_UPDATE)]
+pub struct CLightUpdate {
+    /// The chunk X coordinate
+    #[write(as = VarInt)]
+    pub x: i32,
+    /// The chunk Z coordinate
+    #[write(as = VarInt)]
+    pub z: i32,
+    /// The light data of the changed sections
+    pub light_data: LightUpdatePacketData,
+}
@@ -0,0 +1,22 @@
+use steel_macros::{ClientPacket, WriteTo};
+use steel_registry::packets::play::C_RESPAWN;
+
+use crate::packets::game::CommonPlayerSpawnInfo;
+
+/// Flags for which player data the client keeps across a respawn.
+/// These match vanilla Minecraft's `ClientboundRespawnPacket` flags.
+pub mod respawn_data_flags {
+    pub const KEEP_ATTRIBUTE_MODIFIERS: u8 = 0x01;
+    pub const KEEP_ENTITY_DATA: u8 = 0x02;
+    pub const KEEP_ALL_DATA: u8 = KEEP_ATTRIBUTE_MODIFIERS | KEEP_ENTITY_DATA;
+}
+
+/// Sent by the server to move the player into another dimension, or to respawn them.
+/// The client drops all chunks and entities and waits for the new world.
+#[derive(ClientPacket, WriteTo, Clone, Debug)]
+#[packet_id(Play = C_RESPAWN)]
+pub struct CRespawn {
+    pub common_player_spawn_info: CommonPlayerSpawnInfo,
+    /// Bitfield of [`respawn_data_flags`]
+    pub data_to_keep: u8,
+}
@@ -0,0 +1,15 @@
+//! Packet for updating an entity's velocity.
+
+use steel_macros::{ClientPacket, WriteTo};
+use steel_registry::packets::play::C_SET_ENTITY_MOTION;
+use steel_utils::codec::LpVec3;
+
+/// Sets an entity's velocity (delta movement per tick).
+#[derive(ClientPacket, WriteTo, Clone, Debug)]
+#[packet_id(Play = C_SET_ENTITY_MOTION)]
+pub struct CSetEntityMotion {
+    #[write(as = VarInt)]
+    pub entity_id: i32,
+    /// The new velocity, in blocks per tick
+    pub velocity: LpVec3,
+}
@@ -0,0 +1,16 @@
+use steel_macros::{ClientPacket, WriteTo};
+use steel_registry::packets::play::C_SET_EXPERIENCE;
+
+/// Packet sent to a player to update their experience bar and level.
+#[derive(WriteTo, ClientPacket, Clone, Debug)]
+#[packet_id(Play = C_SET_EXPERIENCE)]
+pub struct CSetExperience {
+    /// How full the experience bar is, from 0 to 1.
+    pub experience_progress: f32,
+    /// The player's experience level.
+    #[write(as = VarInt)]
+    pub experience_level: i32,
+    /// The total experience points the player has collected.
+    #[write(as = VarInt)]
+    pub total_experience: i32,
+}
@@ -0,0 +1,14 @@
+use steel_macros::{ClientPacket, WriteTo};
+use steel_registry::packets::play::C_SET_TIME;
+
+/// Packet sent to clients to synchronize the time of their current world.
+#[derive(WriteTo, ClientPacket, Clone, Debug)]
+#[packet_id(Play = C_SET_TIME)]
+pub struct CSetTime {
+    /// Total ticks the world has run for.
+    pub game_time: i64,
+    /// The time of day in ticks, counting up across days.
+    pub day_time: i64,
+    /// Whether the client should advance the time of day on its own between syncs.
+    pub tick_day_time: bool,
+}
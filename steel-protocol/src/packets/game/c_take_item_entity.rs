@@ -0,0 +1,21 @@
+//! Packet for playing the item pickup animation.
+
+use steel_macros::{ClientPacket, WriteTo};
+use steel_registry::packets::play::C_TAKE_ITEM_ENTITY;
+
+/// Plays the animation of an entity collecting an item (or XP orb / arrow).
+///
+/// This doesn't remove the item entity, a `CRemoveEntities` must still be sent.
+#[derive(ClientPacket, WriteTo, Clone, Debug)]
+#[packet_id(Play = C_TAKE_ITEM_ENTITY)]
+pub struct CTakeItemEntity {
+    /// The entity ID of the collected item
+    #[write(as = VarInt)]
+    pub item_id: i32,
+    /// The entity ID of the collector
+    #[write(as = VarInt)]
+    pub player_id: i32,
+    /// The number of items picked up
+    #[write(as = VarInt)]
+    pub amount: i32,
+}
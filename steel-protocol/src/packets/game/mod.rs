@@ -19,6 +19,7 @@ mod c_forget_level_chunk;
 mod c_game_event;
 mod c_level_chunk_with_light;
 mod c_level_event;
+mod c_light_update;
 mod c_login;
 mod c_move_entity;
 mod c_open_screen;
@@ -29,17 +30,22 @@ mod c_player_info_update;
 mod c_player_position;
 mod c_remove_entities;
 mod c_remove_player_info;
+mod c_respawn;
 mod c_rotate_head;
 mod c_section_blocks_update;
 mod c_set_chunk_cache_radius;
 mod c_set_chunk_center;
 mod c_set_cursor_item;
 mod c_set_entity_data;
+mod c_set_entity_motion;
+mod c_set_experience;
 mod c_set_held_slot;
+mod c_set_time;
 mod c_sound;
 mod c_system_chat;
 mod c_system_chat_message;
 mod c_tab_list;
+mod c_take_item_entity;
 mod c_ticking_state;
 mod c_ticking_step;
 mod chat_session_data;
@@ -80,7 +86,10 @@ pub use c_block_update::CBlockUpdate;
 pub use c_chunk_batch_finished::CChunkBatchFinished;
 pub use c_chunk_batch_start::CChunkBatchStart;
 pub use c_command_suggestions::{CCommandSuggestions, SuggestionEntry};
-pub use c_commands::{ArgumentType, CCommands, CommandNode, CommandNodeInfo, SuggestionType};
+pub use c_commands::{
+    ArgumentStringTypeBehavior, ArgumentType, CCommands, CommandNode, CommandNodeInfo,
+    SuggestionType,
+};
 pub use c_container_close::CContainerClose;
 pub use c_container_set_content::CContainerSetContent;
 pub use c_container_set_data::CContainerSetData;
@@ -95,6 +104,7 @@ pub use c_level_chunk_with_light::{
     LightUpdatePacketData,
 };
 pub use c_level_event::CLevelEvent;
+pub use c_light_update::CLightUpdate;
 pub use c_login::CLogin;
 pub use c_login::CommonPlayerSpawnInfo;
 pub use c_move_entity::{
@@ -110,17 +120,22 @@ pub use c_player_info_update::{
 pub use c_player_position::{CPlayerPosition, RelativeMovement};
 pub use c_remove_entities::CRemoveEntities;
 pub use c_remove_player_info::CRemovePlayerInfo;
+pub use c_respawn::{CRespawn, respawn_data_flags};
 pub use c_rotate_head::CRotateHead;
 pub use c_section_blocks_update::{BlockChange, CSectionBlocksUpdate};
 pub use c_set_chunk_cache_radius::CSetChunkCacheRadius;
 pub use c_set_chunk_center::CSetChunkCenter;
 pub use c_set_cursor_item::CSetCursorItem;
 pub use c_set_entity_data::CSetEntityData;
+pub use c_set_entity_motion::CSetEntityMotion;
+pub use c_set_experience::CSetExperience;
 pub use c_set_held_slot::CSetHeldSlot;
+pub use c_set_time::CSetTime;
 pub use c_sound::{CSound, SoundSource};
 pub use c_system_chat::CSystemChat;
 pub use c_system_chat_message::CSystemChatMessage;
 pub use c_tab_list::CTabList;
+pub use c_take_item_entity::CTakeItemEntity;
 pub use c_ticking_state::CTickingState;
 pub use c_ticking_step::CTickingStep;
 pub use chat_session_data::ProtocolRemoteChatSessionData;
@@ -12,7 +12,10 @@ pub enum ClientIntent {
 pub struct SClientIntention {
     #[read(as = VarInt)]
     pub protocol_version: i32,
-    #[read(as = Prefixed(VarInt), bound = 255)]
+    /// The address the client connected with. Vanilla limits it to 255 characters, but
+    /// BungeeCord forwarding appends player information to it, so that limit is checked
+    /// by the login handler instead.
+    #[read(as = Prefixed(VarInt), bound = 32767)]
     pub hostname: String,
     pub port: u16,
     pub intention: ClientIntent,
@@ -0,0 +1,42 @@
+use std::io::Write;
+
+use steel_macros::ClientPacket;
+use steel_registry::packets::login::C_CUSTOM_QUERY;
+use steel_utils::Identifier;
+use steel_utils::codec::VarInt;
+use steel_utils::serial::WriteTo;
+
+/// Asks the client a plugin specific question during login.
+///
+/// Vanilla clients answer every query as not understood, but proxies like Velocity
+/// answer their own channels.
+#[derive(ClientPacket, Clone, Debug)]
+#[packet_id(Login = C_CUSTOM_QUERY)]
+pub struct CCustomQuery {
+    /// The id the answer will carry, so it can be matched to this query.
+    pub transaction_id: i32,
+    /// The channel the query is sent on.
+    pub identifier: Identifier,
+    /// The query data, which takes up the rest of the packet.
+    pub payload: Box<[u8]>,
+}
+
+impl CCustomQuery {
+    /// Creates a new custom query packet.
+    #[must_use]
+    pub fn new(transaction_id: i32, identifier: Identifier, payload: Box<[u8]>) -> Self {
+        Self {
+            transaction_id,
+            identifier,
+            payload,
+        }
+    }
+}
+
+impl WriteTo for CCustomQuery {
+    fn write(&self, writer: &mut impl Write) -> std::io::Result<()> {
+        VarInt(self.transaction_id).write(writer)?;
+        self.identifier.write(writer)?;
+        writer.write_all(&self.payload)
+    }
+}
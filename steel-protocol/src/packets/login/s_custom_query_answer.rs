@@ -0,0 +1,31 @@
+use std::io::{Cursor, Read};
+
+use steel_macros::ServerPacket;
+use steel_utils::codec::VarInt;
+use steel_utils::serial::ReadFrom;
+
+/// The client's answer to a [`CCustomQuery`](super::CCustomQuery).
+#[derive(ServerPacket, Clone, Debug)]
+pub struct SCustomQueryAnswer {
+    /// The id of the query this answers.
+    pub transaction_id: i32,
+    /// The answer data, or `None` if the client didn't understand the query.
+    pub payload: Option<Vec<u8>>,
+}
+
+impl ReadFrom for SCustomQueryAnswer {
+    fn read(data: &mut Cursor<&[u8]>) -> std::io::Result<Self> {
+        let transaction_id = VarInt::read(data)?.0;
+        let payload = if bool::read(data)? {
+            let mut payload = Vec::new();
+            data.read_to_end(&mut payload)?;
+            Some(payload)
+        } else {
+            None
+        };
+        Ok(Self {
+            transaction_id,
+            payload,
+        })
+    }
+}
@@ -10,6 +10,7 @@ mod chat_types;
 mod chicken_variants;
 mod cow_variants;
 mod damage_types;
+mod density_functions;
 mod dialog_tags;
 mod dialogs;
 mod dimension_types;
@@ -27,6 +28,8 @@ mod jukebox_songs;
 mod level_events;
 mod loot_tables;
 mod menu_types;
+mod noise_parameters;
+mod noise_settings;
 mod packets;
 mod painting_variants;
 mod pig_variants;
@@ -85,6 +88,9 @@ const GAME_RULES: &str = "game_rules";
 const LEVEL_EVENTS: &str = "level_events";
 const SOUND_EVENTS: &str = "sound_events";
 const SOUND_TYPES: &str = "sound_types";
+const NOISE_PARAMETERS: &str = "noise_parameters";
+const DENSITY_FUNCTIONS: &str = "density_functions";
+const NOISE_SETTINGS: &str = "noise_settings";
 
 pub fn main() {
     // Rerun build script when any file in the build/ directory changes
@@ -135,6 +141,9 @@ pub fn main() {
         (level_events::build(), LEVEL_EVENTS),
         (sound_events::build(), SOUND_EVENTS),
         (sound_types::build(), SOUND_TYPES),
+        (noise_parameters::build(), NOISE_PARAMETERS),
+        (density_functions::build(), DENSITY_FUNCTIONS),
+        (noise_settings::build(), NOISE_SETTINGS),
     ];
 
     // Track which files we're generating this run
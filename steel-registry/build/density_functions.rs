@@ -0,0 +1,339 @@
+use std::{fs, path::Path};
+
+use heck::ToShoutySnakeCase;
+use proc_macro2::{Ident, Span, TokenStream};
+use quote::quote;
+use serde_json::{Map, Value};
+
+use crate::noise_parameters::noise_ident;
+
+const DENSITY_FUNCTION_DIR: &str =
+    "build_assets/builtin_datapacks/minecraft/data/minecraft/worldgen/density_function";
+
+/// The ident of the static generated for a `worldgen/density_function` file.
+fn density_function_ident(name: &str) -> Ident {
+    let name = name.strip_prefix("minecraft:").unwrap_or(name);
+    Ident::new(
+        &name.replace('/', "_").to_shouty_snake_case(),
+        Span::call_site(),
+    )
+}
+
+/// Turns density function JSON into static trees.
+///
+/// Every inline node gets its own private static so that children can be referenced with
+/// `&'static` pointers. References to density function files point at the public statics
+/// in `vanilla_density_functions`.
+pub(crate) struct DensityFunctionWriter {
+    prefix: &'static str,
+    counter: usize,
+    statics: TokenStream,
+}
+
+impl DensityFunctionWriter {
+    pub(crate) fn new(prefix: &'static str) -> Self {
+        Self {
+            prefix,
+            counter: 0,
+            statics: TokenStream::new(),
+        }
+    }
+
+    /// The private statics created so far.
+    pub(crate) fn into_statics(self) -> TokenStream {
+        self.statics
+    }
+
+    fn next_ident(&mut self, kind: &str) -> Ident {
+        self.counter += 1;
+        Ident::new(
+            &format!("{}_{}_{}", self.prefix, kind, self.counter),
+            Span::call_site(),
+        )
+    }
+
+    /// Returns an expression of type `&'static DensityFunction` for `value`.
+    pub(crate) fn reference(&mut self, value: &Value) -> TokenStream {
+        if let Value::String(name) = value {
+            let ident = density_function_ident(name);
+            return quote! { &crate::vanilla_density_functions::#ident };
+        }
+        let expr = self.expr(value);
+        let ident = self.next_ident("DF");
+        self.statics.extend(quote! {
+            static #ident: DensityFunction = #expr;
+        });
+        quote! { &#ident }
+    }
+
+    /// Returns an expression of type `DensityFunction` for an inline `value`.
+    pub(crate) fn expr(&mut self, value: &Value) -> TokenStream {
+        let object = match value {
+            Value::Number(number) => {
+                let value = number.as_f64().unwrap();
+                return quote! { DensityFunction::Constant(#value) };
+            }
+            Value::Object(object) => object,
+            _ => panic!("Unsupported density function: {value}"),
+        };
+
+        let ty = object["type"].as_str().unwrap();
+        let ty = ty.strip_prefix("minecraft:").unwrap_or(ty);
+        match ty {
+            "constant" => {
+                let value = f64_field(object, "argument");
+                quote! { DensityFunction::Constant(#value) }
+            }
+            "add" | "mul" | "min" | "max" => {
+                let variant = variant_ident(ty);
+                let argument1 = self.reference(&object["argument1"]);
+                let argument2 = self.reference(&object["argument2"]);
+                quote! { DensityFunction::#variant(#argument1, #argument2) }
+            }
+            "abs" | "square" | "cube" | "half_negative" | "quarter_negative" | "squeeze"
+            | "invert" | "interpolated" | "flat_cache" | "cache_2d" | "cache_once"
+            | "cache_all_in_cell" | "blend_density" => {
+                let variant = variant_ident(ty);
+                let argument = self.reference(&object["argument"]);
+                quote! { DensityFunction::#variant(#argument) }
+            }
+            "blend_alpha" | "blend_offset" | "beardifier" | "end_islands" => {
+                let variant = variant_ident(ty);
+                quote! { DensityFunction::#variant }
+            }
+            "clamp" => {
+                let input = self.reference(&object["input"]);
+                let min = f64_field(object, "min");
+                let max = f64_field(object, "max");
+                quote! { DensityFunction::Clamp { input: #input, min: #min, max: #max } }
+            }
+            "y_clamped_gradient" => {
+                let from_y = i32_field(object, "from_y");
+                let to_y = i32_field(object, "to_y");
+                let from_value = f64_field(object, "from_value");
+                let to_value = f64_field(object, "to_value");
+                quote! {
+                    DensityFunction::YClampedGradient {
+                        from_y: #from_y,
+                        to_y: #to_y,
+                        from_value: #from_value,
+                        to_value: #to_value,
+                    }
+                }
+            }
+            "noise" => {
+                let noise = noise_reference(&object["noise"]);
+                let xz_scale = f64_field(object, "xz_scale");
+                let y_scale = f64_field(object, "y_scale");
+                quote! {
+                    DensityFunction::Noise { noise: #noise, xz_scale: #xz_scale, y_scale: #y_scale }
+                }
+            }
+            "shifted_noise" => {
+                let noise = noise_reference(&object["noise"]);
+                let xz_scale = f64_field(object, "xz_scale");
+                let y_scale = f64_field(object, "y_scale");
+                let shift_x = self.reference(&object["shift_x"]);
+                let shift_y = self.reference(&object["shift_y"]);
+                let shift_z = self.reference(&object["shift_z"]);
+                quote! {
+                    DensityFunction::ShiftedNoise {
+                        noise: #noise,
+                        xz_scale: #xz_scale,
+                        y_scale: #y_scale,
+                        shift_x: #shift_x,
+                        shift_y: #shift_y,
+                        shift_z: #shift_z,
+                    }
+                }
+            }
+            "shift_a" | "shift_b" | "shift" => {
+                let variant = variant_ident(ty);
+                let noise = noise_reference(&object["argument"]);
+                quote! { DensityFunction::#variant(#noise) }
+            }
+            "range_choice" => {
+                let input = self.reference(&object["input"]);
+                let min_inclusive = f64_field(object, "min_inclusive");
+                let max_exclusive = f64_field(object, "max_exclusive");
+                let when_in_range = self.reference(&object["when_in_range"]);
+                let when_out_of_range = self.reference(&object["when_out_of_range"]);
+                quote! {
+                    DensityFunction::RangeChoice {
+                        input: #input,
+                        min_inclusive: #min_inclusive,
+                        max_exclusive: #max_exclusive,
+                        when_in_range: #when_in_range,
+                        when_out_of_range: #when_out_of_range,
+                    }
+                }
+            }
+            "weird_scaled_sampler" => {
+                let input = self.reference(&object["input"]);
+                let noise = noise_reference(&object["noise"]);
+                let mapper = match object["rarity_value_mapper"].as_str().unwrap() {
+                    "type_1" => quote! { RarityValueMapper::Tunnels },
+                    "type_2" => quote! { RarityValueMapper::Caves },
+                    other => panic!("Unknown rarity value mapper {other}"),
+                };
+                quote! {
+                    DensityFunction::WeirdScaledSampler {
+                        input: #input,
+                        noise: #noise,
+                        rarity_value_mapper: #mapper,
+                    }
+                }
+            }
+            "spline" => {
+                let spline = self.spline(&object["spline"]);
+                quote! { DensityFunction::Spline(#spline) }
+            }
+            "old_blended_noise" => {
+                let xz_scale = f64_field(object, "xz_scale");
+                let y_scale = f64_field(object, "y_scale");
+                let xz_factor = f64_field(object, "xz_factor");
+                let y_factor = f64_field(object, "y_factor");
+                let smear_scale_multiplier = f64_field(object, "smear_scale_multiplier");
+                quote! {
+                    DensityFunction::OldBlendedNoise {
+                        xz_scale: #xz_scale,
+                        y_scale: #y_scale,
+                        xz_factor: #xz_factor,
+                        y_factor: #y_factor,
+                        smear_scale_multiplier: #smear_scale_multiplier,
+                    }
+                }
+            }
+            "find_top_surface" => {
+                let density = self.reference(&object["density"]);
+                let upper_bound = self.reference(&object["upper_bound"]);
+                let lower_bound = i32_field(object, "lower_bound");
+                let cell_height = i32_field(object, "cell_height");
+                quote! {
+                    DensityFunction::FindTopSurface {
+                        density: #density,
+                        upper_bound: #upper_bound,
+                        lower_bound: #lower_bound,
+                        cell_height: #cell_height,
+                    }
+                }
+            }
+            other => panic!("Unknown density function type {other}"),
+        }
+    }
+
+    /// Returns an expression of type `&'static CubicSpline` for a spline object.
+    fn spline(&mut self, value: &Value) -> TokenStream {
+        let coordinate = self.reference(&value["coordinate"]);
+        let mut points = Vec::new();
+        for point in value["points"].as_array().unwrap() {
+            let location = point["location"].as_f64().unwrap() as f32;
+            let derivative = point["derivative"].as_f64().unwrap() as f32;
+            let point_value = match &point["value"] {
+                Value::Number(number) => {
+                    let number = number.as_f64().unwrap() as f32;
+                    quote! { SplineValue::Constant(#number) }
+                }
+                nested => {
+                    let nested = self.spline(nested);
+                    quote! { SplineValue::Spline(#nested) }
+                }
+            };
+            points.push(quote! {
+                SplinePoint {
+                    location: #location,
+                    value: #point_value,
+                    derivative: #derivative,
+                }
+            });
+        }
+
+        let ident = self.next_ident("SPLINE");
+        self.statics.extend(quote! {
+            static #ident: CubicSpline = CubicSpline {
+                coordinate: #coordinate,
+                points: &[#(#points),*],
+            };
+        });
+        quote! { &#ident }
+    }
+}
+
+/// The imports every file containing density function trees needs.
+pub(crate) fn imports() -> TokenStream {
+    quote! {
+        use crate::density_function::{
+            CubicSpline, DensityFunction, RarityValueMapper, SplinePoint, SplineValue,
+        };
+    }
+}
+
+fn variant_ident(ty: &str) -> Ident {
+    let name = match ty {
+        "cache_2d" => "Cache2d".to_string(),
+        _ => ty
+            .split('_')
+            .map(|part| {
+                let mut chars = part.chars();
+                chars.next().unwrap().to_uppercase().chain(chars).collect::<String>()
+            })
+            .collect(),
+    };
+    Ident::new(&name, Span::call_site())
+}
+
+fn noise_reference(value: &Value) -> TokenStream {
+    let ident = noise_ident(value.as_str().expect("inline noise parameters are not supported"));
+    quote! { &crate::vanilla_noise_parameters::#ident }
+}
+
+fn f64_field(object: &Map<String, Value>, key: &str) -> f64 {
+    object[key]
+        .as_f64()
+        .unwrap_or_else(|| panic!("Missing number {key} in {object:?}"))
+}
+
+fn i32_field(object: &Map<String, Value>, key: &str) -> i32 {
+    object[key]
+        .as_i64()
+        .unwrap_or_else(|| panic!("Missing integer {key} in {object:?}")) as i32
+}
+
+fn collect_files(dir: &Path, prefix: &str, files: &mut Vec<(String, Value)>) {
+    for entry in fs::read_dir(dir).unwrap() {
+        let path = entry.unwrap().path();
+        let stem = path.file_stem().unwrap().to_str().unwrap();
+        if path.is_dir() {
+            collect_files(&path, &format!("{prefix}{stem}/"), files);
+        } else if path.extension().and_then(|s| s.to_str()) == Some("json") {
+            let name = format!("{prefix}{stem}");
+            let content = fs::read_to_string(&path).unwrap();
+            let value = serde_json::from_str(&content)
+                .unwrap_or_else(|e| panic!("Failed to parse density function {}: {}", name, e));
+            files.push((name, value));
+        }
+    }
+}
+
+pub(crate) fn build() -> TokenStream {
+    println!("cargo:rerun-if-changed={DENSITY_FUNCTION_DIR}/");
+
+    let mut files = Vec::new();
+    collect_files(Path::new(DENSITY_FUNCTION_DIR), "", &mut files);
+    files.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let mut writer = DensityFunctionWriter::new("INLINE");
+    let mut stream = TokenStream::new();
+    stream.extend(imports());
+
+    for (name, value) in &files {
+        let ident = density_function_ident(name);
+        let expr = writer.expr(value);
+        stream.extend(quote! {
+            pub static #ident: DensityFunction = #expr;
+        });
+    }
+
+    stream.extend(writer.into_statics());
+    stream
+}
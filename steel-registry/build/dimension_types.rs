@@ -43,6 +43,8 @@ struct DimensionAttributes {
     respawn_anchor_works: Option<bool>,
     #[serde(rename = "minecraft:gameplay/can_start_raid")]
     can_start_raid: Option<bool>,
+    #[serde(rename = "minecraft:gameplay/fast_lava")]
+    fast_lava: Option<bool>,
     #[serde(rename = "minecraft:visual/cloud_height")]
     cloud_height: Option<f64>,
 }
@@ -161,6 +163,7 @@ pub(crate) fn build() -> TokenStream {
             .respawn_anchor_works
             .unwrap_or(false);
         let has_raids = dimension_type.attributes.can_start_raid.unwrap_or(true);
+        let fast_lava = dimension_type.attributes.fast_lava.unwrap_or(false);
         let cloud_height = generate_option(
             &dimension_type.attributes.cloud_height.map(|h| h as i32),
             |h| quote! { #h },
@@ -191,6 +194,7 @@ pub(crate) fn build() -> TokenStream {
                 ambient_light: #ambient_light,
                 cloud_height: #cloud_height,
                 has_raids: #has_raids,
+                fast_lava: #fast_lava,
                 monster_spawn_light_level: #monster_spawn_light_level,
                 monster_spawn_block_light_limit: #monster_spawn_block_light_limit,
             };
@@ -0,0 +1,64 @@
+use std::fs;
+
+use heck::ToShoutySnakeCase;
+use proc_macro2::{Ident, Span, TokenStream};
+use quote::quote;
+use serde::Deserialize;
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct NoiseParametersJson {
+    first_octave: i32,
+    amplitudes: Vec<f64>,
+}
+
+/// The ident of the static generated for a `worldgen/noise` file.
+pub(crate) fn noise_ident(name: &str) -> Ident {
+    let name = name.strip_prefix("minecraft:").unwrap_or(name);
+    Ident::new(&name.to_shouty_snake_case(), Span::call_site())
+}
+
+pub(crate) fn build() -> TokenStream {
+    println!(
+        "cargo:rerun-if-changed=build_assets/builtin_datapacks/minecraft/data/minecraft/worldgen/noise/"
+    );
+
+    let noise_dir = "build_assets/builtin_datapacks/minecraft/data/minecraft/worldgen/noise";
+    let mut noises = Vec::new();
+
+    for entry in fs::read_dir(noise_dir).unwrap() {
+        let path = entry.unwrap().path();
+        if path.extension().and_then(|s| s.to_str()) == Some("json") {
+            let name = path.file_stem().unwrap().to_str().unwrap().to_string();
+            let content = fs::read_to_string(&path).unwrap();
+            let noise: NoiseParametersJson = serde_json::from_str(&content)
+                .unwrap_or_else(|e| panic!("Failed to parse noise {}: {}", name, e));
+            noises.push((name, noise));
+        }
+    }
+
+    noises.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let mut stream = TokenStream::new();
+
+    stream.extend(quote! {
+        use crate::density_function::NoiseParameters;
+        use steel_utils::Identifier;
+    });
+
+    for (name, noise) in &noises {
+        let ident = noise_ident(name);
+        let first_octave = noise.first_octave;
+        let amplitudes = &noise.amplitudes;
+
+        stream.extend(quote! {
+            pub static #ident: NoiseParameters = NoiseParameters {
+                key: Identifier::vanilla_static(#name),
+                first_octave: #first_octave,
+                amplitudes: &[#(#amplitudes),*],
+            };
+        });
+    }
+
+    stream
+}
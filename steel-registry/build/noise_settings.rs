@@ -0,0 +1,167 @@
+use std::fs;
+
+use heck::ToShoutySnakeCase;
+use proc_macro2::{Ident, Span, TokenStream};
+use quote::quote;
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::density_functions::{self, DensityFunctionWriter};
+
+#[derive(Deserialize, Debug)]
+struct NoiseGeneratorSettingsJson {
+    noise: NoiseSettingsJson,
+    default_block: BlockStateJson,
+    default_fluid: BlockStateJson,
+    noise_router: NoiseRouterJson,
+    sea_level: i32,
+    disable_mob_generation: bool,
+    aquifers_enabled: bool,
+    ore_veins_enabled: bool,
+    legacy_random_source: bool,
+}
+
+#[derive(Deserialize, Debug)]
+struct NoiseSettingsJson {
+    min_y: i32,
+    height: i32,
+    size_horizontal: i32,
+    size_vertical: i32,
+}
+
+#[derive(Deserialize, Debug)]
+struct BlockStateJson {
+    #[serde(rename = "Name")]
+    name: String,
+}
+
+#[derive(Deserialize, Debug)]
+struct NoiseRouterJson {
+    barrier: Value,
+    fluid_level_floodedness: Value,
+    fluid_level_spread: Value,
+    lava: Value,
+    temperature: Value,
+    vegetation: Value,
+    continents: Value,
+    erosion: Value,
+    depth: Value,
+    ridges: Value,
+    preliminary_surface_level: Value,
+    final_density: Value,
+    vein_toggle: Value,
+    vein_ridged: Value,
+    vein_gap: Value,
+}
+
+fn block_ident(state: &BlockStateJson) -> Ident {
+    let name = state.name.strip_prefix("minecraft:").unwrap_or(&state.name);
+    Ident::new(&name.to_shouty_snake_case(), Span::call_site())
+}
+
+pub(crate) fn build() -> TokenStream {
+    println!(
+        "cargo:rerun-if-changed=build_assets/builtin_datapacks/minecraft/data/minecraft/worldgen/noise_settings/"
+    );
+
+    let settings_dir =
+        "build_assets/builtin_datapacks/minecraft/data/minecraft/worldgen/noise_settings";
+    let mut all_settings = Vec::new();
+
+    for entry in fs::read_dir(settings_dir).unwrap() {
+        let path = entry.unwrap().path();
+        if path.extension().and_then(|s| s.to_str()) == Some("json") {
+            let name = path.file_stem().unwrap().to_str().unwrap().to_string();
+            let content = fs::read_to_string(&path).unwrap();
+            let settings: NoiseGeneratorSettingsJson = serde_json::from_str(&content)
+                .unwrap_or_else(|e| panic!("Failed to parse noise settings {}: {}", name, e));
+            all_settings.push((name, settings));
+        }
+    }
+
+    all_settings.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let mut writer = DensityFunctionWriter::new("ROUTER");
+    let mut stream = TokenStream::new();
+
+    stream.extend(density_functions::imports());
+    stream.extend(quote! {
+        use crate::noise_settings::{NoiseGeneratorSettings, NoiseRouter, NoiseSettings};
+        use crate::vanilla_blocks;
+        use steel_utils::Identifier;
+    });
+
+    for (name, settings) in &all_settings {
+        let ident = Ident::new(&name.to_shouty_snake_case(), Span::call_site());
+
+        let noise = &settings.noise;
+        let min_y = noise.min_y;
+        let height = noise.height;
+        let size_horizontal = noise.size_horizontal;
+        let size_vertical = noise.size_vertical;
+
+        let router = &settings.noise_router;
+        let barrier_noise = writer.reference(&router.barrier);
+        let fluid_level_floodedness_noise = writer.reference(&router.fluid_level_floodedness);
+        let fluid_level_spread_noise = writer.reference(&router.fluid_level_spread);
+        let lava_noise = writer.reference(&router.lava);
+        let temperature = writer.reference(&router.temperature);
+        let vegetation = writer.reference(&router.vegetation);
+        let continents = writer.reference(&router.continents);
+        let erosion = writer.reference(&router.erosion);
+        let depth = writer.reference(&router.depth);
+        let ridges = writer.reference(&router.ridges);
+        let preliminary_surface_level = writer.reference(&router.preliminary_surface_level);
+        let final_density = writer.reference(&router.final_density);
+        let vein_toggle = writer.reference(&router.vein_toggle);
+        let vein_ridged = writer.reference(&router.vein_ridged);
+        let vein_gap = writer.reference(&router.vein_gap);
+
+        let default_block = block_ident(&settings.default_block);
+        let default_fluid = block_ident(&settings.default_fluid);
+        let sea_level = settings.sea_level;
+        let disable_mob_generation = settings.disable_mob_generation;
+        let aquifers_enabled = settings.aquifers_enabled;
+        let ore_veins_enabled = settings.ore_veins_enabled;
+        let use_legacy_random_source = settings.legacy_random_source;
+
+        stream.extend(quote! {
+            pub static #ident: &NoiseGeneratorSettings = &NoiseGeneratorSettings {
+                key: Identifier::vanilla_static(#name),
+                noise: NoiseSettings {
+                    min_y: #min_y,
+                    height: #height,
+                    size_horizontal: #size_horizontal,
+                    size_vertical: #size_vertical,
+                },
+                default_block: vanilla_blocks::#default_block,
+                default_fluid: vanilla_blocks::#default_fluid,
+                noise_router: NoiseRouter {
+                    barrier_noise: #barrier_noise,
+                    fluid_level_floodedness_noise: #fluid_level_floodedness_noise,
+                    fluid_level_spread_noise: #fluid_level_spread_noise,
+                    lava_noise: #lava_noise,
+                    temperature: #temperature,
+                    vegetation: #vegetation,
+                    continents: #continents,
+                    erosion: #erosion,
+                    depth: #depth,
+                    ridges: #ridges,
+                    preliminary_surface_level: #preliminary_surface_level,
+                    final_density: #final_density,
+                    vein_toggle: #vein_toggle,
+                    vein_ridged: #vein_ridged,
+                    vein_gap: #vein_gap,
+                },
+                sea_level: #sea_level,
+                disable_mob_generation: #disable_mob_generation,
+                aquifers_enabled: #aquifers_enabled,
+                ore_veins_enabled: #ore_veins_enabled,
+                use_legacy_random_source: #use_legacy_random_source,
+            };
+        });
+    }
+
+    stream.extend(writer.into_statics());
+    stream
+}
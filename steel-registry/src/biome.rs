@@ -1,5 +1,9 @@
+use std::sync::LazyLock;
+
 use rustc_hash::FxHashMap;
-use steel_utils::Identifier;
+use steel_utils::noise::PerlinSimplexNoise;
+use steel_utils::random::legacy_random::LegacyRandom;
+use steel_utils::{BlockPos, Identifier};
 
 use crate::RegistryExt;
 
@@ -56,6 +60,98 @@ pub enum TemperatureModifier {
     Frozen,
 }
 
+/// Biomes at least this warm get rain instead of snow.
+const RAIN_TEMPERATURE: f32 = 0.15;
+
+/// How far above sea level the temperature starts to drop with height.
+const SNOW_LINE_OFFSET: i32 = 17;
+
+static TEMPERATURE_NOISE: LazyLock<PerlinSimplexNoise> =
+    LazyLock::new(|| PerlinSimplexNoise::new(&mut LegacyRandom::from_seed(1234), &[0]));
+
+static FROZEN_TEMPERATURE_NOISE: LazyLock<PerlinSimplexNoise> =
+    LazyLock::new(|| PerlinSimplexNoise::new(&mut LegacyRandom::from_seed(3456), &[-2, -1, 0]));
+
+static BIOME_INFO_NOISE: LazyLock<PerlinSimplexNoise> =
+    LazyLock::new(|| PerlinSimplexNoise::new(&mut LegacyRandom::from_seed(2345), &[0]));
+
+impl TemperatureModifier {
+    /// Applies the modifier to a biome's base temperature at `pos`.
+    ///
+    /// Matches vanilla's `Biome.TemperatureModifier.modifyTemperature()`. Frozen biomes
+    /// get warmer patches where the noise is low, which is where frozen oceans melt.
+    #[must_use]
+    pub fn modify_temperature(&self, pos: BlockPos, temperature: f32) -> f32 {
+        match self {
+            Self::None => temperature,
+            Self::Frozen => {
+                let x = f64::from(pos.0.x);
+                let z = f64::from(pos.0.z);
+                let frozen = FROZEN_TEMPERATURE_NOISE.get_value(x * 0.05, z * 0.05, false) * 7.0;
+                let info = BIOME_INFO_NOISE.get_value(x * 0.2, z * 0.2, false);
+                if frozen + info < 0.3
+                    && BIOME_INFO_NOISE.get_value(x * 0.09, z * 0.09, false) < 0.8
+                {
+                    0.2
+                } else {
+                    temperature
+                }
+            }
+        }
+    }
+}
+
+/// What falls from the sky in a biome while it is raining.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Precipitation {
+    None,
+    Rain,
+    Snow,
+}
+
+impl Biome {
+    /// Gets the temperature at `pos`, which drops the higher it is above the snow line.
+    ///
+    /// Matches vanilla's `Biome.getHeightAdjustedTemperature()`.
+    #[must_use]
+    pub fn get_temperature(&self, pos: BlockPos, sea_level: i32) -> f32 {
+        let temperature = self
+            .temperature_modifier
+            .modify_temperature(pos, self.temperature);
+        let snow_line = sea_level + SNOW_LINE_OFFSET;
+        if pos.0.y > snow_line {
+            let noise = (TEMPERATURE_NOISE.get_value(
+                f64::from(pos.0.x as f32 / 8.0),
+                f64::from(pos.0.z as f32 / 8.0),
+                false,
+            ) * 8.0) as f32;
+            temperature - (noise + (pos.0.y - snow_line) as f32) * 0.05 / 40.0
+        } else {
+            temperature
+        }
+    }
+
+    /// Returns whether it snows rather than rains at `pos`.
+    #[must_use]
+    pub fn cold_enough_to_snow(&self, pos: BlockPos, sea_level: i32) -> bool {
+        self.get_temperature(pos, sea_level) < RAIN_TEMPERATURE
+    }
+
+    /// Gets what falls at `pos` while it is raining.
+    ///
+    /// Matches vanilla's `Biome.getPrecipitationAt()`.
+    #[must_use]
+    pub fn get_precipitation_at(&self, pos: BlockPos, sea_level: i32) -> Precipitation {
+        if !self.has_precipitation {
+            Precipitation::None
+        } else if self.cold_enough_to_snow(pos, sea_level) {
+            Precipitation::Snow
+        } else {
+            Precipitation::Rain
+        }
+    }
+}
+
 #[derive(Debug)]
 pub enum GrassColorModifier {
     None,
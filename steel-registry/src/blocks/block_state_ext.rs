@@ -1,12 +1,16 @@
+use std::ptr;
+
 use steel_utils::BlockStateId;
 
 use crate::{
     REGISTRY,
     blocks::{
         self, BlockRef,
-        properties::{Direction, Property},
+        properties::{BlockStateProperties, Direction, Property},
         shapes::SupportType,
     },
+    fluid::{FluidRef, FluidState},
+    vanilla_blocks, vanilla_fluids,
 };
 
 pub trait BlockStateExt {
@@ -31,6 +35,19 @@ pub trait BlockStateExt {
     /// This matches vanilla's `BlockState.isSolid()` which is used by standing signs
     /// to check if they can be placed on a block.
     fn is_solid(&self) -> bool;
+    /// Gets the block light emitted by this state (0-15).
+    fn get_light_emission(&self) -> u8;
+    /// Gets how much light this state absorbs (0-15).
+    ///
+    /// Matches vanilla's `BlockState.getLightBlock()`.
+    fn get_light_block(&self) -> u8;
+    /// Checks if skylight passes straight down through this state without dimming.
+    fn propagates_skylight_down(&self) -> bool;
+    /// Gets the fluid held by this state.
+    ///
+    /// Matches vanilla's `BlockState.getFluidState()`: liquid blocks hold the fluid
+    /// given by their level, waterlogged blocks and underwater plants hold a water source.
+    fn get_fluid_state(&self) -> FluidState;
 }
 
 impl BlockStateExt for BlockStateId {
@@ -103,4 +120,61 @@ impl BlockStateExt for BlockStateId {
         let shape = self.get_collision_shape();
         blocks::shapes::is_shape_full_block(shape)
     }
+
+    fn get_light_emission(&self) -> u8 {
+        REGISTRY.blocks.get_light_properties(*self).emission
+    }
+
+    fn get_light_block(&self) -> u8 {
+        REGISTRY.blocks.get_light_properties(*self).light_block
+    }
+
+    fn propagates_skylight_down(&self) -> bool {
+        REGISTRY
+            .blocks
+            .get_light_properties(*self)
+            .propagates_skylight_down
+    }
+
+    fn get_fluid_state(&self) -> FluidState {
+        let block = self.get_block();
+        if ptr::eq(block, vanilla_blocks::WATER) {
+            return liquid_fluid_state(
+                *self,
+                &vanilla_fluids::WATER,
+                &vanilla_fluids::FLOWING_WATER,
+            );
+        }
+        if ptr::eq(block, vanilla_blocks::LAVA) {
+            return liquid_fluid_state(*self, &vanilla_fluids::LAVA, &vanilla_fluids::FLOWING_LAVA);
+        }
+
+        let holds_water = ptr::eq(block, vanilla_blocks::BUBBLE_COLUMN)
+            || ptr::eq(block, vanilla_blocks::KELP)
+            || ptr::eq(block, vanilla_blocks::KELP_PLANT)
+            || ptr::eq(block, vanilla_blocks::SEAGRASS)
+            || ptr::eq(block, vanilla_blocks::TALL_SEAGRASS)
+            || self
+                .try_get_value(&BlockStateProperties::WATERLOGGED)
+                .unwrap_or(false);
+        if holds_water {
+            FluidState::source(fluid_id(&vanilla_fluids::WATER))
+        } else {
+            FluidState::EMPTY
+        }
+    }
+}
+
+/// Decodes the fluid of a liquid block from its level property.
+fn liquid_fluid_state(state: BlockStateId, source: FluidRef, flowing: FluidRef) -> FluidState {
+    let level: u8 = state.get_value(&BlockStateProperties::LEVEL);
+    let fluid = if level == 0 { source } else { flowing };
+    FluidState::from_block_level(fluid_id(fluid), level)
+}
+
+fn fluid_id(fluid: FluidRef) -> u8 {
+    *REGISTRY
+        .fluids
+        .get_id(fluid)
+        .expect("vanilla fluids should be registered") as u8
 }
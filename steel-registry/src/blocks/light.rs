@@ -0,0 +1,198 @@
+//! Light properties of block states.
+//!
+//! Emission follows the `lightLevel` functions passed to block properties in
+//! vanilla's `Blocks`, opacity follows `BlockStateBase.getLightBlock()` and
+//! `propagatesSkylightDown()`.
+
+use steel_utils::BlockStateId;
+
+use crate::blocks::properties::{BlockStateProperties, TrialSpawnerState, VaultState};
+use crate::blocks::shapes::is_shape_full_block;
+use crate::blocks::{BlockRef, BlockRegistry};
+
+/// Highest light level.
+pub const MAX_LIGHT_LEVEL: u8 = 15;
+
+/// Precomputed light behaviour of a single block state.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LightProperties {
+    /// Block light emitted by the state (0-15).
+    pub emission: u8,
+    /// How much light is absorbed when passing through the state (0-15).
+    pub light_block: u8,
+    /// Whether skylight passes straight down through the state without dimming.
+    pub propagates_skylight_down: bool,
+}
+
+impl LightProperties {
+    /// Computes the light properties of a block state.
+    pub(crate) fn compute(registry: &BlockRegistry, block: BlockRef, state: BlockStateId) -> Self {
+        let solid_render =
+            block.config.can_occlude && is_shape_full_block(registry.get_outline_shape(state));
+        let propagates_skylight_down = propagates_skylight_down(registry, block, state);
+
+        // Tinted glass is the only block that blocks all light without occluding
+        let light_block = if solid_render || block.key.path == "tinted_glass" {
+            MAX_LIGHT_LEVEL
+        } else if propagates_skylight_down {
+            0
+        } else {
+            1
+        };
+
+        Self {
+            emission: light_emission(registry, block, state),
+            light_block,
+            propagates_skylight_down,
+        }
+    }
+}
+
+/// Matches vanilla's `BlockBehaviour.propagatesSkylightDown()` and its overrides
+/// in `TransparentBlock` and `BarrierBlock`.
+fn propagates_skylight_down(
+    registry: &BlockRegistry,
+    block: BlockRef,
+    state: BlockStateId,
+) -> bool {
+    if has_fluid(registry, block, state) {
+        return false;
+    }
+    let path = block.key.path.as_ref();
+    if path == "tinted_glass" {
+        return false;
+    }
+    if path == "barrier" || path == "glass" || path.ends_with("_stained_glass") {
+        return true;
+    }
+    !is_shape_full_block(registry.get_outline_shape(state))
+}
+
+/// Returns whether the state holds a fluid, which always dims skylight.
+fn has_fluid(registry: &BlockRegistry, block: BlockRef, state: BlockStateId) -> bool {
+    block.config.liquid
+        || matches!(
+            block.key.path.as_ref(),
+            "bubble_column" | "kelp" | "kelp_plant" | "seagrass" | "tall_seagrass"
+        )
+        || registry
+            .try_get_property(state, &BlockStateProperties::WATERLOGGED)
+            .unwrap_or(false)
+}
+
+/// Matches the `lightLevel` functions in vanilla's `Blocks`.
+fn light_emission(registry: &BlockRegistry, block: BlockRef, state: BlockStateId) -> u8 {
+    let lit = |level: u8| {
+        if registry
+            .try_get_property(state, &BlockStateProperties::LIT)
+            .unwrap_or(false)
+        {
+            level
+        } else {
+            0
+        }
+    };
+
+    let path = block.key.path.as_ref();
+    match path {
+        "glowstone"
+        | "jack_o_lantern"
+        | "sea_lantern"
+        | "shroomlight"
+        | "lantern"
+        | "beacon"
+        | "conduit"
+        | "end_gateway"
+        | "end_portal"
+        | "fire"
+        | "lava"
+        | "ochre_froglight"
+        | "verdant_froglight"
+        | "pearlescent_froglight" => 15,
+        "torch" | "wall_torch" | "end_rod" | "copper_torch" | "copper_wall_torch" => 14,
+        "nether_portal" => 11,
+        "crying_obsidian" | "soul_torch" | "soul_wall_torch" | "soul_lantern" | "soul_fire" => 10,
+        "enchanting_table" | "ender_chest" | "glow_lichen" => 7,
+        "sculk_catalyst" => 6,
+        "amethyst_cluster" => 5,
+        "large_amethyst_bud" => 4,
+        "magma_block" => 3,
+        "medium_amethyst_bud" | "firefly_bush" => 2,
+        "small_amethyst_bud"
+        | "brewing_stand"
+        | "brown_mushroom"
+        | "dragon_egg"
+        | "end_portal_frame"
+        | "sculk_sensor"
+        | "calibrated_sculk_sensor" => 1,
+        "redstone_lamp" | "campfire" => lit(15),
+        "soul_campfire" => lit(10),
+        "furnace" | "blast_furnace" | "smoker" => lit(13),
+        "redstone_ore" | "deepslate_redstone_ore" => lit(9),
+        "redstone_torch" | "redstone_wall_torch" => lit(7),
+        "candle_cake" => lit(3),
+        "cave_vines" | "cave_vines_plant" => {
+            if registry
+                .try_get_property(state, &BlockStateProperties::BERRIES)
+                .unwrap_or(false)
+            {
+                14
+            } else {
+                0
+            }
+        }
+        "light" => registry
+            .try_get_property(state, &BlockStateProperties::LEVEL)
+            .unwrap_or(0),
+        "respawn_anchor" => {
+            // RespawnAnchorBlock.getScaledChargeLevel(state, 15)
+            let charges = registry
+                .try_get_property(state, &BlockStateProperties::RESPAWN_ANCHOR_CHARGES)
+                .unwrap_or(0);
+            (f32::from(charges) / 4.0 * 15.0).floor() as u8
+        }
+        "sea_pickle" => {
+            // Dead (not waterlogged) pickles don't glow
+            let waterlogged = registry
+                .try_get_property(state, &BlockStateProperties::WATERLOGGED)
+                .unwrap_or(false);
+            let pickles = registry
+                .try_get_property(state, &BlockStateProperties::PICKLES)
+                .unwrap_or(1);
+            if waterlogged { 3 + 3 * pickles } else { 0 }
+        }
+        "vault" => match registry.try_get_property(state, &BlockStateProperties::VAULT_STATE) {
+            Some(VaultState::Inactive) | None => 6,
+            Some(_) => 12,
+        },
+        "trial_spawner" => {
+            match registry.try_get_property(state, &BlockStateProperties::TRIAL_SPAWNER_STATE) {
+                Some(TrialSpawnerState::Inactive | TrialSpawnerState::Cooldown) | None => 4,
+                Some(_) => 8,
+            }
+        }
+        _ if path.ends_with("copper_bulb") => lit(copper_level(path, [15, 12, 8, 4])),
+        _ if path.ends_with("copper_lantern") => 15,
+        _ if path.ends_with("candle") => {
+            let candles = registry
+                .try_get_property(state, &BlockStateProperties::CANDLES)
+                .unwrap_or(1);
+            lit(3 * candles)
+        }
+        _ if path.ends_with("candle_cake") => lit(3),
+        _ => 0,
+    }
+}
+
+/// Picks the value matching the oxidation stage in a copper block's name.
+fn copper_level(path: &str, levels: [u8; 4]) -> u8 {
+    if path.contains("oxidized") {
+        levels[3]
+    } else if path.contains("weathered") {
+        levels[2]
+    } else if path.contains("exposed") {
+        levels[1]
+    } else {
+        levels[0]
+    }
+}
@@ -1,5 +1,6 @@
 pub mod behaviour;
 pub mod block_state_ext;
+pub mod light;
 pub mod properties;
 pub mod shapes;
 
@@ -7,6 +8,7 @@ use rustc_hash::FxHashMap;
 
 use crate::RegistryExt;
 use crate::blocks::behaviour::BlockConfig;
+use crate::blocks::light::LightProperties;
 use crate::blocks::properties::{DynProperty, Property};
 
 /// Function type for shape lookups. Takes a state offset and returns the shape.
@@ -132,6 +134,8 @@ pub struct BlockRegistry {
     pub block_to_base_state: Vec<u16>,
     /// The next state ID to be allocated
     pub next_state_id: u16,
+    /// Light properties per state ID, computed when the registry is frozen
+    light_properties: Vec<LightProperties>,
 }
 
 impl Default for BlockRegistry {
@@ -153,6 +157,7 @@ impl BlockRegistry {
             state_to_block_id: Vec::new(),
             block_to_base_state: Vec::new(),
             next_state_id: 0,
+            light_properties: Vec::new(),
         }
     }
 
@@ -476,6 +481,27 @@ impl BlockRegistry {
 impl RegistryExt for BlockRegistry {
     fn freeze(&mut self) {
         self.allows_registering = false;
+        self.light_properties = (0..self.state_to_block_lookup.len())
+            .map(|state| {
+                let state = BlockStateId(state as u16);
+                LightProperties::compute(self, self.state_to_block_lookup[state.0 as usize], state)
+            })
+            .collect();
+    }
+}
+
+// Light lookup methods
+impl BlockRegistry {
+    /// Gets the light properties of a block state.
+    ///
+    /// Returns the default (non-emitting, transparent) properties before the
+    /// registry is frozen.
+    #[must_use]
+    pub fn get_light_properties(&self, state_id: BlockStateId) -> LightProperties {
+        self.light_properties
+            .get(state_id.0 as usize)
+            .copied()
+            .unwrap_or_default()
     }
 }
 
@@ -907,4 +933,32 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn test_light_properties() {
+        let registry = create_test_registry();
+        let light = |name: &'static str| {
+            let block = registry
+                .by_key(&Identifier::vanilla_static(name))
+                .expect("block should exist");
+            registry.get_light_properties(registry.get_default_state_id(block))
+        };
+
+        assert_eq!(light("glowstone").emission, 15);
+        assert_eq!(light("stone").emission, 0);
+        assert_eq!(light("stone").light_block, 15);
+        assert!(light("air").propagates_skylight_down);
+        assert_eq!(light("air").light_block, 0);
+        assert!(light("glass").propagates_skylight_down);
+        assert_eq!(light("water").light_block, 1);
+        assert!(!light("water").propagates_skylight_down);
+
+        let furnace = registry
+            .by_key(&Identifier::vanilla_static("furnace"))
+            .expect("furnace should exist");
+        let unlit = registry.get_default_state_id(furnace);
+        let lit = registry.set_property(unlit, &properties::BlockStateProperties::LIT, true);
+        assert_eq!(registry.get_light_properties(unlit).emission, 0);
+        assert_eq!(registry.get_light_properties(lit).emission, 13);
+    }
 }
@@ -0,0 +1,166 @@
+//! Density functions and the noise parameters they sample.
+//!
+//! These mirror the `worldgen/density_function` and `worldgen/noise` data pack files. The
+//! build script turns every file into a static tree, and references between files become
+//! plain `&'static` pointers, so a function used in several places is one shared node.
+
+use steel_utils::Identifier;
+
+/// The octave layout of a noise, from a `worldgen/noise` file.
+#[derive(Debug)]
+pub struct NoiseParameters {
+    pub key: Identifier,
+    pub first_octave: i32,
+    pub amplitudes: &'static [f64],
+}
+
+pub type NoiseParametersRef = &'static NoiseParameters;
+
+pub type DensityFunctionRef = &'static DensityFunction;
+
+/// A node of a density function tree.
+///
+/// Variants match vanilla's density function types one to one. Marker variants like
+/// [`Interpolated`](Self::Interpolated) don't change the value, only how a chunk caches it.
+#[derive(Debug)]
+pub enum DensityFunction {
+    Constant(f64),
+    Add(DensityFunctionRef, DensityFunctionRef),
+    Mul(DensityFunctionRef, DensityFunctionRef),
+    Min(DensityFunctionRef, DensityFunctionRef),
+    Max(DensityFunctionRef, DensityFunctionRef),
+    Abs(DensityFunctionRef),
+    Square(DensityFunctionRef),
+    Cube(DensityFunctionRef),
+    HalfNegative(DensityFunctionRef),
+    QuarterNegative(DensityFunctionRef),
+    Squeeze(DensityFunctionRef),
+    Invert(DensityFunctionRef),
+    Clamp {
+        input: DensityFunctionRef,
+        min: f64,
+        max: f64,
+    },
+    YClampedGradient {
+        from_y: i32,
+        to_y: i32,
+        from_value: f64,
+        to_value: f64,
+    },
+    Noise {
+        noise: NoiseParametersRef,
+        xz_scale: f64,
+        y_scale: f64,
+    },
+    ShiftedNoise {
+        noise: NoiseParametersRef,
+        xz_scale: f64,
+        y_scale: f64,
+        shift_x: DensityFunctionRef,
+        shift_y: DensityFunctionRef,
+        shift_z: DensityFunctionRef,
+    },
+    ShiftA(NoiseParametersRef),
+    ShiftB(NoiseParametersRef),
+    Shift(NoiseParametersRef),
+    RangeChoice {
+        input: DensityFunctionRef,
+        min_inclusive: f64,
+        max_exclusive: f64,
+        when_in_range: DensityFunctionRef,
+        when_out_of_range: DensityFunctionRef,
+    },
+    WeirdScaledSampler {
+        input: DensityFunctionRef,
+        noise: NoiseParametersRef,
+        rarity_value_mapper: RarityValueMapper,
+    },
+    Spline(&'static CubicSpline),
+    OldBlendedNoise {
+        xz_scale: f64,
+        y_scale: f64,
+        xz_factor: f64,
+        y_factor: f64,
+        smear_scale_multiplier: f64,
+    },
+    FindTopSurface {
+        density: DensityFunctionRef,
+        upper_bound: DensityFunctionRef,
+        lower_bound: i32,
+        cell_height: i32,
+    },
+    EndIslands,
+    Interpolated(DensityFunctionRef),
+    FlatCache(DensityFunctionRef),
+    Cache2d(DensityFunctionRef),
+    CacheOnce(DensityFunctionRef),
+    CacheAllInCell(DensityFunctionRef),
+    BlendAlpha,
+    BlendOffset,
+    BlendDensity(DensityFunctionRef),
+    Beardifier,
+}
+
+/// How a `weird_scaled_sampler` turns its input into a sampling scale.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RarityValueMapper {
+    /// `type_1`, used by spaghetti caves in 3D.
+    Tunnels,
+    /// `type_2`, used by spaghetti caves in 2D.
+    Caves,
+}
+
+impl RarityValueMapper {
+    /// Maps the sampler input to the scale the noise is divided by.
+    #[must_use]
+    pub fn map(self, rarity: f64) -> f64 {
+        match self {
+            Self::Tunnels => {
+                if rarity < -0.5 {
+                    0.75
+                } else if rarity < 0.0 {
+                    1.0
+                } else if rarity < 0.5 {
+                    1.5
+                } else {
+                    2.0
+                }
+            }
+            Self::Caves => {
+                if rarity < -0.75 {
+                    0.5
+                } else if rarity < -0.5 {
+                    0.75
+                } else if rarity < 0.5 {
+                    1.0
+                } else if rarity < 0.75 {
+                    2.0
+                } else {
+                    3.0
+                }
+            }
+        }
+    }
+}
+
+/// A cubic Hermite spline over the value of a density function.
+#[derive(Debug)]
+pub struct CubicSpline {
+    pub coordinate: DensityFunctionRef,
+    pub points: &'static [SplinePoint],
+}
+
+/// A control point of a [`CubicSpline`].
+#[derive(Debug)]
+pub struct SplinePoint {
+    pub location: f32,
+    pub value: SplineValue,
+    pub derivative: f32,
+}
+
+/// The value at a spline point, which can itself be a spline.
+#[derive(Debug)]
+pub enum SplineValue {
+    Constant(f32),
+    Spline(&'static CubicSpline),
+}
@@ -19,6 +19,7 @@ pub struct DimensionType {
     pub ambient_light: f32,
     pub cloud_height: Option<i32>,
     pub has_raids: bool,
+    pub fast_lava: bool,
     pub monster_spawn_light_level: MonsterSpawnLightLevel,
     pub monster_spawn_block_light_limit: i32,
 }
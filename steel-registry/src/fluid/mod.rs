@@ -3,7 +3,7 @@
 use rustc_hash::FxHashMap;
 use steel_utils::Identifier;
 
-use crate::RegistryExt;
+use crate::{REGISTRY, RegistryExt};
 
 /// A fluid type definition (e.g., water, lava, empty).
 #[derive(Debug, Clone)]
@@ -93,6 +93,24 @@ impl FluidState {
         self.amount == 8 && !self.falling
     }
 
+    /// Returns the fluid type of this state.
+    ///
+    /// # Panics
+    /// Panics if the fluid id is not registered.
+    #[must_use]
+    pub fn fluid(&self) -> FluidRef {
+        REGISTRY
+            .fluids
+            .by_id(self.fluid_id as usize)
+            .expect("fluid state should hold a registered fluid")
+    }
+
+    /// Returns true if this state's fluid type is in the given tag.
+    #[must_use]
+    pub fn is(&self, tag: &Identifier) -> bool {
+        !self.is_empty() && REGISTRY.fluids.is_in_tag(self.fluid(), tag)
+    }
+
     /// Returns the fluid's own height (0.0 to ~0.89).
     #[must_use]
     pub fn own_height(&self) -> f32 {
@@ -7,6 +7,7 @@ use steel_utils::{
     codec::VarInt,
     serial::{ReadFrom, WriteTo},
 };
+use text_components::TextComponent;
 
 use crate::{
     REGISTRY,
@@ -14,8 +15,8 @@ use crate::{
         Component, ComponentData, ComponentPatchEntry, DataComponentMap, DataComponentPatch,
         DataComponentType,
         vanilla_components::{
-            DAMAGE, EQUIPPABLE, Equippable, EquippableSlot, MAX_DAMAGE, MAX_STACK_SIZE, TOOL, Tool,
-            UNBREAKABLE,
+            CUSTOM_NAME, DAMAGE, EQUIPPABLE, Equippable, EquippableSlot, MAX_DAMAGE,
+            MAX_STACK_SIZE, TOOL, Tool, UNBREAKABLE,
         },
     },
     items::ItemRef,
@@ -415,13 +416,23 @@ impl ItemStack {
     /// Copies components from a source (block entity, attacker, etc.) to this item.
     pub fn copy_components<R: rand::Rng>(
         &mut self,
-        _source: crate::loot_table::CopySource,
-        _include: &[Identifier],
-        _ctx: &crate::loot_table::LootContext<'_, R>,
+        source: crate::loot_table::CopySource,
+        include: &[Identifier],
+        ctx: &crate::loot_table::LootContext<'_, R>,
     ) {
-        // TODO: Implement when block entity system is ready
-        // 1. Get the source entity/block entity from context
-        // 2. For each component in `include`, copy it to this item's patch
+        let (crate::loot_table::CopySource::BlockEntity, Some(block_entity)) =
+            (source, ctx.block_entity)
+        else {
+            return;
+        };
+
+        // The custom name is the only block entity component modelled so far
+        // TODO: Copy container contents once CONTAINER has a real type
+        if let Some(name) = block_entity.custom_name
+            && include.contains(&CUSTOM_NAME.key)
+        {
+            self.set(CUSTOM_NAME, name.clone());
+        }
     }
 
     /// Copies block state properties to this item (for blocks like note_block).
@@ -531,12 +542,28 @@ impl ItemStack {
     /// Copies the name from a source entity/block to this item.
     pub fn copy_name<R: rand::Rng>(
         &mut self,
-        _source: crate::loot_table::CopySource,
-        _ctx: &crate::loot_table::LootContext<'_, R>,
+        source: crate::loot_table::CopySource,
+        ctx: &crate::loot_table::LootContext<'_, R>,
     ) {
-        // TODO: Implement when entity/block entity name access is available
-        // Get name from source (block_entity.custom_name or entity.custom_name)
-        // Set as CUSTOM_NAME component
+        use crate::loot_table::CopySource;
+
+        let entity_name = |entity: Option<crate::loot_table::EntityRef<'_>>| {
+            entity
+                .and_then(|entity| entity.custom_name)
+                .map(|name| TextComponent::plain(name.to_owned()))
+        };
+        let name = match source {
+            CopySource::BlockEntity => ctx
+                .block_entity
+                .and_then(|block_entity| block_entity.custom_name)
+                .cloned(),
+            CopySource::This => entity_name(ctx.this_entity),
+            CopySource::Attacker => entity_name(ctx.killer_entity),
+            CopySource::DirectAttacker => entity_name(ctx.direct_killer_entity),
+        };
+        if let Some(name) = name {
+            self.set(CUSTOM_NAME, name);
+        }
     }
 
     /// Sets lore lines on this item.
@@ -53,6 +53,7 @@ pub mod chicken_variant;
 pub mod cow_variant;
 pub mod damage_type;
 pub mod data_components;
+pub mod density_function;
 pub mod dialog;
 pub mod dimension_type;
 pub mod entity_data;
@@ -66,6 +67,7 @@ pub mod items;
 pub mod jukebox_song;
 pub mod loot_table;
 pub mod menu_type;
+pub mod noise_settings;
 pub mod painting_variant;
 pub mod pig_variant;
 pub mod recipe;
@@ -264,6 +266,21 @@ pub mod sound_events;
 #[path = "generated/vanilla_sound_types.rs"]
 pub mod sound_types;
 
+#[allow(warnings)]
+#[rustfmt::skip]
+#[path = "generated/vanilla_noise_parameters.rs"]
+pub mod vanilla_noise_parameters;
+
+#[allow(warnings)]
+#[rustfmt::skip]
+#[path = "generated/vanilla_density_functions.rs"]
+pub mod vanilla_density_functions;
+
+#[allow(warnings)]
+#[rustfmt::skip]
+#[path = "generated/vanilla_noise_settings.rs"]
+pub mod vanilla_noise_settings;
+
 #[allow(warnings)]
 #[rustfmt::skip]
 #[path = "generated/vanilla_packets.rs"]
@@ -1,5 +1,6 @@
 use rustc_hash::FxHashMap;
 use steel_utils::{BlockStateId, Identifier};
+use text_components::TextComponent;
 
 use crate::{REGISTRY, RegistryExt, blocks::block_state_ext::BlockStateExt, item_stack::ItemStack};
 
@@ -352,8 +353,8 @@ pub struct DamageSourceInfo<'a> {
 pub struct BlockEntityRef<'a> {
     /// The block entity type identifier.
     pub block_entity_type: Option<&'a Identifier>,
-    /// Custom name of the block entity (for copy_name).
-    pub custom_name: Option<&'a str>,
+    /// Custom name of the block entity (for copy_name and copy_components).
+    pub custom_name: Option<&'a TextComponent>,
     /// Inventory contents (for dynamic/slots entries).
     pub inventory: Option<&'a [ItemStack]>,
 }
@@ -1653,7 +1654,6 @@ impl LootFunction {
                 item.enchant_with_levels(level, options, ctx.rng);
             }
             LootFunction::CopyComponents { source, include } => {
-                // TODO: Implement when block entity system is ready
                 item.copy_components(*source, include, ctx);
             }
             LootFunction::CopyState { block, properties } => {
@@ -2033,4 +2033,32 @@ mod tests {
             survived
         );
     }
+
+    #[test]
+    fn test_barrel_keeps_its_custom_name() {
+        init_test_registries();
+        let name = TextComponent::plain("Loot");
+        let barrel = Identifier::vanilla_static("barrel");
+
+        let mut rng = test_rng();
+        let mut ctx = LootContext::new(&mut rng).with_block_entity(BlockEntityRef {
+            block_entity_type: Some(&barrel),
+            custom_name: Some(&name),
+            inventory: None,
+        });
+        let items = vanilla_loot_tables::BLOCKS_BARREL.get_random_items(&mut ctx);
+        assert_eq!(items.len(), 1);
+        assert_eq!(
+            items[0].get(crate::data_components::vanilla_components::CUSTOM_NAME),
+            Some(&name)
+        );
+
+        // Unnamed barrels drop a plain barrel
+        let mut ctx = LootContext::new(&mut rng);
+        let items = vanilla_loot_tables::BLOCKS_BARREL.get_random_items(&mut ctx);
+        assert_eq!(
+            items[0].get(crate::data_components::vanilla_components::CUSTOM_NAME),
+            None
+        );
+    }
 }
@@ -0,0 +1,70 @@
+//! Noise generator settings from the `worldgen/noise_settings` data pack files.
+//!
+//! Surface rules and spawn targets aren't read yet; only what shapes the terrain is.
+
+use steel_utils::Identifier;
+
+use crate::blocks::BlockRef;
+use crate::density_function::DensityFunctionRef;
+
+/// The settings of a noise based chunk generator.
+#[derive(Debug)]
+pub struct NoiseGeneratorSettings {
+    pub key: Identifier,
+    pub noise: NoiseSettings,
+    /// The block placed wherever the final density is positive, in its default state.
+    pub default_block: BlockRef,
+    /// The fluid block that fills empty space below sea level, in its default state.
+    pub default_fluid: BlockRef,
+    pub noise_router: NoiseRouter,
+    pub sea_level: i32,
+    pub disable_mob_generation: bool,
+    pub aquifers_enabled: bool,
+    pub ore_veins_enabled: bool,
+    pub use_legacy_random_source: bool,
+}
+
+pub type NoiseGeneratorSettingsRef = &'static NoiseGeneratorSettings;
+
+/// The vertical range of the noise and the size of its interpolation cells.
+#[derive(Debug, Clone, Copy)]
+pub struct NoiseSettings {
+    pub min_y: i32,
+    pub height: i32,
+    pub size_horizontal: i32,
+    pub size_vertical: i32,
+}
+
+impl NoiseSettings {
+    /// The width of an interpolation cell in blocks.
+    #[must_use]
+    pub const fn cell_width(&self) -> i32 {
+        self.size_horizontal * 4
+    }
+
+    /// The height of an interpolation cell in blocks.
+    #[must_use]
+    pub const fn cell_height(&self) -> i32 {
+        self.size_vertical * 4
+    }
+}
+
+/// The density functions a noise generator samples.
+#[derive(Debug)]
+pub struct NoiseRouter {
+    pub barrier_noise: DensityFunctionRef,
+    pub fluid_level_floodedness_noise: DensityFunctionRef,
+    pub fluid_level_spread_noise: DensityFunctionRef,
+    pub lava_noise: DensityFunctionRef,
+    pub temperature: DensityFunctionRef,
+    pub vegetation: DensityFunctionRef,
+    pub continents: DensityFunctionRef,
+    pub erosion: DensityFunctionRef,
+    pub depth: DensityFunctionRef,
+    pub ridges: DensityFunctionRef,
+    pub preliminary_surface_level: DensityFunctionRef,
+    pub final_density: DensityFunctionRef,
+    pub vein_toggle: DensityFunctionRef,
+    pub vein_ridged: DensityFunctionRef,
+    pub vein_gap: DensityFunctionRef,
+}
@@ -0,0 +1,107 @@
+use std::io::{Error, Write};
+
+use crate::{codec::VarInt, math::Vector3, serial::WriteTo};
+
+/// A low-precision 3D vector, used by the protocol for entity velocities.
+///
+/// Each component is quantized to 15 bits relative to the largest absolute
+/// component, so small velocities only take a single byte on the wire.
+/// Matches vanilla's `LpVec3`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct LpVec3(pub Vector3<f64>);
+
+impl LpVec3 {
+    /// Vectors with every component below this are written as a single zero byte.
+    const ABS_MIN: f64 = 3.051_944_088_384_301E-5;
+    /// Components are clamped to this before encoding.
+    const ABS_MAX: f64 = 1.717_986_918_3E10;
+    /// The largest quantized component value.
+    const MAX_QUANTIZED_VALUE: f64 = 32766.0;
+
+    fn sanitize(value: f64) -> f64 {
+        if value.is_nan() {
+            0.0
+        } else {
+            value.clamp(-Self::ABS_MAX, Self::ABS_MAX)
+        }
+    }
+
+    fn pack(value: f64) -> u64 {
+        ((value * 0.5 + 0.5) * Self::MAX_QUANTIZED_VALUE).round() as u64
+    }
+}
+
+#[allow(missing_docs)]
+impl WriteTo for LpVec3 {
+    fn write(&self, writer: &mut impl Write) -> Result<(), Error> {
+        let x = Self::sanitize(self.0.x);
+        let y = Self::sanitize(self.0.y);
+        let z = Self::sanitize(self.0.z);
+
+        let chessboard_length = x.abs().max(y.abs()).max(z.abs());
+        if chessboard_length < Self::ABS_MIN {
+            return 0u8.write(writer);
+        }
+
+        let scale = chessboard_length.ceil() as u64;
+        let is_partial = scale & 3 != scale;
+        let markers = if is_partial { (scale & 3) | 4 } else { scale };
+
+        let buffer = markers
+            | (Self::pack(x / scale as f64) << 3)
+            | (Self::pack(y / scale as f64) << 18)
+            | (Self::pack(z / scale as f64) << 33);
+
+        (buffer as u8).write(writer)?;
+        ((buffer >> 8) as u8).write(writer)?;
+        ((buffer >> 16) as u32).write(writer)?;
+
+        if is_partial {
+            VarInt((scale >> 2) as i32).write(writer)?;
+        }
+        Ok(())
+    }
+}
+
+impl From<Vector3<f64>> for LpVec3 {
+    fn from(value: Vector3<f64>) -> Self {
+        Self(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lp_vec3_zero_is_single_byte() {
+        let mut buf = Vec::new();
+        LpVec3(Vector3::new(0.0, 0.0, 0.0))
+            .write(&mut buf)
+            .expect("write failed");
+        assert_eq!(buf, vec![0]);
+    }
+
+    #[test]
+    fn test_lp_vec3_small_velocity_has_no_scale_suffix() {
+        let mut buf = Vec::new();
+        LpVec3(Vector3::new(0.1, 0.2, -0.1))
+            .write(&mut buf)
+            .expect("write failed");
+        // Scale 1 fits in the marker bits, so no trailing VarInt
+        assert_eq!(buf.len(), 6);
+        assert_eq!(buf[0] & 0b111, 1);
+    }
+
+    #[test]
+    fn test_lp_vec3_large_velocity_writes_scale() {
+        let mut buf = Vec::new();
+        LpVec3(Vector3::new(10.0, 0.0, 0.0))
+            .write(&mut buf)
+            .expect("write failed");
+        // Scale 10 doesn't fit in two bits: marker has the continuation bit set
+        assert_eq!(buf.len(), 7);
+        assert_eq!(buf[0] & 0b100, 0b100);
+        assert_eq!(buf[6], 10 >> 2);
+    }
+}
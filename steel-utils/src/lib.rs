@@ -18,6 +18,7 @@ pub mod hash;
 /// A module for custom locks.
 pub mod locks;
 pub mod math;
+pub mod noise;
 pub mod random;
 pub mod serial;
 pub mod text;
@@ -0,0 +1,120 @@
+//! Interpolation helpers matching vanilla's `Mth`.
+//!
+//! World generation compares these results against zero to decide where blocks go, so
+//! the order of operations follows vanilla exactly.
+
+/// Linearly interpolates between `start` and `end`.
+#[inline]
+#[must_use]
+pub fn lerp(delta: f64, start: f64, end: f64) -> f64 {
+    start + delta * (end - start)
+}
+
+/// Linearly interpolates between `start` and `end` in single precision.
+#[inline]
+#[must_use]
+pub fn lerp_f32(delta: f32, start: f32, end: f32) -> f32 {
+    start + delta * (end - start)
+}
+
+/// Bilinearly interpolates between four corner values.
+#[inline]
+#[must_use]
+pub fn lerp2(delta_x: f64, delta_y: f64, x0y0: f64, x1y0: f64, x0y1: f64, x1y1: f64) -> f64 {
+    lerp(delta_y, lerp(delta_x, x0y0, x1y0), lerp(delta_x, x0y1, x1y1))
+}
+
+/// Trilinearly interpolates between eight corner values.
+#[inline]
+#[must_use]
+#[allow(clippy::too_many_arguments)]
+pub fn lerp3(
+    delta_x: f64,
+    delta_y: f64,
+    delta_z: f64,
+    x0y0z0: f64,
+    x1y0z0: f64,
+    x0y1z0: f64,
+    x1y1z0: f64,
+    x0y0z1: f64,
+    x1y0z1: f64,
+    x0y1z1: f64,
+    x1y1z1: f64,
+) -> f64 {
+    lerp(
+        delta_z,
+        lerp2(delta_x, delta_y, x0y0z0, x1y0z0, x0y1z0, x1y1z0),
+        lerp2(delta_x, delta_y, x0y0z1, x1y0z1, x0y1z1, x1y1z1),
+    )
+}
+
+/// Returns where `value` lies between `start` and `end`, where 0 is `start` and 1 is `end`.
+#[inline]
+#[must_use]
+pub fn inverse_lerp(value: f64, start: f64, end: f64) -> f64 {
+    (value - start) / (end - start)
+}
+
+/// Interpolates between `start` and `end`, holding the end values outside of `0..=1`.
+#[inline]
+#[must_use]
+pub fn clamped_lerp(factor: f64, start: f64, end: f64) -> f64 {
+    if factor < 0.0 {
+        start
+    } else if factor > 1.0 {
+        end
+    } else {
+        lerp(factor, start, end)
+    }
+}
+
+/// Maps `value` from the range `from_start..from_end` to `to_start..to_end`.
+#[inline]
+#[must_use]
+pub fn map(value: f64, from_start: f64, from_end: f64, to_start: f64, to_end: f64) -> f64 {
+    lerp(inverse_lerp(value, from_start, from_end), to_start, to_end)
+}
+
+/// Maps `value` like [`map`], clamping the result to the target range.
+#[inline]
+#[must_use]
+pub fn clamped_map(value: f64, from_start: f64, from_end: f64, to_start: f64, to_end: f64) -> f64 {
+    clamped_lerp(inverse_lerp(value, from_start, from_end), to_start, to_end)
+}
+
+/// The smoothstep curve `6t^5 - 15t^4 + 10t^3` used by Perlin noise.
+#[inline]
+#[must_use]
+pub fn smoothstep(x: f64) -> f64 {
+    x * x * x * (x * (x * 6.0 - 15.0) + 10.0)
+}
+
+#[cfg(test)]
+#[allow(clippy::float_cmp)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clamped_map_holds_ends() {
+        assert_eq!(clamped_map(-5.0, 0.0, 10.0, 1.0, 0.0), 1.0);
+        assert_eq!(clamped_map(15.0, 0.0, 10.0, 1.0, 0.0), 0.0);
+        assert_eq!(clamped_map(2.5, 0.0, 10.0, 1.0, 0.0), 0.75);
+    }
+
+    #[test]
+    fn test_lerp3_corners() {
+        let corners = [1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0];
+        let at = |x, y, z| {
+            lerp3(
+                x, y, z, corners[0], corners[1], corners[2], corners[3], corners[4], corners[5],
+                corners[6], corners[7],
+            )
+        };
+        assert_eq!(at(0.0, 0.0, 0.0), 1.0);
+        assert_eq!(at(1.0, 0.0, 0.0), 2.0);
+        assert_eq!(at(0.0, 1.0, 0.0), 3.0);
+        assert_eq!(at(0.0, 0.0, 1.0), 5.0);
+        assert_eq!(at(1.0, 1.0, 1.0), 8.0);
+        assert_eq!(at(0.5, 0.5, 0.5), 4.5);
+    }
+}
@@ -1,4 +1,5 @@
 //! This module contains math related utilities.
+pub mod interpolation;
 pub mod vector2;
 pub mod vector3;
 
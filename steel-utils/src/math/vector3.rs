@@ -196,6 +196,18 @@ impl<T: Math + Copy + Float> Vector3<T> {
         }
     }
 
+    /// Like [`Self::normalize`], but returns zero for vectors too short to point anywhere.
+    ///
+    /// Matches vanilla's `Vec3.normalize()`.
+    #[must_use]
+    pub fn normalize_or_zero(&self) -> Self {
+        let min_length = <T as num_traits::NumCast>::from(1.0E-5_f32).expect("fits any float");
+        if self.length() < min_length {
+            return Vector3::new(T::zero(), T::zero(), T::zero());
+        }
+        self.normalize()
+    }
+
     #[must_use]
     pub fn rotation_vector(pitch: T, yaw: T) -> Self {
         let h = pitch.to_radians();
@@ -0,0 +1,114 @@
+//! The legacy terrain noise behind the `old_blended_noise` density function.
+
+use crate::math::interpolation::clamped_lerp;
+use crate::noise::PerlinNoise;
+use crate::random::Random;
+
+/// Blends between two limit noises using a third, lower resolution main noise.
+///
+/// Matches vanilla's `BlendedNoise`, which is the pre-1.18 terrain noise that the
+/// overworld still uses as its base 3D noise.
+pub struct BlendedNoise {
+    min_limit_noise: PerlinNoise,
+    max_limit_noise: PerlinNoise,
+    main_noise: PerlinNoise,
+    xz_multiplier: f64,
+    y_multiplier: f64,
+    xz_factor: f64,
+    y_factor: f64,
+    smear_scale_multiplier: f64,
+    max_value: f64,
+}
+
+impl BlendedNoise {
+    /// Creates the noise, drawing all three octave sets from `random`.
+    pub fn new<R: Random>(
+        random: &mut R,
+        xz_scale: f64,
+        y_scale: f64,
+        xz_factor: f64,
+        y_factor: f64,
+        smear_scale_multiplier: f64,
+    ) -> Self {
+        let min_limit_noise = PerlinNoise::new_legacy_for_blended_noise(random, -15..=0);
+        let max_limit_noise = PerlinNoise::new_legacy_for_blended_noise(random, -15..=0);
+        let main_noise = PerlinNoise::new_legacy_for_blended_noise(random, -7..=0);
+        let xz_multiplier = 684.412 * xz_scale;
+        let y_multiplier = 684.412 * y_scale;
+        let max_value = min_limit_noise.max_broken_value(y_multiplier);
+        Self {
+            min_limit_noise,
+            max_limit_noise,
+            main_noise,
+            xz_multiplier,
+            y_multiplier,
+            xz_factor,
+            y_factor,
+            smear_scale_multiplier,
+            max_value,
+        }
+    }
+
+    /// Samples the noise at a block position.
+    #[must_use]
+    #[allow(clippy::manual_midpoint)]
+    pub fn compute(&self, block_x: i32, block_y: i32, block_z: i32) -> f64 {
+        let limit_x = f64::from(block_x) * self.xz_multiplier;
+        let limit_y = f64::from(block_y) * self.y_multiplier;
+        let limit_z = f64::from(block_z) * self.xz_multiplier;
+        let main_x = limit_x / self.xz_factor;
+        let main_y = limit_y / self.y_factor;
+        let main_z = limit_z / self.xz_factor;
+        let limit_smear = self.y_multiplier * self.smear_scale_multiplier;
+        let main_smear = limit_smear / self.y_factor;
+
+        let mut main_noise_value = 0.0;
+        let mut pow = 1.0;
+        for i in 0..8 {
+            if let Some(noise) = self.main_noise.get_octave_noise(i) {
+                main_noise_value += noise.noise_with_fudge(
+                    PerlinNoise::wrap(main_x * pow),
+                    PerlinNoise::wrap(main_y * pow),
+                    PerlinNoise::wrap(main_z * pow),
+                    main_smear * pow,
+                    main_y * pow,
+                ) / pow;
+            }
+            pow /= 2.0;
+        }
+
+        let factor = (main_noise_value / 10.0 + 1.0) / 2.0;
+        let is_max = factor >= 1.0;
+        let is_min = factor <= 0.0;
+        let mut blend_min = 0.0;
+        let mut blend_max = 0.0;
+        pow = 1.0;
+        for i in 0..16 {
+            let x = PerlinNoise::wrap(limit_x * pow);
+            let y = PerlinNoise::wrap(limit_y * pow);
+            let z = PerlinNoise::wrap(limit_z * pow);
+            let y_scale = limit_smear * pow;
+            if !is_max && let Some(noise) = self.min_limit_noise.get_octave_noise(i) {
+                blend_min += noise.noise_with_fudge(x, y, z, y_scale, limit_y * pow) / pow;
+            }
+            if !is_min && let Some(noise) = self.max_limit_noise.get_octave_noise(i) {
+                blend_max += noise.noise_with_fudge(x, y, z, y_scale, limit_y * pow) / pow;
+            }
+            pow /= 2.0;
+        }
+
+        clamped_lerp(factor, blend_min / 512.0, blend_max / 512.0) / 128.0
+    }
+
+    /// The largest value this noise can return.
+    #[must_use]
+    pub const fn max_value(&self) -> f64 {
+        self.max_value
+    }
+
+    /// The smear multiplier this noise was created with.
+    #[must_use]
+    pub const fn smear_scale_multiplier(&self) -> f64 {
+        self.smear_scale_multiplier
+    }
+}
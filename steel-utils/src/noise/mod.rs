@@ -0,0 +1,121 @@
+//! Gradient noise used by world generation.
+//!
+//! These are ports of vanilla's noise classes and produce the same values for the same
+//! random source, which is what lets a seed generate the same terrain as vanilla.
+
+mod blended_noise;
+mod improved_noise;
+mod normal_noise;
+mod perlin_noise;
+mod perlin_simplex_noise;
+mod simplex_noise;
+
+pub use blended_noise::BlendedNoise;
+pub use improved_noise::ImprovedNoise;
+pub use normal_noise::NormalNoise;
+pub use perlin_noise::PerlinNoise;
+pub use perlin_simplex_noise::PerlinSimplexNoise;
+pub use simplex_noise::SimplexNoise;
+
+/// Gradient vectors shared by Perlin and simplex noise.
+const GRADIENT: [[i32; 3]; 16] = [
+    [1, 1, 0],
+    [-1, 1, 0],
+    [1, -1, 0],
+    [-1, -1, 0],
+    [1, 0, 1],
+    [-1, 0, 1],
+    [1, 0, -1],
+    [-1, 0, -1],
+    [0, 1, 1],
+    [0, -1, 1],
+    [0, 1, -1],
+    [0, -1, -1],
+    [1, 1, 0],
+    [0, -1, 1],
+    [-1, 1, 0],
+    [0, -1, -1],
+];
+
+#[inline]
+fn dot(gradient: [i32; 3], x: f64, y: f64, z: f64) -> f64 {
+    f64::from(gradient[0]) * x + f64::from(gradient[1]) * y + f64::from(gradient[2]) * z
+}
+
+/// Floors a double to an int, saturating like Java's `(int)` cast.
+#[inline]
+fn floor(value: f64) -> i32 {
+    value.floor() as i32
+}
+
+#[cfg(test)]
+#[allow(clippy::float_cmp)]
+mod tests {
+    use super::*;
+    use crate::random::legacy_random::LegacyRandom;
+    use crate::random::xoroshiro::Xoroshiro;
+    use crate::random::{PositionalRandom, Random};
+
+    const CONTINENTALNESS: [f64; 9] = [1.0, 1.0, 2.0, 2.0, 2.0, 1.0, 1.0, 1.0, 1.0];
+
+    fn noise(seed: u64) -> NormalNoise {
+        let positional = Xoroshiro::from_seed(seed).next_positional();
+        NormalNoise::new(
+            &mut positional.with_hash_of("minecraft:continentalness"),
+            -9,
+            &CONTINENTALNESS,
+        )
+    }
+
+    #[test]
+    fn test_normal_noise_is_deterministic() {
+        let first = noise(12345);
+        let same_seed = noise(12345);
+        let other_seed = noise(54321);
+        let mut differs = false;
+        for i in 0..64 {
+            let x = f64::from(i) * 37.5;
+            let z = f64::from(i) * -11.25;
+            let value = first.get_value(x, 0.0, z);
+            assert_eq!(value, same_seed.get_value(x, 0.0, z));
+            differs |= value != other_seed.get_value(x, 0.0, z);
+        }
+        assert!(differs);
+    }
+
+    #[test]
+    fn test_noise_stays_within_max_value() {
+        let normal = noise(0);
+        let mut random = Xoroshiro::from_seed(7);
+        let blended = BlendedNoise::new(&mut random, 0.25, 0.125, 80.0, 160.0, 8.0);
+        for i in -200..200 {
+            let value = normal.get_value(f64::from(i) * 13.0, f64::from(i), f64::from(i) * 7.0);
+            assert!(value.abs() <= normal.max_value());
+            let value = blended.compute(i * 5, i, -i * 3);
+            assert!(value.abs() <= blended.max_value());
+        }
+    }
+
+    #[test]
+    fn test_legacy_octaves_skip_missing_amplitudes() {
+        // Skipping an octave must consume the random exactly like creating one does.
+        let mut skipped = LegacyRandom::from_seed(3);
+        let mut created = LegacyRandom::from_seed(3);
+        let _ = PerlinNoise::new_legacy(&mut skipped, -1, &[0.0, 1.0]);
+        let _ = PerlinNoise::new_legacy(&mut created, -1, &[1.0, 1.0]);
+        assert_eq!(skipped.next_i64(), created.next_i64());
+    }
+
+    #[test]
+    fn test_simplex_octaves_skip_missing_octaves() {
+        let mut skipped = LegacyRandom::from_seed(3456);
+        let mut created = LegacyRandom::from_seed(3456);
+        let sparse = PerlinSimplexNoise::new(&mut skipped, &[-2, 0]);
+        let full = PerlinSimplexNoise::new(&mut created, &[-2, -1, 0]);
+        assert_eq!(skipped.next_i64(), created.next_i64());
+        assert_ne!(
+            sparse.get_value(3.5, -7.25, false),
+            full.get_value(3.5, -7.25, false)
+        );
+    }
+}
@@ -0,0 +1,78 @@
+//! Normalized double Perlin noise.
+
+use crate::noise::PerlinNoise;
+use crate::random::Random;
+
+/// The second noise is sampled at slightly scaled coordinates so the two don't line up.
+const INPUT_FACTOR: f64 = 1.018_126_888_217_522_7;
+
+/// Two [`PerlinNoise`] instances added together and scaled to a standard deviation of
+/// about a third.
+///
+/// Matches vanilla's `NormalNoise`, which is what noise parameter files instantiate.
+pub struct NormalNoise {
+    value_factor: f64,
+    first: PerlinNoise,
+    second: PerlinNoise,
+    max_value: f64,
+}
+
+impl NormalNoise {
+    /// Creates noise from the given octave parameters.
+    pub fn new<R: Random>(random: &mut R, first_octave: i32, amplitudes: &[f64]) -> Self {
+        let first = PerlinNoise::new(random, first_octave, amplitudes);
+        let second = PerlinNoise::new(random, first_octave, amplitudes);
+        Self::from_parts(first, second, amplitudes)
+    }
+
+    /// Creates noise with the legacy octave initialization.
+    ///
+    /// Vanilla only uses this for the temperature and vegetation noises of worlds with a
+    /// legacy random source.
+    pub fn new_legacy<R: Random>(random: &mut R, first_octave: i32, amplitudes: &[f64]) -> Self {
+        let first = PerlinNoise::new_legacy(random, first_octave, amplitudes);
+        let second = PerlinNoise::new_legacy(random, first_octave, amplitudes);
+        Self::from_parts(first, second, amplitudes)
+    }
+
+    fn from_parts(first: PerlinNoise, second: PerlinNoise, amplitudes: &[f64]) -> Self {
+        let mut min_octave = i32::MAX;
+        let mut max_octave = i32::MIN;
+        for (i, &amplitude) in amplitudes.iter().enumerate() {
+            if amplitude != 0.0 {
+                min_octave = min_octave.min(i as i32);
+                max_octave = max_octave.max(i as i32);
+            }
+        }
+
+        // Overflows like vanilla when every amplitude is zero.
+        let value_factor =
+            (1.0 / 6.0) / expected_deviation(max_octave.wrapping_sub(min_octave));
+        let max_value = (first.max_value() + second.max_value()) * value_factor;
+        Self {
+            value_factor,
+            first,
+            second,
+            max_value,
+        }
+    }
+
+    /// Samples the noise at the given position.
+    #[must_use]
+    pub fn get_value(&self, x: f64, y: f64, z: f64) -> f64 {
+        let x2 = x * INPUT_FACTOR;
+        let y2 = y * INPUT_FACTOR;
+        let z2 = z * INPUT_FACTOR;
+        (self.first.get_value(x, y, z) + self.second.get_value(x2, y2, z2)) * self.value_factor
+    }
+
+    /// The largest value this noise can return.
+    #[must_use]
+    pub const fn max_value(&self) -> f64 {
+        self.max_value
+    }
+}
+
+fn expected_deviation(octave_span: i32) -> f64 {
+    0.1 * (1.0 + 1.0 / f64::from(octave_span.wrapping_add(1)))
+}
@@ -0,0 +1,186 @@
+//! Multi-octave Perlin noise.
+
+use std::ops::RangeInclusive;
+
+use crate::noise::ImprovedNoise;
+use crate::random::{PositionalRandom, Random};
+
+/// Coordinates are wrapped to this range so precision isn't lost far from the origin.
+const ROUND_OFF: f64 = 33_554_432.0;
+
+/// Several octaves of [`ImprovedNoise`] summed with per-octave amplitudes.
+///
+/// Matches vanilla's `PerlinNoise`. Octaves with a zero amplitude are never created.
+pub struct PerlinNoise {
+    noise_levels: Vec<Option<ImprovedNoise>>,
+    amplitudes: Vec<f64>,
+    lowest_freq_value_factor: f64,
+    lowest_freq_input_factor: f64,
+    max_value: f64,
+}
+
+impl PerlinNoise {
+    /// Creates noise whose octaves are seeded from a positional fork of `random`.
+    pub fn new<R: Random>(random: &mut R, first_octave: i32, amplitudes: &[f64]) -> Self {
+        let positional = random.next_positional();
+        let noise_levels = amplitudes
+            .iter()
+            .enumerate()
+            .map(|(i, &amplitude)| {
+                (amplitude != 0.0).then(|| {
+                    let octave = first_octave + i as i32;
+                    ImprovedNoise::new(&mut positional.with_hash_of(&format!("octave_{octave}")))
+                })
+            })
+            .collect();
+        Self::from_levels(noise_levels, first_octave, amplitudes.to_vec())
+    }
+
+    /// Creates noise the pre-1.18 way, drawing every octave from `random` in sequence.
+    ///
+    /// # Panics
+    /// Panics if `amplitudes` has octaves above zero, which vanilla doesn't support here.
+    pub fn new_legacy<R: Random>(random: &mut R, first_octave: i32, amplitudes: &[f64]) -> Self {
+        let octaves = amplitudes.len() as i32;
+        let zero_octave_index = -first_octave;
+        assert!(
+            zero_octave_index >= octaves - 1,
+            "Positive octaves are not supported by legacy noise"
+        );
+
+        let mut noise_levels: Vec<Option<ImprovedNoise>> =
+            amplitudes.iter().map(|_| None).collect();
+        let zero_octave = ImprovedNoise::new(random);
+        if (0..octaves).contains(&zero_octave_index)
+            && amplitudes[zero_octave_index as usize] != 0.0
+        {
+            noise_levels[zero_octave_index as usize] = Some(zero_octave);
+        }
+        for i in (0..zero_octave_index).rev() {
+            if i < octaves && amplitudes[i as usize] != 0.0 {
+                noise_levels[i as usize] = Some(ImprovedNoise::new(random));
+            } else {
+                // Keeps the random in step with vanilla, which creates and drops the octave.
+                random.consume_count(262);
+            }
+        }
+
+        Self::from_levels(noise_levels, first_octave, amplitudes.to_vec())
+    }
+
+    /// Creates the legacy noise used by [`BlendedNoise`](super::BlendedNoise), with a full
+    /// amplitude for every octave in `octaves`.
+    ///
+    /// # Panics
+    /// Panics if the range is empty or contains octaves above zero.
+    pub fn new_legacy_for_blended_noise<R: Random>(
+        random: &mut R,
+        octaves: RangeInclusive<i32>,
+    ) -> Self {
+        assert!(!octaves.is_empty(), "Need some octaves!");
+        let low_freq_octaves = -octaves.start();
+        let high_freq_octaves = *octaves.end();
+        let mut amplitudes = vec![0.0; (low_freq_octaves + high_freq_octaves + 1) as usize];
+        for octave in octaves {
+            amplitudes[(octave + low_freq_octaves) as usize] = 1.0;
+        }
+        Self::new_legacy(random, -low_freq_octaves, &amplitudes)
+    }
+
+    fn from_levels(
+        noise_levels: Vec<Option<ImprovedNoise>>,
+        first_octave: i32,
+        amplitudes: Vec<f64>,
+    ) -> Self {
+        let octaves = amplitudes.len() as i32;
+        let mut noise = Self {
+            noise_levels,
+            amplitudes,
+            lowest_freq_value_factor: 2.0_f64.powi(octaves - 1) / (2.0_f64.powi(octaves) - 1.0),
+            lowest_freq_input_factor: 2.0_f64.powi(first_octave),
+            max_value: 0.0,
+        };
+        noise.max_value = noise.edge_value(2.0);
+        noise
+    }
+
+    /// Samples the noise at the given position.
+    #[must_use]
+    pub fn get_value(&self, x: f64, y: f64, z: f64) -> f64 {
+        self.get_value_with_fudge(x, y, z, 0.0, 0.0, false)
+    }
+
+    /// Samples the noise with vanilla's deprecated y snapping.
+    ///
+    /// With `y_flat_hack` every octave samples its own y origin instead of `y`.
+    #[must_use]
+    pub fn get_value_with_fudge(
+        &self,
+        x: f64,
+        y: f64,
+        z: f64,
+        y_scale: f64,
+        y_fudge: f64,
+        y_flat_hack: bool,
+    ) -> f64 {
+        let mut value = 0.0;
+        let mut factor = self.lowest_freq_input_factor;
+        let mut value_factor = self.lowest_freq_value_factor;
+        for (noise, amplitude) in self.noise_levels.iter().zip(&self.amplitudes) {
+            if let Some(noise) = noise {
+                let sample_y = if y_flat_hack {
+                    -noise.yo
+                } else {
+                    Self::wrap(y * factor)
+                };
+                let noise_value = noise.noise_with_fudge(
+                    Self::wrap(x * factor),
+                    sample_y,
+                    Self::wrap(z * factor),
+                    y_scale * factor,
+                    y_fudge * factor,
+                );
+                value += amplitude * noise_value * value_factor;
+            }
+            factor *= 2.0;
+            value_factor /= 2.0;
+        }
+        value
+    }
+
+    /// The largest value this noise can return.
+    #[must_use]
+    pub const fn max_value(&self) -> f64 {
+        self.max_value
+    }
+
+    /// The largest value this noise can return when sampled with a y scale.
+    #[must_use]
+    pub fn max_broken_value(&self, y_scale: f64) -> f64 {
+        self.edge_value(y_scale + 2.0)
+    }
+
+    fn edge_value(&self, noise_value: f64) -> f64 {
+        let mut value = 0.0;
+        let mut value_factor = self.lowest_freq_value_factor;
+        for (noise, amplitude) in self.noise_levels.iter().zip(&self.amplitudes) {
+            if noise.is_some() {
+                value += amplitude * noise_value * value_factor;
+            }
+            value_factor /= 2.0;
+        }
+        value
+    }
+
+    /// Returns the octave `i` steps below the highest frequency one, if it was created.
+    #[must_use]
+    pub fn get_octave_noise(&self, i: usize) -> Option<&ImprovedNoise> {
+        self.noise_levels[self.noise_levels.len() - 1 - i].as_ref()
+    }
+
+    /// Wraps a coordinate into vanilla's round-off range.
+    #[must_use]
+    pub fn wrap(x: f64) -> f64 {
+        x - (x / ROUND_OFF + 0.5).floor() * ROUND_OFF
+    }
+}
@@ -0,0 +1,67 @@
+//! Multi-octave simplex noise.
+
+use crate::noise::SimplexNoise;
+use crate::random::Random;
+
+/// Several octaves of [`SimplexNoise`], each at half the frequency and twice the weight
+/// of the one before.
+///
+/// Matches vanilla's `PerlinSimplexNoise`, which biomes sample for their temperature.
+pub struct PerlinSimplexNoise {
+    noise_levels: Vec<Option<SimplexNoise>>,
+    highest_freq_value_factor: f64,
+}
+
+impl PerlinSimplexNoise {
+    /// Creates noise with the given octaves, drawing each one from `random` in sequence.
+    ///
+    /// # Panics
+    /// Panics if `octaves` is empty or has octaves above zero. Vanilla never uses those.
+    pub fn new<R: Random>(random: &mut R, octaves: &[i32]) -> Self {
+        let lowest = octaves.iter().copied().min().expect("No octaves given");
+        assert!(
+            octaves.iter().all(|&octave| octave <= 0),
+            "Positive octaves are not supported by simplex noise"
+        );
+        let count = (1 - lowest) as usize;
+
+        let zero_octave = SimplexNoise::new(random);
+        let mut noise_levels = Vec::with_capacity(count);
+        noise_levels.push(octaves.contains(&0).then_some(zero_octave));
+        for i in 1..count as i32 {
+            if octaves.contains(&-i) {
+                noise_levels.push(Some(SimplexNoise::new(random)));
+            } else {
+                random.consume_count(262);
+                noise_levels.push(None);
+            }
+        }
+
+        Self {
+            noise_levels,
+            highest_freq_value_factor: 1.0 / (2.0_f64.powi(count as i32) - 1.0),
+        }
+    }
+
+    /// Samples the noise at a 2D position, optionally shifted by each octave's offsets.
+    #[must_use]
+    pub fn get_value(&self, x: f64, y: f64, use_offsets: bool) -> f64 {
+        let mut value = 0.0;
+        let mut input_factor = 1.0;
+        let mut value_factor = self.highest_freq_value_factor;
+        for noise in &self.noise_levels {
+            if let Some(noise) = noise {
+                let (xo, yo) = if use_offsets {
+                    (noise.xo, noise.yo)
+                } else {
+                    (0.0, 0.0)
+                };
+                value +=
+                    noise.get_value(x * input_factor + xo, y * input_factor + yo) * value_factor;
+            }
+            input_factor /= 2.0;
+            value_factor *= 2.0;
+        }
+        value
+    }
+}
@@ -0,0 +1,87 @@
+//! 2D simplex noise.
+
+use crate::noise::{GRADIENT, dot, floor};
+use crate::random::Random;
+
+/// Matches vanilla's `SimplexNoise`, which the End uses to place its outer islands.
+pub struct SimplexNoise {
+    permutations: [i32; 256],
+    /// The random x offset.
+    pub xo: f64,
+    /// The random y offset.
+    pub yo: f64,
+    /// The random z offset.
+    pub zo: f64,
+}
+
+impl SimplexNoise {
+    /// Creates a new simplex noise, consuming values from `random`.
+    pub fn new<R: Random>(random: &mut R) -> Self {
+        let xo = random.next_f64() * 256.0;
+        let yo = random.next_f64() * 256.0;
+        let zo = random.next_f64() * 256.0;
+
+        let mut permutations = [0i32; 256];
+        for (i, value) in permutations.iter_mut().enumerate() {
+            *value = i as i32;
+        }
+        for i in 0..256 {
+            let offset = random.next_i32_bounded(256 - i as i32) as usize;
+            permutations.swap(i, i + offset);
+        }
+
+        Self {
+            permutations,
+            xo,
+            yo,
+            zo,
+        }
+    }
+
+    #[inline]
+    fn p(&self, x: i32) -> i32 {
+        self.permutations[(x & 0xFF) as usize]
+    }
+
+    fn corner_noise(index: i32, x: f64, y: f64, z: f64, base: f64) -> f64 {
+        let t0 = base - x * x - y * y - z * z;
+        if t0 < 0.0 {
+            0.0
+        } else {
+            let t0 = t0 * t0;
+            t0 * t0 * dot(GRADIENT[index as usize], x, y, z)
+        }
+    }
+
+    /// Samples the noise at a 2D position.
+    #[must_use]
+    #[allow(clippy::many_single_char_names)]
+    pub fn get_value(&self, x: f64, y: f64) -> f64 {
+        let sqrt_3 = 3.0_f64.sqrt();
+        let f2 = 0.5 * (sqrt_3 - 1.0);
+        let g2 = (3.0 - sqrt_3) / 6.0;
+
+        let s = (x + y) * f2;
+        let i = floor(x + s);
+        let j = floor(y + s);
+        let t = f64::from(i.wrapping_add(j)) * g2;
+        let x0 = x - (f64::from(i) - t);
+        let y0 = y - (f64::from(j) - t);
+        let (i1, j1) = if x0 > y0 { (1, 0) } else { (0, 1) };
+        let x1 = x0 - f64::from(i1) + g2;
+        let y1 = y0 - f64::from(j1) + g2;
+        let x2 = x0 - 1.0 + 2.0 * g2;
+        let y2 = y0 - 1.0 + 2.0 * g2;
+
+        let ii = i & 0xFF;
+        let jj = j & 0xFF;
+        let gi0 = self.p(ii + self.p(jj)) % 12;
+        let gi1 = self.p(ii + i1 + self.p(jj + j1)) % 12;
+        let gi2 = self.p(ii + 1 + self.p(jj + 1)) % 12;
+
+        let n0 = Self::corner_noise(gi0, x0, y0, 0.0, 0.5);
+        let n1 = Self::corner_noise(gi1, x1, y1, 0.0, 0.5);
+        let n2 = Self::corner_noise(gi2, x2, y2, 0.0, 0.5);
+        70.0 * (n0 + n1 + n2)
+    }
+}
@@ -156,6 +156,15 @@ mod test {
         }
     }
 
+    #[test]
+    fn test_consume_count() {
+        // Vanilla skips with `nextInt()`, one LCG step each, so this lands on the fourth
+        // value of `new Random(0)`.
+        let mut rand = LegacyRandom::from_seed(0);
+        rand.consume_count(3);
+        assert_eq!(rand.next_i32(), -1_690_734_402);
+    }
+
     #[test]
     fn test_next_i32_bounded() {
         let mut rand = LegacyRandom::from_seed(0);
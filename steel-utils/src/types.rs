@@ -437,6 +437,18 @@ impl GameType {
             GameType::Spectator => "spectator",
         }
     }
+
+    /// Returns the game type with the given network id, or `None` if the id is unknown.
+    #[must_use]
+    pub const fn by_id(id: i32) -> Option<Self> {
+        match id {
+            0 => Some(GameType::Survival),
+            1 => Some(GameType::Creative),
+            2 => Some(GameType::Adventure),
+            3 => Some(GameType::Spectator),
+            _ => None,
+        }
+    }
 }
 
 #[allow(missing_docs)]
@@ -8,7 +8,10 @@ use base64::{Engine, prelude::BASE64_STANDARD};
 use std::{fs, path::Path, sync::LazyLock};
 
 // Re-export types from steel-core for convenience
-pub use steel_core::config::{ConfigLabel, ConfigLink, ServerConfig, ServerConfigRef, ServerLinks};
+pub use steel_core::config::{
+    ConfigLabel, ConfigLink, ForwardingMode, ProxyConfig, QueryConfig, RconConfig, ServerConfig,
+    ServerConfigRef, ServerLinks,
+};
 
 #[cfg(feature = "stand-alone")]
 const DEFAULT_FAVICON: &[u8] = include_bytes!("../../package-content/favicon.png");
@@ -80,6 +83,24 @@ fn validate(config: &ServerConfig) -> Result<(), &'static str> {
             return Err("Compression level must be between 1 and 9");
         }
     }
+    if let Some(rcon) = &config.rcon
+        && rcon.enable
+        && rcon.password.is_empty()
+    {
+        return Err("RCON password must not be empty when RCON is enabled");
+    }
+    if let Some(proxy) = &config.proxy
+        && proxy.forwarding == ForwardingMode::Velocity
+        && proxy.velocity_secret.is_empty()
+    {
+        return Err("Velocity secret must not be empty when Velocity forwarding is enabled");
+    }
+    if let Some(proxy) = &config.proxy
+        && proxy.proxy_protocol
+        && proxy.trusted_proxies.is_empty()
+    {
+        return Err("Trusted proxies must not be empty when the PROXY protocol is enabled");
+    }
     if config.enforce_secure_chat {
         if !config.online_mode {
             return Err("online_mode must be true when enforce_secure_chat is enabled");
@@ -0,0 +1,371 @@
+//! Interactive server console.
+//!
+//! Reads commands from stdin with line editing, history and tab completion, and runs
+//! them as [`CommandSender::Console`]. While the console is reading, log lines are
+//! printed above the prompt through [`ConsoleLogWriter`] instead of over it.
+
+use std::io::{self, Write};
+#[cfg(unix)]
+use std::mem::MaybeUninit;
+#[cfg(unix)]
+use std::os::unix::thread::JoinHandleExt;
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use crossbeam::channel::{self, Receiver, RecvTimeoutError};
+
+use rustyline::completion::{Completer, Pair};
+use rustyline::error::ReadlineError;
+use rustyline::highlight::Highlighter;
+use rustyline::hint::Hinter;
+use rustyline::history::DefaultHistory;
+use rustyline::validate::Validator;
+use rustyline::{CompletionType, Config, Context, Editor, ExternalPrinter, Helper};
+use steel_core::command::sender::CommandSender;
+use steel_core::server::Server;
+use steel_utils::locks::SyncMutex;
+use tokio::runtime::Handle;
+use tokio_util::sync::CancellationToken;
+use tracing_subscriber::fmt::MakeWriter;
+
+/// Prompt shown in front of the line being typed.
+const PROMPT: &str = "> ";
+/// How long shutdown waits for the console thread after interrupting it.
+const STOP_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Prints messages above the prompt without breaking the line being typed.
+type Printer = Box<dyn ExternalPrinter + Send>;
+
+/// A tracing writer that keeps log lines from tearing through the console prompt.
+///
+/// Output goes directly to stderr until a console attaches to it.
+///
+/// Internally reference-counted — cloning is cheap and shares the same state.
+#[derive(Clone, Default)]
+pub struct ConsoleLogWriter {
+    printer: Arc<SyncMutex<Option<Printer>>>,
+}
+
+impl ConsoleLogWriter {
+    /// Creates a new writer in normal (stderr) mode.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Writes one formatted log line, above the prompt if a console is reading.
+    pub fn write_line(&self, line: &[u8]) {
+        let mut printer = self.printer.lock();
+        if let Some(printer) = printer.as_mut()
+            && printer
+                .print(String::from_utf8_lossy(line).into_owned())
+                .is_ok()
+        {
+            return;
+        }
+        drop(printer);
+        let _ = io::stderr().write_all(line);
+    }
+
+    /// Routes log lines through the console's prompt.
+    fn attach(&self, printer: Printer) {
+        *self.printer.lock() = Some(printer);
+    }
+
+    /// Goes back to writing log lines straight to stderr.
+    fn detach(&self) {
+        self.printer.lock().take();
+    }
+}
+
+impl<'a> MakeWriter<'a> for ConsoleLogWriter {
+    type Writer = ConsoleLogTarget;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        ConsoleLogTarget {
+            writer: self.clone(),
+            buffer: Vec::with_capacity(256),
+        }
+    }
+}
+
+/// Per-log-event writer that buffers the formatted line and flushes on drop.
+pub struct ConsoleLogTarget {
+    writer: ConsoleLogWriter,
+    buffer: Vec<u8>,
+}
+
+impl Write for ConsoleLogTarget {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.buffer.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Drop for ConsoleLogTarget {
+    fn drop(&mut self) {
+        if !self.buffer.is_empty() {
+            self.writer.write_line(&self.buffer);
+        }
+    }
+}
+
+/// Completes console input with the suggestions players get in chat.
+struct ConsoleHelper {
+    server: Arc<Server>,
+}
+
+impl Completer for ConsoleHelper {
+    type Candidate = Pair;
+
+    fn complete(
+        &self,
+        line: &str,
+        pos: usize,
+        _ctx: &Context<'_>,
+    ) -> rustyline::Result<(usize, Vec<Pair>)> {
+        let line = &line[..pos];
+        let command = line.strip_prefix('/').unwrap_or(line);
+        let slash_len = line.len() - command.len();
+
+        let result = self.server.command_dispatcher.read().suggest(
+            CommandSender::Console,
+            command,
+            self.server.clone(),
+        );
+        let Some(result) = result else {
+            return Ok((pos, Vec::new()));
+        };
+
+        let candidates = result
+            .suggestions
+            .into_iter()
+            .map(|entry| Pair {
+                display: entry.text.clone(),
+                replacement: entry.text,
+            })
+            .collect();
+        Ok((slash_len + result.start as usize, candidates))
+    }
+}
+
+impl Hinter for ConsoleHelper {
+    type Hint = String;
+}
+
+impl Highlighter for ConsoleHelper {}
+
+impl Validator for ConsoleHelper {}
+
+impl Helper for ConsoleHelper {}
+
+/// The thread reading console commands, returned by [`start`].
+pub struct Console {
+    thread: Option<JoinHandle<()>>,
+    /// Disconnects once the thread is done with the terminal.
+    finished: Receiver<()>,
+    /// The terminal mode from before the console started reading.
+    terminal_mode: TerminalMode,
+}
+
+impl Console {
+    /// Stops reading commands, waits for the console thread to exit and puts the terminal
+    /// back into the mode it had before the console started.
+    ///
+    /// Must be called after the server's cancel token is cancelled, otherwise the console
+    /// just reads the next line.
+    pub fn stop(self) {
+        if let Some(thread) = self.thread {
+            stop_thread(thread, &self.finished);
+        }
+        // Rustyline leaves raw mode once its read is interrupted, but a thread that didn't
+        // exit may still be in it
+        self.terminal_mode.restore();
+    }
+}
+
+/// Interrupts the console thread and waits for it to exit.
+fn stop_thread(thread: JoinHandle<()>, finished: &Receiver<()>) {
+    if !thread.is_finished() && !interrupt(&thread) {
+        // The thread can't be woken up, it exits with the process instead
+        return;
+    }
+    if let Err(RecvTimeoutError::Timeout) = finished.recv_timeout(STOP_TIMEOUT) {
+        // Reads from a stdin that isn't a terminal can't be interrupted
+        log::warn!(
+            "The console thread did not stop within {}s, it exits with the process instead",
+            STOP_TIMEOUT.as_secs()
+        );
+        return;
+    }
+    if thread.join().is_err() {
+        log::error!("The console thread panicked");
+    }
+}
+
+/// The terminal mode of stdin, saved to be restored when the console stops.
+#[cfg(unix)]
+struct TerminalMode(Option<libc::termios>);
+
+#[cfg(unix)]
+impl TerminalMode {
+    /// Saves the current mode, or nothing if stdin isn't a terminal.
+    fn save() -> Self {
+        let mut mode = MaybeUninit::<libc::termios>::uninit();
+        // SAFETY: `tcgetattr` only writes to the termios it is given, and fully initializes
+        // it when it succeeds.
+        let saved = unsafe { libc::tcgetattr(libc::STDIN_FILENO, mode.as_mut_ptr()) == 0 };
+        // SAFETY: Checked that `tcgetattr` succeeded.
+        Self(saved.then(|| unsafe { mode.assume_init() }))
+    }
+
+    /// Puts the terminal back into the saved mode.
+    fn restore(&self) {
+        let Some(mode) = &self.0 else {
+            return;
+        };
+        // SAFETY: `mode` is a termios filled in by `tcgetattr`.
+        if unsafe { libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, mode) } != 0 {
+            log::warn!(
+                "Failed to restore the terminal mode: {}",
+                io::Error::last_os_error()
+            );
+        }
+    }
+}
+
+/// The terminal mode of stdin, which can only be saved on Unix.
+#[cfg(not(unix))]
+struct TerminalMode;
+
+#[cfg(not(unix))]
+impl TerminalMode {
+    /// Saves nothing, rustyline restores the console mode itself.
+    const fn save() -> Self {
+        Self
+    }
+
+    /// Does nothing, since nothing was saved.
+    #[allow(clippy::unused_self)]
+    const fn restore(&self) {}
+}
+
+/// Wakes the console thread from reading a line, which it sees as Ctrl-C.
+///
+/// Returns whether the thread was signalled.
+#[cfg(unix)]
+fn interrupt(thread: &JoinHandle<()>) -> bool {
+    // SAFETY: The thread hasn't been joined, so its handle is valid. Rustyline handles
+    // SIGINT while reading a line and the runtime's Ctrl-C handler does otherwise.
+    unsafe { libc::pthread_kill(thread.as_pthread_t(), libc::SIGINT) == 0 }
+}
+
+/// Wakes the console thread from reading a line, which it sees as Ctrl-C.
+///
+/// Returns whether the thread was signalled, which is only possible on Unix.
+#[cfg(not(unix))]
+fn interrupt(_thread: &JoinHandle<()>) -> bool {
+    false
+}
+
+/// Starts reading console commands.
+///
+/// Reading a line blocks, so the console runs on its own thread instead of a runtime
+/// worker. It stops once stdin is closed or [`Console::stop`] is called.
+#[must_use]
+pub fn start(
+    server: Arc<Server>,
+    log_writer: ConsoleLogWriter,
+    cancel_token: CancellationToken,
+) -> Console {
+    let runtime = Handle::current();
+    let terminal_mode = TerminalMode::save();
+    let (finished_sender, finished) = channel::bounded(0);
+    let spawned = thread::Builder::new()
+        .name("console".to_owned())
+        .spawn(move || {
+            let _finished = finished_sender;
+            // Commands may spawn tasks, so they need to run inside the runtime
+            let _guard = runtime.enter();
+            read_commands(&server, &log_writer, &cancel_token);
+            log_writer.detach();
+        });
+
+    match spawned {
+        Ok(thread) => Console {
+            thread: Some(thread),
+            finished,
+            terminal_mode,
+        },
+        Err(e) => {
+            log::error!("Failed to start the console: {e}");
+            Console {
+                thread: None,
+                finished,
+                terminal_mode,
+            }
+        }
+    }
+}
+
+/// Reads and dispatches commands until the server stops or stdin is closed.
+fn read_commands(
+    server: &Arc<Server>,
+    log_writer: &ConsoleLogWriter,
+    cancel_token: &CancellationToken,
+) {
+    let config = Config::builder()
+        .completion_type(CompletionType::List)
+        .build();
+    let mut editor: Editor<ConsoleHelper, DefaultHistory> = match Editor::with_config(config) {
+        Ok(editor) => editor,
+        Err(e) => {
+            log::warn!("Console input is unavailable: {e}");
+            return;
+        }
+    };
+    editor.set_helper(Some(ConsoleHelper {
+        server: server.clone(),
+    }));
+    match editor.create_external_printer() {
+        Ok(printer) => log_writer.attach(Box::new(printer)),
+        Err(e) => log::warn!("Log lines may overwrite the console prompt: {e}"),
+    }
+
+    while !cancel_token.is_cancelled() {
+        match editor.readline(PROMPT) {
+            Ok(line) => {
+                let line = line.trim();
+                if line.is_empty() {
+                    continue;
+                }
+                let _ = editor.add_history_entry(line);
+
+                let command = line.strip_prefix('/').unwrap_or(line);
+                server.command_dispatcher.read().handle_command(
+                    CommandSender::Console,
+                    command.to_owned(),
+                    server,
+                );
+            }
+            // The terminal is in raw mode while reading, so Ctrl-C never reaches the signal handler
+            Err(ReadlineError::Interrupted) => {
+                // Console::stop interrupts the read once the server is shutting down
+                if !cancel_token.is_cancelled() {
+                    log::info!("Shutdown signal received");
+                    cancel_token.cancel();
+                }
+            }
+            Err(ReadlineError::Eof) => break,
+            Err(e) => {
+                log::error!("Failed to read console input: {e}");
+                break;
+            }
+        }
+    }
+}
@@ -3,19 +3,34 @@
 //! The main library for the Steel Minecraft server.
 
 use std::{
-    net::{Ipv4Addr, SocketAddrV4},
+    net::{Ipv4Addr, SocketAddr, SocketAddrV4},
     sync::Arc,
 };
 
 use steel_core::server::Server;
 use steel_login::JavaTcpClient;
-use tokio::{net::TcpListener, runtime::Runtime, select};
+use tokio::{
+    net::{TcpListener, TcpStream},
+    runtime::Runtime,
+    select,
+    time::timeout,
+};
 use tokio_util::{sync::CancellationToken, task::TaskTracker};
 
 /// Server configuration module.
 pub mod config;
+/// Interactive server console.
+pub mod console;
+/// HAProxy PROXY protocol headers sent by load balancers.
+pub mod proxy_protocol;
+/// GameSpy4 query listener for server lists and monitoring tools.
+pub mod query;
+/// Remote console over the Source RCON protocol.
+pub mod rcon;
 /// Spawn chunk generation with optional terminal progress display.
 pub mod spawn_progress;
+/// Conversion of vanilla worlds from the command line.
+pub mod world_convert;
 
 pub use config::{MC_VERSION, STEEL_CONFIG};
 
@@ -74,24 +89,92 @@ impl SteelServer {
                     break;
                 }
                 accept_result = self.tcp_listener.accept() => {
-                    let Ok((connection, address)) = accept_result else {
+                    let Ok((mut connection, address)) = accept_result else {
                         continue;
                     };
                     if let Err(e) = connection.set_nodelay(true) {
                         log::warn!("Failed to set TCP_NODELAY: {e}");
                     }
-                    let (java_client, sender_recv, net_reader) = JavaTcpClient::new(connection, address, self.client_id, self.cancel_token.child_token(), self.server.clone(), task_tracker.clone());
+                    let id = self.client_id;
                     self.client_id = self.client_id.wrapping_add(1);
-                    log::info!("Accepted connection from Java Edition: {address} (id {})", self.client_id);
 
-                    let java_client = Arc::new(java_client);
-                    java_client.start_outgoing_packet_task(sender_recv);
-                    java_client.start_incoming_packet_task(net_reader);
-                    // Java_client won't drop until the incoming and outcoming task close
-                    // So we dont need to care about them here anymore
+                    if proxy_protocol::expects_header(STEEL_CONFIG.proxy.as_ref(), address) {
+                        // Read the header in its own task, so a slow proxy doesn't hold up
+                        // accepting other connections
+                        let cancel_token = self.cancel_token.child_token();
+                        let server = self.server.clone();
+                        let tracker = task_tracker.clone();
+                        task_tracker.spawn(async move {
+                            let Some(address) =
+                                read_proxy_header(&mut connection, address).await
+                            else {
+                                return;
+                            };
+                            Self::start_client(
+                                connection,
+                                address,
+                                id,
+                                cancel_token,
+                                server,
+                                tracker,
+                            );
+                        });
+                    } else {
+                        Self::start_client(
+                            connection,
+                            address,
+                            id,
+                            self.cancel_token.child_token(),
+                            self.server.clone(),
+                            task_tracker.clone(),
+                        );
+                    }
                 }
             }
         }
         let _ = server_handle.await;
     }
+
+    /// Starts the packet tasks of a newly accepted client.
+    fn start_client(
+        connection: TcpStream,
+        address: SocketAddr,
+        id: u64,
+        cancel_token: CancellationToken,
+        server: Arc<Server>,
+        task_tracker: TaskTracker,
+    ) {
+        let (java_client, sender_recv, net_reader) =
+            JavaTcpClient::new(connection, address, id, cancel_token, server, task_tracker);
+        log::info!("Accepted connection from Java Edition: {address} (id {id})");
+
+        let java_client = Arc::new(java_client);
+        java_client.start_outgoing_packet_task(sender_recv);
+        java_client.start_incoming_packet_task(net_reader);
+        // Java_client won't drop until the incoming and outcoming task close
+        // So we dont need to care about them here anymore
+    }
+}
+
+/// Reads the PROXY protocol header a trusted proxy sends ahead of the client's bytes.
+///
+/// Returns the relayed client's address, `peer` itself if the proxy made the connection on
+/// its own, or `None` if the header is missing or invalid and the connection is dropped.
+async fn read_proxy_header(connection: &mut TcpStream, peer: SocketAddr) -> Option<SocketAddr> {
+    match timeout(
+        proxy_protocol::HEADER_TIMEOUT,
+        proxy_protocol::read_header(connection),
+    )
+    .await
+    {
+        Ok(Ok(address)) => Some(address.unwrap_or(peer)),
+        Ok(Err(e)) => {
+            log::warn!("Invalid PROXY protocol header from {peer}: {e}");
+            None
+        }
+        Err(_) => {
+            log::warn!("Timed out waiting for a PROXY protocol header from {peer}");
+            None
+        }
+    }
 }
@@ -1,11 +1,13 @@
 //! Main entry point for the Steel Minecraft server.
 
-use std::sync::Arc;
+use std::{env, process::ExitCode, sync::Arc};
 
-use steel::SteelServer;
+use steel::console::{self, ConsoleLogWriter};
 #[cfg(feature = "spawn_chunk_display")]
 use steel::spawn_progress::SwitchableWriter;
 use steel::spawn_progress::generate_spawn_chunks;
+use steel::{STEEL_CONFIG, SteelServer};
+use steel::{query, rcon, world_convert};
 use steel_utils::text::DisplayResolutor;
 use text_components::fmt::set_display_resolutor;
 use tokio::{
@@ -58,7 +60,7 @@ fn init_jaeger() {
 }
 
 #[cfg(not(feature = "spawn_chunk_display"))]
-fn init_tracing() {
+fn init_tracing(console: &ConsoleLogWriter) {
     #[cfg(feature = "jaeger")]
     {
         use opentelemetry::global;
@@ -76,6 +78,7 @@ fn init_tracing() {
             .with(
                 fmt::layer()
                     .with_timer(fmt::time::uptime())
+                    .with_writer(console.clone())
                     .with_filter(default_env_filter()),
             )
             .init();
@@ -84,15 +87,19 @@ fn init_tracing() {
     #[cfg(not(feature = "jaeger"))]
     {
         tracing_subscriber::registry()
-            .with(fmt::layer().with_timer(fmt::time::uptime()))
+            .with(
+                fmt::layer()
+                    .with_timer(fmt::time::uptime())
+                    .with_writer(console.clone()),
+            )
             .with(default_env_filter())
             .init();
     }
 }
 
 #[cfg(feature = "spawn_chunk_display")]
-fn init_tracing() -> SwitchableWriter {
-    let writer = SwitchableWriter::new();
+fn init_tracing(console: &ConsoleLogWriter) -> SwitchableWriter {
+    let writer = SwitchableWriter::new(console.clone());
 
     #[cfg(feature = "jaeger")]
     {
@@ -151,7 +158,7 @@ static ALLOC: mimalloc::MiMalloc = mimalloc::MiMalloc;
 ///
 /// We have to create the runtimes at this level cause tokio panics if you drop a runtime in a context where blocking is not allowed.
 #[allow(clippy::unwrap_used)]
-fn main() {
+fn main() -> ExitCode {
     #[cfg(feature = "dhat-heap")]
     let _profiler = dhat::Profiler::new_heap();
 
@@ -159,27 +166,40 @@ fn main() {
 
     let main_runtime = Builder::new_multi_thread().enable_all().build().unwrap();
 
+    // Arguments select a world conversion instead of starting the server
+    let args: Vec<String> = env::args().skip(1).collect();
+    if !args.is_empty() {
+        tracing_subscriber::registry()
+            .with(fmt::layer())
+            .with(default_env_filter())
+            .init();
+        return main_runtime.block_on(world_convert::run(&args));
+    }
+
     main_runtime.block_on(main_async(chunk_runtime.clone()));
 
     drop(main_runtime);
     drop(chunk_runtime);
+    ExitCode::SUCCESS
 }
 
 async fn main_async(chunk_runtime: Arc<Runtime>) {
+    let console = ConsoleLogWriter::new();
     #[cfg(feature = "spawn_chunk_display")]
     {
-        let writer = init_tracing();
-        run_server(chunk_runtime, &writer).await;
+        let writer = init_tracing(&console);
+        run_server(chunk_runtime, console, &writer).await;
     }
     #[cfg(not(feature = "spawn_chunk_display"))]
     {
-        init_tracing();
-        run_server(chunk_runtime).await;
+        init_tracing(&console);
+        run_server(chunk_runtime, console).await;
     }
 }
 
 async fn run_server(
     chunk_runtime: Arc<Runtime>,
+    console: ConsoleLogWriter,
     #[cfg(feature = "spawn_chunk_display")] writer: &SwitchableWriter,
 ) {
     set_display_resolutor(&DisplayResolutor);
@@ -229,9 +249,31 @@ async fn run_server(
         }
     });
 
+    let console_reader = console::start(server.clone(), console, steel.cancel_token.clone());
+
     let task_tracker = TaskTracker::new();
 
+    rcon::start(
+        server.clone(),
+        STEEL_CONFIG.rcon.as_ref(),
+        &task_tracker,
+        steel.cancel_token.clone(),
+    )
+    .await;
+    query::start(
+        server.clone(),
+        steel
+            .tcp_listener
+            .local_addr()
+            .expect("Failed to get the server address"),
+        STEEL_CONFIG.query.as_ref(),
+        &task_tracker,
+        steel.cancel_token.clone(),
+    )
+    .await;
+
     steel.start(task_tracker.clone()).await;
+    console_reader.stop();
 
     log::info!("Waiting for pending tasks...");
 
@@ -243,6 +285,10 @@ async fn run_server(
         world.chunk_map.task_tracker.wait().await;
     }
 
+    // Players still online at this point have not been saved on disconnect
+    log::info!("Saving player data...");
+    server.save_players().await;
+
     // Save all dirty chunks before shutdown
     log::info!("Saving world data...");
     let mut total_saved = 0;
@@ -0,0 +1,179 @@
+//! HAProxy PROXY protocol.
+//!
+//! Load balancers that speak the PROXY protocol send a header with the real client address
+//! before any of the client's own bytes. Version 1 is a single text line such as
+//! `PROXY TCP4 203.0.113.7 10.0.0.1 51234 25565\r\n`, version 2 is a binary header behind a
+//! fixed 12 byte signature. Headers are only read from configured trusted sources, so other
+//! clients can't spoof their address by sending one themselves.
+
+use std::{
+    io,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
+    time::Duration,
+};
+
+use tokio::io::{AsyncRead, AsyncReadExt};
+
+use crate::config::ProxyConfig;
+
+/// Signature every version 2 header starts with.
+const V2_SIGNATURE: [u8; 12] = *b"\r\n\r\n\0\r\nQUIT\n";
+/// Prefix every version 1 header starts with.
+const V1_PREFIX: &[u8] = b"PROXY ";
+/// Longest version 1 header, including the line break, as set by the specification.
+const V1_MAX_LEN: usize = 107;
+/// How long a trusted source gets to send its header before the connection is dropped.
+pub const HEADER_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Version 2 command sent for connections the proxy makes on its own, like health checks.
+const V2_COMMAND_LOCAL: u8 = 0x0;
+/// Version 2 command sent for relayed connections.
+const V2_COMMAND_PROXY: u8 = 0x1;
+/// Version 2 address family for IPv4.
+const V2_FAMILY_INET: u8 = 0x1;
+/// Version 2 address family for IPv6.
+const V2_FAMILY_INET6: u8 = 0x2;
+
+/// Returns whether connections from `address` have to start with a PROXY protocol header.
+#[must_use]
+pub fn expects_header(config: Option<&ProxyConfig>, address: SocketAddr) -> bool {
+    config.is_some_and(|config| {
+        config.proxy_protocol && config.trusted_proxies.contains(&address.ip())
+    })
+}
+
+/// Reads a PROXY protocol header of either version.
+///
+/// Returns the address of the client the proxy relays, or `None` if the proxy made the
+/// connection itself and the peer address should be kept.
+///
+/// # Errors
+/// Returns an error if the stream doesn't start with a valid header.
+pub async fn read_header(reader: &mut (impl AsyncRead + Unpin)) -> io::Result<Option<SocketAddr>> {
+    // Both versions are at least this long, so this never reads past the header
+    let mut start = [0; V2_SIGNATURE.len()];
+    reader.read_exact(&mut start).await?;
+
+    if start == V2_SIGNATURE {
+        let mut header = [0; 4];
+        reader.read_exact(&mut header).await?;
+        let mut addresses = vec![0; usize::from(u16::from_be_bytes([header[2], header[3]]))];
+        reader.read_exact(&mut addresses).await?;
+        return parse_v2(header[0], header[1], &addresses);
+    }
+
+    if !start.starts_with(V1_PREFIX) {
+        return Err(invalid("missing PROXY protocol header"));
+    }
+    let mut line = start.to_vec();
+    while !line.ends_with(b"\r\n") {
+        if line.len() >= V1_MAX_LEN {
+            return Err(invalid("PROXY protocol header is too long"));
+        }
+        line.push(reader.read_u8().await?);
+    }
+    let line = str::from_utf8(&line).map_err(|_| invalid("PROXY protocol header is not ASCII"))?;
+    parse_v1(line)
+}
+
+/// Parses a version 1 header line, including its line break.
+fn parse_v1(line: &str) -> io::Result<Option<SocketAddr>> {
+    let mut parts = line.trim_end_matches("\r\n").split(' ').skip(1);
+    match parts.next() {
+        Some("TCP4" | "TCP6") => {}
+        // The proxy doesn't know the client, so the rest of the line can be ignored
+        Some("UNKNOWN") => return Ok(None),
+        _ => return Err(invalid("unknown PROXY protocol version 1 family")),
+    }
+
+    let (Some(source), Some(_destination), Some(source_port), Some(_destination_port), None) = (
+        parts.next(),
+        parts.next(),
+        parts.next(),
+        parts.next(),
+        parts.next(),
+    ) else {
+        return Err(invalid("malformed PROXY protocol version 1 header"));
+    };
+
+    let ip: IpAddr = source
+        .parse()
+        .map_err(|_| invalid("invalid PROXY protocol source address"))?;
+    let port: u16 = source_port
+        .parse()
+        .map_err(|_| invalid("invalid PROXY protocol source port"))?;
+    Ok(Some(SocketAddr::new(ip, port)))
+}
+
+/// Parses the part of a version 2 header after the signature.
+fn parse_v2(version_command: u8, family: u8, addresses: &[u8]) -> io::Result<Option<SocketAddr>> {
+    if version_command >> 4 != 2 {
+        return Err(invalid("unknown PROXY protocol version"));
+    }
+    match version_command & 0x0F {
+        V2_COMMAND_LOCAL => return Ok(None),
+        V2_COMMAND_PROXY => {}
+        _ => return Err(invalid("unknown PROXY protocol command")),
+    }
+
+    // Addresses are followed by the destination address and ports, then optional TLVs
+    let source = match family >> 4 {
+        V2_FAMILY_INET => addresses.get(..12).map(|block| {
+            let ip = Ipv4Addr::new(block[0], block[1], block[2], block[3]);
+            (IpAddr::V4(ip), [block[8], block[9]])
+        }),
+        V2_FAMILY_INET6 => addresses.get(..36).map(|block| {
+            let ip = Ipv6Addr::from(<[u8; 16]>::try_from(&block[..16]).expect("Length checked"));
+            (IpAddr::V6(ip), [block[32], block[33]])
+        }),
+        // Unix sockets and unspecified families carry no usable client address
+        _ => return Ok(None),
+    };
+
+    let (ip, port) = source.ok_or_else(|| invalid("PROXY protocol address block is too short"))?;
+    Ok(Some(SocketAddr::new(ip, u16::from_be_bytes(port))))
+}
+
+fn invalid(message: &'static str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn reads_version_1_and_leaves_the_rest() {
+        let mut data: &[u8] = b"PROXY TCP4 203.0.113.7 10.0.0.1 51234 25565\r\nhandshake";
+        let address = read_header(&mut data).await.expect("Valid header");
+        assert_eq!(
+            address,
+            Some("203.0.113.7:51234".parse().expect("Valid address"))
+        );
+        assert_eq!(data, b"handshake");
+
+        let mut unknown: &[u8] = b"PROXY UNKNOWN\r\n";
+        assert_eq!(read_header(&mut unknown).await.expect("Valid header"), None);
+    }
+
+    #[tokio::test]
+    async fn reads_version_2() {
+        let mut data = V2_SIGNATURE.to_vec();
+        data.extend_from_slice(&[0x21, 0x21, 0, 36]);
+        data.extend_from_slice(&Ipv6Addr::LOCALHOST.octets());
+        data.extend_from_slice(&Ipv6Addr::UNSPECIFIED.octets());
+        data.extend_from_slice(&[0xC8, 0x1E, 0x63, 0xDD]);
+        data.extend_from_slice(b"handshake");
+
+        let mut reader = data.as_slice();
+        let address = read_header(&mut reader).await.expect("Valid header");
+        assert_eq!(address, Some("[::1]:51230".parse().expect("Valid address")));
+        assert_eq!(reader, b"handshake");
+    }
+
+    #[tokio::test]
+    async fn rejects_missing_headers() {
+        let mut data: &[u8] = b"\x10\x00\xfa\x05\x09localhost";
+        assert!(read_header(&mut data).await.is_err());
+    }
+}
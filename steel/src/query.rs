@@ -0,0 +1,380 @@
+//! GameSpy4 query listener.
+//!
+//! Answers the UDP query protocol server lists and monitoring tools use. Clients first
+//! send a handshake and get a challenge token back, which they have to repeat in their
+//! stat requests. A basic stat reports the MOTD, map and player counts; a full stat adds
+//! the version and the names of every online player.
+
+use std::{
+    net::{Ipv4Addr, SocketAddr, SocketAddrV4},
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use rustc_hash::FxHashMap;
+use steel_core::server::Server;
+use tokio::{net::UdpSocket, select};
+use tokio_util::{sync::CancellationToken, task::TaskTracker};
+
+use crate::config::{QueryConfig, STEEL_CONFIG};
+use crate::world_convert::WORLD_DIR;
+
+/// First two bytes of every request.
+const MAGIC: [u8; 2] = [0xFE, 0xFD];
+/// Request type that asks for a challenge token.
+const TYPE_HANDSHAKE: u8 = 9;
+/// Request type that asks for a basic or full stat.
+const TYPE_STAT: u8 = 0;
+
+/// Length of a basic stat request: magic, type, session id and challenge token.
+const BASIC_STAT_LENGTH: usize = 11;
+/// Length of a full stat request, a basic one padded with four bytes.
+const FULL_STAT_LENGTH: usize = 15;
+/// How long a challenge token stays valid, the same as vanilla.
+const CHALLENGE_LIFETIME: Duration = Duration::from_secs(30);
+/// Largest request read, anything longer is not a query packet.
+const MAX_REQUEST_LENGTH: usize = 1460;
+
+/// Game type reported to query clients.
+const GAME_TYPE: &str = "SMP";
+/// Game id reported in full stats.
+const GAME_ID: &str = "MINECRAFT";
+
+/// What the server reports about itself.
+struct QueryStatus {
+    /// The message of the day.
+    motd: String,
+    /// The version name.
+    version: String,
+    /// The name of the world.
+    map: String,
+    /// The names of the online players.
+    players: Vec<String>,
+    /// The maximum number of players.
+    max_players: u32,
+    /// The port players connect to.
+    host_port: u16,
+    /// The address players connect to.
+    host_ip: String,
+}
+
+/// A challenge token handed out to one client address.
+struct Challenge {
+    token: i32,
+    created: Instant,
+}
+
+/// Answers query requests, keeping track of the challenges handed out.
+///
+/// Based on vanilla's `QueryThreadGs4`.
+#[derive(Default)]
+struct QueryHandler {
+    challenges: FxHashMap<SocketAddr, Challenge>,
+}
+
+impl QueryHandler {
+    /// Returns the response to a request, or `None` if it is invalid or not answered.
+    fn handle(
+        &mut self,
+        request: &[u8],
+        from: SocketAddr,
+        now: Instant,
+        status: impl FnOnce() -> QueryStatus,
+    ) -> Option<Vec<u8>> {
+        if request.len() < 7 || request[..2] != MAGIC {
+            return None;
+        }
+        let kind = request[2];
+        let session_id = &request[3..7];
+
+        match kind {
+            TYPE_HANDSHAKE => {
+                self.challenges
+                    .retain(|_, challenge| now - challenge.created < CHALLENGE_LIFETIME);
+                let token = rand::random::<i32>() & 0x00FF_FFFF;
+                self.challenges.insert(
+                    from,
+                    Challenge {
+                        token,
+                        created: now,
+                    },
+                );
+
+                let mut response = response_header(TYPE_HANDSHAKE, session_id);
+                write_string(&mut response, &token.to_string());
+                Some(response)
+            }
+            TYPE_STAT if request.len() >= BASIC_STAT_LENGTH => {
+                let token = i32::from_be_bytes(request[7..11].try_into().ok()?);
+                let challenge = self.challenges.get(&from)?;
+                if challenge.token != token || now - challenge.created >= CHALLENGE_LIFETIME {
+                    return None;
+                }
+
+                let status = status();
+                let response = if request.len() == FULL_STAT_LENGTH {
+                    full_stat(session_id, &status)
+                } else {
+                    basic_stat(session_id, &status)
+                };
+                Some(response)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Starts a response with its type and the client's session id.
+fn response_header(kind: u8, session_id: &[u8]) -> Vec<u8> {
+    let mut response = Vec::with_capacity(64);
+    response.push(kind);
+    response.extend_from_slice(session_id);
+    response
+}
+
+/// Writes a NUL-terminated string.
+fn write_string(buffer: &mut Vec<u8>, value: &str) {
+    buffer.extend_from_slice(value.as_bytes());
+    buffer.push(0);
+}
+
+/// Builds a basic stat response.
+fn basic_stat(session_id: &[u8], status: &QueryStatus) -> Vec<u8> {
+    let mut response = response_header(TYPE_STAT, session_id);
+    write_string(&mut response, &status.motd);
+    write_string(&mut response, GAME_TYPE);
+    write_string(&mut response, &status.map);
+    write_string(&mut response, &status.players.len().to_string());
+    write_string(&mut response, &status.max_players.to_string());
+    response.extend_from_slice(&status.host_port.to_le_bytes());
+    write_string(&mut response, &status.host_ip);
+    response
+}
+
+/// Builds a full stat response: a key/value section followed by the player names.
+fn full_stat(session_id: &[u8], status: &QueryStatus) -> Vec<u8> {
+    let mut response = response_header(TYPE_STAT, session_id);
+    write_string(&mut response, "splitnum");
+    response.extend_from_slice(&[0x80, 0]);
+
+    let num_players = status.players.len().to_string();
+    let max_players = status.max_players.to_string();
+    let host_port = status.host_port.to_string();
+    let values = [
+        ("hostname", status.motd.as_str()),
+        ("gametype", GAME_TYPE),
+        ("game_id", GAME_ID),
+        ("version", status.version.as_str()),
+        ("plugins", ""),
+        ("map", status.map.as_str()),
+        ("numplayers", num_players.as_str()),
+        ("maxplayers", max_players.as_str()),
+        ("hostport", host_port.as_str()),
+        ("hostip", status.host_ip.as_str()),
+    ];
+    for (key, value) in values {
+        write_string(&mut response, key);
+        write_string(&mut response, value);
+    }
+    response.push(0);
+
+    response.push(1);
+    write_string(&mut response, "player_");
+    response.push(0);
+    for player in &status.players {
+        write_string(&mut response, player);
+    }
+    response.push(0);
+    response
+}
+
+/// Answers query requests until the token is cancelled.
+async fn serve<F>(socket: UdpSocket, status: F, cancel_token: CancellationToken)
+where
+    F: Fn() -> QueryStatus,
+{
+    let mut handler = QueryHandler::default();
+    let mut buffer = [0; MAX_REQUEST_LENGTH];
+    loop {
+        select! {
+            received = socket.recv_from(&mut buffer) => {
+                let (length, from) = match received {
+                    Ok(received) => received,
+                    Err(e) => {
+                        log::debug!("Failed to receive query packet: {e}");
+                        continue;
+                    }
+                };
+                let request = &buffer[..length];
+                let Some(response) = handler.handle(request, from, Instant::now(), &status) else {
+                    continue;
+                };
+                if let Err(e) = socket.send_to(&response, from).await {
+                    log::debug!("Failed to answer query from {from}: {e}");
+                }
+            }
+            () = cancel_token.cancelled() => break,
+        }
+    }
+}
+
+/// Collects what query clients are told about the server listening on `address`.
+fn server_status(server: &Server, address: SocketAddr) -> QueryStatus {
+    QueryStatus {
+        motd: STEEL_CONFIG.motd.clone(),
+        version: STEEL_CONFIG.mc_version.to_owned(),
+        map: WORLD_DIR.to_owned(),
+        players: server
+            .get_players()
+            .iter()
+            .map(|player| player.gameprofile.name.clone())
+            .collect(),
+        max_players: STEEL_CONFIG.max_players,
+        host_port: address.port(),
+        host_ip: address.ip().to_string(),
+    }
+}
+
+/// Starts the query listener if it is enabled in the config, reporting the game server
+/// at `game_address`.
+///
+/// Failing to bind the port is logged and leaves the query protocol disabled, the game
+/// server keeps running either way.
+pub async fn start(
+    server: Arc<Server>,
+    game_address: SocketAddr,
+    config: Option<&QueryConfig>,
+    task_tracker: &TaskTracker,
+    cancel_token: CancellationToken,
+) {
+    let Some(config) = config.filter(|config| config.enable) else {
+        return;
+    };
+    let socket = match UdpSocket::bind(SocketAddrV4::new(Ipv4Addr::UNSPECIFIED, config.port)).await
+    {
+        Ok(socket) => socket,
+        Err(e) => {
+            log::warn!("Failed to start query on port {}: {e}", config.port);
+            return;
+        }
+    };
+    log::info!("Query running on port {}", config.port);
+
+    let status = move || server_status(&server, game_address);
+    task_tracker.spawn(serve(socket, status, cancel_token));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SESSION_ID: [u8; 4] = [0, 0, 0, 1];
+
+    fn test_status() -> QueryStatus {
+        QueryStatus {
+            motd: "A Steel server".to_owned(),
+            version: "1.21.11".to_owned(),
+            map: "world".to_owned(),
+            players: vec!["Steve".to_owned(), "Alex".to_owned()],
+            max_players: 20,
+            host_port: 25566,
+            host_ip: "192.0.2.10".to_owned(),
+        }
+    }
+
+    fn contains(haystack: &[u8], needle: &[u8]) -> bool {
+        haystack
+            .windows(needle.len())
+            .any(|window| window == needle)
+    }
+
+    fn stat_request(token: i32, full: bool) -> Vec<u8> {
+        let mut request = vec![0xFE, 0xFD, TYPE_STAT];
+        request.extend_from_slice(&SESSION_ID);
+        request.extend_from_slice(&token.to_be_bytes());
+        if full {
+            request.extend_from_slice(&[0; 4]);
+        }
+        request
+    }
+
+    /// Performs the handshake and returns the challenge token.
+    fn handshake(handler: &mut QueryHandler, from: SocketAddr, now: Instant) -> i32 {
+        let mut request = vec![0xFE, 0xFD, TYPE_HANDSHAKE];
+        request.extend_from_slice(&SESSION_ID);
+        let response = handler
+            .handle(&request, from, now, test_status)
+            .expect("handshake response");
+        assert_eq!(response[0], TYPE_HANDSHAKE);
+        assert_eq!(response[1..5], SESSION_ID);
+        assert_eq!(response.last(), Some(&0));
+        String::from_utf8(response[5..response.len() - 1].to_vec())
+            .expect("ascii token")
+            .parse()
+            .expect("numeric token")
+    }
+
+    #[test]
+    fn basic_and_full_stat() {
+        let from = "127.0.0.1:5000".parse().expect("address");
+        let now = Instant::now();
+        let mut handler = QueryHandler::default();
+        let token = handshake(&mut handler, from, now);
+
+        let basic = handler
+            .handle(&stat_request(token, false), from, now, test_status)
+            .expect("basic stat");
+        let mut expected = vec![TYPE_STAT, 0, 0, 0, 1];
+        expected.extend_from_slice(b"A Steel server\0SMP\0world\x002\x0020\0");
+        expected.extend_from_slice(&25566_u16.to_le_bytes());
+        expected.extend_from_slice(b"192.0.2.10\0");
+        assert_eq!(basic, expected);
+
+        let full = handler
+            .handle(&stat_request(token, true), from, now, test_status)
+            .expect("full stat");
+        assert_eq!(full[..5], [TYPE_STAT, 0, 0, 0, 1]);
+        assert!(full[5..].starts_with(b"splitnum\0\x80\0hostname\0A Steel server\0"));
+        assert!(contains(&full, b"\0version\x001.21.11\0"));
+        assert!(contains(&full, b"\0numplayers\x002\0maxplayers\x0020\0"));
+        assert!(contains(
+            &full,
+            b"\0hostport\x0025566\0hostip\x00192.0.2.10\0"
+        ));
+        assert!(full.ends_with(b"\0\0\x01player_\0\0Steve\0Alex\0\0"));
+    }
+
+    #[test]
+    fn rejects_invalid_challenges() {
+        let from: SocketAddr = "127.0.0.1:5000".parse().expect("address");
+        let other = "127.0.0.1:5001".parse().expect("address");
+        let now = Instant::now();
+        let mut handler = QueryHandler::default();
+
+        // No handshake yet
+        let request = stat_request(0, false);
+        assert!(handler.handle(&request, from, now, test_status).is_none());
+
+        let token = handshake(&mut handler, from, now);
+        let wrong_token = stat_request(token.wrapping_add(1), false);
+        assert!(
+            handler
+                .handle(&wrong_token, from, now, test_status)
+                .is_none()
+        );
+        let request = stat_request(token, false);
+        assert!(handler.handle(&request, other, now, test_status).is_none());
+        let expired = now + CHALLENGE_LIFETIME;
+        assert!(
+            handler
+                .handle(&request, from, expired, test_status)
+                .is_none()
+        );
+        assert!(
+            handler
+                .handle(b"\xFE\xFD", from, now, test_status)
+                .is_none()
+        );
+        assert!(handler.handle(&request, from, now, test_status).is_some());
+    }
+}
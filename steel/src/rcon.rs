@@ -0,0 +1,347 @@
+//! Source RCON server.
+//!
+//! Lets remote tools run commands over the Source RCON protocol. Every packet is a
+//! little-endian `i32` length followed by the request id, the packet type, the body and
+//! two NUL bytes. Clients have to authenticate with the configured password before they
+//! can run commands. Command output is collected and sent back instead of logged.
+
+use std::{
+    io, mem,
+    net::{Ipv4Addr, SocketAddrV4},
+    sync::Arc,
+};
+
+use steel_core::{command::sender::CommandSender, server::Server};
+use steel_utils::locks::SyncMutex;
+use subtle::ConstantTimeEq;
+use tokio::{
+    io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, BufReader},
+    net::{TcpListener, TcpStream},
+    select,
+};
+use tokio_util::{sync::CancellationToken, task::TaskTracker};
+
+use crate::config::RconConfig;
+
+/// Request type that authenticates a connection.
+const SERVERDATA_AUTH: i32 = 3;
+/// Response type sent after an authentication attempt.
+const SERVERDATA_AUTH_RESPONSE: i32 = 2;
+/// Request type that runs a command.
+const SERVERDATA_EXECCOMMAND: i32 = 2;
+/// Response type carrying command output.
+const SERVERDATA_RESPONSE_VALUE: i32 = 0;
+
+/// Request id sent back when authentication fails.
+const AUTH_FAILURE_ID: i32 = -1;
+/// Smallest valid packet: id, type and the two NUL terminators.
+const MIN_PACKET_SIZE: i32 = 10;
+/// Largest packet accepted from clients, the same limit vanilla reads.
+const MAX_PACKET_SIZE: i32 = 1460;
+/// Longest body sent in one response, longer output is split over several packets.
+const MAX_RESPONSE_BODY: usize = 4096;
+
+/// A packet sent by an RCON client.
+#[derive(Debug, PartialEq, Eq)]
+struct RconPacket {
+    id: i32,
+    kind: i32,
+    body: String,
+}
+
+/// Reads one packet, failing on malformed lengths.
+async fn read_packet(reader: &mut (impl AsyncRead + Unpin)) -> io::Result<RconPacket> {
+    let length = reader.read_i32_le().await?;
+    if !(MIN_PACKET_SIZE..=MAX_PACKET_SIZE).contains(&length) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("invalid RCON packet length {length}"),
+        ));
+    }
+    let id = reader.read_i32_le().await?;
+    let kind = reader.read_i32_le().await?;
+    let mut body = vec![0; length as usize - 8];
+    reader.read_exact(&mut body).await?;
+
+    // The body is NUL-terminated and followed by an empty string
+    let end = body.iter().position(|&b| b == 0).unwrap_or(body.len());
+    body.truncate(end);
+    Ok(RconPacket {
+        id,
+        kind,
+        body: String::from_utf8_lossy(&body).into_owned(),
+    })
+}
+
+/// Writes one packet.
+async fn write_packet(
+    writer: &mut (impl AsyncWrite + Unpin),
+    id: i32,
+    kind: i32,
+    body: &str,
+) -> io::Result<()> {
+    let mut packet = Vec::with_capacity(body.len() + 14);
+    packet.extend_from_slice(&(body.len() as i32 + 10).to_le_bytes());
+    packet.extend_from_slice(&id.to_le_bytes());
+    packet.extend_from_slice(&kind.to_le_bytes());
+    packet.extend_from_slice(body.as_bytes());
+    packet.extend_from_slice(&[0, 0]);
+    writer.write_all(&packet).await?;
+    writer.flush().await
+}
+
+/// Sends command output, split into as many packets as needed.
+async fn write_response(
+    writer: &mut (impl AsyncWrite + Unpin),
+    id: i32,
+    output: &str,
+) -> io::Result<()> {
+    let mut rest = output;
+    loop {
+        let mut split = rest.len().min(MAX_RESPONSE_BODY);
+        while !rest.is_char_boundary(split) {
+            split -= 1;
+        }
+        let (chunk, remaining) = rest.split_at(split);
+        write_packet(writer, id, SERVERDATA_RESPONSE_VALUE, chunk).await?;
+        if remaining.is_empty() {
+            return Ok(());
+        }
+        rest = remaining;
+    }
+}
+
+/// Serves one client until it disconnects.
+///
+/// Mirrors vanilla's `RconClient`: commands are only run once the client authenticated,
+/// and unknown packet types are answered with an error message.
+async fn handle_client<F>(stream: TcpStream, password: &str, execute: &F) -> io::Result<()>
+where
+    F: Fn(String) -> String,
+{
+    let (reader, mut writer) = stream.into_split();
+    let mut reader = BufReader::new(reader);
+    let mut authenticated = false;
+
+    loop {
+        let packet = match read_packet(&mut reader).await {
+            Ok(packet) => packet,
+            Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(()),
+            Err(e) => return Err(e),
+        };
+
+        match packet.kind {
+            SERVERDATA_AUTH => {
+                // Compared in constant time so response timing doesn't leak the password
+                authenticated = !password.is_empty()
+                    && bool::from(packet.body.as_bytes().ct_eq(password.as_bytes()));
+                let id = if authenticated {
+                    packet.id
+                } else {
+                    AUTH_FAILURE_ID
+                };
+                write_packet(&mut writer, id, SERVERDATA_AUTH_RESPONSE, "").await?;
+            }
+            SERVERDATA_EXECCOMMAND if authenticated => {
+                let output = execute(packet.body);
+                write_response(&mut writer, packet.id, &output).await?;
+            }
+            SERVERDATA_EXECCOMMAND => {
+                write_packet(&mut writer, AUTH_FAILURE_ID, SERVERDATA_AUTH_RESPONSE, "").await?;
+            }
+            kind => {
+                let message = format!("Unknown request {kind:x}");
+                write_packet(&mut writer, packet.id, SERVERDATA_RESPONSE_VALUE, &message).await?;
+            }
+        }
+    }
+}
+
+/// Accepts RCON clients until the token is cancelled.
+async fn serve<F>(
+    listener: TcpListener,
+    password: Arc<str>,
+    execute: Arc<F>,
+    task_tracker: TaskTracker,
+    cancel_token: CancellationToken,
+) where
+    F: Fn(String) -> String + Send + Sync + 'static,
+{
+    loop {
+        select! {
+            accept_result = listener.accept() => {
+                let (stream, addr) = match accept_result {
+                    Ok(connection) => connection,
+                    Err(e) => {
+                        log::warn!("Failed to accept RCON connection: {e}");
+                        continue;
+                    }
+                };
+                log::info!("RCON connection from {addr}");
+
+                let password = password.clone();
+                let execute = execute.clone();
+                let cancel_token = cancel_token.clone();
+                task_tracker.spawn(async move {
+                    select! {
+                        result = handle_client(stream, &password, execute.as_ref()) => {
+                            if let Err(e) = result {
+                                log::debug!("RCON connection from {addr} closed: {e}");
+                            }
+                        }
+                        () = cancel_token.cancelled() => {}
+                    }
+                });
+            }
+            () = cancel_token.cancelled() => break,
+        }
+    }
+}
+
+/// Runs a command on behalf of an RCON client and returns what it printed.
+fn run_command(server: &Arc<Server>, command: String) -> String {
+    let output = Arc::new(SyncMutex::new(String::new()));
+    server.command_dispatcher.read().handle_command(
+        CommandSender::Rcon(output.clone()),
+        command,
+        server,
+    );
+    mem::take(&mut output.lock())
+}
+
+/// Starts the RCON listener if it is enabled in the config.
+///
+/// The listener binds every interface, and authenticated clients run commands with the
+/// same permissions as the console. If the port is taken, a warning is logged and remote
+/// tools can't connect until the server is restarted.
+pub async fn start(
+    server: Arc<Server>,
+    config: Option<&RconConfig>,
+    task_tracker: &TaskTracker,
+    cancel_token: CancellationToken,
+) {
+    let Some(config) = config.filter(|config| config.enable) else {
+        return;
+    };
+    let listener =
+        match TcpListener::bind(SocketAddrV4::new(Ipv4Addr::UNSPECIFIED, config.port)).await {
+            Ok(listener) => listener,
+            Err(e) => {
+                log::warn!("Failed to start RCON on port {}: {e}", config.port);
+                return;
+            }
+        };
+    log::info!("RCON running on port {}", config.port);
+
+    let execute = Arc::new(move |command| run_command(&server, command));
+    task_tracker.spawn(serve(
+        listener,
+        config.password.as_str().into(),
+        execute,
+        task_tracker.clone(),
+        cancel_token,
+    ));
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::SocketAddr;
+
+    use super::*;
+
+    /// A minimal RCON client, the way tools like mcrcon talk to the server.
+    struct TestClient {
+        stream: TcpStream,
+    }
+
+    impl TestClient {
+        async fn connect(addr: SocketAddr) -> Self {
+            Self {
+                stream: TcpStream::connect(addr).await.expect("connect to RCON"),
+            }
+        }
+
+        async fn send(&mut self, id: i32, kind: i32, body: &str) {
+            write_packet(&mut self.stream, id, kind, body)
+                .await
+                .expect("send packet");
+        }
+
+        async fn receive(&mut self) -> RconPacket {
+            let length = self.stream.read_i32_le().await.expect("packet length");
+            let mut rest = vec![0; length as usize];
+            self.stream.read_exact(&mut rest).await.expect("packet");
+            let id = i32::from_le_bytes(rest[0..4].try_into().expect("id"));
+            let kind = i32::from_le_bytes(rest[4..8].try_into().expect("type"));
+            assert_eq!(&rest[rest.len() - 2..], &[0, 0]);
+            RconPacket {
+                id,
+                kind,
+                body: String::from_utf8(rest[8..rest.len() - 2].to_vec()).expect("utf-8"),
+            }
+        }
+    }
+
+    async fn start_test_server(cancel_token: &CancellationToken) -> SocketAddr {
+        let listener = TcpListener::bind("127.0.0.1:0").await.expect("bind");
+        let addr = listener.local_addr().expect("local address");
+        let execute = Arc::new(|command: String| match command.as_str() {
+            "long" => "x".repeat(MAX_RESPONSE_BODY + 100),
+            _ => format!("ran {command}"),
+        });
+        tokio::spawn(serve(
+            listener,
+            "secret".into(),
+            execute,
+            TaskTracker::new(),
+            cancel_token.clone(),
+        ));
+        addr
+    }
+
+    #[tokio::test]
+    async fn rejects_wrong_password() {
+        let cancel_token = CancellationToken::new();
+        let mut client = TestClient::connect(start_test_server(&cancel_token).await).await;
+
+        client.send(7, SERVERDATA_AUTH, "wrong").await;
+        let response = client.receive().await;
+        assert_eq!(response.id, AUTH_FAILURE_ID);
+        assert_eq!(response.kind, SERVERDATA_AUTH_RESPONSE);
+
+        client.send(8, SERVERDATA_EXECCOMMAND, "list").await;
+        assert_eq!(client.receive().await.id, AUTH_FAILURE_ID);
+        cancel_token.cancel();
+    }
+
+    #[tokio::test]
+    async fn runs_commands_after_auth() {
+        let cancel_token = CancellationToken::new();
+        let mut client = TestClient::connect(start_test_server(&cancel_token).await).await;
+
+        client.send(1, SERVERDATA_AUTH, "secret").await;
+        let response = client.receive().await;
+        assert_eq!((response.id, response.kind), (1, SERVERDATA_AUTH_RESPONSE));
+
+        client.send(2, SERVERDATA_EXECCOMMAND, "list").await;
+        assert_eq!(
+            client.receive().await,
+            RconPacket {
+                id: 2,
+                kind: SERVERDATA_RESPONSE_VALUE,
+                body: "ran list".to_owned(),
+            }
+        );
+
+        client.send(3, SERVERDATA_EXECCOMMAND, "long").await;
+        let first = client.receive().await;
+        let second = client.receive().await;
+        assert_eq!((first.id, second.id), (3, 3));
+        assert_eq!(first.body.len(), MAX_RESPONSE_BODY);
+        assert_eq!(second.body.len(), 100);
+
+        client.send(4, 9, "").await;
+        assert_eq!(client.receive().await.body, "Unknown request 9");
+        cancel_token.cancel();
+    }
+}
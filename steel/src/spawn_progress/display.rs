@@ -12,6 +12,7 @@ use steel_utils::locks::SyncMutex;
 use tracing_subscriber::fmt::MakeWriter;
 
 use super::DISPLAY_DIAMETER;
+use crate::console::ConsoleLogWriter;
 
 /// Grid type alias for convenience.
 pub type Grid = [[Option<ChunkStatus>; DISPLAY_DIAMETER]; DISPLAY_DIAMETER];
@@ -22,27 +23,29 @@ pub type Grid = [[Option<ChunkStatus>; DISPLAY_DIAMETER]; DISPLAY_DIAMETER];
 
 /// A tracing writer that can redirect output through a [`SpawnProgressDisplay`].
 ///
-/// When the display is not activated, output goes directly to stderr.
+/// When the display is not activated, output goes to the console writer.
 /// When activated, log lines are rendered above the progress grid.
 ///
 /// Internally reference-counted — cloning is cheap and shares the same state.
 #[derive(Clone)]
 pub struct SwitchableWriter {
     inner: Arc<SyncMutex<Option<SpawnProgressDisplay>>>,
+    console: ConsoleLogWriter,
 }
 
 impl Default for SwitchableWriter {
     fn default() -> Self {
-        Self::new()
+        Self::new(ConsoleLogWriter::new())
     }
 }
 
 impl SwitchableWriter {
-    /// Creates a new writer in normal (stderr) mode.
+    /// Creates a new writer in normal (console) mode.
     #[must_use]
-    pub fn new() -> Self {
+    pub fn new(console: ConsoleLogWriter) -> Self {
         Self {
             inner: Arc::new(SyncMutex::new(None)),
+            console,
         }
     }
 
@@ -75,6 +78,7 @@ impl<'a> MakeWriter<'a> for SwitchableWriter {
     fn make_writer(&'a self) -> Self::Writer {
         SwitchableWriteTarget {
             inner: Arc::clone(&self.inner),
+            console: self.console.clone(),
             buffer: Vec::with_capacity(256),
         }
     }
@@ -83,6 +87,7 @@ impl<'a> MakeWriter<'a> for SwitchableWriter {
 /// Per-log-event writer that buffers the formatted line and flushes on drop.
 pub struct SwitchableWriteTarget {
     inner: Arc<SyncMutex<Option<SpawnProgressDisplay>>>,
+    console: ConsoleLogWriter,
     buffer: Vec<u8>,
 }
 
@@ -107,7 +112,7 @@ impl Drop for SwitchableWriteTarget {
             display.write_log_line(&self.buffer);
         } else {
             drop(inner);
-            let _ = io::stderr().write_all(&self.buffer);
+            self.console.write_line(&self.buffer);
         }
     }
 }
@@ -0,0 +1,61 @@
+//! World conversion commands.
+//!
+//! Running `steel import-anvil <world>` converts a vanilla world into the directory the
+//! server loads its worlds from, `steel export-anvil <world>` converts the server's worlds
+//! into a new vanilla world. Both exit instead of starting the server.
+
+use std::{path::Path, process::ExitCode};
+
+use steel_core::chunk_saver::anvil;
+use steel_registry::{REGISTRY, Registry};
+
+/// Directory the server loads its worlds from.
+pub(crate) const WORLD_DIR: &str = "world";
+
+/// Printed when the arguments are not a known command.
+const USAGE: &str = "Usage: steel import-anvil <vanilla world directory> \
+                     | steel export-anvil <vanilla world directory>";
+
+/// Runs the conversion command given on the command line.
+pub async fn run(args: &[String]) -> ExitCode {
+    let [command, vanilla_dir] = args else {
+        log::error!("{USAGE}");
+        return ExitCode::FAILURE;
+    };
+    let import = match command.as_str() {
+        "import-anvil" => true,
+        "export-anvil" => false,
+        _ => {
+            log::error!("Unknown command {command}. {USAGE}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    // Level data needs the game rules from the registry
+    let mut registry = Registry::new_vanilla();
+    registry.freeze();
+    let _ = REGISTRY.init(registry);
+
+    let vanilla_dir = Path::new(vanilla_dir);
+    let result = if import {
+        log::info!("Importing {} into {WORLD_DIR}", vanilla_dir.display());
+        anvil::import_world(vanilla_dir, Path::new(WORLD_DIR)).await
+    } else {
+        log::info!("Exporting {WORLD_DIR} to {}", vanilla_dir.display());
+        anvil::export_world(Path::new(WORLD_DIR), vanilla_dir).await
+    };
+    match result {
+        Ok(summary) => {
+            log::info!(
+                "Converted {} chunks, skipped {} that were not fully generated or unreadable",
+                summary.converted,
+                summary.skipped
+            );
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            log::error!("Failed to convert {}: {e}", vanilla_dir.display());
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,148 @@
+//! Runs commands over RCON against a real server process.
+//!
+//! The server runs in its own scratch directory, so this lives in its own test binary
+//! instead of touching the working directory or the registry of the unit tests.
+
+use std::{
+    fs,
+    io::{self, Read, Write},
+    net::{Ipv4Addr, SocketAddr, TcpListener, TcpStream},
+    process::{Child, Command, Stdio},
+    thread,
+    time::{Duration, Instant},
+};
+
+/// Request type that authenticates a connection.
+const SERVERDATA_AUTH: i32 = 3;
+/// Request type that runs a command.
+const SERVERDATA_EXECCOMMAND: i32 = 2;
+/// Response type carrying command output.
+const SERVERDATA_RESPONSE_VALUE: i32 = 0;
+
+/// How long the server may take to generate its spawn chunks and open the RCON port.
+const STARTUP_TIMEOUT: Duration = Duration::from_secs(120);
+/// How long the server may take to save and exit after `stop`.
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Kills the server if the test fails before it stopped on its own.
+struct ServerProcess(Child);
+
+impl Drop for ServerProcess {
+    fn drop(&mut self) {
+        let _ = self.0.kill();
+        let _ = self.0.wait();
+    }
+}
+
+/// A minimal RCON client, the way tools like mcrcon talk to the server.
+struct RconClient {
+    stream: TcpStream,
+}
+
+impl RconClient {
+    /// Connects once the server accepts connections, failing after [`STARTUP_TIMEOUT`].
+    fn connect(addr: SocketAddr, server: &mut ServerProcess) -> Self {
+        let start = Instant::now();
+        loop {
+            if let Ok(stream) = TcpStream::connect(addr) {
+                stream
+                    .set_read_timeout(Some(Duration::from_secs(30)))
+                    .expect("set read timeout");
+                return Self { stream };
+            }
+            if let Some(status) = server.0.try_wait().expect("poll server") {
+                panic!("server exited before RCON started: {status}");
+            }
+            assert!(start.elapsed() < STARTUP_TIMEOUT, "RCON never started");
+            thread::sleep(Duration::from_millis(200));
+        }
+    }
+
+    fn send(&mut self, id: i32, kind: i32, body: &str) {
+        let mut packet = Vec::with_capacity(body.len() + 14);
+        packet.extend_from_slice(&(body.len() as i32 + 10).to_le_bytes());
+        packet.extend_from_slice(&id.to_le_bytes());
+        packet.extend_from_slice(&kind.to_le_bytes());
+        packet.extend_from_slice(body.as_bytes());
+        packet.extend_from_slice(&[0, 0]);
+        self.stream.write_all(&packet).expect("send packet");
+    }
+
+    /// Reads one packet, returning its id, type and body.
+    fn receive(&mut self) -> (i32, i32, String) {
+        let mut length = [0; 4];
+        self.stream.read_exact(&mut length).expect("packet length");
+        let mut rest = vec![0; i32::from_le_bytes(length) as usize];
+        self.stream.read_exact(&mut rest).expect("packet");
+        let id = i32::from_le_bytes(rest[0..4].try_into().expect("id"));
+        let kind = i32::from_le_bytes(rest[4..8].try_into().expect("type"));
+        assert_eq!(&rest[rest.len() - 2..], &[0, 0]);
+        let body = String::from_utf8(rest[8..rest.len() - 2].to_vec()).expect("utf-8");
+        (id, kind, body)
+    }
+
+    /// Runs a command and returns its output.
+    fn run(&mut self, id: i32, command: &str) -> String {
+        self.send(id, SERVERDATA_EXECCOMMAND, command);
+        let (response_id, kind, body) = self.receive();
+        assert_eq!((response_id, kind), (id, SERVERDATA_RESPONSE_VALUE));
+        body
+    }
+}
+
+/// Returns a local port nothing is listening on right now.
+fn free_port() -> io::Result<u16> {
+    Ok(TcpListener::bind((Ipv4Addr::LOCALHOST, 0))?
+        .local_addr()?
+        .port())
+}
+
+#[test]
+fn runs_real_commands_through_the_dispatcher() {
+    let dir = tempfile::tempdir().expect("create scratch directory");
+    let server_port = free_port().expect("find a server port");
+    let rcon_port = free_port().expect("find an RCON port");
+
+    let config = include_str!("../../package-content/steel_config.json5")
+        .replacen(
+            "server_port: 25565",
+            &format!("server_port: {server_port}"),
+            1,
+        )
+        .replacen("seed: \"\"", "seed: \"8675309\"", 1)
+        .replacen("// flat_world: \"classic\"", "flat_world: \"classic\"", 1)
+        .replacen("enable: false", "enable: true", 1)
+        .replacen("port: 25575", &format!("port: {rcon_port}"), 1)
+        .replacen("password: \"\"", "password: \"secret\"", 1);
+    fs::create_dir_all(dir.path().join("config")).expect("create config directory");
+    fs::write(dir.path().join("config/steel_config.json5"), config).expect("write config");
+
+    let mut server = ServerProcess(
+        Command::new(env!("CARGO_BIN_EXE_steel"))
+            .current_dir(dir.path())
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .spawn()
+            .expect("start the server"),
+    );
+
+    let mut client = RconClient::connect((Ipv4Addr::LOCALHOST, rcon_port).into(), &mut server);
+    client.send(1, SERVERDATA_AUTH, "secret");
+    assert_eq!(client.receive().0, 1);
+
+    // Output has to travel through `CommandSender::Rcon` into the response
+    let output = client.run(2, "seed");
+    assert!(output.contains("Seed: "), "{output:?}");
+    assert!(output.contains("8675309"), "{output:?}");
+
+    // Errors are sent to the sender as well
+    let output = client.run(3, "nope");
+    assert!(output.contains("Command nope does not exist"), "{output:?}");
+
+    client.send(4, SERVERDATA_EXECCOMMAND, "stop");
+    let start = Instant::now();
+    while server.0.try_wait().expect("poll server").is_none() {
+        assert!(start.elapsed() < SHUTDOWN_TIMEOUT, "server did not stop");
+        thread::sleep(Duration::from_millis(200));
+    }
+}